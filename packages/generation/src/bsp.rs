@@ -0,0 +1,203 @@
+//! Rooms from binary space partitioning
+//!
+//! The map is split in two again and again, alternating mostly between cuts
+//! across and down, until the pieces are too small to split. Each leaf gets a
+//! room somewhere inside it, and each split joins its two halves with an
+//! L-shaped corridor between a room on either side, so every room is reachable
+//! and no two rooms overlap.
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::grid::Grid;
+
+/// Settings for a BSP map
+#[derive(Debug, Clone, Copy)]
+pub struct BspConfig {
+    pub width: usize,
+    pub height: usize,
+    /// The same seed and settings always give the same map
+    pub seed: u64,
+    /// Pieces narrower or shorter than twice this aren't split along that side
+    pub min_leaf: usize,
+    /// Smallest room width and height, walls not included
+    pub min_room: usize,
+    /// Width of the corridors between rooms
+    pub corridor_width: usize,
+}
+
+impl Default for BspConfig {
+    fn default() -> Self {
+        Self {
+            width: 256,
+            height: 256,
+            seed: 0,
+            min_leaf: 24,
+            min_room: 6,
+            corridor_width: 2,
+        }
+    }
+}
+
+/// An area of the map as x, y, width and height
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    fn center(&self) -> (usize, usize) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+/// Split the map, put a room in each leaf and join them up
+///
+/// The outer ring is always wall.
+pub fn bsp(config: &BspConfig) -> Vec<Vec<bool>> {
+    let (width, height) = (config.width, config.height);
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut map = Grid::new(width, height, false);
+    if width < 3 || height < 3 {
+        return map.into_rows();
+    }
+
+    let inside = Rect { x: 1, y: 1, width: width - 2, height: height - 2 };
+    split(inside, config, &mut rng, &mut map);
+    map.into_rows()
+}
+
+/// Carve `area` and return the rooms in it
+fn split(area: Rect, config: &BspConfig, rng: &mut StdRng, map: &mut Grid<bool>) -> Vec<Rect> {
+    let min_leaf = config.min_leaf.max(config.min_room + 2).max(3);
+    let can_split_x = area.width >= min_leaf * 2;
+    let can_split_y = area.height >= min_leaf * 2;
+    // Cut across the longer side, so leaves don't end up as thin strips
+    let split_x = match (can_split_x, can_split_y) {
+        (false, false) => return vec![carve_room(area, config, rng, map)],
+        (true, false) => true,
+        (false, true) => false,
+        (true, true) if area.width * 4 >= area.height * 5 => true,
+        (true, true) if area.height * 4 >= area.width * 5 => false,
+        (true, true) => rng.random_bool(0.5),
+    };
+
+    let (first, second) = if split_x {
+        let at = rng.random_range(min_leaf..=area.width - min_leaf);
+        (
+            Rect { width: at, ..area },
+            Rect { x: area.x + at, width: area.width - at, ..area },
+        )
+    } else {
+        let at = rng.random_range(min_leaf..=area.height - min_leaf);
+        (
+            Rect { height: at, ..area },
+            Rect { y: area.y + at, height: area.height - at, ..area },
+        )
+    };
+
+    let mut rooms = split(first, config, rng, map);
+    let others = split(second, config, rng, map);
+    // Join the rooms nearest the cut, so corridors stay short and don't cross the whole map
+    let target = second.center();
+    let from = *rooms.iter().min_by_key(|room| distance(room.center(), target)).unwrap();
+    let to = *others.iter().min_by_key(|room| distance(room.center(), from.center())).unwrap();
+    carve_corridor(from.center(), to.center(), config.corridor_width, rng, map);
+
+    rooms.extend(others);
+    rooms
+}
+
+fn distance((ax, ay): (usize, usize), (bx, by): (usize, usize)) -> usize {
+    ax.abs_diff(bx).pow(2) + ay.abs_diff(by).pow(2)
+}
+
+/// Carve a room of random size and position inside `leaf`, leaving a wall around it
+fn carve_room(leaf: Rect, config: &BspConfig, rng: &mut StdRng, map: &mut Grid<bool>) -> Rect {
+    let max_width = leaf.width.saturating_sub(2).max(1);
+    let max_height = leaf.height.saturating_sub(2).max(1);
+    let width = rng.random_range(config.min_room.clamp(1, max_width)..=max_width);
+    let height = rng.random_range(config.min_room.clamp(1, max_height)..=max_height);
+    let x = leaf.x + rng.random_range(0..=leaf.width.saturating_sub(width + 2)) + (leaf.width > width) as usize;
+    let y = leaf.y + rng.random_range(0..=leaf.height.saturating_sub(height + 2)) + (leaf.height > height) as usize;
+
+    let room = Rect { x, y, width, height };
+    for cy in y..y + height {
+        for cx in x..x + width {
+            map[(cx, cy)] = true;
+        }
+    }
+    room
+}
+
+/// Carve an L-shaped corridor, turning at one of the two corners at random
+fn carve_corridor(from: (usize, usize), to: (usize, usize), corridor_width: usize, rng: &mut StdRng, map: &mut Grid<bool>) {
+    let corner = if rng.random_bool(0.5) { (to.0, from.1) } else { (from.0, to.1) };
+    let (width, height) = (map.width(), map.height());
+    let corridor_width = corridor_width.max(1);
+    let mut open = |a: (usize, usize), b: (usize, usize)| {
+        // Grown right and down from the line, but never into the outer ring
+        for y in a.1.min(b.1)..=a.1.max(b.1) + corridor_width - 1 {
+            for x in a.0.min(b.0)..=a.0.max(b.0) + corridor_width - 1 {
+                if x > 0 && y > 0 && x < width - 1 && y < height - 1 {
+                    map[(x, y)] = true;
+                }
+            }
+        }
+    };
+    open(from, corner);
+    open(corner, to);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post::flood::flood_fill;
+    use proptest::prelude::*;
+
+    fn configs() -> impl Strategy<Value = BspConfig> {
+        (3..120usize, 3..120usize, any::<u64>(), 3..30usize, 1..8usize, 1..4usize).prop_map(
+            |(width, height, seed, min_leaf, min_room, corridor_width)| BspConfig {
+                width,
+                height,
+                seed,
+                min_leaf,
+                min_room,
+                corridor_width,
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_all_floor_is_connected_and_walled_in(config in configs()) {
+            let map = bsp(&config);
+            prop_assert_eq!(map.len(), config.height);
+            prop_assert!(map.iter().all(|row| row.len() == config.width));
+
+            let floor: Vec<(usize, usize)> = (0..config.height)
+                .flat_map(|y| (0..config.width).map(move |x| (x, y)))
+                .filter(|&(x, y)| map[y][x])
+                .collect();
+            prop_assert!(!floor.is_empty());
+            let distances = flood_fill(&map, floor[0]);
+            for &(x, y) in &floor {
+                prop_assert!(distances[y][x] != usize::MAX);
+                prop_assert!(x > 0 && y > 0 && x < config.width - 1 && y < config.height - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_map_has_many_rooms() {
+        let config = BspConfig::default();
+        let map = bsp(&config);
+        let floor = map.iter().flatten().filter(|&&cell| cell).count();
+        // Rooms and corridors, with plenty of wall left between them
+        assert!(floor > config.width * config.height / 10);
+        assert!(floor < config.width * config.height * 9 / 10);
+        assert_eq!(bsp(&config), map);
+    }
+}
@@ -0,0 +1,283 @@
+//! Endless worlds generated a chunk at a time
+//!
+//! A `ChunkedGenerator` makes any chunk of a world on its own from the seed
+//! and the chunk's coordinates, so a game can generate chunks in whatever
+//! order the player walks into them, without a map of the whole world. Chunks
+//! line up with their neighbors because the edge between two chunks is
+//! decided once, from the seed and where the edge is: both chunks get the
+//! same `EdgeConstraints` for it and keep floor at its openings along their
+//! border and wall everywhere else on it.
+use std::ops::RangeInclusive;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::grid::Grid;
+
+/// Chunk coordinates; chunk `(x, y + 1)` is below `(x, y)`, as rows go down a grid
+pub type ChunkCoord = (i32, i32);
+
+/// A side of a chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::North, Side::East, Side::South, Side::West];
+
+    /// The chunk on the other side
+    pub fn neighbor(self, (x, y): ChunkCoord) -> ChunkCoord {
+        match self {
+            Side::North => (x, y - 1),
+            Side::East => (x + 1, y),
+            Side::South => (x, y + 1),
+            Side::West => (x - 1, y),
+        }
+    }
+
+    /// Cells along this side of a `size` chunk, left to right or top to bottom
+    pub fn border(self, size: usize) -> Vec<(usize, usize)> {
+        let last = size - 1;
+        (0..size)
+            .map(|i| match self {
+                Side::North => (i, 0),
+                Side::East => (last, i),
+                Side::South => (i, last),
+                Side::West => (0, i),
+            })
+            .collect()
+    }
+
+    /// The edge this side lies on, named the same from the chunks on both sides of it
+    fn edge(self, (x, y): ChunkCoord) -> (bool, i32, i32) {
+        match self {
+            Side::North => (true, x, y),
+            Side::South => (true, x, y + 1),
+            Side::West => (false, x, y),
+            Side::East => (false, x + 1, y),
+        }
+    }
+}
+
+/// How the edges between chunks open up
+#[derive(Debug, Clone)]
+pub struct OpeningRules {
+    /// Openings on each edge
+    pub per_edge: RangeInclusive<usize>,
+    /// Width of an opening, in cells
+    pub width: usize,
+}
+
+impl Default for OpeningRules {
+    fn default() -> Self {
+        Self { per_edge: 1..=2, width: 3 }
+    }
+}
+
+/// Which border cells of a chunk are floor, side by side, in `Side::border` order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeConstraints {
+    pub north: Vec<bool>,
+    pub east: Vec<bool>,
+    pub south: Vec<bool>,
+    pub west: Vec<bool>,
+}
+
+impl EdgeConstraints {
+    /// The edges of `chunk`, each the same as seen from the neighbor across it
+    pub fn for_chunk(seed: u64, chunk: ChunkCoord, size: usize, rules: &OpeningRules) -> Self {
+        let side = |side: Side| edge_openings(seed, side.edge(chunk), size, rules);
+        Self { north: side(Side::North), east: side(Side::East), south: side(Side::South), west: side(Side::West) }
+    }
+
+    pub fn side(&self, side: Side) -> &[bool] {
+        match side {
+            Side::North => &self.north,
+            Side::East => &self.east,
+            Side::South => &self.south,
+            Side::West => &self.west,
+        }
+    }
+
+    /// Border cells that are floor
+    pub fn openings(&self, size: usize) -> Vec<(usize, usize)> {
+        Side::ALL
+            .iter()
+            .flat_map(|&side| side.border(size).into_iter().zip(self.side(side).iter().copied()))
+            .filter(|&(_, floor)| floor)
+            .map(|(cell, _)| cell)
+            .collect()
+    }
+}
+
+/// Openings along one edge, kept clear of the corners so they never meet another edge's
+fn edge_openings(seed: u64, (horizontal, x, y): (bool, i32, i32), size: usize, rules: &OpeningRules) -> Vec<bool> {
+    let mut rng = StdRng::seed_from_u64(mix(seed, &[horizontal as i64, x as i64, y as i64]));
+    let mut floor = vec![false; size];
+    let width = rules.width.max(1);
+    if size < width + 2 {
+        return floor;
+    }
+
+    for _ in 0..rng.random_range(rules.per_edge.clone()) {
+        let start = rng.random_range(1..=size - 1 - width);
+        floor[start..start + width].fill(true);
+    }
+    floor
+}
+
+/// Seed for one chunk or edge of the world made from `seed`
+fn mix(seed: u64, values: &[i64]) -> u64 {
+    // SplitMix64 steps, so neighboring coordinates get unrelated seeds
+    values.iter().fold(seed, |hash, &value| {
+        let mut z = (hash ^ value as u64).wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    })
+}
+
+/// Makes a world one chunk at a time
+pub trait ChunkedGenerator {
+    type Tile;
+
+    /// Width and height of every chunk, in cells
+    fn chunk_size(&self) -> usize;
+
+    /// What the border of `chunk` has to be for it to line up with its neighbors
+    fn edges(&self, seed: u64, chunk: ChunkCoord) -> EdgeConstraints;
+
+    /// The chunk at `chunk` of the world made from `seed`, the same every time
+    /// and with its border following `edges`
+    fn generate_chunk(&self, seed: u64, chunk: ChunkCoord) -> Grid<Self::Tile>;
+}
+
+/// `across` by `down` chunks from `origin` stitched into one grid, for previews and tests
+pub fn region<G>(generator: &G, seed: u64, origin: ChunkCoord, across: usize, down: usize) -> Grid<G::Tile>
+where
+    G: ChunkedGenerator,
+    G::Tile: Clone,
+{
+    let size = generator.chunk_size();
+    let chunks: Vec<Grid<G::Tile>> = (0..down)
+        .flat_map(|cy| (0..across).map(move |cx| (origin.0 + cx as i32, origin.1 + cy as i32)))
+        .map(|chunk| generator.generate_chunk(seed, chunk))
+        .collect();
+    Grid::from_fn(across * size, down * size, |x, y| chunks[(y / size) * across + x / size][(x % size, y % size)].clone())
+}
+
+/// Settings for caves made a chunk at a time
+#[derive(Debug, Clone)]
+pub struct CaveChunks {
+    /// Width and height of a chunk, in cells
+    pub size: usize,
+    /// Chance from 0 to 1 of each cell starting as floor
+    pub fill: f64,
+    /// Smoothing passes
+    pub iterations: usize,
+    pub openings: OpeningRules,
+}
+
+impl Default for CaveChunks {
+    fn default() -> Self {
+        Self { size: 32, fill: 0.5, iterations: 4, openings: OpeningRules::default() }
+    }
+}
+
+impl ChunkedGenerator for CaveChunks {
+    /// `true` for floor
+    type Tile = bool;
+
+    fn chunk_size(&self) -> usize {
+        self.size
+    }
+
+    fn edges(&self, seed: u64, chunk: ChunkCoord) -> EdgeConstraints {
+        EdgeConstraints::for_chunk(seed, chunk, self.size, &self.openings)
+    }
+
+    fn generate_chunk(&self, seed: u64, chunk: ChunkCoord) -> Grid<bool> {
+        let size = self.size;
+        let edges = self.edges(seed, chunk);
+        let mut rng = StdRng::seed_from_u64(mix(seed, &[chunk.0 as i64, chunk.1 as i64]));
+
+        // The border comes from the edges and the automaton leaves the outer ring alone
+        let mut grid = Grid::from_fn(size, size, |_, _| rng.random_bool(self.fill));
+        for side in Side::ALL {
+            for (cell, &floor) in side.border(size).into_iter().zip(edges.side(side)) {
+                grid[cell] = floor;
+            }
+        }
+        grid.cellular_automata(self.iterations, |&floor, neighbors| {
+            let count = neighbors.iter().filter(|&&&neighbor| neighbor).count();
+            count >= 5 || (floor && count >= 4)
+        });
+
+        // Tunnel from every opening to the middle, so each can reach the others
+        let middle = (size / 2, size / 2);
+        let inside = |(x, y): (usize, usize)| x > 0 && y > 0 && x < size - 1 && y < size - 1;
+        for opening in edges.openings(size) {
+            for cell in grid.line(opening, middle) {
+                // With the cells around it, so diagonal steps stay joined up, down, left and right
+                for cell in std::iter::once(cell).chain(grid.neighbors(cell)) {
+                    if inside(cell) {
+                        grid[cell] = true;
+                    }
+                }
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_neighbors_share_their_edges(seed in any::<u64>(), x in -50i32..50, y in -50i32..50) {
+            let caves = CaveChunks::default();
+            let chunk = caves.generate_chunk(seed, (x, y));
+            let last = caves.size - 1;
+
+            let east = caves.generate_chunk(seed, Side::East.neighbor((x, y)));
+            let south = caves.generate_chunk(seed, Side::South.neighbor((x, y)));
+            for i in 0..caves.size {
+                prop_assert_eq!(chunk[(last, i)], east[(0, i)]);
+                prop_assert_eq!(chunk[(i, last)], south[(i, 0)]);
+            }
+        }
+
+        #[test]
+        fn test_openings_reach_each_other(seed in any::<u64>(), x in -50i32..50, y in -50i32..50) {
+            let caves = CaveChunks::default();
+            let chunk = caves.generate_chunk(seed, (x, y));
+            let openings = caves.edges(seed, (x, y)).openings(caves.size);
+            prop_assert!(!openings.is_empty());
+
+            let reached = chunk.flood_fill(openings[0], |&floor| floor);
+            for opening in openings {
+                prop_assert!(reached[opening] != usize::MAX);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunks_come_out_the_same_in_any_order() {
+        let caves = CaveChunks::default();
+        let first = caves.generate_chunk(7, (3, -2));
+        caves.generate_chunk(7, (0, 0));
+        assert_eq!(caves.generate_chunk(7, (3, -2)), first);
+        assert_ne!(caves.generate_chunk(8, (3, -2)), first);
+
+        let stitched = region(&caves, 7, (3, -2), 2, 1);
+        assert_eq!((stitched.width(), stitched.height()), (64, 32));
+        assert_eq!(stitched[(5, 9)], first[(5, 9)]);
+    }
+}
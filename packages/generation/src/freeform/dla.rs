@@ -1,26 +1,37 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::progress::Progress;
+
+/// Particles walked at once by `par_dla_generation` per thread, between progress reports
+const PARTICLES_PER_THREAD: usize = 64;
+
+/// Settings for growing structures by diffusion-limited aggregation
+#[derive(Debug, Clone, Default)]
+pub struct DlaConfig {
+    /// Number of tiles to fill before stopping
+    pub target_tiles: usize,
+    /// Maximum number of particles to spawn
+    pub max_particles: usize,
+    /// Where particles can spawn
+    pub spawn_points: Vec<(usize, usize)>,
+    /// Points that particles may be biased towards
+    pub bias_points: Vec<(usize, usize)>,
+    /// Probability (0.0 to 1.0) of biasing towards a bias point each step
+    pub bias_chance: f64,
+}
 
 /// Perform diffusion-limited aggregation to grow structures on the map
 ///
-/// # Parameters
-/// - `map`: 2D boolean grid representing the map (true = occupied)
-/// - `target_tiles`: Number of tiles to fill before stopping
-/// - `max_particles`: Maximum number of particles to spawn
-/// - `spawn_points`: List of (x, y) coordinates where particles can spawn
-/// - `bias_points`: List of (x, y) coordinates that particles may be biased towards
-/// - `bias_chance`: Probability (0.0 to 1.0) of biasing towards a bias point each step
-/// - `progress_callback`: Function called after each tile is filled, with current count, target, and map
-pub fn dla_generation<F>(
-    map: &mut Vec<Vec<bool>>,
-    target_tiles: usize,
-    max_particles: usize,
-    spawn_points: &Vec<(usize, usize)>,
-    bias_points: &Vec<(usize, usize)>,
-    bias_chance: f64,
-    mut progress_callback: F,
-) where F: FnMut(usize, usize, &Vec<Vec<bool>>),
-{
+/// `map` is `true` where occupied, and particles stick next to occupied cells.
+/// `rng` drives spawns and steps, so the same seed grows the same structure.
+/// `progress` is told after each tile is filled, with the tiles filled so far
+/// out of `target_tiles`.
+pub fn dla_generation(map: &mut [Vec<bool>], rng: &mut impl Rng, config: &DlaConfig, progress: &mut Progress) {
+    let DlaConfig { target_tiles, max_particles, ref spawn_points, ref bias_points, bias_chance } = *config;
     let width = map[0].len();
     let height = map.len();
     let mut occupied = HashSet::new();
@@ -33,7 +44,6 @@ pub fn dla_generation<F>(
     }
     let seed_count = occupied.len();
 
-    let mut rng = rand::rng();
     let mut particles_spawned = 0;
 
     while (occupied.len() - seed_count) < target_tiles && particles_spawned < max_particles {
@@ -48,7 +58,7 @@ pub fn dla_generation<F>(
             if is_adjacent_to_occupied(x, y, &occupied) {
                 map[y][x] = true;
                 occupied.insert((x, y));
-                progress_callback(occupied.len(), target_tiles, &map);
+                progress.report(occupied.len() - seed_count, target_tiles, map);
                 break;
             }
 
@@ -84,6 +94,89 @@ pub fn dla_generation<F>(
     }
 }
 
+/// `dla_generation` with particles walking on every core at once
+///
+/// Each particle gets its own generator from `seed` and its number, and sticks
+/// by claiming its cell in a shared grid of atomic flags, so exactly
+/// `target_tiles` cells are filled when there are particles enough. Which of two
+/// particles racing for the same spot wins depends on thread timing, so unlike
+/// `dla_generation` the same seed can grow slightly different structures.
+/// `progress` is told after each batch of particles.
+pub fn par_dla_generation(map: &mut [Vec<bool>], seed: u64, config: &DlaConfig, progress: &mut Progress) {
+    let DlaConfig { target_tiles, max_particles, ref spawn_points, ref bias_points, bias_chance } = *config;
+    let width = map[0].len();
+    let height = map.len();
+    let occupied: Vec<AtomicBool> = map.iter().flatten().map(|&cell| AtomicBool::new(cell)).collect();
+    let filled = AtomicUsize::new(0);
+    let max_steps = (width * height / 100).max(10000);
+
+    let is_occupied = |x: usize, y: usize| occupied[y * width + x].load(Ordering::Relaxed);
+    let is_adjacent = |x: usize, y: usize| {
+        (x > 0 && is_occupied(x - 1, y))
+            || (x + 1 < width && is_occupied(x + 1, y))
+            || (y > 0 && is_occupied(x, y - 1))
+            || (y + 1 < height && is_occupied(x, y + 1))
+    };
+
+    let walk = |particle: usize| {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(particle as u64));
+        let (mut x, mut y) = spawn_points[rng.random_range(0..spawn_points.len())];
+
+        for _ in 0..max_steps {
+            if filled.load(Ordering::Relaxed) >= target_tiles {
+                return;
+            }
+
+            if !is_occupied(x, y) && is_adjacent(x, y) {
+                // Take one of the remaining tiles, then the cell; give the tile back if another particle got there first
+                let reserved = filled.fetch_update(Ordering::AcqRel, Ordering::Relaxed, |count| (count < target_tiles).then_some(count + 1));
+                if reserved.is_err() {
+                    return;
+                }
+                if occupied[y * width + x].compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+                    return;
+                }
+                filled.fetch_sub(1, Ordering::AcqRel);
+            }
+
+            if rng.random::<f64>() < bias_chance {
+                let (bias_x, bias_y) = bias_points[rng.random_range(0..bias_points.len())];
+                if bias_x > x && x < width - 1 {
+                    x += 1;
+                } else if bias_x < x && x > 0 {
+                    x -= 1;
+                }
+                if bias_y > y && y < height - 1 {
+                    y += 1;
+                } else if bias_y < y && y > 0 {
+                    y -= 1;
+                }
+            } else {
+                match rng.random_range(0..4) {
+                    0 if x > 0 => x -= 1,
+                    1 if x < width - 1 => x += 1,
+                    2 if y > 0 => y -= 1,
+                    3 if y < height - 1 => y += 1,
+                    _ => {}
+                }
+            }
+        }
+    };
+
+    let batch = rayon::current_num_threads() * PARTICLES_PER_THREAD;
+    let mut particles_spawned = 0;
+    while filled.load(Ordering::Relaxed) < target_tiles && particles_spawned < max_particles {
+        let end = (particles_spawned + batch).min(max_particles);
+        (particles_spawned..end).into_par_iter().for_each(walk);
+        particles_spawned = end;
+
+        for (cell, flag) in map.iter_mut().flatten().zip(&occupied) {
+            *cell = flag.load(Ordering::Relaxed);
+        }
+        progress.report(filled.load(Ordering::Relaxed), target_tiles, map);
+    }
+}
+
 fn is_adjacent_to_occupied(x: usize, y: usize, occupied: &HashSet<(usize, usize)>) -> bool {
     for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
         let nx = x as i32 + dx;
@@ -94,3 +187,36 @@ fn is_adjacent_to_occupied(x: usize, y: usize, occupied: &HashSet<(usize, usize)
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post::flood::flood_fill;
+
+    #[test]
+    fn test_parallel_dla_fills_target_connected_to_seed() {
+        let size = 64;
+        let mut map = vec![vec![false; size]; size];
+        map[size / 2][size / 2] = true;
+        let edges: Vec<_> = (0..size).flat_map(|i| [(i, 0), (i, size - 1), (0, i), (size - 1, i)]).collect();
+
+        let target = 300;
+        let config = DlaConfig {
+            target_tiles: target,
+            max_particles: 100_000,
+            spawn_points: edges,
+            bias_points: vec![(size / 2, size / 2)],
+            bias_chance: 0.2,
+        };
+        par_dla_generation(&mut map, 7, &config, &mut Progress::none());
+
+        let floor = map.iter().flatten().filter(|&&cell| cell).count();
+        assert_eq!(floor, target + 1);
+        let distances = flood_fill(&map, (size / 2, size / 2));
+        for (y, row) in map.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                assert!(!cell || distances[y][x] != usize::MAX, "({x}, {y}) isn't attached to the structure");
+            }
+        }
+    }
+}
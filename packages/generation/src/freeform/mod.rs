@@ -3,26 +3,126 @@ pub mod dla;
 pub mod simplex;
 
 use image::{ImageBuffer, Rgb};
+use super::grid::{self, Grid};
 use super::post;
+use super::post::symmetry::{apply_symmetry, Symmetry};
+use super::progress::Progress;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+/// Settings for a freeform cave map
+#[derive(Debug, Clone, Copy)]
+pub struct FreeformConfig {
+    /// Width and height of the map, in cells
+    pub size: usize,
+    /// The same seed and settings always give the same map
+    pub seed: u64,
+    /// Random walks out from the spawn room, or `None` to pick a few more for bigger maps
+    pub path_count: Option<usize>,
+    /// Half the side of the square spawn room in the center
+    pub spawn_room_half_size: usize,
+    /// Cellular automata passes that smooth the walls
+    pub ca_passes: usize,
+    /// Arena maps set this so every side gets the same layout
+    pub symmetry: Symmetry,
+    /// Narrowest passage left in the map, in cells, so the player and enemies fit through
+    pub min_corridor_width: usize,
+    /// Cells of branching tunnels grown onto the walked caves by diffusion-limited aggregation; 0 for none
+    pub dla_tiles: usize,
+    /// Where the tunnel-growing particles start
+    pub dla_seeding: Seeding,
+    /// Grow the tunnels on every core; faster on big maps, but the same seed can give slightly different tunnels
+    pub parallel_dla: bool,
+}
+
+/// Where diffusion-limited aggregation particles start from
+///
+/// Tunnels grow toward wherever particles come from, so the spread of the
+/// starting points decides whether they branch evenly or bunch up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seeding {
+    /// Anywhere, so some areas get many and others none
+    Random { count: usize },
+    /// On a grid, which shows as straight lines of tunnels
+    Regular { spacing: usize },
+    /// Evenly around the center
+    Ring { count: usize, radius: usize },
+    /// In a few clumps, each less than half the map across
+    Cluster { count: usize, size: usize, radius: usize },
+    /// Spread like blue noise, no two closer than `min_distance`
+    Poisson { min_distance: usize },
+}
+
+impl Seeding {
+    /// Starting points in the map, where it's still wall
+    fn points(&self, map: &Vec<Vec<bool>>, rng: &mut impl Rng) -> Vec<(usize, usize)> {
+        let points = match *self {
+            Self::Random { count } => random(map, rng, count),
+            Self::Regular { spacing } => regular(map, spacing.max(1)),
+            Self::Ring { count, radius } => ring(map, count, radius),
+            Self::Cluster { count, size, radius } => cluster(map, rng, count, size, radius),
+            Self::Poisson { min_distance } => poisson(map, rng, min_distance),
+        };
+        points
+            .into_iter()
+            .filter(|&(x, y)| y < map.len() && x < map[0].len() && !map[y][x])
+            .collect()
+    }
+}
 
-use rand::Rng;
+impl Default for FreeformConfig {
+    fn default() -> Self {
+        Self {
+            size: 256,
+            seed: 0,
+            path_count: None,
+            spawn_room_half_size: 4,
+            ca_passes: 5,
+            symmetry: Symmetry::None,
+            min_corridor_width: 3,
+            dla_tiles: 0,
+            dla_seeding: Seeding::Poisson { min_distance: 16 },
+            parallel_dla: false,
+        }
+    }
+}
+
+/// Generate a cave map, `true` for floor
+///
+/// Parts of the map can be sealed off from the spawn room; `reachable` gives
+/// the part the player can walk to.
+pub fn freeform(config: &FreeformConfig) -> Vec<Vec<bool>> {
+    freeform_with_progress(config, &mut Progress::none())
+}
 
-pub fn freeform(size: usize, outfile: &str, floodfile: &str) {
-    let mut rng = rand::rng();
+/// `freeform`, reporting after each random walk, the tunnels, the noise, and each smoothing pass
+pub fn freeform_with_progress(config: &FreeformConfig, progress: &mut Progress) -> Vec<Vec<bool>> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let size = config.size;
     let width = 3 * size / 4;
     let height = width;
     let mut map = vec![vec![false; width]; height];
     let (cx, cy) = center(&map);
 
     // Spawn room
-    square_fill_seeds(&mut map, 4);
+    square_fill_seeds(&mut map, config.spawn_room_half_size);
 
     // Add some random paths
-    let mut path_count = (size as i32) / 256;
-    path_count = rng.random_range((path_count - 2)..(path_count + 2)).max(4).min(32);
+    let path_count = match config.path_count {
+        Some(path_count) => path_count.max(1),
+        None => {
+            let path_count = (size as i32) / 256;
+            rng.random_range((path_count - 2)..(path_count + 2)).max(4).min(32) as usize
+        }
+    };
+    // Each walk, the tunnels if any, the noise, each smoothing pass, then widening the corridors
+    let tunnel_steps = usize::from(config.dla_tiles > 0);
+    let total_steps = path_count + tunnel_steps + 1 + config.ca_passes + 1;
+
     let path_points = circle(&map, 1, width / 2, (cx, cy));
     let mut a = path_points[rng.random_range(0..path_points.len())];
-    for _ in 0..path_count {
+    for path in 0..path_count {
         let last_a = a;
         // make sure the new point is at least tau / path_count radians from the previous
         // prevents paths from overlapping too much
@@ -39,6 +139,7 @@ pub fn freeform(size: usize, outfile: &str, floodfile: &str) {
         }
         random_walk_fill(
             &mut map,
+            &mut rng,
             &(cx, cy),
             &a,
             0.02,
@@ -46,27 +147,70 @@ pub fn freeform(size: usize, outfile: &str, floodfile: &str) {
             2,
             0.3,
         );
+        progress.report(path + 1, total_steps, &map);
+    }
+
+    // Branching tunnels, pulled loosely toward the spawn room so they join the caves
+    if config.dla_tiles > 0 {
+        let spawn_points = config.dla_seeding.points(&map, &mut rng);
+        if !spawn_points.is_empty() {
+            let dla_config = dla::DlaConfig {
+                target_tiles: config.dla_tiles,
+                max_particles: config.dla_tiles * 20,
+                spawn_points,
+                bias_points: vec![(cx, cy)],
+                bias_chance: 0.1,
+            };
+            if config.parallel_dla {
+                let seed = rng.random();
+                dla::par_dla_generation(&mut map, seed, &dla_config, &mut Progress::none());
+            } else {
+                dla::dla_generation(&mut map, &mut rng, &dla_config, &mut Progress::none());
+            }
+        }
+        progress.report(path_count + 1, total_steps, &map);
     }
 
     // scale out to guarantee solid boarder
     resize(&mut map, size, size);
-    let (cx, cy) = center(&map);
 
     // Add some noise to make it less uniform
     simplex::generate_simplex_noise(&mut map, &mut rng, 0.045, 0.8);
     simplex::generate_simplex_noise(&mut map, &mut rng, 0.1, 0.7);
 
-    // Smoothing
-    ca::cellular_automata(&mut map, 5, |_, _| { });
+    apply_symmetry(&mut map, config.symmetry);
+    progress.report(path_count + tunnel_steps + 1, total_steps, &map);
 
-    save_png(outfile, &map).unwrap();
+    // Smoothing, which also evens out the seams between mirrored parts
+    let mut grid = Grid::from_rows(map);
+    for pass in 1..=config.ca_passes {
+        ca::smooth(&mut grid);
+        progress.report_grid(path_count + tunnel_steps + 1 + pass, total_steps, &grid);
+    }
+    let mut map = grid.into_rows();
+    post::corridors::widen_corridors(&mut map, config.min_corridor_width);
+    progress.report(total_steps, total_steps, &map);
 
-    let distances = post::flood::flood_fill(&mut map, (cx, cy));
-    let flooded = post::flood::threshold(&distances, usize::MAX);
-    save_png(floodfile, &flooded).unwrap();
+    map
 }
 
-fn save_png(path: &str, map: &Vec<Vec<bool>>) -> Result<(), std::io::Error> {
+/// The floor that can be walked to from the floor cell nearest the middle
+///
+/// For a freeform map that's the spawn room. A map with no floor comes back as it is.
+pub fn reachable(map: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let (width, height) = (map[0].len(), map.len());
+    let middle = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| map[y][x])
+        .min_by_key(|&(x, y)| x.abs_diff(width / 2).pow(2) + y.abs_diff(height / 2).pow(2));
+    match middle {
+        Some(start) => post::flood::threshold(&post::flood::flood_fill(map, start), usize::MAX),
+        None => map.to_vec(),
+    }
+}
+
+/// Write a map to a black and white PNG, white for floor
+pub fn save_png(path: &str, map: &[Vec<bool>]) -> Result<(), std::io::Error> {
     let height = map.len();
     let width = map[0].len();
     let mut imgbuf: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::new(width as u32, height as u32);
@@ -85,68 +229,27 @@ fn save_png(path: &str, map: &Vec<Vec<bool>>) -> Result<(), std::io::Error> {
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
 }
 
-/// Crop the map to the specified rectangle
+/// Grow or crop the map to the specified rectangle, keeping it centered; new space is wall
 fn resize(map: &mut Vec<Vec<bool>>, new_width: usize, new_height: usize) {
-    let old_width = map[0].len();
-    let old_height = map.len();
-
-    let offset_x = (new_width.saturating_sub(old_width)) / 2;
-    let offset_y = (new_height.saturating_sub(old_height)) / 2;
-
-    let mut new_map = vec![vec![false; new_width]; new_height];
-
-    for y in 0..old_height.min(new_height) {
-        for x in 0..old_width.min(new_width) {
-            new_map[y + offset_y][x + offset_x] = map[y][x];
-        }
-    }
-
-    *map = new_map;
+    let mut grid = Grid::from_rows(std::mem::take(map));
+    grid.resize(new_width, new_height, false);
+    *map = grid.into_rows();
 }
 
-fn fill(map: &mut Vec<Vec<bool>>, points: &Vec<(usize, usize)>) {
+pub(crate) fn fill(map: &mut Vec<Vec<bool>>, points: &Vec<(usize, usize)>) {
     for (x, y) in points {
         map[*y][*x] = true;
     }
 }
 
 /// Bresenham's line algorithm to get all points between two coordinates
-fn line(map: &Vec<Vec<bool>>, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
-    let (x0, y0) = (a.0 as isize, a.1 as isize);
-    let (x1, y1) = (b.0 as isize, b.1 as isize);
-    let dx = (x1 - x0).abs();
-    let dy = -(y1 - y0).abs();
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let sy = if y0 < y1 { 1 } else { -1 };
-    let mut err = dx + dy;
-    let mut points = Vec::new();
-
-    let mut x = x0;
-    let mut y = y0;
-
-    loop {
-        if x >= 0 && y >= 0 && (x as usize) < map[0].len() && (y as usize) < map.len() {
-            points.push((x as usize, y as usize));
-        }
-        if x == x1 && y == y1 {
-            break;
-        }
-        let e2 = 2 * err;
-        if e2 >= dy {
-            err += dy;
-            x += sx;
-        }
-        if e2 <= dx {
-            err += dx;
-            y += sy;
-        }
-    }
-
-    points
+pub(crate) fn line(map: &Vec<Vec<bool>>, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
+    grid::line(map[0].len(), map.len(), a, b)
 }
 
 fn random_walk_fill(
     map: &mut Vec<Vec<bool>>,
+    rng: &mut impl Rng,
     spawn_point: &(usize, usize),
     bias_point: &(usize, usize),
     bias_chance: f64,
@@ -154,12 +257,13 @@ fn random_walk_fill(
     path_thickness: usize,
     steer_aggression: f64,
 ) {
-    let path = random_walk(map, spawn_point, bias_point, bias_chance, path_length, path_thickness, steer_aggression);
+    let path = random_walk(map, rng, spawn_point, bias_point, bias_chance, path_length, path_thickness, steer_aggression);
     fill(map, &path);
 }
 
 fn random_walk(
     map: &Vec<Vec<bool>>,
+    rng: &mut impl Rng,
     spawn_point: &(usize, usize),
     bias_point: &(usize, usize),
     bias_chance: f64,
@@ -169,7 +273,6 @@ fn random_walk(
 ) -> Vec<(usize, usize)> {
     let width = map[0].len();
     let height = map.len();
-    let mut rng = rand::rng();
     let (mut x, mut y) = *spawn_point;
     let mut path = Vec::new();
 
@@ -218,38 +321,20 @@ fn random_walk(
     path
 }
 
-/// Place a single seed in the center of the map
-fn center_seed(map: &mut Vec<Vec<bool>>) {
-    let (cx, cy) = center(map);
-}
-
 fn center(map: &Vec<Vec<bool>>) -> (usize, usize) {
     let width = map[0].len();
     let height = map.len();
     (width / 2, height / 2)
 }
 
-/// Generate `count` random seeds within the map bounds
-fn random_seeds(map: &mut Vec<Vec<bool>>, count: usize) {
-    for (dx, dy) in random(map, count) {
-        map[dy][dx] = true;
-    }
-}
-
-fn random(map: &Vec<Vec<bool>>, count: usize) -> Vec<(usize, usize)> {
-    let mut rng = rand::rng();
+/// `count` seeds anywhere in the map
+fn random(map: &Vec<Vec<bool>>, rng: &mut impl Rng, count: usize) -> Vec<(usize, usize)> {
     let width = map[0].len();
     let height = map.len();
     (0..count).map(|_| (rng.random_range(0..width), rng.random_range(0..height))).collect()
 }
 
-/// Generate seeds in a regular grid pattern
-fn regular_seeds(map: &mut Vec<Vec<bool>>, spacing: usize) {
-    for (dx, dy) in regular(map, spacing) {
-        map[dy][dx] = true;
-    }
-}
-
+/// Seeds on a grid, `spacing` cells apart
 fn regular(map: &Vec<Vec<bool>>, spacing: usize) -> Vec<(usize, usize)> {
     let width = map[0].len();
     let height = map.len();
@@ -262,13 +347,7 @@ fn regular(map: &Vec<Vec<bool>>, spacing: usize) -> Vec<(usize, usize)> {
     seeds
 }
 
-/// Generate seeds in a ring pattern around the center
-fn ring_seeds(map: &mut Vec<Vec<bool>>, count: usize, radius: usize) {
-    for (dx, dy) in ring(map, count, radius) {
-        map[dy][dx] = true;
-    }
-}
-
+/// `count` seeds evenly spaced on a ring around the center
 fn ring(map: &Vec<Vec<bool>>, count: usize, radius: usize) -> Vec<(usize, usize)> {
     let width = map[0].len();
     let height = map.len();
@@ -286,15 +365,8 @@ fn ring(map: &Vec<Vec<bool>>, count: usize, radius: usize) -> Vec<(usize, usize)
     seeds
 }
 
-/// Generate clusters of seeds scattered across the map
-fn cluster_seeds(map: &mut Vec<Vec<bool>>, cluster_count: usize, cluster_size: usize, cluster_radius: usize) {
-    for (dx, dy) in cluster(map, cluster_count, cluster_size, cluster_radius) {
-        map[dy][dx] = true;
-    }
-}
-
-fn cluster(map: &Vec<Vec<bool>>, cluster_count: usize, cluster_size: usize, cluster_radius: usize) -> Vec<(usize, usize)> {
-    let mut rng = rand::rng();
+/// Clumps of seeds scattered across the map
+fn cluster(map: &Vec<Vec<bool>>, rng: &mut impl Rng, cluster_count: usize, cluster_size: usize, cluster_radius: usize) -> Vec<(usize, usize)> {
     let width = map[0].len();
     let height = map.len();
     let mut seeds = Vec::new();
@@ -315,29 +387,71 @@ fn cluster(map: &Vec<Vec<bool>>, cluster_count: usize, cluster_size: usize, clus
     seeds
 }
 
-/// Draw a filled circle of seeds centered in the map with the given radius
-fn circle_fill_seeds(map: &mut Vec<Vec<bool>>, radius: usize) {
+/// Bridson's Poisson disk sampling: new seeds are tried around ones already
+/// placed until no room is left, so seeds cover the map evenly without the
+/// clumps and gaps of `random` or the lines of `regular`
+fn poisson(map: &[Vec<bool>], rng: &mut impl Rng, min_distance: usize) -> Vec<(usize, usize)> {
+    // Tries around a seed before it's given up on
+    const ATTEMPTS: usize = 30;
+
     let width = map[0].len();
     let height = map.len();
-    let center_x = width as f64 / 2.0;
-    let center_y = height as f64 / 2.0;
-
-    for y in 0..height {
-        for x in 0..width {
-            let dx = x as f64 - center_x;
-            let dy = y as f64 - center_y;
-            if (dx * dx + dy * dy).sqrt() <= radius as f64 {
-                map[y][x] = true;
+    let min_distance = min_distance.max(1) as f64;
+
+    // Buckets small enough that each holds at most one seed
+    let bucket_size = min_distance / std::f64::consts::SQRT_2;
+    let buckets_x = (width as f64 / bucket_size).ceil() as usize;
+    let buckets_y = (height as f64 / bucket_size).ceil() as usize;
+    let bucket = |(x, y): (usize, usize)| ((x as f64 / bucket_size) as usize, (y as f64 / bucket_size) as usize);
+    let mut buckets: Vec<Vec<Option<usize>>> = vec![vec![None; buckets_x]; buckets_y];
+
+    let first = (rng.random_range(0..width), rng.random_range(0..height));
+    let (bx, by) = bucket(first);
+    buckets[by][bx] = Some(0);
+    let mut seeds = vec![first];
+    // Seeds that might still have room around them
+    let mut active = vec![0];
+
+    while !active.is_empty() {
+        let index = rng.random_range(0..active.len());
+        let (x, y) = seeds[active[index]];
+
+        let candidate = (0..ATTEMPTS).find_map(|_| {
+            let angle = rng.random_range(0.0..std::f64::consts::TAU);
+            let radius = rng.random_range(min_distance..2.0 * min_distance);
+            let cx = (x as f64 + angle.cos() * radius).round();
+            let cy = (y as f64 + angle.sin() * radius).round();
+            if cx < 0.0 || cy < 0.0 || cx >= width as f64 || cy >= height as f64 {
+                return None;
+            }
+            let candidate = (cx as usize, cy as usize);
+
+            let (bx, by) = bucket(candidate);
+            let too_close = (by.saturating_sub(2)..(by + 3).min(buckets_y))
+                .flat_map(|ny| (bx.saturating_sub(2)..(bx + 3).min(buckets_x)).map(move |nx| (nx, ny)))
+                .filter_map(|(nx, ny)| buckets[ny][nx])
+                .any(|other| {
+                    let (ox, oy) = seeds[other];
+                    let (dx, dy) = (ox as f64 - cx, oy as f64 - cy);
+                    dx * dx + dy * dy < min_distance * min_distance
+                });
+            (!too_close).then_some(candidate)
+        });
+
+        match candidate {
+            Some(candidate) => {
+                let (bx, by) = bucket(candidate);
+                buckets[by][bx] = Some(seeds.len());
+                active.push(seeds.len());
+                seeds.push(candidate);
+            }
+            None => {
+                active.swap_remove(index);
             }
         }
     }
-}
 
-/// Draw a contiguous, hollow circle of seeds centered in the map with the given thickness
-fn circle_seeds(map: &mut Vec<Vec<bool>>, thickness: usize, radius: usize) {
-    for (dx, dy) in circle(map, thickness, radius, (map[0].len() / 2, map.len() / 2)) {
-        map[dy][dx] = true;
-    }
+    seeds
 }
 
 fn circle(map: &Vec<Vec<bool>>, thickness: usize, radius: usize, center: (usize, usize)) -> Vec<(usize, usize)> {
@@ -379,64 +493,110 @@ fn square_fill_seeds(map: &mut Vec<Vec<bool>>, half_size: usize) {
     }
 }
 
-/// Draw a cross pattern of seeds centered in the map
-fn cross_seeds(map: &mut Vec<Vec<bool>>, thickness: usize, length: usize) {
-    let width = map[0].len();
-    let height = map.len();
-    let center_x = width / 2;
-    let center_y = height / 2;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::maps;
+    use proptest::prelude::*;
+
+    /// Whether every floor cell can be walked to from every other one
+    fn is_one_region(map: &[Vec<bool>]) -> bool {
+        let floor: Vec<(usize, usize)> = (0..map.len())
+            .flat_map(|y| (0..map[0].len()).map(move |x| (x, y)))
+            .filter(|&(x, y)| map[y][x])
+            .collect();
+        let Some(&start) = floor.first() else {
+            return true;
+        };
+        let distances = post::flood::flood_fill(map, start);
+        floor.iter().all(|&(x, y)| distances[y][x] != usize::MAX)
+    }
 
-    for t in 0..thickness {
-        let offset = t as isize - (thickness as isize / 2);
-        for l in 0..length {
-            if center_x + l < width {
-                map[center_y.wrapping_add(offset as usize)][center_x + l] = true;
-            }
-            if center_x >= l {
-                map[center_y.wrapping_add(offset as usize)][center_x - l] = true;
-            }
-            if center_y + l < height {
-                map[center_y + l][center_x.wrapping_add(offset as usize)] = true;
-            }
-            if center_y >= l {
-                map[center_y - l][center_x.wrapping_add(offset as usize)] = true;
+    proptest! {
+        #[test]
+        fn test_reachable_is_one_region(map in maps(40)) {
+            let reachable = reachable(&map);
+            prop_assert!(is_one_region(&reachable));
+            // Only floor from the map
+            for y in 0..map.len() {
+                for x in 0..map[0].len() {
+                    prop_assert!(!reachable[y][x] || map[y][x]);
+                }
             }
         }
-    }
-}
 
-fn diamond_seeds(map: &mut Vec<Vec<bool>>, thickness: usize, half_size: usize) {
-    // Draw hollow diamond by only drawing points at specific Manhattan distances
-    for (dx, dy) in diamond(map, thickness, half_size, (map[0].len() / 2, map.len() / 2)) {
-        map[dy][dx] = true;
-    }
-}
+        #[test]
+        fn test_resize_round_trips(map in maps(30), grow_x in 0..20usize, grow_y in 0..20usize) {
+            let (width, height) = (map[0].len(), map.len());
+            let mut resized = map.clone();
+            resize(&mut resized, width + grow_x, height + grow_y);
+            let floor = |map: &[Vec<bool>]| map.iter().flatten().filter(|&&floor| floor).count();
+            prop_assert_eq!(floor(&resized), floor(&map));
 
-fn diamond(map: &Vec<Vec<bool>>, thickness: usize, half_size: usize, center: (usize, usize)) -> Vec<(usize, usize)> {
-    let width = map[0].len();
-    let height = map.len();
-    let center_x = center.0;
-    let center_y = center.1;
-    let mut seeds = Vec::new();
+            resize(&mut resized, width, height);
+            prop_assert_eq!(resized, map);
+        }
 
-    // Draw hollow diamond by only drawing points at specific Manhattan distances
-    for t in 0..thickness {
-        let target_distance = (half_size as isize + t as isize).max(0) as usize;
-
-        // Iterate through all points in the map
-        for y in 0..height {
-            for x in 0..width {
-                // Calculate Manhattan distance from center
-                let manhattan_distance =
-                    ((x as isize - center_x as isize).abs() +
-                     (y as isize - center_y as isize).abs()) as usize;
-
-                // Only draw points that are exactly at the target distance
-                if manhattan_distance == target_distance {
-                    seeds.push((x, y));
+        #[test]
+        fn test_resize_keeps_content_centered(width in 1..30usize, height in 1..30usize, new_width in 1..60usize, new_height in 1..60usize) {
+            let mut map = vec![vec![true; width]; height];
+            resize(&mut map, new_width, new_height);
+
+            // Floor columns and rows sit in the middle, off by at most one cell
+            let columns: Vec<usize> = (0..new_width).filter(|&x| map.iter().any(|row| row[x])).collect();
+            let rows: Vec<usize> = (0..new_height).filter(|&y| map[y].iter().any(|&floor| floor)).collect();
+            let margins = |kept: &[usize], size: usize| (kept[0], size - 1 - kept[kept.len() - 1]);
+            let (left, right) = margins(&columns, new_width);
+            let (top, bottom) = margins(&rows, new_height);
+            prop_assert!(left.abs_diff(right) <= 1);
+            prop_assert!(top.abs_diff(bottom) <= 1);
+        }
+
+        #[test]
+        fn test_poisson_seeds_keep_their_distance(map in maps(60), min_distance in 1..10usize, seed in any::<u64>()) {
+            let seeds = poisson(&map, &mut StdRng::seed_from_u64(seed), min_distance);
+            prop_assert!(!seeds.is_empty());
+            for (i, &(ax, ay)) in seeds.iter().enumerate() {
+                prop_assert!(ax < map[0].len() && ay < map.len());
+                for &(bx, by) in &seeds[i + 1..] {
+                    prop_assert!(ax.abs_diff(bx).pow(2) + ay.abs_diff(by).pow(2) >= min_distance.pow(2));
                 }
             }
         }
     }
-    seeds
+
+    #[test]
+    fn test_progress_ends_with_the_finished_map() {
+        let config = FreeformConfig { size: 64, seed: 7, ..Default::default() };
+        let (mut progress, receiver) = Progress::channel(1);
+        let map = freeform_with_progress(&config, &mut progress);
+        drop(progress);
+
+        let events: Vec<_> = receiver.iter().collect();
+        let Some(crate::progress::GenerationEvent::Progress { step, total, snapshot }) = events.last() else {
+            panic!("no progress reported");
+        };
+        assert_eq!(step, total);
+        assert_eq!(events.len(), *total);
+        assert_eq!(*snapshot, map);
+        assert_eq!(map, freeform(&config));
+    }
+
+    #[test]
+    fn test_tunnels_change_the_map_and_report_a_step() {
+        let plain = FreeformConfig { size: 64, seed: 7, ..Default::default() };
+        let config = FreeformConfig { dla_tiles: 200, dla_seeding: Seeding::Poisson { min_distance: 6 }, ..plain };
+        let steps = |config: &FreeformConfig| {
+            let (mut progress, receiver) = Progress::channel(1);
+            let map = freeform_with_progress(config, &mut progress);
+            drop(progress);
+            (map, receiver.iter().count())
+        };
+
+        let (map, tunnel_steps) = steps(&config);
+        let (plain_map, plain_steps) = steps(&plain);
+        assert_eq!(tunnel_steps, plain_steps + 1);
+        assert_ne!(map, plain_map);
+        assert_eq!(map, freeform(&config));
+    }
 }
@@ -0,0 +1,321 @@
+//! Maps holding any kind of cell, not just floor and wall
+//!
+//! The operators the generators use on `Vec<Vec<bool>>` maps work on a
+//! `Grid<T>` too, so maps can carry tile types, biome ids or heights. The
+//! floor and wall versions are thin wrappers over these.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::{Index, IndexMut};
+
+use serde::{Deserialize, Serialize};
+
+/// A rectangle of cells, stored by row and indexed by `(x, y)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "SavedGrid<T>")]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// A grid as loaded, before its size is checked against its cells
+#[derive(Deserialize)]
+struct SavedGrid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> TryFrom<SavedGrid<T>> for Grid<T> {
+    type Error = String;
+
+    fn try_from(SavedGrid { width, height, cells }: SavedGrid<T>) -> Result<Self, String> {
+        if cells.len() != width * height {
+            return Err(format!("{} cells don't fill a {}x{} grid", cells.len(), width, height));
+        }
+        Ok(Self { width, height, cells })
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` x `height` grid with every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self { width, height, cells: vec![value; width * height] }
+    }
+
+    /// Every cell set by `cell(x, y)`
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..width * height).map(|i| cell(i % width, i / width)).collect();
+        Self { width, height, cells }
+    }
+
+    /// Rows of equal length, top first
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let (width, height) = (rows.first().map_or(0, Vec::len), rows.len());
+        assert!(rows.iter().all(|row| row.len() == width), "Rows of a grid must all be {} cells long", width);
+        Self { width, height, cells: rows.into_iter().flatten().collect() }
+    }
+
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        if self.width == 0 {
+            return vec![Vec::new(); self.height];
+        }
+        self.cells.chunks(self.width).map(<[T]>::to_vec).collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Cells with their positions, by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells.iter().enumerate().map(|(i, cell)| ((i % self.width, i / self.width), cell))
+    }
+
+    /// A grid the same size with `f` applied to every cell
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// The cells above, below, left and right of `cell` that are on the grid
+    pub fn neighbors(&self, (x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let (width, height) = (self.width, self.height);
+        [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)]
+            .into_iter()
+            .filter(move |&(nx, ny)| nx < width && ny < height)
+    }
+
+    /// Set every cell in `points` to `value`
+    pub fn fill(&mut self, points: &[(usize, usize)], value: T) {
+        for &point in points {
+            self[point] = value.clone();
+        }
+    }
+
+    /// Bresenham line from `a` to `b`, keeping only the cells on the grid
+    pub fn line(&self, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
+        line(self.width, self.height, a, b)
+    }
+
+    /// Grow or crop to `width` x `height`, keeping the old cells centered; new space gets `value`
+    pub fn resize(&mut self, width: usize, height: usize, value: T) {
+        // Old cell for each new one, as an offset; negative when growing
+        let shift_x = (self.width as isize - width as isize) / 2;
+        let shift_y = (self.height as isize - height as isize) / 2;
+
+        *self = Self::from_fn(width, height, |x, y| {
+            let (old_x, old_y) = (x as isize + shift_x, y as isize + shift_y);
+            if old_x < 0 || old_y < 0 {
+                return value.clone();
+            }
+            self.get((old_x as usize, old_y as usize)).cloned().unwrap_or_else(|| value.clone())
+        });
+    }
+
+    /// Steps from `start` to every cell reachable through cells `passable` says yes to
+    ///
+    /// Moves go up, down, left and right; unreachable cells are `usize::MAX`.
+    /// `start` itself is 0 whether it's passable or not.
+    pub fn flood_fill(&self, start: (usize, usize), passable: impl Fn(&T) -> bool) -> Grid<usize> {
+        let mut distances = Grid::new(self.width, self.height, usize::MAX);
+        let mut queue = VecDeque::from([start]);
+        distances[start] = 0;
+
+        while let Some(cell) = queue.pop_front() {
+            let next = distances[cell] + 1;
+            for neighbor in self.neighbors(cell) {
+                if passable(&self[neighbor]) && distances[neighbor] == usize::MAX {
+                    distances[neighbor] = next;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Cheapest cost from the nearest of `sources` to every cell, walking up, down, left and right
+    ///
+    /// `cost` is what stepping onto a cell costs, or `None` for cells that
+    /// can't be entered. Sources are 0 whatever they cost; unreachable cells
+    /// are `u32::MAX`. With every cost 1 it's the step count, like
+    /// `flood_fill` from several starts at once.
+    pub fn distance_field(&self, sources: &[(usize, usize)], cost: impl Fn(&T) -> Option<u32>) -> Grid<u32> {
+        let mut distances = Grid::new(self.width, self.height, u32::MAX);
+        let mut heap = BinaryHeap::new();
+        for &source in sources {
+            distances[source] = 0;
+            heap.push(Reverse((0, source)));
+        }
+
+        while let Some(Reverse((distance, cell))) = heap.pop() {
+            if distance > distances[cell] {
+                continue;
+            }
+            for neighbor in self.neighbors(cell) {
+                let Some(step) = cost(&self[neighbor]) else {
+                    continue;
+                };
+                let next = distance.saturating_add(step);
+                if next < distances[neighbor] {
+                    distances[neighbor] = next;
+                    heap.push(Reverse((next, neighbor)));
+                }
+            }
+        }
+        distances
+    }
+
+    /// Run `iterations` passes of a cellular automaton over every cell but the outer ring
+    ///
+    /// `rule` gets a cell and its eight neighbors, from the top left by row,
+    /// and gives the cell's next value. Every cell in a pass sees the grid as
+    /// it was before the pass.
+    pub fn cellular_automata(&mut self, iterations: usize, rule: impl Fn(&T, [&T; 8]) -> T) {
+        for _ in 0..iterations {
+            let mut next = self.clone();
+            for y in 1..self.height.saturating_sub(1) {
+                for x in 1..self.width.saturating_sub(1) {
+                    let around = [
+                        (x - 1, y - 1), (x, y - 1), (x + 1, y - 1),
+                        (x - 1, y), (x + 1, y),
+                        (x - 1, y + 1), (x, y + 1), (x + 1, y + 1),
+                    ];
+                    next[(x, y)] = rule(&self[(x, y)], around.map(|cell| &self[cell]));
+                }
+            }
+            *self = next;
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width && y < self.height, "({}, {}) is off a {}x{} grid", x, y, self.width, self.height);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width && y < self.height, "({}, {}) is off a {}x{} grid", x, y, self.width, self.height);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Clone> From<Vec<Vec<T>>> for Grid<T> {
+    fn from(rows: Vec<Vec<T>>) -> Self {
+        Self::from_rows(rows)
+    }
+}
+
+impl<T: Clone> From<Grid<T>> for Vec<Vec<T>> {
+    fn from(grid: Grid<T>) -> Self {
+        grid.into_rows()
+    }
+}
+
+/// Automaton rule for cells with many values, like biome ids: a value held
+/// by at least `at_least` of the neighbors takes the cell over
+pub fn majority<T: Clone + Eq>(cell: &T, neighbors: [&T; 8], at_least: usize) -> T {
+    neighbors
+        .iter()
+        .find(|&&candidate| neighbors.iter().filter(|&&other| other == candidate).count() >= at_least)
+        .map_or_else(|| cell.clone(), |&winner| winner.clone())
+}
+
+/// Bresenham line from `a` to `b`, keeping only the cells inside `width` x `height`
+pub fn line(width: usize, height: usize, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
+    let (x0, y0) = (a.0 as isize, a.1 as isize);
+    let (x1, y1) = (b.0 as isize, b.1 as isize);
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut points = Vec::new();
+
+    let mut x = x0;
+    let mut y = y0;
+
+    loop {
+        if x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height {
+            points.push((x as usize, y as usize));
+        }
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::maps;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_rows_round_trip(map in maps(40)) {
+            let grid = Grid::from_rows(map.clone());
+            prop_assert_eq!(grid.width(), map[0].len());
+            prop_assert_eq!(grid.height(), map.len());
+            for ((x, y), &cell) in grid.iter() {
+                prop_assert_eq!(cell, map[y][x]);
+            }
+            prop_assert_eq!(grid.into_rows(), map);
+        }
+    }
+
+    #[test]
+    fn test_grids_carry_more_than_floor_and_wall() {
+        // Biome ids: a lone 2 in a field of 1s is swallowed, walls of 0 stop the flood
+        let mut biomes = Grid::from_fn(7, 5, |x, _| if x == 3 { 0 } else { 1 });
+        biomes[(1, 2)] = 2;
+        biomes.cellular_automata(1, |cell, neighbors| majority(cell, neighbors, 7));
+        assert_eq!(biomes[(1, 2)], 1);
+        assert_eq!(biomes[(3, 2)], 0);
+
+        let distances = biomes.flood_fill((1, 2), |&biome| biome != 0);
+        assert_eq!(distances[(2, 2)], 1);
+        assert_eq!(distances[(5, 2)], usize::MAX);
+
+        // Biome 2 is slow going, so the way round through 1s is cheaper; 0 can't be crossed
+        let mut terrain = Grid::new(5, 3, 1);
+        terrain.fill(&[(1, 0), (1, 1), (2, 1), (3, 1)], 2);
+        let cost = |&biome: &i32| (biome != 0).then(|| biome as u32 * 5 - 4);
+        let costs = terrain.distance_field(&[(0, 1), (4, 1)], cost);
+        assert_eq!((costs[(1, 1)], costs[(2, 2)], costs[(2, 1)]), (6, 3, 9));
+        terrain.fill(&[(2, 0), (2, 1), (2, 2)], 0);
+        assert_eq!(terrain.distance_field(&[(0, 1)], cost)[(4, 1)], u32::MAX);
+
+        let mut heights = biomes.map(|&biome| biome as f32 / 2.0);
+        heights.resize(9, 7, -1.0);
+        assert_eq!(heights[(0, 0)], -1.0);
+        assert_eq!(heights[(4, 3)], 0.0);
+    }
+}
@@ -0,0 +1,92 @@
+//! Heightmaps from fractal simplex noise, for terrain with more than floor and wall
+//!
+//! `heightmap` gives a field of heights, and `post::terrain::classify` cuts it
+//! into water, floor and wall by height.
+use noise::{NoiseFn, OpenSimplex};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+/// Settings for a fractal Brownian motion heightmap
+#[derive(Debug, Clone, Copy)]
+pub struct HeightmapConfig {
+    pub width: usize,
+    pub height: usize,
+    /// The same seed and settings always give the same field
+    pub seed: u64,
+    /// Frequency of the first octave, in noise units per cell; smaller is smoother
+    pub scale: f64,
+    /// Layers of noise summed together, each finer than the last
+    pub octaves: usize,
+    /// How much finer each octave is than the one before
+    pub lacunarity: f64,
+    /// How much weaker each octave is than the one before
+    pub persistence: f64,
+}
+
+impl Default for HeightmapConfig {
+    fn default() -> Self {
+        Self {
+            width: 256,
+            height: 256,
+            seed: 0,
+            scale: 0.02,
+            octaves: 5,
+            lacunarity: 2.0,
+            persistence: 0.5,
+        }
+    }
+}
+
+/// Heights for every cell, by row, stretched so the lowest is 0 and the highest 1
+///
+/// Stretching makes thresholds fractions of the height range, so they cut off
+/// about the same share of the map whatever the octaves and scale. A flat
+/// field comes back all 0.
+pub fn heightmap(config: &HeightmapConfig) -> Vec<Vec<f32>> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let octaves: Vec<OpenSimplex> = (0..config.octaves.max(1)).map(|_| OpenSimplex::new(rng.random())).collect();
+
+    let mut field: Vec<Vec<f32>> = (0..config.height)
+        .map(|y| {
+            (0..config.width)
+                .map(|x| {
+                    let (mut frequency, mut amplitude, mut value) = (config.scale, 1.0, 0.0);
+                    for noise in &octaves {
+                        value += amplitude * noise.get([x as f64 * frequency, y as f64 * frequency]);
+                        frequency *= config.lacunarity;
+                        amplitude *= config.persistence;
+                    }
+                    value as f32
+                })
+                .collect()
+        })
+        .collect();
+
+    let (low, high) = field.iter().flatten().fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), &value| {
+        (low.min(value), high.max(value))
+    });
+    let range = high - low;
+    for value in field.iter_mut().flatten() {
+        *value = if range > 0.0 { (*value - low) / range } else { 0.0 };
+    }
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_heights_span_zero_to_one(width in 1..40usize, height in 1..40usize, seed in any::<u64>(), octaves in 0..6usize) {
+            let config = HeightmapConfig { width, height, seed, octaves, ..Default::default() };
+            let field = heightmap(&config);
+
+            prop_assert_eq!(field.len(), height);
+            prop_assert!(field.iter().all(|row| row.len() == width));
+            prop_assert!(field.iter().flatten().all(|value| (0.0..=1.0).contains(value)));
+            prop_assert_eq!(&heightmap(&config), &field);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use rand::Rng;
+
+use super::graph::{LevelGraph, RoomKind};
+use super::LayoutConfig;
+use crate::freeform::{ca, fill, line};
+use crate::progress::Progress;
+
+/// Carve `graph` into a map, `config.spacing` cells per grid slot
+///
+/// Rooms become round caverns near the middle of their slot and corridors
+/// straight tunnels between neighboring slots, so nothing connects that the
+/// graph doesn't connect. Sets each room's `center` and `radius`.
+pub fn carve(graph: &mut LevelGraph, config: &LayoutConfig, rng: &mut impl Rng) -> Vec<Vec<bool>> {
+    let spacing = config.spacing;
+    let size = config.grid_size * spacing;
+    let mut map = vec![vec![false; size]; size];
+
+    // Rooms may shift off their slot's middle, but never far enough to touch a neighbor
+    let jitter = spacing / 16;
+    for room in &mut graph.rooms {
+        let offset = (rng.random_range(0..=2 * jitter), rng.random_range(0..=2 * jitter));
+        room.center = (
+            room.slot.0 * spacing + spacing / 2 - jitter + offset.0,
+            room.slot.1 * spacing + spacing / 2 - jitter + offset.1,
+        );
+        room.radius = match room.kind {
+            RoomKind::Boss => spacing * 3 / 8,
+            _ => rng.random_range(spacing / 5..=spacing / 3),
+        };
+        let disc = disc(&map, room.center, room.radius);
+        fill(&mut map, &disc);
+    }
+
+    for corridor in &graph.corridors {
+        let (from, to) = (graph.rooms[corridor.from].center, graph.rooms[corridor.to].center);
+        let tunnel = thicken(&map, &line(&map, from, to), config.corridor_width);
+        fill(&mut map, &tunnel);
+    }
+
+    // Round off the corners where tunnels meet the rooms
+    ca::cellular_automata(&mut map, config.ca_passes, &mut Progress::none());
+    map
+}
+
+/// Cells within `radius` of `center`
+fn disc(map: &[Vec<bool>], center: (usize, usize), radius: usize) -> Vec<(usize, usize)> {
+    let (width, height) = (map[0].len(), map.len());
+    let mut cells = Vec::new();
+    for y in center.1.saturating_sub(radius)..=(center.1 + radius).min(height - 1) {
+        for x in center.0.saturating_sub(radius)..=(center.0 + radius).min(width - 1) {
+            let (dx, dy) = (x as f64 - center.0 as f64, y as f64 - center.1 as f64);
+            if (dx * dx + dy * dy).sqrt() <= radius as f64 {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// `path` drawn with a square brush `width` cells across
+fn thicken(map: &[Vec<bool>], path: &[(usize, usize)], width: usize) -> Vec<(usize, usize)> {
+    let (map_width, map_height) = (map[0].len(), map.len());
+    let (before, after) = (width.saturating_sub(1) / 2, width / 2);
+    let mut cells = Vec::new();
+    for &(x, y) in path {
+        for ty in y.saturating_sub(before)..=(y + after).min(map_height - 1) {
+            for tx in x.saturating_sub(before)..=(x + after).min(map_width - 1) {
+                cells.push((tx, ty));
+            }
+        }
+    }
+    cells
+}
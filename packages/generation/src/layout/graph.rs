@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use rand::Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+
+use super::LayoutConfig;
+
+/// Index of a room in `LevelGraph::rooms`
+pub type RoomId = usize;
+
+/// What a room is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RoomKind {
+    Start,
+    Normal,
+    /// Holds the key to the corridor locked with this number
+    Key(usize),
+    /// Guards the exit; the only way to it
+    Boss,
+    Exit,
+}
+
+/// A room, placed on the layout grid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Room {
+    pub kind: RoomKind,
+    /// Column and row on the layout grid
+    pub slot: (usize, usize),
+    /// Middle of the room on the map, in cells; set when carved
+    pub center: (usize, usize),
+    /// Set when carved
+    pub radius: usize,
+}
+
+/// A corridor between two rooms on neighboring slots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Corridor {
+    pub from: RoomId,
+    pub to: RoomId,
+    /// Number of the key that opens it, if locked
+    pub lock: Option<usize>,
+    /// Closes a loop rather than leading somewhere new
+    pub is_loop: bool,
+}
+
+/// Rooms and the corridors between them, before anything is carved
+///
+/// Locks are numbered in the order the main path meets them, and the key to
+/// each one sits in a side room that can be reached with only the earlier
+/// keys. Loops never skip a lock, and the exit hangs off the boss room alone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelGraph {
+    pub rooms: Vec<Room>,
+    pub corridors: Vec<Corridor>,
+    /// Rooms from the start to the exit
+    pub main_path: Vec<RoomId>,
+}
+
+impl LevelGraph {
+    pub fn start(&self) -> RoomId {
+        self.main_path[0]
+    }
+
+    pub fn exit(&self) -> RoomId {
+        self.main_path[self.main_path.len() - 1]
+    }
+
+    /// The room holding the key to lock `lock`
+    pub fn key_room(&self, lock: usize) -> Option<RoomId> {
+        self.rooms.iter().position(|room| room.kind == RoomKind::Key(lock))
+    }
+
+    /// Rooms a corridor leads to from `room`, with the corridor's lock
+    pub fn neighbors(&self, room: RoomId) -> impl Iterator<Item = (RoomId, Option<usize>)> + '_ {
+        self.corridors.iter().filter_map(move |corridor| {
+            if corridor.from == room {
+                Some((corridor.to, corridor.lock))
+            } else if corridor.to == room {
+                Some((corridor.from, corridor.lock))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Build a graph that follows `config`, trying `config.attempts` times
+///
+/// Returns `None` when the rooms never fit on the grid, like when the main
+/// path is longer than the grid has slots.
+pub fn generate_graph(config: &LayoutConfig, rng: &mut impl Rng) -> Option<LevelGraph> {
+    (0..config.attempts).find_map(|_| try_generate_graph(config, rng))
+}
+
+/// A graph being built; `regions` counts the locks between the start and each room
+struct Builder<'a> {
+    config: &'a LayoutConfig,
+    graph: LevelGraph,
+    regions: Vec<usize>,
+    slots: HashMap<(usize, usize), RoomId>,
+}
+
+impl Builder<'_> {
+    fn add_room(&mut self, kind: RoomKind, slot: (usize, usize), region: usize) -> RoomId {
+        let id = self.graph.rooms.len();
+        self.graph.rooms.push(Room { kind, slot, center: (0, 0), radius: 0 });
+        self.regions.push(region);
+        self.slots.insert(slot, id);
+        id
+    }
+
+    fn connect(&mut self, from: RoomId, to: RoomId, lock: Option<usize>, is_loop: bool) {
+        self.graph.corridors.push(Corridor { from, to, lock, is_loop });
+    }
+
+    fn is_connected(&self, a: RoomId, b: RoomId) -> bool {
+        self.graph.neighbors(a).any(|(room, _)| room == b)
+    }
+
+    /// Slots next to `slot`, in the grid and taken or not
+    fn adjacent(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let size = self.config.grid_size;
+        [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)]
+            .into_iter()
+            .filter(|&(nx, ny)| nx < size && ny < size)
+            .collect()
+    }
+
+    fn free_adjacent(&self, slot: (usize, usize)) -> Vec<(usize, usize)> {
+        self.adjacent(slot).into_iter().filter(|slot| !self.slots.contains_key(slot)).collect()
+    }
+
+    /// Hang a dead-end room off one of `parents` that has a free slot next to it
+    fn add_side_room(&mut self, kind: RoomKind, parents: &[RoomId], rng: &mut impl Rng) -> Option<RoomId> {
+        let mut parents = parents.to_vec();
+        parents.shuffle(rng);
+        for parent in parents {
+            let parent_slot = self.graph.rooms[parent].slot;
+            if let Some(&slot) = self.free_adjacent(parent_slot).choose(rng) {
+                let room = self.add_room(kind, slot, self.regions[parent]);
+                self.connect(parent, room, None, false);
+                return Some(room);
+            }
+        }
+        None
+    }
+}
+
+fn try_generate_graph(config: &LayoutConfig, rng: &mut impl Rng) -> Option<LevelGraph> {
+    let mut builder = Builder {
+        config,
+        graph: LevelGraph { rooms: Vec::new(), corridors: Vec::new(), main_path: Vec::new() },
+        regions: Vec::new(),
+        slots: HashMap::new(),
+    };
+
+    // Start, the rooms between, the boss, then the exit
+    let main_rooms = config.main_rooms.max(2);
+    let boss = main_rooms - 1;
+    // A lock goes on the way out of a main room between the start and the boss,
+    // so every lock has a room before it to hang its key off
+    let mut lock_edges: Vec<usize> = (1..boss).collect();
+    lock_edges.shuffle(rng);
+    lock_edges.truncate(config.locks);
+    lock_edges.sort();
+
+    // Start in the middle half of the grid so the level has room to spread out
+    let grid = config.grid_size;
+    let middle = grid / 4..(grid - grid / 4).max(grid / 4 + 1);
+    let mut slot = (rng.random_range(middle.clone()), rng.random_range(middle));
+    for index in 0..=main_rooms {
+        let kind = match index {
+            0 => RoomKind::Start,
+            index if index == boss => RoomKind::Boss,
+            index if index == main_rooms => RoomKind::Exit,
+            _ => RoomKind::Normal,
+        };
+        if index > 0 {
+            slot = *builder.free_adjacent(slot).choose(rng)?;
+        }
+        let region = lock_edges.iter().filter(|&&edge| edge < index).count();
+        let room = builder.add_room(kind, slot, region);
+        if let Some(&previous) = builder.graph.main_path.last() {
+            let lock = lock_edges.iter().position(|&edge| edge + 1 == index);
+            builder.connect(previous, room, lock, false);
+        }
+        builder.graph.main_path.push(room);
+    }
+
+    // Each key goes behind the locks before its own, off a room in the same stretch of the main path
+    for (lock, &edge) in lock_edges.iter().enumerate() {
+        let parents: Vec<RoomId> = builder.graph.main_path[..=edge]
+            .iter()
+            .copied()
+            .filter(|&room| builder.regions[room] == lock)
+            .collect();
+        builder.add_side_room(RoomKind::Key(lock), &parents, rng)?;
+    }
+
+    // Side rooms branch off anything but the boss and the exit
+    for _ in 0..config.side_rooms {
+        let parents: Vec<RoomId> = (0..builder.graph.rooms.len())
+            .filter(|&room| !matches!(builder.graph.rooms[room].kind, RoomKind::Boss | RoomKind::Exit))
+            .collect();
+        if builder.add_side_room(RoomKind::Normal, &parents, rng).is_none() {
+            break;
+        }
+    }
+
+    // Loops join neighboring rooms behind the same locks, so they never skip one or the boss
+    let mut loops = Vec::new();
+    for (&slot, &room) in &builder.slots {
+        for other_slot in builder.adjacent(slot) {
+            let Some(&other) = builder.slots.get(&other_slot) else {
+                continue;
+            };
+            let open = |id: RoomId| !matches!(builder.graph.rooms[id].kind, RoomKind::Boss | RoomKind::Exit);
+            if room < other && open(room) && open(other)
+                && builder.regions[room] == builder.regions[other]
+                && !builder.is_connected(room, other)
+            {
+                loops.push((room, other));
+            }
+        }
+    }
+    // Slots iterate in no fixed order, so sort before picking to keep seeds reproducible
+    loops.sort();
+    loops.shuffle(rng);
+    for &(room, other) in loops.iter().take(config.loops) {
+        builder.connect(room, other, None, true);
+    }
+
+    Some(builder.graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use proptest::prelude::*;
+
+    fn configs() -> impl Strategy<Value = LayoutConfig> {
+        (any::<u64>(), 4..10usize, 2..10usize, 0..6usize, 0..4usize, 0..4usize).prop_map(
+            |(seed, grid_size, main_rooms, side_rooms, loops, locks)| LayoutConfig {
+                seed,
+                grid_size,
+                main_rooms,
+                side_rooms,
+                loops,
+                locks,
+                ..LayoutConfig::default()
+            },
+        )
+    }
+
+    /// Rooms the start reaches through open corridors and the first `keys` locks
+    fn reachable(graph: &LevelGraph, keys: usize) -> Vec<bool> {
+        let mut seen = vec![false; graph.rooms.len()];
+        let mut stack = vec![graph.start()];
+        seen[graph.start()] = true;
+        while let Some(room) = stack.pop() {
+            for (next, lock) in graph.neighbors(room) {
+                if !seen[next] && lock.is_none_or(|lock| lock < keys) {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Fewest keys needed to reach each room
+    fn keys_needed(graph: &LevelGraph, locks: usize) -> Vec<usize> {
+        let mut needed = vec![usize::MAX; graph.rooms.len()];
+        for keys in (0..=locks).rev() {
+            for (room, seen) in reachable(graph, keys).into_iter().enumerate() {
+                if seen {
+                    needed[room] = keys;
+                }
+            }
+        }
+        needed
+    }
+
+    proptest! {
+        #[test]
+        fn test_every_key_is_reachable_before_its_lock(config in configs()) {
+            let Some(graph) = generate_graph(&config, &mut StdRng::seed_from_u64(config.seed)) else {
+                return Ok(());
+            };
+            let locks = graph.corridors.iter().filter(|corridor| corridor.lock.is_some()).count();
+            for lock in 0..locks {
+                let key = graph.key_room(lock);
+                prop_assert!(key.is_some());
+                prop_assert!(reachable(&graph, lock)[key.unwrap()]);
+            }
+            prop_assert!(reachable(&graph, locks).into_iter().all(|seen| seen));
+        }
+
+        #[test]
+        fn test_the_exit_is_only_reached_through_the_boss(config in configs()) {
+            let Some(graph) = generate_graph(&config, &mut StdRng::seed_from_u64(config.seed)) else {
+                return Ok(());
+            };
+            let exit_neighbors: Vec<RoomId> = graph.neighbors(graph.exit()).map(|(room, _)| room).collect();
+            prop_assert_eq!(exit_neighbors.len(), 1);
+            prop_assert_eq!(graph.rooms[exit_neighbors[0]].kind, RoomKind::Boss);
+            prop_assert_eq!(graph.rooms.iter().filter(|room| room.kind == RoomKind::Boss).count(), 1);
+        }
+
+        #[test]
+        fn test_loops_never_skip_a_lock_or_the_boss(config in configs()) {
+            let Some(graph) = generate_graph(&config, &mut StdRng::seed_from_u64(config.seed)) else {
+                return Ok(());
+            };
+            let locks = graph.corridors.iter().filter(|corridor| corridor.lock.is_some()).count();
+            let needed = keys_needed(&graph, locks);
+            for corridor in graph.corridors.iter().filter(|corridor| corridor.is_loop) {
+                prop_assert_eq!(corridor.lock, None);
+                prop_assert_eq!(needed[corridor.from], needed[corridor.to]);
+                for room in [corridor.from, corridor.to] {
+                    prop_assert!(!matches!(graph.rooms[room].kind, RoomKind::Boss | RoomKind::Exit));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_graph_has_its_locks_and_loops() {
+        let config = LayoutConfig::default();
+        let graph = generate_graph(&config, &mut StdRng::seed_from_u64(config.seed)).unwrap();
+        assert_eq!(graph.main_path.len(), config.main_rooms + 1);
+        assert_eq!(graph.corridors.iter().filter(|corridor| corridor.lock.is_some()).count(), config.locks);
+        assert!(graph.corridors.iter().filter(|corridor| corridor.is_loop).count() <= config.loops);
+    }
+}
@@ -0,0 +1,89 @@
+//! Levels planned as a graph of rooms first, then carved to match
+//!
+//! `generate_graph` lays out rooms on a coarse grid: a main path from the
+//! start through the boss to the exit, locked corridors with their keys in
+//! side rooms, dead ends and loops. `carve` turns that into tiles, and
+//! `layout` does both and hands back the graph, so quests and locks can be
+//! placed in the rooms it names.
+pub mod carve;
+pub mod graph;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::post::exits::MapMetadata;
+use crate::post::flood::flood_fill;
+use graph::LevelGraph;
+
+/// Settings for a graph-planned level
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutConfig {
+    /// The same seed and settings always give the same level
+    pub seed: u64,
+    /// Slots per side of the square grid rooms are placed on
+    pub grid_size: usize,
+    /// Cells per slot on the carved map
+    pub spacing: usize,
+    /// Rooms from the start to the boss, counting both
+    pub main_rooms: usize,
+    /// Dead-end rooms off the main path, besides the ones holding keys
+    pub side_rooms: usize,
+    /// Extra corridors joining rooms that are already connected another way
+    pub loops: usize,
+    /// Locked corridors on the main path before the boss
+    pub locks: usize,
+    /// Width of the carved corridors, in cells
+    pub corridor_width: usize,
+    /// Cellular automata passes that round off the carved shapes
+    pub ca_passes: usize,
+    /// Graphs tried before giving up
+    pub attempts: usize,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            grid_size: 8,
+            spacing: 32,
+            main_rooms: 8,
+            side_rooms: 4,
+            loops: 2,
+            locks: 2,
+            corridor_width: 3,
+            ca_passes: 3,
+            attempts: 32,
+        }
+    }
+}
+
+/// A carved level with the graph it was carved from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutLevel {
+    /// `true` for floor
+    pub map: Vec<Vec<bool>>,
+    pub graph: LevelGraph,
+    /// Entrance in the start room and exit in the exit room; the walking
+    /// distance between them ignores locks
+    pub metadata: MapMetadata,
+}
+
+/// Plan a level as a graph, then carve it
+///
+/// Returns `None` when no graph fits `config`, like a main path longer than
+/// the grid has slots.
+pub fn layout(config: &LayoutConfig) -> Option<LayoutLevel> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut graph = graph::generate_graph(config, &mut rng)?;
+    let map = carve::carve(&mut graph, config, &mut rng);
+
+    let entrance = graph.rooms[graph.start()].center;
+    let exit = graph.rooms[graph.exit()].center;
+    let path_distance = flood_fill(&map, entrance)[exit.1][exit.0];
+    Some(LayoutLevel {
+        map,
+        graph,
+        metadata: MapMetadata { entrance, exit, path_distance },
+    })
+}
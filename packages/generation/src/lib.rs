@@ -1,3 +1,16 @@
+pub mod bsp;
+pub mod chunked;
 pub mod freeform;
+pub mod grid;
+pub mod heightmap;
+pub mod layout;
+pub mod maze;
 pub mod post;
+pub mod progress;
+pub mod save;
+pub mod tiled;
 pub mod tileset;
+pub mod voronoi;
+
+#[cfg(test)]
+mod strategies;
@@ -1,12 +1,228 @@
-use generation::freeform;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use generation::bsp::{self, BspConfig};
+use generation::chunked::{self, CaveChunks};
+use generation::freeform::{self, FreeformConfig};
+use generation::heightmap::{self, HeightmapConfig};
+use generation::layout::{self, LayoutConfig};
+use generation::maze::{self, MazeAlgorithm, MazeConfig};
+use generation::post::exits::{place_exits, ExitRules};
+use generation::post::rooms::{room_graph, RoomRules};
+use generation::post::symmetry::{apply_symmetry, apply_symmetry_with, Symmetry};
+use generation::post::terrain::{self, Terrain, TerrainThresholds};
+use generation::save::{self, SavedMap};
+use generation::tiled;
+use generation::voronoi::{self, VoronoiConfig};
 use rayon::prelude::*;
 
+/// Generate a map and write it to a PNG, white for floor
+///
+/// `cargo run -p generation -- --algo freeform --size 1024 --seed 42 --out map.png`;
+/// passing the seed printed by an earlier run gives the same map again. An
+/// `--out` ending in `.json` or `.tmx` writes a Tiled map instead, and one in
+/// `.ron` or `.bin` saves the map with its exits and rooms to load again
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, value_enum, default_value_t = Algo::Freeform)]
+    algo: Algo,
+    /// Width and height of the map, in cells
+    #[arg(long, default_value_t = 256)]
+    size: usize,
+    /// Random when not given
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long, default_value = "out/map.png")]
+    out: PathBuf,
+    /// Only write the floor that can be walked to from the middle of the map
+    #[arg(long)]
+    flood: bool,
+    /// Symmetry for arena maps, like `mirror_x`, `rotate4` or `rotate6`; freeform
+    /// maps are made symmetric before smoothing and the rest once generated
+    #[arg(long, value_parser = parse_symmetry, default_value = "none")]
+    symmetry: Symmetry,
+    /// Maps to make, with seeds counting up from `seed`; each file gets its number added
+    #[arg(long, default_value_t = 1)]
+    count: usize,
+    /// How maze paths are chosen
+    #[arg(long, value_enum, default_value_t = MazeStyle::Backtracker)]
+    maze_algorithm: MazeStyle,
+    /// Chance from 0 to 1 of each maze dead end being opened into a loop
+    #[arg(long, default_value_t = 0.0)]
+    braid: f64,
+    /// Cells of branching tunnels grown onto freeform caves, from Poisson-spread starting points
+    #[arg(long, default_value_t = 0)]
+    dla_tiles: usize,
+    /// Grow the tunnels on every core, at the cost of maps no longer repeating exactly for a seed
+    #[arg(long)]
+    parallel_dla: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Algo {
+    /// Caves from random walks and noise
+    Freeform,
+    /// Rooms on a grid joined by corridors, with a main path, loops and locks
+    Layout,
+    /// Rooms in the leaves of a binary space partition, joined by corridors
+    Bsp,
+    /// Voronoi regions with walls between them
+    Voronoi,
+    /// Water, floor and wall from fractal noise
+    Heightmap,
+    /// A maze, picked with `--maze-algorithm` and loosened with `--braid`
+    Maze,
+    /// Caves made a chunk at a time, as an endless world streams them in
+    Chunked,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum MazeStyle {
+    /// Long winding corridors with few branches
+    Backtracker,
+    /// Every maze equally likely, so more short dead ends
+    Wilson,
+}
+
+/// Pixels per tile in exported Tiled maps
+const TILE_SIZE: u32 = 16;
+
+fn parse_symmetry(name: &str) -> Result<Symmetry, String> {
+    Symmetry::parse(name).ok_or_else(|| format!("Unknown symmetry {}", name))
+}
+
 pub fn main() {
-    (0..16).into_par_iter().for_each(|i| {
-        freeform::freeform(
-            256 * (i + 1),
-            &format!("out/output_{}.png", i),
-            &format!("out/flooded_{}.png", i),
-        );
+    let args = Args::parse();
+    let seed = args.seed.unwrap_or_else(rand::random);
+    println!("seed {}", seed);
+
+    if let Some(parent) = args.out.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).unwrap_or_else(|e| panic!("Can't create {}: {}", parent.display(), e));
+    }
+
+    (0..args.count).into_par_iter().for_each(|i| {
+        let out = if args.count == 1 { args.out.clone() } else { numbered(&args.out, i) };
+        generate(&args, seed.wrapping_add(i as u64), &out);
     });
 }
+
+/// `out/map.png` as `out/map_3.png`
+fn numbered(path: &Path, i: usize) -> PathBuf {
+    let stem = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+    let name = match path.extension() {
+        Some(extension) => format!("{}_{}.{}", stem, i, extension.to_string_lossy()),
+        None => format!("{}_{}", stem, i),
+    };
+    path.with_file_name(name)
+}
+
+/// Whether to write a Tiled map rather than a PNG
+fn is_tiled(out: &Path) -> bool {
+    out.extension().is_some_and(|extension| extension == "json" || extension == "tmj" || extension == "tmx")
+}
+
+/// Whether to save the map with its exits and rooms, to load without generating it again
+fn is_saved_map(out: &Path) -> bool {
+    out.extension().is_some_and(|extension| extension == "ron" || extension == "bin")
+}
+
+fn generate(args: &Args, seed: u64, out: &Path) {
+    let path = out.to_string_lossy();
+    let mut map = match args.algo {
+        Algo::Freeform => {
+            let config = FreeformConfig {
+                size: args.size,
+                seed,
+                symmetry: args.symmetry,
+                dla_tiles: args.dla_tiles,
+                parallel_dla: args.parallel_dla,
+                ..Default::default()
+            };
+            freeform::freeform(&config)
+        }
+        Algo::Layout => {
+            let defaults = LayoutConfig::default();
+            let config = LayoutConfig { seed, spacing: (args.size / defaults.grid_size).max(1), ..defaults };
+            let Some(level) = layout::layout(&config) else {
+                println!("{}: no graph fits", path);
+                return;
+            };
+            let graph = &level.graph;
+            println!(
+                "{}: {} rooms, {} on the main path, exit {} cells from the entrance",
+                path, graph.rooms.len(), graph.main_path.len(), level.metadata.path_distance
+            );
+            level.map
+        }
+        Algo::Bsp => {
+            let config = BspConfig { width: args.size, height: args.size, seed, ..Default::default() };
+            bsp::bsp(&config)
+        }
+        Algo::Voronoi => {
+            let config = VoronoiConfig { width: args.size, height: args.size, seed, ..Default::default() };
+            voronoi::voronoi(&config).map
+        }
+        Algo::Heightmap => {
+            let config = HeightmapConfig { width: args.size, height: args.size, seed, ..Default::default() };
+            let mut terrain = terrain::classify(&heightmap::heightmap(&config), &TerrainThresholds::default());
+            apply_symmetry_with(&mut terrain, args.symmetry, Terrain::Wall);
+            let count = |kind: Terrain| terrain.iter().flatten().filter(|&&cell| cell == kind).count();
+            println!(
+                "{}: {} deep water, {} water, {} floor, {} wall",
+                path, count(Terrain::DeepWater), count(Terrain::Water), count(Terrain::Floor), count(Terrain::Wall)
+            );
+            // Water only shows in color; flooding works on where can be walked
+            if !args.flood {
+                if is_tiled(out) {
+                    tiled::terrain_map(&terrain, TILE_SIZE).save(&path).unwrap();
+                } else {
+                    terrain::save_terrain_png(&path, &terrain).unwrap();
+                }
+                return;
+            }
+            terrain::walkable(&terrain)
+        }
+        Algo::Maze => {
+            let defaults = MazeConfig::default();
+            let cells = (args.size / (defaults.corridor_width + defaults.wall_width)).max(1);
+            let algorithm = match args.maze_algorithm {
+                MazeStyle::Backtracker => MazeAlgorithm::RecursiveBacktracker,
+                MazeStyle::Wilson => MazeAlgorithm::Wilson,
+            };
+            maze::maze(&MazeConfig { columns: cells, rows: cells, seed, algorithm, braid: args.braid, ..defaults })
+        }
+        Algo::Chunked => {
+            let caves = CaveChunks::default();
+            let chunks = (args.size / caves.size).max(1);
+            chunked::region(&caves, seed, (0, 0), chunks, chunks).into_rows()
+        }
+    };
+
+    // Freeform smooths its seams itself; heightmap terrain was already done
+    if !matches!(args.algo, Algo::Freeform | Algo::Heightmap) {
+        apply_symmetry(&mut map, args.symmetry);
+    }
+    let map = if args.flood { freeform::reachable(&map) } else { map };
+    if is_tiled(out) {
+        tiled::floor_map(&map, TILE_SIZE).save(&path).unwrap();
+    } else if !is_saved_map(out) {
+        freeform::save_png(&path, &map).unwrap();
+    }
+
+    let rules = ExitRules { min_path_distance: args.size / 4, ..Default::default() };
+    let metadata = place_exits(&map, &rules, seed);
+    match metadata {
+        Some(metadata) => println!(
+            "{}: entrance {:?}, exit {:?}, {} cells apart",
+            path, metadata.entrance, metadata.exit, metadata.path_distance
+        ),
+        None => println!("{}: no room for an entrance and exit", path),
+    }
+
+    let rooms = room_graph(&map, &RoomRules::default());
+    println!("{}: {} rooms, {} corridors", path, rooms.rooms.len(), rooms.corridors.len());
+
+    if is_saved_map(out) {
+        save::save(&path, &SavedMap { seed, map, metadata, rooms }).unwrap();
+    }
+}
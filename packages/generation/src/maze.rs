@@ -0,0 +1,268 @@
+//! Mazes carved out of solid wall
+//!
+//! A maze is planned on a coarse grid of cells, each joined to some of its
+//! neighbors, then drawn onto a floor and wall map with corridors and walls of
+//! any width. Without braiding every pair of cells has exactly one path
+//! between them; braiding knocks out dead ends to add loops.
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::grid::Grid;
+
+/// How the paths through a maze are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    /// Depth first search: long winding corridors with few branches
+    RecursiveBacktracker,
+    /// Loop-erased random walks: every maze equally likely, so more short dead ends
+    Wilson,
+}
+
+/// Settings for a maze
+#[derive(Debug, Clone, Copy)]
+pub struct MazeConfig {
+    /// Maze cells across and down; the map is bigger by the corridor and wall widths
+    pub columns: usize,
+    pub rows: usize,
+    /// The same seed and settings always give the same maze
+    pub seed: u64,
+    pub algorithm: MazeAlgorithm,
+    /// Width of the corridors, in map cells
+    pub corridor_width: usize,
+    /// Width of the walls between corridors and around the outside, in map cells
+    pub wall_width: usize,
+    /// Chance from 0 to 1 of each dead end being opened into a neighbor, adding a loop
+    pub braid: f64,
+}
+
+impl Default for MazeConfig {
+    fn default() -> Self {
+        Self {
+            columns: 32,
+            rows: 32,
+            seed: 0,
+            algorithm: MazeAlgorithm::RecursiveBacktracker,
+            corridor_width: 3,
+            wall_width: 1,
+            braid: 0.0,
+        }
+    }
+}
+
+/// Which maze cells are joined to the cell right of and below them
+#[derive(Debug, Clone)]
+struct Passages {
+    east: Grid<bool>,
+    south: Grid<bool>,
+}
+
+impl Passages {
+    fn new(columns: usize, rows: usize) -> Self {
+        Self { east: Grid::new(columns, rows, false), south: Grid::new(columns, rows, false) }
+    }
+
+    /// Which of the grids holds the wall between two neighboring cells, and at which cell
+    fn wall(a: (usize, usize), b: (usize, usize)) -> (bool, (usize, usize)) {
+        let first = if (a.1, a.0) < (b.1, b.0) { a } else { b };
+        (a.1 == b.1, first)
+    }
+
+    fn connect(&mut self, a: (usize, usize), b: (usize, usize)) {
+        match Self::wall(a, b) {
+            (true, cell) => self.east[cell] = true,
+            (false, cell) => self.south[cell] = true,
+        }
+    }
+
+    fn is_open(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        match Self::wall(a, b) {
+            (true, cell) => self.east[cell],
+            (false, cell) => self.south[cell],
+        }
+    }
+
+    fn degree(&self, cell: (usize, usize)) -> usize {
+        self.east.neighbors(cell).filter(|&neighbor| self.is_open(cell, neighbor)).count()
+    }
+}
+
+/// Generate a maze, `true` for floor
+///
+/// The map is `columns * (corridor_width + wall_width) + wall_width` cells
+/// wide and likewise high, with a wall all the way around.
+pub fn maze(config: &MazeConfig) -> Vec<Vec<bool>> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let (columns, rows) = (config.columns.max(1), config.rows.max(1));
+
+    let mut passages = match config.algorithm {
+        MazeAlgorithm::RecursiveBacktracker => recursive_backtracker(columns, rows, &mut rng),
+        MazeAlgorithm::Wilson => wilson(columns, rows, &mut rng),
+    };
+    braid(&mut passages, config.braid, &mut rng);
+
+    draw(&passages, config.corridor_width.max(1), config.wall_width)
+}
+
+fn recursive_backtracker(columns: usize, rows: usize, rng: &mut impl Rng) -> Passages {
+    let mut passages = Passages::new(columns, rows);
+    let mut visited = Grid::new(columns, rows, false);
+    let start = (rng.random_range(0..columns), rng.random_range(0..rows));
+    visited[start] = true;
+    let mut stack = vec![start];
+
+    while let Some(&cell) = stack.last() {
+        let unvisited: Vec<_> = visited.neighbors(cell).filter(|&neighbor| !visited[neighbor]).collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = unvisited[rng.random_range(0..unvisited.len())];
+        passages.connect(cell, next);
+        visited[next] = true;
+        stack.push(next);
+    }
+
+    passages
+}
+
+fn wilson(columns: usize, rows: usize, rng: &mut impl Rng) -> Passages {
+    let mut passages = Passages::new(columns, rows);
+    let mut in_maze = Grid::new(columns, rows, false);
+    in_maze[(rng.random_range(0..columns), rng.random_range(0..rows))] = true;
+
+    // Where the walk last left each cell; revisiting a cell overwrites it, erasing the loop
+    let mut exits = Grid::new(columns, rows, (0, 0));
+    let mut order: Vec<(usize, usize)> = in_maze.iter().map(|(cell, _)| cell).collect();
+    order.shuffle(rng);
+
+    for start in order {
+        if in_maze[start] {
+            continue;
+        }
+
+        let mut cell = start;
+        while !in_maze[cell] {
+            let neighbors: Vec<_> = in_maze.neighbors(cell).collect();
+            let next = neighbors[rng.random_range(0..neighbors.len())];
+            exits[cell] = next;
+            cell = next;
+        }
+
+        let mut cell = start;
+        while !in_maze[cell] {
+            in_maze[cell] = true;
+            passages.connect(cell, exits[cell]);
+            cell = exits[cell];
+        }
+    }
+
+    passages
+}
+
+/// Open dead ends into a neighbor with chance `chance`, preferring neighbors that are dead ends too
+fn braid(passages: &mut Passages, chance: f64, rng: &mut impl Rng) {
+    if chance <= 0.0 {
+        return;
+    }
+
+    let cells: Vec<(usize, usize)> = passages.east.iter().map(|(cell, _)| cell).collect();
+    for cell in cells {
+        if passages.degree(cell) != 1 || !rng.random_bool(chance.min(1.0)) {
+            continue;
+        }
+
+        let closed: Vec<_> = passages.east.neighbors(cell).filter(|&neighbor| !passages.is_open(cell, neighbor)).collect();
+        let dead_ends: Vec<_> = closed.iter().copied().filter(|&neighbor| passages.degree(neighbor) == 1).collect();
+        let choices = if dead_ends.is_empty() { closed } else { dead_ends };
+        // A dead end at the end of a single row or column has nothing to open into
+        if choices.is_empty() {
+            continue;
+        }
+        passages.connect(cell, choices[rng.random_range(0..choices.len())]);
+    }
+}
+
+fn draw(passages: &Passages, corridor_width: usize, wall_width: usize) -> Vec<Vec<bool>> {
+    let (columns, rows) = (passages.east.width(), passages.east.height());
+    let pitch = corridor_width + wall_width;
+    let mut map = Grid::new(columns * pitch + wall_width, rows * pitch + wall_width, false);
+
+    let mut open = |x0: usize, y0: usize, width: usize, height: usize| {
+        for y in y0..y0 + height {
+            for x in x0..x0 + width {
+                map[(x, y)] = true;
+            }
+        }
+    };
+    for ((column, row), &east) in passages.east.iter() {
+        let (x, y) = (wall_width + column * pitch, wall_width + row * pitch);
+        open(x, y, corridor_width, corridor_width);
+        if east {
+            open(x + corridor_width, y, wall_width, corridor_width);
+        }
+        if passages.south[(column, row)] {
+            open(x, y + corridor_width, corridor_width, wall_width);
+        }
+    }
+
+    map.into_rows()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post::flood::flood_fill;
+    use proptest::prelude::*;
+
+    fn algorithms() -> impl Strategy<Value = MazeAlgorithm> {
+        prop_oneof![Just(MazeAlgorithm::RecursiveBacktracker), Just(MazeAlgorithm::Wilson)]
+    }
+
+    fn passage_count(passages: &Passages) -> usize {
+        passages.east.iter().chain(passages.south.iter()).filter(|(_, open)| **open).count()
+    }
+
+    proptest! {
+        #[test]
+        fn test_mazes_are_perfect(columns in 1..20usize, rows in 1..20usize, algorithm in algorithms(), seed in any::<u64>()) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let passages = match algorithm {
+                MazeAlgorithm::RecursiveBacktracker => recursive_backtracker(columns, rows, &mut rng),
+                MazeAlgorithm::Wilson => wilson(columns, rows, &mut rng),
+            };
+            // Connected with one fewer passage than cells means no loops
+            prop_assert_eq!(passage_count(&passages), columns * rows - 1);
+
+            let map = draw(&passages, 2, 1);
+            let distances = flood_fill(&map, (1, 1));
+            for (y, row) in map.iter().enumerate() {
+                for (x, &floor) in row.iter().enumerate() {
+                    prop_assert!(!floor || distances[y][x] != usize::MAX);
+                }
+            }
+        }
+
+        #[test]
+        fn test_full_braiding_leaves_no_dead_ends(columns in 2..20usize, rows in 2..20usize, seed in any::<u64>()) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut passages = recursive_backtracker(columns, rows, &mut rng);
+            braid(&mut passages, 1.0, &mut rng);
+            for row in 0..rows {
+                for column in 0..columns {
+                    prop_assert!(passages.degree((column, row)) >= 2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_maze_size_and_border() {
+        let config = MazeConfig { columns: 5, rows: 3, corridor_width: 2, wall_width: 2, ..Default::default() };
+        let map = maze(&config);
+
+        assert_eq!((map[0].len(), map.len()), (5 * 4 + 2, 3 * 4 + 2));
+        assert!(map[0].iter().chain(&map[map.len() - 1]).all(|&floor| !floor));
+        assert!(map.iter().all(|row| !row[0] && !row[row.len() - 1]));
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+
+use super::flood::{clearance, threshold};
+
+/// Carve out every passage narrower than `min_width` cells until it is that wide
+///
+/// Narrow spots are floor cells near a wall whose floor run along their row or
+/// column is shorter than `min_width`. Everything within `min_width / 2` steps
+/// of one becomes floor, so odd widths come out exact and even ones a cell
+/// wider. Rooms already wide enough are left alone and the outer ring of the
+/// map stays wall.
+pub fn widen_corridors(map: &mut [Vec<bool>], min_width: usize) {
+    let (width, height) = (map[0].len(), map.len());
+    let clearance = clearance(map);
+    let (rows, columns) = (spans(map, false), spans(map, true));
+    let needed = min_width.div_ceil(2);
+
+    let mut narrow = vec![vec![false; width]; height];
+    for y in 0..height {
+        for x in 0..width {
+            narrow[y][x] = map[y][x]
+                && clearance[y][x] < needed
+                && rows[y][x].min(columns[y][x]) < min_width;
+        }
+    }
+
+    let carved = threshold(&steps_from(&narrow), min_width / 2 + 1);
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            map[y][x] |= carved[y][x];
+        }
+    }
+}
+
+/// Wall off every passage narrower than `min_width` cells
+///
+/// The opposite of `widen_corridors`: only floor a `min_width` brush fits in
+/// is kept, so whatever is left can be walked by something that wide. Pockets
+/// behind a closed passage stay floor, so flood fill again before using the map.
+/// Room corners get rounded off by the brush.
+pub fn close_narrow_corridors(map: &mut [Vec<bool>], min_width: usize) {
+    let needed = min_width.div_ceil(2);
+    let roomy = threshold(&clearance(map), needed)
+        .into_iter()
+        .map(|row| row.into_iter().map(|tight| !tight).collect())
+        .collect::<Vec<Vec<bool>>>();
+
+    let kept = threshold(&steps_from(&roomy), needed);
+    for (row, kept_row) in map.iter_mut().zip(kept) {
+        for (cell, kept) in row.iter_mut().zip(kept_row) {
+            *cell &= kept;
+        }
+    }
+}
+
+/// Length of the floor run through each cell, along its column if `vertical`, else along its row
+fn spans(map: &[Vec<bool>], vertical: bool) -> Vec<Vec<usize>> {
+    let (width, height) = (map[0].len(), map.len());
+    let (lines, length) = if vertical { (width, height) } else { (height, width) };
+    let cell = |line: usize, i: usize| if vertical { (line, i) } else { (i, line) };
+
+    let mut spans = vec![vec![0; width]; height];
+    for line in 0..lines {
+        let mut start = 0;
+        for i in 0..=length {
+            let floor = i < length && {
+                let (x, y) = cell(line, i);
+                map[y][x]
+            };
+            if floor {
+                continue;
+            }
+            for j in start..i {
+                let (x, y) = cell(line, j);
+                spans[y][x] = i - start;
+            }
+            start = i + 1;
+        }
+    }
+    spans
+}
+
+/// Steps from each cell to the nearest marked one, walls or not
+fn steps_from(marked: &[Vec<bool>]) -> Vec<Vec<usize>> {
+    let (width, height) = (marked[0].len(), marked.len());
+    let mut distances = vec![vec![usize::MAX; width]; height];
+    let mut queue = VecDeque::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            if marked[y][x] {
+                distances[y][x] = 0;
+                queue.push_back((x, y));
+            }
+        }
+    }
+
+    while let Some((x, y)) = queue.pop_front() {
+        let next = distances[y][x] + 1;
+        let neighbors = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (nx, ny) in neighbors {
+            if nx < width && ny < height && distances[ny][nx] > next {
+                distances[ny][nx] = next;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::maps;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_closing_never_adds_floor(map in maps(40), min_width in 1..6usize) {
+            let mut closed = map.clone();
+            close_narrow_corridors(&mut closed, min_width);
+            for (row, closed_row) in map.iter().zip(&closed) {
+                for (&before, &after) in row.iter().zip(closed_row) {
+                    prop_assert!(before || !after);
+                }
+            }
+        }
+
+        #[test]
+        fn test_widening_never_removes_floor_or_opens_the_edge(map in maps(40), min_width in 1..6usize) {
+            let mut widened = map.clone();
+            widen_corridors(&mut widened, min_width);
+            let (width, height) = (map[0].len(), map.len());
+            for y in 0..height {
+                for x in 0..width {
+                    prop_assert!(!map[y][x] || widened[y][x]);
+                    let on_edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                    if on_edge {
+                        prop_assert_eq!(widened[y][x], map[y][x]);
+                    }
+                }
+            }
+        }
+    }
+}
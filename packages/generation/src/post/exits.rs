@@ -0,0 +1,159 @@
+use rand::{SeedableRng, seq::SliceRandom};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use super::flood::{clearance, floor_grid};
+
+/// What a generated map tells the game beyond its tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapMetadata {
+    /// Cell for the staircase or portal the player arrives by
+    pub entrance: (usize, usize),
+    /// Cell for the way on to the next map
+    pub exit: (usize, usize),
+    /// Walking distance from entrance to exit, in cells
+    pub path_distance: usize,
+}
+
+/// Where entrances and exits may go
+#[derive(Debug, Clone, Copy)]
+pub struct ExitRules {
+    /// Floor within this many cells of the map edge counts as the border
+    pub border_width: usize,
+    /// Floor at least this many cells from the nearest wall counts as a large room
+    pub room_clearance: usize,
+    /// Shortest walking distance allowed between entrance and exit, in cells
+    pub min_path_distance: usize,
+    /// Entrances tried before giving up
+    pub attempts: usize,
+}
+
+impl Default for ExitRules {
+    fn default() -> Self {
+        Self {
+            border_width: 2,
+            room_clearance: 6,
+            min_path_distance: 100,
+            attempts: 16,
+        }
+    }
+}
+
+/// Pick an entrance and an exit that follow `rules` and can be walked between
+///
+/// Both go on the border or in a large room, and the same map and seed always
+/// give the same cells. Returns `None` when no pair fits, like on a map too
+/// small for `min_path_distance` or an empty one.
+pub fn place_exits(map: &[Vec<bool>], rules: &ExitRules, seed: u64) -> Option<MapMetadata> {
+    if map.first().is_none_or(|row| row.is_empty()) {
+        return None;
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut candidates = exit_candidates(map, rules);
+    candidates.shuffle(&mut rng);
+    let floor = floor_grid(map);
+
+    for &entrance in candidates.iter().take(rules.attempts) {
+        let distances = floor.flood_fill(entrance, |&floor| floor);
+        // Candidates are shuffled, so the first reachable one far enough away is a random one
+        let exit = candidates
+            .iter()
+            .copied()
+            .find(|&cell| distances[cell] != usize::MAX && distances[cell] >= rules.min_path_distance);
+
+        if let Some(exit) = exit {
+            return Some(MapMetadata {
+                entrance,
+                exit,
+                path_distance: distances[exit],
+            });
+        }
+    }
+    None
+}
+
+/// Floor cells on the border or in a large room
+fn exit_candidates(map: &[Vec<bool>], rules: &ExitRules) -> Vec<(usize, usize)> {
+    let (width, height) = (map[0].len(), map.len());
+    let clearance = clearance(map);
+
+    let mut candidates = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !map[y][x] {
+                continue;
+            }
+            let edge_distance = x.min(y).min(width - 1 - x).min(height - 1 - y);
+            if edge_distance < rules.border_width || clearance[y][x] >= rules.room_clearance {
+                candidates.push((x, y));
+            }
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post::flood::flood_fill;
+    use crate::strategies::maps;
+    use proptest::prelude::*;
+
+    /// Whether `cell` is somewhere `rules` allow an entrance or exit
+    fn allowed(map: &[Vec<bool>], rules: &ExitRules, (x, y): (usize, usize)) -> bool {
+        let (width, height) = (map[0].len(), map.len());
+        let edge_distance = x.min(y).min(width - 1 - x).min(height - 1 - y);
+        map[y][x] && (edge_distance < rules.border_width || clearance(map)[y][x] >= rules.room_clearance)
+    }
+
+    proptest! {
+        #[test]
+        fn test_exits_follow_the_rules(map in maps(30), seed in any::<u64>(), min_path_distance in 0..20usize) {
+            let rules = ExitRules { border_width: 2, room_clearance: 3, min_path_distance, attempts: 8 };
+            if let Some(metadata) = place_exits(&map, &rules, seed) {
+                prop_assert!(allowed(&map, &rules, metadata.entrance));
+                prop_assert!(allowed(&map, &rules, metadata.exit));
+                prop_assert!(metadata.path_distance >= min_path_distance);
+                let (x, y) = metadata.exit;
+                prop_assert_eq!(flood_fill(&map, metadata.entrance)[y][x], metadata.path_distance);
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_map_has_no_exits() {
+        assert_eq!(place_exits(&[], &ExitRules::default(), 0), None);
+        assert_eq!(place_exits(&[vec![]], &ExitRules::default(), 0), None);
+    }
+
+    #[test]
+    fn test_min_path_distance() {
+        // A straight corridor 30 cells long along the top edge
+        let map = vec![vec![true; 30], vec![false; 30]];
+        let rules = |min_path_distance| ExitRules { min_path_distance, attempts: 30, ..ExitRules::default() };
+
+        assert_eq!(place_exits(&map, &rules(30), 0), None);
+        let metadata = place_exits(&map, &rules(29), 0).unwrap();
+        assert_eq!(metadata.path_distance, 29);
+        assert_eq!(metadata.entrance.0.abs_diff(metadata.exit.0), 29);
+    }
+
+    #[test]
+    fn test_inner_exits_need_room_clearance() {
+        // A 21x21 room walled in three cells thick, so nothing open touches the border
+        let map: Vec<Vec<bool>> = (0..27)
+            .map(|y| (0..27).map(|x| (3..24).contains(&x) && (3..24).contains(&y)).collect())
+            .collect();
+        let rules = ExitRules { border_width: 2, room_clearance: 6, min_path_distance: 0, attempts: 16 };
+
+        for seed in 0..16 {
+            let metadata = place_exits(&map, &rules, seed).unwrap();
+            for (x, y) in [metadata.entrance, metadata.exit] {
+                assert!((8..19).contains(&x) && (8..19).contains(&y), "({x}, {y}) is too close to a wall");
+            }
+        }
+        let tight = ExitRules { room_clearance: 12, ..rules };
+        assert_eq!(place_exits(&map, &tight, 0), None);
+    }
+}
@@ -1,18 +1,56 @@
 use std::collections::VecDeque;
 use image::{ImageBuffer, Rgb};
 
-pub fn flood_fill(map: &Vec<Vec<bool>>, start: (usize, usize)) -> Vec<Vec<usize>> {
+use crate::grid::Grid;
+
+/// Steps from `start` to every floor cell reachable from it; unreachable cells are `usize::MAX`
+pub fn flood_fill(map: &[Vec<bool>], start: (usize, usize)) -> Vec<Vec<usize>> {
+    floor_grid(map).flood_fill(start, |&floor| floor).into_rows()
+}
+
+/// Steps from the nearest of `sources` to every floor cell reachable from them; unreachable cells are `u32::MAX`
+///
+/// For placing things far from the entrance, or thinning out spawns near it.
+pub fn distance_field(map: &[Vec<bool>], sources: &[(usize, usize)]) -> Grid<u32> {
+    floor_grid(map).distance_field(sources, |&floor| floor.then_some(1))
+}
+
+/// `map` as a grid in one copy, for callers that run several grid operators on it
+pub fn floor_grid(map: &[Vec<bool>]) -> Grid<bool> {
+    let width = map.first().map_or(0, Vec::len);
+    assert!(map.iter().all(|row| row.len() == width), "Rows of a map must all be {} cells long", width);
+    Grid::from_fn(width, map.len(), |x, y| map[y][x])
+}
+
+/// Steps from each floor cell to the nearest wall, counting beyond the edge as wall
+pub fn clearance(map: &[Vec<bool>]) -> Vec<Vec<usize>> {
     let (width, height) = (map[0].len(), map.len());
     let mut distances = vec![vec![usize::MAX; width]; height];
-    let mut queue = VecDeque::from([start]);
-    distances[start.1][start.0] = 0;
+    let mut queue = VecDeque::new();
 
-    while let Some((x, y)) = queue.pop_front() {
-        let current_distance = distances[y][x];
+    for y in 0..height {
+        for x in 0..width {
+            if !map[y][x] {
+                distances[y][x] = 0;
+                queue.push_back((x, y));
+            } else if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                distances[y][x] = 1;
+                queue.push_back((x, y));
+            }
+        }
+    }
 
-        for (nx, ny) in neighbors((x, y), width, height) {
-            if map[ny][nx] && distances[ny][nx] == usize::MAX {
-                distances[ny][nx] = current_distance + 1;
+    while let Some((x, y)) = queue.pop_front() {
+        let next = distances[y][x] + 1;
+        let neighbors = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (nx, ny) in neighbors {
+            if nx < width && ny < height && distances[ny][nx] > next {
+                distances[ny][nx] = next;
                 queue.push_back((nx, ny));
             }
         }
@@ -26,15 +64,6 @@ pub fn threshold(map: &Vec<Vec<usize>>, threshold: usize) -> Vec<Vec<bool>> {
         .collect()
 }
 
-fn neighbors((x, y): (usize, usize), width: usize, height: usize) -> Vec<(usize, usize)> {
-    let mut result = Vec::new();
-    if x > 0 { result.push((x - 1, y)); }
-    if x < width - 1 { result.push((x + 1, y)); }
-    if y > 0 { result.push((x, y - 1)); }
-    if y < height - 1 { result.push((x, y + 1)); }
-    result
-}
-
 pub fn write_choropleth(values: &Vec<Vec<usize>>, filename: &str) -> std::io::Result<()> {
     let height = values.len() as u32;
     let width = values[0].len() as u32;
@@ -52,3 +81,52 @@ pub fn write_choropleth(values: &Vec<Vec<usize>>, filename: &str) -> std::io::Re
     img.save(filename)
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::{maps, maps_with_cell};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_flood_fill_stays_on_the_map((map, start) in maps_with_cell(40)) {
+            let distances = flood_fill(&map, start);
+            let (width, height) = (map[0].len(), map.len());
+
+            prop_assert_eq!(distances.len(), height);
+            prop_assert!(distances.iter().all(|row| row.len() == width));
+            for y in 0..height {
+                for x in 0..width {
+                    let distance = distances[y][x];
+                    if distance == usize::MAX || (x, y) == start {
+                        continue;
+                    }
+                    // Only floor is reached, and never further than a path through every cell
+                    prop_assert!(map[y][x]);
+                    prop_assert!(distance < width * height);
+                }
+            }
+        }
+
+        #[test]
+        fn test_distance_field_from_one_source_matches_flood_fill((map, start) in maps_with_cell(40)) {
+            let distances = flood_fill(&map, start);
+            let field = distance_field(&map, &[start]);
+            for ((x, y), &distance) in field.iter() {
+                let expected = distances[y][x];
+                prop_assert_eq!(distance, if expected == usize::MAX { u32::MAX } else { expected as u32 });
+            }
+        }
+
+        #[test]
+        fn test_clearance_is_zero_on_walls_only(map in maps(40)) {
+            let clearance = clearance(&map);
+            for (row, clearance_row) in map.iter().zip(&clearance) {
+                for (&floor, &distance) in row.iter().zip(clearance_row) {
+                    prop_assert_eq!(distance == 0, !floor);
+                }
+            }
+        }
+    }
+}
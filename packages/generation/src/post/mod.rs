@@ -1 +1,6 @@
+pub mod corridors;
+pub mod exits;
 pub mod flood;
+pub mod rooms;
+pub mod symmetry;
+pub mod terrain;
@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::grid::Grid;
+use super::flood::{clearance, floor_grid};
+
+/// What counts as a room when reading rooms out of a map
+#[derive(Debug, Clone, Copy)]
+pub struct RoomRules {
+    /// Floor at least this many steps from the nearest wall is the middle of a room
+    pub room_clearance: usize,
+    /// Rooms smaller than this, in cells, count as part of the corridors
+    pub min_room_area: usize,
+}
+
+impl Default for RoomRules {
+    fn default() -> Self {
+        Self {
+            room_clearance: 3,
+            min_room_area: 16,
+        }
+    }
+}
+
+/// An open area found in a map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Room {
+    /// The room's cell nearest its middle, so it's always inside even in odd shapes
+    pub centroid: (usize, usize),
+    /// Number of cells
+    pub area: usize,
+    /// Connected open region the room is in, as numbered in `RoomGraph::regions`
+    pub region: usize,
+}
+
+/// A way between rooms
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Corridor {
+    /// Rooms the corridor opens onto, at least two, in ascending order
+    pub rooms: Vec<usize>,
+    /// Floor between the rooms; empty when two rooms open straight onto each other
+    pub cells: Vec<(usize, usize)>,
+    /// The narrowest cell on the way, where a door or an ambush fits
+    pub chokepoint: (usize, usize),
+    /// Steps from the chokepoint to the nearest wall
+    pub chokepoint_clearance: usize,
+}
+
+/// Rooms and the corridors between them, read from a finished map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomGraph {
+    /// Number of the connected open region each floor cell is in
+    pub regions: Grid<Option<usize>>,
+    /// Index into `rooms` of the room each cell is in; `None` for walls and corridors
+    pub labels: Grid<Option<usize>>,
+    pub rooms: Vec<Room>,
+    /// One per stretch of corridor, however many rooms it joins
+    pub corridors: Vec<Corridor>,
+}
+
+impl RoomGraph {
+    /// Rooms a corridor leads to from `room`
+    pub fn neighbors(&self, room: usize) -> impl Iterator<Item = usize> + '_ {
+        self.corridors
+            .iter()
+            .filter(move |corridor| corridor.rooms.contains(&room))
+            .flat_map(move |corridor| corridor.rooms.iter().copied().filter(move |&other| other != room))
+    }
+}
+
+/// Find the rooms in a map and the corridors joining them
+///
+/// Rooms grow out from floor far enough from the walls until they meet the
+/// walls again, so a narrow passage never becomes part of one. Whatever floor
+/// is left over is corridor, and each stretch of it joins the rooms it
+/// touches.
+pub fn room_graph(map: &[Vec<bool>], rules: &RoomRules) -> RoomGraph {
+    let floor = floor_grid(map);
+    let clearance = Grid::from_rows(clearance(map));
+    let (width, height) = (floor.width(), floor.height());
+    let room_clearance = rules.room_clearance.max(1);
+
+    let (regions, _) = components(&floor);
+    let core = Grid::from_fn(width, height, |x, y| floor[(x, y)] && clearance[(x, y)] >= room_clearance);
+    let (mut labels, core_count) = components(&core);
+
+    // Grow each middle back out to the walls; a cell goes to whichever reaches it first
+    let mut steps = Grid::new(width, height, usize::MAX);
+    let mut queue = VecDeque::new();
+    for (cell, label) in labels.iter() {
+        if label.is_some() {
+            steps[cell] = 0;
+            queue.push_back(cell);
+        }
+    }
+    while let Some(cell) = queue.pop_front() {
+        let next = steps[cell] + 1;
+        if next >= room_clearance {
+            continue;
+        }
+        for neighbor in floor.neighbors(cell) {
+            if floor[neighbor] && steps[neighbor] == usize::MAX {
+                steps[neighbor] = next;
+                labels[neighbor] = labels[cell];
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    // Small rooms are just wide spots in a corridor
+    let mut cells = vec![Vec::new(); core_count];
+    for (cell, label) in labels.iter() {
+        if let Some(label) = *label {
+            cells[label].push(cell);
+        }
+    }
+    cells.retain(|room| room.len() >= rules.min_room_area);
+    let mut labels = Grid::new(width, height, None);
+    for (room, room_cells) in cells.iter().enumerate() {
+        labels.fill(room_cells, Some(room));
+    }
+
+    let rooms = cells
+        .iter()
+        .map(|room_cells| {
+            let (sum_x, sum_y) = room_cells.iter().fold((0, 0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+            let middle = (sum_x / room_cells.len(), sum_y / room_cells.len());
+            let centroid = *room_cells
+                .iter()
+                .min_by_key(|&&(x, y)| x.abs_diff(middle.0).pow(2) + y.abs_diff(middle.1).pow(2))
+                .unwrap();
+            Room { centroid, area: room_cells.len(), region: regions[centroid].unwrap() }
+        })
+        .collect();
+
+    let mut corridors = Vec::new();
+
+    // Stretches of leftover floor, joining every room they touch
+    let leftover = Grid::from_fn(width, height, |x, y| floor[(x, y)] && labels[(x, y)].is_none());
+    let (stretch_labels, stretch_count) = components(&leftover);
+    let mut stretches = vec![(Vec::new(), BTreeSet::new()); stretch_count];
+    for (cell, stretch) in stretch_labels.iter() {
+        let Some(stretch) = *stretch else {
+            continue;
+        };
+        stretches[stretch].0.push(cell);
+        stretches[stretch].1.extend(floor.neighbors(cell).filter_map(|neighbor| labels[neighbor]));
+    }
+    for (stretch_cells, touching) in stretches {
+        let touching: Vec<usize> = touching.into_iter().collect();
+        if touching.len() < 2 {
+            continue;
+        }
+        let chokepoint = *stretch_cells.iter().min_by_key(|&&cell| clearance[cell]).unwrap();
+        corridors.push(Corridor {
+            rooms: touching,
+            cells: stretch_cells,
+            chokepoint,
+            chokepoint_clearance: clearance[chokepoint],
+        });
+    }
+
+    // Rooms that open straight onto each other, joined where they first meet
+    let mut openings = BTreeMap::new();
+    for (cell, label) in labels.iter() {
+        let Some(room) = *label else {
+            continue;
+        };
+        for neighbor in labels.neighbors(cell) {
+            if let Some(other) = labels[neighbor].filter(|&other| other > room) {
+                openings.entry((room, other)).or_insert(cell);
+            }
+        }
+    }
+    for ((from, to), chokepoint) in openings {
+        corridors.push(Corridor {
+            rooms: vec![from, to],
+            cells: Vec::new(),
+            chokepoint,
+            chokepoint_clearance: clearance[chokepoint],
+        });
+    }
+
+    RoomGraph { regions, labels, rooms, corridors }
+}
+
+/// Number the groups of `true` cells joined up, down, left and right, in row order
+fn components(mask: &Grid<bool>) -> (Grid<Option<usize>>, usize) {
+    let mut labels = Grid::new(mask.width(), mask.height(), None);
+    let mut count = 0;
+    for (start, &set) in mask.iter() {
+        if !set || labels[start].is_some() {
+            continue;
+        }
+        labels[start] = Some(count);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            for neighbor in mask.neighbors(cell) {
+                if mask[neighbor] && labels[neighbor].is_none() {
+                    labels[neighbor] = Some(count);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        count += 1;
+    }
+    (labels, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::maps;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_rooms_and_corridors_stay_on_the_floor(map in maps(40)) {
+            let graph = room_graph(&map, &RoomRules { room_clearance: 2, min_room_area: 4 });
+
+            for ((x, y), label) in graph.labels.iter() {
+                prop_assert!(label.is_none() || map[y][x]);
+            }
+            for (room, info) in graph.rooms.iter().enumerate() {
+                prop_assert_eq!(graph.labels[info.centroid], Some(room));
+                prop_assert_eq!(graph.labels.iter().filter(|(_, label)| **label == Some(room)).count(), info.area);
+            }
+            for corridor in &graph.corridors {
+                prop_assert!(corridor.rooms.len() >= 2);
+                prop_assert!(corridor.rooms.windows(2).all(|pair| pair[0] < pair[1]));
+                prop_assert!(*corridor.rooms.last().unwrap() < graph.rooms.len());
+                let region = graph.rooms[corridor.rooms[0]].region;
+                prop_assert!(corridor.rooms.iter().all(|&room| graph.rooms[room].region == region));
+                let (x, y) = corridor.chokepoint;
+                prop_assert!(map[y][x]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_two_rooms_and_a_corridor() {
+        // Two 8x9 rooms joined by a passage one cell wide
+        let map: Vec<Vec<bool>> = (0..11)
+            .map(|y| {
+                (0..30)
+                    .map(|x| (1..=9).contains(&y) && ((1..=8).contains(&x) || (21..=28).contains(&x)) || (y == 5 && (9..=20).contains(&x)))
+                    .collect()
+            })
+            .collect();
+        let graph = room_graph(&map, &RoomRules::default());
+
+        assert_eq!(graph.rooms.len(), 2);
+        assert_eq!(graph.corridors.len(), 1);
+        let corridor = &graph.corridors[0];
+        assert_eq!(corridor.rooms, [0, 1]);
+        assert_eq!(corridor.chokepoint_clearance, 1);
+        assert_eq!(corridor.chokepoint.1, 5);
+        assert!(graph.rooms[0].centroid.0 <= 8 && graph.rooms[1].centroid.0 >= 21);
+        assert_eq!(graph.neighbors(0).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn test_cave_web_gives_one_corridor_per_stretch() {
+        // A 5x5 grid of 7x7 rooms, every one opening onto a single one-wide web of passages
+        let size = 5 * 12 + 1;
+        let map: Vec<Vec<bool>> = (0..size)
+            .map(|y| {
+                (0..size)
+                    .map(|x| {
+                        let in_room = (3..=9).contains(&(x % 12)) && (3..=9).contains(&(y % 12));
+                        let on_web = (x % 12 == 0 || y % 12 == 0) && x > 0 && y > 0 && x < size - 1 && y < size - 1;
+                        let door = (x % 12 == 6 && matches!(y % 12, 1 | 2 | 10 | 11)) || (y % 12 == 6 && matches!(x % 12, 1 | 2 | 10 | 11));
+                        in_room || on_web || door
+                    })
+                    .collect()
+            })
+            .collect();
+        let graph = room_graph(&map, &RoomRules::default());
+
+        assert_eq!(graph.rooms.len(), 25);
+        let web = graph.corridors.iter().max_by_key(|corridor| corridor.rooms.len()).unwrap();
+        assert_eq!(web.rooms.len(), 25);
+        assert_eq!(graph.corridors.iter().filter(|corridor| !corridor.cells.is_empty()).count(), 1);
+        assert_eq!(graph.neighbors(0).count(), 24);
+    }
+}
@@ -0,0 +1,179 @@
+/// How an arena map repeats itself, so every side gets the same layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Left half mirrored onto the right
+    MirrorX,
+    /// Top half mirrored onto the bottom
+    MirrorY,
+    /// Top-left quarter mirrored onto the other three
+    MirrorBoth,
+    /// Half turned 180 degrees onto the other half, for two opposite sides
+    Rotate2,
+    /// Quarter turned onto the other three, for four sides; needs a square map
+    Rotate4,
+    /// A wedge turned onto the rest, for any number of sides
+    ///
+    /// Turned copies of the corners would land off the map, so everything
+    /// outside the largest circle that fits becomes wall. Other than at 2 and
+    /// 4, the copies are rounded to the nearest cell, so they can be a cell off.
+    Rotate(usize),
+}
+
+impl Symmetry {
+    /// The symmetry named like the variant in snake case, e.g. `mirror_x`, or
+    /// `rotate` and the number of sides, e.g. `rotate6`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::None),
+            "mirror_x" => Some(Self::MirrorX),
+            "mirror_y" => Some(Self::MirrorY),
+            "mirror_both" => Some(Self::MirrorBoth),
+            "rotate2" => Some(Self::Rotate2),
+            "rotate4" => Some(Self::Rotate4),
+            _ => match name.strip_prefix("rotate")?.parse() {
+                Ok(sides) if sides >= 2 => Some(Self::Rotate(sides)),
+                _ => None,
+            },
+        }
+    }
+
+    /// The cell whose tile `(x, y)` copies, in the part of the map that is
+    /// kept, or `None` where it becomes wall
+    fn source(self, (x, y): (usize, usize), width: usize, height: usize) -> Option<(usize, usize)> {
+        let (flip_x, flip_y) = (width - 1 - x, height - 1 - y);
+        // Turned copies take the cell that comes first in row order
+        let first = |cells: &[(usize, usize)]| *cells.iter().min_by_key(|(x, y)| (*y, *x)).unwrap();
+
+        Some(match self {
+            Self::None => (x, y),
+            Self::MirrorX => (x.min(flip_x), y),
+            Self::MirrorY => (x, y.min(flip_y)),
+            Self::MirrorBoth => (x.min(flip_x), y.min(flip_y)),
+            Self::Rotate2 => first(&[(x, y), (flip_x, flip_y)]),
+            Self::Rotate4 => first(&[(x, y), (flip_y, x), (flip_x, flip_y), (y, flip_x)]),
+            Self::Rotate(sides) => return wedge_source((x, y), width, height, sides),
+        })
+    }
+}
+
+/// The cell in the first of `sides` wedges around the middle that `(x, y)` is a turned copy of
+fn wedge_source((x, y): (usize, usize), width: usize, height: usize, sides: usize) -> Option<(usize, usize)> {
+    let center = ((width - 1) as f64 / 2.0, (height - 1) as f64 / 2.0);
+    let radius = (width.min(height) - 1) as f64 / 2.0;
+    let (dx, dy) = (x as f64 - center.0, y as f64 - center.1);
+    if dx.hypot(dy) > radius {
+        return None;
+    }
+
+    let wedge = std::f64::consts::TAU / sides as f64;
+    let angle = dy.atan2(dx).rem_euclid(std::f64::consts::TAU);
+    let (sin, cos) = (-(angle / wedge).floor() * wedge).sin_cos();
+    let (sx, sy) = (center.0 + dx * cos - dy * sin, center.1 + dx * sin + dy * cos);
+    Some((sx.round().clamp(0.0, (width - 1) as f64) as usize, sy.round().clamp(0.0, (height - 1) as f64) as usize))
+}
+
+/// Copy one part of the map over the rest so it follows `symmetry`
+///
+/// Seams where the copies meet can be ragged; a cellular automata pass
+/// afterwards smooths them and keeps the symmetry, since its rule treats
+/// every direction the same.
+pub fn apply_symmetry(map: &mut [Vec<bool>], symmetry: Symmetry) {
+    apply_symmetry_with(map, symmetry, false);
+}
+
+/// `apply_symmetry` for any grid, like terrain, with `wall` where `Rotate` cuts off the corners
+pub fn apply_symmetry_with<T: Clone>(map: &mut [Vec<T>], symmetry: Symmetry, wall: T) {
+    let (width, height) = (map[0].len(), map.len());
+    if symmetry == Symmetry::Rotate4 {
+        assert_eq!(width, height, "Rotate4 needs a square map");
+    }
+
+    let original = map.to_vec();
+    for (y, row) in map.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            *cell = match symmetry.source((x, y), width, height) {
+                Some((sx, sy)) => original[sy][sx].clone(),
+                None => wall.clone(),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::maps;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_symmetric_maps_match_their_reflections(map in maps(40)) {
+            let (width, height) = (map[0].len(), map.len());
+            let apply = |symmetry| {
+                let mut symmetric = map.clone();
+                apply_symmetry(&mut symmetric, symmetry);
+                symmetric
+            };
+            let (mirror_x, mirror_y, rotate2) = (apply(Symmetry::MirrorX), apply(Symmetry::MirrorY), apply(Symmetry::Rotate2));
+
+            for y in 0..height {
+                for x in 0..width {
+                    let (flip_x, flip_y) = (width - 1 - x, height - 1 - y);
+                    prop_assert_eq!(mirror_x[y][x], mirror_x[y][flip_x]);
+                    prop_assert_eq!(mirror_y[y][x], mirror_y[flip_y][x]);
+                    prop_assert_eq!(rotate2[y][x], rotate2[flip_y][flip_x]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_rotate4_matches_its_quarter_turn(map in maps(40)) {
+            let size = map.len().min(map[0].len());
+            let mut square: Vec<Vec<bool>> = map[..size].iter().map(|row| row[..size].to_vec()).collect();
+            apply_symmetry(&mut square, Symmetry::Rotate4);
+
+            for y in 0..size {
+                for x in 0..size {
+                    prop_assert_eq!(square[y][x], square[x][size - 1 - y]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_rotate_keeps_its_first_wedge(map in maps(40), sides in 3usize..9) {
+            let mut symmetric = map.clone();
+            apply_symmetry(&mut symmetric, Symmetry::Rotate(sides));
+
+            let (width, height) = (map[0].len(), map.len());
+            let wedge = std::f64::consts::TAU / sides as f64;
+            for y in 0..height {
+                for x in 0..width {
+                    let (dx, dy) = (x as f64 - (width - 1) as f64 / 2.0, y as f64 - (height - 1) as f64 / 2.0);
+                    let angle = dy.atan2(dx).rem_euclid(std::f64::consts::TAU);
+                    if angle < wedge && dx.hypot(dy) <= (width.min(height) - 1) as f64 / 2.0 {
+                        prop_assert_eq!(symmetric[y][x], map[y][x]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_walls_off_the_corners() {
+        let mut map = vec![vec![true; 9]; 9];
+        apply_symmetry(&mut map, Symmetry::Rotate(6));
+
+        assert!(!map[0][0] && !map[8][8]);
+        assert!(map[4][4] && map[0][4] && map[4][8]);
+    }
+
+    #[test]
+    fn test_rotate_parses_any_number_of_sides() {
+        assert_eq!(Symmetry::parse("rotate4"), Some(Symmetry::Rotate4));
+        assert_eq!(Symmetry::parse("rotate6"), Some(Symmetry::Rotate(6)));
+        assert_eq!(Symmetry::parse("rotate1"), None);
+        assert_eq!(Symmetry::parse("rotatex"), None);
+    }
+}
@@ -0,0 +1,104 @@
+use image::{ImageBuffer, Rgb};
+use serde::{Deserialize, Serialize};
+
+/// Kind of ground in a cell, from lowest to highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Terrain {
+    DeepWater,
+    /// Shallow enough to wade through
+    Water,
+    Floor,
+    Wall,
+}
+
+impl Terrain {
+    /// Whether the player and enemies can walk on it
+    pub fn is_walkable(self) -> bool {
+        matches!(self, Self::Water | Self::Floor)
+    }
+
+    /// Color used when saving terrain to an image
+    pub(crate) fn color(self) -> Rgb<u8> {
+        match self {
+            Self::DeepWater => Rgb([20, 40, 120]),
+            Self::Water => Rgb([60, 110, 200]),
+            Self::Floor => Rgb([255, 255, 255]),
+            Self::Wall => Rgb([0, 0, 0]),
+        }
+    }
+}
+
+/// Heights where each kind of terrain starts, for heights from 0 to 1
+///
+/// Anything below `water` is deep water; the thresholds should go up in order.
+#[derive(Debug, Clone, Copy)]
+pub struct TerrainThresholds {
+    pub water: f32,
+    pub floor: f32,
+    pub wall: f32,
+}
+
+impl Default for TerrainThresholds {
+    fn default() -> Self {
+        Self { water: 0.2, floor: 0.35, wall: 0.65 }
+    }
+}
+
+/// Cut a height field into terrain by the band each height falls in
+pub fn classify(heights: &[Vec<f32>], thresholds: &TerrainThresholds) -> Vec<Vec<Terrain>> {
+    heights
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&height| {
+                    if height >= thresholds.wall {
+                        Terrain::Wall
+                    } else if height >= thresholds.floor {
+                        Terrain::Floor
+                    } else if height >= thresholds.water {
+                        Terrain::Water
+                    } else {
+                        Terrain::DeepWater
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Walkable terrain as floor, for the operators that work on floor and wall maps
+pub fn walkable(terrain: &[Vec<Terrain>]) -> Vec<Vec<bool>> {
+    terrain.iter().map(|row| row.iter().map(|cell| cell.is_walkable()).collect()).collect()
+}
+
+/// Write terrain to a PNG, blues for water, white for floor and black for wall
+pub fn save_terrain_png(path: &str, terrain: &[Vec<Terrain>]) -> Result<(), std::io::Error> {
+    let (width, height) = (terrain[0].len(), terrain.len());
+    let imgbuf = ImageBuffer::from_fn(width as u32, height as u32, |x, y| terrain[y as usize][x as usize].color());
+
+    imgbuf.save(path)
+        .map_err(std::io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_higher_ground_is_never_lower_terrain(a in 0.0..=1.0f32, b in 0.0..=1.0f32) {
+            let terrain = classify(&[vec![a, b]], &TerrainThresholds::default());
+            prop_assert!(a > b || terrain[0][0] <= terrain[0][1]);
+        }
+    }
+
+    #[test]
+    fn test_every_band_gets_its_terrain() {
+        let heights = [vec![0.0, 0.25, 0.5, 1.0]];
+        let terrain = classify(&heights, &TerrainThresholds::default());
+
+        assert_eq!(terrain[0], [Terrain::DeepWater, Terrain::Water, Terrain::Floor, Terrain::Wall]);
+        assert_eq!(walkable(&terrain)[0], [false, true, true, false]);
+    }
+}
@@ -0,0 +1,89 @@
+//! Progress reports from long generation runs
+//!
+//! Generators that take a while report to a `Progress` as they go. Reports
+//! are sent down a channel, so a UI or the game can show them on another
+//! thread while the map generates on this one. The channel closes when the
+//! generator finishes and drops its `Progress`.
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::grid::Grid;
+
+/// A report from a generator partway through
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationEvent {
+    /// `step` of `total` steps done, with the map as it is now
+    Progress { step: usize, total: usize, snapshot: Vec<Vec<bool>> },
+}
+
+/// Where a generator sends its progress
+#[derive(Debug, Clone)]
+pub struct Progress {
+    sender: Option<Sender<GenerationEvent>>,
+    every: usize,
+}
+
+impl Progress {
+    /// Report every `every` steps and the last one; each report copies the map
+    pub fn channel(every: usize) -> (Self, Receiver<GenerationEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        (Self { sender: Some(sender), every: every.max(1) }, receiver)
+    }
+
+    /// Report nothing
+    pub fn none() -> Self {
+        Self { sender: None, every: 1 }
+    }
+
+    /// Send a report if `step` is due; stops sending once the receiver is dropped
+    pub fn report(&mut self, step: usize, total: usize, map: &[Vec<bool>]) {
+        if self.is_due(step, total) {
+            self.send(step, total, map.to_vec());
+        }
+    }
+
+    /// `report` for a map kept as a grid, copied out only when a report is due
+    pub fn report_grid(&mut self, step: usize, total: usize, grid: &Grid<bool>) {
+        if self.is_due(step, total) {
+            self.send(step, total, grid.clone().into_rows());
+        }
+    }
+
+    fn is_due(&self, step: usize, total: usize) -> bool {
+        self.sender.is_some() && (step.is_multiple_of(self.every) || step >= total)
+    }
+
+    fn send(&mut self, step: usize, total: usize, snapshot: Vec<Vec<bool>>) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if sender.send(GenerationEvent::Progress { step, total, snapshot }).is_err() {
+            self.sender = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_every_nth_step_and_the_last() {
+        let (mut progress, receiver) = Progress::channel(4);
+        let map = vec![vec![true; 2]; 2];
+        for step in 1..=10 {
+            progress.report(step, 10, &map);
+        }
+        drop(progress);
+
+        let steps: Vec<usize> = receiver.iter().map(|GenerationEvent::Progress { step, .. }| step).collect();
+        assert_eq!(steps, vec![4, 8, 10]);
+    }
+
+    #[test]
+    fn test_dropped_receiver_stops_reports() {
+        let (mut progress, receiver) = Progress::channel(1);
+        drop(receiver);
+        progress.report(1, 2, &[vec![false]]);
+        assert!(progress.sender.is_none());
+    }
+}
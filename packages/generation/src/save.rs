@@ -0,0 +1,143 @@
+//! Saving generated maps to load later without generating them again
+//!
+//! Anything generated that can be serialized, like a `LayoutLevel` or a
+//! `RoomGraph`, can be written as RON, to read and diff by hand, or as
+//! bincode, which is smaller and quicker to load. `save` and `load` pick the
+//! format from the file's extension, so a build step can generate worlds
+//! ahead of time and the game only loads them.
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+
+use crate::post::exits::MapMetadata;
+use crate::post::rooms::RoomGraph;
+
+/// How a saved map is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ron,
+    Bincode,
+}
+
+impl Format {
+    /// RON for a `.ron` path and bincode otherwise
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension() {
+            Some(extension) if extension == "ron" => Self::Ron,
+            _ => Self::Bincode,
+        }
+    }
+}
+
+/// Why a map couldn't be saved or loaded
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Ron(ron::Error),
+    /// Loading RON, with where in the file it went wrong
+    RonSyntax(ron::error::SpannedError),
+    Bincode(bincode::Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "Can't read or write the file: {}", error),
+            SaveError::Ron(error) => write!(f, "Bad RON: {}", error),
+            SaveError::RonSyntax(error) => write!(f, "Bad RON: {}", error),
+            SaveError::Bincode(error) => write!(f, "Bad bincode: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(error: std::io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+/// A generated floor map with what was read out of it, ready to save
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedMap {
+    /// Seed the map was generated from
+    pub seed: u64,
+    /// `true` for floor
+    pub map: Vec<Vec<bool>>,
+    /// `None` when there was no room for an entrance and exit
+    pub metadata: Option<MapMetadata>,
+    pub rooms: RoomGraph,
+}
+
+pub fn to_bytes<T: Serialize>(value: &T, format: Format) -> Result<Vec<u8>, SaveError> {
+    match format {
+        // Compact arrays keep a map's rows to a line each
+        Format::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default().compact_arrays(true))
+            .map(String::into_bytes)
+            .map_err(SaveError::Ron),
+        Format::Bincode => bincode::serialize(value).map_err(SaveError::Bincode),
+    }
+}
+
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8], format: Format) -> Result<T, SaveError> {
+    match format {
+        Format::Ron => ron::de::from_bytes(bytes).map_err(SaveError::RonSyntax),
+        Format::Bincode => bincode::deserialize(bytes).map_err(SaveError::Bincode),
+    }
+}
+
+/// Write `value` to `path`, as RON for a `.ron` path and bincode otherwise
+pub fn save<T: Serialize>(path: &str, value: &T) -> Result<(), SaveError> {
+    let bytes = to_bytes(value, Format::from_path(path))?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Read a value written by `save`
+pub fn load<T: DeserializeOwned>(path: &str) -> Result<T, SaveError> {
+    let bytes = std::fs::read(path)?;
+    from_bytes(&bytes, Format::from_path(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::layout::{self, LayoutConfig, LayoutLevel};
+
+    fn assert_same_level(loaded: &LayoutLevel, level: &LayoutLevel) {
+        assert_eq!(loaded.map, level.map);
+        assert_eq!(loaded.metadata, level.metadata);
+        assert_eq!(loaded.graph.main_path, level.graph.main_path);
+        assert_eq!(loaded.graph.corridors, level.graph.corridors);
+    }
+
+    #[test]
+    fn test_level_round_trips_in_both_formats() {
+        let level = layout::layout(&LayoutConfig::default()).unwrap();
+        for format in [Format::Ron, Format::Bincode] {
+            let bytes = to_bytes(&level, format).unwrap();
+            let loaded: LayoutLevel = from_bytes(&bytes, format).unwrap();
+            assert_same_level(&loaded, &level);
+        }
+    }
+
+    #[test]
+    fn test_grid_with_missing_cells_fails_to_load() {
+        let grid = Grid::new(3, 2, 1u8);
+        let mut ron = String::from_utf8(to_bytes(&grid, Format::Ron).unwrap()).unwrap();
+        assert_eq!(from_bytes::<Grid<u8>>(ron.as_bytes(), Format::Ron).unwrap(), grid);
+
+        ron = ron.replace("height: 2", "height: 3");
+        assert!(from_bytes::<Grid<u8>>(ron.as_bytes(), Format::Ron).is_err());
+    }
+
+    #[test]
+    fn test_format_follows_extension() {
+        assert_eq!(Format::from_path("worlds/level.ron"), Format::Ron);
+        assert_eq!(Format::from_path("worlds/level.bin"), Format::Bincode);
+    }
+}
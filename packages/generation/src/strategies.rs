@@ -0,0 +1,17 @@
+//! Proptest strategies shared by the operator tests
+use proptest::prelude::*;
+
+/// Maps of random floor and wall, from 1x1 up to `max` cells a side
+pub fn maps(max: usize) -> impl Strategy<Value = Vec<Vec<bool>>> {
+    (1..=max, 1..=max).prop_flat_map(|(width, height)| {
+        prop::collection::vec(prop::collection::vec(any::<bool>(), width), height)
+    })
+}
+
+/// A map with a cell inside it
+pub fn maps_with_cell(max: usize) -> impl Strategy<Value = (Vec<Vec<bool>>, (usize, usize))> {
+    maps(max).prop_flat_map(|map| {
+        let (width, height) = (map[0].len(), map.len());
+        (Just(map), (0..width, 0..height))
+    })
+}
@@ -0,0 +1,295 @@
+//! Export to the Tiled map editor
+//!
+//! Generated grids become tile layers of a Tiled map, saved as JSON (`.json`
+//! or `.tmj`) or XML (`.tmx`), so they can be opened and edited in Tiled or
+//! loaded into a tilemap. Tiled needs a tileset image, so one is written next
+//! to the map with a flat colored tile for each kind of tile.
+use std::fmt::Write;
+use std::path::Path;
+
+use image::{ImageBuffer, Rgb};
+use serde::Serialize;
+
+use crate::post::terrain::Terrain;
+
+/// Version of the Tiled map format written
+const FORMAT_VERSION: &str = "1.10";
+
+/// A kind of tile in the tileset, drawn as a flat color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileKind {
+    /// Tiled's class for the tile, for telling kinds apart once loaded
+    pub name: &'static str,
+    pub color: Rgb<u8>,
+}
+
+/// A Tiled map with one tileset and any number of tile layers
+#[derive(Debug, Clone)]
+pub struct TiledMap {
+    pub width: usize,
+    pub height: usize,
+    /// Width and height of a tile, in pixels
+    pub tile_size: u32,
+    pub kinds: Vec<TileKind>,
+    /// Names and tiles, row by row from the top; 0 is empty and 1 is the first kind
+    pub layers: Vec<(String, Vec<u32>)>,
+}
+
+impl TiledMap {
+    pub fn new(width: usize, height: usize, tile_size: u32, kinds: Vec<TileKind>) -> Self {
+        Self { width, height, tile_size, kinds, layers: Vec::new() }
+    }
+
+    /// Add a layer on top, with the index into `kinds` for each cell or `None` to leave it empty
+    pub fn add_layer<T>(&mut self, name: &str, grid: &[Vec<T>], kind: impl Fn(&T) -> Option<usize>) -> &mut Self {
+        assert_eq!((grid[0].len(), grid.len()), (self.width, self.height), "layer {} doesn't match the map size", name);
+        let tiles = grid
+            .iter()
+            .flatten()
+            .map(|cell| kind(cell).map_or(0, |index| index as u32 + 1))
+            .collect();
+        self.layers.push((name.to_string(), tiles));
+        self
+    }
+
+    /// The map in Tiled's JSON format, with the tileset image at `tileset_image`
+    pub fn to_json(&self, tileset_image: &str) -> String {
+        let size = self.tile_size;
+        let layers = self
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(i, (name, tiles))| JsonLayer {
+                kind: "tilelayer",
+                id: i + 1,
+                name,
+                x: 0,
+                y: 0,
+                width: self.width,
+                height: self.height,
+                opacity: 1.0,
+                visible: true,
+                data: tiles,
+            })
+            .collect();
+        let tileset = JsonTileset {
+            firstgid: 1,
+            name: "generated",
+            tilewidth: size,
+            tileheight: size,
+            tilecount: self.kinds.len(),
+            columns: self.kinds.len(),
+            margin: 0,
+            spacing: 0,
+            image: tileset_image,
+            imagewidth: self.kinds.len() as u32 * size,
+            imageheight: size,
+            tiles: self.kinds.iter().enumerate().map(|(id, kind)| JsonTile { id, kind: kind.name, class: kind.name }).collect(),
+        };
+        let map = JsonMap {
+            kind: "map",
+            version: FORMAT_VERSION,
+            orientation: "orthogonal",
+            renderorder: "right-down",
+            width: self.width,
+            height: self.height,
+            tilewidth: size,
+            tileheight: size,
+            infinite: false,
+            nextlayerid: self.layers.len() + 1,
+            nextobjectid: 1,
+            layers,
+            tilesets: vec![tileset],
+        };
+        serde_json::to_string(&map).expect("Tiled maps are plain data")
+    }
+
+    /// The map in Tiled's XML format, with the tileset image at `tileset_image`
+    pub fn to_tmx(&self, tileset_image: &str) -> String {
+        let mut tmx = String::new();
+        let size = self.tile_size;
+        let count = self.kinds.len();
+        writeln!(tmx, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+        writeln!(
+            tmx,
+            "<map version=\"{FORMAT_VERSION}\" orientation=\"orthogonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" \
+             tilewidth=\"{size}\" tileheight=\"{size}\" infinite=\"0\" nextlayerid=\"{}\" nextobjectid=\"1\">",
+            self.width, self.height, self.layers.len() + 1
+        ).unwrap();
+        writeln!(
+            tmx,
+            " <tileset firstgid=\"1\" name=\"generated\" tilewidth=\"{size}\" tileheight=\"{size}\" tilecount=\"{count}\" columns=\"{count}\">"
+        ).unwrap();
+        writeln!(tmx, "  <image source=\"{}\" width=\"{}\" height=\"{size}\"/>", escape_xml(tileset_image), count as u32 * size).unwrap();
+        for (id, kind) in self.kinds.iter().enumerate() {
+            writeln!(tmx, "  <tile id=\"{id}\" type=\"{0}\" class=\"{0}\"/>", escape_xml(kind.name)).unwrap();
+        }
+        writeln!(tmx, " </tileset>").unwrap();
+
+        for (i, (name, tiles)) in self.layers.iter().enumerate() {
+            writeln!(tmx, " <layer id=\"{}\" name=\"{}\" width=\"{}\" height=\"{}\">", i + 1, escape_xml(name), self.width, self.height).unwrap();
+            writeln!(tmx, "  <data encoding=\"csv\">").unwrap();
+            let rows: Vec<String> = tiles
+                .chunks(self.width)
+                .map(|row| row.iter().map(u32::to_string).collect::<Vec<_>>().join(","))
+                .collect();
+            writeln!(tmx, "{}", rows.join(",\n")).unwrap();
+            writeln!(tmx, "  </data>").unwrap();
+            writeln!(tmx, " </layer>").unwrap();
+        }
+        writeln!(tmx, "</map>").unwrap();
+        tmx
+    }
+
+    /// Write the map, as XML for a `.tmx` path and JSON otherwise, with its tileset image beside it
+    pub fn save(&self, path: &str) -> Result<(), std::io::Error> {
+        let path = Path::new(path);
+        let stem = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        let tileset_name = format!("{}_tiles.png", stem);
+
+        let size = self.tile_size;
+        let tileset = ImageBuffer::from_fn(self.kinds.len() as u32 * size, size, |x, _| self.kinds[(x / size) as usize].color);
+        tileset.save(path.with_file_name(&tileset_name)).map_err(std::io::Error::other)?;
+
+        let is_tmx = path.extension().is_some_and(|extension| extension == "tmx");
+        let text = if is_tmx { self.to_tmx(&tileset_name) } else { self.to_json(&tileset_name) };
+        std::fs::write(path, text)
+    }
+}
+
+/// A map as Tiled's JSON has it, field for field
+#[derive(Serialize)]
+struct JsonMap<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    version: &'static str,
+    orientation: &'static str,
+    renderorder: &'static str,
+    width: usize,
+    height: usize,
+    tilewidth: u32,
+    tileheight: u32,
+    infinite: bool,
+    nextlayerid: usize,
+    nextobjectid: usize,
+    layers: Vec<JsonLayer<'a>>,
+    tilesets: Vec<JsonTileset<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonLayer<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    id: usize,
+    name: &'a str,
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    opacity: f32,
+    visible: bool,
+    data: &'a [u32],
+}
+
+#[derive(Serialize)]
+struct JsonTileset<'a> {
+    firstgid: u32,
+    name: &'static str,
+    tilewidth: u32,
+    tileheight: u32,
+    tilecount: usize,
+    columns: usize,
+    margin: u32,
+    spacing: u32,
+    image: &'a str,
+    imagewidth: u32,
+    imageheight: u32,
+    tiles: Vec<JsonTile>,
+}
+
+#[derive(Serialize)]
+struct JsonTile {
+    id: usize,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    class: &'static str,
+}
+
+/// A floor and wall map as a single layer
+pub fn floor_map(map: &[Vec<bool>], tile_size: u32) -> TiledMap {
+    let kinds = vec![
+        TileKind { name: "floor", color: Rgb([255, 255, 255]) },
+        TileKind { name: "wall", color: Rgb([0, 0, 0]) },
+    ];
+    let mut tiled = TiledMap::new(map[0].len(), map.len(), tile_size, kinds);
+    tiled.add_layer("map", map, |&floor| Some(if floor { 0 } else { 1 }));
+    tiled
+}
+
+/// Terrain as a ground layer of floor and water with the walls on a layer above
+pub fn terrain_map(terrain: &[Vec<Terrain>], tile_size: u32) -> TiledMap {
+    let all = [Terrain::DeepWater, Terrain::Water, Terrain::Floor, Terrain::Wall];
+    let kinds = all
+        .iter()
+        .map(|&kind| TileKind { name: terrain_name(kind), color: kind.color() })
+        .collect();
+    let index = |kind: Terrain| all.iter().position(|&other| other == kind);
+
+    let mut tiled = TiledMap::new(terrain[0].len(), terrain.len(), tile_size, kinds);
+    tiled.add_layer("ground", terrain, |&cell| if cell == Terrain::Wall { index(Terrain::Floor) } else { index(cell) });
+    tiled.add_layer("walls", terrain, |&cell| if cell == Terrain::Wall { index(Terrain::Wall) } else { None });
+    tiled
+}
+
+fn terrain_name(terrain: Terrain) -> &'static str {
+    match terrain {
+        Terrain::DeepWater => "deep_water",
+        Terrain::Water => "water",
+        Terrain::Floor => "floor",
+        Terrain::Wall => "wall",
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_hold_one_based_tile_ids() {
+        let map = vec![vec![true, false], vec![false, true], vec![true, true]];
+        let tiled = floor_map(&map, 16);
+
+        assert_eq!(tiled.layers, vec![("map".to_string(), vec![1, 2, 2, 1, 1, 1])]);
+        let json = tiled.to_json("map_tiles.png");
+        assert!(json.contains("\"width\":2,\"height\":3"));
+        assert!(json.contains("\"data\":[1,2,2,1,1,1]"));
+        assert!(json.contains("\"imagewidth\":32"));
+
+        let tmx = tiled.to_tmx("map_tiles.png");
+        assert!(tmx.contains("<data encoding=\"csv\">\n1,2,\n2,1,\n1,1\n  </data>"));
+    }
+
+    #[test]
+    fn test_json_escapes_names() {
+        let mut tiled = floor_map(&[vec![true]], 16);
+        tiled.add_layer("quote \" tab \t line\n", &[vec![false]], |_| Some(1));
+        let json: serde_json::Value = serde_json::from_str(&tiled.to_json("C:\\maps\\tiles.png")).unwrap();
+
+        assert_eq!(json["layers"][1]["name"], "quote \" tab \t line\n");
+        assert_eq!(json["tilesets"][0]["image"], "C:\\maps\\tiles.png");
+        assert_eq!(json["tilesets"][0]["tiles"][1]["class"], "wall");
+    }
+
+    #[test]
+    fn test_terrain_walls_go_on_their_own_layer() {
+        let terrain = vec![vec![Terrain::Wall, Terrain::Water]];
+        let tiled = terrain_map(&terrain, 8);
+
+        assert_eq!(tiled.layers[0], ("ground".to_string(), vec![3, 2]));
+        assert_eq!(tiled.layers[1], ("walls".to_string(), vec![4, 0]));
+    }
+}
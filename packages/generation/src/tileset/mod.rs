@@ -1,3 +1,5 @@
+pub mod wfc;
+
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 use std::collections::HashMap;
@@ -73,6 +75,20 @@ impl TilesetGenerator {
         }
     }
 
+    pub fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    /// Fill a grid of chunks whose connectors all line up, with wave function collapse
+    ///
+    /// Unlike `generate_chunk_with_constraints`, which picks any template that
+    /// fits the chunks already placed, this backtracks out of dead ends. Returns
+    /// the chunks by row, or `None` when the library can't fill the grid.
+    pub fn generate_grid(&self, width: usize, height: usize, options: &wfc::WfcOptions) -> Option<Vec<Vec<&Template>>> {
+        let grid = wfc::collapse(&self.templates, width, height, options)?;
+        Some(grid.into_iter().map(|row| row.into_iter().map(|index| &self.templates[index]).collect()).collect())
+    }
+
     pub fn generate_chunk(&self, x: i32, y: i32) -> Template {
         self.generate_chunk_with_constraints(x, y, &HashMap::new())
     }
@@ -91,7 +107,7 @@ impl TilesetGenerator {
             // If no template satisfies constraints, generate a simple one
             self.generate_simple_template(0, &mut rng)
         } else {
-            let idx = rng.gen_range(0..valid_templates.len());
+            let idx = rng.random_range(0..valid_templates.len());
             valid_templates[idx].clone()
         }
     }
@@ -0,0 +1,240 @@
+use std::collections::{HashMap, VecDeque};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+
+use super::{EdgeSide, PathType, Template};
+
+const SIDES: [EdgeSide; 4] = [EdgeSide::North, EdgeSide::South, EdgeSide::East, EdgeSide::West];
+
+/// Settings for a wave function collapse run
+#[derive(Debug, Clone, Copy)]
+pub struct WfcOptions {
+    /// The same seed and templates always give the same grid
+    pub seed: u64,
+    /// Whether connectors may point off the edge of the grid
+    pub open_border: bool,
+    /// Choices undone after contradictions before giving up
+    pub max_backtracks: usize,
+}
+
+impl Default for WfcOptions {
+    fn default() -> Self {
+        Self { seed: 0, open_border: false, max_backtracks: 1000 }
+    }
+}
+
+/// Fill a `width` x `height` grid with templates whose connectors line up
+///
+/// Two chunks side by side fit when their connectors on the shared edge are
+/// the same, so every corridor leading out of one leads into the other. The
+/// cell with the fewest templates left is collapsed first, and each choice is
+/// propagated to its neighbors; when a cell runs out of templates the last
+/// choice is undone and another tried. Returns the template index for each
+/// cell, by row, or `None` when no grid fits or `max_backtracks` runs out.
+pub fn collapse(templates: &[Template], width: usize, height: usize, options: &WfcOptions) -> Option<Vec<Vec<usize>>> {
+    if templates.is_empty() || width == 0 || height == 0 {
+        return None;
+    }
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let signatures = edge_signatures(templates);
+    let closed = signatures.closed;
+
+    let mut wave = Wave {
+        width,
+        height,
+        signatures: &signatures.ids,
+        domains: vec![(0..templates.len()).collect(); width * height],
+    };
+
+    // Nothing may lead off a closed border
+    if !options.open_border {
+        for cell in 0..width * height {
+            for side in SIDES {
+                if wave.neighbor(cell, side).is_none() {
+                    wave.domains[cell].retain(|&template| wave.signatures[template][side_index(side)] == closed);
+                }
+            }
+        }
+    }
+    if !wave.propagate((0..width * height).collect()) {
+        return None;
+    }
+
+    // Each choice remembers the domains from before it, to undo it
+    let mut choices: Vec<(Vec<Vec<usize>>, usize, usize)> = Vec::new();
+    let mut backtracks = 0;
+    while let Some(cell) = wave.least_open(&mut rng) {
+        let template = *wave.domains[cell].choose(&mut rng)?;
+        choices.push((wave.domains.clone(), cell, template));
+        wave.domains[cell] = vec![template];
+
+        if wave.propagate(vec![cell]) {
+            continue;
+        }
+
+        // Undo choices until ruling one out leaves a grid that still works
+        loop {
+            backtracks += 1;
+            if backtracks > options.max_backtracks {
+                return None;
+            }
+            let (domains, cell, template) = choices.pop()?;
+            wave.domains = domains;
+            wave.domains[cell].retain(|&other| other != template);
+            if !wave.domains[cell].is_empty() && wave.propagate(vec![cell]) {
+                break;
+            }
+        }
+    }
+
+    Some(wave.domains.chunks(width).map(|row| row.iter().map(|domain| domain[0]).collect()).collect())
+}
+
+/// Templates still possible for each cell, by row
+struct Wave<'a> {
+    width: usize,
+    height: usize,
+    /// Per template, an id for the connectors on each side in `SIDES` order
+    signatures: &'a [[usize; 4]],
+    domains: Vec<Vec<usize>>,
+}
+
+impl Wave<'_> {
+    fn neighbor(&self, cell: usize, side: EdgeSide) -> Option<usize> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let (nx, ny) = match side {
+            EdgeSide::North => (x, y.checked_sub(1)?),
+            EdgeSide::South => (x, y + 1),
+            EdgeSide::East => (x + 1, y),
+            EdgeSide::West => (x.checked_sub(1)?, y),
+        };
+        (nx < self.width && ny < self.height).then_some(ny * self.width + nx)
+    }
+
+    /// The undecided cell with the fewest templates left, ties picked at random
+    fn least_open(&self, rng: &mut impl Rng) -> Option<usize> {
+        let fewest = self.domains.iter().map(Vec::len).filter(|&count| count > 1).min()?;
+        let tied: Vec<usize> = (0..self.domains.len()).filter(|&cell| self.domains[cell].len() == fewest).collect();
+        tied.choose(rng).copied()
+    }
+
+    /// Drop templates that no longer fit next to their neighbors, spreading out from `changed`
+    ///
+    /// Returns false on a contradiction, when a cell has nothing left.
+    fn propagate(&mut self, changed: Vec<usize>) -> bool {
+        let mut queue = VecDeque::from(changed);
+        while let Some(cell) = queue.pop_front() {
+            for side in SIDES {
+                let Some(neighbor) = self.neighbor(cell, side) else {
+                    continue;
+                };
+                let (ours, theirs) = (side_index(side), side_index(side.opposite()));
+                let allowed: Vec<usize> = self.domains[cell].iter().map(|&template| self.signatures[template][ours]).collect();
+
+                let before = self.domains[neighbor].len();
+                let signatures = self.signatures;
+                self.domains[neighbor].retain(|&template| allowed.contains(&signatures[template][theirs]));
+                if self.domains[neighbor].is_empty() {
+                    return false;
+                }
+                if self.domains[neighbor].len() < before {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        true
+    }
+}
+
+fn side_index(side: EdgeSide) -> usize {
+    SIDES.iter().position(|&other| other == side).unwrap()
+}
+
+/// Each template's connectors per side, numbered so equal sets get equal ids
+struct EdgeSignatures {
+    ids: Vec<[usize; 4]>,
+    /// The id of a side with no connectors
+    closed: usize,
+}
+
+fn edge_signatures(templates: &[Template]) -> EdgeSignatures {
+    let mut numbers: HashMap<Vec<(usize, PathType)>, usize> = HashMap::new();
+    let closed = numbers.len();
+    numbers.insert(Vec::new(), closed);
+
+    let ids = templates
+        .iter()
+        .map(|template| {
+            SIDES.map(|side| {
+                let mut connectors: Vec<(usize, PathType)> = template
+                    .connectors
+                    .iter()
+                    .filter(|connector| connector.edge == side)
+                    .map(|connector| (connector.position, connector.path_type))
+                    .collect();
+                connectors.sort_by_key(|&(position, path_type)| (position, path_type == PathType::Side));
+                connectors.dedup();
+                let next = numbers.len();
+                *numbers.entry(connectors).or_insert(next)
+            })
+        })
+        .collect();
+    EdgeSignatures { ids, closed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tileset::{Connector, TilesetGenerator};
+
+    fn fits(templates: &[Template], grid: &[Vec<usize>]) -> bool {
+        let connectors = |template: usize, side: EdgeSide| {
+            let mut connectors: Vec<(usize, PathType)> = templates[template]
+                .connectors
+                .iter()
+                .filter(|connector| connector.edge == side)
+                .map(|connector| (connector.position, connector.path_type))
+                .collect();
+            connectors.sort_by_key(|&(position, path_type)| (position, path_type == PathType::Side));
+            connectors
+        };
+        let (width, height) = (grid[0].len(), grid.len());
+        (0..height).all(|y| {
+            (0..width).all(|x| {
+                let east = x + 1 == width || connectors(grid[y][x], EdgeSide::East) == connectors(grid[y][x + 1], EdgeSide::West);
+                let south = y + 1 == height || connectors(grid[y][x], EdgeSide::South) == connectors(grid[y + 1][x], EdgeSide::North);
+                east && south
+            })
+        })
+    }
+
+    #[test]
+    fn test_collapsed_grid_lines_up() {
+        let mut generator = TilesetGenerator::new();
+        generator.generate_template_library(1);
+        let templates = generator.templates();
+
+        for seed in 0..4 {
+            let grid = collapse(templates, 6, 5, &WfcOptions { seed, ..Default::default() }).unwrap();
+            assert!(fits(templates, &grid));
+            // A closed border leaves no corridor leading off the grid
+            assert!(grid[0].iter().all(|&template| templates[template].connectors.iter().all(|c| c.edge != EdgeSide::North)));
+            assert!(grid.iter().all(|row| templates[row[0]].connectors.iter().all(|c| c.edge != EdgeSide::West)));
+        }
+    }
+
+    #[test]
+    fn test_unsolvable_grid_gives_none() {
+        // A crossroads only, which can't sit against a closed border
+        let mut crossroads = Template::new(0);
+        crossroads.connectors = SIDES
+            .iter()
+            .map(|&edge| Connector { edge, position: 128, path_type: PathType::Main })
+            .collect();
+        let templates = [crossroads];
+
+        assert!(collapse(&templates, 3, 3, &WfcOptions::default()).is_none());
+        assert!(collapse(&templates, 3, 3, &WfcOptions { open_border: true, ..Default::default() }).is_some());
+    }
+}
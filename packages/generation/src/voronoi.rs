@@ -0,0 +1,232 @@
+//! Maps split into Voronoi regions around scattered sites
+//!
+//! Each cell belongs to the region of its nearest site. Regions are opened or
+//! left solid at random, and the borders between them can be walled off, for
+//! cracked-earth plates, or carved out, for passages winding between solid
+//! blocks. The regions come back with the map, so biomes can be painted per
+//! region.
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::index;
+use serde::{Deserialize, Serialize};
+
+/// What happens to the cells along the borders between regions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Border {
+    /// Borders are whatever the region they're in is
+    None,
+    /// Walls between regions, even between two open ones
+    Wall,
+    /// Passages between regions, even between two solid ones
+    Floor,
+}
+
+/// Settings for a Voronoi map
+#[derive(Debug, Clone, Copy)]
+pub struct VoronoiConfig {
+    pub width: usize,
+    pub height: usize,
+    /// The same seed and settings always give the same map
+    pub seed: u64,
+    /// Number of regions
+    pub sites: usize,
+    /// Lloyd relaxation passes, each moving the sites to the middle of their
+    /// region so region sizes even out
+    pub relax_passes: usize,
+    /// Chance each region's interior is floor
+    pub open_chance: f64,
+    pub border: Border,
+    /// Thickness of the borders, in cells
+    pub border_width: usize,
+}
+
+impl Default for VoronoiConfig {
+    fn default() -> Self {
+        Self {
+            width: 256,
+            height: 256,
+            seed: 0,
+            sites: 24,
+            relax_passes: 2,
+            open_chance: 1.0,
+            border: Border::Wall,
+            border_width: 1,
+        }
+    }
+}
+
+/// A carved Voronoi map with the regions it was carved from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoronoiMap {
+    /// `true` for floor
+    pub map: Vec<Vec<bool>>,
+    /// Index into `sites` of the region each cell is in, by row
+    pub regions: Vec<Vec<usize>>,
+    /// Where each region's site ended up after relaxing
+    pub sites: Vec<(usize, usize)>,
+    /// Whether each region's interior was opened
+    pub open: Vec<bool>,
+}
+
+/// Scatter sites, split the map into their regions and carve it
+///
+/// The outer ring is always wall.
+pub fn voronoi(config: &VoronoiConfig) -> VoronoiMap {
+    let (width, height) = (config.width, config.height);
+    let mut rng = StdRng::seed_from_u64(config.seed);
+
+    // Distinct cells, so no two regions start out on top of each other
+    let count = config.sites.clamp(1, width * height);
+    let mut sites: Vec<(usize, usize)> = index::sample(&mut rng, width * height, count)
+        .into_iter()
+        .map(|cell| (cell % width, cell / width))
+        .collect();
+
+    let mut regions = nearest_sites(&sites, width, height);
+    for _ in 0..config.relax_passes {
+        sites = centroids(&regions, &sites);
+        regions = nearest_sites(&sites, width, height);
+    }
+
+    let open_chance = config.open_chance.clamp(0.0, 1.0);
+    let open: Vec<bool> = sites.iter().map(|_| rng.random_bool(open_chance)).collect();
+
+    // Cells exactly on the line between two sites count, or one cell wide borders would leave gaps
+    let half_width = config.border_width as f64 / 2.0 + 1e-9;
+    let mut map = vec![vec![false; width]; height];
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let region = regions[y][x];
+            let on_border = config.border != Border::None
+                && config.border_width > 0
+                && border_distance(&sites, region, (x, y)) <= half_width;
+            map[y][x] = if on_border { config.border == Border::Floor } else { open[region] };
+        }
+    }
+
+    VoronoiMap { map, regions, sites, open }
+}
+
+/// Index of the nearest site to each cell, ties going to the first
+fn nearest_sites(sites: &[(usize, usize)], width: usize, height: usize) -> Vec<Vec<usize>> {
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    (0..sites.len())
+                        .min_by_key(|&site| {
+                            let (dx, dy) = (sites[site].0.abs_diff(x), sites[site].1.abs_diff(y));
+                            dx * dx + dy * dy
+                        })
+                        .unwrap()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The middle cell of each region; a region with no cells keeps its site
+fn centroids(regions: &[Vec<usize>], sites: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut sums = vec![(0, 0, 0); sites.len()];
+    for (y, row) in regions.iter().enumerate() {
+        for (x, &region) in row.iter().enumerate() {
+            let sum = &mut sums[region];
+            *sum = (sum.0 + x, sum.1 + y, sum.2 + 1);
+        }
+    }
+    sums.iter()
+        .zip(sites)
+        .map(|(&(x, y, count), &site)| x.checked_div(count).zip(y.checked_div(count)).unwrap_or(site))
+        .collect()
+}
+
+/// Distance from `cell` to the nearest edge of its region
+///
+/// Every edge lies on the line halfway between the region's site and another,
+/// so this is the distance to the nearest of those lines.
+fn border_distance(sites: &[(usize, usize)], region: usize, (x, y): (usize, usize)) -> f64 {
+    let (px, py) = (x as f64, y as f64);
+    let (sx, sy) = (sites[region].0 as f64, sites[region].1 as f64);
+    let own = (px - sx).powi(2) + (py - sy).powi(2);
+
+    sites
+        .iter()
+        .filter(|&&site| site != sites[region])
+        .map(|&(ox, oy)| {
+            let (ox, oy) = (ox as f64, oy as f64);
+            let other = (px - ox).powi(2) + (py - oy).powi(2);
+            (other - own) / (2.0 * (ox - sx).hypot(oy - sy))
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn configs() -> impl Strategy<Value = VoronoiConfig> {
+        (3..40usize, 3..40usize, any::<u64>(), 1..16usize, 0..3usize, 1..4usize).prop_map(
+            |(width, height, seed, sites, relax_passes, border_width)| VoronoiConfig {
+                width,
+                height,
+                seed,
+                sites,
+                relax_passes,
+                border_width,
+                ..Default::default()
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_walled_borders_keep_regions_apart(config in configs()) {
+            let level = voronoi(&config);
+            let (map, regions) = (&level.map, &level.regions);
+
+            for y in 0..config.height {
+                for x in 0..config.width {
+                    // No floor cell touches floor from another region
+                    if x + 1 < config.width && map[y][x] && map[y][x + 1] {
+                        prop_assert_eq!(regions[y][x], regions[y][x + 1]);
+                    }
+                    if y + 1 < config.height && map[y][x] && map[y + 1][x] {
+                        prop_assert_eq!(regions[y][x], regions[y + 1][x]);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_regions_are_nearest_sites(config in configs()) {
+            let level = voronoi(&config);
+            prop_assert_eq!(level.sites.len(), level.open.len());
+
+            for (y, row) in level.regions.iter().enumerate() {
+                for (x, &region) in row.iter().enumerate() {
+                    let distance = |(sx, sy): (usize, usize)| sx.abs_diff(x).pow(2) + sy.abs_diff(y).pow(2);
+                    prop_assert!(level.sites.iter().all(|&site| distance(site) >= distance(level.sites[region])));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_floor_borders_between_solid_regions() {
+        let config = VoronoiConfig { open_chance: 0.0, border: Border::Floor, ..Default::default() };
+        let level = voronoi(&config);
+
+        // Only the borders are open, and they run between every pair of neighboring regions
+        for y in 1..config.height - 1 {
+            for x in 1..config.width - 2 {
+                if level.regions[y][x] != level.regions[y][x + 1] {
+                    assert!(level.map[y][x] || level.map[y][x + 1]);
+                }
+            }
+        }
+        let floor = level.map.iter().flatten().filter(|&&cell| cell).count();
+        assert!(floor > 0 && floor < config.width * config.height / 4);
+        assert_eq!(voronoi(&config).map, level.map);
+    }
+}
@@ -0,0 +1,55 @@
+//! Dumps the shadow geometry of a small moving scene to JSON, without a window
+//!
+//! `cargo run --example headless --features headless -- [frames] [output]`
+//! writes one line per frame to `output` (default `shadows.jsonl`).
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use shadowcast::headless::HeadlessShadowPlugin;
+use shadowcast::{Flashlight, LightSource, ShadowCaster};
+use std::env;
+use std::f32::consts::PI;
+
+const DEFAULT_FRAMES: u32 = 120;
+
+/// Spin in place (radians per second)
+#[derive(Component)]
+struct Spin(f32);
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let frames = args.get(1).and_then(|arg| arg.parse().ok()).unwrap_or(DEFAULT_FRAMES);
+    let output = args.get(2).cloned().unwrap_or_else(|| "shadows.jsonl".to_string());
+
+    App::new()
+        .add_plugins((MinimalPlugins, TransformPlugin, HeadlessShadowPlugin::new(&output).with_frames(frames)))
+        .add_systems(Startup, setup)
+        .add_systems(Update, spin)
+        .run();
+
+    println!("Wrote {} frames to {}", frames, output);
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Transform::default(),
+        LightSource::new(500.0, PI / 2.0).with_sweep(PI / 2.0, 4.0),
+    ));
+    // A guard turning on the spot, so its beam sweeps the whole room
+    commands.spawn((Transform::from_xyz(-150.0, -100.0, 0.0), Flashlight::new(300.0, PI / 4.0), Spin(1.0)));
+
+    for (x, y) in [(150.0, 0.0), (0.0, 150.0), (-120.0, 60.0)] {
+        commands.spawn((
+            Transform::from_xyz(x, y, 0.0),
+            Collider::cuboid(25.0, 10.0),
+            ShadowCaster::default(),
+            Spin(0.5),
+        ));
+    }
+    commands.spawn((Transform::from_xyz(80.0, -120.0, 0.0), Collider::ball(20.0), ShadowCaster::default().with_opacity(0.4)));
+}
+
+fn spin(time: Res<Time>, mut spinners: Query<(&mut Transform, &Spin)>) {
+    for (mut transform, spin) in spinners.iter_mut() {
+        transform.rotate_z(spin.0 * time.delta_secs());
+    }
+}
@@ -0,0 +1,128 @@
+//! Flicker, pulse and sweep for lights
+//!
+//! The settings live on `LightSource`; `animate_lights` turns them into the
+//! brightness and direction the mask is drawn with each frame, so the base
+//! values the game sets are never overwritten.
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::LightSource;
+
+/// Random dips in brightness, like a torch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flicker {
+    /// Largest fraction of the brightness a dip removes
+    pub amount: f32,
+    /// Noise samples per second; higher is more nervous
+    pub speed: f32,
+}
+
+/// Smooth brightness cycle, like an alarm beacon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pulse {
+    /// Fraction of the brightness lost at the dimmest point
+    pub amount: f32,
+    /// Seconds per cycle
+    pub period: f32,
+}
+
+/// Cone swinging back and forth around its direction, like a searchlight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    /// Total swing in radians
+    pub arc: f32,
+    /// Seconds per full swing there and back
+    pub period: f32,
+}
+
+/// Brightness and direction a light is drawn with this frame
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LightState {
+    pub intensity: f32,
+    pub direction: Vec2,
+}
+
+impl Default for LightState {
+    fn default() -> Self {
+        Self { intensity: 1.0, direction: Vec2::X }
+    }
+}
+
+impl LightSource {
+    /// Brightness at `time` seconds; `seed` keeps lights from flickering in sync
+    pub fn intensity_at(&self, time: f32, seed: u32) -> f32 {
+        let mut intensity = self.intensity;
+        if let Some(pulse) = self.pulse.filter(|pulse| pulse.period > 0.0) {
+            let phase = (1.0 - (time / pulse.period * TAU).cos()) / 2.0;
+            intensity *= 1.0 - pulse.amount * phase;
+        }
+        if let Some(flicker) = self.flicker {
+            intensity *= 1.0 - flicker.amount * value_noise(time * flicker.speed, seed);
+        }
+        intensity.clamp(0.0, 1.0)
+    }
+
+    /// Cone direction at `time` seconds, swung by the sweep if there is one
+    pub fn direction_at(&self, time: f32) -> Vec2 {
+        let direction = self.direction.normalize_or_zero();
+        let Some(sweep) = self.sweep.filter(|sweep| sweep.period > 0.0) else {
+            return direction;
+        };
+        let angle = (time / sweep.period * TAU).sin() * sweep.arc / 2.0;
+        Vec2::from_angle(angle).rotate(direction)
+    }
+}
+
+/// Smooth noise in [0, 1], interpolating a random value per whole `t`
+fn value_noise(t: f32, seed: u32) -> f32 {
+    let cell = t.floor();
+    let blend = t - cell;
+    let blend = blend * blend * (3.0 - 2.0 * blend);
+    let a = hash(cell as i32 as u32, seed);
+    let b = hash((cell as i32 as u32).wrapping_add(1), seed);
+    a + (b - a) * blend
+}
+
+fn hash(x: u32, seed: u32) -> f32 {
+    let mut h = x.wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x9e37_79b9);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h & 0xffff) as f32 / 65535.0
+}
+
+/// Work out every light's brightness and direction for this frame
+pub(crate) fn animate_lights(time: Res<Time>, mut lights: Query<(Entity, &LightSource, &mut LightState)>) {
+    let elapsed = time.elapsed_secs();
+    for (entity, light, mut state) in lights.iter_mut() {
+        state.intensity = light.intensity_at(elapsed, entity.index());
+        state.direction = light.direction_at(elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulse_and_flicker_stay_in_range() {
+        let light = LightSource::new(100.0, 1.0).with_pulse(0.5, 2.0);
+        assert_eq!(light.intensity_at(0.0, 0), 1.0);
+        assert!((light.intensity_at(1.0, 0) - 0.5).abs() < 1e-5);
+
+        let torch = LightSource::new(100.0, 1.0).with_flicker(0.4, 12.0);
+        let samples: Vec<f32> = (0..200).map(|i| torch.intensity_at(i as f32 * 0.013, 3)).collect();
+        assert!(samples.iter().all(|i| (0.6..=1.0).contains(i)));
+        assert!(samples.iter().any(|i| *i < 0.95));
+        assert_eq!(torch.intensity_at(0.5, 3), torch.intensity_at(0.5, 3));
+    }
+
+    #[test]
+    fn test_sweep_swings_around_base_direction() {
+        let light = LightSource::new(100.0, 1.0).with_direction(Vec2::Y).with_sweep(1.0, 4.0);
+        assert!(light.direction_at(0.0).abs_diff_eq(Vec2::Y, 1e-5));
+        assert!((Vec2::Y.angle_to(light.direction_at(1.0)) - 0.5).abs() < 1e-5);
+        assert!((Vec2::Y.angle_to(light.direction_at(3.0)) + 0.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,183 @@
+//! Cached shadow geometry for casters and lights that hold still
+//!
+//! Most casters in a level never move, so their world-space outlines are kept
+//! in `CasterOutlines` and only rebuilt when the `GlobalTransform` or
+//! `Collider` changes. Each light keeps the silhouette edges it found per
+//! caster in its `ShadowCache`, and only finds them again for casters whose
+//! outline changed, or for all of them once the light has moved further than
+//! `LIGHT_MOVE_THRESHOLD` from where they were found.
+//!
+//! Outlines are also built at a level of detail picked from the nearest light:
+//! round shapes get fewer sides the further away that light is, and casters
+//! out of every light's range get no outline at all until one comes close.
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::geometry::{collider_bounds, get_collider_vertices_with_segments, silhouette_edges, CIRCLE_SEGMENTS};
+use crate::LightSource;
+
+/// How far a light may drift before its silhouette edges are found again
+///
+/// Silhouettes only change when the light crosses the line of an edge, so a
+/// small drift leaves almost every edge as it was.
+pub const LIGHT_MOVE_THRESHOLD: f32 = 2.0;
+
+/// Sides per circle for a caster in the nearest, middle and furthest third of a light's range
+pub const LOD_CIRCLE_SEGMENTS: [usize; 3] = [CIRCLE_SEGMENTS, 10, 6];
+
+/// World-space outlines of a caster's collider, one per convex part
+#[derive(Component, Debug, Clone, Default)]
+pub struct CasterOutlines(pub Vec<Vec<Vec2>>);
+
+/// The detail a caster's outlines were built at, and the circle around it that picks it
+#[derive(Component, Debug, Clone, Default)]
+pub struct CasterDetail {
+    /// Sides per circle, or `None` while no light reaches the caster and it has no outlines
+    pub circle_segments: Option<usize>,
+    center: Vec2,
+    radius: f32,
+}
+
+impl CasterDetail {
+    /// Sides per circle wanted for a caster at `center` with `radius`, given each light's position and range
+    pub fn circle_segments_for(center: Vec2, radius: f32, lights: &[(Vec2, f32)]) -> Option<usize> {
+        lights
+            .iter()
+            .filter(|(_, range)| *range > 0.0)
+            .map(|(position, range)| (position.distance(center) - radius).max(0.0) / range)
+            .filter(|reach| *reach <= 1.0)
+            .min_by(f32::total_cmp)
+            .map(|reach| {
+                let band = (reach * LOD_CIRCLE_SEGMENTS.len() as f32) as usize;
+                LOD_CIRCLE_SEGMENTS[band.min(LOD_CIRCLE_SEGMENTS.len() - 1)]
+            })
+    }
+}
+
+/// Silhouette edges a light found for each caster, per outline
+#[derive(Component, Debug, Clone, Default)]
+pub struct ShadowCache {
+    /// Light position the cached edges were found from
+    anchor: Option<Vec2>,
+    edges: HashMap<Entity, Vec<Vec<(Vec2, Vec2)>>>,
+}
+
+impl ShadowCache {
+    /// Drop every cached edge if the light moved past the threshold
+    pub fn follow_light(&mut self, light_pos: Vec2) {
+        let moved = self.anchor.is_none_or(|anchor| anchor.distance(light_pos) > LIGHT_MOVE_THRESHOLD);
+        if moved {
+            self.anchor = Some(light_pos);
+            self.edges.clear();
+        }
+    }
+
+    /// Silhouette edges of each of the caster's outlines, found again only if
+    /// missing or the outlines `changed`
+    pub fn edges(&mut self, caster: Entity, outlines: &[Vec<Vec2>], changed: bool) -> &[Vec<(Vec2, Vec2)>] {
+        let anchor = self.anchor.unwrap_or_default();
+        let find = || outlines.iter().map(|outline| silhouette_edges(anchor, outline)).collect();
+        let cached = self.edges.entry(caster).or_insert_with(find);
+        if changed {
+            *cached = find();
+        }
+        cached
+    }
+
+    /// Drop one caster's edges so they are found again next time
+    pub fn forget(&mut self, caster: Entity) {
+        self.edges.remove(&caster);
+    }
+
+    /// Forget casters that no longer exist
+    pub fn retain(&mut self, keep: impl Fn(Entity) -> bool) {
+        self.edges.retain(|entity, _| keep(*entity));
+    }
+}
+
+/// Rebuild the outlines of casters that moved, changed shape or need another level of detail
+///
+/// Only outlines that are rebuilt are marked changed, so light caches keep
+/// the edges of every other caster.
+pub(crate) fn update_caster_outlines(
+    lights: Query<(&GlobalTransform, &LightSource)>,
+    mut casters: Query<(Ref<GlobalTransform>, Ref<Collider>, &mut CasterOutlines, &mut CasterDetail)>,
+) {
+    let lights: Vec<(Vec2, f32)> = lights
+        .iter()
+        .map(|(transform, light)| (transform.translation().truncate(), light.range))
+        .collect();
+
+    for (transform, collider, mut outlines, mut detail) in casters.iter_mut() {
+        let changed = transform.is_changed() || collider.is_changed() || outlines.is_added();
+        if changed {
+            (detail.center, detail.radius) = collider_bounds(&collider, &transform.compute_transform());
+        }
+
+        let circle_segments = CasterDetail::circle_segments_for(detail.center, detail.radius, &lights);
+        if !changed && circle_segments == detail.circle_segments {
+            continue;
+        }
+        detail.circle_segments = circle_segments;
+        outlines.0 = match circle_segments {
+            Some(segments) => get_collider_vertices_with_segments(&collider, &transform.compute_transform(), segments),
+            None => Vec::new(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(offset: f32) -> Vec<Vec<Vec2>> {
+        vec![vec![
+            Vec2::new(offset - 10.0, -10.0),
+            Vec2::new(offset + 10.0, -10.0),
+            Vec2::new(offset + 10.0, 10.0),
+            Vec2::new(offset - 10.0, 10.0),
+        ]]
+    }
+
+    #[test]
+    fn test_detail_drops_with_distance_to_nearest_light() {
+        let lights = [(Vec2::ZERO, 300.0), (Vec2::new(1000.0, 0.0), 300.0)];
+        let at = |x: f32| CasterDetail::circle_segments_for(Vec2::new(x, 0.0), 10.0, &lights);
+
+        assert_eq!(at(50.0), Some(LOD_CIRCLE_SEGMENTS[0]));
+        assert_eq!(at(200.0), Some(LOD_CIRCLE_SEGMENTS[1]));
+        assert_eq!(at(300.0), Some(LOD_CIRCLE_SEGMENTS[2]));
+        // Out of range of one light but close to the other
+        assert_eq!(at(950.0), Some(LOD_CIRCLE_SEGMENTS[0]));
+        assert_eq!(at(500.0), None);
+        assert_eq!(CasterDetail::circle_segments_for(Vec2::ZERO, 10.0, &[]), None);
+    }
+
+    #[test]
+    fn test_edges_are_reused_until_the_caster_changes() {
+        let caster = Entity::from_raw(1);
+        let mut cache = ShadowCache::default();
+        cache.follow_light(Vec2::new(-100.0, 0.0));
+
+        let first = cache.edges(caster, &square(0.0), false).to_vec();
+        // An unchanged caster keeps its edges even if handed other outlines
+        assert_eq!(cache.edges(caster, &square(50.0), false), first.as_slice());
+        assert_ne!(cache.edges(caster, &square(50.0), true), first.as_slice());
+    }
+
+    #[test]
+    fn test_light_moving_past_threshold_clears_cache() {
+        let caster = Entity::from_raw(1);
+        let mut cache = ShadowCache::default();
+        cache.follow_light(Vec2::new(-100.0, 0.0));
+        let first = cache.edges(caster, &square(0.0), false).to_vec();
+
+        cache.follow_light(Vec2::new(-100.0, LIGHT_MOVE_THRESHOLD / 2.0));
+        assert_eq!(cache.edges(caster, &square(50.0), false), first.as_slice());
+
+        cache.follow_light(Vec2::new(-100.0, LIGHT_MOVE_THRESHOLD * 2.0));
+        assert_ne!(cache.edges(caster, &square(50.0), false), first.as_slice());
+    }
+}
@@ -0,0 +1,140 @@
+//! Lights carried by other entities
+//!
+//! Give a player or an enemy a `Flashlight` and a `LightSource` entity is
+//! spawned for it. Every frame the light is moved to the holder's `offset`
+//! and pointed along the holder's facing (its local +X), so the game only
+//! moves and turns the holder.
+
+use bevy::prelude::*;
+
+use crate::LightSource;
+
+/// A light held by this entity, pointing the way it faces
+#[derive(Component, Debug, Clone)]
+pub struct Flashlight {
+    /// Where the light sits in the holder's local space
+    pub offset: Vec2,
+    /// Light cone angle in radians
+    pub cone_angle: f32,
+    /// Maximum distance the light reaches
+    pub range: f32,
+}
+
+impl Flashlight {
+    pub fn new(range: f32, cone_angle: f32) -> Self {
+        Self { offset: Vec2::ZERO, cone_angle, range }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// World position and direction of the light for a holder at `holder`
+    pub fn pose(&self, holder: &GlobalTransform) -> (Vec2, Vec2) {
+        let position = holder.transform_point(self.offset.extend(0.0)).truncate();
+        let facing = (holder.rotation() * Vec3::X).truncate().normalize_or(Vec2::X);
+        (position, facing)
+    }
+}
+
+/// On a `Flashlight` holder: the light entity it carries
+///
+/// Change the beam's `LightSource` for anything `Flashlight` doesn't cover,
+/// like flicker or layers.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlashlightBeam(pub Entity);
+
+/// On a flashlight's light: the entity carrying it
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CarriedBy(pub Entity);
+
+/// Spawn the light for new holders, and pass on range and cone changes
+pub(crate) fn attach_flashlights(
+    mut commands: Commands,
+    holders: Query<(Entity, Ref<Flashlight>, Option<&FlashlightBeam>)>,
+    mut beams: Query<&mut LightSource, With<CarriedBy>>,
+) {
+    for (holder, flashlight, beam) in holders.iter() {
+        match beam {
+            None => {
+                let light = commands
+                    .spawn((Transform::default(), LightSource::new(flashlight.range, flashlight.cone_angle), CarriedBy(holder)))
+                    .id();
+                commands.entity(holder).insert(FlashlightBeam(light));
+            }
+            Some(beam) if flashlight.is_changed() => {
+                if let Ok(mut light) = beams.get_mut(beam.0) {
+                    light.range = flashlight.range;
+                    light.cone_angle = flashlight.cone_angle;
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Move each beam to its holder, or remove it once the holder or its `Flashlight` is gone
+///
+/// Runs after transform propagation, so the holder's `GlobalTransform` is this
+/// frame's; the beam has no parent, so its own is written along with `Transform`.
+pub(crate) fn follow_holders(
+    mut commands: Commands,
+    holders: Query<(&Flashlight, &GlobalTransform)>,
+    mut beams: Query<(Entity, &CarriedBy, &mut Transform, &mut GlobalTransform, &mut LightSource), Without<Flashlight>>,
+) {
+    for (beam, carried_by, mut transform, mut global, mut light) in beams.iter_mut() {
+        let Ok((flashlight, holder)) = holders.get(carried_by.0) else {
+            commands.entity(beam).despawn();
+            continue;
+        };
+
+        let (position, facing) = flashlight.pose(holder);
+        let translation = position.extend(holder.translation().z);
+        if transform.translation != translation {
+            transform.translation = translation;
+            *global = GlobalTransform::from(*transform);
+        }
+        if light.direction != facing {
+            light.direction = facing;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_pose_follows_holder_rotation() {
+        let flashlight = Flashlight::new(300.0, 1.0).with_offset(Vec2::new(10.0, 0.0));
+        let holder = GlobalTransform::from(Transform::from_xyz(100.0, 50.0, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_2)));
+
+        let (position, facing) = flashlight.pose(&holder);
+        assert!(position.distance(Vec2::new(100.0, 60.0)) < 1e-4);
+        assert!(facing.distance(Vec2::Y) < 1e-4);
+    }
+
+    #[test]
+    fn test_beam_is_spawned_and_removed_with_holder() {
+        let mut app = App::new();
+        app.add_systems(Update, (attach_flashlights, follow_holders).chain());
+
+        let holder = app
+            .world_mut()
+            .spawn((Flashlight::new(300.0, 1.0), GlobalTransform::from_xyz(20.0, 0.0, 0.0)))
+            .id();
+        app.update();
+        app.update();
+
+        let beam = app.world().get::<FlashlightBeam>(holder).unwrap().0;
+        let light = app.world().get::<LightSource>(beam).unwrap();
+        assert_eq!(light.range, 300.0);
+        assert_eq!(app.world().get::<Transform>(beam).unwrap().translation, Vec3::new(20.0, 0.0, 0.0));
+
+        app.world_mut().entity_mut(holder).remove::<Flashlight>();
+        app.update();
+        assert!(app.world().get_entity(beam).is_err());
+    }
+}
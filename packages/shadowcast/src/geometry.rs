@@ -0,0 +1,204 @@
+//! Collider outlines and silhouette edges, computed on the CPU each frame
+use bevy::prelude::*;
+use bevy_rapier2d::{
+    parry::{
+        math::{Isometry, Point, Real},
+        shape::{Shape, TypedShape},
+    },
+    prelude::*,
+};
+use std::f32::consts::PI;
+
+/// Sides used for a ball's outline at full detail; each capsule end gets half
+pub(crate) const CIRCLE_SEGMENTS: usize = 16;
+
+/// Edges of a polygon that face away from the light; the shader darkens everything behind them
+pub fn silhouette_edges(light_pos: Vec2, vertices: &[Vec2]) -> Vec<(Vec2, Vec2)> {
+    if vertices.len() < 3 {
+        return vec![];
+    }
+
+    let mut edges = Vec::new();
+    let shadow_inset = 5.0; // Move shadow start point inward by this amount
+
+    // Process each edge of the polygon
+    for i in 0..vertices.len() {
+        let v1 = vertices[i];
+        let v2 = vertices[(i + 1) % vertices.len()];
+
+        // Calculate edge vector and normal
+        let edge_vec = v2 - v1;
+        let edge_normal = Vec2::new(-edge_vec.y, edge_vec.x).normalize_or_zero(); // Perpendicular to edge, pointing outward
+
+        // Vector from edge midpoint to light
+        let edge_midpoint = (v1 + v2) * 0.5;
+        let to_light = (light_pos - edge_midpoint).normalize_or_zero();
+
+        // Check if this edge faces away from the light (dot product < 0)
+        if edge_normal.dot(to_light) < 0.0 {
+            // Move the shadow start points slightly inward along the light direction
+            let dir1 = (v1 - light_pos).normalize_or_zero();
+            let dir2 = (v2 - light_pos).normalize_or_zero();
+            edges.push((v1 + dir1 * shadow_inset, v2 + dir2 * shadow_inset));
+        }
+    }
+
+    edges
+}
+
+/// Extracts world-space outlines from a Rapier collider, one counter-clockwise polygon per convex part
+/// Balls and capsule ends are approximated with polygons; compound shapes and triangle meshes
+/// give several outlines
+pub fn get_collider_vertices(collider: &Collider, transform: &Transform) -> Vec<Vec<Vec2>> {
+    get_collider_vertices_with_segments(collider, transform, CIRCLE_SEGMENTS)
+}
+
+/// `get_collider_vertices` with balls cut into `circle_segments` sides, and capsule ends into half that
+pub fn get_collider_vertices_with_segments(collider: &Collider, transform: &Transform, circle_segments: usize) -> Vec<Vec<Vec2>> {
+    let mut outlines = Vec::new();
+    collect_outlines(&*collider.raw, &Isometry::identity(), circle_segments.max(3), &mut outlines);
+
+    // Transform vertices from collider space to world space
+    for outline in &mut outlines {
+        for vertex in outline.iter_mut() {
+            let rotated = transform.rotation * vertex.extend(0.0);
+            *vertex = rotated.truncate() + transform.translation.truncate();
+        }
+    }
+    outlines
+}
+
+/// World-space center and radius of a circle around the whole collider
+pub(crate) fn collider_bounds(collider: &Collider, transform: &Transform) -> (Vec2, f32) {
+    let sphere = collider.raw.compute_local_bounding_sphere();
+    let center = Vec2::new(sphere.center.x, sphere.center.y);
+    let rotated = transform.rotation * center.extend(0.0);
+    (rotated.truncate() + transform.translation.truncate(), sphere.radius)
+}
+
+/// Appends the outlines of `shape`, placed by `isometry`, in collider space
+fn collect_outlines(shape: &dyn Shape, isometry: &Isometry<Real>, circle_segments: usize, outlines: &mut Vec<Vec<Vec2>>) {
+    let place = |point: Point<Real>| {
+        let placed = isometry * point;
+        Vec2::new(placed.x, placed.y)
+    };
+
+    match shape.as_typed_shape() {
+        TypedShape::Cuboid(cuboid) => {
+            let half_extents = cuboid.half_extents;
+
+            // Counter-clockwise from bottom-left
+            outlines.push(vec![
+                place(Point::new(-half_extents.x, -half_extents.y)),
+                place(Point::new(half_extents.x, -half_extents.y)),
+                place(Point::new(half_extents.x, half_extents.y)),
+                place(Point::new(-half_extents.x, half_extents.y)),
+            ]);
+        }
+        TypedShape::Ball(ball) => {
+            // For circles, create an approximation using a polygon
+            outlines.push((0..circle_segments).map(|i| {
+                let angle = (i as f32 / circle_segments as f32) * 2.0 * PI;
+                place(Point::new(angle.cos() * ball.radius, angle.sin() * ball.radius))
+            }).collect());
+        }
+        TypedShape::Capsule(capsule) => {
+            // Half a circle around each end of the segment, counter-clockwise,
+            // starting with the end the segment points at
+            let a = Vec2::new(capsule.segment.a.x, capsule.segment.a.y);
+            let b = Vec2::new(capsule.segment.b.x, capsule.segment.b.y);
+            let heading = (b - a).try_normalize().unwrap_or(Vec2::Y).to_angle();
+            let cap_segments = circle_segments / 2;
+
+            let cap = |center: Vec2, facing: f32| {
+                (0..=cap_segments).map(move |i| {
+                    let angle = facing - PI / 2.0 + (i as f32 / cap_segments as f32) * PI;
+                    let point = center + Vec2::from_angle(angle) * capsule.radius;
+                    place(Point::new(point.x, point.y))
+                })
+            };
+            outlines.push(cap(b, heading).chain(cap(a, heading + PI)).collect());
+        }
+        TypedShape::ConvexPolygon(polygon) => {
+            // Parry keeps convex polygon points counter-clockwise
+            outlines.push(polygon.points().iter().map(|point| place(*point)).collect());
+        }
+        TypedShape::Triangle(triangle) => {
+            outlines.push(counter_clockwise(vec![place(triangle.a), place(triangle.b), place(triangle.c)]));
+        }
+        TypedShape::TriMesh(trimesh) => {
+            // Each triangle shadows on its own; edges shared inside the mesh only shadow
+            // area that is already behind the mesh
+            for triangle in trimesh.triangles() {
+                outlines.push(counter_clockwise(vec![place(triangle.a), place(triangle.b), place(triangle.c)]));
+            }
+        }
+        TypedShape::Compound(compound) => {
+            for (part_isometry, part) in compound.shapes() {
+                collect_outlines(&**part, &(isometry * part_isometry), circle_segments, outlines);
+            }
+        }
+        _ => {
+            // Segments, polylines and heightfields don't cast shadows yet
+        }
+    }
+}
+
+/// Reverses a polygon wound clockwise, so silhouette tests see consistent normals
+fn counter_clockwise(mut vertices: Vec<Vec2>) -> Vec<Vec2> {
+    let signed_area: f32 = (0..vertices.len())
+        .map(|i| vertices[i].perp_dot(vertices[(i + 1) % vertices.len()]))
+        .sum();
+    if signed_area < 0.0 {
+        vertices.reverse();
+    }
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compound_gives_one_outline_per_part() {
+        let collider = Collider::compound(vec![
+            (Vec2::new(-50.0, 0.0), 0.0, Collider::cuboid(10.0, 10.0)),
+            (Vec2::new(50.0, 0.0), 0.0, Collider::triangle(Vec2::ZERO, Vec2::Y * 10.0, Vec2::X * 10.0)),
+            (Vec2::new(0.0, 100.0), PI / 2.0, Collider::capsule_y(20.0, 5.0)),
+        ]);
+        let outlines = get_collider_vertices(&collider, &Transform::from_xyz(100.0, 0.0, 0.0));
+
+        assert_eq!(outlines.len(), 3);
+        assert!(outlines[0].contains(&Vec2::new(40.0, -10.0)));
+        // The clockwise triangle was rewound
+        let triangle = &outlines[1];
+        assert!((triangle[1] - triangle[0]).perp_dot(triangle[2] - triangle[0]) > 0.0);
+
+        // The capsule part is turned on its side by its local pose
+        let capsule = &outlines[2];
+        assert_eq!(capsule.len(), 2 * (CIRCLE_SEGMENTS / 2 + 1));
+        let center = Vec2::new(100.0, 100.0);
+        let extent = |axis: Vec2| capsule.iter().map(|point| (*point - center).dot(axis).abs()).fold(0.0, f32::max);
+        assert!((extent(Vec2::X) - 25.0).abs() < 1e-3);
+        assert!((extent(Vec2::Y) - 5.0).abs() < 1e-3);
+        assert!((0..capsule.len()).all(|i| {
+            let (a, b, c) = (capsule[i], capsule[(i + 1) % capsule.len()], capsule[(i + 2) % capsule.len()]);
+            (b - a).perp_dot(c - b) >= -1e-3
+        }));
+    }
+
+    #[test]
+    fn test_silhouette_uses_edges_facing_the_light() {
+        let square = vec![
+            Vec2::new(-10.0, -10.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(-10.0, 10.0),
+        ];
+        let edges = silhouette_edges(Vec2::new(0.0, -100.0), &square);
+
+        assert_eq!(edges.len(), 1);
+        // Pushed inward, away from the light
+        assert!(edges[0].0.y > -10.0 && edges[0].1.y > -10.0);
+    }
+}
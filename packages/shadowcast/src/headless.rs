@@ -0,0 +1,186 @@
+//! Shadow geometry without a window, dumped to JSON
+//!
+//! `HeadlessShadowPlugin` runs `ShadowGeometryPlugin` and writes what every
+//! light sees each frame as one line of JSON, so the geometry can be checked
+//! in CI or diffed between runs. Add it to an app with `MinimalPlugins` and a
+//! `TransformPlugin`; every frame steps a fixed 1/60 s, so a run with the same
+//! scene always writes the same file.
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use serde::Serialize;
+
+use crate::light_view::{LightView, LightViews};
+use crate::{CastShadows, LightSource, LightState, ShadowGeometryPlugin};
+
+/// Simulated time per frame
+const FRAME_TIME: f64 = 1.0 / 60.0;
+
+/// Runs the shadow geometry and writes every light's view to `output`, one frame per line
+pub struct HeadlessShadowPlugin {
+    pub output: PathBuf,
+    /// Frames to dump before the app exits, or `None` to run until told to stop
+    pub frames: Option<u32>,
+}
+
+impl HeadlessShadowPlugin {
+    pub fn new(output: impl Into<PathBuf>) -> Self {
+        Self { output: output.into(), frames: None }
+    }
+
+    pub fn with_frames(mut self, frames: u32) -> Self {
+        self.frames = Some(frames);
+        self
+    }
+}
+
+impl Plugin for HeadlessShadowPlugin {
+    fn build(&self, app: &mut App) {
+        let file = File::create(&self.output)
+            .unwrap_or_else(|err| panic!("Could not create {}: {}", self.output.display(), err));
+
+        app
+            .add_plugins(ShadowGeometryPlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(FRAME_TIME)))
+            .insert_resource(DumpWriter { file: BufWriter::new(file), frame: 0, frames: self.frames })
+            .add_systems(PostUpdate, dump_light_views.after(CastShadows).after(crate::animation::animate_lights));
+    }
+}
+
+/// Every light's view in one frame
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FrameDump {
+    pub frame: u32,
+    pub lights: Vec<LightDump>,
+}
+
+/// One light's view, with points as `[x, y]`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LightDump {
+    pub entity: u32,
+    pub origin: [f32; 2],
+    pub direction: [f32; 2],
+    pub range: f32,
+    pub half_cone: f32,
+    pub intensity: f32,
+    pub polygon: Vec<[f32; 2]>,
+    pub shadows: Vec<ShadowDump>,
+}
+
+/// One silhouette edge's shadow
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ShadowDump {
+    pub corners: [[f32; 2]; 4],
+    pub opacity: f32,
+}
+
+impl LightDump {
+    pub fn new(entity: Entity, intensity: f32, view: &LightView) -> Self {
+        Self {
+            entity: entity.index(),
+            origin: view.origin.to_array(),
+            direction: view.direction.to_array(),
+            range: view.range,
+            half_cone: view.half_cone,
+            intensity,
+            polygon: view.polygon.iter().map(|point| point.to_array()).collect(),
+            shadows: view
+                .shadows
+                .iter()
+                .map(|quad| ShadowDump { corners: quad.corners.map(|corner| corner.to_array()), opacity: quad.opacity })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct DumpWriter {
+    file: BufWriter<File>,
+    frame: u32,
+    frames: Option<u32>,
+}
+
+/// Write this frame's views, lights in spawn order, and stop after the last frame
+fn dump_light_views(
+    mut writer: ResMut<DumpWriter>,
+    lights: Query<(Entity, &LightState), With<LightSource>>,
+    views: LightViews,
+    mut exit: EventWriter<AppExit>,
+) {
+    let mut lights: Vec<_> = lights.iter().collect();
+    lights.sort_by_key(|(entity, _)| *entity);
+
+    let dump = FrameDump {
+        frame: writer.frame,
+        lights: lights
+            .into_iter()
+            .filter_map(|(entity, state)| views.get(entity).map(|view| LightDump::new(entity, state.intensity, &view)))
+            .collect(),
+    };
+
+    let written = serde_json::to_writer(&mut writer.file, &dump)
+        .map_err(std::io::Error::from)
+        .and_then(|()| writeln!(writer.file))
+        .and_then(|()| writer.file.flush());
+    if let Err(err) = written {
+        error!("Could not write shadow dump: {}", err);
+        exit.write(AppExit::error());
+        return;
+    }
+
+    writer.frame += 1;
+    if writer.frames.is_some_and(|frames| writer.frame >= frames) {
+        exit.write(AppExit::Success);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_rapier2d::prelude::Collider;
+
+    use crate::ShadowCaster;
+
+    fn headless_app(name: &str, frames: u32) -> (App, PathBuf) {
+        let output = std::env::temp_dir().join(format!("shadowcast_{}_{}.jsonl", name, std::process::id()));
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, HeadlessShadowPlugin::new(&output).with_frames(frames)));
+        (app, output)
+    }
+
+    #[test]
+    fn test_dump_has_wall_shadow() {
+        let (mut app, output) = headless_app("wall", 10);
+        app.world_mut().spawn((Transform::default(), LightSource::new(200.0, std::f32::consts::FRAC_PI_2)));
+        app.world_mut().spawn((Transform::from_xyz(50.0, 0.0, 0.0), Collider::cuboid(5.0, 20.0), ShadowCaster::default()));
+        app.update();
+        app.update();
+
+        let text = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(&output).unwrap();
+        let frames: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(frames.len(), 2);
+        let light = &frames[1]["lights"][0];
+        assert_eq!(light["range"], 200.0);
+        assert!(!light["shadows"].as_array().unwrap().is_empty());
+        // Nothing in the polygon reaches past the wall along the light's axis
+        assert!(light["polygon"].as_array().unwrap().iter().all(|point| {
+            point[1].as_f64().unwrap().abs() > 20.0 || point[0].as_f64().unwrap() <= 45.001
+        }));
+    }
+
+    #[test]
+    fn test_exits_after_frame_count() {
+        let (mut app, output) = headless_app("exit", 3);
+        app.update();
+        app.update();
+        assert!(app.should_exit().is_none());
+        app.update();
+        assert_eq!(app.should_exit(), Some(AppExit::Success));
+        std::fs::remove_file(&output).unwrap();
+    }
+}
@@ -0,0 +1,360 @@
+//! 2D flashlight shadow casting for Bevy and Rapier
+//!
+//! Add `ShadowCastPlugin`, give lights a `LightSource` and obstacles a
+//! `ShadowCaster` next to their Rapier `Collider`. The silhouette edges of
+//! every caster in a light cone are found on the CPU, and cached until the
+//! caster or the light moves, and a fullscreen shader darkens everything
+//! outside the cones and behind the edges.
+pub mod animation;
+pub mod cache;
+pub mod flashlight;
+pub mod geometry;
+#[cfg(feature = "headless")]
+pub mod headless;
+mod light_mask;
+pub mod light_view;
+pub mod visibility;
+
+use bevy::{asset::embedded_asset, prelude::*, sprite::Material2dPlugin};
+
+pub use animation::{Flicker, LightState, Pulse, Sweep};
+pub use cache::{CasterDetail, CasterOutlines, ShadowCache, LIGHT_MOVE_THRESHOLD, LOD_CIRCLE_SEGMENTS};
+pub use flashlight::{CarriedBy, Flashlight, FlashlightBeam};
+pub use geometry::{get_collider_vertices, get_collider_vertices_with_segments, silhouette_edges};
+pub use light_view::{light_view, LightView, LightViews, ShadowQuad};
+pub use visibility::{compute_visibility_polygon, is_visible, polygon_contains, reach_along};
+pub use light_mask::{MAX_EDGES, MAX_LIGHTS};
+use light_mask::{LightMaskMaterial, LightUniform, ShadowEdge};
+
+/// Side length of the darkness quad, centered on the camera
+const MASK_SIZE: f32 = 6000.0;
+/// Draw depth of the darkness quad
+const MASK_Z: f32 = 1.0;
+
+/// Plugin that draws darkness everywhere except inside `LightSource` cones
+pub struct ShadowCastPlugin;
+
+impl Plugin for ShadowCastPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "light_mask.wgsl");
+
+        app
+            .add_plugins((ShadowGeometryPlugin, Material2dPlugin::<LightMaskMaterial>::default()))
+            .init_resource::<AmbientDarkness>()
+            .add_systems(Startup, spawn_light_mask)
+            .add_systems(PostUpdate, (
+                follow_camera.before(TransformSystem::TransformPropagate),
+                update_light_mask
+                    .after(TransformSystem::TransformPropagate)
+                    .after(animation::animate_lights)
+                    .after(cache::update_caster_outlines)
+                    .in_set(CastShadows),
+            ));
+    }
+}
+
+/// The shadow geometry alone: flashlights, light animation and caster outlines
+///
+/// Needs no window, renderer or assets, so it runs under `MinimalPlugins` with
+/// a `TransformPlugin`, and `LightViews` reads what a light sees from it.
+/// `ShadowCastPlugin` adds it on its own.
+pub struct ShadowGeometryPlugin;
+
+impl Plugin for ShadowGeometryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, (
+            flashlight::attach_flashlights.before(flashlight::follow_holders),
+            // Beams follow where their holders ended up this frame
+            flashlight::follow_holders
+                .after(TransformSystem::TransformPropagate)
+                .before(animation::animate_lights),
+            animation::animate_lights,
+            // Read the propagated transforms so lights and casters can be children,
+            // and the beams' positions to pick each caster's detail
+            cache::update_caster_outlines
+                .after(TransformSystem::TransformPropagate)
+                .after(flashlight::follow_holders)
+                .in_set(CastShadows),
+        ));
+    }
+}
+
+/// The PostUpdate systems that find shadow edges, for ordering or timing other systems against
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CastShadows;
+
+/// How dark the unlit parts of the scene are, from 0 (daylight) to 1 (pitch black)
+///
+/// Lights cut through it, so below 1 the scene reads as dusk with brighter
+/// pools where the lights fall.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct AmbientDarkness(pub f32);
+
+impl Default for AmbientDarkness {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Layer mask matching every light and caster
+pub const ALL_LAYERS: u32 = u32::MAX;
+
+/// Component marking an entity as a light source
+#[derive(Component, Debug, Clone)]
+#[require(LightState, ShadowCache)]
+pub struct LightSource {
+    /// Maximum distance the light reaches
+    pub range: f32,
+    /// Light cone angle in radians (90 degrees = π/2)
+    pub cone_angle: f32,
+    /// World-space direction the cone points in, the center of any sweep
+    pub direction: Vec2,
+    /// Only casters sharing one of these layers block this light
+    pub layers: u32,
+    /// Brightness from 0 (off) to 1 (fully cuts through the darkness)
+    pub intensity: f32,
+    pub flicker: Option<Flicker>,
+    pub pulse: Option<Pulse>,
+    pub sweep: Option<Sweep>,
+}
+
+impl LightSource {
+    pub fn new(range: f32, cone_angle: f32) -> Self {
+        Self {
+            range,
+            cone_angle,
+            direction: Vec2::X,
+            layers: ALL_LAYERS,
+            intensity: 1.0,
+            flicker: None,
+            pulse: None,
+            sweep: None,
+        }
+    }
+
+    pub fn with_direction(mut self, direction: Vec2) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_layers(mut self, layers: u32) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_flicker(mut self, amount: f32, speed: f32) -> Self {
+        self.flicker = Some(Flicker { amount, speed });
+        self
+    }
+
+    pub fn with_pulse(mut self, amount: f32, period: f32) -> Self {
+        self.pulse = Some(Pulse { amount, period });
+        self
+    }
+
+    pub fn with_sweep(mut self, arc: f32, period: f32) -> Self {
+        self.sweep = Some(Sweep { arc, period });
+        self
+    }
+}
+
+/// Component marking an entity with a `Collider` as something that casts shadows
+#[derive(Component, Debug, Clone)]
+#[require(CasterOutlines, CasterDetail)]
+pub struct ShadowCaster {
+    /// Layers of the lights this caster blocks
+    pub layers: u32,
+    /// Share of the light stopped, from 0 (clear) to 1 (solid); glass sits in between
+    pub opacity: f32,
+}
+
+impl Default for ShadowCaster {
+    fn default() -> Self {
+        Self { layers: ALL_LAYERS, opacity: 1.0 }
+    }
+}
+
+impl ShadowCaster {
+    pub fn on_layers(layers: u32) -> Self {
+        Self { layers, ..default() }
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Whether this caster throws a shadow from `light`
+    pub fn blocks(&self, light: &LightSource) -> bool {
+        self.layers & light.layers != 0
+    }
+}
+
+/// Component marking the fullscreen darkness/light mask quad
+#[derive(Component)]
+struct LightMask;
+
+fn spawn_light_mask(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut mask_materials: ResMut<Assets<LightMaskMaterial>>,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(MASK_SIZE, MASK_SIZE))),
+        MeshMaterial2d(mask_materials.add(LightMaskMaterial::default())),
+        Transform::from_xyz(0.0, 0.0, MASK_Z),
+        LightMask,
+    ));
+}
+
+/// Keep the darkness quad under the camera, scaled with its zoom, so it always covers the viewport
+fn follow_camera(
+    cameras: Query<(&Transform, Option<&Projection>), With<Camera2d>>,
+    mut masks: Query<&mut Transform, (With<LightMask>, Without<Camera2d>)>,
+) {
+    let Some((camera, projection)) = cameras.iter().next() else {
+        return;
+    };
+    let center = camera.translation.truncate().extend(MASK_Z);
+    let zoom = match projection {
+        Some(Projection::Orthographic(ortho)) => ortho.scale.max(1.0),
+        _ => 1.0,
+    };
+    let scale = Vec3::new(zoom, zoom, 1.0);
+
+    for mut transform in masks.iter_mut() {
+        if transform.translation != center || transform.scale != scale {
+            transform.translation = center;
+            transform.scale = scale;
+        }
+    }
+}
+
+/// Main shadow casting system: finds the silhouette edges and hands them to the mask shader
+///
+/// Edges come from each light's `ShadowCache`, so only casters that changed
+/// (or every caster, once the light has moved far enough) are looked at again.
+fn update_light_mask(
+    mut mask_materials: ResMut<Assets<LightMaskMaterial>>,
+    mask_query: Query<&MeshMaterial2d<LightMaskMaterial>, With<LightMask>>,
+    mut light_query: Query<(&GlobalTransform, &LightSource, &LightState, &mut ShadowCache)>,
+    caster_query: Query<(Entity, Ref<CasterOutlines>, &ShadowCaster)>,
+    mut removed_casters: RemovedComponents<ShadowCaster>,
+    darkness: Res<AmbientDarkness>,
+) {
+    let Ok(mask_material) = mask_query.single() else {
+        return;
+    };
+    let Some(material) = mask_materials.get_mut(&mask_material.0) else {
+        return;
+    };
+    let mask = &mut material.mask;
+    mask.light_count = 0;
+    mask.edge_count = 0;
+    mask.darkness = darkness.0.clamp(0.0, 1.0);
+    let casters_removed = removed_casters.read().count() > 0;
+
+    // Process each light source
+    for (light_transform, light, state, mut cache) in light_query.iter_mut().take(MAX_LIGHTS) {
+        let light_pos = light_transform.translation().truncate();
+        let light_direction = state.direction;
+
+        let light_index = mask.light_count;
+        mask.lights[light_index as usize] = LightUniform {
+            position: light_pos,
+            direction: light_direction,
+            range: light.range,
+            half_cone: light.cone_angle / 2.0,
+            intensity: state.intensity,
+            _padding: 0.0,
+        };
+        mask.light_count += 1;
+
+        cache.follow_light(light_pos);
+        if casters_removed {
+            cache.retain(|entity| caster_query.contains(entity));
+        }
+
+        // Cast shadows for every part of an object that is in the light cone
+        for (entity, outlines, caster) in caster_query.iter() {
+            if !caster.blocks(light) || caster.opacity <= 0.0 {
+                // Find its edges afresh if it starts blocking this light later
+                cache.forget(entity);
+                continue;
+            }
+
+            let edges = cache.edges(entity, &outlines.0, outlines.is_changed());
+            for (vertices, edges) in outlines.0.iter().zip(edges) {
+                // Check if any vertex is within the light cone
+                let any_vertex_in_cone = vertices.iter().any(|vertex| {
+                    let to_vertex = *vertex - light_pos;
+                    to_vertex.length() <= light.range
+                        && light_direction.angle_to(to_vertex.normalize_or_zero()).abs() <= light.cone_angle / 2.0
+                });
+
+                if !any_vertex_in_cone {
+                    continue;
+                }
+
+                for &(start, end) in edges {
+                    if mask.edge_count as usize >= MAX_EDGES {
+                        warn_once!("More than {} silhouette edges, extra shadows are dropped", MAX_EDGES);
+                        break;
+                    }
+                    mask.edges[mask.edge_count as usize] = ShadowEdge {
+                        points: Vec4::new(start.x, start.y, end.x, end.y),
+                        light: light_index,
+                        opacity: caster.opacity,
+                    };
+                    mask.edge_count += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_casters_only_block_lights_on_shared_layers() {
+        let world_light = LightSource::new(100.0, 1.0).with_layers(0b01);
+        let vision_light = LightSource::new(100.0, 1.0).with_layers(0b10);
+
+        let wall = ShadowCaster::on_layers(0b01);
+        assert!(wall.blocks(&world_light));
+        assert!(!wall.blocks(&vision_light));
+        assert!(ShadowCaster::default().blocks(&vision_light));
+    }
+
+    #[test]
+    fn test_opacity_is_clamped() {
+        assert_eq!(ShadowCaster::default().opacity, 1.0);
+        assert_eq!(ShadowCaster::default().with_opacity(1.5).opacity, 1.0);
+        assert_eq!(ShadowCaster::on_layers(0b10).with_opacity(0.4).opacity, 0.4);
+    }
+
+    #[test]
+    fn test_geometry_runs_without_renderer() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, ShadowGeometryPlugin));
+        let light = app.world_mut().spawn((Transform::default(), LightSource::new(200.0, 1.0))).id();
+        app.world_mut().spawn((
+            Transform::from_xyz(50.0, 0.0, 0.0),
+            bevy_rapier2d::prelude::Collider::cuboid(5.0, 20.0),
+            ShadowCaster::default(),
+        ));
+        app.update();
+
+        let mut state = bevy::ecs::system::SystemState::<LightViews>::new(app.world_mut());
+        let view = state.get(app.world()).get(light).unwrap();
+        assert!(view.lights(Vec2::new(30.0, 0.0)));
+        assert!(!view.lights(Vec2::new(100.0, 0.0)));
+    }
+}
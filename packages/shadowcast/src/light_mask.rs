@@ -0,0 +1,85 @@
+//! Uniform layout and material for `light_mask.wgsl`
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+    sprite::{AlphaMode2d, Material2d},
+};
+
+pub use uniforms::{LightMaskUniform, LightUniform, ShadowEdge};
+
+/// Lights the mask shader can handle, must match the shader
+pub const MAX_LIGHTS: usize = 8;
+/// Silhouette edges the mask shader can handle, must match the shader
+pub const MAX_EDGES: usize = 256;
+
+/// The uniform structs, apart from the rest so the allow below covers nothing else
+mod uniforms {
+    // encase's ShaderType derive emits a `check` fn per field that it never calls
+    #![allow(dead_code)]
+
+    use bevy::{prelude::*, render::render_resource::ShaderType};
+
+    use super::{MAX_EDGES, MAX_LIGHTS};
+
+    /// A light as seen by the mask shader
+    #[derive(ShaderType, Clone, Copy, Default)]
+    pub struct LightUniform {
+        pub position: Vec2,
+        pub direction: Vec2,
+        pub range: f32,
+        pub half_cone: f32,
+        pub intensity: f32,
+        pub _padding: f32,
+    }
+
+    /// A silhouette edge and the light it blocks
+    #[derive(ShaderType, Clone, Copy, Default)]
+    pub struct ShadowEdge {
+        /// Edge start in xy, end in zw
+        pub points: Vec4,
+        pub light: u32,
+        /// Share of the light the caster stops, 1 for solid
+        pub opacity: f32,
+    }
+
+    /// Everything the shader needs for one frame, rewritten by `update_light_mask`
+    #[derive(ShaderType, Clone)]
+    pub struct LightMaskUniform {
+        pub lights: [LightUniform; MAX_LIGHTS],
+        pub edges: [ShadowEdge; MAX_EDGES],
+        pub light_count: u32,
+        pub edge_count: u32,
+        /// Alpha of the unlit darkness, from `AmbientDarkness`
+        pub darkness: f32,
+    }
+
+    impl Default for LightMaskUniform {
+        fn default() -> Self {
+            Self {
+                lights: [LightUniform::default(); MAX_LIGHTS],
+                edges: [ShadowEdge::default(); MAX_EDGES],
+                light_count: 0,
+                edge_count: 0,
+                darkness: 1.0,
+            }
+        }
+    }
+}
+
+/// Darkness everywhere except inside light cones, minus the shadows behind the edges
+#[derive(Asset, TypePath, AsBindGroup, Clone, Default)]
+pub struct LightMaskMaterial {
+    #[uniform(0)]
+    pub mask: LightMaskUniform,
+}
+
+impl Material2d for LightMaskMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://shadowcast/light_mask.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
@@ -0,0 +1,83 @@
+// Fullscreen darkness with light cones cut out and shadows behind silhouette edges
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct Light {
+    position: vec2<f32>,
+    direction: vec2<f32>,
+    range: f32,
+    half_cone: f32,
+    intensity: f32,
+    _padding: f32,
+}
+
+struct ShadowEdge {
+    // Edge start in xy, end in zw
+    points: vec4<f32>,
+    // Index of the light this edge blocks
+    light: u32,
+    // Share of the light the caster stops, 1 for solid
+    opacity: f32,
+}
+
+struct LightMask {
+    lights: array<Light, 8>,
+    edges: array<ShadowEdge, 256>,
+    light_count: u32,
+    edge_count: u32,
+    // Alpha of the unlit darkness, 1 for pitch black
+    darkness: f32,
+}
+
+@group(2) @binding(0) var<uniform> mask: LightMask;
+
+fn cross2(a: vec2<f32>, b: vec2<f32>) -> f32 {
+    return a.x * b.y - a.y * b.x;
+}
+
+// True when the segment a-b crosses the segment c-d
+fn segments_cross(a: vec2<f32>, b: vec2<f32>, c: vec2<f32>, d: vec2<f32>) -> bool {
+    let r = b - a;
+    let s = d - c;
+    let denom = cross2(r, s);
+    if abs(denom) < 1e-6 {
+        return false;
+    }
+    let t = cross2(c - a, s) / denom;
+    let u = cross2(c - a, r) / denom;
+    return t > 0.0 && t < 1.0 && u >= 0.0 && u <= 1.0;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let pixel = in.world_position.xy;
+    // Overlapping lights don't add up, the brightest one wins
+    var lit = 0.0;
+
+    for (var i = 0u; i < mask.light_count; i++) {
+        let light = mask.lights[i];
+        let to_pixel = pixel - light.position;
+        let distance = length(to_pixel);
+        if distance > light.range || light.intensity <= lit {
+            continue;
+        }
+        if distance > 0.001 && acos(clamp(dot(to_pixel / distance, light.direction), -1.0, 1.0)) > light.half_cone {
+            continue;
+        }
+
+        // Each caster in the way lets through what its opacity doesn't stop,
+        // so translucent casters lined up darken together
+        var transmitted = 1.0;
+        for (var e = 0u; e < mask.edge_count; e++) {
+            let edge = mask.edges[e];
+            if edge.light == i && segments_cross(pixel, light.position, edge.points.xy, edge.points.zw) {
+                transmitted *= 1.0 - edge.opacity;
+                if transmitted <= 0.0 {
+                    break;
+                }
+            }
+        }
+        lit = max(lit, light.intensity * transmitted);
+    }
+
+    return vec4<f32>(0.0, 0.0, 0.0, mask.darkness * (1.0 - lit));
+}
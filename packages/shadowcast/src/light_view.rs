@@ -0,0 +1,181 @@
+//! What a light sees, as data
+//!
+//! The mask shader only needs silhouette edges, but gameplay (stealth checks,
+//! AI) and tests want the lit area itself. `light_view` builds it for one
+//! light from plain outlines; the `LightViews` system param does the same for
+//! a light entity from the casters in the world.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::geometry::silhouette_edges;
+use crate::visibility::{compute_visibility_polygon, polygon_contains};
+use crate::{CasterOutlines, LightSource, LightState, ShadowCaster};
+
+/// The shadow thrown by one silhouette edge, out to the light's range
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowQuad {
+    /// Edge start, edge end, then the two far corners, in winding order
+    pub corners: [Vec2; 4],
+    /// Share of the light the caster stops
+    pub opacity: f32,
+}
+
+/// The area one light reaches and the shadows inside its cone
+#[derive(Debug, Clone)]
+pub struct LightView {
+    pub origin: Vec2,
+    pub direction: Vec2,
+    pub range: f32,
+    pub half_cone: f32,
+    /// Everything visible from the light within range, ignoring the cone;
+    /// only fully opaque casters block it
+    pub polygon: Vec<Vec2>,
+    pub shadows: Vec<ShadowQuad>,
+}
+
+impl LightView {
+    /// Whether `point` is within the cone and not behind an opaque caster
+    pub fn lights(&self, point: Vec2) -> bool {
+        let offset = point - self.origin;
+        if offset.length() > self.range {
+            return false;
+        }
+        if offset != Vec2::ZERO && self.direction.angle_to(offset).abs() > self.half_cone {
+            return false;
+        }
+        polygon_contains(&self.polygon, self.origin, point)
+    }
+
+    /// Whether `point` is inside the cone and range, blocked or not
+    fn in_cone(&self, point: Vec2) -> bool {
+        let offset = point - self.origin;
+        offset.length() <= self.range
+            && self.direction.angle_to(offset.normalize_or_zero()).abs() <= self.half_cone
+    }
+}
+
+/// Build the view of a light at `origin` facing `direction`
+///
+/// `casters` are the world-space outlines of each caster with its settings;
+/// casters that don't share a layer with the light or are clear are skipped.
+pub fn light_view<'a>(
+    origin: Vec2,
+    direction: Vec2,
+    light: &LightSource,
+    casters: impl IntoIterator<Item = (&'a [Vec<Vec2>], &'a ShadowCaster)>,
+) -> LightView {
+    let casters: Vec<_> = casters
+        .into_iter()
+        .filter(|(_, caster)| caster.blocks(light) && caster.opacity > 0.0)
+        .collect();
+
+    let opaque: Vec<Vec<Vec2>> = casters
+        .iter()
+        .filter(|(_, caster)| caster.opacity >= 1.0)
+        .flat_map(|(outlines, _)| outlines.iter().cloned())
+        .collect();
+
+    let mut view = LightView {
+        origin,
+        direction: direction.normalize_or(Vec2::X),
+        range: light.range,
+        half_cone: light.cone_angle / 2.0,
+        polygon: compute_visibility_polygon(origin, light.range, &opaque),
+        shadows: Vec::new(),
+    };
+
+    let far = |point: Vec2| point + (point - origin).normalize_or_zero() * light.range;
+    let mut shadows = Vec::new();
+    for (outlines, caster) in casters {
+        for outline in outlines.iter().filter(|outline| outline.iter().any(|&vertex| view.in_cone(vertex))) {
+            for (start, end) in silhouette_edges(origin, outline) {
+                shadows.push(ShadowQuad {
+                    corners: [start, end, far(end), far(start)],
+                    opacity: caster.opacity,
+                });
+            }
+        }
+    }
+    view.shadows = shadows;
+
+    view
+}
+
+/// Views of light entities, built from the casters currently in the world
+#[derive(SystemParam)]
+pub struct LightViews<'w, 's> {
+    lights: Query<'w, 's, (&'static GlobalTransform, &'static LightSource, &'static LightState)>,
+    casters: Query<'w, 's, (&'static CasterOutlines, &'static ShadowCaster)>,
+}
+
+impl LightViews<'_, '_> {
+    /// The view of one light, or `None` if the entity is not a light
+    pub fn get(&self, light: Entity) -> Option<LightView> {
+        let (transform, source, state) = self.lights.get(light).ok()?;
+        Some(light_view(
+            transform.translation().truncate(),
+            state.direction,
+            source,
+            self.casters.iter().map(|(outlines, caster)| (outlines.0.as_slice(), caster)),
+        ))
+    }
+
+    /// Whether any light shines on `point`
+    pub fn is_lit(&self, point: Vec2) -> bool {
+        self.lights.iter().any(|(transform, source, state)| {
+            let origin = transform.translation().truncate();
+            // Skip building views for lights that can't reach the point
+            origin.distance(point) <= source.range
+                && light_view(
+                    origin,
+                    state.direction,
+                    source,
+                    self.casters.iter().map(|(outlines, caster)| (outlines.0.as_slice(), caster)),
+                )
+                .lights(point)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wall(x: f32) -> Vec<Vec<Vec2>> {
+        vec![vec![
+            Vec2::new(x - 5.0, -20.0),
+            Vec2::new(x + 5.0, -20.0),
+            Vec2::new(x + 5.0, 20.0),
+            Vec2::new(x - 5.0, 20.0),
+        ]]
+    }
+
+    #[test]
+    fn test_wall_hides_point_behind_it() {
+        let light = LightSource::new(200.0, std::f32::consts::FRAC_PI_2);
+        let outlines = wall(50.0);
+        let caster = ShadowCaster::default();
+        let view = light_view(Vec2::ZERO, Vec2::X, &light, [(outlines.as_slice(), &caster)]);
+
+        assert!(view.lights(Vec2::new(30.0, 0.0)));
+        assert!(!view.lights(Vec2::new(100.0, 0.0)));
+        // Outside the cone, even with nothing in the way
+        assert!(!view.lights(Vec2::new(-30.0, 0.0)));
+        assert!(!view.shadows.is_empty());
+        assert!(view.shadows.iter().all(|quad| quad.opacity == 1.0));
+    }
+
+    #[test]
+    fn test_glass_casts_shadow_but_does_not_hide() {
+        let light = LightSource::new(200.0, std::f32::consts::FRAC_PI_2);
+        let outlines = wall(50.0);
+        let glass = ShadowCaster::default().with_opacity(0.4);
+        let view = light_view(Vec2::ZERO, Vec2::X, &light, [(outlines.as_slice(), &glass)]);
+
+        assert!(view.lights(Vec2::new(100.0, 0.0)));
+        assert!(view.shadows.iter().all(|quad| quad.opacity == 0.4));
+        let quad = &view.shadows[0];
+        assert!((quad.corners[2].distance(quad.corners[1]) - 200.0).abs() < 1e-3);
+    }
+}
@@ -1,36 +1,73 @@
-use bevy::prelude::*;
-use bevy_rapier2d::{parry::shape::ShapeType, prelude::*};
+use bevy::{input::mouse::MouseWheel, prelude::*};
+use bevy_rapier2d::prelude::*;
+use shadowcast::{AmbientDarkness, Flashlight, LightSource, ShadowCastPlugin, ShadowCaster};
+use std::env;
 use std::f32::consts::PI;
 
+mod stress;
+
+use stress::{StressConfig, StressPlugin};
+
+/// Cone angle change per scroll step (radians)
+const CONE_STEP: f32 = PI / 36.0;
+/// Range change per +/- press
+const RANGE_STEP: f32 = 50.0;
+const MIN_CONE: f32 = PI / 36.0;
+const MIN_RANGE: f32 = 50.0;
+const MAX_RANGE: f32 = 2000.0;
+/// Cursor movement per frame below this keeps the previous cone direction
+const MIN_AIM_MOVEMENT: f32 = 2.0;
+/// Camera pan speed at 1x zoom (units per second)
+const PAN_SPEED: f32 = 500.0;
+/// Zoom factor per Ctrl+scroll step
+const ZOOM_STEP: f32 = 1.1;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 4.0;
+/// Ambient darkness change per [ / ] press
+const DARKNESS_STEP: f32 = 0.1;
+
 fn main() {
-    App::new()
+    let args: Vec<String> = env::args().collect();
+
+    let mut app = App::new();
+    app
         .add_plugins(DefaultPlugins)
         // Initialize Rapier physics with 100 pixels per meter scaling
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+        // Darkness and shadows are drawn by a fullscreen shader
+        .add_plugins(ShadowCastPlugin)
         // Enable debug rendering to visualize colliders
         // .add_plugins(RapierDebugRenderPlugin::default())
-        .add_systems(Startup, setup)
-        // Run animation before shadow casting so transforms update first
-        .add_systems(Update, (animate_light, animate_objects, cast_shadows).chain())
-        .run();
+        .init_resource::<LightControl>()
+        // Shadows are cast in PostUpdate, after these moved everything
+        .add_systems(Update, (
+            toggle_light_control,
+            animate_light.run_if(|control: Res<LightControl>| !control.follow_cursor),
+            follow_cursor.run_if(|control: Res<LightControl>| control.follow_cursor),
+        ).chain())
+        .add_systems(Update, (animate_objects, patrol_guards, pan_and_zoom_camera, (adjust_light, adjust_darkness, update_light_readout).chain()));
+
+    // `--stress [casters] [lights]` swaps the demo scene for a timed stress scene
+    match StressConfig::from_args(&args) {
+        Some(config) => app.add_plugins(StressPlugin(config)),
+        None => app.add_systems(Startup, setup),
+    };
+
+    app.run();
 }
 
-/// Component marking an entity as a light source
+/// Orbit of the demo flashlight holder around the center
 #[derive(Component)]
-struct LightSource {
-    /// Maximum distance shadows can be cast
-    range: f32,
+struct LightOrbit {
     /// Orbital radius around center
     orbit_radius: f32,
     /// Speed of orbital motion (radians per second)
     orbit_speed: f32,
-    /// Light cone angle in radians (90 degrees = π/2)
-    cone_angle: f32,
 }
 
-/// Component marking an entity as something that casts shadows
+/// Orbit and spin of a demo shadow caster
 #[derive(Component)]
-struct ShadowCaster {
+struct CasterOrbit {
     /// Distance from center to orbit around
     orbit_radius: f32,
     /// Speed of orbital motion (radians per second)
@@ -41,13 +78,24 @@ struct ShadowCaster {
     spin_speed: f32,
 }
 
-/// Component marking shadow mesh entities for cleanup
+/// A guard pacing between two points, carrying a flashlight the way it walks
 #[derive(Component)]
-struct ShadowMesh;
+struct Guard {
+    from: Vec2,
+    to: Vec2,
+    /// Seconds for one leg of the walk
+    leg_time: f32,
+}
 
-/// Component marking the darkness/light mask mesh
+/// Whether the main light orbits on its own or is steered by the mouse
+#[derive(Resource, Default)]
+struct LightControl {
+    follow_cursor: bool,
+}
+
+/// On-screen text showing the current light parameters
 #[derive(Component)]
-struct LightMask;
+struct LightReadout;
 
 /// Initial scene setup - creates light source and shadow casting objects
 fn setup(
@@ -58,7 +106,19 @@ fn setup(
     // Create 2D camera
     commands.spawn(Camera2d::default());
 
-    // Light source orbiting inside the shapes
+    commands.spawn((
+        Text::default(),
+        TextFont { font_size: 16.0, ..default() },
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..default()
+        },
+        LightReadout,
+    ));
+
+    // Flashlight holder orbiting inside the shapes
     let mut light_mesh = Mesh::new(
         bevy::render::render_resource::PrimitiveTopology::TriangleList,
         bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
@@ -96,14 +156,44 @@ fn setup(
             Color::linear_rgb(1.0, 1.0, 0.0) // Bright yellow
         ))),
         Transform::from_xyz(70.0, 0.0, 2.0), // Start at right side of smaller inner orbit
-        LightSource {
-            range: 600.0,       // Shadow distance
+        Flashlight::new(600.0, PI / 2.0), // Shadow distance, 90 degree cone
+        LightOrbit {
             orbit_radius: 70.0,  // Smaller radius (was 100, now 70)
             orbit_speed: -0.5,   // Counter-clockwise (opposite to shapes)
-            cone_angle: PI / 2.0, // 90 degree cone
         },
     ));
 
+    // A guard walking up and down the left side with a narrow torch held out in front
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(8.0))),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::linear_rgb(0.9, 0.2, 0.2)))),
+        Transform::from_xyz(-350.0, -200.0, 2.0),
+        Flashlight::new(350.0, PI / 5.0).with_offset(Vec2::new(10.0, 0.0)),
+        Guard {
+            from: Vec2::new(-350.0, -200.0),
+            to: Vec2::new(-350.0, 200.0),
+            leg_time: 6.0,
+        },
+    ));
+
+    // A flickering torch and a sweeping alarm beacon in the corners
+    let lamp_mesh = meshes.add(Circle::new(6.0));
+    commands.spawn((
+        Mesh2d(lamp_mesh.clone()),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::linear_rgb(1.0, 0.5, 0.1)))),
+        Transform::from_xyz(-500.0, 260.0, 2.0),
+        LightSource::new(220.0, 2.0 * PI).with_intensity(0.8).with_flicker(0.35, 10.0),
+    ));
+    commands.spawn((
+        Mesh2d(lamp_mesh),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::linear_rgb(1.0, 0.1, 0.1)))),
+        Transform::from_xyz(500.0, -260.0, 2.0),
+        LightSource::new(500.0, PI / 6.0)
+            .with_direction(Vec2::new(-1.0, 0.5))
+            .with_pulse(0.6, 1.0)
+            .with_sweep(PI / 2.0, 4.0),
+    ));
+
     // Blue square - starts at 0 degrees (right side)
     let mut blue_square_mesh = Mesh::new(
         bevy::render::render_resource::PrimitiveTopology::TriangleList,
@@ -131,7 +221,8 @@ fn setup(
         Transform::from_xyz(160.0, 0.0, -0.1), // Below darkness layer
         RigidBody::Fixed,
         Collider::cuboid(50.0, 50.0),
-        ShadowCaster {
+        ShadowCaster::default(),
+        CasterOrbit {
             orbit_radius: 160.0,
             orbit_speed: 0.3,    // Slower orbital speed
             orbit_phase: 0.0,    // 0 degrees starting position
@@ -170,7 +261,8 @@ fn setup(
         ), // Below darkness layer
         RigidBody::Fixed,
         Collider::cuboid(40.0, 40.0),
-        ShadowCaster {
+        ShadowCaster::default(),
+        CasterOrbit {
             orbit_radius: 140.0,
             orbit_speed: 0.3,    // Same orbital speed
             orbit_phase: 120.0_f32.to_radians(), // 120 degrees (2π/3)
@@ -211,7 +303,8 @@ fn setup(
         ), // Below darkness layer
         RigidBody::Fixed,
         Collider::cuboid(30.0, 60.0),
-        ShadowCaster {
+        ShadowCaster::default(),
+        CasterOrbit {
             orbit_radius: 200.0,
             orbit_speed: 0.3,    // Same orbital speed
             orbit_phase: 240.0_f32.to_radians(), // 240 degrees (4π/3)
@@ -219,6 +312,17 @@ fn setup(
         },
     ));
 
+    // Two glass panes side by side: each lets some light through, both together less
+    for offset in [0.0, 40.0] {
+        commands.spawn((
+            Sprite::from_color(Color::srgba(0.6, 0.85, 1.0, 0.5), Vec2::new(16.0, 140.0)),
+            Transform::from_xyz(-380.0 - offset, 0.0, -0.1),
+            RigidBody::Fixed,
+            Collider::cuboid(8.0, 70.0),
+            ShadowCaster::default().with_opacity(0.4),
+        ));
+    }
+
     // Small circles orbiting outside the shapes in reverse direction
     let circle_count = 8;
     let circle_radius = 280.0; // Outside the largest shape orbit
@@ -269,7 +373,8 @@ fn setup(
             Transform::from_xyz(x, y, -0.1), // Below darkness layer
             RigidBody::Fixed,
             Collider::ball(circle_size / 2.0), // Use ball collider for proper circle physics
-            ShadowCaster {
+            ShadowCaster::default(),
+            CasterOrbit {
                 orbit_radius: circle_radius,
                 orbit_speed: -0.3,   // Reverse direction from main shapes
                 orbit_phase: angle,  // Starting position
@@ -279,30 +384,98 @@ fn setup(
     }
 }
 
-/// Animates the light source - makes it orbit counter-clockwise inside the shapes
+/// Animates the flashlight holder - makes it orbit counter-clockwise inside the shapes
 fn animate_light(
     time: Res<Time>,
-    mut light_query: Query<(&mut Transform, &LightSource)>,
+    mut light_query: Query<(&mut Transform, &LightOrbit)>,
 ) {
-    for (mut transform, light) in light_query.iter_mut() {
+    for (mut transform, orbit) in light_query.iter_mut() {
         let elapsed = time.elapsed_secs();
 
         // Orbit around the center point (0,0)
-        let orbit_angle = elapsed * light.orbit_speed;
-        let x = orbit_angle.cos() * light.orbit_radius;
-        let y = orbit_angle.sin() * light.orbit_radius;
+        let orbit_angle = elapsed * orbit.orbit_speed;
+        let x = orbit_angle.cos() * orbit.orbit_radius;
+        let y = orbit_angle.sin() * orbit.orbit_radius;
 
         transform.translation.x = x;
         transform.translation.y = y;
         // Keep Z at 2 for proper layering above everything else
         transform.translation.z = 2.0;
+
+        // Face the center of the orbits (0, 0); the flashlight points where the holder faces
+        transform.rotation = facing(-Vec2::new(x, y));
+    }
+}
+
+/// Rotation that turns local +X, the way a flashlight points, towards `direction`
+fn facing(direction: Vec2) -> Quat {
+    Quat::from_rotation_z(direction.to_angle())
+}
+
+/// Walk guards back and forth, turning to face the way they go
+fn patrol_guards(time: Res<Time>, mut guards: Query<(&mut Transform, &Guard)>) {
+    for (mut transform, guard) in guards.iter_mut() {
+        let legs = time.elapsed_secs() / guard.leg_time;
+        let returning = legs as u32 % 2 == 1;
+        let progress = if returning { 1.0 - legs.fract() } else { legs.fract() };
+
+        transform.translation = guard.from.lerp(guard.to, progress).extend(transform.translation.z);
+        let heading = if returning { guard.from - guard.to } else { guard.to - guard.from };
+        transform.rotation = facing(heading);
+    }
+}
+
+/// M switches the main light between its orbit and following the cursor
+fn toggle_light_control(keyboard: Res<ButtonInput<KeyCode>>, mut control: ResMut<LightControl>) {
+    if keyboard.just_pressed(KeyCode::KeyM) {
+        control.follow_cursor = !control.follow_cursor;
+    }
+}
+
+/// Move the main light to the cursor and point it the way the cursor moves,
+/// or the way the held arrow keys point
+fn follow_cursor(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut light_query: Query<&mut Transform, With<LightOrbit>>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.single(), cameras.single()) else {
+        return;
+    };
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    else {
+        return;
+    };
+
+    let held = [
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowUp, Vec2::Y),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+    ]
+    .into_iter()
+    .filter(|(key, _)| keyboard.pressed(*key))
+    .map(|(_, direction)| direction)
+    .sum::<Vec2>();
+
+    for mut transform in light_query.iter_mut() {
+        let movement = cursor - transform.translation.truncate();
+        if held != Vec2::ZERO {
+            transform.rotation = facing(held);
+        } else if movement.length() >= MIN_AIM_MOVEMENT {
+            transform.rotation = facing(movement);
+        }
+        transform.translation = cursor.extend(2.0);
     }
 }
 
 /// Animates shadow casting objects - makes them orbit around center and spin
 fn animate_objects(
     time: Res<Time>,
-    mut caster_query: Query<(&mut Transform, &ShadowCaster)>,
+    mut caster_query: Query<(&mut Transform, &CasterOrbit)>,
 ) {
     for (mut transform, caster) in caster_query.iter_mut() {
         let elapsed = time.elapsed_secs();
@@ -323,305 +496,104 @@ fn animate_objects(
     }
 }
 
-/// Creates a viewport darkness mesh with a cone-shaped hole cut out for light
-fn create_viewport_with_light_cone(
-    light_pos: Vec2,
-    light_direction: Vec2,
-    cone_angle: f32,
-    range: f32,
-) -> Mesh {
-    let half_cone_angle = cone_angle / 2.0; // 45 degrees for 90° cone
-    let darkness_angle = PI - half_cone_angle; // 135 degrees for each darkness triangle
-    let triangle_radius = 3000.0; // Much larger to cover entire viewport
-
-    let base_angle = light_direction.to_angle();
-
-    // Calculate the cone edges
-    let left_cone_edge = base_angle - half_cone_angle;
-    let right_cone_edge = base_angle + half_cone_angle;
-
-    let mut positions = Vec::new();
-    let mut indices = Vec::new();
-
-    // Left darkness triangle (135° starting from left cone edge, going counter-clockwise)
-    let left_start_angle = left_cone_edge;
-    let left_end_angle = left_start_angle - darkness_angle;
-
-    positions.push([light_pos.x, light_pos.y, 0.0]); // 0: light center
-    positions.push([
-        light_pos.x + left_start_angle.cos() * triangle_radius,
-        light_pos.y + left_start_angle.sin() * triangle_radius,
-        0.0
-    ]); // 1: left cone edge
-    positions.push([
-        light_pos.x + left_end_angle.cos() * triangle_radius,
-        light_pos.y + left_end_angle.sin() * triangle_radius,
-        0.0
-    ]); // 2: left darkness end
-
-    indices.extend_from_slice(&[0, 1, 2]);
-
-    // Right darkness triangle (135° starting from right cone edge, going clockwise)
-    let right_start_angle = right_cone_edge;
-    let right_end_angle = right_start_angle + darkness_angle;
-
-    positions.push([
-        light_pos.x + right_start_angle.cos() * triangle_radius,
-        light_pos.y + right_start_angle.sin() * triangle_radius,
-        0.0
-    ]); // 3: right cone edge
-    positions.push([
-        light_pos.x + right_end_angle.cos() * triangle_radius,
-        light_pos.y + right_end_angle.sin() * triangle_radius,
-        0.0
-    ]); // 4: right darkness end
-
-    indices.extend_from_slice(&[0, 3, 4]);
-
-    let mut mesh = Mesh::new(
-        bevy::render::render_resource::PrimitiveTopology::TriangleList,
-        bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
-    );
-
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-    mesh.insert_indices(bevy::render::mesh::Indices::U32(indices));
-    mesh
+fn ctrl_held(keyboard: &ButtonInput<KeyCode>) -> bool {
+    keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
 }
 
-/// Main shadow casting system with proper light masking
-fn cast_shadows(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    light_query: Query<(&Transform, &LightSource)>,
-    caster_query: Query<(&Transform, &Collider), With<ShadowCaster>>,
-    shadow_query: Query<Entity, With<ShadowMesh>>,
-    mask_query: Query<Entity, With<LightMask>>,
+/// WASD pans the camera, Ctrl+scroll zooms it
+fn pan_and_zoom_camera(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut scroll_events: EventReader<MouseWheel>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
 ) {
-    // Clear all existing shadow meshes and light masks from previous frame
-    for entity in shadow_query.iter() {
-        commands.entity(entity).despawn();
-    }
-    for entity in mask_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Process each light source
-    for (light_transform, light) in light_query.iter() {
-        let light_pos = light_transform.translation.truncate();
-
-        // Point light toward center of orbits (0, 0)
-        let light_direction = (Vec2::ZERO - light_pos).normalize_or_zero();
-
-        // Create full viewport darkness
-        let darkness_mesh = create_viewport_with_light_cone(
-            light_pos,
-            light_direction,
-            light.cone_angle,
-            light.range,
-        );
-
-        commands.spawn((
-            Mesh2d(meshes.add(darkness_mesh)),
-            MeshMaterial2d(materials.add(ColorMaterial::from_color(
-                Color::linear_rgba(0.0, 0.0, 0.0, 1.0) // Completely opaque black
-            ))),
-            Transform::from_xyz(0.0, 0.0, 0.5), // Above background shapes
-            LightMask,
-        ));
-
-        // Cast shadows for objects that have any part in the light cone
-        for (caster_transform, collider) in caster_query.iter() {
-            // Get the world-space vertices of this collider
-            let vertices = get_collider_vertices(collider, caster_transform);
-
-            if vertices.is_empty() {
-                continue;
-            }
-
-            // Check if any vertex is within the light cone
-            let mut any_vertex_in_cone = false;
-            for vertex in &vertices {
-                let to_vertex = *vertex - light_pos;
-                let distance = to_vertex.length();
-
-                if distance <= light.range {
-                    let to_vertex_normalized = to_vertex.normalize_or_zero();
-                    let angle_to_vertex = light_direction.angle_to(to_vertex_normalized).abs();
-
-                    if angle_to_vertex <= light.cone_angle / 2.0 {
-                        any_vertex_in_cone = true;
-                        break;
-                    }
-                }
-            }
-
-            // If any vertex is in the cone, cast shadows for this object
-            if any_vertex_in_cone {
-                // Calculate shadow geometry for all edges facing away from light
-                let shadow_vertices = cast_directional_shadow_from_vertices(
-                    light_pos, &vertices, light.range
-                );
-
-                // Only create mesh if we have enough vertices for triangles
-                if shadow_vertices.len() >= 3 {
-                    let mesh = create_shadow_mesh(shadow_vertices);
-                    let mesh_handle = meshes.add(mesh);
-
-                    // Spawn shadow mesh entity
-                    commands.spawn((
-                        Mesh2d(mesh_handle),
-                        MeshMaterial2d(materials.add(ColorMaterial::from_color(
-                            Color::linear_rgba(0.0, 0.0, 0.0, 1.0) // Completely opaque black like darkness
-                        ))),
-                        Transform::from_xyz(0.0, 0.0, 1.0), // Above light cone
-                        ShadowMesh,
-                    ));
-                }
-            }
+    let scroll: f32 = scroll_events.read().map(|event| event.y.signum()).sum();
+    let pan = [
+        (KeyCode::KeyD, Vec2::X),
+        (KeyCode::KeyA, Vec2::NEG_X),
+        (KeyCode::KeyW, Vec2::Y),
+        (KeyCode::KeyS, Vec2::NEG_Y),
+    ]
+    .into_iter()
+    .filter(|(key, _)| keyboard.pressed(*key))
+    .map(|(_, direction)| direction)
+    .sum::<Vec2>();
+
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        let Projection::Orthographic(ortho) = projection.as_mut() else {
+            continue;
+        };
+        if scroll != 0.0 && ctrl_held(&keyboard) {
+            // Scrolling up zooms in
+            ortho.scale = (ortho.scale * ZOOM_STEP.powf(-scroll)).clamp(MIN_ZOOM, MAX_ZOOM);
+        }
+        if pan != Vec2::ZERO {
+            let step = pan.normalize() * PAN_SPEED * ortho.scale * time.delta_secs();
+            transform.translation += step.extend(0.0);
         }
     }
 }
 
-/// Enhanced shadow calculation that properly handles self-shadowing
-fn cast_directional_shadow_from_vertices(
-    light_pos: Vec2,
-    vertices: &[Vec2],
-    range: f32
-) -> Vec<Vec2> {
-    if vertices.len() < 3 {
-        return vec![];
+/// Scroll to widen or narrow the cone, +/- to change the range
+fn adjust_light(
+    mut scroll_events: EventReader<MouseWheel>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut light_query: Query<&mut Flashlight, With<LightOrbit>>,
+) {
+    let scroll: f32 = scroll_events.read().map(|event| event.y.signum()).sum();
+    // Ctrl+scroll belongs to the camera zoom
+    let scroll = if ctrl_held(&keyboard) { 0.0 } else { scroll };
+    let mut range_change = 0.0;
+    if keyboard.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        range_change += RANGE_STEP;
+    }
+    if keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        range_change -= RANGE_STEP;
     }
 
-    let mut shadow_vertices = Vec::new();
-    let shadow_inset = 5.0; // Move shadow start point inward by this amount
-
-    // Process each edge of the polygon
-    for i in 0..vertices.len() {
-        let v1 = vertices[i];
-        let v2 = vertices[(i + 1) % vertices.len()];
-
-        // Calculate edge vector and normal
-        let edge_vec = v2 - v1;
-        let edge_normal = Vec2::new(-edge_vec.y, edge_vec.x).normalize(); // Perpendicular to edge, pointing outward
-
-        // Vector from edge midpoint to light
-        let edge_midpoint = (v1 + v2) * 0.5;
-        let to_light = (light_pos - edge_midpoint).normalize_or_zero();
-
-        // Check if this edge faces away from the light (dot product < 0)
-        let faces_away = edge_normal.dot(to_light) < 0.0;
-
-        if faces_away {
-            // This edge is a "silhouette edge" - it faces away from light
-            // Calculate directions from light to each vertex of the edge
-            let dir1 = (v1 - light_pos).normalize_or_zero();
-            let dir2 = (v2 - light_pos).normalize_or_zero();
-
-            // Move the shadow start points slightly inward along the light direction
-            let shadow_start1 = v1 + dir1 * shadow_inset;
-            let shadow_start2 = v2 + dir2 * shadow_inset;
-
-            // Project vertices far away to create shadow
-            let far1 = v1 + dir1 * range;
-            let far2 = v2 + dir2 * range;
-
-            // Create shadow quad: inset edge + far edge
-            // Ensure proper winding order for consistent rendering
-            shadow_vertices.extend_from_slice(&[shadow_start1, shadow_start2, far2, far1]);
-        }
+    if scroll == 0.0 && range_change == 0.0 {
+        return;
     }
 
-    shadow_vertices
+    for mut light in light_query.iter_mut() {
+        light.cone_angle = (light.cone_angle + scroll * CONE_STEP).clamp(MIN_CONE, 2.0 * PI);
+        light.range = (light.range + range_change).clamp(MIN_RANGE, MAX_RANGE);
+    }
 }
 
-/// Extracts world-space vertices from a Rapier collider
-/// Supports both cuboid and ball (circle) shapes
-fn get_collider_vertices(collider: &Collider, transform: &Transform) -> Vec<Vec2> {
-    match collider.raw.shape_type() {
-        ShapeType::Cuboid => {
-            // Get the cuboid shape and its dimensions
-            let cuboid = collider.raw.as_cuboid().unwrap();
-            let half_extents = cuboid.half_extents;
-
-            // Define vertices in local space (counter-clockwise from bottom-left)
-            let local_vertices = vec![
-                Vec2::new(-half_extents.x, -half_extents.y), // Bottom-left
-                Vec2::new(half_extents.x, -half_extents.y),  // Bottom-right
-                Vec2::new(half_extents.x, half_extents.y),   // Top-right
-                Vec2::new(-half_extents.x, half_extents.y),  // Top-left
-            ];
-
-            // Transform vertices from local space to world space
-            local_vertices.iter().map(|&v| {
-                // Apply rotation
-                let rotated = transform.rotation * Vec3::new(v.x, v.y, 0.0);
-                // Apply translation
-                Vec2::new(rotated.x, rotated.y) + transform.translation.truncate()
-            }).collect()
-        }
-        ShapeType::Ball => {
-            // For circles, create an approximation using a polygon
-            let ball = collider.raw.as_ball().unwrap();
-            let radius = ball.radius;
-            let segments = 16; // 16 sides for smooth circle approximation
-
-            let mut vertices = Vec::new();
-            for i in 0..segments {
-                let angle = (i as f32 / segments as f32) * 2.0 * PI;
-                let local_vertex = Vec2::new(
-                    angle.cos() * radius,
-                    angle.sin() * radius
-                );
-
-                // Transform from local space to world space
-                let rotated = transform.rotation * Vec3::new(local_vertex.x, local_vertex.y, 0.0);
-                let world_vertex = Vec2::new(rotated.x, rotated.y) + transform.translation.truncate();
-                vertices.push(world_vertex);
-            }
-            vertices
-        }
-        _ => {
-            // TODO: Add support for other shape types (polygons, etc.)
-            vec![]
-        }
+/// [ and ] to lighten or darken the unlit scene, from daylight to pitch black
+fn adjust_darkness(keyboard: Res<ButtonInput<KeyCode>>, mut darkness: ResMut<AmbientDarkness>) {
+    let mut change = 0.0;
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        change -= DARKNESS_STEP;
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        change += DARKNESS_STEP;
+    }
+    if change != 0.0 {
+        darkness.0 = (darkness.0 + change).clamp(0.0, 1.0);
     }
 }
 
-/// Creates a Bevy mesh from shadow geometry vertices
-/// Converts quads to triangles for GPU rendering
-fn create_shadow_mesh(vertices: Vec<Vec2>) -> Mesh {
-    // Create mesh with triangle list topology for GPU rendering
-    let mut mesh = Mesh::new(
-        bevy::render::render_resource::PrimitiveTopology::TriangleList,
-        bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD,
+fn update_light_readout(
+    control: Res<LightControl>,
+    darkness: Res<AmbientDarkness>,
+    light_query: Query<&Flashlight, With<LightOrbit>>,
+    mut readout_query: Query<&mut Text, With<LightReadout>>,
+) {
+    let (Some(light), Ok(mut text)) = (light_query.iter().next(), readout_query.single_mut()) else {
+        return;
+    };
+
+    let readout = format!(
+        "Cone: {:.0}° (scroll)\nRange: {:.0} (+/-)\nDarkness: {:.0}% ([ / ])\nControl: {} (M)\nCamera: WASD pan, Ctrl+scroll zoom",
+        light.cone_angle.to_degrees(),
+        light.range,
+        darkness.0 * 100.0,
+        if control.follow_cursor { "cursor, arrows aim" } else { "orbit" },
     );
-
-    let mut positions = Vec::new();
-    let mut indices = Vec::new();
-
-    // Convert each quad (4 vertices) into two triangles (6 vertices)
-    for quad in vertices.chunks(4) {
-        if quad.len() == 4 {
-            let base_idx = positions.len() as u32;
-
-            // Add all 4 vertices of the quad to positions
-            for vertex in quad {
-                positions.push([vertex.x, vertex.y, 0.0]);
-            }
-
-            // Create two triangles from the quad
-            // Triangle 1: vertices 0, 1, 2
-            // Triangle 2: vertices 0, 2, 3
-            indices.extend_from_slice(&[
-                base_idx,     base_idx + 1, base_idx + 2, // First triangle
-                base_idx,     base_idx + 2, base_idx + 3, // Second triangle
-            ]);
-        }
+    // Avoid marking the text changed, and re-laid out, every frame
+    if **text != readout {
+        **text = readout;
     }
-
-    // Set mesh attributes required by Bevy's renderer
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-    mesh.insert_indices(bevy::render::mesh::Indices::U32(indices));
-    mesh
 }
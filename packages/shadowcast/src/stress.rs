@@ -0,0 +1,182 @@
+//! Stress test mode for measuring the shadow pipeline
+//!
+//! `cargo run -- --stress <casters> <lights>` replaces the demo scene with
+//! randomly placed casters that orbit and spin, and lights that sweep, then
+//! prints how long the `CastShadows` systems took every `REPORT_FRAMES` frames.
+
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use shadowcast::{CastShadows, LightSource, ShadowCaster, MAX_LIGHTS};
+
+use crate::CasterOrbit;
+
+/// Frames between printed reports
+const REPORT_FRAMES: usize = 120;
+/// Half the side of the square everything is scattered over
+const ARENA_HALF: f32 = 600.0;
+const DEFAULT_CASTERS: usize = 200;
+const DEFAULT_LIGHTS: usize = 4;
+
+/// How much to spawn, read from `--stress [casters] [lights]`
+#[derive(Debug, Clone, Copy)]
+pub struct StressConfig {
+    pub casters: usize,
+    pub lights: usize,
+}
+
+impl StressConfig {
+    /// The config if `--stress` is among the arguments; missing counts use the defaults
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let position = args.iter().position(|arg| arg == "--stress")?;
+        let mut counts = args[position + 1..].iter().map_while(|arg| arg.parse::<usize>().ok());
+        Some(Self {
+            casters: counts.next().unwrap_or(DEFAULT_CASTERS),
+            lights: counts.next().unwrap_or(DEFAULT_LIGHTS),
+        })
+    }
+}
+
+/// Spawns the stress scene and reports shadow timings
+pub struct StressPlugin(pub StressConfig);
+
+impl Plugin for StressPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StressSettings(self.0))
+            .init_resource::<ShadowTimings>()
+            .add_systems(Startup, spawn_stress_scene)
+            .add_systems(PostUpdate, (
+                // Start once transforms are in, so propagation isn't counted as shadow work
+                start_shadow_timer.after(TransformSystem::TransformPropagate).before(CastShadows),
+                stop_shadow_timer.after(CastShadows),
+            ))
+            .add_systems(Last, report_timings);
+    }
+}
+
+#[derive(Resource)]
+struct StressSettings(StressConfig);
+
+/// Shadow pass and frame durations since the last report
+#[derive(Resource, Default)]
+struct ShadowTimings {
+    started: Option<Instant>,
+    shadow: Vec<Duration>,
+    frame: Vec<Duration>,
+}
+
+fn spawn_stress_scene(mut commands: Commands, settings: Res<StressSettings>) {
+    let config = settings.0;
+    let mut rng = rand::rng();
+
+    commands.spawn(Camera2d);
+
+    for _ in 0..config.casters {
+        let size = Vec2::new(rng.random_range(8.0..40.0), rng.random_range(8.0..40.0));
+        let orbit_radius = rng.random_range(20.0..ARENA_HALF);
+        let orbit_phase = rng.random_range(0.0..2.0 * PI);
+        let collider = if rng.random_bool(0.5) {
+            Collider::cuboid(size.x / 2.0, size.y / 2.0)
+        } else {
+            Collider::ball(size.x / 2.0)
+        };
+
+        commands.spawn((
+            Sprite::from_color(Color::srgb(0.5, 0.5, 0.6), size),
+            Transform::from_xyz(orbit_phase.cos() * orbit_radius, orbit_phase.sin() * orbit_radius, -0.1),
+            RigidBody::Fixed,
+            collider,
+            ShadowCaster::default(),
+            CasterOrbit {
+                orbit_radius,
+                orbit_speed: rng.random_range(-0.5..0.5),
+                orbit_phase,
+                spin_speed: rng.random_range(-2.0..2.0),
+            },
+        ));
+    }
+
+    for _ in 0..config.lights {
+        let position = Vec2::new(
+            rng.random_range(-ARENA_HALF..ARENA_HALF),
+            rng.random_range(-ARENA_HALF..ARENA_HALF),
+        );
+        commands.spawn((
+            Transform::from_translation(position.extend(2.0)),
+            LightSource::new(rng.random_range(300.0..800.0), rng.random_range(PI / 4.0..2.0 * PI))
+                .with_direction(Vec2::from_angle(rng.random_range(0.0..2.0 * PI)))
+                .with_sweep(PI / 2.0, rng.random_range(2.0..6.0)),
+        ));
+    }
+
+    println!(
+        "Stress test: {} casters, {} lights{}",
+        config.casters,
+        config.lights,
+        if config.lights > MAX_LIGHTS { format!(" (only the first {} are shaded)", MAX_LIGHTS) } else { String::new() },
+    );
+}
+
+fn start_shadow_timer(mut timings: ResMut<ShadowTimings>) {
+    timings.started = Some(Instant::now());
+}
+
+fn stop_shadow_timer(mut timings: ResMut<ShadowTimings>) {
+    if let Some(started) = timings.started.take() {
+        timings.shadow.push(started.elapsed());
+    }
+}
+
+/// Print min/mean/p95/max of the shadow pass and the mean frame time
+fn report_timings(time: Res<Time>, mut timings: ResMut<ShadowTimings>) {
+    timings.frame.push(time.delta());
+    if timings.shadow.len() < REPORT_FRAMES {
+        return;
+    }
+
+    let mut shadow = std::mem::take(&mut timings.shadow);
+    let frames = std::mem::take(&mut timings.frame);
+    shadow.sort();
+
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    let mean = shadow.iter().sum::<Duration>() / shadow.len() as u32;
+    let p95 = shadow[(shadow.len() * 95 / 100).min(shadow.len() - 1)];
+    let frame_mean = frames.iter().sum::<Duration>() / frames.len().max(1) as u32;
+
+    println!(
+        "CastShadows over {} frames: min {:.3} ms, mean {:.3} ms, p95 {:.3} ms, max {:.3} ms | frame mean {:.2} ms",
+        shadow.len(),
+        ms(shadow[0]),
+        ms(mean),
+        ms(p95),
+        ms(shadow[shadow.len() - 1]),
+        ms(frame_mean),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_stress_flag_reads_counts() {
+        let config = StressConfig::from_args(&args(&["shadowcast", "--stress", "500", "6"])).unwrap();
+        assert_eq!((config.casters, config.lights), (500, 6));
+        assert!(StressConfig::from_args(&args(&["shadowcast"])).is_none());
+    }
+
+    #[test]
+    fn test_missing_counts_use_defaults() {
+        let config = StressConfig::from_args(&args(&["shadowcast", "--stress", "50"])).unwrap();
+        assert_eq!((config.casters, config.lights), (50, DEFAULT_LIGHTS));
+        let config = StressConfig::from_args(&args(&["shadowcast", "--stress", "--other"])).unwrap();
+        assert_eq!((config.casters, config.lights), (DEFAULT_CASTERS, DEFAULT_LIGHTS));
+    }
+}
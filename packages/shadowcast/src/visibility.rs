@@ -0,0 +1,212 @@
+//! Visibility polygons
+//!
+//! `compute_visibility_polygon` answers what can be seen from a point, using
+//! the same kind of outlines the light mask shades. Fog of war, AI sight
+//! checks and the mask can all ask it, so what an enemy sees, what the player
+//! has revealed and what is lit never disagree.
+//!
+//! The polygon comes from casting a ray at every occluder corner (and just to
+//! either side of it, so the ray can slip past), plus a ring of rays tracing
+//! the edge of the range, keeping the nearest hit of each.
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+/// Angle either side of a corner to cast the rays that slip past it
+const CORNER_EPSILON: f32 = 1.0e-4;
+/// Rays tracing the edge of the range where nothing blocks
+const RANGE_SEGMENTS: usize = 64;
+/// Angular buckets segments are sorted into, so a ray only tests the few it can hit
+const ANGLE_BINS: usize = 360;
+
+/// Outline of everything visible from `origin` within `range`, sorted by angle
+///
+/// Each occluder is a closed outline; a two-point outline is a single wall
+/// segment. The result is star-shaped around `origin`, so it can be queried
+/// with `reach_along` and `polygon_contains`.
+pub fn compute_visibility_polygon(origin: Vec2, range: f32, occluders: &[Vec<Vec2>]) -> Vec<Vec2> {
+    if range <= 0.0 {
+        return Vec::new();
+    }
+
+    // Nearest segments first, so a ray can stop once nothing closer is left
+    let mut segments: Vec<(f32, Vec2, Vec2)> = occluders
+        .iter()
+        .flat_map(|outline| outline_segments(outline))
+        .map(|(a, b)| (distance_to_segment(origin, a, b), a, b))
+        .filter(|&(near, _, _)| near < range)
+        .collect();
+    segments.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    let bin_width = TAU / ANGLE_BINS as f32;
+    let bin_of = |angle: f32| (angle.rem_euclid(TAU) / bin_width) as usize % ANGLE_BINS;
+    let mut bins = vec![Vec::new(); ANGLE_BINS];
+    for (index, &(_, a, b)) in segments.iter().enumerate() {
+        let (start, span) = angular_span(origin, a, b);
+        let start = start - CORNER_EPSILON;
+        let count = ((start + span + 2.0 * CORNER_EPSILON) / bin_width).floor() - (start / bin_width).floor();
+        let first = bin_of(start);
+        for step in 0..=(count as usize).min(ANGLE_BINS - 1) {
+            bins[(first + step) % ANGLE_BINS].push(index);
+        }
+    }
+
+    let mut angles: Vec<f32> = (0..RANGE_SEGMENTS)
+        .map(|i| i as f32 / RANGE_SEGMENTS as f32 * TAU)
+        .collect();
+    for &(_, a, b) in &segments {
+        for corner in [a, b] {
+            let offset = corner - origin;
+            if offset.length_squared() > range * range {
+                continue;
+            }
+            let angle = offset.to_angle();
+            angles.extend([angle - CORNER_EPSILON, angle, angle + CORNER_EPSILON].map(|a| a.rem_euclid(TAU)));
+        }
+    }
+    angles.sort_by(f32::total_cmp);
+    angles.dedup();
+
+    angles
+        .into_iter()
+        .map(|angle| {
+            let direction = Vec2::from_angle(angle);
+            let mut distance = range;
+            for &index in &bins[bin_of(angle)] {
+                let (near, a, b) = segments[index];
+                if near >= distance {
+                    break;
+                }
+                if let Some(hit) = ray_segment_distance(origin, direction, a, b) {
+                    distance = distance.min(hit);
+                }
+            }
+            origin + direction * distance
+        })
+        .collect()
+}
+
+/// How far the polygon reaches from `origin` in `direction`
+pub fn reach_along(polygon: &[Vec2], origin: Vec2, direction: Vec2) -> f32 {
+    let Some(&last) = polygon.last() else {
+        return 0.0;
+    };
+
+    let angle = direction.to_angle().rem_euclid(TAU);
+    let after = polygon.partition_point(|point| (*point - origin).to_angle().rem_euclid(TAU) < angle);
+    let next = polygon[after % polygon.len()];
+    let previous = if after == 0 { last } else { polygon[after - 1] };
+
+    ray_segment_distance(origin, direction.normalize_or_zero(), previous, next)
+        .unwrap_or_else(|| previous.distance(origin).min(next.distance(origin)))
+}
+
+/// Whether `point` lies inside a polygon built around `origin`
+pub fn polygon_contains(polygon: &[Vec2], origin: Vec2, point: Vec2) -> bool {
+    let offset = point - origin;
+    offset == Vec2::ZERO || offset.length() <= reach_along(polygon, origin, offset)
+}
+
+/// Whether `target` can be seen from `origin`, without building the whole polygon
+///
+/// Gives the same answer as `polygon_contains` on a polygon reaching `target`,
+/// for sight checks that only ask about one point.
+pub fn is_visible(origin: Vec2, target: Vec2, occluders: &[Vec<Vec2>]) -> bool {
+    let offset = target - origin;
+    let distance = offset.length();
+    if distance == 0.0 {
+        return true;
+    }
+
+    let direction = offset / distance;
+    occluders
+        .iter()
+        .flat_map(|outline| outline_segments(outline))
+        .all(|(a, b)| ray_segment_distance(origin, direction, a, b).is_none_or(|hit| hit >= distance))
+}
+
+/// Edges of a closed outline, or the segment itself for a two-point outline
+fn outline_segments(outline: &[Vec2]) -> Vec<(Vec2, Vec2)> {
+    match outline.len() {
+        0 | 1 => Vec::new(),
+        2 => vec![(outline[0], outline[1])],
+        len => (0..len).map(|i| (outline[i], outline[(i + 1) % len])).collect(),
+    }
+}
+
+/// Start angle and width of the arc a segment covers, seen from `origin`
+fn angular_span(origin: Vec2, a: Vec2, b: Vec2) -> (f32, f32) {
+    if distance_to_segment(origin, a, b) < CORNER_EPSILON {
+        return (0.0, TAU);
+    }
+    let to_a = a - origin;
+    let delta = to_a.angle_to(b - origin);
+    let start = if delta >= 0.0 { to_a.to_angle() } else { to_a.to_angle() + delta };
+    (start.rem_euclid(TAU), delta.abs())
+}
+
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let edge = b - a;
+    let t = ((point - a).dot(edge) / edge.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    point.distance(a + edge * t)
+}
+
+/// Distance along the ray to where it crosses the segment, if it does
+fn ray_segment_distance(origin: Vec2, direction: Vec2, a: Vec2, b: Vec2) -> Option<f32> {
+    let edge = b - a;
+    let denominator = direction.perp_dot(edge);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let to_a = a - origin;
+    let distance = to_a.perp_dot(edge) / denominator;
+    let along_edge = to_a.perp_dot(direction) / denominator;
+    (distance >= 0.0 && (0.0..=1.0).contains(&along_edge)).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(center: Vec2, half: f32) -> Vec<Vec2> {
+        vec![
+            center + Vec2::new(-half, -half),
+            center + Vec2::new(half, -half),
+            center + Vec2::new(half, half),
+            center + Vec2::new(-half, half),
+        ]
+    }
+
+    #[test]
+    fn test_open_space_reaches_full_range() {
+        let polygon = compute_visibility_polygon(Vec2::ZERO, 50.0, &[]);
+        assert_eq!(polygon.len(), RANGE_SEGMENTS);
+        assert!(polygon.iter().all(|point| (point.length() - 50.0).abs() < 1e-3));
+        assert!(polygon_contains(&polygon, Vec2::ZERO, Vec2::new(30.0, -30.0)));
+        assert!(!polygon_contains(&polygon, Vec2::ZERO, Vec2::new(60.0, 0.0)));
+    }
+
+    #[test]
+    fn test_occluder_hides_what_is_behind_it() {
+        let origin = Vec2::new(5.0, 5.0);
+        let wall = square(origin + Vec2::new(20.0, 0.0), 5.0);
+        let polygon = compute_visibility_polygon(origin, 100.0, &[wall, vec![Vec2::new(0.0, 40.0), Vec2::new(10.0, 40.0)]]);
+
+        assert!((reach_along(&polygon, origin, Vec2::X) - 15.0).abs() < 1e-3);
+        assert!(!polygon_contains(&polygon, origin, origin + Vec2::new(40.0, 0.0)));
+        assert!(polygon_contains(&polygon, origin, origin + Vec2::new(40.0, 15.0)));
+        assert!((reach_along(&polygon, origin, Vec2::Y) - 35.0).abs() < 1e-3);
+        assert!(polygon_contains(&polygon, origin, origin + Vec2::new(-20.0, 60.0)));
+    }
+
+    #[test]
+    fn test_single_point_checks_match_the_polygon() {
+        let origin = Vec2::new(5.0, 5.0);
+        let occluders = [square(origin + Vec2::new(20.0, 0.0), 5.0), vec![Vec2::new(0.0, 40.0), Vec2::new(10.0, 40.0)]];
+        let polygon = compute_visibility_polygon(origin, 100.0, &occluders);
+
+        for target in [Vec2::new(45.0, 5.0), Vec2::new(45.0, 20.0), Vec2::new(5.0, 60.0), Vec2::new(-15.0, 65.0), Vec2::new(22.0, 5.0)] {
+            assert_eq!(is_visible(origin, target, &occluders), polygon_contains(&polygon, origin, target), "{target}");
+        }
+    }
+}
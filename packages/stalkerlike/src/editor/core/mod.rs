@@ -13,5 +13,5 @@ pub mod types;
 pub use plugin::CorePlugin;
 
 // Re-export commonly used types for other modules
-pub use types::{EditorEntity, PlayerSpawn, GlbModel, RigidBodyType, MissingAsset, EditorLight, EditorVisualization, LightType};
+pub use types::{EditorEntity, PlayerSpawn, GlbModel, RigidBodyType, MissingAsset, EditorLight, EditorVisualization, LightType, RadiationZone, PatrolWaypoint, NpcSpawn, Door, LootContainer};
 pub use materials::{GridMaterial, GizmoMaterial, OutlineMaterial};
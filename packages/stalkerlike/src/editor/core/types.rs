@@ -6,12 +6,16 @@
 //! - **PlayerSpawn**: Marker for player spawn points (used by game mode)
 //! - **GlbModel**: Component storing GLB/GLTF model file path
 //! - **RigidBodyType**: Editor representation of physics body types
+//! - **RadiationZone**: Box volume that irradiates the player in game mode
+//! - **PatrolWaypoint** / **NpcSpawn**: Patrol routes and the NPCs that walk them
+//! - **Door** / **LootContainer**: Lockable doors and lootable containers, saved by id
 
 use bevy::prelude::*;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Marker component for entities that belong to the editor scene
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct EditorEntity;
 
 /// Marker component for player spawn point entities
@@ -53,6 +57,121 @@ impl RigidBodyType {
     }
 }
 
+/// Box volume that exposes the player to radiation in game mode
+/// The box is a unit cube scaled by the entity's Transform
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct RadiationZone {
+    /// Dose rate at the center in rads per second, falling to zero at the edges
+    pub intensity: f32,
+}
+
+impl Default for RadiationZone {
+    fn default() -> Self {
+        Self { intensity: 5.0 }
+    }
+}
+
+impl RadiationZone {
+    /// Dose rate at a world position, given the zone's global transform
+    pub fn rate_at(&self, transform: &GlobalTransform, position: Vec3) -> f32 {
+        let local = transform.affine().inverse().transform_point3(position);
+        // 0 at the center, 1 on the faces of the unit cube
+        let depth = (local.abs() * 2.0).max_element();
+        if depth >= 1.0 {
+            return 0.0;
+        }
+        self.intensity * (1.0 - depth)
+    }
+}
+
+/// One stop on a patrol route
+/// Waypoints sharing a network form a loop, walked in `order`
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct PatrolWaypoint {
+    pub network: String,
+    pub order: u32,
+}
+
+impl Default for PatrolWaypoint {
+    fn default() -> Self {
+        Self { network: "patrol_a".to_string(), order: 0 }
+    }
+}
+
+/// Where game mode spawns NPCs of a data pack archetype, and the network they patrol
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct NpcSpawn {
+    /// Id of an `NpcArchetype` from the data packs
+    pub archetype: String,
+    pub network: String,
+    pub count: u32,
+}
+
+impl Default for NpcSpawn {
+    fn default() -> Self {
+        Self { archetype: "loner".to_string(), network: "patrol_a".to_string(), count: 1 }
+    }
+}
+
+/// Waypoint positions of every network, in walking order
+pub fn patrol_routes<'a>(waypoints: impl IntoIterator<Item = (&'a PatrolWaypoint, Vec3)>) -> BTreeMap<String, Vec<Vec3>> {
+    let mut stops: BTreeMap<String, Vec<(u32, Vec3)>> = BTreeMap::new();
+    for (waypoint, position) in waypoints {
+        stops.entry(waypoint.network.clone()).or_default().push((waypoint.order, position));
+    }
+    stops
+        .into_iter()
+        .map(|(network, mut route)| {
+            route.sort_by_key(|&(order, _)| order);
+            (network, route.into_iter().map(|(_, position)| position).collect())
+        })
+        .collect()
+}
+
+/// Sliding door panel the player can open, unlock with a key or pick
+/// The panel is a 1 x 2 x 0.1 box scaled by the entity's Transform
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Door {
+    /// Name the save file knows this door by; unique within the scene
+    pub id: String,
+    /// Whether it starts locked
+    pub locked: bool,
+    /// Id of the data pack item that unlocks it; empty if no key fits
+    pub key: String,
+    /// How hard the lock is to pick, from 1 to 5; 0 if it can't be picked
+    pub lock_level: u32,
+}
+
+impl Default for Door {
+    fn default() -> Self {
+        Self { id: "door_0".to_string(), locked: false, key: String::new(), lock_level: 1 }
+    }
+}
+
+/// Crate the player can empty once; its items are rolled from a loot table when opened
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct LootContainer {
+    /// Name the save file knows this container by; unique within the scene
+    pub id: String,
+    /// Id of a `LootTable` from the data packs
+    pub loot_table: String,
+}
+
+impl Default for LootContainer {
+    fn default() -> Self {
+        Self { id: "container_0".to_string(), loot_table: "stalker_common".to_string() }
+    }
+}
+
+/// First `{prefix}_{n}` that isn't taken, for naming new doors and containers
+pub fn unique_id<'a>(prefix: &str, taken: impl IntoIterator<Item = &'a str>) -> String {
+    let taken: std::collections::HashSet<&str> = taken.into_iter().collect();
+    (0..)
+        .map(|n| format!("{}_{}", prefix, n))
+        .find(|id| !taken.contains(id.as_str()))
+        .unwrap_or_default()
+}
+
 /// Marker component for light entities
 #[derive(Component, Clone, Debug)]
 pub struct EditorLight {
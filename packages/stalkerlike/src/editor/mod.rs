@@ -26,10 +26,10 @@ use bevy::picking::mesh_picking::MeshPickingPlugin;
 use bevy::winit::WinitWindows;
 
 // Domain modules
-mod core;
+pub(crate) mod core;
 mod input;
 mod objects;
-mod persistence;
+pub(crate) mod persistence;
 mod ui;
 mod viewport;
 
@@ -0,0 +1,124 @@
+//! Array duplicate tool
+//!
+//! Copies the selection several times in a row, for fences, pipe runs and
+//! rows of lights. Copies either step along a fixed offset from the original,
+//! or are spread evenly between two points (the selection's center lands on
+//! each). Each copy can also turn a little more than the last around the
+//! vertical axis through the selection's center.
+//!
+//! The tool window is opened with Ctrl+R or Edit > Array Duplicate; the copies
+//! are made by `apply_array_duplicate` when the window asks for them.
+
+use bevy::prelude::*;
+
+use crate::editor::objects::duplication::{duplicate_entities, select_entities};
+use crate::editor::objects::selection::Selected;
+
+/// How copies are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayMode {
+    /// Each copy one step further from the original
+    #[default]
+    Offset,
+    /// Copies spread evenly from the start point to the end point
+    BetweenPoints,
+}
+
+/// Settings for the array tool and its window
+#[derive(Resource)]
+pub struct ArrayTool {
+    pub show: bool,
+    pub mode: ArrayMode,
+    /// Number of copies to make
+    pub count: u32,
+    /// Offset between consecutive copies in `Offset` mode
+    pub step: Vec3,
+    /// First and last point in `BetweenPoints` mode
+    pub start: Vec3,
+    pub end: Vec3,
+    /// Extra rotation around Y for each copy, in degrees
+    pub rotation_step: f32,
+    /// Set by the window; the copies are made on the next update
+    pub apply_requested: bool,
+}
+
+impl Default for ArrayTool {
+    fn default() -> Self {
+        Self {
+            show: false,
+            mode: ArrayMode::Offset,
+            count: 4,
+            step: Vec3::X * 2.0,
+            start: Vec3::ZERO,
+            end: Vec3::X * 10.0,
+            rotation_step: 0.0,
+            apply_requested: false,
+        }
+    }
+}
+
+impl ArrayTool {
+    /// Where the selection's center goes and how far it turns, for each copy
+    pub fn placements(&self, center: Vec3) -> Vec<(Vec3, Quat)> {
+        (0..self.count)
+            .map(|i| {
+                let (position, turns) = match self.mode {
+                    ArrayMode::Offset => (center + self.step * (i + 1) as f32, (i + 1) as f32),
+                    ArrayMode::BetweenPoints => {
+                        let t = if self.count > 1 { i as f32 / (self.count - 1) as f32 } else { 0.0 };
+                        (self.start.lerp(self.end, t), i as f32)
+                    }
+                };
+                (position, Quat::from_rotation_y((self.rotation_step * turns).to_radians()))
+            })
+            .collect()
+    }
+}
+
+/// Ctrl+R opens or closes the array tool window
+pub fn handle_array_key(keyboard: Res<ButtonInput<KeyCode>>, mut array_tool: ResMut<ArrayTool>) {
+    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
+    if ctrl && keyboard.just_pressed(KeyCode::KeyR) {
+        array_tool.show = !array_tool.show;
+    }
+}
+
+/// Make the copies the array tool window asked for, then select them
+pub fn apply_array_duplicate(world: &mut World) {
+    let mut array_tool = world.resource_mut::<ArrayTool>();
+    if !array_tool.apply_requested {
+        return;
+    }
+    array_tool.apply_requested = false;
+
+    let mut selected_query = world.query_filtered::<(Entity, &Transform, Option<&ChildOf>), With<Selected>>();
+    let selected: Vec<(Entity, Transform, Option<Entity>)> = selected_query
+        .iter(world)
+        .map(|(entity, transform, child_of)| (entity, *transform, child_of.map(|c| c.0)))
+        .collect();
+    if selected.is_empty() {
+        info!("Cannot array duplicate: no entities selected");
+        return;
+    }
+
+    // Center of the top-level selected entities, which the placements are relative to
+    let entities: Vec<Entity> = selected.iter().map(|(entity, ..)| *entity).collect();
+    let roots: Vec<Vec3> = selected.iter()
+        .filter(|(_, _, parent)| parent.is_none_or(|parent| !entities.contains(&parent)))
+        .map(|(_, transform, _)| transform.translation)
+        .collect();
+    let center = roots.iter().sum::<Vec3>() / roots.len().max(1) as f32;
+
+    let placements = world.resource::<ArrayTool>().placements(center);
+    let mut copies = Vec::new();
+    for (position, rotation) in placements {
+        copies.extend(duplicate_entities(world, &entities, |transform| Transform {
+            translation: position + rotation * (transform.translation - center),
+            rotation: rotation * transform.rotation,
+            scale: transform.scale,
+        }));
+    }
+    select_entities(world, &copies);
+
+    info!("Array duplicated {} entities into {} copies", entities.len(), copies.len());
+}
@@ -2,6 +2,7 @@
 //!
 //! This module provides:
 //! - Ctrl+D: Duplicate selected entities with +1m X-axis offset
+//! - `duplicate_entities`: The cloning itself, shared with the array tool
 //! - Maintains parent-child relationships
 //! - Clones all components (Transform, Mesh3d, MeshMaterial3d, Name, etc.)
 //! - Auto-selects duplicated objects after creation
@@ -28,7 +29,7 @@ use bevy::prelude::*;
 use crate::editor::objects::selection::{SelectionSet, Selected};
 use crate::editor::objects::placement::PlacementState;
 use crate::editor::objects::grouping::Group;
-use crate::editor::core::types::{EditorEntity, GlbModel, RigidBodyType, PlayerSpawn, EditorLight, EditorVisualization};
+use crate::editor::core::types::{EditorEntity, GlbModel, RigidBodyType, PlayerSpawn, EditorLight, EditorVisualization, RadiationZone, PatrolWaypoint, NpcSpawn, Door, LootContainer, unique_id};
 use std::collections::HashMap;
 
 /// Handle Ctrl+D to duplicate selected entities
@@ -61,13 +62,31 @@ pub fn handle_duplicate(world: &mut World) {
     // Calculate offset for duplicated entities
     let offset = Vec3::new(1.0, 0.0, 0.0);
 
+    let clones = duplicate_entities(world, &selected_entities, |transform| Transform {
+        translation: transform.translation + offset,
+        ..transform
+    });
+    select_entities(world, &clones);
+
+    info!("Duplicated {} entities with +1m X-axis offset", clones.len());
+}
+
+/// Clone `entities`, placing each copy whose parent isn't also being copied with `place`
+///
+/// Children of copied parents keep their local transform, so a group moves as a
+/// whole. Returns the copies in the same order as `entities`.
+pub fn duplicate_entities(
+    world: &mut World,
+    entities: &[Entity],
+    place: impl Fn(Transform) -> Transform,
+) -> Vec<Entity> {
     // Map from original entity to cloned entity
     let mut entity_map: HashMap<Entity, Entity> = HashMap::new();
 
     // Collect all component data first to avoid borrow conflicts
     let mut entities_data = Vec::new();
 
-    for &original_entity in &selected_entities {
+    for &original_entity in entities {
         // Get transform (required)
         let Some(transform) = world.get::<Transform>(original_entity) else {
             warn!("Entity {:?} has no transform, skipping", original_entity);
@@ -78,7 +97,7 @@ pub fn handle_duplicate(world: &mut World) {
         let name = world.get::<Name>(original_entity).map(|n| n.as_str().to_string());
         let mesh = world.get::<Mesh3d>(original_entity).cloned();
         let material = world.get::<MeshMaterial3d<StandardMaterial>>(original_entity).cloned();
-        let is_group = world.get::<Group>(original_entity).is_some();
+        let group = world.get::<Group>(original_entity).copied();
         let glb_model = world.get::<GlbModel>(original_entity).cloned();
         let has_scene_root = world.get::<SceneRoot>(original_entity).is_some();
         let rb_type = world.get::<RigidBodyType>(original_entity).copied();
@@ -87,6 +106,11 @@ pub fn handle_duplicate(world: &mut World) {
         let editor_light = world.get::<EditorLight>(original_entity).cloned();
         let point_light = world.get::<PointLight>(original_entity).cloned();
         let spot_light = world.get::<SpotLight>(original_entity).cloned();
+        let radiation_zone = world.get::<RadiationZone>(original_entity).copied();
+        let waypoint = world.get::<PatrolWaypoint>(original_entity).cloned();
+        let npc_spawn = world.get::<NpcSpawn>(original_entity).cloned();
+        let door = world.get::<Door>(original_entity).cloned();
+        let container = world.get::<LootContainer>(original_entity).cloned();
         let children = world.get::<Children>(original_entity).map(|c| c.iter().collect::<Vec<_>>());
 
         entities_data.push((
@@ -95,7 +119,7 @@ pub fn handle_duplicate(world: &mut World) {
             name,
             mesh,
             material,
-            is_group,
+            group,
             glb_model,
             has_scene_root,
             rb_type,
@@ -104,10 +128,19 @@ pub fn handle_duplicate(world: &mut World) {
             editor_light,
             point_light,
             spot_light,
+            radiation_zone,
+            waypoint,
+            npc_spawn,
+            door,
+            container,
             children,
         ));
     }
 
+    // Ids already used by doors and containers
+    let mut taken_ids: Vec<String> = world.query::<&Door>().iter(world).map(|door| door.id.clone()).collect();
+    taken_ids.extend(world.query::<&LootContainer>().iter(world).map(|container| container.id.clone()));
+
     // First pass: Clone all entities
     for (
         original_entity,
@@ -115,7 +148,7 @@ pub fn handle_duplicate(world: &mut World) {
         name,
         mesh,
         material,
-        is_group,
+        group,
         glb_model,
         has_scene_root,
         rb_type,
@@ -124,15 +157,18 @@ pub fn handle_duplicate(world: &mut World) {
         editor_light,
         point_light,
         spot_light,
+        radiation_zone,
+        waypoint,
+        npc_spawn,
+        door,
+        container,
         _children,
     ) in &entities_data {
 
-        // Create new transform with offset
-        let new_transform = Transform {
-            translation: transform.translation + offset,
-            rotation: transform.rotation,
-            scale: transform.scale,
-        };
+        // Place copies of top-level entities; children follow their copied parent
+        let parent_copied = world.get::<ChildOf>(*original_entity)
+            .is_some_and(|child_of| entities.contains(&child_of.0));
+        let new_transform = if parent_copied { *transform } else { place(*transform) };
 
         // Spawn new entity with base components
         let mut new_entity = world.spawn((
@@ -162,9 +198,9 @@ pub fn handle_duplicate(world: &mut World) {
             new_entity.insert(material.clone());
         }
 
-        // Clone Group marker
-        if *is_group {
-            new_entity.insert(Group);
+        // Clone Group, keeping its pivot
+        if let Some(group) = group {
+            new_entity.insert(*group);
         }
 
         // Clone GlbModel (SceneRoot handled after)
@@ -200,6 +236,31 @@ pub fn handle_duplicate(world: &mut World) {
             }
         }
 
+        // Clone radiation zone
+        if let Some(radiation_zone) = radiation_zone {
+            new_entity.insert(*radiation_zone);
+        }
+
+        // Clone patrol markers
+        if let Some(waypoint) = waypoint {
+            new_entity.insert(waypoint.clone());
+        }
+        if let Some(npc_spawn) = npc_spawn {
+            new_entity.insert(npc_spawn.clone());
+        }
+
+        // Clone doors and containers under fresh ids, so the save can tell them apart
+        if let Some(door) = door {
+            let id = unique_id("door", taken_ids.iter().map(String::as_str));
+            taken_ids.push(id.clone());
+            new_entity.insert(Door { id, ..door.clone() });
+        }
+        if let Some(container) = container {
+            let id = unique_id("container", taken_ids.iter().map(String::as_str));
+            taken_ids.push(id.clone());
+            new_entity.insert(LootContainer { id, ..container.clone() });
+        }
+
         let cloned_entity = new_entity.id();
 
         // Handle SceneRoot separately (requires asset_server)
@@ -226,7 +287,7 @@ pub fn handle_duplicate(world: &mut World) {
         _name,
         _mesh,
         _material,
-        _is_group,
+        _group,
         _glb_model,
         _has_scene_root,
         _rb_type,
@@ -235,6 +296,11 @@ pub fn handle_duplicate(world: &mut World) {
         _editor_light,
         _point_light,
         _spot_light,
+        _radiation_zone,
+        _waypoint,
+        _npc_spawn,
+        _door,
+        _container,
         children,
     ) in &entities_data {
         let cloned_entity = entity_map[original_entity];
@@ -271,8 +337,10 @@ pub fn handle_duplicate(world: &mut World) {
     }
 
     // Third pass: Set up parent-child relationships
-    for &original_entity in &selected_entities {
-        let cloned_entity = entity_map[&original_entity];
+    for &original_entity in entities {
+        let Some(&cloned_entity) = entity_map.get(&original_entity) else {
+            continue;
+        };
 
         if let Some(child_of) = world.get::<ChildOf>(original_entity) {
             if let Some(&cloned_parent) = entity_map.get(&child_of.0) {
@@ -282,8 +350,14 @@ pub fn handle_duplicate(world: &mut World) {
         }
     }
 
-    // Clear previous selection and select duplicated entities
-    for entity in selected_entities {
+    entities.iter().filter_map(|entity| entity_map.get(entity).copied()).collect()
+}
+
+/// Replace the selection with `entities`
+pub fn select_entities(world: &mut World, entities: &[Entity]) {
+    let mut selected_query = world.query_filtered::<Entity, With<Selected>>();
+    let previously_selected: Vec<Entity> = selected_query.iter(world).collect();
+    for entity in previously_selected {
         world.entity_mut(entity).remove::<Selected>();
     }
 
@@ -291,10 +365,8 @@ pub fn handle_duplicate(world: &mut World) {
     selection.clear();
     drop(selection); // Release the borrow before inserting Selected components
 
-    for &cloned_entity in entity_map.values() {
-        world.resource_mut::<SelectionSet>().add(cloned_entity);
-        world.entity_mut(cloned_entity).insert(Selected);
+    for &entity in entities {
+        world.resource_mut::<SelectionSet>().add(entity);
+        world.entity_mut(entity).insert(Selected);
     }
-
-    info!("Duplicated {} entities with +1m X-axis offset", entity_map.len());
 }
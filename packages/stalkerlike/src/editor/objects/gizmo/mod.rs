@@ -7,16 +7,20 @@
 //!
 //! - **Three transform modes**: Translate, Rotate, Scale (cycle with F key)
 //! - **Two orientations**: Global (world-aligned) or Local (object-aligned) axes (toggle with O key)
+//! - **Pivot modes**: Rotate and scale around the selection center, the active object,
+//!   the 3D cursor or a group's stored pivot (cycle with . key)
 //! - **Multi-select support**: Transform multiple objects simultaneously
 //! - **Grid snapping**: Snap translations and rotations when grid snap is enabled
 //! - **Visual feedback**: Handles highlight on hover
 //! - **Speed modifiers**: Hold Shift for 4x speed, Ctrl for 0.25x speed during drag
+//! - **Numeric input**: Type a value while dragging for an exact move, rotation (degrees)
+//!   or scale factor; Enter confirms and Escape restores the pre-drag transforms
 //!
 //! # Architecture
 //!
 //! The gizmo is spawned/despawned automatically based on object selection using
 //! Bevy observers. Each gizmo consists of:
-//! - A root entity (`GizmoRoot`) positioned at the selection center
+//! - A root entity (`GizmoRoot`) positioned at the pivot
 //! - Three handle entities (`GizmoHandle`) for each axis (X, Y, Z)
 //! - Visual mesh children (arrow shafts, cones, and axis lines)
 
@@ -24,9 +28,10 @@ pub mod systems;
 pub mod types;
 
 // Re-export commonly used types
-pub use types::{GizmoState, GizmoHandle};
+pub use types::{GizmoState, GizmoDrag, GizmoHandle, TransformMode};
 
 // Re-export all systems for plugin registration
 pub use systems::{
     spawn_gizmo, despawn_gizmo, update_gizmo_position, toggle_transform_mode,
+    handle_gizmo_numeric_input,
 };
@@ -1,11 +1,13 @@
 //! Gizmo system functions
 //!
 //! This module contains all the systems that operate on gizmo entities:
-//! - `toggle_transform_mode`: Handle keyboard input to cycle gizmo modes
+//! - `toggle_transform_mode`: Handle keyboard input to cycle gizmo modes and pivots
 //! - `spawn_gizmo`: Create gizmo visualization when entity selected
 //! - `despawn_gizmo`: Clean up gizmo when entity deselected
 //! - `update_gizmo_position`: Update gizmo transform to follow selection
+//! - `on_gizmo_drag_start` / `on_gizmo_drag_end`: Remember the selection while a handle is dragged
 //! - `on_gizmo_drag`: Handle drag events to transform selected objects
+//! - `handle_gizmo_numeric_input`: Apply a value typed during a drag
 //! - `on_gizmo_hover`: Visual feedback when hovering over gizmo handles
 //! - `on_gizmo_hover_end`: Reset visual feedback when hover ends
 //!
@@ -15,22 +17,27 @@
 //! - `update_gizmo_position()` runs every frame - syncs gizmo to selected object
 //! - `despawn_gizmo()` triggers on OnRemove<Selected> - cleans up when deselected
 //! - Entity-specific observers (on_gizmo_*) handle all interaction events:
+//!   * on_gizmo_drag_start/on_gizmo_drag_end: Track the drag on DragStart/DragEnd
 //!   * on_gizmo_drag: Update transform during Drag
 //!   * on_gizmo_hover/on_gizmo_hover_end: Visual feedback on Over/Out
 
 use bevy::prelude::*;
-use bevy::picking::events::{Pointer, Drag, Over, Out};
+use bevy::picking::events::{Pointer, Drag, DragEnd, DragStart, Over, Out};
 
 use crate::editor::core::materials::GizmoMaterial;
+use crate::editor::objects::grouping::Group;
 use crate::editor::objects::selection::{SelectionSet, Selected};
 use crate::editor::viewport::grid::GridConfig;
+use crate::editor::viewport::EditorCursor;
 
-use super::types::{GizmoState, TransformMode, TransformOrientation, GizmoAxis, GizmoHandle, GizmoRoot};
+use super::types::{GizmoState, GizmoDrag, TransformMode, TransformOrientation, PivotMode, GizmoAxis, GizmoHandle, GizmoRoot};
 
-/// Toggle transform mode with F key (forward) and Shift+F (backward)
+/// Toggle transform mode with F key (forward) and Shift+F (backward),
+/// and cycle the pivot with . (Ctrl+. sets group pivots instead)
 pub fn toggle_transform_mode(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut gizmo_state: ResMut<GizmoState>,
+    gizmo_drag: Res<GizmoDrag>,
 ) {
     let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
 
@@ -60,6 +67,44 @@ pub fn toggle_transform_mode(
         };
         info!("Transform orientation: {:?}", gizmo_state.orientation);
     }
+
+    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
+    // While dragging, . is a decimal point for numeric input
+    if keyboard.just_pressed(KeyCode::Period) && !ctrl && gizmo_drag.axis.is_none() {
+        gizmo_state.pivot = gizmo_state.pivot.next();
+        info!("Pivot: {}", gizmo_state.pivot.label());
+    }
+}
+
+/// Work out the pivot point for the current selection
+pub fn selection_pivot(
+    pivot: PivotMode,
+    selection: &SelectionSet,
+    selected_query: &Query<(&Transform, Option<&Group>), With<Selected>>,
+    cursor: &EditorCursor,
+) -> Option<Vec3> {
+    let active = selection.active().and_then(|entity| selected_query.get(entity).ok());
+
+    match pivot {
+        PivotMode::Cursor => Some(cursor.position),
+        PivotMode::ActiveObject => active.map(|(transform, _)| transform.translation),
+        PivotMode::Custom => active.map(|(transform, group)| match group {
+            Some(group) => group.pivot(transform),
+            None => transform.translation,
+        }),
+        PivotMode::SelectionCenter => {
+            let positions: Vec<Vec3> = selection
+                .entities
+                .iter()
+                .filter_map(|entity| selected_query.get(*entity).ok())
+                .map(|(transform, _)| transform.translation)
+                .collect();
+            if positions.is_empty() {
+                return None;
+            }
+            Some(positions.iter().sum::<Vec3>() / positions.len() as f32)
+        }
+    }
 }
 
 /// Spawn gizmo handles at the selected object's position
@@ -166,7 +211,9 @@ pub fn spawn_gizmo(
     ))
     .observe(on_gizmo_hover)
     .observe(on_gizmo_hover_end)
+    .observe(on_gizmo_drag_start)
     .observe(on_gizmo_drag)
+    .observe(on_gizmo_drag_end)
     .add_children(&[x_line, x_shaft, x_cone])
     .id();
 
@@ -223,7 +270,9 @@ pub fn spawn_gizmo(
     ))
     .observe(on_gizmo_hover)
     .observe(on_gizmo_hover_end)
+    .observe(on_gizmo_drag_start)
     .observe(on_gizmo_drag)
+    .observe(on_gizmo_drag_end)
     .add_children(&[y_line, y_shaft, y_cone])
     .id();
 
@@ -283,7 +332,9 @@ pub fn spawn_gizmo(
     ))
     .observe(on_gizmo_hover)
     .observe(on_gizmo_hover_end)
+    .observe(on_gizmo_drag_start)
     .observe(on_gizmo_drag)
+    .observe(on_gizmo_drag_end)
     .add_children(&[z_line, z_shaft, z_cone])
     .id();
 
@@ -306,14 +357,15 @@ pub fn despawn_gizmo(
 }
 
 /// Update gizmo position, rotation, and scale to follow selected object(s)
-/// For multi-select, positions at the center of all selected objects' bounding box
+/// The gizmo sits on the pivot chosen in `GizmoState`
 /// Scale is adjusted based on camera distance to maintain constant screen-space size
 pub fn update_gizmo_position(
     selection: Res<SelectionSet>,
-    selected_query: Query<&Transform, With<Selected>>,
+    selected_query: Query<(&Transform, Option<&Group>), With<Selected>>,
     mut gizmo_query: Query<&mut Transform, (With<GizmoRoot>, Without<Selected>)>,
     camera_query: Query<&GlobalTransform, With<Camera>>,
     gizmo_state: Res<GizmoState>,
+    cursor: Res<EditorCursor>,
 ) {
     if selection.is_empty() {
         return;
@@ -323,22 +375,9 @@ pub fn update_gizmo_position(
         return;
     };
 
-    // Calculate the center position of all selected entities
-    let mut center = Vec3::ZERO;
-    let mut count = 0;
-
-    for entity in &selection.entities {
-        if let Ok(transform) = selected_query.get(*entity) {
-            center += transform.translation;
-            count += 1;
-        }
-    }
-
-    if count == 0 {
+    let Some(center) = selection_pivot(gizmo_state.pivot, &selection, &selected_query, &cursor) else {
         return;
-    }
-
-    center /= count as f32;
+    };
 
     // Calculate distance from camera to gizmo center
     let distance = (camera_transform.translation() - center).length();
@@ -355,7 +394,7 @@ pub fn update_gizmo_position(
         gizmo_transform.rotation = if selection.len() > 1 {
             Quat::IDENTITY // Multi-select always uses Global orientation
         } else if let Some(first_entity) = selection.first() {
-            if let Ok(first_transform) = selected_query.get(first_entity) {
+            if let Ok((first_transform, _)) = selected_query.get(first_entity) {
                 match gizmo_state.orientation {
                     TransformOrientation::Global => Quat::IDENTITY,
                     TransformOrientation::Local => first_transform.rotation,
@@ -373,6 +412,7 @@ pub fn update_gizmo_position(
 
 /// Handle drag on gizmo handle (entity observer) - fires continuously while dragging
 /// For multi-select, applies transform to all selected entities
+/// Rotation and scaling also move each entity around the gizmo's pivot
 pub fn on_gizmo_drag(
     drag: Trigger<Pointer<Drag>>,
     gizmo_state: Res<GizmoState>,
@@ -383,7 +423,13 @@ pub fn on_gizmo_drag(
     camera_query: Query<(&Camera, &GlobalTransform)>,
     keyboard: Res<ButtonInput<KeyCode>>,
     gizmo_query: Query<&Transform, (With<GizmoRoot>, Without<Selected>)>,
+    gizmo_drag: Res<GizmoDrag>,
 ) {
+    // A typed value, or a drag ended with Enter/Escape, overrides the mouse
+    if gizmo_drag.finished || !gizmo_drag.typed.is_empty() {
+        return;
+    }
+
     let handle_entity = drag.target();
 
     // Get the axis of the handle being dragged
@@ -426,6 +472,7 @@ pub fn on_gizmo_drag(
     };
 
     let drag_scale = base_drag_scale * speed_multiplier;
+    let pivot = gizmo_transform.translation;
 
     // Apply drag to ALL selected entities
     for entity in &selection.entities {
@@ -481,6 +528,8 @@ pub fn on_gizmo_drag(
                 }
             }
             TransformMode::Rotate => {
+                let previous_rotation = transform.rotation;
+
                 // Rotation: use drag distance as angle delta
                 let angle_delta = delta.length() * drag_scale * if delta.x + delta.y < 0.0 { -1.0 } else { 1.0 };
 
@@ -521,8 +570,13 @@ pub fn on_gizmo_drag(
 
                     transform.rotation = Quat::from_euler(EulerRot::XYZ, euler.0, euler.1, euler.2);
                 }
+
+                // Swing the position around the pivot by the rotation actually applied
+                let applied = transform.rotation * previous_rotation.inverse();
+                transform.translation = pivot + applied * (transform.translation - pivot);
             }
             TransformMode::Scale => {
+                let previous_scale = transform.scale;
                 let scale_delta = -delta.y * drag_scale;
 
                 match axis {
@@ -530,9 +584,155 @@ pub fn on_gizmo_drag(
                     GizmoAxis::Y => transform.scale.y = (transform.scale.y + scale_delta).max(0.01),
                     GizmoAxis::Z => transform.scale.z = (transform.scale.z + scale_delta).max(0.01),
                 }
+
+                // Push the position away from or toward the pivot by the same ratio
+                let ratio = transform.scale / previous_scale;
+                transform.translation = pivot + (transform.translation - pivot) * ratio;
+            }
+        }
+    }
+}
+
+/// Remember the selection's transforms when a handle drag starts (entity observer)
+pub fn on_gizmo_drag_start(
+    trigger: Trigger<Pointer<DragStart>>,
+    handle_query: Query<&GizmoAxis, With<GizmoHandle>>,
+    selection: Res<SelectionSet>,
+    selected_query: Query<&Transform, With<Selected>>,
+    gizmo_query: Query<&Transform, (With<GizmoRoot>, Without<Selected>)>,
+    mut gizmo_drag: ResMut<GizmoDrag>,
+) {
+    let Ok(axis) = handle_query.get(trigger.target()) else {
+        return;
+    };
+
+    *gizmo_drag = GizmoDrag {
+        axis: Some(*axis),
+        start_transforms: selection.entities.iter()
+            .filter_map(|entity| selected_query.get(*entity).ok().map(|transform| (*entity, *transform)))
+            .collect(),
+        pivot: gizmo_query.single().map(|transform| transform.translation).unwrap_or_default(),
+        typed: String::new(),
+        finished: false,
+    };
+}
+
+/// Forget the drag once the mouse is released (entity observer)
+pub fn on_gizmo_drag_end(_trigger: Trigger<Pointer<DragEnd>>, mut gizmo_drag: ResMut<GizmoDrag>) {
+    *gizmo_drag = GizmoDrag::default();
+}
+
+/// Keys accepted as digits while typing a value
+const DIGIT_KEYS: [(KeyCode, KeyCode, char); 10] = [
+    (KeyCode::Digit0, KeyCode::Numpad0, '0'),
+    (KeyCode::Digit1, KeyCode::Numpad1, '1'),
+    (KeyCode::Digit2, KeyCode::Numpad2, '2'),
+    (KeyCode::Digit3, KeyCode::Numpad3, '3'),
+    (KeyCode::Digit4, KeyCode::Numpad4, '4'),
+    (KeyCode::Digit5, KeyCode::Numpad5, '5'),
+    (KeyCode::Digit6, KeyCode::Numpad6, '6'),
+    (KeyCode::Digit7, KeyCode::Numpad7, '7'),
+    (KeyCode::Digit8, KeyCode::Numpad8, '8'),
+    (KeyCode::Digit9, KeyCode::Numpad9, '9'),
+];
+
+/// Type an exact value while dragging a handle, Blender-style
+///
+/// Digits, . and - build the value, which is previewed straight away along the
+/// dragged axis: world units for translate, degrees for rotate and a factor for
+/// scale. Enter keeps the result, Escape puts everything back where the drag
+/// started. Either way the mouse is ignored until the button is released.
+pub fn handle_gizmo_numeric_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gizmo_state: Res<GizmoState>,
+    mut gizmo_drag: ResMut<GizmoDrag>,
+    mut selected_query: Query<&mut Transform, With<Selected>>,
+) {
+    let Some(axis) = gizmo_drag.axis.filter(|_| gizmo_drag.is_active()) else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::Escape) {
+        for (entity, start) in &gizmo_drag.start_transforms {
+            if let Ok(mut transform) = selected_query.get_mut(*entity) {
+                *transform = *start;
             }
         }
+        gizmo_drag.finished = true;
+        info!("Gizmo drag cancelled");
+        return;
+    }
+
+    if keyboard.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]) {
+        gizmo_drag.finished = true;
+        info!("Gizmo drag confirmed along {:?}", axis);
+        return;
+    }
+
+    let previous = gizmo_drag.typed.clone();
+    for (key, numpad_key, digit) in DIGIT_KEYS {
+        if keyboard.any_just_pressed([key, numpad_key]) {
+            gizmo_drag.typed.push(digit);
+        }
+    }
+    if keyboard.any_just_pressed([KeyCode::Period, KeyCode::NumpadDecimal]) && !gizmo_drag.typed.contains('.') {
+        gizmo_drag.typed.push('.');
+    }
+    if keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        // Minus flips the sign wherever it is typed
+        gizmo_drag.typed = match gizmo_drag.typed.strip_prefix('-') {
+            Some(positive) => positive.to_string(),
+            None => format!("-{}", gizmo_drag.typed),
+        };
+    }
+    if keyboard.just_pressed(KeyCode::Backspace) {
+        gizmo_drag.typed.pop();
+    }
+    if gizmo_drag.typed == previous {
+        return;
+    }
+
+    // Preview from the starting transforms so retyping never accumulates;
+    // until something parses (or after erasing everything) nothing has moved
+    let value = gizmo_drag.typed_value().unwrap_or(match gizmo_state.mode {
+        TransformMode::Scale => 1.0,
+        _ => 0.0,
+    });
+    let local = gizmo_drag.start_transforms.len() == 1 && gizmo_state.orientation == TransformOrientation::Local;
+    for (entity, start) in &gizmo_drag.start_transforms {
+        if let Ok(mut transform) = selected_query.get_mut(*entity) {
+            *transform = typed_transform(gizmo_state.mode, axis, local, gizmo_drag.pivot, value, start);
+        }
+    }
+}
+
+/// Transform `start` moved exactly `value` along `axis`, around `pivot`
+fn typed_transform(mode: TransformMode, axis: GizmoAxis, local: bool, pivot: Vec3, value: f32, start: &Transform) -> Transform {
+    let axis_vector = match axis {
+        GizmoAxis::X => Vec3::X,
+        GizmoAxis::Y => Vec3::Y,
+        GizmoAxis::Z => Vec3::Z,
+    };
+
+    let mut transform = *start;
+    match mode {
+        TransformMode::Translate => {
+            let direction = if local { start.rotation * axis_vector } else { axis_vector };
+            transform.translation += direction * value;
+        }
+        TransformMode::Rotate => {
+            let axis_rotation = Quat::from_axis_angle(axis_vector, value.to_radians());
+            transform.rotation = if local { start.rotation * axis_rotation } else { axis_rotation * start.rotation };
+            let applied = transform.rotation * start.rotation.inverse();
+            transform.translation = pivot + applied * (start.translation - pivot);
+        }
+        TransformMode::Scale => {
+            let factor = Vec3::ONE + axis_vector * (value - 1.0);
+            transform.scale = (start.scale * factor).max(Vec3::splat(0.01));
+            transform.translation = pivot + (start.translation - pivot) * (transform.scale / start.scale);
+        }
     }
+    transform
 }
 
 /// Highlight gizmo handle on hover (entity observer)
@@ -3,6 +3,8 @@
 //! This module defines the data structures used by the gizmo system:
 //! - Transform modes (translate, rotate, scale)
 //! - Transform orientations (global, local)
+//! - Pivot modes (what rotation and scaling happen around)
+//! - Drag state, including values typed during a drag
 //! - Gizmo components and markers
 
 use bevy::prelude::*;
@@ -26,11 +28,47 @@ pub enum TransformOrientation {
     Local,
 }
 
-/// Resource tracking current transform mode and orientation
+/// Point the gizmo sits on, which rotation and scaling happen around
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PivotMode {
+    /// Average position of the selected objects
+    #[default]
+    SelectionCenter,
+    /// Origin of the most recently selected object
+    ActiveObject,
+    /// The 3D cursor
+    Cursor,
+    /// The active object's stored pivot (a group's pivot offset, otherwise its origin)
+    Custom,
+}
+
+impl PivotMode {
+    /// Next mode when cycling with the . key
+    pub fn next(self) -> Self {
+        match self {
+            PivotMode::SelectionCenter => PivotMode::ActiveObject,
+            PivotMode::ActiveObject => PivotMode::Cursor,
+            PivotMode::Cursor => PivotMode::Custom,
+            PivotMode::Custom => PivotMode::SelectionCenter,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PivotMode::SelectionCenter => "Selection Center",
+            PivotMode::ActiveObject => "Active Object",
+            PivotMode::Cursor => "3D Cursor",
+            PivotMode::Custom => "Custom",
+        }
+    }
+}
+
+/// Resource tracking current transform mode, orientation and pivot
 #[derive(Resource)]
 pub struct GizmoState {
     pub mode: TransformMode,
     pub orientation: TransformOrientation,
+    pub pivot: PivotMode,
 }
 
 impl Default for GizmoState {
@@ -38,10 +76,41 @@ impl Default for GizmoState {
         Self {
             mode: TransformMode::Translate,
             orientation: TransformOrientation::Global,
+            pivot: PivotMode::SelectionCenter,
         }
     }
 }
 
+/// The handle drag in progress and any value typed during it
+///
+/// While a number is being typed the mouse no longer moves the selection; the
+/// typed value is applied from the transforms the drag started with instead.
+#[derive(Resource, Default)]
+pub struct GizmoDrag {
+    /// Axis of the handle being dragged, `None` when no drag is in progress
+    pub axis: Option<GizmoAxis>,
+    /// Transforms of the selection when the drag started, restored by Escape
+    pub start_transforms: Vec<(Entity, Transform)>,
+    /// Where the gizmo sat when the drag started
+    pub pivot: Vec3,
+    /// Characters typed so far, e.g. "-2.5"
+    pub typed: String,
+    /// Enter or Escape ended the drag; the mouse is ignored until it is released
+    pub finished: bool,
+}
+
+impl GizmoDrag {
+    /// A drag is in progress and still responds to input
+    pub fn is_active(&self) -> bool {
+        self.axis.is_some() && !self.finished
+    }
+
+    /// The typed value, once it parses as a number
+    pub fn typed_value(&self) -> Option<f32> {
+        self.typed.parse().ok()
+    }
+}
+
 /// Axis identifier for gizmo handles
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
 pub enum GizmoAxis {
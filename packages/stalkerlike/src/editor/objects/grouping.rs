@@ -5,6 +5,7 @@
 //! - Ctrl+Shift+G: Ungroup selected groups (flatten hierarchy)
 //! - Automatic group naming with incrementing numbers
 //! - Transform hierarchy management (world ↔ local space conversion)
+//! - Ctrl+.: Move the pivot of selected groups to the 3D cursor
 
 //! Object grouping and hierarchy system
 //!
@@ -18,6 +19,8 @@
 //! - **Ungroup**: Ctrl+Shift+G ungroups, reparenting children to world root
 //! - **Transform inheritance**: Grouped objects inherit parent transforms
 //! - **Automatic naming**: Groups are named "Group 1", "Group 2", etc.
+//! - **Pivot offset**: Each group stores where it rotates and scales around,
+//!   used by the gizmo's Custom pivot mode and saved with the scene
 //!
 //! # Implementation
 //!
@@ -29,6 +32,7 @@ use bevy::prelude::*;
 use crate::editor::objects::selection::SelectionSet;
 use crate::editor::objects::placement::PlacementState;
 use crate::editor::core::types::EditorEntity;
+use crate::editor::viewport::EditorCursor;
 
 /// Resource to track group count for auto-incrementing group names
 #[derive(Resource, Default)]
@@ -50,9 +54,24 @@ impl GroupCounter {
     }
 }
 
-/// Marker component for entities that are groups (parents of grouped objects)
-#[derive(Component)]
-pub struct Group;
+/// Component for entities that are groups (parents of grouped objects)
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Group {
+    /// Pivot position in the group's local space; zero is the group origin
+    pub pivot_offset: Vec3,
+}
+
+impl Group {
+    /// World position of the pivot for a group with the given transform
+    pub fn pivot(&self, transform: &Transform) -> Vec3 {
+        transform.transform_point(self.pivot_offset)
+    }
+
+    /// Store `point` as the pivot, given the group's transform
+    pub fn set_pivot(&mut self, transform: &Transform, point: Vec3) {
+        self.pivot_offset = transform.compute_affine().inverse().transform_point3(point);
+    }
+}
 
 /// Handle Ctrl+G to group selected entities
 pub fn handle_group(
@@ -103,7 +122,7 @@ pub fn handle_group(
             .spawn((
                 Name::new(group_name.clone()),
                 EditorEntity,
-                Group,
+                Group::default(),
                 Transform::from_translation(center),
                 GlobalTransform::default(),
                 Visibility::default(),
@@ -224,3 +243,30 @@ pub fn handle_ungroup(
         }
     }
 }
+
+/// Handle Ctrl+. to move the pivot of selected groups to the 3D cursor
+pub fn handle_set_group_pivot(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    selection: Res<SelectionSet>,
+    cursor: Res<EditorCursor>,
+    mut group_query: Query<(&Transform, &mut Group)>,
+) {
+    let ctrl_held = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
+    if !ctrl_held || !keyboard.just_pressed(KeyCode::Period) {
+        return;
+    }
+
+    let mut updated = 0;
+    for &entity in &selection.entities {
+        if let Ok((transform, mut group)) = group_query.get_mut(entity) {
+            group.set_pivot(transform, cursor.position);
+            updated += 1;
+        }
+    }
+
+    if updated == 0 {
+        info!("Cannot set pivot: no groups selected");
+    } else {
+        info!("Moved pivot of {} group(s) to {:?}", updated, cursor.position);
+    }
+}
@@ -7,13 +7,16 @@
 //! - Box selection system (multi-select via drag)
 //! - Transform manipulation (gizmos)
 //! - Grouping/ungrouping (hierarchical organization)
-//! - Duplication/deletion operations
+//! - Duplication/deletion operations, including array duplicates
+//! - Patrol route display
 
+pub mod array;
 pub mod box_select;
 pub mod duplication;
 pub mod gizmo;
 pub mod grouping;
 pub mod outline;
+pub mod patrols;
 pub mod placement;
 pub mod plugin;
 pub mod primitives;
@@ -0,0 +1,33 @@
+//! Patrol route display
+//!
+//! Waypoints that share a network are joined into a loop in the viewport, so
+//! the route NPCs will walk in game mode can be checked while placing them.
+
+use bevy::prelude::*;
+
+use crate::editor::core::types::{patrol_routes, NpcSpawn, PatrolWaypoint};
+
+const ROUTE_COLOR: Color = Color::srgb(0.3, 0.6, 1.0);
+const SPAWN_LINK_COLOR: Color = Color::srgb(1.0, 0.5, 0.1);
+
+/// Draw every patrol loop, and a line from each NPC spawn to the start of its route
+pub fn draw_patrol_routes(
+    waypoints: Query<(&PatrolWaypoint, &GlobalTransform)>,
+    spawns: Query<(&NpcSpawn, &GlobalTransform)>,
+    mut gizmos: Gizmos,
+) {
+    let routes = patrol_routes(waypoints.iter().map(|(waypoint, transform)| (waypoint, transform.translation())));
+
+    for route in routes.values() {
+        if route.len() > 1 {
+            // Close the loop back to the first stop
+            gizmos.linestrip(route.iter().copied().chain(route.first().copied()), ROUTE_COLOR);
+        }
+    }
+
+    for (spawn, transform) in spawns.iter() {
+        if let Some(start) = routes.get(&spawn.network).and_then(|route| route.first()) {
+            gizmos.line(transform.translation(), *start, SPAWN_LINK_COLOR);
+        }
+    }
+}
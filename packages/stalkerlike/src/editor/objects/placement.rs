@@ -23,7 +23,7 @@ use bevy::prelude::*;
 use bevy::asset::LoadState;
 use std::path::PathBuf;
 
-use crate::editor::core::types::{EditorEntity, PlayerSpawn, GlbModel, EditorLight, LightType, EditorVisualization, MissingAsset};
+use crate::editor::core::types::{EditorEntity, PlayerSpawn, GlbModel, EditorLight, LightType, EditorVisualization, MissingAsset, RadiationZone, PatrolWaypoint, NpcSpawn, Door, LootContainer, unique_id};
 use crate::editor::viewport::{camera::EditorCamera, grid::{snap_to_grid, GridConfig}, raycasting::ray_plane_intersection};
 use crate::editor::objects::primitives::{PrimitiveDefinition, PrimitiveType};
 
@@ -192,6 +192,9 @@ pub fn place_object(
     asset_server: Res<AssetServer>,
     preview_query: Query<&Transform, With<PreviewEntity>>,
     camera_query: Query<&EditorCamera>,
+    waypoint_query: Query<&PatrolWaypoint>,
+    door_query: Query<&Door>,
+    container_query: Query<&LootContainer>,
 ) {
     if !placement_state.active {
         return;
@@ -222,11 +225,20 @@ pub fn place_object(
             if let Ok(preview_transform) = preview_query.single() {
                 match asset {
                     PlacementAsset::Primitive(primitive) => {
-                        // Check if this is a light primitive - lights need special handling
-                        let is_light = matches!(primitive.primitive_type, PrimitiveType::PointLight | PrimitiveType::SpotLight);
-
-                        if is_light {
-                            // For lights, spawn without mesh/material initially
+                        // Lights and radiation zones keep their mesh on a child visualization
+                        let has_gizmo = matches!(
+                            primitive.primitive_type,
+                            PrimitiveType::PointLight
+                                | PrimitiveType::SpotLight
+                                | PrimitiveType::RadiationZone
+                                | PrimitiveType::PatrolWaypoint
+                                | PrimitiveType::NpcSpawn
+                                | PrimitiveType::Door
+                                | PrimitiveType::LootContainer
+                        );
+
+                        if has_gizmo {
+                            // Spawn the light or zone itself without mesh/material
                             let light_entity = match primitive.primitive_type {
                                 PrimitiveType::PointLight => {
                                     commands.spawn((
@@ -258,6 +270,57 @@ pub fn place_object(
                                         *preview_transform,
                                     )).id()
                                 }
+                                PrimitiveType::RadiationZone => {
+                                    commands.spawn((
+                                        EditorEntity,
+                                        RadiationZone::default(),
+                                        Name::new("Radiation Zone"),
+                                        *preview_transform,
+                                    )).id()
+                                }
+                                PrimitiveType::PatrolWaypoint => {
+                                    // Continue the default network's route
+                                    let waypoint = PatrolWaypoint::default();
+                                    let order = waypoint_query
+                                        .iter()
+                                        .filter(|other| other.network == waypoint.network)
+                                        .map(|other| other.order + 1)
+                                        .max()
+                                        .unwrap_or(0);
+                                    commands.spawn((
+                                        EditorEntity,
+                                        PatrolWaypoint { order, ..waypoint },
+                                        Name::new(format!("Waypoint {}", order)),
+                                        *preview_transform,
+                                    )).id()
+                                }
+                                PrimitiveType::NpcSpawn => {
+                                    commands.spawn((
+                                        EditorEntity,
+                                        NpcSpawn::default(),
+                                        Name::new("NPC Spawn"),
+                                        *preview_transform,
+                                    )).id()
+                                }
+                                PrimitiveType::Door => {
+                                    // Saves refer to doors by id, so every door needs its own
+                                    let id = unique_id("door", door_query.iter().map(|door| door.id.as_str()));
+                                    commands.spawn((
+                                        EditorEntity,
+                                        Name::new("Door"),
+                                        Door { id, ..default() },
+                                        *preview_transform,
+                                    )).id()
+                                }
+                                PrimitiveType::LootContainer => {
+                                    let id = unique_id("container", container_query.iter().map(|container| container.id.as_str()));
+                                    commands.spawn((
+                                        EditorEntity,
+                                        Name::new("Loot Container"),
+                                        LootContainer { id, ..default() },
+                                        *preview_transform,
+                                    )).id()
+                                }
                                 _ => Entity::PLACEHOLDER,
                             };
 
@@ -268,6 +331,7 @@ pub fn place_object(
                                 Mesh3d(meshes.add(viz_mesh)),
                                 MeshMaterial3d(materials.add(StandardMaterial {
                                     base_color: primitive.color,
+                                    alpha_mode: if primitive.color.alpha() < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque },
                                     ..default()
                                 })),
                                 Transform::default(),
@@ -2,11 +2,13 @@
 
 use bevy::prelude::*;
 
+use super::array::{ArrayTool, handle_array_key, apply_array_duplicate};
 use super::box_select::{BoxSelectState, start_box_select, update_box_select, complete_box_select, cancel_box_select, render_box_select};
 use super::duplication::handle_duplicate;
-use super::gizmo::{GizmoState, spawn_gizmo, despawn_gizmo, update_gizmo_position, toggle_transform_mode};
-use super::grouping::{GroupCounter, handle_group, handle_ungroup};
+use super::gizmo::{GizmoState, GizmoDrag, spawn_gizmo, despawn_gizmo, update_gizmo_position, toggle_transform_mode, handle_gizmo_numeric_input};
+use super::grouping::{GroupCounter, handle_group, handle_ungroup, handle_set_group_pivot};
 use super::outline::{spawn_outlines, despawn_outlines, sync_outline_transforms};
+use super::patrols::draw_patrol_routes;
 use super::placement::{PlacementState, update_preview_position, place_object};
 use super::primitives::AssetCatalog;
 use super::selection::{SelectionSet, SelectedEntity, handle_selection, handle_deselection, highlight_selected, remove_outline_from_deselected, delete_selected};
@@ -19,8 +21,8 @@ use super::selection::{SelectionSet, SelectedEntity, handle_selection, handle_de
 /// - Selection system (picking and multi-select)
 /// - Gizmo system (transform manipulation)
 /// - Grouping/ungrouping (hierarchical organization)
-/// - Duplication/deletion operations
-/// - Visual feedback (outlines, highlights)
+/// - Duplication/deletion operations (including array duplicates)
+/// - Visual feedback (outlines, highlights, patrol routes)
 pub struct ObjectsPlugin;
 
 impl Plugin for ObjectsPlugin {
@@ -32,8 +34,10 @@ impl Plugin for ObjectsPlugin {
             .init_resource::<SelectionSet>()
             .init_resource::<SelectedEntity>() // Legacy - kept for backward compatibility
             .init_resource::<GizmoState>()
+            .init_resource::<GizmoDrag>()
             .init_resource::<BoxSelectState>()
             .init_resource::<GroupCounter>()
+            .init_resource::<ArrayTool>()
 
             // ===== Observers =====
             // Selection events trigger gizmo spawn/despawn
@@ -73,15 +77,24 @@ impl Plugin for ObjectsPlugin {
             .add_systems(Update, (
                 update_gizmo_position,
                 toggle_transform_mode,
+                handle_gizmo_numeric_input,
             ))
 
             // Grouping systems - hierarchical organization
             .add_systems(Update, (
                 handle_group,
                 handle_ungroup,
+                handle_set_group_pivot,
             ))
 
-            // Duplication system
-            .add_systems(Update, handle_duplicate);
+            // Duplication systems
+            .add_systems(Update, (
+                handle_duplicate,
+                handle_array_key,
+                apply_array_duplicate,
+            ))
+
+            // Patrol routes between waypoints
+            .add_systems(Update, draw_patrol_routes);
     }
 }
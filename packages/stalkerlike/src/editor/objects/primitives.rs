@@ -12,6 +12,8 @@
 //! - **Capsule**: Capsule mesh (good for characters, pillars)
 //! - **Torus**: Donut-shaped mesh
 //! - **Cone**: Cone mesh
+//! - **Radiation Zone**: Translucent box marking a radiation volume
+//! - **Patrol Waypoint** / **NPC Spawn**: Markers for NPC patrols
 //!
 //! # Asset Catalog
 //!
@@ -79,6 +81,36 @@ impl Default for AssetCatalog {
                     default_size: Vec3::new(0.3, 0.5, 0.3), // Cone shape
                     color: Color::srgb(1.0, 0.9, 0.6), // Warm white
                 },
+                PrimitiveDefinition {
+                    name: "Radiation Zone".to_string(),
+                    primitive_type: PrimitiveType::RadiationZone,
+                    default_size: Vec3::ONE, // Unit box, sized with the Transform scale
+                    color: Color::srgba(0.4, 1.0, 0.2, 0.25), // Translucent green
+                },
+                PrimitiveDefinition {
+                    name: "Patrol Waypoint".to_string(),
+                    primitive_type: PrimitiveType::PatrolWaypoint,
+                    default_size: Vec3::splat(0.4), // Small marker
+                    color: Color::srgb(0.3, 0.6, 1.0), // Blue
+                },
+                PrimitiveDefinition {
+                    name: "NPC Spawn".to_string(),
+                    primitive_type: PrimitiveType::NpcSpawn,
+                    default_size: Vec3::new(0.5, 1.8, 0.5), // Person-sized capsule
+                    color: Color::srgb(1.0, 0.5, 0.1), // Orange
+                },
+                PrimitiveDefinition {
+                    name: "Door".to_string(),
+                    primitive_type: PrimitiveType::Door,
+                    default_size: Vec3::new(1.0, 2.0, 0.1), // Doorway-sized panel
+                    color: Color::srgb(0.45, 0.3, 0.2), // Brown
+                },
+                PrimitiveDefinition {
+                    name: "Loot Container".to_string(),
+                    primitive_type: PrimitiveType::LootContainer,
+                    default_size: Vec3::new(0.8, 0.6, 0.5), // Supply crate
+                    color: Color::srgb(0.35, 0.4, 0.25), // Army green
+                },
             ],
         }
     }
@@ -102,6 +134,11 @@ pub enum PrimitiveType {
     PlayerSpawn,
     PointLight,
     SpotLight,
+    RadiationZone,
+    PatrolWaypoint,
+    NpcSpawn,
+    Door,
+    LootContainer,
 }
 
 impl PrimitiveType {
@@ -116,6 +153,11 @@ impl PrimitiveType {
             PrimitiveType::PlayerSpawn => Vec3::new(0.5, 2.0, 0.5),
             PrimitiveType::PointLight => Vec3::splat(0.3),  // Small sphere representation
             PrimitiveType::SpotLight => Vec3::new(0.3, 0.5, 0.3),  // Cone representation
+            PrimitiveType::RadiationZone => Vec3::ONE,
+            PrimitiveType::PatrolWaypoint => Vec3::splat(0.4),
+            PrimitiveType::NpcSpawn => Vec3::new(0.5, 1.8, 0.5),
+            PrimitiveType::Door => Vec3::new(1.0, 2.0, 0.1),
+            PrimitiveType::LootContainer => Vec3::new(0.8, 0.6, 0.5),
         }
     }
 
@@ -157,6 +199,13 @@ impl PrimitiveType {
                     height: size.y,
                 }.into()
             }
+            PrimitiveType::RadiationZone => Cuboid::new(size.x, size.y, size.z).into(),
+            PrimitiveType::PatrolWaypoint => Sphere::new(size.x / 2.0).mesh().ico(8).unwrap().into(),
+            PrimitiveType::NpcSpawn => {
+                let radius = size.x / 2.0;
+                Capsule3d::new(radius, (size.y / 2.0 - radius).max(0.001)).into()
+            }
+            PrimitiveType::Door | PrimitiveType::LootContainer => Cuboid::new(size.x, size.y, size.z).into(),
         };
 
         // Add vertex colors (white by default, can be modified per-vertex later)
@@ -13,7 +13,7 @@ use bevy::picking::events::{Pointer, Click};
 use crate::editor::core::types::EditorEntity;
 use crate::editor::objects::placement::PlacementState;
 use crate::editor::objects::outline::Outlined;
-use crate::editor::objects::gizmo::GizmoHandle;
+use crate::editor::objects::gizmo::{GizmoDrag, GizmoHandle};
 use crate::editor::ui::hierarchy::Locked;
 
 use super::types::{SelectionSet, Selected};
@@ -91,9 +91,10 @@ pub fn handle_deselection(
     mut commands: Commands,
     selected_query: Query<Entity, With<Selected>>,
     placement_state: Res<PlacementState>,
+    gizmo_drag: Res<GizmoDrag>,
 ) {
-    // Don't deselect if in placement mode (ESC cancels placement instead)
-    if placement_state.active {
+    // Don't deselect if in placement mode or dragging a gizmo (ESC cancels those instead)
+    if placement_state.active || gizmo_drag.axis.is_some() {
         return;
     }
 
@@ -12,6 +12,8 @@ use std::collections::HashSet;
 #[derive(Resource, Default)]
 pub struct SelectionSet {
     pub entities: HashSet<Entity>,
+    /// Most recently selected entity, used by the Active Object pivot
+    active: Option<Entity>,
 }
 
 impl SelectionSet {
@@ -23,25 +25,30 @@ impl SelectionSet {
     /// Add an entity to the selection
     pub fn add(&mut self, entity: Entity) {
         self.entities.insert(entity);
+        self.active = Some(entity);
     }
 
     /// Remove an entity from the selection
     pub fn remove(&mut self, entity: Entity) {
         self.entities.remove(&entity);
+        if self.active == Some(entity) {
+            self.active = None;
+        }
     }
 
     /// Toggle an entity's selection state
     pub fn toggle(&mut self, entity: Entity) {
         if self.entities.contains(&entity) {
-            self.entities.remove(&entity);
+            self.remove(entity);
         } else {
-            self.entities.insert(entity);
+            self.add(entity);
         }
     }
 
     /// Clear all selections
     pub fn clear(&mut self) {
         self.entities.clear();
+        self.active = None;
     }
 
     /// Get the first selected entity (for backward compatibility)
@@ -49,6 +56,11 @@ impl SelectionSet {
         self.entities.iter().next().copied()
     }
 
+    /// Get the active entity, falling back to any selected entity
+    pub fn active(&self) -> Option<Entity> {
+        self.active.filter(|entity| self.entities.contains(entity)).or_else(|| self.first())
+    }
+
     /// Check if the selection is empty
     pub fn is_empty(&self) -> bool {
         self.entities.is_empty()
@@ -10,23 +10,26 @@
 //!
 //! # Current Support
 //!
-//! - Transform (position, rotation, scale)
-//! - Mesh3d (primitive type)
-//! - MeshMaterial3d (base color only for now)
+//! - Transform (position, rotation, scale) and parent
+//! - Mesh (primitive type) and material (base color only for now)
+//! - Player spawn, rigid body type, GLB model, point and spot lights, groups
 //!
-//! # Future Extensibility
+//! # Adding a Component
 //!
-//! Additional component types can be added by:
-//! 1. Creating a serializable struct in `scene.rs`
-//! 2. Implementing `ComponentSerializer` trait
-//! 3. Registering in `SerializerRegistry`
+//! Each stored component has a `ComponentSerializer` (see `serializers.rs`)
+//! registered in `SerializerRegistry::default`. Editor save, editor load and
+//! the game's scene load all go through the registry, so a new component only
+//! needs its serializer and that one registration.
 
 pub mod events;
 pub mod plugin;
+pub mod registry;
 pub mod scene;
+pub mod serializers;
 pub mod systems;
 
 // Re-export plugin and commonly used types
 pub use events::{NewFileEvent, OpenFileEvent, SaveEvent, SaveAsEvent};
 pub use plugin::PersistencePlugin;
+pub use registry::{LoadContext, SceneTarget, SerializerRegistry};
 pub use systems::{AutoSaveInterval, AutoSaveTimer, AutoSaveNotification, CurrentFile};
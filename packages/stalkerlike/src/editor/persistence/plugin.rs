@@ -2,6 +2,7 @@
 
 use bevy::prelude::*;
 
+use super::registry::SerializerRegistry;
 use super::events::{NewFileEvent, OpenFileEvent, SaveEvent, SaveAsEvent};
 use super::systems::{AutoSaveTimer, AutoSaveNotification, CurrentFile, save_scene_system, load_scene_system, mark_scene_dirty, handle_new_file, handle_save, handle_open_file, handle_save_as, poll_file_open_tasks, poll_save_as_tasks, autosave_system, check_missing_assets};
 
@@ -21,6 +22,7 @@ impl Plugin for PersistencePlugin {
             .init_resource::<CurrentFile>()
             .init_resource::<AutoSaveTimer>()
             .init_resource::<AutoSaveNotification>()
+            .init_resource::<SerializerRegistry>()
 
             // Update systems
             .add_systems(Update, (
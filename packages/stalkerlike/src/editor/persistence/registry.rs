@@ -0,0 +1,190 @@
+//! Component serializer registry shared by the editor and the game
+//!
+//! Every component that can be stored in a scene file has a
+//! `ComponentSerializer`, registered once in `SerializerRegistry::default`.
+//! Saving asks each serializer whether the entity has its component; loading
+//! hands each stored component to the serializer registered under its `type`
+//! tag. The editor and the game load through the same serializers, which only
+//! branch on `SceneTarget` where the two really differ (light gizmos in the
+//! editor, physics in the game).
+
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use super::scene::ComponentData;
+use super::serializers::{
+    DoorSerializer, GlbModelSerializer, GroupSerializer, LootContainerSerializer, MaterialSerializer,
+    MeshSerializer, NpcSpawnSerializer, PatrolWaypointSerializer, PlayerSpawnSerializer,
+    PointLightSerializer, RadiationZoneSerializer, RigidBodySerializer, SpotLightSerializer,
+};
+
+/// Which app a scene is being loaded into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneTarget {
+    Editor,
+    Game,
+}
+
+/// Assets serializers can read while saving
+pub struct SaveContext<'a> {
+    pub meshes: &'a Assets<Mesh>,
+    pub materials: &'a Assets<StandardMaterial>,
+}
+
+/// Assets serializers can add to while loading, and what they are loading into
+pub struct LoadContext<'a> {
+    pub target: SceneTarget,
+    pub meshes: &'a mut Assets<Mesh>,
+    pub materials: &'a mut Assets<StandardMaterial>,
+    pub asset_server: &'a AssetServer,
+    /// `type` tags of every component stored on the entity being loaded
+    stored: Vec<String>,
+}
+
+impl<'a> LoadContext<'a> {
+    pub fn new(
+        target: SceneTarget,
+        meshes: &'a mut Assets<Mesh>,
+        materials: &'a mut Assets<StandardMaterial>,
+        asset_server: &'a AssetServer,
+    ) -> Self {
+        Self { target, meshes, materials, asset_server, stored: Vec::new() }
+    }
+
+    /// Whether the entity being loaded also stores a component of type `S`
+    pub fn has<S: ComponentSerializer>(&self) -> bool {
+        self.stored.iter().any(|type_name| type_name == S::TYPE_NAME)
+    }
+}
+
+/// Converts one component type to and from its scene file form
+pub trait ComponentSerializer: 'static {
+    /// Tag the component is stored under as `type` in the scene file
+    const TYPE_NAME: &'static str;
+    /// Fields stored next to the tag; must serialize as a map
+    type Data: Serialize + DeserializeOwned;
+
+    /// Read the component off an entity, or `None` if it doesn't have one
+    fn save(entity: EntityRef, context: &SaveContext) -> Option<Self::Data>;
+
+    /// Add the component, and whatever the target needs with it, to a loading entity
+    fn load(data: Self::Data, entity: &mut EntityCommands, context: &mut LoadContext);
+}
+
+type SaveFn = fn(EntityRef, &SaveContext) -> Result<Option<Mapping>, serde_yaml::Error>;
+type LoadFn = fn(Mapping, &mut EntityCommands, &mut LoadContext) -> Result<(), serde_yaml::Error>;
+
+struct RegisteredSerializer {
+    type_name: &'static str,
+    save: SaveFn,
+    load: LoadFn,
+}
+
+/// Every component type scenes can store, in the order they are saved and loaded
+#[derive(Resource)]
+pub struct SerializerRegistry {
+    serializers: Vec<RegisteredSerializer>,
+}
+
+impl Default for SerializerRegistry {
+    /// The registry with every built-in scene component
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register::<MeshSerializer>()
+            .register::<MaterialSerializer>()
+            .register::<PlayerSpawnSerializer>()
+            .register::<RigidBodySerializer>()
+            .register::<GlbModelSerializer>()
+            .register::<PointLightSerializer>()
+            .register::<SpotLightSerializer>()
+            .register::<GroupSerializer>()
+            .register::<RadiationZoneSerializer>()
+            .register::<PatrolWaypointSerializer>()
+            .register::<NpcSpawnSerializer>()
+            .register::<DoorSerializer>()
+            .register::<LootContainerSerializer>();
+        registry
+    }
+}
+
+impl SerializerRegistry {
+    /// A registry that knows no components
+    pub fn empty() -> Self {
+        Self { serializers: Vec::new() }
+    }
+
+    /// Add a serializer, replacing any registered under the same tag
+    pub fn register<S: ComponentSerializer>(&mut self) -> &mut Self {
+        self.serializers.retain(|serializer| serializer.type_name != S::TYPE_NAME);
+        self.serializers.push(RegisteredSerializer {
+            type_name: S::TYPE_NAME,
+            save: save_erased::<S>,
+            load: load_erased::<S>,
+        });
+        self
+    }
+
+    /// Every registered component the entity has, in registration order
+    pub fn save_components(&self, entity: EntityRef, context: &SaveContext) -> Result<Vec<ComponentData>, serde_yaml::Error> {
+        let mut components = Vec::new();
+        for serializer in &self.serializers {
+            if let Some(fields) = (serializer.save)(entity, context)? {
+                components.push(ComponentData {
+                    type_name: serializer.type_name.to_string(),
+                    fields,
+                });
+            }
+        }
+        Ok(components)
+    }
+
+    /// Add stored components to a loading entity, in registration order
+    ///
+    /// Components with a tag nothing is registered under are skipped with a
+    /// warning, so scenes saved by a newer build still open.
+    pub fn load_components(
+        &self,
+        components: Vec<ComponentData>,
+        entity: &mut EntityCommands,
+        context: &mut LoadContext,
+    ) -> Result<(), serde_yaml::Error> {
+        context.stored = components.iter().map(|component| component.type_name.clone()).collect();
+
+        for component in &components {
+            if !self.serializers.iter().any(|serializer| serializer.type_name == component.type_name) {
+                warn!("Skipping unknown scene component type '{}'", component.type_name);
+            }
+        }
+
+        for serializer in &self.serializers {
+            for component in components.iter().filter(|component| component.type_name == serializer.type_name) {
+                (serializer.load)(component.fields.clone(), entity, context)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn save_erased<S: ComponentSerializer>(entity: EntityRef, context: &SaveContext) -> Result<Option<Mapping>, serde_yaml::Error> {
+    let Some(data) = S::save(entity, context) else {
+        return Ok(None);
+    };
+    match serde_yaml::to_value(data)? {
+        Value::Mapping(fields) => Ok(Some(fields)),
+        other => Err(serde::ser::Error::custom(format!(
+            "{} must serialize to a map, got {:?}",
+            S::TYPE_NAME,
+            other
+        ))),
+    }
+}
+
+fn load_erased<S: ComponentSerializer>(fields: Mapping, entity: &mut EntityCommands, context: &mut LoadContext) -> Result<(), serde_yaml::Error> {
+    let data = serde_yaml::from_value(Value::Mapping(fields))?;
+    S::load(data, entity, context);
+    Ok(())
+}
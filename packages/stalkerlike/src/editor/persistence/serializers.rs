@@ -0,0 +1,534 @@
+//! Serializers for the built-in scene components
+//!
+//! Registered in `SerializerRegistry::default`. Each one reads its component
+//! off an editor entity when saving, and rebuilds it when a scene is loaded
+//! into the editor or the game.
+
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, RigidBody};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::registry::{ComponentSerializer, LoadContext, SaveContext, SceneTarget};
+use super::scene::{PrimitiveTypeSerde, RigidBodyTypeSerde};
+use crate::editor::core::types::{Door, EditorLight, EditorVisualization, GlbModel, LightType, LootContainer, NpcSpawn, PatrolWaypoint, PlayerSpawn, RadiationZone, RigidBodyType};
+use crate::editor::objects::grouping::Group;
+use crate::editor::objects::primitives::PrimitiveType;
+use crate::game::NeedsCollider;
+
+/// Data for components that are only a marker
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct NoFields {}
+
+fn color_to_array(color: Color) -> [f32; 4] {
+    color.to_srgba().to_f32_array()
+}
+
+fn color_from_array(color: [f32; 4]) -> Color {
+    Color::srgba(color[0], color[1], color[2], color[3])
+}
+
+/// Whether the entity's mesh lives on an editor-only visualization child
+fn has_gizmo(entity: &EntityRef) -> bool {
+    entity.contains::<PointLight>()
+        || entity.contains::<SpotLight>()
+        || entity.contains::<RadiationZone>()
+        || entity.contains::<PatrolWaypoint>()
+        || entity.contains::<NpcSpawn>()
+        || entity.contains::<Door>()
+        || entity.contains::<LootContainer>()
+}
+
+/// Rapier body for a loaded entity, unless a stored `RigidBody` will set one
+fn default_body(context: &LoadContext) -> Option<RigidBody> {
+    (!context.has::<RigidBodySerializer>()).then_some(RigidBody::Fixed)
+}
+
+/// Primitive mesh, rebuilt at its default size (Transform.scale handles the rest)
+pub struct MeshSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MeshData {
+    pub primitive_type: PrimitiveTypeSerde,
+}
+
+impl ComponentSerializer for MeshSerializer {
+    const TYPE_NAME: &'static str = "Mesh";
+    type Data = MeshData;
+
+    fn save(entity: EntityRef, context: &SaveContext) -> Option<MeshData> {
+        // A light's or zone's mesh is its gizmo, rebuilt by the component itself
+        if has_gizmo(&entity) {
+            return None;
+        }
+        let mesh = context.meshes.get(&entity.get::<Mesh3d>()?.0)?;
+        Some(MeshData { primitive_type: identify_primitive_type(mesh).into() })
+    }
+
+    fn load(data: MeshData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        let primitive: PrimitiveType = data.primitive_type.into();
+        // The spawn arrow is only drawn in the editor
+        if context.target == SceneTarget::Game && context.has::<PlayerSpawnSerializer>() {
+            return;
+        }
+
+        let size = primitive.default_size();
+        entity.insert((
+            Mesh3d(context.meshes.add(primitive.create_mesh(size))),
+            MeshMaterial3d(context.materials.add(StandardMaterial {
+                base_color: Color::srgb(0.7, 0.7, 0.7),
+                ..default()
+            })),
+        ));
+
+        if context.target == SceneTarget::Game
+            && let Some(collider) = primitive_collider(primitive, size)
+        {
+            entity.insert(collider);
+            if let Some(body) = default_body(context) {
+                entity.insert(body);
+            }
+        }
+    }
+}
+
+/// Collider matching a primitive's mesh at `size`
+fn primitive_collider(primitive: PrimitiveType, size: Vec3) -> Option<Collider> {
+    match primitive {
+        PrimitiveType::Cube => Some(Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0)),
+        PrimitiveType::Sphere => Some(Collider::ball(size.x / 2.0)),
+        PrimitiveType::Plane => Some(Collider::cuboid(size.x / 2.0, 0.001, size.z / 2.0)),
+        PrimitiveType::Cylinder => Some(Collider::cylinder(size.y / 2.0, size.x / 2.0)),
+        PrimitiveType::Capsule => {
+            let radius = size.x / 2.0;
+            let half_height = (size.y / 2.0) - radius;
+            Some(Collider::capsule_y(half_height.max(0.001), radius))
+        }
+        PrimitiveType::PlayerSpawn
+        | PrimitiveType::PointLight
+        | PrimitiveType::SpotLight
+        | PrimitiveType::RadiationZone
+        | PrimitiveType::PatrolWaypoint
+        | PrimitiveType::NpcSpawn
+        | PrimitiveType::Door
+        | PrimitiveType::LootContainer => None,
+    }
+}
+
+/// Heuristic to identify primitive type from a mesh
+/// This is a temporary solution - ideally we'd store this metadata on the entity
+fn identify_primitive_type(mesh: &Mesh) -> PrimitiveType {
+    // Count vertices to identify the primitive
+    let vertex_count = if let Some(bevy::render::mesh::VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    {
+        positions.len()
+    } else {
+        0
+    };
+
+    // Rough heuristic based on typical vertex counts
+    match vertex_count {
+        24 => PrimitiveType::Cube,       // 4 vertices per face * 6 faces
+        4 => PrimitiveType::Plane,        // Simple plane
+        _ if vertex_count > 100 => PrimitiveType::Sphere, // Ico sphere has many vertices
+        _ if vertex_count > 40 => PrimitiveType::Cylinder, // Cylinder has circular ends
+        _ => PrimitiveType::Capsule,     // Fallback
+    }
+}
+
+/// Material base color (the only material property saved for now)
+pub struct MaterialSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MaterialData {
+    pub base_color: [f32; 4],
+}
+
+impl ComponentSerializer for MaterialSerializer {
+    const TYPE_NAME: &'static str = "Material";
+    type Data = MaterialData;
+
+    fn save(entity: EntityRef, context: &SaveContext) -> Option<MaterialData> {
+        if has_gizmo(&entity) {
+            return None;
+        }
+        let material = context.materials.get(&entity.get::<MeshMaterial3d<StandardMaterial>>()?.0)?;
+        Some(MaterialData { base_color: color_to_array(material.base_color) })
+    }
+
+    fn load(data: MaterialData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        // Registered after the mesh, so this replaces its default material
+        entity.insert(MeshMaterial3d(context.materials.add(StandardMaterial {
+            base_color: color_from_array(data.base_color),
+            ..default()
+        })));
+    }
+}
+
+/// Where the game spawns the player
+pub struct PlayerSpawnSerializer;
+
+impl ComponentSerializer for PlayerSpawnSerializer {
+    const TYPE_NAME: &'static str = "PlayerSpawn";
+    type Data = NoFields;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<NoFields> {
+        entity.contains::<PlayerSpawn>().then_some(NoFields {})
+    }
+
+    fn load(_data: NoFields, entity: &mut EntityCommands, _context: &mut LoadContext) {
+        entity.insert(PlayerSpawn);
+    }
+}
+
+/// Physics body type; a Rapier `RigidBody` in the game
+pub struct RigidBodySerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct RigidBodyData {
+    pub body_type: RigidBodyTypeSerde,
+}
+
+impl ComponentSerializer for RigidBodySerializer {
+    const TYPE_NAME: &'static str = "RigidBody";
+    type Data = RigidBodyData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<RigidBodyData> {
+        entity.get::<RigidBodyType>().map(|&body_type| RigidBodyData { body_type: body_type.into() })
+    }
+
+    fn load(data: RigidBodyData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        let body_type: RigidBodyType = data.body_type.into();
+        match context.target {
+            SceneTarget::Editor => {
+                entity.insert(body_type);
+            }
+            SceneTarget::Game => {
+                entity.insert(match body_type {
+                    RigidBodyType::Fixed => RigidBody::Fixed,
+                    RigidBodyType::Dynamic => RigidBody::Dynamic,
+                });
+            }
+        }
+    }
+}
+
+/// GLB/GLTF model, loaded from its path as a scene
+pub struct GlbModelSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GlbModelData {
+    pub path: String,
+}
+
+impl ComponentSerializer for GlbModelSerializer {
+    const TYPE_NAME: &'static str = "GlbModel";
+    type Data = GlbModelData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<GlbModelData> {
+        entity.get::<GlbModel>().map(|glb| GlbModelData { path: glb.path.to_string_lossy().to_string() })
+    }
+
+    fn load(data: GlbModelData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        let scene_handle = context.asset_server.load(format!("{}#Scene0", data.path));
+        match context.target {
+            SceneTarget::Editor => {
+                entity.insert((
+                    GlbModel { path: PathBuf::from(&data.path) },
+                    SceneRoot(scene_handle),
+                    Visibility::Inherited,
+                ));
+            }
+            SceneTarget::Game => {
+                // Colliders go on the model's meshes once the scene has spawned them
+                entity.insert((SceneRoot(scene_handle), NeedsCollider));
+                if let Some(body) = default_body(context) {
+                    entity.insert(body);
+                }
+            }
+        }
+    }
+}
+
+/// Spawn the mesh that shows where a light or zone is in the editor
+fn spawn_gizmo(entity: &mut EntityCommands, context: &mut LoadContext, primitive: PrimitiveType, color: Color) {
+    let mesh = context.meshes.add(primitive.create_mesh(primitive.default_size()));
+    let material = context.materials.add(StandardMaterial {
+        base_color: color,
+        alpha_mode: if color.alpha() < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque },
+        ..default()
+    });
+    entity.with_child((
+        EditorVisualization,
+        Mesh3d(mesh),
+        MeshMaterial3d(material),
+        Transform::default(),
+    ));
+}
+
+/// Point light
+pub struct PointLightSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PointLightData {
+    pub intensity: f32,
+    pub color: [f32; 4],
+    pub shadows_enabled: bool,
+    pub range: f32,
+}
+
+impl ComponentSerializer for PointLightSerializer {
+    const TYPE_NAME: &'static str = "PointLight";
+    type Data = PointLightData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<PointLightData> {
+        entity.get::<PointLight>().map(|light| PointLightData {
+            intensity: light.intensity,
+            color: color_to_array(light.color),
+            shadows_enabled: light.shadows_enabled,
+            range: light.range,
+        })
+    }
+
+    fn load(data: PointLightData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        entity.insert(PointLight {
+            intensity: data.intensity,
+            color: color_from_array(data.color),
+            shadows_enabled: data.shadows_enabled,
+            range: data.range,
+            ..default()
+        });
+
+        if context.target == SceneTarget::Editor {
+            entity.insert(EditorLight { light_type: LightType::Point });
+            spawn_gizmo(entity, context, PrimitiveType::PointLight, Color::srgb(1.0, 1.0, 0.6));
+        }
+    }
+}
+
+/// Spot light
+pub struct SpotLightSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SpotLightData {
+    pub intensity: f32,
+    pub color: [f32; 4],
+    pub shadows_enabled: bool,
+    pub range: f32,
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+}
+
+impl ComponentSerializer for SpotLightSerializer {
+    const TYPE_NAME: &'static str = "SpotLight";
+    type Data = SpotLightData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<SpotLightData> {
+        entity.get::<SpotLight>().map(|light| SpotLightData {
+            intensity: light.intensity,
+            color: color_to_array(light.color),
+            shadows_enabled: light.shadows_enabled,
+            range: light.range,
+            inner_angle: light.inner_angle,
+            outer_angle: light.outer_angle,
+        })
+    }
+
+    fn load(data: SpotLightData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        entity.insert(SpotLight {
+            intensity: data.intensity,
+            color: color_from_array(data.color),
+            shadows_enabled: data.shadows_enabled,
+            range: data.range,
+            inner_angle: data.inner_angle,
+            outer_angle: data.outer_angle,
+            ..default()
+        });
+
+        if context.target == SceneTarget::Editor {
+            entity.insert(EditorLight { light_type: LightType::Spot });
+            spawn_gizmo(entity, context, PrimitiveType::SpotLight, Color::srgb(1.0, 0.9, 0.6));
+        }
+    }
+}
+
+/// Group of child entities and the pivot it transforms around
+pub struct GroupSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct GroupData {
+    pub pivot_offset: [f32; 3],
+}
+
+impl ComponentSerializer for GroupSerializer {
+    const TYPE_NAME: &'static str = "Group";
+    type Data = GroupData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<GroupData> {
+        entity.get::<Group>().map(|group| GroupData { pivot_offset: group.pivot_offset.to_array() })
+    }
+
+    fn load(data: GroupData, entity: &mut EntityCommands, _context: &mut LoadContext) {
+        entity.insert((
+            Group { pivot_offset: Vec3::from_array(data.pivot_offset) },
+            Visibility::default(),
+        ));
+    }
+}
+
+/// Radiation volume; only the game reacts to it, the editor draws its box
+pub struct RadiationZoneSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct RadiationZoneData {
+    pub intensity: f32,
+}
+
+impl ComponentSerializer for RadiationZoneSerializer {
+    const TYPE_NAME: &'static str = "RadiationZone";
+    type Data = RadiationZoneData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<RadiationZoneData> {
+        entity.get::<RadiationZone>().map(|zone| RadiationZoneData { intensity: zone.intensity })
+    }
+
+    fn load(data: RadiationZoneData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        entity.insert((RadiationZone { intensity: data.intensity }, Visibility::default()));
+
+        if context.target == SceneTarget::Editor {
+            spawn_gizmo(entity, context, PrimitiveType::RadiationZone, Color::srgba(0.4, 1.0, 0.2, 0.25));
+        }
+    }
+}
+
+/// Stop on a patrol route; game NPCs walk the loops these form
+pub struct PatrolWaypointSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PatrolWaypointData {
+    pub network: String,
+    pub order: u32,
+}
+
+impl ComponentSerializer for PatrolWaypointSerializer {
+    const TYPE_NAME: &'static str = "PatrolWaypoint";
+    type Data = PatrolWaypointData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<PatrolWaypointData> {
+        entity.get::<PatrolWaypoint>().map(|waypoint| PatrolWaypointData {
+            network: waypoint.network.clone(),
+            order: waypoint.order,
+        })
+    }
+
+    fn load(data: PatrolWaypointData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        entity.insert((PatrolWaypoint { network: data.network, order: data.order }, Visibility::default()));
+
+        if context.target == SceneTarget::Editor {
+            spawn_gizmo(entity, context, PrimitiveType::PatrolWaypoint, Color::srgb(0.3, 0.6, 1.0));
+        }
+    }
+}
+
+/// Spawn point for NPCs of one archetype
+pub struct NpcSpawnSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NpcSpawnData {
+    pub archetype: String,
+    pub network: String,
+    pub count: u32,
+}
+
+impl ComponentSerializer for NpcSpawnSerializer {
+    const TYPE_NAME: &'static str = "NpcSpawn";
+    type Data = NpcSpawnData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<NpcSpawnData> {
+        entity.get::<NpcSpawn>().map(|spawn| NpcSpawnData {
+            archetype: spawn.archetype.clone(),
+            network: spawn.network.clone(),
+            count: spawn.count,
+        })
+    }
+
+    fn load(data: NpcSpawnData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        entity.insert((
+            NpcSpawn { archetype: data.archetype, network: data.network, count: data.count },
+            Visibility::default(),
+        ));
+
+        if context.target == SceneTarget::Editor {
+            spawn_gizmo(entity, context, PrimitiveType::NpcSpawn, Color::srgb(1.0, 0.5, 0.1));
+        }
+    }
+}
+
+/// Lockable door; the game builds its panel and collider
+pub struct DoorSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DoorData {
+    pub id: String,
+    pub locked: bool,
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub lock_level: u32,
+}
+
+impl ComponentSerializer for DoorSerializer {
+    const TYPE_NAME: &'static str = "Door";
+    type Data = DoorData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<DoorData> {
+        entity.get::<Door>().map(|door| DoorData {
+            id: door.id.clone(),
+            locked: door.locked,
+            key: door.key.clone(),
+            lock_level: door.lock_level,
+        })
+    }
+
+    fn load(data: DoorData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        entity.insert((
+            Door { id: data.id, locked: data.locked, key: data.key, lock_level: data.lock_level },
+            Visibility::default(),
+        ));
+
+        if context.target == SceneTarget::Editor {
+            spawn_gizmo(entity, context, PrimitiveType::Door, Color::srgb(0.45, 0.3, 0.2));
+        }
+    }
+}
+
+/// Container looted once per save; the game builds its crate
+pub struct LootContainerSerializer;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LootContainerData {
+    pub id: String,
+    pub loot_table: String,
+}
+
+impl ComponentSerializer for LootContainerSerializer {
+    const TYPE_NAME: &'static str = "LootContainer";
+    type Data = LootContainerData;
+
+    fn save(entity: EntityRef, _context: &SaveContext) -> Option<LootContainerData> {
+        entity.get::<LootContainer>().map(|container| LootContainerData {
+            id: container.id.clone(),
+            loot_table: container.loot_table.clone(),
+        })
+    }
+
+    fn load(data: LootContainerData, entity: &mut EntityCommands, context: &mut LoadContext) {
+        entity.insert((LootContainer { id: data.id, loot_table: data.loot_table }, Visibility::default()));
+
+        if context.target == SceneTarget::Editor {
+            spawn_gizmo(entity, context, PrimitiveType::LootContainer, Color::srgb(0.35, 0.4, 0.25));
+        }
+    }
+}
@@ -6,9 +6,11 @@ use bevy::asset::LoadState;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
-use crate::editor::persistence::scene::{save_scene, load_scene};
+use crate::editor::persistence::registry::{LoadContext, SceneTarget, SerializerRegistry};
+use crate::editor::persistence::scene::{load_scene, SceneSaver};
 use crate::editor::persistence::events::{NewFileEvent, OpenFileEvent, SaveEvent, SaveAsEvent};
-use crate::editor::core::types::{EditorEntity, PlayerSpawn, RigidBodyType, GlbModel, MissingAsset};
+use crate::editor::core::types::{EditorEntity, GlbModel, MissingAsset};
+use crate::editor::objects::grouping::Group;
 use crate::editor::ui::confirmation_dialog::{ConfirmationDialog, ErrorDialog, PendingAction, AutoSaveRecoveryDialog, AutoSaveChoice};
 
 /// Resource for showing autosave notifications
@@ -121,24 +123,7 @@ pub fn save_scene_system(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut current_file: ResMut<CurrentFile>,
     mut save_as_events: EventWriter<SaveAsEvent>,
-    editor_entities: Query<(
-        Entity,
-        &Transform,
-        Option<&Name>,
-        Option<&Mesh3d>,
-        Option<&MeshMaterial3d<StandardMaterial>>,
-        Option<&PlayerSpawn>,
-        Option<&RigidBodyType>,
-        Option<&GlbModel>,
-        Option<&PointLight>,
-        Option<&SpotLight>,
-    ), With<EditorEntity>>,
-    meshes: Res<Assets<Mesh>>,
-    materials: Res<Assets<StandardMaterial>>,
-    directional_light: Query<(&DirectionalLight, &Transform), Without<EditorEntity>>,
-    ambient_light: Res<AmbientLight>,
-    lighting_enabled: Res<crate::editor::viewport::LightingEnabled>,
-    saved_lighting_state: Res<crate::editor::viewport::SavedLightingState>,
+    scene_saver: SceneSaver,
 ) {
     // Check for Ctrl+S
     if keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight) {
@@ -163,7 +148,7 @@ pub fn save_scene_system(
                 }
             }
 
-            match save_scene(path.clone(), editor_entities, meshes, materials, directional_light, ambient_light, &lighting_enabled, &saved_lighting_state) {
+            match scene_saver.save(path.clone()) {
                 Ok(()) => {
                     info!("Scene saved to {}", path.display());
                     current_file.set_path(path.clone());
@@ -223,6 +208,7 @@ pub fn mark_scene_dirty(
                 Changed<Transform>,
                 Changed<Mesh3d>,
                 Changed<MeshMaterial3d<StandardMaterial>>,
+                Changed<Group>,
             )>,
         ),
     >,
@@ -280,24 +266,7 @@ pub fn handle_save(
     mut events: EventReader<SaveEvent>,
     mut current_file: ResMut<CurrentFile>,
     mut error_dialog: ResMut<ErrorDialog>,
-    editor_entities: Query<(
-        Entity,
-        &Transform,
-        Option<&Name>,
-        Option<&Mesh3d>,
-        Option<&MeshMaterial3d<StandardMaterial>>,
-        Option<&PlayerSpawn>,
-        Option<&RigidBodyType>,
-        Option<&GlbModel>,
-        Option<&PointLight>,
-        Option<&SpotLight>,
-    ), With<EditorEntity>>,
-    meshes: Res<Assets<Mesh>>,
-    materials: Res<Assets<StandardMaterial>>,
-    directional_light: Query<(&DirectionalLight, &Transform), Without<EditorEntity>>,
-    ambient_light: Res<AmbientLight>,
-    lighting_enabled: Res<crate::editor::viewport::LightingEnabled>,
-    saved_lighting_state: Res<crate::editor::viewport::SavedLightingState>,
+    scene_saver: SceneSaver,
 ) {
     for _ in events.read() {
         // Skip save if no file is open - should not happen since button is disabled
@@ -322,7 +291,7 @@ pub fn handle_save(
             }
         }
 
-        match save_scene(path.clone(), editor_entities, Res::clone(&meshes), Res::clone(&materials), directional_light, Res::clone(&ambient_light), &lighting_enabled, &saved_lighting_state) {
+        match scene_saver.save(path.clone()) {
             Ok(()) => {
                 info!("Scene saved to {}", path.display());
                 current_file.set_path(path);
@@ -374,6 +343,7 @@ pub fn poll_file_open_tasks(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
+    registry: Res<SerializerRegistry>,
     mut tasks: Query<(Entity, &mut FileOpenTask)>,
     editor_entities: Query<Entity, With<EditorEntity>>,
     existing_lights: Query<Entity, With<DirectionalLight>>,
@@ -408,7 +378,7 @@ pub fn poll_file_open_tasks(
             commands.entity(entity).despawn();
         }
 
-        match load_scene(load_path.clone(), &mut commands, &mut meshes, &mut materials, &asset_server) {
+        match load_scene(load_path.clone(), &mut commands, &registry, &mut LoadContext::new(SceneTarget::Editor, &mut meshes, &mut materials, &asset_server)) {
             Ok(()) => {
                 info!("Scene loaded from {}", load_path.display());
                 // Always set the current file to the original path, not the autosave
@@ -465,7 +435,7 @@ pub fn poll_file_open_tasks(
                     commands.entity(entity).despawn();
                 }
 
-                match load_scene(path.clone(), &mut commands, &mut meshes, &mut materials, &asset_server) {
+                match load_scene(path.clone(), &mut commands, &registry, &mut LoadContext::new(SceneTarget::Editor, &mut meshes, &mut materials, &asset_server)) {
                     Ok(()) => {
                         info!("Scene loaded from {}", path.display());
                         current_file.set_path(path);
@@ -515,25 +485,8 @@ pub fn poll_save_as_tasks(
     mut commands: Commands,
     mut current_file: ResMut<CurrentFile>,
     mut error_dialog: ResMut<ErrorDialog>,
-    mut tasks: Query<(Entity, &mut FileSaveTask)>,
-    editor_entities: Query<(
-        Entity,
-        &Transform,
-        Option<&Name>,
-        Option<&Mesh3d>,
-        Option<&MeshMaterial3d<StandardMaterial>>,
-        Option<&PlayerSpawn>,
-        Option<&RigidBodyType>,
-        Option<&GlbModel>,
-        Option<&PointLight>,
-        Option<&SpotLight>,
-    ), With<EditorEntity>>,
-    meshes: Res<Assets<Mesh>>,
-    materials: Res<Assets<StandardMaterial>>,
-    directional_light: Query<(&DirectionalLight, &Transform), Without<EditorEntity>>,
-    ambient_light: Res<AmbientLight>,
-    lighting_enabled: Res<crate::editor::viewport::LightingEnabled>,
-    saved_lighting_state: Res<crate::editor::viewport::SavedLightingState>,
+    mut tasks: Query<(Entity, &mut FileSaveTask), Without<EditorEntity>>,
+    scene_saver: SceneSaver,
 ) {
     for (task_entity, mut task) in tasks.iter_mut() {
         if let Some(result) = block_on(futures_lite::future::poll_once(&mut task.0)) {
@@ -553,7 +506,7 @@ pub fn poll_save_as_tasks(
                     }
                 }
 
-                match save_scene(path.clone(), editor_entities, Res::clone(&meshes), Res::clone(&materials), directional_light, Res::clone(&ambient_light), &lighting_enabled, &saved_lighting_state) {
+                match scene_saver.save(path.clone()) {
                     Ok(()) => {
                         info!("Scene saved as {}", path.display());
 
@@ -675,24 +628,7 @@ pub fn autosave_system(
     mut timer: ResMut<AutoSaveTimer>,
     current_file: Res<CurrentFile>,
     mut notification: ResMut<AutoSaveNotification>,
-    editor_entities: Query<(
-        Entity,
-        &Transform,
-        Option<&Name>,
-        Option<&Mesh3d>,
-        Option<&MeshMaterial3d<StandardMaterial>>,
-        Option<&PlayerSpawn>,
-        Option<&RigidBodyType>,
-        Option<&GlbModel>,
-        Option<&PointLight>,
-        Option<&SpotLight>,
-    ), With<EditorEntity>>,
-    meshes: Res<Assets<Mesh>>,
-    materials: Res<Assets<StandardMaterial>>,
-    directional_light: Query<(&DirectionalLight, &Transform), Without<EditorEntity>>,
-    ambient_light: Res<AmbientLight>,
-    lighting_enabled: Res<crate::editor::viewport::LightingEnabled>,
-    saved_lighting_state: Res<crate::editor::viewport::SavedLightingState>,
+    scene_saver: SceneSaver,
 ) {
     // Tick the timer
     timer.timer.tick(time.delta());
@@ -729,7 +665,7 @@ pub fn autosave_system(
             }
         }
 
-        match save_scene(autosave_path.clone(), editor_entities, meshes, materials, directional_light, ambient_light, &lighting_enabled, &saved_lighting_state) {
+        match scene_saver.save(autosave_path.clone()) {
             Ok(()) => {
                 info!("Autosaved scene to {}", autosave_path.display());
 
@@ -0,0 +1,92 @@
+//! Array duplicate tool window
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::editor::objects::array::{ArrayMode, ArrayTool};
+use crate::editor::objects::selection::SelectionSet;
+use crate::editor::viewport::EditorCursor;
+
+/// System to render the array tool window
+pub fn array_tool_ui(
+    mut contexts: EguiContexts,
+    mut array_tool: ResMut<ArrayTool>,
+    selection: Res<SelectionSet>,
+    cursor: Res<EditorCursor>,
+) {
+    if !array_tool.show {
+        return;
+    }
+
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let mut should_close = false;
+    let tool = &mut *array_tool;
+
+    egui::Window::new("Array Duplicate")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut tool.mode, ArrayMode::Offset, "Offset");
+                ui.radio_value(&mut tool.mode, ArrayMode::BetweenPoints, "Between Points");
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Copies:");
+                ui.add(egui::DragValue::new(&mut tool.count).range(1..=200));
+            });
+
+            match tool.mode {
+                ArrayMode::Offset => {
+                    vec3_row(ui, "Step:", &mut tool.step);
+                }
+                ArrayMode::BetweenPoints => {
+                    vec3_row(ui, "Start:", &mut tool.start);
+                    if ui.button("Start at 3D cursor").clicked() {
+                        tool.start = cursor.position;
+                    }
+                    vec3_row(ui, "End:", &mut tool.end);
+                    if ui.button("End at 3D cursor").clicked() {
+                        tool.end = cursor.position;
+                    }
+                }
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Rotation per copy:");
+                ui.add(egui::DragValue::new(&mut tool.rotation_step).speed(1.0).suffix("°"));
+            });
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let apply = ui.add_enabled(!selection.is_empty(), egui::Button::new("Apply"));
+                if apply.clicked() {
+                    tool.apply_requested = true;
+                }
+                if ui.button("Close").clicked() {
+                    should_close = true;
+                }
+            });
+
+            if selection.is_empty() {
+                ui.colored_label(egui::Color32::GRAY, "Select something to duplicate");
+            }
+        });
+
+    if should_close {
+        array_tool.show = false;
+    }
+}
+
+fn vec3_row(ui: &mut egui::Ui, label: &str, value: &mut Vec3) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(egui::DragValue::new(&mut value.x).speed(0.1).prefix("X: "));
+        ui.add(egui::DragValue::new(&mut value.y).speed(0.1).prefix("Y: "));
+        ui.add(egui::DragValue::new(&mut value.z).speed(0.1).prefix("Z: "));
+    });
+}
@@ -4,8 +4,9 @@ use bevy_egui::{egui, EguiContexts};
 use std::any::TypeId;
 use std::collections::HashMap;
 
+use crate::editor::objects::grouping::Group;
 use crate::editor::objects::selection::SelectionSet;
-use crate::editor::core::types::{RigidBodyType, EditorLight, LightType, EditorEntity};
+use crate::editor::core::types::{RigidBodyType, EditorLight, LightType, EditorEntity, RadiationZone, PatrolWaypoint, NpcSpawn, Door, LootContainer};
 use crate::editor::viewport::LightingEnabled;
 
 // ============================================================================
@@ -837,6 +838,233 @@ impl Inspectable for SpotLight {
     }
 }
 
+impl Inspectable for Group {
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property {
+                name: "Pivot Offset".to_string(),
+                value: PropertyValue::Vec3(self.pivot_offset),
+                metadata: PropertyMetadata {
+                    step: Some(0.1),
+                    tooltip: Some("Pivot relative to the group origin, used by the Custom pivot mode".to_string()),
+                    ..default()
+                },
+            },
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) {
+        if name == "Pivot Offset" {
+            if let PropertyValue::Vec3(v) = value {
+                self.pivot_offset = v;
+            }
+        }
+    }
+
+    fn display_name() -> &'static str {
+        "Group"
+    }
+}
+
+impl Inspectable for RadiationZone {
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property {
+                name: "Intensity".to_string(),
+                value: PropertyValue::Float(self.intensity),
+                metadata: PropertyMetadata {
+                    step: Some(0.5),
+                    min: Some(0.0),
+                    tooltip: Some("Dose rate at the center in rads per second; size the zone with Scale".to_string()),
+                    ..default()
+                },
+            },
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) {
+        if name == "Intensity"
+            && let PropertyValue::Float(v) = value
+        {
+            self.intensity = v;
+        }
+    }
+
+    fn display_name() -> &'static str {
+        "Radiation Zone"
+    }
+}
+
+impl Inspectable for PatrolWaypoint {
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property {
+                name: "Network".to_string(),
+                value: PropertyValue::String(self.network.clone()),
+                metadata: PropertyMetadata {
+                    tooltip: Some("Waypoints with the same network form one patrol loop".to_string()),
+                    ..default()
+                },
+            },
+            Property {
+                name: "Order".to_string(),
+                value: PropertyValue::Float(self.order as f32),
+                metadata: PropertyMetadata {
+                    step: Some(1.0),
+                    min: Some(0.0),
+                    tooltip: Some("Position of this stop along the loop".to_string()),
+                    ..default()
+                },
+            },
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) {
+        match (name, value) {
+            ("Network", PropertyValue::String(v)) => self.network = v,
+            ("Order", PropertyValue::Float(v)) => self.order = v.round().max(0.0) as u32,
+            _ => {}
+        }
+    }
+
+    fn display_name() -> &'static str {
+        "Patrol Waypoint"
+    }
+}
+
+impl Inspectable for NpcSpawn {
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property {
+                name: "Archetype".to_string(),
+                value: PropertyValue::String(self.archetype.clone()),
+                metadata: PropertyMetadata {
+                    tooltip: Some("NPC archetype id from the data packs".to_string()),
+                    ..default()
+                },
+            },
+            Property {
+                name: "Network".to_string(),
+                value: PropertyValue::String(self.network.clone()),
+                metadata: PropertyMetadata {
+                    tooltip: Some("Patrol network the NPCs walk".to_string()),
+                    ..default()
+                },
+            },
+            Property {
+                name: "Count".to_string(),
+                value: PropertyValue::Float(self.count as f32),
+                metadata: PropertyMetadata {
+                    step: Some(1.0),
+                    min: Some(1.0),
+                    ..default()
+                },
+            },
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) {
+        match (name, value) {
+            ("Archetype", PropertyValue::String(v)) => self.archetype = v,
+            ("Network", PropertyValue::String(v)) => self.network = v,
+            ("Count", PropertyValue::Float(v)) => self.count = v.round().max(1.0) as u32,
+            _ => {}
+        }
+    }
+
+    fn display_name() -> &'static str {
+        "NPC Spawn"
+    }
+}
+
+impl Inspectable for Door {
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property {
+                name: "Id".to_string(),
+                value: PropertyValue::String(self.id.clone()),
+                metadata: PropertyMetadata {
+                    tooltip: Some("Name the save file knows this door by; keep it unique".to_string()),
+                    ..default()
+                },
+            },
+            Property {
+                name: "Locked".to_string(),
+                value: PropertyValue::Bool(self.locked),
+                metadata: PropertyMetadata::default(),
+            },
+            Property {
+                name: "Key".to_string(),
+                value: PropertyValue::String(self.key.clone()),
+                metadata: PropertyMetadata {
+                    tooltip: Some("Item id from the data packs that unlocks it; empty for none".to_string()),
+                    ..default()
+                },
+            },
+            Property {
+                name: "Lock Level".to_string(),
+                value: PropertyValue::Float(self.lock_level as f32),
+                metadata: PropertyMetadata {
+                    step: Some(1.0),
+                    min: Some(0.0),
+                    max: Some(5.0),
+                    tooltip: Some("How hard the lock is to pick; 0 can't be picked".to_string()),
+                    ..default()
+                },
+            },
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) {
+        match (name, value) {
+            ("Id", PropertyValue::String(v)) => self.id = v,
+            ("Locked", PropertyValue::Bool(v)) => self.locked = v,
+            ("Key", PropertyValue::String(v)) => self.key = v,
+            ("Lock Level", PropertyValue::Float(v)) => self.lock_level = v.round().clamp(0.0, 5.0) as u32,
+            _ => {}
+        }
+    }
+
+    fn display_name() -> &'static str {
+        "Door"
+    }
+}
+
+impl Inspectable for LootContainer {
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property {
+                name: "Id".to_string(),
+                value: PropertyValue::String(self.id.clone()),
+                metadata: PropertyMetadata {
+                    tooltip: Some("Name the save file knows this container by; keep it unique".to_string()),
+                    ..default()
+                },
+            },
+            Property {
+                name: "Loot Table".to_string(),
+                value: PropertyValue::String(self.loot_table.clone()),
+                metadata: PropertyMetadata {
+                    tooltip: Some("Loot table id from the data packs".to_string()),
+                    ..default()
+                },
+            },
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) {
+        match (name, value) {
+            ("Id", PropertyValue::String(v)) => self.id = v,
+            ("Loot Table", PropertyValue::String(v)) => self.loot_table = v,
+            _ => {}
+        }
+    }
+
+    fn display_name() -> &'static str {
+        "Loot Container"
+    }
+}
+
 // ============================================================================
 // Inspector UI System
 // ============================================================================
@@ -1035,5 +1263,11 @@ pub fn init_inspector_registry(app: &mut App) {
     registry.register::<RigidBodyType>();
     registry.register::<PointLight>();
     registry.register::<SpotLight>();
+    registry.register::<Group>();
+    registry.register::<RadiationZone>();
+    registry.register::<PatrolWaypoint>();
+    registry.register::<NpcSpawn>();
+    registry.register::<Door>();
+    registry.register::<LootContainer>();
     // Add more components as needed
 }
@@ -3,6 +3,7 @@ use bevy_egui::{egui, EguiContexts};
 
 use crate::editor::persistence::{AutoSaveInterval, AutoSaveTimer, CurrentFile, NewFileEvent, OpenFileEvent, SaveEvent, SaveAsEvent};
 use crate::editor::ui::confirmation_dialog::{ConfirmationDialog, PendingAction};
+use crate::editor::objects::array::ArrayTool;
 use crate::editor::ui::shortcuts::ShortcutsPanel;
 
 /// Render the top menu bar
@@ -12,6 +13,7 @@ pub fn menu_bar_ui(
     mut autosave_timer: ResMut<AutoSaveTimer>,
     mut dialog: ResMut<ConfirmationDialog>,
     mut shortcuts_panel: ResMut<ShortcutsPanel>,
+    mut array_tool: ResMut<ArrayTool>,
     mut new_file_events: EventWriter<NewFileEvent>,
     mut open_file_events: EventWriter<OpenFileEvent>,
     mut save_events: EventWriter<SaveEvent>,
@@ -89,6 +91,13 @@ pub fn menu_bar_ui(
                 });
             });
 
+            ui.menu_button("Edit", |ui| {
+                if ui.button("Array Duplicate... (Ctrl+R)").clicked() {
+                    array_tool.show = true;
+                    ui.close();
+                }
+            });
+
             ui.menu_button("Help", |ui| {
                 if ui.button("Keyboard Shortcuts (F1)").clicked() {
                     shortcuts_panel.toggle();
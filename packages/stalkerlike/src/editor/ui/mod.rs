@@ -7,8 +7,10 @@
 //! - Inspector (property editing for selected objects)
 //! - Status bar (editor state indicators)
 //! - Confirmation dialog (unsaved changes prompt)
+//! - Array duplicate tool window
 //! - Future: Toolbar, settings
 
+pub mod array_tool;
 pub mod asset_browser;
 pub mod confirmation_dialog;
 pub mod hierarchy;
@@ -3,6 +3,7 @@
 use bevy::prelude::*;
 use bevy_egui::{EguiPlugin, EguiPrimaryContextPass};
 
+use super::array_tool::array_tool_ui;
 use super::asset_browser::{asset_browser_ui, AssetBrowserSections};
 use super::confirmation_dialog::{
     ConfirmationDialog, ErrorDialog, AutoSaveRecoveryDialog,
@@ -44,6 +45,7 @@ impl Plugin for UiPlugin {
                 error_dialog_ui,
                 autosave_recovery_dialog_ui,
                 shortcuts_panel_ui,
+                array_tool_ui,
                 status_bar_ui,
                 asset_browser_ui,  // Left panel - asset selection
                 hierarchy_ui,      // Right panel - scene tree
@@ -81,9 +81,11 @@ pub fn shortcuts_panel_ui(
             shortcut_row(ui, "Ctrl+Click", "Multi-select (add/remove)");
             shortcut_row(ui, "Escape", "Deselect all / Cancel mode");
             shortcut_row(ui, "Ctrl+D", "Duplicate selected");
+            shortcut_row(ui, "Ctrl+R", "Array duplicate (offset or between points)");
             shortcut_row(ui, "Delete", "Delete selected");
             shortcut_row(ui, "Ctrl+G", "Group selected");
             shortcut_row(ui, "Ctrl+Shift+G", "Ungroup selected");
+            shortcut_row(ui, "Ctrl+.", "Set group pivot to 3D cursor");
 
             ui.add_space(10.0);
 
@@ -93,9 +95,13 @@ pub fn shortcuts_panel_ui(
             shortcut_row(ui, "F", "Cycle gizmo mode (→)");
             shortcut_row(ui, "Shift+F", "Cycle gizmo mode (←)");
             shortcut_row(ui, "O", "Toggle Local/Global space");
+            shortcut_row(ui, ".", "Cycle pivot (center, active, cursor, custom)");
+            shortcut_row(ui, "Shift+Right Click", "Place 3D cursor");
             shortcut_row(ui, "G", "Toggle grid snapping");
             shortcut_row(ui, "Shift (drag)", "Move faster (4x speed)");
             shortcut_row(ui, "Ctrl (drag)", "Move slower (0.25x speed)");
+            shortcut_row(ui, "0-9 . - (drag)", "Type an exact move, angle or scale factor");
+            shortcut_row(ui, "Enter / Esc (drag)", "Apply typed value / Cancel the drag");
 
             ui.add_space(10.0);
 
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
 use crate::editor::viewport::grid::GridConfig;
-use crate::editor::objects::gizmo::GizmoState;
+use crate::editor::objects::gizmo::{GizmoDrag, GizmoState, TransformMode};
 use crate::editor::persistence::{AutoSaveTimer, AutoSaveNotification, CurrentFile};
 
 /// Render the status bar at the bottom of the screen
@@ -11,6 +11,7 @@ pub fn status_bar_ui(
     time: Res<Time>,
     grid_config: Res<GridConfig>,
     gizmo_state: Res<GizmoState>,
+    gizmo_drag: Res<GizmoDrag>,
     current_file: Res<CurrentFile>,
     autosave_timer: Res<AutoSaveTimer>,
     mut notification: ResMut<AutoSaveNotification>,
@@ -38,6 +39,24 @@ pub fn status_bar_ui(
 
                 ui.separator();
 
+                // Value being typed during a gizmo drag
+                if let Some(axis) = gizmo_drag.axis.filter(|_| gizmo_drag.is_active() && !gizmo_drag.typed.is_empty()) {
+                    let unit = match gizmo_state.mode {
+                        TransformMode::Translate => "m",
+                        TransformMode::Rotate => "°",
+                        TransformMode::Scale => "x",
+                    };
+                    let input_text = format!("{:?}: {}{} (Enter to apply, Esc to cancel)", axis, gizmo_drag.typed, unit);
+                    ui.colored_label(egui::Color32::YELLOW, input_text);
+                    ui.separator();
+                }
+
+                // Pivot indicator
+                let pivot_text = format!("Pivot: {}", gizmo_state.pivot.label());
+                ui.colored_label(egui::Color32::LIGHT_BLUE, pivot_text);
+
+                ui.separator();
+
                 // Grid snap indicator
                 let snap_text = if grid_config.snap_enabled {
                     "Grid Snap: ON"
@@ -86,7 +105,7 @@ pub fn status_bar_ui(
                 ui.separator();
 
                 // Help text
-                ui.label("F: cycle mode | G: toggle snap | Del: delete | Ctrl+S: save | Ctrl+O: load");
+                ui.label("F: cycle mode | .: cycle pivot | G: toggle snap | Del: delete | Ctrl+S: save | Ctrl+O: load");
             });
         });
 }
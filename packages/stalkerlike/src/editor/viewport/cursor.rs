@@ -0,0 +1,70 @@
+//! 3D cursor
+//!
+//! A point in the scene the user places with Shift+Right Click on the ground
+//! plane. The gizmo can use it as the pivot for rotating and scaling, and
+//! groups can take it as their stored pivot (Ctrl+.).
+
+use bevy::prelude::*;
+
+use super::camera::EditorCamera;
+use super::grid::{GridConfig, snap_to_grid};
+use super::raycasting::ray_plane_intersection;
+
+/// Size of the cursor cross in world units
+const CURSOR_SIZE: f32 = 0.3;
+
+/// Resource holding the 3D cursor position
+#[derive(Resource, Default)]
+pub struct EditorCursor {
+    pub position: Vec3,
+}
+
+/// Move the 3D cursor to the ground point under the mouse on Shift+Right Click
+pub fn place_editor_cursor(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
+    windows: Query<&Window>,
+    grid_config: Res<GridConfig>,
+    mut cursor: ResMut<EditorCursor>,
+) {
+    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    if !shift || !mouse_input.just_pressed(MouseButton::Right) {
+        return;
+    }
+
+    let (Ok((camera, camera_transform)), Ok(window)) = (camera_query.single(), windows.single()) else {
+        return;
+    };
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world(camera_transform, position).ok())
+    else {
+        return;
+    };
+
+    let Some(distance) = ray_plane_intersection(ray.origin, ray.direction.as_vec3(), Vec3::ZERO, Vec3::Y) else {
+        return;
+    };
+
+    let mut point = ray.origin + ray.direction.as_vec3() * distance;
+    if grid_config.snap_enabled {
+        point = snap_to_grid(point, grid_config.spacing);
+    }
+    cursor.position = point;
+    info!("3D cursor placed at {:?}", point);
+}
+
+/// Draw the 3D cursor as a small cross with a ring on the ground
+pub fn draw_editor_cursor(cursor: Res<EditorCursor>, mut gizmos: Gizmos) {
+    let color = Color::srgb(1.0, 0.4, 0.1);
+    let center = cursor.position;
+    for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+        gizmos.line(center - axis * CURSOR_SIZE, center + axis * CURSOR_SIZE, color);
+    }
+    gizmos.circle(
+        Isometry3d::new(center, Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+        CURSOR_SIZE * 0.6,
+        color,
+    );
+}
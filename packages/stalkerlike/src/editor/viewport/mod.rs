@@ -4,8 +4,10 @@
 //! - Camera controls (fly camera with WASD + mouse look)
 //! - Grid rendering and snapping
 //! - Ray-plane intersection utilities for object placement
+//! - The 3D cursor used as a transform pivot
 
 pub mod camera;
+pub mod cursor;
 pub mod grid;
 pub mod plugin;
 pub mod raycasting;
@@ -15,5 +17,6 @@ pub use plugin::{ViewportPlugin, LightingEnabled, SavedLightingState};
 
 // Re-export commonly used types for other modules
 pub use camera::EditorCamera;
+pub use cursor::EditorCursor;
 pub use grid::{GridConfig, snap_to_grid};
 pub use raycasting::ray_plane_intersection;
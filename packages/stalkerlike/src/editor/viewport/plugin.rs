@@ -3,6 +3,7 @@
 use bevy::prelude::*;
 
 use super::camera::{setup_editor_camera, toggle_mouse_lock, camera_look, camera_movement, lock_cursor_on_start};
+use super::cursor::{EditorCursor, place_editor_cursor, draw_editor_cursor};
 use super::grid::{GridConfig, setup_grid, toggle_snap};
 
 /// Resource to track whether custom lighting is enabled
@@ -35,7 +36,7 @@ impl Default for SavedLightingState {
     }
 }
 
-/// Plugin for viewport functionality (camera, grid, raycasting)
+/// Plugin for viewport functionality (camera, grid, raycasting, 3D cursor)
 pub struct ViewportPlugin;
 
 impl Plugin for ViewportPlugin {
@@ -43,6 +44,7 @@ impl Plugin for ViewportPlugin {
         app
             // Resources
             .init_resource::<GridConfig>()
+            .init_resource::<EditorCursor>()
             .init_resource::<LightingEnabled>()
             .init_resource::<SavedLightingState>()
 
@@ -61,6 +63,8 @@ impl Plugin for ViewportPlugin {
                 camera_movement,
                 toggle_snap,
                 update_lighting_mode,
+                place_editor_cursor,
+                draw_editor_cursor,
             ));
     }
 }
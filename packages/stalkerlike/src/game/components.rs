@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Marker component for the player entity
 #[derive(Component)]
@@ -61,6 +62,61 @@ impl Default for Health {
     }
 }
 
-/// Marker component for entities that should be cleaned up when leaving game state
+/// Radiation the player has absorbed and is currently standing in
+#[derive(Component, Default, Clone, Debug)]
+pub struct RadiationDose {
+    /// Accumulated dose in rads
+    pub dose: f32,
+    /// Dose rate around the player this frame, before gear, in rads per second
+    pub exposure: f32,
+}
+
+/// Data pack faction an NPC, or the player, fights for
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct FactionMember(pub String);
+
+/// Ids of the data pack items the player is wearing
+#[derive(Component, Default, Clone, Debug)]
+pub struct EquippedGear(pub Vec<String>);
+
+/// Data pack items the player carries, by id, with how many of each
+#[derive(Component, Default, Clone, Debug)]
+pub struct Inventory(pub BTreeMap<String, u32>);
+
+impl Inventory {
+    pub fn count(&self, item: &str) -> u32 {
+        self.0.get(item).copied().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, item: &str, count: u32) {
+        *self.0.entry(item.to_string()).or_default() += count;
+    }
+
+    /// Take one of `item`, returning false if there is none
+    pub fn take_one(&mut self, item: &str) -> bool {
+        match self.0.get_mut(item) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.0.remove(item);
+            }
+            None => return false,
+        }
+        true
+    }
+}
+
+/// On a scene `Door` that started locked and has since been unlocked
+#[derive(Component)]
+pub struct Unlocked;
+
+/// On a scene `Door` that stands open
+#[derive(Component)]
+pub struct Opened;
+
+/// On a scene `LootContainer` that has been emptied
 #[derive(Component)]
+pub struct Looted;
+
+/// Marker component for entities that should be cleaned up when leaving game state
+#[derive(Component, Clone)]
 pub struct GameEntity;
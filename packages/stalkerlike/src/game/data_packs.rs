@@ -0,0 +1,368 @@
+//! Item, NPC and loot definitions loaded from RON data packs
+//!
+//! Every subdirectory of `DATA_PACK_DIR` is a pack and may hold `items.ron`,
+//! `npcs.ron`, `factions.ron` and `loot.ron`, each a list of definitions. Packs load in name
+//! order, and a definition with an id an earlier pack already used replaces
+//! it, so a mod pack can override the base content. Problems are collected in
+//! `GameData::errors` and shown on the main menu instead of stopping the game.
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::resources::GameState;
+
+/// Directory scanned for data packs, relative to the workspace root like the scenes
+pub const DATA_PACK_DIR: &str = "packages/stalkerlike/assets/data_packs";
+/// Faction id the player belongs to, which factions can name as hostile
+pub const PLAYER_FACTION: &str = "player";
+
+pub struct DataPackPlugin;
+
+impl Plugin for DataPackPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<ReloadDataPacksEvent>()
+            .add_systems(Startup, load_data_packs)
+            .add_systems(Update, reload_data_packs.run_if(in_state(GameState::MainMenu)));
+    }
+}
+
+/// An item that can be carried, traded or dropped
+#[derive(Deserialize, Debug, Clone)]
+pub struct Item {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Weight in kilograms
+    pub weight: f32,
+    /// Trade value in roubles
+    pub value: u32,
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u32,
+    /// Share of radiation kept out while worn, from 0 to 1
+    #[serde(default)]
+    pub radiation_protection: f32,
+}
+
+fn default_max_stack() -> u32 {
+    1
+}
+
+/// A kind of NPC the world can spawn
+#[derive(Deserialize, Debug, Clone)]
+pub struct NpcArchetype {
+    pub id: String,
+    pub name: String,
+    pub faction: String,
+    pub health: f32,
+    /// Loot table rolled when the NPC dies
+    #[serde(default)]
+    pub loot_table: Option<String>,
+}
+
+/// A group NPCs belong to, and who it fights
+#[derive(Deserialize, Debug, Clone)]
+pub struct Faction {
+    pub id: String,
+    pub name: String,
+    /// Factions this one attacks on sight; hostility goes both ways
+    #[serde(default)]
+    pub hostile_to: Vec<String>,
+    /// Body color of its NPCs
+    #[serde(default = "default_faction_color")]
+    pub color: [f32; 3],
+}
+
+fn default_faction_color() -> [f32; 3] {
+    [0.6, 0.6, 0.6]
+}
+
+/// Weighted item drops
+#[derive(Deserialize, Debug, Clone)]
+pub struct LootTable {
+    pub id: String,
+    /// How many entries are picked
+    #[serde(default = "default_rolls")]
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    /// Pick `rolls` entries by weight, each with a count between its min and max
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<(String, u32)> {
+        let total: u32 = self.entries.iter().map(|entry| entry.weight).sum();
+        if total == 0 {
+            return Vec::new();
+        }
+        (0..self.rolls)
+            .filter_map(|_| {
+                let mut pick = rng.random_range(0..total);
+                let entry = self.entries.iter().find(|entry| {
+                    if pick < entry.weight {
+                        return true;
+                    }
+                    pick -= entry.weight;
+                    false
+                })?;
+                Some((entry.item.clone(), rng.random_range(entry.min..=entry.max.max(entry.min))))
+            })
+            .collect()
+    }
+}
+
+fn default_rolls() -> u32 {
+    1
+}
+
+/// One possible drop in a loot table
+#[derive(Deserialize, Debug, Clone)]
+pub struct LootEntry {
+    pub item: String,
+    /// Chance relative to the table's other entries
+    pub weight: u32,
+    #[serde(default = "default_count")]
+    pub min: u32,
+    #[serde(default = "default_count")]
+    pub max: u32,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+/// A problem found in a data pack file
+#[derive(Debug, Clone)]
+pub struct DataPackError {
+    pub file: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for DataPackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file.display(), self.message)
+    }
+}
+
+/// Every definition from the loaded data packs, by id
+#[derive(Resource, Default, Debug)]
+pub struct GameData {
+    pub items: HashMap<String, Item>,
+    pub npcs: HashMap<String, NpcArchetype>,
+    pub factions: HashMap<String, Faction>,
+    pub loot_tables: HashMap<String, LootTable>,
+    /// Names of the packs that were loaded, in load order
+    pub packs: Vec<String>,
+    pub errors: Vec<DataPackError>,
+    /// File each definition came from, by kind and id
+    origins: HashMap<(&'static str, String), PathBuf>,
+}
+
+impl GameData {
+    /// Load and validate every pack under `dir`
+    pub fn load(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref();
+        let mut data = Self::default();
+
+        let mut pack_dirs: Vec<PathBuf> = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir())
+                .collect(),
+            Err(e) => {
+                data.error(dir, format!("cannot read data pack directory: {}", e));
+                return data;
+            }
+        };
+        pack_dirs.sort();
+
+        for pack_dir in pack_dirs {
+            data.load_pack(&pack_dir);
+        }
+        data.validate();
+        data
+    }
+
+    fn load_pack(&mut self, pack_dir: &Path) {
+        self.packs.push(pack_dir.file_name().unwrap_or_default().to_string_lossy().to_string());
+
+        let file = pack_dir.join("items.ron");
+        for item in self.read_list::<Item>(&file) {
+            let id = item.id.clone();
+            self.merge(&file, "item", id, |data| &mut data.items, item);
+        }
+        let file = pack_dir.join("npcs.ron");
+        for npc in self.read_list::<NpcArchetype>(&file) {
+            let id = npc.id.clone();
+            self.merge(&file, "NPC", id, |data| &mut data.npcs, npc);
+        }
+        let file = pack_dir.join("factions.ron");
+        for faction in self.read_list::<Faction>(&file) {
+            let id = faction.id.clone();
+            self.merge(&file, "faction", id, |data| &mut data.factions, faction);
+        }
+        let file = pack_dir.join("loot.ron");
+        for table in self.read_list::<LootTable>(&file) {
+            let id = table.id.clone();
+            self.merge(&file, "loot table", id, |data| &mut data.loot_tables, table);
+        }
+    }
+
+    /// Definitions in one file; a missing file is an empty list
+    fn read_list<T: for<'de> Deserialize<'de>>(&mut self, path: &Path) -> Vec<T> {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        match ron::de::from_str(&text) {
+            Ok(list) => list,
+            Err(e) => {
+                self.error(path, e.to_string());
+                Vec::new()
+            }
+        }
+    }
+
+    /// Add a definition, replacing one an earlier pack gave the same id
+    fn merge<T>(
+        &mut self,
+        file: &Path,
+        kind: &'static str,
+        id: String,
+        map: fn(&mut Self) -> &mut HashMap<String, T>,
+        definition: T,
+    ) {
+        if id.trim().is_empty() {
+            self.error(file, format!("{} with an empty id", kind));
+            return;
+        }
+        if map(self).insert(id.clone(), definition).is_some() {
+            info!("{} '{}' overridden by {}", kind, id, file.display());
+        }
+        self.origins.insert((kind, id), file.to_path_buf());
+    }
+
+    /// Check values and that every id a definition refers to exists
+    fn validate(&mut self) {
+        let mut problems = Vec::new();
+
+        for item in self.items.values() {
+            if item.name.trim().is_empty() {
+                problems.push(("item", &item.id, "has no name".to_string()));
+            }
+            if item.weight < 0.0 {
+                problems.push(("item", &item.id, "has a negative weight".to_string()));
+            }
+            if item.max_stack == 0 {
+                problems.push(("item", &item.id, "has a max_stack of 0".to_string()));
+            }
+            if !(0.0..=1.0).contains(&item.radiation_protection) {
+                problems.push(("item", &item.id, "has radiation_protection outside 0..1".to_string()));
+            }
+        }
+
+        for npc in self.npcs.values() {
+            if npc.name.trim().is_empty() {
+                problems.push(("NPC", &npc.id, "has no name".to_string()));
+            }
+            if npc.faction.trim().is_empty() {
+                problems.push(("NPC", &npc.id, "has no faction".to_string()));
+            }
+            if npc.health <= 0.0 {
+                problems.push(("NPC", &npc.id, "needs positive health".to_string()));
+            }
+            if !npc.faction.trim().is_empty() && !self.factions.contains_key(&npc.faction) {
+                problems.push(("NPC", &npc.id, format!("belongs to unknown faction '{}'", npc.faction)));
+            }
+            if let Some(table) = &npc.loot_table
+                && !self.loot_tables.contains_key(table)
+            {
+                problems.push(("NPC", &npc.id, format!("uses unknown loot table '{}'", table)));
+            }
+        }
+
+        for faction in self.factions.values() {
+            if faction.name.trim().is_empty() {
+                problems.push(("faction", &faction.id, "has no name".to_string()));
+            }
+            for other in &faction.hostile_to {
+                if other != PLAYER_FACTION && !self.factions.contains_key(other) {
+                    problems.push(("faction", &faction.id, format!("is hostile to unknown faction '{}'", other)));
+                }
+            }
+        }
+
+        for table in self.loot_tables.values() {
+            if table.rolls == 0 {
+                problems.push(("loot table", &table.id, "has 0 rolls".to_string()));
+            }
+            if table.entries.is_empty() {
+                problems.push(("loot table", &table.id, "has no entries".to_string()));
+            }
+            for entry in &table.entries {
+                if !self.items.contains_key(&entry.item) {
+                    problems.push(("loot table", &table.id, format!("drops unknown item '{}'", entry.item)));
+                }
+                if entry.weight == 0 {
+                    problems.push(("loot table", &table.id, format!("gives '{}' a weight of 0", entry.item)));
+                }
+                if entry.min > entry.max {
+                    problems.push(("loot table", &table.id, format!("drops '{}' with min above max", entry.item)));
+                }
+            }
+        }
+
+        let errors: Vec<DataPackError> = problems
+            .into_iter()
+            .map(|(kind, id, problem)| DataPackError {
+                file: self.origins.get(&(kind, id.clone())).cloned().unwrap_or_default(),
+                message: format!("{} '{}' {}", kind, id, problem),
+            })
+            .collect();
+        for error in errors {
+            warn!("Data pack error: {}", error);
+            self.errors.push(error);
+        }
+        self.errors.sort_by_key(|error| error.to_string());
+    }
+
+    /// Whether members of the two factions fight; either side naming the other is enough
+    pub fn hostile(&self, a: &str, b: &str) -> bool {
+        let names = |from: &str, to: &str| self.factions.get(from).is_some_and(|faction| faction.hostile_to.iter().any(|id| id == to));
+        a != b && (names(a, b) || names(b, a))
+    }
+
+    fn error(&mut self, file: &Path, message: String) {
+        let error = DataPackError { file: file.to_path_buf(), message };
+        warn!("Data pack error: {}", error);
+        self.errors.push(error);
+    }
+}
+
+/// Ask for the data packs to be read from disk again
+#[derive(Event)]
+pub struct ReloadDataPacksEvent;
+
+fn load_data_packs(mut commands: Commands) {
+    let data = GameData::load(DATA_PACK_DIR);
+    info!(
+        "Loaded {} items, {} NPCs, {} factions and {} loot tables from {} data packs ({} errors)",
+        data.items.len(),
+        data.npcs.len(),
+        data.factions.len(),
+        data.loot_tables.len(),
+        data.packs.len(),
+        data.errors.len(),
+    );
+    commands.insert_resource(data);
+}
+
+fn reload_data_packs(mut events: EventReader<ReloadDataPacksEvent>, commands: Commands) {
+    if events.read().count() > 0 {
+        load_data_packs(commands);
+    }
+}
@@ -0,0 +1,421 @@
+//! Doors, loot containers and lockpicking
+//!
+//! `Door` and `LootContainer` markers from the scene get a panel or crate
+//! with a collider once the game starts. Looking at one within reach shows a
+//! prompt, and E uses it: doors slide open and shut, locked ones open with
+//! their key item or can be picked, and containers hand their rolled loot to
+//! the player once. What has been unlocked, opened and emptied is kept on the
+//! entities as `Unlocked`, `Opened` and `Looted`, which the save file stores
+//! by id.
+//!
+//! Lockpicking is a timing check standing in for a proper minigame: a pin
+//! sweeps across the lock and has to be set while it is over the sweet spot,
+//! which narrows with the lock level. A miss snaps a lockpick.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use super::components::*;
+use super::data_packs::GameData;
+use super::resources::GameState;
+use crate::editor::core::types::{Door, LootContainer};
+
+/// Item id of the lockpicks used up picking locks
+pub const LOCKPICK_ITEM: &str = "lockpick";
+/// How far away things can be used, in meters
+const REACH: f32 = 2.5;
+/// Share of its width an open door slides aside
+const DOOR_SLIDE: f32 = 0.95;
+/// Door widths per second a door slides
+const DOOR_SPEED: f32 = 2.0;
+/// Width of the sweet spot on a level 1 lock, as a share of the pin's travel
+const SWEET_SPOT: f32 = 0.3;
+/// Pin travels per second across the lock
+const PIN_SPEED: f32 = 0.8;
+/// Seconds a message stays on screen
+const MESSAGE_TIME: f32 = 3.0;
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<InteractionHud>()
+            .init_resource::<Lockpicking>()
+            .add_systems(Startup, setup_interactable_assets)
+            .add_systems(OnEnter(GameState::NewGame), reset_interaction)
+            .add_systems(OnEnter(GameState::Loading), reset_interaction)
+            .add_systems(
+                Update,
+                (
+                    build_doors,
+                    build_containers,
+                    show_restored_state,
+                    find_focus,
+                    interact,
+                    sweep_lock_pin,
+                    slide_doors,
+                    fade_message,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// What the player is looking at and the last thing that happened, for the HUD
+#[derive(Resource, Default)]
+pub struct InteractionHud {
+    /// Door or container within reach under the crosshair
+    pub target: Option<Entity>,
+    pub prompt: Option<String>,
+    pub message: Option<String>,
+    message_time: f32,
+}
+
+impl InteractionHud {
+    fn show(&mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+        self.message_time = MESSAGE_TIME;
+    }
+}
+
+/// The lock being picked, if any
+#[derive(Resource, Default)]
+pub struct Lockpicking(pub Option<LockPick>);
+
+/// State of the lockpicking timing check
+#[derive(Debug, Clone)]
+pub struct LockPick {
+    pub door: Entity,
+    pub lock_level: u32,
+    /// Pin position across the lock, from 0 to 1
+    pub pin: f32,
+    /// Pin travel direction, 1 or -1
+    direction: f32,
+    /// Center of the sweet spot, from 0 to 1
+    pub sweet_spot: f32,
+    /// Width of the sweet spot
+    pub width: f32,
+}
+
+impl LockPick {
+    fn new(door: Entity, lock_level: u32, rng: &mut impl Rng) -> Self {
+        let width = SWEET_SPOT / lock_level.max(1) as f32;
+        Self {
+            door,
+            lock_level,
+            pin: 0.0,
+            direction: 1.0,
+            sweet_spot: rng.random_range(width / 2.0..=1.0 - width / 2.0),
+            width,
+        }
+    }
+
+    fn in_sweet_spot(&self) -> bool {
+        (self.pin - self.sweet_spot).abs() <= self.width / 2.0
+    }
+}
+
+/// The sliding panel of a door
+#[derive(Component)]
+struct DoorPanel {
+    door: Entity,
+}
+
+/// Marks doors and containers whose geometry is already built
+#[derive(Component)]
+pub(crate) struct Built;
+
+/// Meshes and materials shared by every door and container
+#[derive(Resource)]
+pub(crate) struct InteractableAssets {
+    door_mesh: Handle<Mesh>,
+    door: Handle<StandardMaterial>,
+    locked_door: Handle<StandardMaterial>,
+    container_mesh: Handle<Mesh>,
+    container: Handle<StandardMaterial>,
+    looted: Handle<StandardMaterial>,
+}
+
+fn setup_interactable_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(InteractableAssets {
+        door_mesh: meshes.add(Cuboid::new(1.0, 2.0, 0.1)),
+        door: materials.add(Color::srgb(0.45, 0.3, 0.2)),
+        locked_door: materials.add(Color::srgb(0.5, 0.2, 0.15)),
+        container_mesh: meshes.add(Cuboid::new(0.8, 0.6, 0.5)),
+        container: materials.add(Color::srgb(0.35, 0.4, 0.25)),
+        looted: materials.add(Color::srgb(0.2, 0.22, 0.15)),
+    });
+}
+
+/// Drop the previous game's target and lockpicking before a new one starts
+fn reset_interaction(mut hud: ResMut<InteractionHud>, mut lockpicking: ResMut<Lockpicking>) {
+    *hud = InteractionHud::default();
+    lockpicking.0 = None;
+}
+
+/// Whether the door still needs unlocking
+fn is_locked(door: &Door, unlocked: bool) -> bool {
+    door.locked && !unlocked
+}
+
+/// Scene doors without their panel yet
+type UnbuiltDoors<'w, 's> = Query<'w, 's, (Entity, &'static Door, Has<Unlocked>, Has<Opened>), Without<Built>>;
+/// Scene containers without their crate yet
+type UnbuiltContainers<'w, 's> = Query<'w, 's, (Entity, Has<Looted>), (With<LootContainer>, Without<Built>)>;
+
+/// Built doors and containers, with the materials that show their state
+#[derive(SystemParam)]
+struct Interactables<'w, 's> {
+    assets: Res<'w, InteractableAssets>,
+    doors: Query<'w, 's, (&'static Door, Has<Unlocked>, Has<Opened>)>,
+    containers: Query<'w, 's, (&'static LootContainer, Has<Looted>)>,
+    panels: Query<'w, 's, (&'static DoorPanel, &'static mut MeshMaterial3d<StandardMaterial>)>,
+}
+
+/// Give each scene door its sliding panel
+pub(crate) fn build_doors(mut commands: Commands, assets: Res<InteractableAssets>, doors: UnbuiltDoors) {
+    for (entity, door, unlocked, opened) in doors.iter() {
+        let material = if is_locked(door, unlocked) { &assets.locked_door } else { &assets.door };
+        let slide = if opened { -DOOR_SLIDE } else { 0.0 };
+        commands.entity(entity).insert(Built).with_child((
+            DoorPanel { door: entity },
+            Mesh3d(assets.door_mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(slide, 0.0, 0.0),
+            RigidBody::KinematicPositionBased,
+            Collider::cuboid(0.5, 1.0, 0.05),
+        ));
+    }
+}
+
+/// Recolor built doors and containers whose state was put back after they were built, like from a save
+fn show_restored_state(
+    mut commands: Commands,
+    assets: Res<InteractableAssets>,
+    unlocked: Query<Entity, (Added<Unlocked>, With<Built>)>,
+    looted: Query<Entity, (Added<Looted>, With<Built>)>,
+    mut panels: Query<(&DoorPanel, &mut MeshMaterial3d<StandardMaterial>)>,
+) {
+    for door in unlocked.iter() {
+        set_door_material(&mut panels, door, &assets.door);
+    }
+    for container in looted.iter() {
+        commands.entity(container).insert(MeshMaterial3d(assets.looted.clone()));
+    }
+}
+
+/// Give each scene container its crate
+fn build_containers(mut commands: Commands, assets: Res<InteractableAssets>, containers: UnbuiltContainers) {
+    for (entity, looted) in containers.iter() {
+        let material = if looted { &assets.looted } else { &assets.container };
+        commands.entity(entity).insert((
+            Built,
+            Mesh3d(assets.container_mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            RigidBody::Fixed,
+            Collider::cuboid(0.4, 0.3, 0.25),
+        ));
+    }
+}
+
+/// Find the door or container under the crosshair and what E would do to it
+fn find_focus(
+    rapier: ReadRapierContext,
+    mut hud: ResMut<InteractionHud>,
+    game_data: Res<GameData>,
+    players: Query<(Entity, &Inventory), With<Player>>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    interactables: Interactables,
+) {
+    hud.target = None;
+    hud.prompt = None;
+
+    let (Ok(context), Ok((player, inventory)), Ok(camera)) = (rapier.single(), players.single(), cameras.single()) else {
+        return;
+    };
+    let filter = QueryFilter::default().exclude_collider(player);
+    let Some((hit, _)) = context.cast_ray(camera.translation(), camera.forward().as_vec3(), REACH, true, filter) else {
+        return;
+    };
+    let target = interactables.panels.get(hit).map_or(hit, |(panel, _)| panel.door);
+
+    let prompt = if let Ok((door, unlocked, opened)) = interactables.doors.get(target) {
+        if opened {
+            "E: Close".to_string()
+        } else if !is_locked(door, unlocked) {
+            "E: Open".to_string()
+        } else if inventory.count(&door.key) > 0 {
+            format!("E: Unlock with {}", item_name(&game_data, &door.key))
+        } else if door.lock_level > 0 {
+            format!("E: Pick lock (level {}, {} lockpicks)", door.lock_level, inventory.count(LOCKPICK_ITEM))
+        } else {
+            "Locked".to_string()
+        }
+    } else if let Ok((_, looted)) = interactables.containers.get(target) {
+        if looted { "Empty".to_string() } else { "E: Search".to_string() }
+    } else {
+        return;
+    };
+
+    hud.target = Some(target);
+    hud.prompt = Some(prompt);
+}
+
+/// Display name of a data pack item, or its id if no pack defines it
+fn item_name<'a>(game_data: &'a GameData, id: &'a str) -> &'a str {
+    game_data.items.get(id).map_or(id, |item| item.name.as_str())
+}
+
+/// Use the focused door or container, or set the pin while picking a lock
+fn interact(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut hud: ResMut<InteractionHud>,
+    mut lockpicking: ResMut<Lockpicking>,
+    game_data: Res<GameData>,
+    mut players: Query<&mut Inventory, With<Player>>,
+    mut interactables: Interactables,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyE) {
+        return;
+    }
+    let Ok(mut inventory) = players.single_mut() else {
+        return;
+    };
+
+    // While picking, E sets the pin instead
+    if let Some(pick) = &mut lockpicking.0 {
+        if pick.in_sweet_spot() {
+            let door = pick.door;
+            lockpicking.0 = None;
+            commands.entity(door).insert((Unlocked, Opened));
+            set_door_material(&mut interactables.panels, door, &interactables.assets.door);
+            hud.show("The lock clicks open");
+        } else if inventory.take_one(LOCKPICK_ITEM) && inventory.count(LOCKPICK_ITEM) > 0 {
+            // A fresh pick finds the sweet spot somewhere else
+            *pick = LockPick::new(pick.door, pick.lock_level, &mut rand::rng());
+            hud.show("The lockpick snapped");
+        } else {
+            lockpicking.0 = None;
+            hud.show("The last lockpick snapped");
+        }
+        return;
+    }
+
+    let Some(target) = hud.target else {
+        return;
+    };
+
+    if let Ok((door, unlocked, opened)) = interactables.doors.get(target) {
+        if opened {
+            commands.entity(target).remove::<Opened>();
+        } else if !is_locked(door, unlocked) {
+            commands.entity(target).insert(Opened);
+        } else if inventory.count(&door.key) > 0 {
+            commands.entity(target).insert((Unlocked, Opened));
+            set_door_material(&mut interactables.panels, target, &interactables.assets.door);
+            hud.show(format!("Unlocked with {}", item_name(&game_data, &door.key)));
+        } else if door.lock_level == 0 {
+            hud.show("It won't budge");
+        } else if inventory.count(LOCKPICK_ITEM) == 0 {
+            hud.show("You need a lockpick");
+        } else {
+            lockpicking.0 = Some(LockPick::new(target, door.lock_level, &mut rand::rng()));
+        }
+    } else if let Ok((container, looted)) = interactables.containers.get(target) {
+        if looted {
+            return;
+        }
+        commands.entity(target).insert((Looted, MeshMaterial3d(interactables.assets.looted.clone())));
+
+        let Some(table) = game_data.loot_tables.get(&container.loot_table) else {
+            warn!("Container '{}' uses unknown loot table '{}'", container.id, container.loot_table);
+            hud.show("Nothing inside");
+            return;
+        };
+        let found = table.roll(&mut rand::rng());
+        if found.is_empty() {
+            hud.show("Nothing inside");
+            return;
+        }
+        let names: Vec<String> = found
+            .iter()
+            .map(|(item, count)| {
+                inventory.add(item, *count);
+                if *count > 1 { format!("{} x{}", item_name(&game_data, item), count) } else { item_name(&game_data, item).to_string() }
+            })
+            .collect();
+        hud.show(format!("Found {}", names.join(", ")));
+    }
+}
+
+fn set_door_material(
+    panels: &mut Query<(&DoorPanel, &mut MeshMaterial3d<StandardMaterial>)>,
+    door: Entity,
+    material: &Handle<StandardMaterial>,
+) {
+    for (panel, mut panel_material) in panels.iter_mut() {
+        if panel.door == door {
+            panel_material.0 = material.clone();
+        }
+    }
+}
+
+/// Move the pin across the lock, and give up if the player walks away
+fn sweep_lock_pin(
+    time: Res<Time>,
+    mut lockpicking: ResMut<Lockpicking>,
+    players: Query<&GlobalTransform, With<Player>>,
+    doors: Query<&GlobalTransform, With<Door>>,
+) {
+    let Some(pick) = &mut lockpicking.0 else {
+        return;
+    };
+    let in_reach = match (players.single(), doors.get(pick.door)) {
+        (Ok(player), Ok(door)) => player.translation().distance(door.translation()) <= REACH * 1.5,
+        _ => false,
+    };
+    if !in_reach {
+        lockpicking.0 = None;
+        return;
+    }
+
+    pick.pin += pick.direction * PIN_SPEED * time.delta_secs();
+    if !(0.0..=1.0).contains(&pick.pin) {
+        pick.pin = pick.pin.clamp(0.0, 1.0);
+        pick.direction = -pick.direction;
+    }
+}
+
+/// Slide door panels towards open or shut
+fn slide_doors(
+    time: Res<Time>,
+    opened: Query<(), With<Opened>>,
+    mut panels: Query<(&DoorPanel, &mut Transform)>,
+) {
+    let step = DOOR_SPEED * time.delta_secs();
+    for (panel, mut transform) in panels.iter_mut() {
+        let goal = if opened.contains(panel.door) { -DOOR_SLIDE } else { 0.0 };
+        if transform.translation.x != goal {
+            transform.translation.x += (goal - transform.translation.x).clamp(-step, step);
+        }
+    }
+}
+
+fn fade_message(time: Res<Time>, mut hud: ResMut<InteractionHud>) {
+    if hud.message.is_none() {
+        return;
+    }
+    hud.message_time -= time.delta_secs();
+    if hud.message_time <= 0.0 {
+        hud.message = None;
+    }
+}
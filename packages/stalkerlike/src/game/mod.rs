@@ -4,14 +4,25 @@ use bevy_rapier3d::prelude::*;
 use bevy_screen_diagnostics::{ScreenDiagnosticsPlugin, ScreenFrameDiagnosticsPlugin};
 
 mod components;
+mod data_packs;
+mod interaction;
+mod npc;
 mod persistence;
 mod player;
+mod radiation;
 mod resources;
 mod ui;
 
+use crate::editor::core::types::PlayerSpawn;
+use crate::editor::persistence::scene::{read_scene, spawn_scene_entities};
+use crate::editor::persistence::{LoadContext, SceneTarget, SerializerRegistry};
 use components::{GameEntity};
+use data_packs::DataPackPlugin;
+use interaction::InteractionPlugin;
+use npc::NpcPlugin;
 use persistence::PersistencePlugin;
 use player::PlayerPlugin;
+use radiation::RadiationPlugin;
 use resources::*;
 use ui::UiPlugin;
 
@@ -36,19 +47,24 @@ impl Plugin for GamePlugin {
             .add_plugins(PlayerPlugin)
             .add_plugins(UiPlugin)
             .add_plugins(PersistencePlugin)
+            .add_plugins(DataPackPlugin)
+            .add_plugins(RadiationPlugin)
+            .add_plugins(NpcPlugin)
+            .add_plugins(InteractionPlugin)
 
-            // Game state
+            // Game state; entities tagged StateScoped are despawned when their state exits
             .init_state::<GameState>()
+            .enable_state_scoped_entities::<GameState>()
 
             // Resources
             .insert_resource(SavePath::default())
+            .init_resource::<SerializerRegistry>()
 
             // MainMenu state
             .add_systems(OnEnter(GameState::MainMenu), (
                 cleanup_game_scene,
                 setup_menu_camera,
             ).chain())
-            .add_systems(OnExit(GameState::MainMenu), cleanup_menu_camera)
 
             // NewGame state
             .add_systems(OnEnter(GameState::NewGame), (
@@ -56,11 +72,10 @@ impl Plugin for GamePlugin {
                 spawn_player_at_marker,
             ).chain())
 
-            // Loading state
+            // Loading state: the save restores the player and the scene's doors and containers
             .add_systems(OnEnter(GameState::Loading), (
-                setup_static_world,
                 cleanup_game_scene,
-                spawn_player_at_marker,
+                setup_static_world,
             ).chain())
 
             // InGame state - handled by PlayerPlugin
@@ -81,6 +96,7 @@ fn setup_static_world(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
+    registry: Res<SerializerRegistry>,
 ) {
     // Load the test scene from YAML
     // Path is relative to the Cargo workspace root when running with cargo run
@@ -89,7 +105,7 @@ fn setup_static_world(
     info!("Attempting to load scene from: {}", scene_path);
     info!("Current directory: {:?}", std::env::current_dir());
 
-    match load_scene_from_yaml(scene_path, &mut commands, &mut meshes, &mut materials, &asset_server) {
+    match load_scene_from_yaml(scene_path, &mut commands, &registry, &mut LoadContext::new(SceneTarget::Game, &mut meshes, &mut materials, &asset_server)) {
         Ok(_) => {
             info!("✅ Successfully loaded scene from {}", scene_path);
         }
@@ -106,6 +122,7 @@ fn setup_world(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
+    registry: Res<SerializerRegistry>,
 ) {
     // Load the test scene from YAML
     let scene_path = "packages/stalkerlike/assets/scenes/test_scene.yaml";
@@ -113,7 +130,7 @@ fn setup_world(
     info!("Attempting to load scene from: {}", scene_path);
     info!("Current directory: {:?}", std::env::current_dir());
 
-    match load_scene_from_yaml(scene_path, &mut commands, &mut meshes, &mut materials, &asset_server) {
+    match load_scene_from_yaml(scene_path, &mut commands, &registry, &mut LoadContext::new(SceneTarget::Game, &mut meshes, &mut materials, &asset_server)) {
         Ok(_) => {
             info!("✅ Successfully loaded scene from {}", scene_path);
         }
@@ -123,10 +140,10 @@ fn setup_world(
     }
 }
 
-/// System that spawns the player at a PlayerSpawnMarker position
+/// System that spawns the player at the scene's PlayerSpawn position
 fn spawn_player_at_marker(
     commands: Commands,
-    spawn_query: Query<&Transform, With<PlayerSpawnMarker>>,
+    spawn_query: Query<&Transform, With<PlayerSpawn>>,
 ) {
     // Find player spawn position from the scene
     let spawn_position = if let Ok(spawn_transform) = spawn_query.single() {
@@ -143,447 +160,39 @@ fn spawn_player_at_marker(
 }
 
 /// Load a scene from a YAML file for the game mode
-/// Similar to the editor's load_scene but uses GameEntity instead of EditorEntity
+/// Goes through the same serializers as the editor, tagging entities with GameEntity
 fn load_scene_from_yaml(
     path: impl AsRef<std::path::Path>,
     commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    asset_server: &Res<AssetServer>,
+    registry: &SerializerRegistry,
+    context: &mut LoadContext,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use serde::{Deserialize, Serialize};
-
-    // Import scene data structures from editor
-    // We'll define minimal versions here to avoid coupling to editor internals
-    #[derive(Serialize, Deserialize, Debug, Clone)]
-    struct SceneData {
-        metadata: SceneMetadata,
-        #[serde(default)]
-        global: GlobalData,
-        entities: Vec<EntityData>,
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone)]
-    struct SceneMetadata {
-        version: u32,
-        name: Option<String>,
-        description: Option<String>,
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-    struct GlobalData {
-        lighting: LightingData,
-    }
-
-    impl Default for GlobalData {
-        fn default() -> Self {
-            Self {
-                lighting: LightingData::default(),
-            }
-        }
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-    struct LightingData {
-        directional: DirectionalLightData,
-        ambient: AmbientLightData,
-    }
-
-    impl Default for LightingData {
-        fn default() -> Self {
-            Self {
-                directional: DirectionalLightData::default(),
-                ambient: AmbientLightData::default(),
-            }
-        }
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-    struct DirectionalLightData {
-        illuminance: f32,
-        color: [f32; 4],
-        position: [f32; 3],
-        look_at: [f32; 3],
-    }
-
-    impl Default for DirectionalLightData {
-        fn default() -> Self {
-            Self {
-                illuminance: 10000.0,
-                color: [1.0, 1.0, 1.0, 1.0],
-                position: [4.0, 8.0, 4.0],
-                look_at: [0.0, 0.0, 0.0],
-            }
-        }
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-    struct AmbientLightData {
-        color: [f32; 4],
-        brightness: f32,
-    }
-
-    impl Default for AmbientLightData {
-        fn default() -> Self {
-            Self {
-                color: [1.0, 1.0, 1.0, 1.0],
-                brightness: 400.0,
-            }
-        }
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone)]
-    struct EntityData {
-        name: Option<String>,
-        transform: TransformData,
-        components: Vec<ComponentData>,
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-    struct TransformData {
-        position: [f32; 3],
-        rotation: [f32; 4],
-        scale: [f32; 3],
-    }
-
-    impl From<TransformData> for Transform {
-        fn from(data: TransformData) -> Self {
-            Transform {
-                translation: Vec3::from_array(data.position),
-                rotation: Quat::from_xyzw(
-                    data.rotation[0],
-                    data.rotation[1],
-                    data.rotation[2],
-                    data.rotation[3],
-                ),
-                scale: Vec3::from_array(data.scale),
-            }
-        }
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone)]
-    #[serde(tag = "type")]
-    enum ComponentData {
-        Mesh { primitive_type: PrimitiveTypeSerde },
-        Material { base_color: [f32; 4] },
-        PlayerSpawn,
-        RigidBody { body_type: RigidBodyTypeSerde },
-        GlbModel { path: String },
-        PointLight {
-            intensity: f32,
-            color: [f32; 4],
-            shadows_enabled: bool,
-            range: f32,
-        },
-        SpotLight {
-            intensity: f32,
-            color: [f32; 4],
-            shadows_enabled: bool,
-            range: f32,
-            inner_angle: f32,
-            outer_angle: f32,
-        },
-    }
-
-    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-    enum PrimitiveTypeSerde {
-        Cube,
-        Sphere,
-        Plane,
-        Cylinder,
-        Capsule,
-        PlayerSpawn,
-        PointLight,
-        SpotLight,
-    }
+    let scene_data = read_scene(path)?;
 
-    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-    enum RigidBodyTypeSerde {
-        Fixed,
-        Dynamic,
-    }
-
-    // Helper to create meshes for each primitive type
-    fn create_primitive_mesh(prim_type: PrimitiveTypeSerde, size: Vec3) -> Mesh {
-        match prim_type {
-            PrimitiveTypeSerde::Cube => Cuboid::new(size.x, size.y, size.z).into(),
-            PrimitiveTypeSerde::Sphere => Sphere::new(size.x / 2.0).mesh().ico(32).unwrap().into(),
-            PrimitiveTypeSerde::Plane => Plane3d::default().mesh().size(size.x, size.z).into(),
-            PrimitiveTypeSerde::Cylinder => Cylinder::new(size.x / 2.0, size.y).into(),
-            PrimitiveTypeSerde::Capsule => {
-                let radius = size.x / 2.0;
-                let half_height = (size.y / 2.0) - radius;
-                Capsule3d::new(radius, half_height.max(0.001)).into()
-            }
-            PrimitiveTypeSerde::PlayerSpawn => {
-                // Create an upward-pointing cone for player spawn marker
-                Cone {
-                    radius: size.x / 2.0,
-                    height: size.y,
-                }.into()
-            }
-            PrimitiveTypeSerde::PointLight => {
-                // Lights don't need meshes in game mode, but provide a sphere if needed
-                Sphere::new(size.x / 2.0).mesh().ico(16).unwrap().into()
-            }
-            PrimitiveTypeSerde::SpotLight => {
-                // Lights don't need meshes in game mode, but provide a cone if needed
-                Cone {
-                    radius: size.x / 2.0,
-                    height: size.y,
-                }.into()
-            }
-        }
-    }
-
-    fn get_default_size(prim_type: PrimitiveTypeSerde) -> Vec3 {
-        match prim_type {
-            PrimitiveTypeSerde::Cube => Vec3::ONE,
-            PrimitiveTypeSerde::Sphere => Vec3::splat(1.0),
-            PrimitiveTypeSerde::Plane => Vec3::new(10.0, 0.1, 10.0),
-            PrimitiveTypeSerde::Cylinder => Vec3::new(1.0, 2.0, 1.0),
-            PrimitiveTypeSerde::Capsule => Vec3::new(0.5, 2.0, 0.5),
-            PrimitiveTypeSerde::PlayerSpawn => Vec3::new(0.5, 2.0, 0.5),
-            PrimitiveTypeSerde::PointLight => Vec3::splat(0.3),
-            PrimitiveTypeSerde::SpotLight => Vec3::new(0.3, 0.5, 0.3),
-        }
-    }
-
-    // Read and parse the YAML file
-    let yaml = std::fs::read_to_string(path)?;
-    let scene_data: SceneData = serde_yaml::from_str(&yaml)?;
-
-    // Spawn entities from the scene
-    for entity_data in scene_data.entities {
-        let mut entity_commands = commands.spawn(GameEntity);
-
-        // Add transform
-        entity_commands.insert(Transform::from(entity_data.transform));
-
-        // Add name if present
-        if let Some(name) = entity_data.name {
-            entity_commands.insert(Name::new(name));
-        }
-
-        // Process components
-        let mut mesh_type: Option<PrimitiveTypeSerde> = None;
-        let mut base_color: Option<Color> = None;
-        let mut is_player_spawn = false;
-        let mut rigid_body_type: Option<RigidBodyTypeSerde> = None;
-        let mut glb_model_path: Option<String> = None;
-        let mut point_light_data: Option<(f32, Color, bool, f32)> = None;
-        let mut spot_light_data: Option<(f32, Color, bool, f32, f32, f32)> = None;
-
-        for component in entity_data.components {
-            match component {
-                ComponentData::Mesh { primitive_type } => {
-                    mesh_type = Some(primitive_type);
-                }
-                ComponentData::Material { base_color: color } => {
-                    base_color = Some(Color::srgba(
-                        color[0],
-                        color[1],
-                        color[2],
-                        color[3],
-                    ));
-                }
-                ComponentData::PlayerSpawn => {
-                    is_player_spawn = true;
-                }
-                ComponentData::RigidBody { body_type } => {
-                    rigid_body_type = Some(body_type);
-                }
-                ComponentData::GlbModel { path } => {
-                    glb_model_path = Some(path);
-                }
-                ComponentData::PointLight { intensity, color, shadows_enabled, range } => {
-                    point_light_data = Some((
-                        intensity,
-                        Color::srgba(color[0], color[1], color[2], color[3]),
-                        shadows_enabled,
-                        range,
-                    ));
-                }
-                ComponentData::SpotLight { intensity, color, shadows_enabled, range, inner_angle, outer_angle } => {
-                    spot_light_data = Some((
-                        intensity,
-                        Color::srgba(color[0], color[1], color[2], color[3]),
-                        shadows_enabled,
-                        range,
-                        inner_angle,
-                        outer_angle,
-                    ));
-                }
-            }
-        }
-
-        // Handle point lights
-        if let Some((intensity, color, shadows_enabled, range)) = point_light_data {
-            entity_commands.insert(PointLight {
-                intensity,
-                color,
-                shadows_enabled,
-                range,
-                ..default()
-            });
-        }
-        // Handle spot lights
-        else if let Some((intensity, color, shadows_enabled, range, inner_angle, outer_angle)) = spot_light_data {
-            entity_commands.insert(SpotLight {
-                intensity,
-                color,
-                shadows_enabled,
-                range,
-                inner_angle,
-                outer_angle,
-                ..default()
-            });
-        }
-        // Handle GLB models
-        else if let Some(glb_path) = glb_model_path {
-            // Spawn GLB entity with physics support
-            let scene_handle = asset_server.load(format!("{}#Scene0", glb_path));
-            let rb = match rigid_body_type.unwrap_or(RigidBodyTypeSerde::Fixed) {
-                RigidBodyTypeSerde::Fixed => RigidBody::Fixed,
-                RigidBodyTypeSerde::Dynamic => RigidBody::Dynamic,
-            };
-
-            entity_commands.insert((
-                SceneRoot(scene_handle),
-                rb,
-                NeedsCollider, // Will add colliders to child meshes
-            ));
-        }
-        // Handle player spawn differently - don't render the cone, just mark the spawn point
-        else if is_player_spawn {
-            // Only add the marker component, no mesh or collider
-            entity_commands.insert(PlayerSpawnMarker);
-        } else if let Some(prim_type) = mesh_type {
-            // Create mesh at the primitive's default size - Transform.scale handles any scaling
-            let default_size = get_default_size(prim_type);
-            let mesh = create_primitive_mesh(prim_type, default_size);
-            entity_commands.insert(Mesh3d(meshes.add(mesh)));
-
-            // Add material
-            let color = base_color.unwrap_or(Color::srgb(0.7, 0.7, 0.7));
-            entity_commands.insert(MeshMaterial3d(materials.add(StandardMaterial {
-                base_color: color,
-                ..default()
-            })));
-
-            // Add physics colliders for scene geometry
-            // Default to Fixed if no rigid body type specified
-            let rb = match rigid_body_type.unwrap_or(RigidBodyTypeSerde::Fixed) {
-                RigidBodyTypeSerde::Fixed => RigidBody::Fixed,
-                RigidBodyTypeSerde::Dynamic => RigidBody::Dynamic,
-            };
-
-            match prim_type {
-                PrimitiveTypeSerde::Cube => {
-                    entity_commands.insert((
-                        rb,
-                        Collider::cuboid(default_size.x / 2.0, default_size.y / 2.0, default_size.z / 2.0),
-                    ));
-                }
-                PrimitiveTypeSerde::Sphere => {
-                    entity_commands.insert((
-                        rb,
-                        Collider::ball(default_size.x / 2.0),
-                    ));
-                }
-                PrimitiveTypeSerde::Plane => {
-                    entity_commands.insert((
-                        rb,
-                        Collider::cuboid(default_size.x / 2.0, 0.001, default_size.z / 2.0),
-                    ));
-                }
-                PrimitiveTypeSerde::Cylinder => {
-                    entity_commands.insert((
-                        rb,
-                        Collider::cylinder(default_size.y / 2.0, default_size.x / 2.0),
-                    ));
-                }
-                PrimitiveTypeSerde::Capsule => {
-                    let radius = default_size.x / 2.0;
-                    let half_height = (default_size.y / 2.0) - radius;
-                    entity_commands.insert((
-                        rb,
-                        Collider::capsule_y(half_height.max(0.001), radius),
-                    ));
-                }
-                PrimitiveTypeSerde::PlayerSpawn => {
-                    // This shouldn't happen since we check is_player_spawn first
-                }
-                PrimitiveTypeSerde::PointLight | PrimitiveTypeSerde::SpotLight => {
-                    // Lights don't have colliders
-                }
-            }
-        }
-    }
+    spawn_scene_entities(scene_data.entities, GameEntity, registry, commands, context)?;
 
     // Apply lighting settings from the scene
-    commands.spawn((
-        GameEntity,
-        DirectionalLight {
-            illuminance: scene_data.global.lighting.directional.illuminance,
-            color: Color::srgba(
-                scene_data.global.lighting.directional.color[0],
-                scene_data.global.lighting.directional.color[1],
-                scene_data.global.lighting.directional.color[2],
-                scene_data.global.lighting.directional.color[3],
-            ),
-            shadows_enabled: true,
-            ..default()
-        },
-        Transform::from_translation(Vec3::from_array(scene_data.global.lighting.directional.position))
-            .looking_at(Vec3::from_array(scene_data.global.lighting.directional.look_at), Vec3::Y),
-    ));
-
-    commands.insert_resource(AmbientLight {
-        color: Color::srgba(
-            scene_data.global.lighting.ambient.color[0],
-            scene_data.global.lighting.ambient.color[1],
-            scene_data.global.lighting.ambient.color[2],
-            scene_data.global.lighting.ambient.color[3],
-        ),
-        brightness: scene_data.global.lighting.ambient.brightness,
-        ..default()
-    });
+    let directional_light = scene_data.global.lighting.apply(commands);
+    commands.entity(directional_light).insert(GameEntity);
 
     Ok(())
 }
 
-/// Marker component for player spawn points in game mode
-#[derive(Component)]
-pub struct PlayerSpawnMarker;
-
-#[derive(Component)]
-struct MenuCamera;
-
 fn setup_menu_camera(mut commands: Commands) {
-    // Spawn a 2D camera for the main menu
+    // Spawn a 2D camera for the main menu, removed when leaving it
     commands.spawn((
         Camera2d,
-        MenuCamera,
         PrimaryEguiContext,
+        StateScoped(GameState::MainMenu),
     ));
 }
 
-fn cleanup_menu_camera(
-    mut commands: Commands,
-    query: Query<Entity, With<MenuCamera>>,
-) {
-    // Remove the menu camera when leaving main menu
-    for entity in query.iter() {
-        commands.entity(entity).despawn();
-    }
-}
-
 fn cleanup_game_scene(
     mut commands: Commands,
     query: Query<Entity, With<GameEntity>>,
 ) {
-    // Clean up all entities marked with GameEntity
+    // GameEntity outlives any one state (NewGame, InGame and Paused share it),
+    // so it's cleared on the way back to the menu or into a load instead
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
@@ -605,7 +214,7 @@ fn resume_physics(mut query: Query<&mut RapierConfiguration>) {
 /// Used because AsyncCollider can't be added directly to SceneRoot -
 /// it needs to be on the actual mesh entities which spawn as children
 #[derive(Component)]
-struct NeedsCollider;
+pub(crate) struct NeedsCollider;
 
 /// Add trimesh colliders to all mesh children of scenes marked with NeedsCollider
 fn add_colliders_to_scene_children(
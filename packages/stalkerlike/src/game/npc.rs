@@ -0,0 +1,336 @@
+//! NPC patrols, perception and skirmishes
+//!
+//! `NpcSpawn` markers from the scene spawn NPCs of a data pack archetype,
+//! which walk the loop formed by the `PatrolWaypoint`s of their network.
+//! Each NPC looks for members of factions its own is hostile to (the player
+//! included) within a view cone, or closer by ear, with a clear line of sight.
+//! Once it has a target it closes in and fires; losing sight for long enough
+//! sends it back to its patrol. Patrols of hostile factions that cross end up
+//! fighting without the player.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use super::components::*;
+use super::data_packs::{GameData, PLAYER_FACTION};
+use super::resources::GameState;
+use crate::editor::core::types::{patrol_routes, NpcSpawn, PatrolWaypoint};
+
+/// Walking speed on patrol, in meters per second
+const WALK_SPEED: f32 = 1.5;
+/// Speed when closing in on a target
+const RUN_SPEED: f32 = 3.5;
+/// Distance at which a waypoint counts as reached
+const ARRIVE_DISTANCE: f32 = 0.5;
+/// How far NPCs can see
+const SIGHT_RANGE: f32 = 30.0;
+/// Full width of the view cone, in radians
+const FIELD_OF_VIEW: f32 = 2.1;
+/// Distance within which NPCs notice hostiles behind them
+const HEARING_RANGE: f32 = 5.0;
+/// Seconds a target can stay out of sight before it is given up
+const FORGET_AFTER: f32 = 4.0;
+/// Distance NPCs try to fight from
+const ENGAGE_RANGE: f32 = 15.0;
+/// Furthest distance NPCs shoot at
+const FIRE_RANGE: f32 = 25.0;
+/// Seconds between shots
+const FIRE_INTERVAL: f32 = 0.8;
+/// Chance a shot hits at point blank, falling off to half at `FIRE_RANGE`
+const ACCURACY: f32 = 0.6;
+const DAMAGE: f32 = 12.0;
+/// Seconds a shot's tracer stays visible
+const TRACER_TIME: f32 = 0.08;
+/// Eye height above the capsule center
+const EYE_HEIGHT: f32 = 0.6;
+
+pub struct NpcPlugin;
+
+impl Plugin for NpcPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                add_faction_to_player,
+                spawn_npcs,
+                perceive_hostiles,
+                (patrol, engage, fire_weapons).chain(),
+                remove_dead_npcs,
+                draw_tracers,
+            )
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+/// An NPC spawned from a data pack archetype
+#[derive(Component, Debug)]
+pub struct Npc;
+
+/// Route an NPC walks while it has nothing to fight
+#[derive(Component, Debug)]
+struct Patrol {
+    network: String,
+    /// Index of the waypoint being walked to
+    next: usize,
+}
+
+/// The hostile an NPC is fighting, and where it was last seen
+#[derive(Component, Default, Debug)]
+struct Perception {
+    target: Option<Entity>,
+    visible: bool,
+    last_seen: Vec3,
+    unseen_for: f32,
+}
+
+/// Shot timing and the tracer of the last shot
+#[derive(Component, Default, Debug)]
+struct Weapon {
+    cooldown: f32,
+    tracer: Option<(Vec3, Vec3, f32)>,
+}
+
+/// Marks spawn points whose NPCs are already out
+#[derive(Component)]
+struct NpcsSpawned;
+
+fn add_faction_to_player(mut commands: Commands, players: Query<Entity, (With<Player>, Without<FactionMember>)>) {
+    for player in players.iter() {
+        commands.entity(player).insert(FactionMember(PLAYER_FACTION.to_string()));
+    }
+}
+
+/// Spawn each marker's NPCs once, in a small ring around it
+fn spawn_npcs(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    game_data: Res<GameData>,
+    spawns: Query<(Entity, &NpcSpawn, &GlobalTransform), Without<NpcsSpawned>>,
+) {
+    for (spawn_entity, spawn, transform) in spawns.iter() {
+        commands.entity(spawn_entity).insert(NpcsSpawned);
+
+        let Some(archetype) = game_data.npcs.get(&spawn.archetype) else {
+            warn!("NPC spawn uses unknown archetype '{}'", spawn.archetype);
+            continue;
+        };
+        let color = game_data
+            .factions
+            .get(&archetype.faction)
+            .map_or(Color::srgb(0.6, 0.6, 0.6), |faction| Color::srgb(faction.color[0], faction.color[1], faction.color[2]));
+        let mesh = meshes.add(Capsule3d::new(0.25, 1.3));
+        let material = materials.add(StandardMaterial { base_color: color, ..default() });
+
+        for i in 0..spawn.count {
+            let angle = i as f32 / spawn.count as f32 * std::f32::consts::TAU;
+            let offset = if spawn.count > 1 { Vec3::new(angle.cos(), 0.0, angle.sin()) } else { Vec3::ZERO };
+
+            commands.spawn((
+                GameEntity,
+                Npc,
+                Name::new(archetype.name.clone()),
+                FactionMember(archetype.faction.clone()),
+                Health { current: archetype.health, maximum: archetype.health },
+                Patrol { network: spawn.network.clone(), next: 0 },
+                Perception::default(),
+                Weapon::default(),
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(transform.translation() + offset),
+                RigidBody::KinematicPositionBased,
+                Collider::capsule_y(0.65, 0.25),
+            ));
+        }
+        info!("Spawned {} {} on network '{}'", spawn.count, archetype.name, spawn.network);
+    }
+}
+
+/// Whether nothing but the target is between the two points
+fn line_of_sight(context: &RapierContext, from: Vec3, viewer: Entity, to: Vec3, target: Entity) -> bool {
+    let offset = to - from;
+    let distance = offset.length();
+    if distance < f32::EPSILON {
+        return true;
+    }
+    let filter = QueryFilter::default().exclude_collider(viewer);
+    match context.cast_ray(from, offset / distance, distance, true, filter) {
+        Some((hit, _)) => hit == target,
+        None => true,
+    }
+}
+
+/// Keep track of the current target, or pick the nearest hostile in view
+fn perceive_hostiles(
+    time: Res<Time>,
+    game_data: Res<GameData>,
+    rapier: ReadRapierContext,
+    mut npcs: Query<(Entity, &Transform, &FactionMember, &mut Perception), With<Npc>>,
+    others: Query<(Entity, &GlobalTransform, &FactionMember, &Health)>,
+) {
+    let Ok(context) = rapier.single() else {
+        return;
+    };
+
+    for (entity, transform, faction, mut perception) in npcs.iter_mut() {
+        let eye = transform.translation + Vec3::Y * EYE_HEIGHT;
+        let forward = transform.forward().as_vec3();
+        let sees = |other: Entity, position: Vec3| line_of_sight(&context, eye, entity, position, other);
+
+        // Stay on the current target while it lives, remembering where it was
+        if let Some(target) = perception.target {
+            match others.get(target) {
+                Ok((_, target_transform, _, health)) if health.current > 0.0 => {
+                    let position = target_transform.translation();
+                    perception.visible = eye.distance(position) <= SIGHT_RANGE && sees(target, position);
+                    if perception.visible {
+                        perception.last_seen = position;
+                        perception.unseen_for = 0.0;
+                    } else {
+                        perception.unseen_for += time.delta_secs();
+                    }
+                    if perception.unseen_for < FORGET_AFTER {
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            *perception = Perception::default();
+        }
+
+        let nearest = others
+            .iter()
+            .filter(|(other, _, other_faction, health)| {
+                *other != entity && health.current > 0.0 && game_data.hostile(&faction.0, &other_faction.0)
+            })
+            .map(|(other, other_transform, _, _)| (other, other_transform.translation()))
+            .filter(|&(_, position)| {
+                let offset = position - eye;
+                let distance = offset.length();
+                distance <= HEARING_RANGE
+                    || (distance <= SIGHT_RANGE && forward.angle_between(offset) <= FIELD_OF_VIEW / 2.0)
+            })
+            .filter(|&(other, position)| sees(other, position))
+            .min_by(|(_, a), (_, b)| eye.distance_squared(*a).total_cmp(&eye.distance_squared(*b)));
+
+        if let Some((target, position)) = nearest {
+            *perception = Perception { target: Some(target), visible: true, last_seen: position, unseen_for: 0.0 };
+        }
+    }
+}
+
+/// Step towards `goal` on the ground plane, facing the way of travel; true once there
+fn walk_towards(transform: &mut Transform, goal: Vec3, distance: f32) -> bool {
+    let offset = Vec3::new(goal.x - transform.translation.x, 0.0, goal.z - transform.translation.z);
+    let remaining = offset.length();
+    if remaining <= f32::EPSILON {
+        return true;
+    }
+    transform.look_to(offset, Vec3::Y);
+    transform.translation += offset / remaining * distance.min(remaining);
+    remaining <= distance
+}
+
+fn patrol(
+    time: Res<Time>,
+    waypoints: Query<(&PatrolWaypoint, &GlobalTransform)>,
+    mut npcs: Query<(&mut Transform, &mut Patrol, &Perception), With<Npc>>,
+) {
+    let routes = patrol_routes(waypoints.iter().map(|(waypoint, transform)| (waypoint, transform.translation())));
+
+    for (mut transform, mut patrol, perception) in npcs.iter_mut() {
+        if perception.target.is_some() {
+            continue;
+        }
+        let Some(route) = routes.get(&patrol.network).filter(|route| !route.is_empty()) else {
+            continue;
+        };
+
+        let goal = route[patrol.next % route.len()];
+        let step = (WALK_SPEED * time.delta_secs()).max(0.0);
+        let flat_distance = Vec2::new(goal.x - transform.translation.x, goal.z - transform.translation.z).length();
+        if walk_towards(&mut transform, goal, step) || flat_distance <= ARRIVE_DISTANCE {
+            patrol.next = (patrol.next + 1) % route.len();
+        }
+    }
+}
+
+/// Close in on a visible target, or go and look where it was last seen
+fn engage(time: Res<Time>, mut npcs: Query<(&mut Transform, &Perception), With<Npc>>) {
+    for (mut transform, perception) in npcs.iter_mut() {
+        if perception.target.is_none() {
+            continue;
+        }
+        let distance = transform.translation.distance(perception.last_seen);
+        let step = RUN_SPEED * time.delta_secs();
+        if !perception.visible {
+            walk_towards(&mut transform, perception.last_seen, step);
+        } else if distance > ENGAGE_RANGE {
+            walk_towards(&mut transform, perception.last_seen, step.min(distance - ENGAGE_RANGE));
+        } else {
+            // In range: stand and face the target
+            let facing = Vec3::new(perception.last_seen.x, transform.translation.y, perception.last_seen.z);
+            if facing.distance(transform.translation) > f32::EPSILON {
+                transform.look_at(facing, Vec3::Y);
+            }
+        }
+    }
+}
+
+fn fire_weapons(
+    time: Res<Time>,
+    mut npcs: Query<(&Transform, &Perception, &mut Weapon, &Name), With<Npc>>,
+    mut targets: Query<(&mut Health, Option<&Name>)>,
+) {
+    let dt = time.delta_secs();
+    let mut rng = rand::rng();
+
+    for (transform, perception, mut weapon, name) in npcs.iter_mut() {
+        weapon.cooldown -= dt;
+        if let Some((_, _, remaining)) = &mut weapon.tracer {
+            *remaining -= dt;
+        }
+
+        let Some(target) = perception.target.filter(|_| perception.visible) else {
+            continue;
+        };
+        let muzzle = transform.translation + Vec3::Y * EYE_HEIGHT;
+        let distance = muzzle.distance(perception.last_seen);
+        if weapon.cooldown > 0.0 || distance > FIRE_RANGE {
+            continue;
+        }
+        weapon.cooldown = FIRE_INTERVAL;
+
+        let hit_chance = ACCURACY * (1.0 - 0.5 * distance / FIRE_RANGE);
+        let hit = rng.random::<f32>() < hit_chance;
+        let spread = if hit { Vec3::ZERO } else { Vec3::new(rng.random_range(-1.0..1.0), rng.random_range(-0.5..1.0), rng.random_range(-1.0..1.0)) };
+        weapon.tracer = Some((muzzle, perception.last_seen + spread, TRACER_TIME));
+
+        if hit && let Ok((mut health, target_name)) = targets.get_mut(target) {
+            health.current = (health.current - DAMAGE).max(0.0);
+            debug!("{} hit {} ({:.0} health left)", name, target_name.map_or("the player", |name| name.as_str()), health.current);
+        }
+    }
+}
+
+fn remove_dead_npcs(mut commands: Commands, npcs: Query<(Entity, &Health, &Name, &FactionMember), With<Npc>>) {
+    for (entity, health, name, faction) in npcs.iter() {
+        if health.current <= 0.0 {
+            info!("{} of {} was killed", name, faction.0);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn draw_tracers(npcs: Query<&Weapon, With<Npc>>, mut gizmos: Gizmos) {
+    for weapon in npcs.iter() {
+        if let Some((from, to, remaining)) = weapon.tracer
+            && remaining > 0.0
+        {
+            gizmos.line(from, to, Color::srgb(1.0, 0.85, 0.4));
+        }
+    }
+}
@@ -7,6 +7,7 @@ use futures_lite::future;
 
 use super::components::*;
 use super::resources::*;
+use crate::editor::core::types::{Door, LootContainer};
 
 pub struct PersistencePlugin;
 
@@ -27,6 +28,10 @@ impl Plugin for PersistencePlugin {
                     handle_load_request,
                     poll_load_task,
                     check_loading_complete,
+                    // Runs after doors are built; show_restored_state then recolors the doors it unlocks
+                    restore_world_objects
+                        .after(super::interaction::build_doors)
+                        .run_if(resource_exists::<RestoredWorldObjects>),
                 )
             );
     }
@@ -38,6 +43,18 @@ struct LoadTask(Task<Option<SavedGameState>>);
 struct SavedGameState {
     player: Option<PlayerData>,
     physics_objects: Vec<PhysicsObjectData>,
+    inventory: Vec<(String, u32)>,
+    /// Radiation the player had absorbed, in rads
+    radiation_dose: f32,
+    world_objects: RestoredWorldObjects,
+}
+
+/// Door and container state from a save, waiting to be put back on the scene
+#[derive(Resource, Default)]
+struct RestoredWorldObjects {
+    /// Door id, whether it was unlocked and whether it stood open
+    doors: Vec<(String, bool, bool)>,
+    looted_containers: Vec<String>,
 }
 
 struct PlayerData {
@@ -88,6 +105,48 @@ fn init_database(save_path: Res<SavePath>) {
             )",
             [],
         ).ok();
+
+        // Carried items
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS inventory (
+                slot INTEGER,
+                item TEXT,
+                count INTEGER,
+                PRIMARY KEY (slot, item)
+            )",
+            [],
+        ).ok();
+
+        // Radiation the player has absorbed
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS radiation (
+                slot INTEGER PRIMARY KEY,
+                dose REAL
+            )",
+            [],
+        ).ok();
+
+        // Scene doors the player has unlocked or opened, by id
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS doors (
+                slot INTEGER,
+                door_id TEXT,
+                unlocked INTEGER,
+                opened INTEGER,
+                PRIMARY KEY (slot, door_id)
+            )",
+            [],
+        ).ok();
+
+        // Scene containers the player has emptied, by id
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS looted_containers (
+                slot INTEGER,
+                container_id TEXT,
+                PRIMARY KEY (slot, container_id)
+            )",
+            [],
+        ).ok();
     }
 }
 
@@ -97,6 +156,8 @@ fn handle_save_game(
     save_path: Res<SavePath>,
     world: &World,
     saveable_query: Query<Entity, With<Saveable>>,
+    door_query: Query<(&Door, Has<Unlocked>, Has<Opened>)>,
+    looted_query: Query<&LootContainer, With<Looted>>,
 ) {
     for event in events.read() {
         if let Ok(mut conn) = Connection::open(&save_path.0) {
@@ -173,6 +234,23 @@ fn handle_save_game(
                         success = false;
                         break;
                     }
+
+                    let inventory = entity_ref.get::<Inventory>().cloned().unwrap_or_default();
+                    if let Err(e) = save_inventory(&tx, event.slot, &inventory) {
+                        error!("Failed to save inventory: {}", e);
+                        success = false;
+                        break;
+                    }
+
+                    let dose = entity_ref.get::<RadiationDose>().map_or(0.0, |radiation| radiation.dose);
+                    if let Err(e) = tx.execute(
+                        "INSERT OR REPLACE INTO radiation (slot, dose) VALUES (?1, ?2)",
+                        (event.slot, dose),
+                    ) {
+                        error!("Failed to save radiation dose: {}", e);
+                        success = false;
+                        break;
+                    }
                 }
                 // Is this a physics object?
                 else if entity_ref.contains::<RigidBody>() {
@@ -238,6 +316,11 @@ fn handle_save_game(
                 }
             }
 
+            if success && let Err(e) = save_world_objects(&tx, event.slot, &door_query, &looted_query) {
+                error!("Failed to save doors and containers: {}", e);
+                success = false;
+            }
+
             if success && saved_physics_count > 0 {
                 info!("Saved {} physics objects", saved_physics_count);
             }
@@ -255,6 +338,44 @@ fn handle_save_game(
     }
 }
 
+fn save_inventory(tx: &rusqlite::Transaction, slot: u32, inventory: &Inventory) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM inventory WHERE slot = ?1", [slot])?;
+    for (item, count) in &inventory.0 {
+        tx.execute(
+            "INSERT INTO inventory (slot, item, count) VALUES (?1, ?2, ?3)",
+            (slot, item, count),
+        )?;
+    }
+    Ok(())
+}
+
+/// Store the doors and containers that are no longer as the scene left them
+fn save_world_objects(
+    tx: &rusqlite::Transaction,
+    slot: u32,
+    door_query: &Query<(&Door, Has<Unlocked>, Has<Opened>)>,
+    looted_query: &Query<&LootContainer, With<Looted>>,
+) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM doors WHERE slot = ?1", [slot])?;
+    for (door, unlocked, opened) in door_query.iter() {
+        if unlocked || opened {
+            tx.execute(
+                "INSERT OR REPLACE INTO doors (slot, door_id, unlocked, opened) VALUES (?1, ?2, ?3, ?4)",
+                (slot, &door.id, unlocked, opened),
+            )?;
+        }
+    }
+
+    tx.execute("DELETE FROM looted_containers WHERE slot = ?1", [slot])?;
+    for container in looted_query.iter() {
+        tx.execute(
+            "INSERT OR REPLACE INTO looted_containers (slot, container_id) VALUES (?1, ?2)",
+            (slot, &container.id),
+        )?;
+    }
+    Ok(())
+}
+
 /// System to handle LoadGameEvent and transition to Loading state
 fn handle_load_request(
     mut events: EventReader<LoadGameEvent>,
@@ -288,6 +409,9 @@ fn handle_load_request(
                     let mut game_state = SavedGameState {
                         player: None,
                         physics_objects: Vec::new(),
+                        inventory: Vec::new(),
+                        radiation_dose: 0.0,
+                        world_objects: RestoredWorldObjects::default(),
                     };
 
                     // Load player
@@ -344,6 +468,21 @@ fn handle_load_request(
                         }
                     }
 
+                    // Load inventory, radiation, doors and containers
+                    game_state.inventory = load_rows(&conn, "SELECT item, count FROM inventory WHERE slot = ?1", slot, |row| {
+                        Ok((row.get(0)?, row.get(1)?))
+                    });
+                    game_state.radiation_dose = load_rows(&conn, "SELECT dose FROM radiation WHERE slot = ?1", slot, |row| row.get(0))
+                        .into_iter()
+                        .next()
+                        .unwrap_or(0.0);
+                    game_state.world_objects.doors = load_rows(&conn, "SELECT door_id, unlocked, opened FROM doors WHERE slot = ?1", slot, |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                    });
+                    game_state.world_objects.looted_containers = load_rows(&conn, "SELECT container_id FROM looted_containers WHERE slot = ?1", slot, |row| {
+                        row.get(0)
+                    });
+
                     Some(game_state)
                 }
                 Err(_) => None,
@@ -355,6 +494,22 @@ fn handle_load_request(
     }
 }
 
+/// Every row a query returns for a slot, skipping any that fail to read
+fn load_rows<T>(
+    conn: &Connection,
+    sql: &str,
+    slot: u32,
+    read: impl FnMut(&rusqlite::Row) -> rusqlite::Result<T>,
+) -> Vec<T> {
+    let Ok(mut stmt) = conn.prepare(sql) else {
+        return Vec::new();
+    };
+    match stmt.query_map([slot], read) {
+        Ok(rows) => rows.filter_map(Result::ok).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
 /// System to poll the load task and spawn entities when complete
 fn poll_load_task(
     mut commands: Commands,
@@ -384,6 +539,8 @@ fn poll_load_task(
                             maximum: 100.0,
                             current: player_data.health,
                         },
+                        Inventory(game_state.inventory.into_iter().collect()),
+                        RadiationDose { dose: game_state.radiation_dose, ..default() },
                         RigidBody::Dynamic,
                         Collider::capsule_y(0.65, 0.25),
                         Velocity::default(),
@@ -433,6 +590,8 @@ fn poll_load_task(
                     ));
                 }
 
+                commands.insert_resource(game_state.world_objects);
+
                 info!("Game loaded successfully");
             } else {
                 error!("Failed to load game data");
@@ -446,6 +605,31 @@ fn poll_load_task(
     }
 }
 
+/// Put saved door and container state back on the freshly loaded scene
+fn restore_world_objects(
+    mut commands: Commands,
+    restored: Res<RestoredWorldObjects>,
+    doors: Query<(Entity, &Door)>,
+    containers: Query<(Entity, &LootContainer)>,
+) {
+    for (entity, door) in doors.iter() {
+        if let Some((_, unlocked, opened)) = restored.doors.iter().find(|(id, _, _)| *id == door.id) {
+            if *unlocked {
+                commands.entity(entity).insert(Unlocked);
+            }
+            if *opened {
+                commands.entity(entity).insert(Opened);
+            }
+        }
+    }
+    for (entity, container) in containers.iter() {
+        if restored.looted_containers.contains(&container.id) {
+            commands.entity(entity).insert(Looted);
+        }
+    }
+    commands.remove_resource::<RestoredWorldObjects>();
+}
+
 /// System to check if loading is complete and transition to InGame
 fn check_loading_complete(
     progress: Option<Res<LoadProgress>>,
@@ -5,6 +5,7 @@ use bevy_rapier3d::prelude::*;
 use bevy_egui::PrimaryEguiContext;
 
 use super::components::*;
+use super::interaction::LOCKPICK_ITEM;
 use super::resources::*;
 
 pub struct PlayerPlugin;
@@ -38,6 +39,7 @@ pub fn setup_player(mut commands: Commands, spawn_position: Vec3) {
         Saveable,
         GameEntity,
         Health::default(),
+        Inventory([(LOCKPICK_ITEM.to_string(), 2)].into()),  // A couple of lockpicks to start with
         Transform::from_translation(spawn_position),  // Use provided spawn position
         PlayerCamera {
             sensitivity: 0.002,
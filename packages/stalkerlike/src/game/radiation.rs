@@ -0,0 +1,216 @@
+//! Radiation zones, absorbed dose and the Geiger counter
+//!
+//! `RadiationZone` volumes are placed in the editor and loaded with the scene.
+//! The player absorbs their dose rate, less what worn gear keeps out, and the
+//! dose slowly wears off outside them. Above `DOSE_THRESHOLD` the dose eats
+//! into health. The Geiger counter clicks at random at a rate that follows the
+//! exposure, so a denser patch sounds busier.
+
+use bevy::audio::{AudioSource, PlaybackSettings, Volume};
+use bevy::prelude::*;
+use std::sync::Arc;
+
+use super::components::*;
+use super::data_packs::GameData;
+use super::resources::GameState;
+use crate::editor::core::types::RadiationZone;
+
+/// Dose in rads above which the player starts losing health
+pub const DOSE_THRESHOLD: f32 = 100.0;
+/// Health lost per second for every rad above the threshold
+const DAMAGE_PER_RAD: f32 = 0.05;
+/// Rads shed per second while not exposed
+const DOSE_DECAY: f32 = 0.5;
+/// Average Geiger clicks per second for each rad per second of exposure
+const CLICKS_PER_RAD: f32 = 4.0;
+/// Clicks per second at which the counter saturates
+const MAX_CLICK_RATE: f32 = 80.0;
+/// Sample rate of the generated click
+const SAMPLE_RATE: u32 = 44_100;
+
+pub struct RadiationPlugin;
+
+impl Plugin for RadiationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, setup_geiger_counter)
+            .add_systems(
+                Update,
+                (
+                    add_radiation_to_player,
+                    cycle_protective_gear,
+                    (accumulate_radiation, radiation_damage, geiger_clicks).chain(),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Click sound and when the next click is due
+#[derive(Resource)]
+struct GeigerCounter {
+    click: Handle<AudioSource>,
+    /// Seconds until the next click
+    until_click: f32,
+    /// Xorshift state for the click intervals
+    seed: u32,
+}
+
+impl GeigerCounter {
+    /// Uniform random number in (0, 1]
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed >> 8) as f32 / (1u32 << 24) as f32 + f32::EPSILON
+    }
+}
+
+fn setup_geiger_counter(mut commands: Commands, mut audio_sources: ResMut<Assets<AudioSource>>) {
+    let click = audio_sources.add(AudioSource { bytes: Arc::from(click_wav()) });
+    commands.insert_resource(GeigerCounter { click, until_click: 0.0, seed: 0x9e37_79b9 });
+}
+
+/// A few milliseconds of decaying noise as a 16-bit mono WAV file
+fn click_wav() -> Vec<u8> {
+    let sample_count = SAMPLE_RATE / 250;
+    let mut noise: u32 = 0x1234_5678;
+    let samples: Vec<i16> = (0..sample_count)
+        .map(|i| {
+            noise = noise.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let value = (noise >> 16) as f32 / 32_768.0 - 1.0;
+            let envelope = (-(i as f32) / (sample_count as f32 / 6.0)).exp();
+            (value * envelope * i16::MAX as f32) as i16
+        })
+        .collect();
+
+    let data_size = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Give players from a new game or a save their radiation state
+/// A loaded player already carries the dose from the save
+fn add_radiation_to_player(
+    mut commands: Commands,
+    players: Query<(Entity, Has<RadiationDose>), (With<Player>, Without<EquippedGear>)>,
+) {
+    for (player, has_dose) in players.iter() {
+        let mut player = commands.entity(player);
+        player.insert(EquippedGear::default());
+        if !has_dose {
+            player.insert(RadiationDose::default());
+        }
+    }
+}
+
+/// Share of radiation the worn gear keeps out
+fn protection(gear: &EquippedGear, game_data: &GameData) -> f32 {
+    let let_through: f32 = gear
+        .0
+        .iter()
+        .filter_map(|id| game_data.items.get(id))
+        .map(|item| 1.0 - item.radiation_protection.clamp(0.0, 1.0))
+        .product();
+    1.0 - let_through
+}
+
+fn accumulate_radiation(
+    time: Res<Time>,
+    game_data: Res<GameData>,
+    zones: Query<(&RadiationZone, &GlobalTransform)>,
+    mut players: Query<(&Transform, &EquippedGear, &mut RadiationDose), With<Player>>,
+) {
+    let dt = time.delta_secs();
+    for (transform, gear, mut radiation) in players.iter_mut() {
+        radiation.exposure = zones
+            .iter()
+            .map(|(zone, zone_transform)| zone.rate_at(zone_transform, transform.translation))
+            .sum();
+
+        if radiation.exposure > 0.0 {
+            radiation.dose += radiation.exposure * (1.0 - protection(gear, &game_data)) * dt;
+        } else {
+            radiation.dose = (radiation.dose - DOSE_DECAY * dt).max(0.0);
+        }
+    }
+}
+
+fn radiation_damage(time: Res<Time>, mut players: Query<(&RadiationDose, &mut Health), With<Player>>) {
+    for (radiation, mut health) in players.iter_mut() {
+        let excess = radiation.dose - DOSE_THRESHOLD;
+        if excess > 0.0 {
+            health.current = (health.current - excess * DAMAGE_PER_RAD * time.delta_secs()).max(0.0);
+        }
+    }
+}
+
+/// Play clicks with exponentially distributed gaps, so they sound random
+fn geiger_clicks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut geiger: ResMut<GeigerCounter>,
+    players: Query<&RadiationDose, With<Player>>,
+) {
+    let Ok(radiation) = players.single() else {
+        return;
+    };
+    let rate = (radiation.exposure * CLICKS_PER_RAD).min(MAX_CLICK_RATE);
+    if rate <= 0.0 {
+        geiger.until_click = 0.0;
+        return;
+    }
+
+    geiger.until_click -= time.delta_secs();
+    while geiger.until_click <= 0.0 {
+        commands.spawn((
+            AudioPlayer(geiger.click.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(0.5)),
+            GameEntity,
+        ));
+        geiger.until_click += -geiger.random().ln() / rate;
+    }
+}
+
+/// Put on the next piece of protective gear from the data packs, or take it off
+/// Stands in for an inventory screen until there is one
+fn cycle_protective_gear(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    game_data: Res<GameData>,
+    mut players: Query<&mut EquippedGear, With<Player>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+
+    let mut protective: Vec<_> = game_data.items.values().filter(|item| item.radiation_protection > 0.0).collect();
+    protective.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for mut gear in players.iter_mut() {
+        let next = gear
+            .0
+            .first()
+            .and_then(|id| protective.iter().position(|item| &item.id == id))
+            .map_or(0, |index| index + 1);
+        gear.0 = protective.get(next).map(|item| vec![item.id.clone()]).unwrap_or_default();
+        match protective.get(next) {
+            Some(item) => info!("Wearing {} ({:.0}% radiation protection)", item.name, item.radiation_protection * 100.0),
+            None => info!("Removed protective gear"),
+        }
+    }
+}
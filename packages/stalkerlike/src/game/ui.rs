@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
 
+use super::components::{EquippedGear, Health, Inventory, Player, RadiationDose};
+use super::data_packs::{GameData, ReloadDataPacksEvent};
+use super::interaction::{InteractionHud, LockPick, Lockpicking, LOCKPICK_ITEM};
+use super::radiation::DOSE_THRESHOLD;
 use super::resources::*;
 
 pub struct UiPlugin;
@@ -33,6 +37,8 @@ fn main_menu_ui(
     mut contexts: EguiContexts,
     mut next_state: ResMut<NextState<GameState>>,
     mut load_events: EventWriter<LoadGameEvent>,
+    mut reload_events: EventWriter<ReloadDataPacksEvent>,
+    game_data: Option<Res<GameData>>,
 ) -> Result {
     egui::CentralPanel::default()
         .show(contexts.ctx_mut()?, |ui| {
@@ -57,11 +63,46 @@ fn main_menu_ui(
                 if ui.button("Exit").clicked() {
                     std::process::exit(0);
                 }
+
+                if let Some(game_data) = &game_data {
+                    ui.add_space(30.0);
+                    data_pack_status(ui, game_data, &mut reload_events);
+                }
             });
         });
     Ok(())
 }
 
+/// Data pack summary, and any validation errors so content can be fixed without digging through logs
+fn data_pack_status(ui: &mut egui::Ui, game_data: &GameData, reload_events: &mut EventWriter<ReloadDataPacksEvent>) {
+    ui.label(format!(
+        "Data packs: {} ({} items, {} NPCs, {} factions, {} loot tables)",
+        game_data.packs.join(", "),
+        game_data.items.len(),
+        game_data.npcs.len(),
+        game_data.factions.len(),
+        game_data.loot_tables.len(),
+    ));
+
+    if !game_data.errors.is_empty() {
+        ui.add_space(10.0);
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 100, 100),
+            format!("{} data pack errors:", game_data.errors.len()),
+        );
+        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+            for error in &game_data.errors {
+                ui.colored_label(egui::Color32::from_rgb(255, 160, 160), error.to_string());
+            }
+        });
+    }
+
+    ui.add_space(10.0);
+    if ui.button("Reload Data Packs").clicked() {
+        reload_events.write(ReloadDataPacksEvent);
+    }
+}
+
 fn pause_menu_ui(
     mut contexts: EguiContexts,
     mut next_state: ResMut<NextState<GameState>>,
@@ -113,15 +154,126 @@ fn pause_menu_ui(
     Ok(())
 }
 
+/// What the HUD shows about the player
+type HudQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Health, Option<&'static RadiationDose>, Option<&'static EquippedGear>, Option<&'static Inventory>),
+    With<Player>,
+>;
+
 fn ingame_ui(
     mut contexts: EguiContexts,
+    game_data: Res<GameData>,
+    interaction: Res<InteractionHud>,
+    lockpicking: Res<Lockpicking>,
+    players: HudQuery,
 ) -> Result {
-    // Empty UI during gameplay - just consume the egui pass so bevy_egui doesn't error
-    // We could add HUD elements here later
-    let _ctx = contexts.ctx_mut()?;
+    let ctx = contexts.ctx_mut()?;
+    let Ok((health, radiation, gear, inventory)) = players.single() else {
+        return Ok(());
+    };
+
+    // What E would do, and what just happened, under the crosshair
+    egui::Area::new(egui::Id::new("interaction"))
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 60.0))
+        .show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                if let Some(prompt) = &interaction.prompt {
+                    ui.label(egui::RichText::new(prompt).color(egui::Color32::WHITE));
+                }
+                if let Some(message) = &interaction.message {
+                    ui.label(egui::RichText::new(message).color(egui::Color32::from_rgb(230, 210, 140)));
+                }
+            });
+        });
+
+    if let Some(pick) = &lockpicking.0 {
+        lockpicking_ui(ctx, pick, inventory);
+    }
+
+    // Carried items in the bottom right corner
+    if let Some(inventory) = inventory.filter(|inventory| !inventory.0.is_empty()) {
+        egui::Area::new(egui::Id::new("inventory"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -16.0))
+            .show(ctx, |ui| {
+                for (id, count) in &inventory.0 {
+                    let item = game_data.items.get(id);
+                    let name = item.map_or(id.as_str(), |item| item.name.as_str());
+                    let label = ui.label(if *count > 1 { format!("{} x{}", name, count) } else { name.to_string() });
+                    // Description and trade value on hover
+                    if let Some(item) = item {
+                        label.on_hover_text(format!("{}\n{} RU each", item.description, item.value));
+                    }
+                }
+            });
+    }
+
+    // Health and radiation meters in the bottom left corner
+    egui::Area::new(egui::Id::new("hud"))
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(16.0, -16.0))
+        .show(ctx, |ui| {
+            ui.set_width(220.0);
+            ui.add(
+                egui::ProgressBar::new(health.current / health.maximum)
+                    .fill(egui::Color32::from_rgb(170, 40, 40))
+                    .text(format!("Health {:.0}", health.current)),
+            );
+
+            let Some(radiation) = radiation else {
+                return;
+            };
+            // The bar is full at twice the threshold, so the damage line sits in the middle
+            let fill = if radiation.dose > DOSE_THRESHOLD {
+                egui::Color32::from_rgb(220, 200, 40)
+            } else {
+                egui::Color32::from_rgb(90, 160, 40)
+            };
+            ui.add(
+                egui::ProgressBar::new(radiation.dose / (DOSE_THRESHOLD * 2.0))
+                    .fill(fill)
+                    .text(format!("Radiation {:.0} rad", radiation.dose)),
+            );
+            if radiation.exposure > 0.0 {
+                ui.colored_label(
+                    egui::Color32::from_rgb(220, 200, 40),
+                    format!("☢ {:.1} rad/s", radiation.exposure),
+                );
+            }
+
+            let worn: Vec<&str> = gear
+                .map(|gear| gear.0.iter().filter_map(|id| game_data.items.get(id)).map(|item| item.name.as_str()).collect())
+                .unwrap_or_default();
+            ui.label(if worn.is_empty() { "Gear: none (G to cycle)".to_string() } else { format!("Gear: {} (G to cycle)", worn.join(", ")) });
+        });
     Ok(())
 }
 
+/// The lock as a bar with the sweet spot marked and the pin sweeping across it
+fn lockpicking_ui(ctx: &egui::Context, pick: &LockPick, inventory: Option<&Inventory>) {
+    egui::Window::new("Lockpicking")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, -120.0))
+        .show(ctx, |ui| {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(300.0, 24.0), egui::Sense::hover());
+            let painter = ui.painter();
+            painter.rect_filled(rect, 2.0, egui::Color32::from_gray(40));
+
+            let x = |share: f32| rect.left() + share * rect.width();
+            let sweet_spot = egui::Rect::from_x_y_ranges(
+                x(pick.sweet_spot - pick.width / 2.0)..=x(pick.sweet_spot + pick.width / 2.0),
+                rect.y_range(),
+            );
+            painter.rect_filled(sweet_spot, 0.0, egui::Color32::from_rgb(60, 150, 60));
+            painter.vline(x(pick.pin), rect.y_range(), egui::Stroke::new(3.0, egui::Color32::WHITE));
+
+            let lockpicks = inventory.map_or(0, |inventory| inventory.count(LOCKPICK_ITEM));
+            ui.label(format!("Level {} lock. E to set the pin in the green, walk away to stop.", pick.lock_level));
+            ui.label(format!("Lockpicks: {}", lockpicks));
+        });
+}
+
 fn loading_screen_ui(
     mut contexts: EguiContexts,
     progress: Option<Res<LoadProgress>>,
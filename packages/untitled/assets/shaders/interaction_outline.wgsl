@@ -0,0 +1,23 @@
+// Rim inside the edge of a hovered interactable's mesh, see world/outline.rs
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct OutlineMaterial {
+    color: vec4<f32>,
+    outline_color: vec4<f32>,
+    size: vec2<f32>,
+    width: f32,
+}
+
+@group(2) @binding(0) var<uniform> material: OutlineMaterial;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // Distance to the nearest edge in world units
+    let to_edge = min(mesh.uv, 1.0 - mesh.uv) * material.size;
+    let distance = min(to_edge.x, to_edge.y);
+
+    // Solid at the edge, fading out towards the middle
+    let rim = 1.0 - smoothstep(0.0, material.width, distance);
+    let strength = rim * material.outline_color.a;
+    return vec4<f32>(mix(material.color.rgb, material.outline_color.rgb, strength), max(material.color.a, strength));
+}
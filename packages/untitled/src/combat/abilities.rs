@@ -0,0 +1,269 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::components::{Health, Team};
+use super::{effects::*, resolver::CombatState};
+
+/// Unique identifier for ability definitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AbilityId(pub u32);
+
+/// Who an ability hits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AbilityTargeting {
+    /// Only the caster
+    SelfCast,
+    /// Hostiles within `radius` of the caster
+    AreaAroundSelf { radius: f32 },
+    /// Hostiles within `radius` of the aimed position, which is clamped to `max_range`
+    AreaAtTarget { radius: f32, max_range: f32 },
+}
+
+/// Data definition of an active ability
+/// Abilities never touch health directly, they only emit effects
+#[derive(Debug, Clone)]
+pub struct AbilityDefinition {
+    pub name: String,
+    pub energy_cost: f32,
+    /// Seconds between casts
+    pub cooldown: f32,
+    pub targeting: AbilityTargeting,
+    /// Effects applied to every target
+    pub effects: Vec<EffectDefId>,
+}
+
+/// Registry of all ability definitions
+#[derive(Resource, Default)]
+pub struct AbilityRegistry {
+    pub abilities: HashMap<AbilityId, AbilityDefinition>,
+}
+
+impl AbilityRegistry {
+    /// Starter abilities, backed by effects from `EffectRegistry::with_basic_effects`
+    pub fn with_basic_abilities() -> Self {
+        let mut registry = Self::default();
+
+        registry.register(AbilityId(1), AbilityDefinition {
+            name: "Mend".to_string(),
+            energy_cost: 30.0,
+            cooldown: 8.0,
+            targeting: AbilityTargeting::SelfCast,
+            effects: vec![EffectDefId(6)],
+        });
+
+        registry.register(AbilityId(2), AbilityDefinition {
+            name: "Shockwave".to_string(),
+            energy_cost: 40.0,
+            cooldown: 6.0,
+            targeting: AbilityTargeting::AreaAroundSelf { radius: 150.0 },
+            effects: vec![EffectDefId(7)],
+        });
+
+        registry.register(AbilityId(3), AbilityDefinition {
+            name: "Siphon".to_string(),
+            energy_cost: 25.0,
+            cooldown: 4.0,
+            targeting: AbilityTargeting::AreaAtTarget { radius: 60.0, max_range: 400.0 },
+            effects: vec![EffectDefId(8)],
+        });
+
+        registry
+    }
+
+    pub fn register(&mut self, id: AbilityId, definition: AbilityDefinition) {
+        self.abilities.insert(id, definition);
+    }
+
+    pub fn get(&self, id: AbilityId) -> Option<&AbilityDefinition> {
+        self.abilities.get(&id)
+    }
+}
+
+/// Secondary resource spent on abilities
+#[derive(Component, Debug, Clone)]
+pub struct Energy {
+    pub current: f32,
+    pub max: f32,
+    pub regen_per_second: f32,
+}
+
+impl Energy {
+    pub fn new(max: f32, regen_per_second: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_per_second,
+        }
+    }
+
+    /// Spend energy if there is enough, returns whether it was spent
+    pub fn try_spend(&mut self, amount: f32) -> bool {
+        if self.current < amount {
+            return false;
+        }
+        self.current -= amount;
+        true
+    }
+}
+
+/// An equipped ability and its cooldown
+#[derive(Debug, Clone)]
+pub struct AbilitySlot {
+    pub ability: AbilityId,
+    /// Seconds until the ability can be cast again
+    pub cooldown_remaining: f32,
+}
+
+/// Abilities an entity can cast, indexed by slot
+#[derive(Component, Debug, Clone, Default)]
+pub struct AbilitySlots {
+    pub slots: Vec<Option<AbilitySlot>>,
+}
+
+impl AbilitySlots {
+    pub fn new(abilities: impl IntoIterator<Item = AbilityId>) -> Self {
+        Self {
+            slots: abilities
+                .into_iter()
+                .map(|ability| Some(AbilitySlot { ability, cooldown_remaining: 0.0 }))
+                .collect(),
+        }
+    }
+}
+
+/// Request to cast the ability in a slot
+#[derive(Event, Debug, Clone)]
+pub struct AbilityCastEvent {
+    pub caster: Entity,
+    pub slot: usize,
+    /// Aimed world position, for targeted abilities
+    pub target_position: Option<Vec2>,
+}
+
+/// Where a targeted ability lands, clamped to its range from the caster
+fn clamp_to_range(caster_pos: Vec2, target: Vec2, max_range: f32) -> Vec2 {
+    let offset = target - caster_pos;
+    caster_pos + offset.clamp_length_max(max_range)
+}
+
+/// Validate casts (cooldown, energy) and turn them into effect events
+pub fn resolve_ability_casts(
+    mut cast_events: EventReader<AbilityCastEvent>,
+    mut effect_events: EventWriter<EffectEvent>,
+    registry: Res<AbilityRegistry>,
+    mut casters: Query<(&Transform, &mut AbilitySlots, Option<&mut Energy>, Option<&Team>)>,
+    targets: Query<(Entity, &Transform, Option<&Team>), Or<(With<CombatState>, With<Health>)>>,
+) {
+    for cast in cast_events.read() {
+        let Ok((caster_transform, mut slots, energy, caster_team)) = casters.get_mut(cast.caster) else {
+            continue;
+        };
+
+        let Some(Some(slot)) = slots.slots.get_mut(cast.slot) else {
+            continue;
+        };
+        let Some(definition) = registry.get(slot.ability) else {
+            warn!("Ability {:?} is not registered", slot.ability);
+            continue;
+        };
+
+        if slot.cooldown_remaining > 0.0 {
+            continue;
+        }
+
+        match energy {
+            Some(mut energy) => {
+                if !energy.try_spend(definition.energy_cost) {
+                    debug!("Not enough energy for {}", definition.name);
+                    continue;
+                }
+            }
+            None if definition.energy_cost > 0.0 => continue,
+            None => {}
+        }
+
+        slot.cooldown_remaining = definition.cooldown;
+
+        let caster_pos = caster_transform.translation.truncate();
+        let is_hostile = |team: Option<&Team>| match (caster_team, team) {
+            (Some(caster_team), Some(team)) => caster_team != team,
+            _ => true,
+        };
+
+        let hit: Vec<(Entity, Vec2)> = match definition.targeting {
+            AbilityTargeting::SelfCast => vec![(cast.caster, caster_pos)],
+            AbilityTargeting::AreaAroundSelf { radius } => targets.iter()
+                .filter(|(entity, _, team)| *entity != cast.caster && is_hostile(*team))
+                .map(|(entity, transform, _)| (entity, transform.translation.truncate()))
+                .filter(|(_, pos)| pos.distance(caster_pos) <= radius)
+                .collect(),
+            AbilityTargeting::AreaAtTarget { radius, max_range } => {
+                let center = clamp_to_range(caster_pos, cast.target_position.unwrap_or(caster_pos), max_range);
+                targets.iter()
+                    .filter(|(entity, _, team)| *entity != cast.caster && is_hostile(*team))
+                    .map(|(entity, transform, _)| (entity, transform.translation.truncate()))
+                    .filter(|(_, pos)| pos.distance(center) <= radius)
+                    .collect()
+            }
+        };
+
+        if hit.is_empty() {
+            continue;
+        }
+
+        let (hit_targets, hit_positions): (Vec<_>, Vec<_>) = hit.into_iter().unzip();
+        for &effect_id in &definition.effects {
+            effect_events.write(EffectEvent {
+                source: cast.caster,
+                targets: hit_targets.clone(),
+                effect_id,
+                hit_positions: hit_positions.clone(),
+            });
+        }
+    }
+}
+
+/// Count down ability cooldowns
+pub fn tick_ability_cooldowns(
+    time: Res<Time>,
+    mut slots_query: Query<&mut AbilitySlots>,
+) {
+    let dt = time.delta_secs();
+    for mut slots in slots_query.iter_mut() {
+        for slot in slots.slots.iter_mut().flatten() {
+            slot.cooldown_remaining = (slot.cooldown_remaining - dt).max(0.0);
+        }
+    }
+}
+
+/// Regenerate energy over time
+pub fn regenerate_energy(
+    time: Res<Time>,
+    mut energy_query: Query<&mut Energy>,
+) {
+    for mut energy in energy_query.iter_mut() {
+        if energy.current < energy.max {
+            energy.current = (energy.current + energy.regen_per_second * time.delta_secs()).min(energy.max);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energy_spend() {
+        let mut energy = Energy::new(50.0, 0.0);
+        assert!(energy.try_spend(30.0));
+        assert!(!energy.try_spend(30.0));
+        assert_eq!(energy.current, 20.0);
+    }
+
+    #[test]
+    fn test_target_clamped_to_range() {
+        let center = clamp_to_range(Vec2::ZERO, Vec2::new(1000.0, 0.0), 400.0);
+        assert_eq!(center, Vec2::new(400.0, 0.0));
+        assert_eq!(clamp_to_range(Vec2::ZERO, Vec2::new(10.0, 0.0), 400.0), Vec2::new(10.0, 0.0));
+    }
+}
@@ -1,10 +1,23 @@
 use bevy::prelude::*;
 use std::collections::HashMap;
 
+use super::healing::{HealingData, OverhealRule};
+
 /// Unique identifier for effect definitions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct EffectDefId(pub u32);
 
+/// Effects the game fires from code rather than from abilities
+impl EffectDefId {
+    pub const PLAYER_PROJECTILE: EffectDefId = EffectDefId(1);
+    pub const ENEMY_BULLET: EffectDefId = EffectDefId(2);
+    /// One pulse of a hazard zone; zones pulse faster the more damage per second they deal
+    pub const HAZARD_PULSE: EffectDefId = EffectDefId(9);
+}
+
+/// Damage of one `EffectDefId::HAZARD_PULSE`
+pub const HAZARD_PULSE_DAMAGE: f32 = 4.0;
+
 /// Identifier for damage types - fully data-driven
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct DamageType(pub u32);
@@ -54,6 +67,8 @@ pub struct DamageEvent {
     pub damage: f32,
     pub damage_type: DamageType,
     pub source: Entity,
+    /// Whether this hit rolled a critical
+    pub critical: bool,
 }
 
 /// Event emitted after status effect calculation but before application
@@ -96,6 +111,10 @@ pub struct EffectDefinition {
     pub damage: f32,
     pub damage_type: DamageType,
     pub status_effects: Vec<StatusEffectData>,
+    /// Healing applied to the targets
+    pub healing: Option<HealingData>,
+    /// Fraction of the damage dealt returned to the source as healing
+    pub lifesteal: f32,
 }
 
 /// Data for a status effect within an effect definition
@@ -158,6 +177,8 @@ impl EffectRegistry {
                     stack_behavior: StackBehavior::Stack,
                 }
             ],
+            healing: None,
+            lifesteal: 0.0,
         });
 
         // Enemy bullet
@@ -165,6 +186,8 @@ impl EffectRegistry {
             damage: 15.0,
             damage_type: DamageType::PHYSICAL,
             status_effects: vec![], // No knockback
+            healing: None,
+            lifesteal: 0.0,
         });
 
         // Grenade explosion
@@ -179,6 +202,8 @@ impl EffectRegistry {
                     stack_behavior: StackBehavior::Stack,
                 }
             ],
+            healing: None,
+            lifesteal: 0.0,
         });
 
         // Contact damage
@@ -186,10 +211,76 @@ impl EffectRegistry {
             damage: 25.0,
             damage_type: DamageType::PHYSICAL,
             status_effects: vec![], // No knockback
+            healing: None,
+            lifesteal: 0.0,
+        });
+
+        // Healing shrine pulse
+        registry.register_effect(EffectDefId(5), EffectDefinition {
+            damage: 0.0,
+            damage_type: DamageType::TRUE,
+            status_effects: vec![],
+            healing: Some(HealingData {
+                instant: 10.0,
+                over_time: 30.0,
+                duration: 5.0,
+                overheal: OverhealRule::Shield { cap: 0.25 },
+            }),
+            lifesteal: 0.0,
+        });
+
+        // Mend ability
+        registry.register_effect(EffectDefId(6), EffectDefinition {
+            damage: 0.0,
+            damage_type: DamageType::TRUE,
+            status_effects: vec![],
+            healing: Some(HealingData {
+                instant: 25.0,
+                over_time: 0.0,
+                duration: 0.0,
+                overheal: OverhealRule::Discard,
+            }),
+            lifesteal: 0.0,
+        });
+
+        // Shockwave ability
+        registry.register_effect(EffectDefId(7), EffectDefinition {
+            damage: 30.0,
+            damage_type: DamageType::MAGICAL,
+            status_effects: vec![
+                StatusEffectData {
+                    status_id: StatusId::KNOCKBACK,
+                    intensity: 300.0,
+                    duration: 0.0, // Instant knockback
+                    stack_behavior: StackBehavior::Stack,
+                }
+            ],
+            healing: None,
+            lifesteal: 0.0,
+        });
+
+        // Siphon ability
+        registry.register_effect(EffectDefId(8), EffectDefinition {
+            damage: 20.0,
+            damage_type: DamageType::MAGICAL,
+            status_effects: vec![],
+            healing: None,
+            lifesteal: 0.5,
+        });
+
+        // Hazard zone pulse
+        registry.register_effect(EffectDefId::HAZARD_PULSE, EffectDefinition {
+            damage: HAZARD_PULSE_DAMAGE,
+            damage_type: DamageType::TRUE,
+            status_effects: vec![],
+            healing: None,
+            lifesteal: 0.0,
         });
 
         registry
-    }    pub fn register_effect(&mut self, id: EffectDefId, definition: EffectDefinition) {
+    }
+
+    pub fn register_effect(&mut self, id: EffectDefId, definition: EffectDefinition) {
         self.effects.insert(id, definition);
     }
 
@@ -16,6 +16,7 @@ impl Plugin for FowPlugin {
         app
             // Initialize resources
             .init_resource::<FowTaskSet>()
+            .init_resource::<crate::pool::PoolMetrics>()
             // FixedUpdate: Core FOW logic (deterministic, budgeted)
             .add_systems(
                 FixedUpdate,
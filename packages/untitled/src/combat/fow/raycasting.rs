@@ -1,14 +1,11 @@
 //! Raycasting utilities for line-of-sight calculations
 //!
-//! Provides Bresenham's line algorithm implementation for wall-based
-//! line-of-sight blocking in the fog of war system.
+//! Turns the terrain snapshot into wall outlines, so the fog of war can use the
+//! same visibility polygons as the rest of the game.
 
 use bevy::prelude::*;
 use std::collections::HashMap;
 
-/// Safety limit for raycasting to prevent infinite loops
-const RAYCAST_MAX_STEPS: i32 = 1000;
-
 /// Check if there's a wall at the given world tile coordinates
 ///
 /// # Arguments
@@ -37,73 +34,74 @@ pub fn is_wall_at(
         .unwrap_or(false) // Treat missing chunks as passable (not walls)
 }
 
-/// Cast a ray using Bresenham's line algorithm to check line of sight
-///
-/// This function traces a line from the start point to the end point,
-/// checking for walls along the way. It returns the distance traveled
-/// before hitting a wall, or the full distance if the path is clear.
-///
-/// This allows for soft blending near walls by comparing the ray distance
-/// to the actual distance to the target tile.
-///
-/// # Arguments
-/// * `terrain` - Map of chunk positions to wall data
-/// * `from_x`, `from_y` - Starting position in world tile coordinates
-/// * `to_x`, `to_y` - Target position in world tile coordinates
-/// * `chunk_size` - Size of terrain chunks in tiles
+/// Wall faces bordering open tiles between `min` and `max`, merged into straight runs
 ///
-/// # Returns
-/// Distance traveled before hitting a wall, or full distance if clear
-pub fn cast_ray_distance(
+/// The faces are occluders for `shadowcast::compute_visibility_polygon`, in
+/// world tile coordinates: tiles are centered on whole numbers, so every face
+/// lies half a tile from the center of its wall.
+pub fn wall_occluders(
     terrain: &HashMap<IVec2, Vec<Vec<bool>>>,
-    from_x: i32,
-    from_y: i32,
-    to_x: i32,
-    to_y: i32,
+    min: IVec2,
+    max: IVec2,
     chunk_size: usize,
-) -> f32 {
-    let dx = (to_x - from_x).abs();
-    let dy = (to_y - from_y).abs();
-    let sx = if from_x < to_x { 1 } else { -1 };
-    let sy = if from_y < to_y { 1 } else { -1 };
-    let mut err = dx - dy;
+) -> Vec<Vec<Vec2>> {
+    let wall = |x: i32, y: i32| is_wall_at(terrain, x, y, chunk_size);
+    let mut faces = Vec::new();
 
-    let mut x = from_x;
-    let mut y = from_y;
-    let mut step_count = 0;
+    for side in [-1, 1] {
+        let offset = side as f32 * 0.5;
+        // Faces below and above each row of walls
+        for y in min.y..=max.y {
+            push_face_runs(&mut faces, min.x, max.x, |x| wall(x, y) && !wall(x, y + side), |start, end| {
+                [Vec2::new(start - 0.5, y as f32 + offset), Vec2::new(end - 0.5, y as f32 + offset)]
+            });
+        }
+        // Faces left and right of each column of walls
+        for x in min.x..=max.x {
+            push_face_runs(&mut faces, min.y, max.y, |y| wall(x, y) && !wall(x + side, y), |start, end| {
+                [Vec2::new(x as f32 + offset, start - 0.5), Vec2::new(x as f32 + offset, end - 0.5)]
+            });
+        }
+    }
 
-    // Calculate total distance to target
-    let total_distance = ((to_x - from_x) as f32).hypot((to_y - from_y) as f32);
+    faces
+}
 
-    loop {
-        // Don't check the starting position
-        if x != from_x || y != from_y {
-            if is_wall_at(terrain, x, y, chunk_size) {
-                // Hit a wall - return distance traveled so far
-                let current_distance = ((x - from_x) as f32).hypot((y - from_y) as f32);
-                return current_distance;
+/// Merge consecutive exposed tiles along one line into a single face each
+fn push_face_runs(
+    faces: &mut Vec<Vec<Vec2>>,
+    from: i32,
+    to: i32,
+    exposed: impl Fn(i32) -> bool,
+    face: impl Fn(f32, f32) -> [Vec2; 2],
+) {
+    let mut run_start = None;
+    for i in from..=to + 1 {
+        match (i <= to && exposed(i), run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                faces.push(face(start as f32, i as f32).to_vec());
+                run_start = None;
             }
+            _ => {}
         }
+    }
+}
 
-        // Reached the destination without hitting a wall
-        if x == to_x && y == to_y {
-            return total_distance;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let e2 = 2 * err;
-        if e2 > -dy {
-            err -= dy;
-            x += sx;
-        }
-        if e2 < dx {
-            err += dx;
-            y += sy;
-        }
-        step_count += 1;
+    #[test]
+    fn test_wall_faces_merge_into_runs() {
+        // A 3-wide wall along the bottom of an otherwise open chunk
+        let mut chunk = vec![vec![false; 8]; 8];
+        chunk[0][2..5].fill(true);
+        let terrain = HashMap::from([(IVec2::ZERO, chunk)]);
 
-        // Safety check to prevent infinite loops
-        if step_count > RAYCAST_MAX_STEPS {
-            return total_distance;
-        }
+        let faces = wall_occluders(&terrain, IVec2::ZERO, IVec2::splat(7), 8);
+        assert_eq!(faces.len(), 4);
+        assert!(faces.contains(&vec![Vec2::new(1.5, 0.5), Vec2::new(4.5, 0.5)]));
+        assert!(faces.contains(&vec![Vec2::new(1.5, -0.5), Vec2::new(1.5, 0.5)]));
     }
 }
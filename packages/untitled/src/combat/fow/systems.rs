@@ -15,7 +15,7 @@
 //!
 //! 3. **Vision Calculation**: Background tasks compute two-tier vision:
 //!    - **Force Radius**: Inner area always visible regardless of obstacles
-//!    - **LOS Radius**: Outer area using raycasting for wall-based line-of-sight
+//!    - **LOS Radius**: Outer area limited by a visibility polygon built from the walls
 //!
 //! 4. **Task Polling**: Completed tasks are polled and their results applied to chunk
 //!    `desired_vision` data. Affected chunks are marked with `NeedsLerp`.
@@ -65,9 +65,6 @@ use super::*;
 // Constants
 // ========================================
 
-/// Z-layer for fog of war rendering (above terrain and entities)
-pub const FOW_Z: f32 = 10.0;
-
 /// Chunk size in tiles
 const CHUNK_SIZE_TILES: usize = CHUNK_SIZE as usize;
 
@@ -282,7 +279,7 @@ fn calculate_tile_visibility(
 /// Spawn FOW calculation tasks for revealers that have moved
 ///
 /// When a revealer (e.g., player) moves, this system:
-/// 1. Creates a terrain snapshot for raycasting
+/// 1. Creates a terrain snapshot the wall outlines are built from
 /// 2. Spawns async tasks on the compute pool
 /// 3. Prevents duplicate tasks for the same revealer
 pub fn spawn_fow_calculation_tasks(
@@ -299,7 +296,7 @@ pub fn spawn_fow_calculation_tasks(
         return;
     }
 
-    // Create a snapshot of terrain data (walls) for raycasting
+    // Create a snapshot of terrain data (walls) for line of sight
     // NOTE: terrain tiles use [x][y] indexing, but we store as [y][x] for FOW consistency
     let mut terrain_snapshot = HashMap::new();
 
@@ -384,6 +381,16 @@ fn calculate_fow_for_work_item(work_item: &FowWorkItem) -> FowWorkResult {
         work_item.los_strength,
     );
 
+    // Everything the revealer can see, from the walls around it
+    let origin = Vec2::new(revealer_tile_x as f32, revealer_tile_y as f32);
+    let occluders = super::raycasting::wall_occluders(
+        &work_item.terrain_snapshot,
+        IVec2::new(revealer_tile_x - stamp_radius, revealer_tile_y - stamp_radius),
+        IVec2::new(revealer_tile_x + stamp_radius, revealer_tile_y + stamp_radius),
+        CHUNK_SIZE_TILES,
+    );
+    let visible_area = shadowcast::compute_visibility_polygon(origin, stamp_radius as f32, &occluders);
+
     // Calculate the range of chunks that might be affected
     let min_chunk_x = ((revealer_tile_x - stamp_radius) as f32 / CHUNK_SIZE_TILES as f32).floor() as i32;
     let max_chunk_x = ((revealer_tile_x + stamp_radius) as f32 / CHUNK_SIZE_TILES as f32).floor() as i32;
@@ -408,7 +415,7 @@ fn calculate_fow_for_work_item(work_item: &FowWorkItem) -> FowWorkResult {
             let start_y = (revealer_tile_y - stamp_radius - chunk_offset_y).max(0);
             let end_y = (revealer_tile_y + stamp_radius - chunk_offset_y).min(CHUNK_SIZE_TILES as i32 - 1);
 
-            // Apply the vision stamp to the chunk with raycasting for LOS
+            // Apply the vision stamp to the chunk, clipped by the visibility polygon
             for local_y in start_y..=end_y {
                 for local_x in start_x..=end_x {
                     let world_tile_x = chunk_offset_x + local_x;
@@ -431,20 +438,15 @@ fn calculate_fow_for_work_item(work_item: &FowWorkItem) -> FowWorkResult {
                     let force_radius_sq = (work_item.force_radius as f32).powi(2);
                     let distance_sq = distance * distance;
 
-                    // Check if we need to raycast for line of sight
+                    // Check if line of sight matters for this tile
                     let visibility = if distance_sq <= force_radius_sq {
                         // Within force radius - always visible
                         stamp_visibility
                     } else if stamp_visibility > 0 {
-                        // Beyond force radius - check line of sight with distance-based blending
-                        let ray_distance = super::raycasting::cast_ray_distance(
-                            &work_item.terrain_snapshot,
-                            revealer_tile_x,
-                            revealer_tile_y,
-                            world_tile_x,
-                            world_tile_y,
-                            CHUNK_SIZE_TILES
-                        );
+                        // Beyond force radius - check line of sight with distance-based blending.
+                        // The polygon stops at the wall face, so count the wall tile behind it as seen
+                        let target = Vec2::new(dx as f32, dy as f32);
+                        let ray_distance = shadowcast::reach_along(&visible_area, origin, target) + 0.5;
 
                         if ray_distance >= distance {
                             // Clear line of sight - use full stamp visibility
@@ -662,7 +664,10 @@ pub fn draw_fow(
     mut chunks_query: Query<(Entity, &FowChunk), Changed<FowChunk>>,
     mut overlay_query: Query<(&mut Sprite, &FowOverlay)>,
     mut images: ResMut<Assets<Image>>,
+    mut pool_metrics: ResMut<crate::pool::PoolMetrics>,
 ) {
+    let stats = pool_metrics.stats_mut("fow_textures");
+
     // Index existing overlay entities by chunk position
     let mut overlays = overlay_query
         .iter_mut()
@@ -671,12 +676,20 @@ pub fn draw_fow(
 
     for (chunk_entity, chunk) in chunks_query.iter_mut() {
         if let Some((sprite, _)) = overlays.get_mut(&chunk.position) {
-            // Update the sprite's image based on interpolated current_vision
-            let vision_texture = create_fow_texture(&chunk.current_vision, &mut images);
-            sprite.image = vision_texture;
+            // Rewrite the existing texture in place rather than allocating a new asset every frame
+            if let Some(image) = images.get_mut(&sprite.image) {
+                if let Some(data) = fow_texture_data(&chunk.current_vision) {
+                    image.data = Some(data);
+                }
+                stats.reused += 1;
+            } else {
+                sprite.image = create_fow_texture(&chunk.current_vision, &mut images);
+                stats.spawned += 1;
+            }
         } else {
             // Create new overlay sprite
             let vision_texture = create_fow_texture(&chunk.current_vision, &mut images);
+            stats.spawned += 1;
 
             commands.spawn((
                 Sprite {
@@ -689,7 +702,7 @@ pub fn draw_fow(
                         + (CHUNK_SIZE_PX as f32 / 2.0),
                     chunk.position.y as f32 * CHUNK_SIZE_PX as f32
                         + (CHUNK_SIZE_PX as f32 / 2.0),
-                    FOW_Z,
+                    crate::depth::DepthLayer::Fog.z(),
                 )),
                 GlobalTransform::default(),
                 Visibility::default(),
@@ -718,9 +731,34 @@ fn create_fow_texture(vision: &Vec<Vec<f32>>, assets: &mut Assets<Image>) -> Han
     let height = vision.len();
     let width = if height > 0 { vision[0].len() } else { 0 };
 
-    if width == 0 || height == 0 {
+    let Some(data) = fow_texture_data(vision) else {
         warn!("Attempted to create FOW texture with invalid dimensions: {}x{}", width, height);
         return Handle::default();
+    };
+
+    // Create the image with Bevy's rendering system
+    let image = Image::new(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        Default::default(),
+    );
+
+    assets.add(image)
+}
+
+/// RGBA pixel data for a FOW texture, `None` for an empty vision grid
+fn fow_texture_data(vision: &[Vec<f32>]) -> Option<Vec<u8>> {
+    let height = vision.len();
+    let width = if height > 0 { vision[0].len() } else { 0 };
+
+    if width == 0 || height == 0 {
+        return None;
     }
 
     // Create RGBA image data from interpolated vision data
@@ -744,18 +782,5 @@ fn create_fow_texture(vision: &Vec<Vec<f32>>, assets: &mut Assets<Image>) -> Han
         }
     }
 
-    // Create the image with Bevy's rendering system
-    let image = Image::new(
-        Extent3d {
-            width: width as u32,
-            height: height as u32,
-            depth_or_array_layers: 1,
-        },
-        TextureDimension::D2,
-        data,
-        TextureFormat::Rgba8UnormSrgb,
-        Default::default(),
-    );
-
-    assets.add(image)
+    Some(data)
 }
@@ -0,0 +1,342 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::components::Health;
+use super::resolver::CombatState;
+
+/// Fraction of max health per second that overheal decays at
+pub const OVERHEAL_DECAY_RATE: f32 = 0.05;
+
+/// What happens to healing beyond max health
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OverhealRule {
+    /// Excess healing is lost
+    #[default]
+    Discard,
+    /// Excess healing becomes a decaying buffer, capped at `cap` times max health
+    Shield { cap: f32 },
+}
+
+/// Healing part of an effect definition
+#[derive(Debug, Clone)]
+pub struct HealingData {
+    /// Applied immediately
+    pub instant: f32,
+    /// Total amount healed over `duration` seconds (0 = none)
+    pub over_time: f32,
+    pub duration: f32,
+    pub overheal: OverhealRule,
+}
+
+/// Resolved healing request, the single entry point for restoring health
+/// Potions, shrines, regeneration and lifesteal all end up here
+#[derive(Event, Debug, Clone)]
+pub struct HealEvent {
+    pub target: Entity,
+    /// Total amount to heal
+    pub amount: f32,
+    pub source: Entity,
+    /// Seconds to spread the amount over (0 = instant)
+    pub duration: f32,
+    pub overheal: OverhealRule,
+}
+
+impl HealEvent {
+    pub fn instant(target: Entity, source: Entity, amount: f32) -> Self {
+        Self {
+            target,
+            amount,
+            source,
+            duration: 0.0,
+            overheal: OverhealRule::Discard,
+        }
+    }
+
+    pub fn over_time(target: Entity, source: Entity, amount: f32, duration: f32) -> Self {
+        Self {
+            duration,
+            ..Self::instant(target, source, amount)
+        }
+    }
+
+    pub fn with_overheal(mut self, overheal: OverhealRule) -> Self {
+        self.overheal = overheal;
+        self
+    }
+}
+
+/// Emitted after healing was applied, for UI feedback and the combat log
+#[derive(Event, Debug, Clone)]
+pub struct HealedEvent {
+    pub target: Entity,
+    pub source: Entity,
+    /// Health actually restored
+    pub healed: f32,
+    /// Amount that went into overheal
+    pub overhealed: f32,
+}
+
+/// Passive health regeneration
+#[derive(Component, Debug, Clone)]
+pub struct HealthRegen {
+    pub per_second: f32,
+}
+
+/// A single running heal-over-time
+#[derive(Debug, Clone)]
+pub struct HealOverTimeInstance {
+    pub per_second: f32,
+    pub remaining: f32,
+    pub source: Entity,
+    pub overheal: OverhealRule,
+}
+
+/// Active heal-over-time effects on an entity; separate instances run side by side
+#[derive(Component, Debug, Clone, Default)]
+pub struct HealOverTime {
+    pub instances: Vec<HealOverTimeInstance>,
+}
+
+/// Temporary health above max, consumed before health and decaying over time
+#[derive(Component, Debug, Clone)]
+pub struct Overheal {
+    pub amount: f32,
+    pub decay_per_second: f32,
+}
+
+/// Fraction of damage dealt that is returned to the attacker as healing
+#[derive(Component, Debug, Clone)]
+pub struct Lifesteal {
+    pub fraction: f32,
+}
+
+/// Result of applying a heal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealOutcome {
+    pub healed: f32,
+    pub overhealed: f32,
+}
+
+/// Split a heal into restored health and overheal according to the overheal rule
+pub fn compute_heal(current: f32, max: f32, overheal: f32, amount: f32, rule: OverhealRule) -> HealOutcome {
+    let healed = amount.min(max - current).max(0.0);
+    let excess = amount - healed;
+
+    let overhealed = match rule {
+        OverhealRule::Discard => 0.0,
+        OverhealRule::Shield { cap } => excess.min(max * cap - overheal).max(0.0),
+    };
+
+    HealOutcome { healed, overhealed }
+}
+
+/// Turn passive regeneration and heal-over-time effects into instant heals for this tick
+pub fn tick_healing(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut heal_events: EventWriter<HealEvent>,
+    regen_query: Query<(Entity, &HealthRegen)>,
+    mut hot_query: Query<(Entity, &mut HealOverTime)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, regen) in regen_query.iter() {
+        if regen.per_second > 0.0 {
+            heal_events.write(HealEvent::instant(entity, entity, regen.per_second * dt));
+        }
+    }
+
+    for (entity, mut hot) in hot_query.iter_mut() {
+        for instance in hot.instances.iter_mut() {
+            let step = dt.min(instance.remaining);
+            instance.remaining -= step;
+            heal_events.write(
+                HealEvent::instant(entity, instance.source, instance.per_second * step)
+                    .with_overheal(instance.overheal),
+            );
+        }
+
+        hot.instances.retain(|instance| instance.remaining > 0.0);
+        if hot.instances.is_empty() {
+            commands.entity(entity).remove::<HealOverTime>();
+        }
+    }
+}
+
+/// Apply heal events to whichever health model the target uses
+pub fn apply_healing(
+    mut commands: Commands,
+    mut heal_events: EventReader<HealEvent>,
+    mut healed_events: EventWriter<HealedEvent>,
+    mut targets: Query<(Option<&mut CombatState>, Option<&mut Health>, Option<&mut Overheal>, Option<&mut HealOverTime>)>,
+) {
+    // Components for targets that don't have them yet, inserted once so same-frame heals merge
+    let mut new_hots: HashMap<Entity, HealOverTime> = HashMap::new();
+    let mut new_overheals: HashMap<Entity, Overheal> = HashMap::new();
+
+    for event in heal_events.read() {
+        if event.amount <= 0.0 {
+            continue;
+        }
+
+        let Ok((combat, health, overheal, hot)) = targets.get_mut(event.target) else {
+            continue;
+        };
+
+        if event.duration > 0.0 {
+            let instance = HealOverTimeInstance {
+                per_second: event.amount / event.duration,
+                remaining: event.duration,
+                source: event.source,
+                overheal: event.overheal,
+            };
+            match hot {
+                Some(mut hot) => hot.instances.push(instance),
+                None => new_hots.entry(event.target).or_default().instances.push(instance),
+            }
+            continue;
+        }
+
+        let (current, max) = match (&combat, &health) {
+            (Some(combat), _) => (combat.health, combat.max_health),
+            (_, Some(health)) => (health.current, health.max),
+            _ => continue,
+        };
+
+        // The dead stay dead
+        if current <= 0.0 {
+            continue;
+        }
+
+        let current_overheal = overheal.as_ref().map(|o| o.amount)
+            .or_else(|| new_overheals.get(&event.target).map(|o| o.amount))
+            .unwrap_or(0.0);
+        let outcome = compute_heal(current, max, current_overheal, event.amount, event.overheal);
+
+        if let Some(mut combat) = combat {
+            combat.health += outcome.healed;
+        } else if let Some(mut health) = health {
+            health.current += outcome.healed;
+        }
+
+        if outcome.overhealed > 0.0 {
+            match overheal {
+                Some(mut overheal) => overheal.amount += outcome.overhealed,
+                None => {
+                    new_overheals
+                        .entry(event.target)
+                        .or_insert(Overheal { amount: 0.0, decay_per_second: max * OVERHEAL_DECAY_RATE })
+                        .amount += outcome.overhealed;
+                }
+            }
+        }
+
+        if outcome.healed > 0.0 || outcome.overhealed > 0.0 {
+            healed_events.write(HealedEvent {
+                target: event.target,
+                source: event.source,
+                healed: outcome.healed,
+                overhealed: outcome.overhealed,
+            });
+        }
+    }
+
+    for (entity, hot) in new_hots {
+        commands.entity(entity).insert(hot);
+    }
+    for (entity, overheal) in new_overheals {
+        commands.entity(entity).insert(overheal);
+    }
+}
+
+/// Take damage out of overheal first, returning what is left for health
+pub fn absorb_with_overheal(damage: f32, overheal: Option<&mut Overheal>) -> f32 {
+    let Some(overheal) = overheal else {
+        return damage;
+    };
+    let absorbed = damage.min(overheal.amount).max(0.0);
+    overheal.amount -= absorbed;
+    damage - absorbed
+}
+
+/// Let overheal drain away over time
+pub fn decay_overheal(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut overheal_query: Query<(Entity, &mut Overheal)>,
+) {
+    for (entity, mut overheal) in overheal_query.iter_mut() {
+        overheal.amount -= overheal.decay_per_second * time.delta_secs();
+        if overheal.amount <= 0.0 {
+            commands.entity(entity).remove::<Overheal>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::{DamageEvent, DamageType, apply_damage};
+
+    #[test]
+    fn test_heal_clamps_to_max() {
+        let outcome = compute_heal(80.0, 100.0, 0.0, 50.0, OverhealRule::Discard);
+        assert_eq!(outcome, HealOutcome { healed: 20.0, overhealed: 0.0 });
+    }
+
+    #[test]
+    fn test_overheal_shield_is_capped() {
+        let rule = OverhealRule::Shield { cap: 0.25 };
+        let outcome = compute_heal(90.0, 100.0, 0.0, 30.0, rule);
+        assert_eq!(outcome, HealOutcome { healed: 10.0, overhealed: 20.0 });
+
+        // Existing overheal counts towards the cap
+        let outcome = compute_heal(100.0, 100.0, 20.0, 30.0, rule);
+        assert_eq!(outcome, HealOutcome { healed: 0.0, overhealed: 5.0 });
+    }
+
+    fn healing_app() -> App {
+        let mut app = App::new();
+        app.add_event::<HealEvent>()
+            .add_event::<HealedEvent>()
+            .add_event::<DamageEvent>()
+            .add_systems(Update, (apply_damage, apply_healing).chain());
+        app
+    }
+
+    #[test]
+    fn test_same_frame_heals_merge() {
+        let mut app = healing_app();
+        let player = app.world_mut().spawn(Health::new(100.0)).id();
+        let shield = OverhealRule::Shield { cap: 1.0 };
+
+        app.world_mut().send_event(HealEvent::over_time(player, player, 10.0, 2.0));
+        app.world_mut().send_event(HealEvent::over_time(player, player, 20.0, 4.0));
+        app.world_mut().send_event(HealEvent::instant(player, player, 15.0).with_overheal(shield));
+        app.world_mut().send_event(HealEvent::instant(player, player, 25.0).with_overheal(shield));
+        app.update();
+
+        assert_eq!(app.world().get::<HealOverTime>(player).unwrap().instances.len(), 2);
+        assert_eq!(app.world().get::<Overheal>(player).unwrap().amount, 40.0);
+    }
+
+    #[test]
+    fn test_overheal_shields_player_health() {
+        let mut app = healing_app();
+        let player = app.world_mut()
+            .spawn((Health::new(100.0), Overheal { amount: 30.0, decay_per_second: 0.0 }))
+            .id();
+
+        app.world_mut().send_event(DamageEvent {
+            target: player,
+            damage: 50.0,
+            damage_type: DamageType::PHYSICAL,
+            source: player,
+            critical: false,
+        });
+        app.update();
+
+        assert_eq!(app.world().get::<Overheal>(player).unwrap().amount, 0.0);
+        assert_eq!(app.world().get::<Health>(player).unwrap().current, 80.0);
+    }
+}
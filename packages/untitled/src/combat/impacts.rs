@@ -0,0 +1,281 @@
+//! Impact effects: what a projectile hitting something looks and sounds like
+//!
+//! Effects are looked up by projectile kind and the surface that was hit, so
+//! a bullet chipping stone and one hitting an enemy differ without the
+//! collision handling knowing about either. Bullets hitting something that
+//! can be hurt also send their damage through the effects pipeline.
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::collections::HashMap;
+use crate::components::{Grenade, Health, Projectile, ProjectileKind, Surface, Team};
+use super::{effects::{EffectDefId, EffectEvent}, resolver::CombatState};
+use crate::events::ProjectileImpactEvent;
+use crate::sounds::{play_sound, GameSounds};
+
+/// Widest angle particles spread from the way a projectile bounced back, in radians
+const PARTICLE_SPREAD: f32 = 0.7;
+const PARTICLE_SIZE: f32 = 2.0;
+
+/// Particles thrown off at the point of impact
+#[derive(Debug, Clone, Copy)]
+pub struct ParticleBurst {
+    pub color: Color,
+    pub count: usize,
+    pub speed: f32,
+    /// Seconds until the particles have faded out
+    pub lifetime: f32,
+}
+
+/// Mark left on the ground where the projectile hit
+#[derive(Debug, Clone, Copy)]
+pub struct ImpactDecal {
+    pub color: Color,
+    pub size: f32,
+    /// Seconds until the mark has faded out
+    pub lifetime: f32,
+}
+
+/// Everything that happens when one kind of projectile hits one kind of surface
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImpactEffect {
+    pub particles: Option<ParticleBurst>,
+    pub decal: Option<ImpactDecal>,
+    pub sound: Option<fn(&GameSounds) -> Handle<AudioSource>>,
+}
+
+/// Impact effects by projectile kind and surface
+#[derive(Resource, Default)]
+pub struct ImpactRegistry {
+    effects: HashMap<(ProjectileKind, Surface), ImpactEffect>,
+    /// Used for projectile kinds with nothing registered for a surface
+    fallbacks: HashMap<Surface, ImpactEffect>,
+}
+
+impl ImpactRegistry {
+    pub fn register(&mut self, kind: ProjectileKind, surface: Surface, effect: ImpactEffect) {
+        self.effects.insert((kind, surface), effect);
+    }
+
+    pub fn register_fallback(&mut self, surface: Surface, effect: ImpactEffect) {
+        self.fallbacks.insert(surface, effect);
+    }
+
+    /// The effect for `kind` hitting `surface`, or the surface's fallback
+    pub fn get(&self, kind: ProjectileKind, surface: Surface) -> Option<&ImpactEffect> {
+        self.effects.get(&(kind, surface)).or_else(|| self.fallbacks.get(&surface))
+    }
+
+    /// Dust off stone, blood off flesh and sparks off metal, with heavier hits for grenades
+    pub fn with_basic_impacts() -> Self {
+        let mut registry = Self::default();
+
+        registry.register_fallback(Surface::Stone, ImpactEffect {
+            particles: Some(ParticleBurst { color: Color::srgb(0.6, 0.55, 0.5), count: 5, speed: 80.0, lifetime: 0.3 }),
+            decal: Some(ImpactDecal { color: Color::srgba(0.1, 0.1, 0.1, 0.6), size: 3.0, lifetime: 8.0 }),
+            sound: None,
+        });
+        registry.register_fallback(Surface::Flesh, ImpactEffect {
+            particles: Some(ParticleBurst { color: Color::srgb(0.7, 0.05, 0.05), count: 6, speed: 60.0, lifetime: 0.4 }),
+            decal: Some(ImpactDecal { color: Color::srgba(0.45, 0.0, 0.0, 0.7), size: 4.0, lifetime: 5.0 }),
+            sound: None,
+        });
+        registry.register_fallback(Surface::Metal, ImpactEffect {
+            particles: Some(ParticleBurst { color: Color::srgb(1.0, 0.85, 0.4), count: 8, speed: 160.0, lifetime: 0.2 }),
+            decal: None,
+            sound: Some(|sounds| sounds.gun_03.clone()),
+        });
+
+        // Pellets are small enough to leave no marks
+        registry.register(ProjectileKind::Pellet, Surface::Stone, ImpactEffect {
+            particles: Some(ParticleBurst { color: Color::srgb(0.6, 0.55, 0.5), count: 2, speed: 60.0, lifetime: 0.2 }),
+            decal: None,
+            sound: None,
+        });
+
+        // Grenades clunk off walls rather than chip them
+        registry.register(ProjectileKind::Grenade, Surface::Stone, ImpactEffect {
+            particles: Some(ParticleBurst { color: Color::srgb(0.6, 0.55, 0.5), count: 3, speed: 40.0, lifetime: 0.3 }),
+            decal: None,
+            sound: Some(|sounds| sounds.gun_02.clone()),
+        });
+
+        registry
+    }
+}
+
+/// A particle flying off an impact
+#[derive(Component)]
+pub struct ImpactParticle {
+    velocity: Vec2,
+    remaining: f32,
+    lifetime: f32,
+}
+
+/// A mark where something was hit, fading out over its last second
+#[derive(Component)]
+pub struct ImpactMark {
+    remaining: f32,
+    /// Opacity the mark was spawned with
+    alpha: f32,
+}
+
+/// Turn collisions involving projectiles or grenades into `ProjectileImpactEvent`s
+pub fn detect_projectile_impacts(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut impact_events: EventWriter<ProjectileImpactEvent>,
+    projectiles: Query<(), Or<(With<Projectile>, With<Grenade>)>>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+
+        for (projectile, target) in [(a, b), (b, a)] {
+            if projectiles.contains(projectile) {
+                impact_events.write(ProjectileImpactEvent { projectile, target });
+            }
+        }
+    }
+}
+
+/// Spawn the registered particles, decal and sound for each impact
+pub fn handle_projectile_impacts(
+    mut commands: Commands,
+    mut impact_events: EventReader<ProjectileImpactEvent>,
+    registry: Res<ImpactRegistry>,
+    game_sounds: Res<GameSounds>,
+    projectiles: Query<(&Transform, Option<&Velocity>, Option<&Projectile>)>,
+    surfaces: Query<(Option<&Surface>, Option<&Team>)>,
+) {
+    for event in impact_events.read() {
+        let Ok((transform, velocity, projectile)) = projectiles.get(event.projectile) else {
+            continue;
+        };
+        // Only grenades hit things without a `Projectile`
+        let kind = projectile.map_or(ProjectileKind::Grenade, |projectile| projectile.kind);
+        let surface = surfaces.get(event.target).map_or(Surface::Stone, |(surface, team)| Surface::of(surface, team));
+        let Some(effect) = registry.get(kind, surface) else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+        if let Some(burst) = effect.particles {
+            // Thrown back the way the projectile came
+            let back = velocity.map_or(Vec2::Y, |velocity| -velocity.linvel.normalize_or(Vec2::NEG_Y));
+            for i in 0..burst.count {
+                let spread = if burst.count == 1 { 0.0 } else { (i as f32 / (burst.count - 1) as f32 - 0.5) * 2.0 * PARTICLE_SPREAD };
+                commands.spawn((
+                    Sprite::from_color(burst.color, Vec2::splat(PARTICLE_SIZE)),
+                    Transform::from_translation(position.extend(crate::depth::DepthLayer::Projectiles.z())),
+                    ImpactParticle {
+                        velocity: Vec2::from_angle(spread).rotate(back) * burst.speed,
+                        remaining: burst.lifetime,
+                        lifetime: burst.lifetime,
+                    },
+                ));
+            }
+        }
+
+        if let Some(decal) = effect.decal {
+            commands.spawn((
+                Sprite::from_color(decal.color, Vec2::splat(decal.size)),
+                Transform::from_translation(position.extend(crate::depth::DepthLayer::TerrainDetail.z())),
+                ImpactMark { remaining: decal.lifetime, alpha: decal.color.alpha() },
+            ));
+        }
+
+        if let Some(sound) = effect.sound {
+            play_sound(&mut commands, sound(&game_sounds), 1.0);
+        }
+    }
+}
+
+/// Turn bullets hitting the other team into effects, crediting whoever fired them
+pub fn apply_projectile_hits(
+    mut commands: Commands,
+    mut impact_events: EventReader<ProjectileImpactEvent>,
+    mut effect_events: EventWriter<EffectEvent>,
+    projectiles: Query<(&Projectile, &Transform)>,
+    targets: Query<Option<&Team>, Or<(With<CombatState>, With<Health>)>>,
+) {
+    for event in impact_events.read() {
+        // Grenades hurt when they explode, not when they land
+        let Ok((projectile, transform)) = projectiles.get(event.projectile) else {
+            continue;
+        };
+        let Ok(team) = targets.get(event.target) else {
+            continue;
+        };
+        if team == Some(&projectile.team) {
+            continue;
+        }
+
+        let effect_id = match projectile.team {
+            Team::Player => EffectDefId::PLAYER_PROJECTILE,
+            Team::Enemy => EffectDefId::ENEMY_BULLET,
+        };
+        effect_events.write(EffectEvent {
+            source: projectile.source,
+            targets: vec![event.target],
+            effect_id,
+            hit_positions: vec![transform.translation.truncate()],
+        });
+        // Spent on the first thing it hurts
+        commands.entity(event.projectile).try_despawn();
+    }
+}
+
+/// Move and fade impact particles and marks, despawning them once they're gone
+pub fn animate_impact_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut ImpactParticle, &mut Transform, &mut Sprite)>,
+    mut marks: Query<(Entity, &mut ImpactMark, &mut Sprite), Without<ImpactParticle>>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        particle.remaining -= dt;
+        if particle.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (particle.velocity * dt).extend(0.0);
+        sprite.color.set_alpha(particle.remaining / particle.lifetime);
+    }
+
+    for (entity, mut mark, mut sprite) in marks.iter_mut() {
+        mark.remaining -= dt;
+        if mark.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color.set_alpha(mark.alpha * mark.remaining.min(1.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surfaces_fall_back_when_kind_has_no_entry() {
+        let registry = ImpactRegistry::with_basic_impacts();
+
+        // Pellets have their own stone impact but share the flesh one
+        let pellet_stone = registry.get(ProjectileKind::Pellet, Surface::Stone).unwrap();
+        assert!(pellet_stone.decal.is_none());
+        let pellet_flesh = registry.get(ProjectileKind::Pellet, Surface::Flesh).unwrap();
+        let bullet_flesh = registry.get(ProjectileKind::Bullet, Surface::Flesh).unwrap();
+        assert_eq!(pellet_flesh.particles.unwrap().count, bullet_flesh.particles.unwrap().count);
+
+        assert!(ImpactRegistry::default().get(ProjectileKind::Bullet, Surface::Metal).is_none());
+    }
+
+    #[test]
+    fn test_untagged_teams_are_flesh() {
+        assert_eq!(Surface::of(None, Some(&Team::Enemy)), Surface::Flesh);
+        assert_eq!(Surface::of(None, None), Surface::Stone);
+        assert_eq!(Surface::of(Some(&Surface::Metal), Some(&Team::Enemy)), Surface::Metal);
+    }
+}
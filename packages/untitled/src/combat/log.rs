@@ -0,0 +1,223 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use super::{effects::*, healing::HealedEvent, resolver::CombatState};
+
+/// Number of entries kept before the oldest are dropped
+pub const COMBAT_LOG_CAPACITY: usize = 256;
+
+/// What happened in a combat log entry
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombatLogKind {
+    Damage { amount: f32, damage_type: DamageType, critical: bool },
+    Status { status_id: StatusId, intensity: f32, duration: f32 },
+    Heal { amount: f32, overheal: f32 },
+    Kill,
+}
+
+/// A single recorded combat event
+#[derive(Debug, Clone)]
+pub struct CombatLogEntry {
+    /// Seconds since startup when this happened
+    pub timestamp: f32,
+    pub source: Entity,
+    pub target: Entity,
+    /// Display names resolved when the entry was recorded, since entities may be gone later
+    pub source_name: String,
+    pub target_name: String,
+    pub kind: CombatLogKind,
+}
+
+impl CombatLogEntry {
+    /// Markup line for UI display (see `ui::rich_text`)
+    pub fn describe(&self, registry: &EffectRegistry) -> String {
+        match &self.kind {
+            CombatLogKind::Damage { amount, damage_type, critical } => {
+                let type_name = registry.get_damage_type(*damage_type)
+                    .map(|d| d.name.as_str())
+                    .unwrap_or("Unknown");
+                let crit = if *critical { " {yellow}CRIT{/}" } else { "" };
+                format!("{} hit {} for {{red}}{:.1}{{/}} {}{}", self.source_name, self.target_name, amount, type_name, crit)
+            }
+            CombatLogKind::Status { status_id, intensity, duration } => {
+                format!("{} applied {} to {} ({:.1} for {:.1}s)",
+                    self.source_name, status_name(*status_id), self.target_name, intensity, duration)
+            }
+            CombatLogKind::Heal { amount, overheal } => {
+                let over = if *overheal > 0.0 { format!(" {{blue}}(+{:.1} overheal){{/}}", overheal) } else { String::new() };
+                if self.source == self.target {
+                    format!("{} healed for {{green}}{:.1}{{/}}{}", self.target_name, amount, over)
+                } else {
+                    format!("{} healed {} for {{green}}{:.1}{{/}}{}", self.source_name, self.target_name, amount, over)
+                }
+            }
+            CombatLogKind::Kill => {
+                format!("{} {{orange}}killed{{/}} {}", self.source_name, self.target_name)
+            }
+        }
+    }
+}
+
+/// Human-readable status effect name
+pub fn status_name(status_id: StatusId) -> &'static str {
+    match status_id {
+        StatusId::SLOW => "Slow",
+        StatusId::STUN => "Stun",
+        StatusId::DAMAGE_OVER_TIME => "Damage over time",
+        StatusId::KNOCKBACK => "Knockback",
+        _ => "Unknown status",
+    }
+}
+
+/// Ring buffer of recent combat events, fed from the effects pipeline
+#[derive(Resource)]
+pub struct CombatLog {
+    entries: VecDeque<CombatLogEntry>,
+    capacity: usize,
+    /// Total number of entries ever recorded, used by UI to detect changes
+    pub total_recorded: u64,
+}
+
+impl Default for CombatLog {
+    fn default() -> Self {
+        Self::with_capacity(COMBAT_LOG_CAPACITY)
+    }
+}
+
+impl CombatLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            total_recorded: 0,
+        }
+    }
+
+    /// Record an entry, dropping the oldest one when full
+    pub fn push(&mut self, entry: CombatLogEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+        self.total_recorded += 1;
+    }
+
+    /// Entries from oldest to newest
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &CombatLogEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Best-effort display name for a combat participant
+fn entity_label(
+    entity: Entity,
+    names: &Query<&Name>,
+    players: &Query<(), With<crate::player::Player>>,
+    enemies: &Query<&crate::components::Enemy>,
+) -> String {
+    if let Ok(name) = names.get(entity) {
+        return name.to_string();
+    }
+    if players.contains(entity) {
+        return "Player".to_string();
+    }
+    if let Ok(enemy) = enemies.get(entity) {
+        return format!("{:?}", enemy.archetype);
+    }
+    if entity == Entity::PLACEHOLDER {
+        return "Environment".to_string();
+    }
+    format!("Entity {}", entity.index())
+}
+
+/// Record damage, status applications, heals and kills into the combat log
+/// Runs after damage is applied so kills can be detected before dead entities are cleaned up
+pub fn record_combat_log(
+    time: Res<Time>,
+    mut log: ResMut<CombatLog>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut status_events: EventReader<StatusEvent>,
+    mut healed_events: EventReader<HealedEvent>,
+    combat_query: Query<&CombatState>,
+    names: Query<&Name>,
+    players: Query<(), With<crate::player::Player>>,
+    enemies: Query<&crate::components::Enemy>,
+) {
+    let timestamp = time.elapsed_secs();
+    let label = |entity| entity_label(entity, &names, &players, &enemies);
+
+    let mut killed = Vec::new();
+
+    for event in damage_events.read() {
+        log.push(CombatLogEntry {
+            timestamp,
+            source: event.source,
+            target: event.target,
+            source_name: label(event.source),
+            target_name: label(event.target),
+            kind: CombatLogKind::Damage {
+                amount: event.damage,
+                damage_type: event.damage_type,
+                critical: event.critical,
+            },
+        });
+
+        let dead = combat_query.get(event.target).is_ok_and(|state| state.is_dead());
+        if dead && !killed.iter().any(|(target, _)| *target == event.target) {
+            killed.push((event.target, event.source));
+        }
+    }
+
+    for event in status_events.read() {
+        log.push(CombatLogEntry {
+            timestamp,
+            source: event.source,
+            target: event.target,
+            source_name: label(event.source),
+            target_name: label(event.target),
+            kind: CombatLogKind::Status {
+                status_id: event.status_id,
+                intensity: event.intensity,
+                duration: event.duration,
+            },
+        });
+    }
+
+    for event in healed_events.read() {
+        // Regeneration and heal-over-time tick every frame and would flood the log
+        if event.healed + event.overhealed < 1.0 {
+            continue;
+        }
+
+        log.push(CombatLogEntry {
+            timestamp,
+            source: event.source,
+            target: event.target,
+            source_name: label(event.source),
+            target_name: label(event.target),
+            kind: CombatLogKind::Heal {
+                amount: event.healed,
+                overheal: event.overhealed,
+            },
+        });
+    }
+
+    for (target, source) in killed {
+        log.push(CombatLogEntry {
+            timestamp,
+            source,
+            target,
+            source_name: label(source),
+            target_name: label(target),
+            kind: CombatLogKind::Kill,
+        });
+    }
+}
@@ -3,10 +3,67 @@
 //! This module replaces the old hardcoded projectile/grenade system with a
 //! data-driven effect system that can handle any type of combat interaction.
 
+pub mod abilities;
 pub mod effects;
 pub mod fow;
+pub mod healing;
+pub mod impacts;
+pub mod log;
 pub mod resolver;
+pub mod telegraph;
 
+pub use abilities::*;
 pub use effects::*;
 pub use fow::*;
+pub use healing::*;
+pub use impacts::*;
+pub use log::*;
 pub use resolver::*;
+pub use telegraph::*;
+
+use bevy::prelude::*;
+use crate::resources::GameState;
+
+/// Plugin wiring the effects pipeline: abilities, effect resolution, damage, healing, statuses, impact effects, attack telegraphs and the combat log
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<crate::components::Team>()
+            .register_type::<crate::components::Enemy>()
+            .add_event::<EffectEvent>()
+            .add_event::<DamageEvent>()
+            .add_event::<StatusEvent>()
+            .add_event::<HealEvent>()
+            .add_event::<HealedEvent>()
+            .add_event::<AbilityCastEvent>()
+            .add_event::<EnemyKilledEvent>()
+            .insert_resource(EffectRegistry::with_basic_effects())
+            .insert_resource(AbilityRegistry::with_basic_abilities())
+            .insert_resource(ImpactRegistry::with_basic_impacts())
+            .init_resource::<CombatLog>()
+            .add_systems(FixedUpdate, (
+                regenerate_energy,
+                tick_ability_cooldowns,
+                resolve_ability_casts,
+                resolve_effects,
+                tick_healing,
+                apply_damage,
+                apply_healing,
+                decay_overheal,
+                apply_status_effects,
+                // Record before cleanup so kills can still be attributed
+                record_combat_log,
+                tick_status_effects,
+                cleanup_dead_entities,
+            ).chain().run_if(resource_equals(GameState::Playing)))
+            .add_systems(Update, (
+                detect_projectile_impacts,
+                handle_projectile_impacts,
+                apply_projectile_hits,
+                animate_impact_effects,
+            ).chain().run_if(resource_equals(GameState::Playing)))
+            .add_systems(Update, (spawn_attack_telegraphs, update_attack_telegraphs).chain());
+    }
+}
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use std::collections::HashMap;
 
-use super::effects::*;
+use super::{effects::*, healing::*};
 
 /// Combat state component that replaces the old Health component
 /// Contains all combat-related data including resistances and modifiers
@@ -43,6 +43,25 @@ impl CombatState {
     }
 }
 
+/// Chance for an attacker's hits to crit, multiplying their damage
+#[derive(Component, Debug, Clone)]
+pub struct CriticalHits {
+    /// Chance per hit, 0 to 1
+    pub chance: f32,
+    pub multiplier: f32,
+}
+
+impl CriticalHits {
+    /// Damage after a crit roll, `roll` uniform in `[0, 1)`, and whether it crit
+    pub fn apply(&self, damage: f32, roll: f32) -> (f32, bool) {
+        if roll < self.chance {
+            (damage * self.multiplier, true)
+        } else {
+            (damage, false)
+        }
+    }
+}
+
 /// Active status effect on an entity
 #[derive(Component, Debug, Clone)]
 pub struct StatusEffect {
@@ -58,9 +77,13 @@ pub fn resolve_effects(
     mut effect_events: EventReader<EffectEvent>,
     mut damage_events: EventWriter<DamageEvent>,
     mut status_events: EventWriter<StatusEvent>,
+    mut heal_events: EventWriter<HealEvent>,
     effect_registry: Res<EffectRegistry>,
     combat_query: Query<&CombatState>,
+    health_query: Query<(), With<crate::components::Health>>,
     faction_query: Query<&Faction>,
+    lifesteal_query: Query<&Lifesteal>,
+    crit_query: Query<&CriticalHits>,
 ) {
     // Group all effects by target for multi-hit resolution
     let mut effects_by_target: HashMap<Entity, Vec<(Entity, &EffectDefinition, Vec2)>> = HashMap::new();
@@ -79,9 +102,16 @@ pub fn resolve_effects(
 
     // Process each target's accumulated effects
     for (target, effects) in effects_by_target {
+        // Healing doesn't depend on combat stats, so targets using plain Health can be healed too
+        resolve_healing_for_target(target, &effects, &mut heal_events);
+
         if let Ok(target_combat) = combat_query.get(target) {
-            resolve_damage_for_target(target, &effects, target_combat, &effect_registry, &mut damage_events);
+            resolve_damage_for_target(target, &effects, Some(target_combat), &effect_registry, &crit_query, &mut damage_events);
+            resolve_lifesteal_for_target(&effects, target_combat, &lifesteal_query, &mut heal_events);
             resolve_status_for_target(target, &effects, target_combat, &mut status_events);
+        } else if health_query.contains(target) {
+            // Plain `Health`, like the player's, takes damage without resistances
+            resolve_damage_for_target(target, &effects, None, &effect_registry, &crit_query, &mut damage_events);
         }
     }
 }
@@ -90,8 +120,9 @@ pub fn resolve_effects(
 fn resolve_damage_for_target(
     target: Entity,
     effects: &[(Entity, &EffectDefinition, Vec2)],
-    target_combat: &CombatState,
+    target_combat: Option<&CombatState>,
     effect_registry: &EffectRegistry,
+    crit_query: &Query<&CriticalHits>,
     damage_events: &mut EventWriter<DamageEvent>,
 ) {
     // Group damage by type and source for proper resolution
@@ -114,12 +145,17 @@ fn resolve_damage_for_target(
 
             // Use first source for the damage event (could be improved later)
             let source = damage_sources.first().copied().unwrap_or(Entity::PLACEHOLDER);
+            let (final_damage, critical) = match crit_query.get(source) {
+                Ok(crits) => crits.apply(final_damage, rand::random()),
+                Err(_) => (final_damage, false),
+            };
 
             damage_events.send(DamageEvent {
                 target,
                 damage: final_damage,
                 damage_type,
                 source,
+                critical,
             });
         }
     }
@@ -129,16 +165,63 @@ fn resolve_damage_for_target(
 fn calculate_final_damage(
     base_damage: f32,
     damage_type: DamageType,
-    target_combat: &CombatState,
+    target_combat: Option<&CombatState>,
     _effect_registry: &EffectRegistry,
 ) -> f32 {
-    let multiplier = target_combat.get_damage_multiplier(damage_type);
+    let multiplier = target_combat.map_or(1.0, |combat| combat.get_damage_multiplier(damage_type));
     // Simple multiplication: 1.0 = normal, 0.5 = half damage, 2.0 = double damage, 0.0 = immune
     base_damage * multiplier
 }
 
 
 
+/// Resolve healing effects for a single target into instant and over-time heal events
+fn resolve_healing_for_target(
+    target: Entity,
+    effects: &[(Entity, &EffectDefinition, Vec2)],
+    heal_events: &mut EventWriter<HealEvent>,
+) {
+    for (source, definition, _hit_pos) in effects {
+        let Some(healing) = &definition.healing else {
+            continue;
+        };
+
+        if healing.instant > 0.0 {
+            heal_events.write(
+                HealEvent::instant(target, *source, healing.instant).with_overheal(healing.overheal),
+            );
+        }
+
+        if healing.over_time > 0.0 && healing.duration > 0.0 {
+            heal_events.write(
+                HealEvent::over_time(target, *source, healing.over_time, healing.duration)
+                    .with_overheal(healing.overheal),
+            );
+        }
+    }
+}
+
+/// Return a fraction of the damage each source dealt to it as healing
+fn resolve_lifesteal_for_target(
+    effects: &[(Entity, &EffectDefinition, Vec2)],
+    target_combat: &CombatState,
+    lifesteal_query: &Query<&Lifesteal>,
+    heal_events: &mut EventWriter<HealEvent>,
+) {
+    for (source, definition, _hit_pos) in effects {
+        let fraction = definition.lifesteal
+            + lifesteal_query.get(*source).map_or(0.0, |lifesteal| lifesteal.fraction);
+        if fraction <= 0.0 || definition.damage <= 0.0 {
+            continue;
+        }
+
+        let dealt = definition.damage * target_combat.get_damage_multiplier(definition.damage_type);
+        if dealt > 0.0 {
+            heal_events.write(HealEvent::instant(*source, *source, dealt * fraction));
+        }
+    }
+}
+
 /// Resolve all status effects for a single target
 fn resolve_status_for_target(
     target: Entity,
@@ -228,14 +311,22 @@ fn get_default_stack_behavior(status_id: StatusId) -> StackBehavior {
     }
 }
 
-/// System that applies damage events to combat state
+/// System that applies damage events to combat state, or plain `Health` for the player
+/// Overheal absorbs damage before health does
 pub fn apply_damage(
     mut damage_events: EventReader<DamageEvent>,
-    mut combat_query: Query<&mut CombatState>,
+    mut targets: Query<(Option<&mut CombatState>, Option<&mut crate::components::Health>, Option<&mut Overheal>)>,
 ) {
     for damage_event in damage_events.read() {
-        if let Ok(mut combat_state) = combat_query.get_mut(damage_event.target) {
-            combat_state.health = (combat_state.health - damage_event.damage).max(0.0);
+        let Ok((combat_state, health, overheal)) = targets.get_mut(damage_event.target) else {
+            continue;
+        };
+
+        let damage = absorb_with_overheal(damage_event.damage, overheal.map(|o| o.into_inner()));
+        if let Some(mut combat_state) = combat_state {
+            combat_state.health = (combat_state.health - damage).max(0.0);
+        } else if let Some(mut health) = health {
+            health.take_damage(damage);
         }
     }
 }
@@ -286,14 +377,37 @@ pub fn tick_status_effects(
     }
 }
 
+/// Event fired when an enemy dies, just before it is despawned
+///
+/// Enemies also despawn when their scene ends or their chunk unloads, so
+/// anything counting kills reads this rather than watching for despawns.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyKilledEvent {
+    pub entity: Entity,
+    pub archetype: crate::components::EnemyArchetype,
+}
+
 /// System that cleans up dead entities (replaces the old cleanup_dead_entities)
 pub fn cleanup_dead_entities(
     mut commands: Commands,
     combat_query: Query<(Entity, &CombatState)>,
     player_query: Query<&crate::player::Player>,
+    player_health: Query<&crate::components::Health, With<crate::player::Player>>,
     enemy_query: Query<&crate::components::Enemy>,
     mut game_state: ResMut<crate::resources::GameState>,
+    mut slow_motion_events: EventWriter<crate::time_scale::SlowMotionEvent>,
+    mut killed_events: EventWriter<EnemyKilledEvent>,
 ) {
+    use crate::constants::{KILL_SLOW_MOTION_DURATION, KILL_SLOW_MOTION_MULTI_KILL, KILL_SLOW_MOTION_SCALE};
+
+    let mut kills = 0;
+    let mut big_kill = false;
+
+    // The player keeps plain `Health` rather than a `CombatState`
+    if player_health.single().is_ok_and(|health| health.is_dead()) {
+        *game_state = crate::resources::GameState::GameOver;
+    }
+
     for (entity, combat_state) in combat_query.iter() {
         if combat_state.is_dead() {
             // Check if it's the player
@@ -304,17 +418,36 @@ pub fn cleanup_dead_entities(
 
             // Award points for enemies (keeping existing logic for now)
             if let Ok(enemy) = enemy_query.get(entity) {
-                let _points = match enemy.archetype {
-                    crate::components::EnemyArchetype::SmallMelee => 10,
-                    crate::components::EnemyArchetype::BigMelee => 50,
-                    crate::components::EnemyArchetype::Shotgunner => 30,
-                    crate::components::EnemyArchetype::Sniper => 75,
-                    crate::components::EnemyArchetype::MachineGunner => 40,
-                };
+                let _points = enemy.archetype.score_value();
                 // TODO: Add points to game state
+
+                kills += 1;
+                big_kill |= enemy.archetype == crate::components::EnemyArchetype::BigMelee;
+                killed_events.write(EnemyKilledEvent { entity, archetype: enemy.archetype });
             }
 
             commands.entity(entity).despawn();
         }
     }
+
+    // Linger on big kills
+    if big_kill || kills >= KILL_SLOW_MOTION_MULTI_KILL {
+        slow_motion_events.write(crate::time_scale::SlowMotionEvent {
+            scale: KILL_SLOW_MOTION_SCALE,
+            duration: KILL_SLOW_MOTION_DURATION,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crit_multiplies_damage_under_its_chance() {
+        let crits = CriticalHits { chance: 0.25, multiplier: 2.0 };
+        assert_eq!(crits.apply(10.0, 0.1), (20.0, true));
+        assert_eq!(crits.apply(10.0, 0.25), (10.0, false));
+        assert_eq!(CriticalHits { chance: 0.0, multiplier: 2.0 }.apply(10.0, 0.0), (10.0, false));
+    }
 }
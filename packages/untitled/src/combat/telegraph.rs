@@ -0,0 +1,175 @@
+//! Telegraphs: ground decals showing where an enemy attack is about to land
+//!
+//! An enemy's `AttackDefinition` gives the area its attack covers and how long
+//! it winds up. While the attack winds up, a decal of that shape lies on the
+//! ground under the enemy, aimed at the player, growing more opaque as the
+//! shot nears and turning red while the player stands inside it. It flashes
+//! when the attack actually fires, as recorded by `AIBehavior::fire`.
+//! Everything is built from the definition, so an enemy with a new attack
+//! gets a telegraph without any code of its own.
+use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+use crate::components::{AIBehavior, Enemy, LineOfSight};
+use crate::depth::DepthLayer;
+use crate::enemy::ArchetypeConfig;
+use crate::player::Player;
+
+/// Seconds a telegraph flashes after its attack fires
+const TELEGRAPH_FLASH_SECS: f32 = 0.12;
+/// Opacity as the windup starts and just before the attack fires
+const TELEGRAPH_MIN_ALPHA: f32 = 0.08;
+const TELEGRAPH_MAX_ALPHA: f32 = 0.4;
+
+/// Area an attack covers, reaching out from the attacker along its aim
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttackShape {
+    /// A wedge `half_angle` radians either side of the aim
+    Cone { range: f32, half_angle: f32 },
+    /// A straight shot
+    Line { range: f32, width: f32 },
+}
+
+impl AttackShape {
+    /// Whether `point` is inside the shape cast from `origin` towards `direction`
+    pub fn contains(&self, origin: Vec2, direction: Vec2, point: Vec2) -> bool {
+        let offset = point - origin;
+        match *self {
+            Self::Cone { range, half_angle } => {
+                offset.length() <= range && direction.angle_to(offset).abs() <= half_angle
+            }
+            Self::Line { range, width } => {
+                let along = offset.dot(direction);
+                (0.0..=range).contains(&along) && offset.perp_dot(direction).abs() <= width / 2.0
+            }
+        }
+    }
+
+    /// Decal mesh pointing along +y from the origin
+    fn mesh(&self) -> Mesh {
+        match *self {
+            Self::Cone { range, half_angle } => CircularSector::new(range, half_angle).into(),
+            Self::Line { range, width } => {
+                Mesh::from(Rectangle::new(width, range)).translated_by(Vec3::new(0.0, range / 2.0, 0.0))
+            }
+        }
+    }
+}
+
+/// How an enemy attacks, for telegraphing it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttackDefinition {
+    pub shape: AttackShape,
+    /// Seconds before firing the telegraph shows; capped at the time between attacks
+    pub windup: f32,
+    /// Farthest the player can be for the enemy to fire; `None` fires at any distance
+    pub max_range: Option<f32>,
+}
+
+/// Ground decal telegraphing the attacks of `owner`
+#[derive(Component, Debug)]
+pub struct Telegraph {
+    pub owner: Entity,
+    pub attack: AttackDefinition,
+}
+
+/// Give every enemy with an attack definition a telegraph decal, hidden until it winds up
+pub fn spawn_attack_telegraphs(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    enemies: Query<(Entity, &Enemy, &Transform), Added<Enemy>>,
+) {
+    for (entity, enemy, transform) in enemies.iter() {
+        let Some(attack) = ArchetypeConfig::for_archetype(enemy.archetype).attack else {
+            continue;
+        };
+
+        commands.spawn((
+            Mesh2d(meshes.add(attack.shape.mesh())),
+            MeshMaterial2d(materials.add(Color::NONE)),
+            Transform::from_translation(transform.translation.truncate().extend(DepthLayer::TerrainDetail.z())),
+            Visibility::Hidden,
+            Telegraph { owner: entity, attack },
+        ));
+    }
+}
+
+/// Follow each telegraph's enemy, aim it at the player and show how close the attack is
+pub fn update_attack_telegraphs(
+    mut commands: Commands,
+    mut telegraphs: Query<(Entity, &Telegraph, &mut Transform, &mut Visibility, &MeshMaterial2d<ColorMaterial>)>,
+    enemies: Query<(&Transform, &AIBehavior, &LineOfSight), (With<Enemy>, Without<Telegraph>)>,
+    players: Query<&Transform, (With<Player>, Without<Enemy>, Without<Telegraph>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let player_pos = players.single().ok().map(|transform| transform.translation.truncate());
+
+    for (entity, telegraph, mut transform, mut visibility, material) in telegraphs.iter_mut() {
+        let Ok((enemy_transform, ai, los)) = enemies.get(telegraph.owner) else {
+            // The enemy died or left with its scene
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let (Some(player_pos), true) = (player_pos, los.has_los_to_player) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        let origin = enemy_transform.translation.truncate();
+        let direction = (player_pos - origin).normalize_or_zero();
+        transform.translation = origin.extend(DepthLayer::TerrainDetail.z());
+        transform.rotation = Quat::from_rotation_z(direction.to_angle() - FRAC_PI_2);
+
+        let windup = telegraph.attack.windup.min(ai.timer.duration().as_secs_f32());
+        let remaining = ai.timer.remaining_secs();
+        let just_fired = ai.since_fired < TELEGRAPH_FLASH_SECS;
+        // Out of range the timer still runs but nothing fires, so there's nothing to wind up
+        let in_range = telegraph.attack.max_range.is_none_or(|range| origin.distance(player_pos) <= range);
+        if (remaining > windup || !in_range) && !just_fired {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Visible;
+
+        let color = if just_fired {
+            Color::srgba(1.0, 0.9, 0.7, 0.7)
+        } else {
+            let progress = 1.0 - remaining / windup.max(f32::EPSILON);
+            let alpha = TELEGRAPH_MIN_ALPHA + (TELEGRAPH_MAX_ALPHA - TELEGRAPH_MIN_ALPHA) * progress;
+            // Red while standing in it, as a cue to get out
+            if telegraph.attack.shape.contains(origin, direction, player_pos) {
+                Color::srgba(1.0, 0.15, 0.1, alpha)
+            } else {
+                Color::srgba(1.0, 0.6, 0.1, alpha)
+            }
+        };
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = color;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cone_covers_only_its_wedge() {
+        let cone = AttackShape::Cone { range: 100.0, half_angle: 0.5 };
+        assert!(cone.contains(Vec2::ZERO, Vec2::X, Vec2::new(50.0, 10.0)));
+        assert!(!cone.contains(Vec2::ZERO, Vec2::X, Vec2::new(50.0, 50.0)));
+        assert!(!cone.contains(Vec2::ZERO, Vec2::X, Vec2::new(150.0, 0.0)));
+        assert!(!cone.contains(Vec2::ZERO, Vec2::X, Vec2::new(-50.0, 0.0)));
+    }
+
+    #[test]
+    fn test_line_covers_only_ahead_of_the_attacker() {
+        let line = AttackShape::Line { range: 200.0, width: 10.0 };
+        let origin = Vec2::new(10.0, 10.0);
+        assert!(line.contains(origin, Vec2::Y, Vec2::new(14.0, 150.0)));
+        assert!(!line.contains(origin, Vec2::Y, Vec2::new(16.0, 150.0)));
+        assert!(!line.contains(origin, Vec2::Y, Vec2::new(10.0, 0.0)));
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::constants::HIT_FLASH_DURATION;
 
 /// Team affiliation for entities - determines collision and damage interactions
-#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[reflect(Component)]
 pub enum Team {
     Player,
     Enemy,
@@ -10,7 +13,7 @@ pub enum Team {
 
 
 /// Enemy archetype defining behavior and stats
-#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect)]
 pub enum EnemyArchetype {
     SmallMelee,
     BigMelee,
@@ -19,8 +22,22 @@ pub enum EnemyArchetype {
     MachineGunner,
 }
 
+impl EnemyArchetype {
+    /// Score awarded for killing this archetype
+    pub fn score_value(&self) -> u32 {
+        match self {
+            EnemyArchetype::SmallMelee => 10,
+            EnemyArchetype::BigMelee => 50,
+            EnemyArchetype::Shotgunner => 30,
+            EnemyArchetype::Sniper => 75,
+            EnemyArchetype::MachineGunner => 40,
+        }
+    }
+}
+
 /// Enemy marker component with archetype
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Enemy {
     pub archetype: EnemyArchetype,
 }
@@ -29,14 +46,23 @@ pub struct Enemy {
 #[derive(Component)]
 pub struct AIBehavior {
     pub timer: Timer,
+    /// Seconds since the enemy last attacked, infinite before its first attack
+    pub since_fired: f32,
 }
 
 impl AIBehavior {
     pub fn new(behavior_interval: f32) -> Self {
         Self {
             timer: Timer::from_seconds(behavior_interval, TimerMode::Repeating),
+            since_fired: f32::INFINITY,
         }
     }
+
+    /// Record an attack and start waiting for the next one
+    pub fn fire(&mut self) {
+        self.timer.reset();
+        self.since_fired = 0.0;
+    }
 }
 
 /// Projectile component with lifetime and team affiliation
@@ -44,6 +70,38 @@ impl AIBehavior {
 pub struct Projectile {
     pub lifetime: Timer,
     pub team: Team,
+    pub kind: ProjectileKind,
+    /// Who fired it, credited with its hits
+    pub source: Entity,
+}
+
+/// What a projectile is, for picking its impact effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProjectileKind {
+    Bullet,
+    /// Shotgun pellets, smaller and more numerous than bullets
+    Pellet,
+    Grenade,
+}
+
+/// What something is made of, for picking impact effects
+/// Entities without one count as flesh if they're on a team and stone otherwise
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Surface {
+    Stone,
+    Flesh,
+    Metal,
+}
+
+impl Surface {
+    /// The surface of an entity with these components
+    pub fn of(surface: Option<&Surface>, team: Option<&Team>) -> Self {
+        match (surface, team) {
+            (Some(surface), _) => *surface,
+            (None, Some(_)) => Self::Flesh,
+            (None, None) => Self::Stone,
+        }
+    }
 }
 
 /// Health component for entities that can take damage
@@ -79,12 +137,21 @@ pub struct MainCamera;
 pub struct LaserSight {
     pub is_active: bool,
     pub target_pos: Vec2,
+    /// Where the beam actually ends, at the first wall or the player on the way to `target_pos`
+    /// Set while the laser is active, so stealth and AI can tell what the beam touches
+    pub beam_end: Vec2,
+    /// What the beam stopped on, if anything
+    pub beam_hit: Option<Entity>,
 }
 
 /// Health bar UI component
 #[derive(Component)]
 pub struct HealthBar;
 
+/// Overheal segment drawn over the health bar
+#[derive(Component)]
+pub struct OverhealBar;
+
 /// Game over overlay UI component
 #[derive(Component)]
 pub struct GameOverOverlay;
@@ -94,10 +161,32 @@ pub struct GameOverOverlay;
 pub struct RestartButton;
 
 /// Hit flash component for visual damage feedback
-#[derive(Component)]
+///
+/// Tints the entity's own material toward the flash color and back, so no
+/// materials are created or swapped. Another hit while flashing restarts the
+/// fade but keeps `base_color`, which is restored when the flash ends.
+#[derive(Component, Debug, Clone)]
 pub struct HitFlash {
-    pub timer: Timer,
-    pub original_color: Color,
+    /// Seconds until the tint is gone
+    pub remaining: f32,
+    /// Material color from before the first overlapping hit
+    pub base_color: Color,
+}
+
+impl HitFlash {
+    pub fn new(base_color: Color) -> Self {
+        Self { remaining: HIT_FLASH_DURATION, base_color }
+    }
+
+    /// Start the fade over, for a hit landing while still flashing
+    pub fn refresh(&mut self) {
+        self.remaining = HIT_FLASH_DURATION;
+    }
+
+    /// How far toward the flash color the tint is, 1 right after a hit
+    pub fn strength(&self) -> f32 {
+        (self.remaining / HIT_FLASH_DURATION).clamp(0.0, 1.0)
+    }
 }
 
 /// Grenade component with fuse timer
@@ -4,6 +4,13 @@
 pub const PLAYER_SPEED: f32 = 250.0;
 pub const PLAYER_RADIUS: f32 = 16.0; // 16 units = 0.5m radius = 1m diameter
 pub const PLAYER_MAX_HEALTH: f32 = 100.0;
+pub const PLAYER_MAX_ENERGY: f32 = 100.0;
+pub const PLAYER_ENERGY_REGEN: f32 = 8.0; // Energy per second
+pub const PLAYER_CRIT_CHANCE: f32 = 0.1;
+pub const PLAYER_CRIT_MULTIPLIER: f32 = 2.0;
+
+// Ability constants
+pub const ABILITY_SLOT_COUNT: usize = 3;
 
 // Dash constants
 pub const DASH_SPEED: f32 = 800.0;
@@ -11,6 +18,10 @@ pub const DASH_DURATION: f32 = 0.2; // 0.2 seconds
 pub const DASH_COOLDOWN: f32 = 1.5; // 1.5 seconds cooldown
 pub const DASH_IFRAME_DURATION: f32 = 0.15; // Invincibility for most of dash
 
+// Physics constants
+pub const FIXED_UPDATE_HZ: f64 = 20.0;
+pub const PHYSICS_SUBSTEPS: usize = 8; // 160 steps per second so 1200 u/s sniper bullets can't skip a 16 unit tile
+
 // Projectile constants
 pub const PROJECTILE_SPEED: f32 = 800.0;
 pub const PROJECTILE_SIZE: f32 = 3.0;
@@ -37,12 +48,16 @@ pub const SHOTGUNNER_RADIUS: f32 = 10.0;
 pub const SHOTGUNNER_RANGE: f32 = 80.0;
 pub const SHOTGUNNER_FIRE_RATE: f32 = 2.0;
 pub const SHOTGUNNER_PELLETS: usize = 5;
+pub const SHOTGUNNER_WINDUP: f32 = 0.6; // Seconds the cone is telegraphed before a spread
 
 pub const SNIPER_HEALTH: f32 = 20.0;
 pub const SNIPER_SPEED: f32 = 100.0;
 pub const SNIPER_RADIUS: f32 = 8.0;
 pub const SNIPER_RANGE: f32 = 500.0;
 pub const SNIPER_FIRE_RATE: f32 = 1.5;
+pub const LASER_FADE_RANGE: f32 = 600.0; // Beam length at which the far end is faintest
+pub const LASER_MIN_ALPHA: f32 = 0.15; // Opacity of the far end of a fully faded beam
+pub const SNIPER_WINDUP: f32 = 1.0; // Matches the laser sight
 
 pub const MACHINE_GUNNER_HEALTH: f32 = 40.0;
 pub const MACHINE_GUNNER_SPEED: f32 = 160.0;
@@ -62,6 +77,11 @@ pub const SHOTGUN_BULLET_SPEED: f32 = 400.0;
 // Hit flash constants
 pub const HIT_FLASH_DURATION: f32 = 0.15; // Duration of hit flash in seconds
 
+// Slow motion constants
+pub const KILL_SLOW_MOTION_SCALE: f32 = 0.3; // Game speed during kill slow motion
+pub const KILL_SLOW_MOTION_DURATION: f32 = 0.6; // Real seconds kill slow motion lasts
+pub const KILL_SLOW_MOTION_MULTI_KILL: usize = 3; // Kills in one tick that count as a big kill
+
 // Grenade constants
 pub const GRENADE_SPEED: f32 = 400.0;
 pub const GRENADE_SIZE: f32 = 4.0;
@@ -5,17 +5,29 @@
 //! - Frame time, FPS, and performance metrics
 //! - Chunk boundaries visualization
 //! - Active game state information
+//! - Spawn palette for placing enemies, items and props (F5)
+//! - Chunk seam scan (F7)
+//! - Photo mode free camera (F8)
+//! - Entity dump to the log (F9)
+//! - Chunk load states and refcounts (F10)
 
 use bevy::{
     prelude::*,
     diagnostic::{FrameTimeDiagnosticsPlugin, DiagnosticsStore},
 };
 
+mod chunks;
+mod dump;
+mod palette;
+mod photo;
+mod seams;
+
 use crate::{
     player::Player,
     world::chunks::{world_pos_to_chunk_coord, ChunkCoord, CHUNK_SIZE, ChunkingState},
     components::MainCamera,
     resources::GameState,
+    ui::theme::UiTheme,
 };
 
 /// Plugin for the debug overlay system
@@ -26,8 +38,19 @@ impl Plugin for DebugOverlayPlugin {
         app
             // Add frame time diagnostics plugin for FPS tracking
             .add_plugins(FrameTimeDiagnosticsPlugin::default())
+            // Spawn palette for manual testing
+            .add_plugins(palette::DebugPalettePlugin)
+            // Chunk border validation
+            .add_plugins(seams::ChunkSeamPlugin)
+            // Free camera for inspecting maps and screenshots
+            .add_plugins(photo::PhotoModePlugin)
+            // Component dumps for bug reports
+            .add_plugins(dump::EntityDumpPlugin)
+            // Chunk load states for tuning loader radii
+            .add_plugins(chunks::ChunkOverlayPlugin)
             // Initialize debug state
             .init_resource::<DebugOverlayState>()
+            .init_resource::<DebugBindings>()
             // Add debug overlay systems
             .add_systems(Startup, setup_debug_overlay)
             .add_systems(Update, (
@@ -43,6 +66,30 @@ impl Plugin for DebugOverlayPlugin {
     }
 }
 
+/// Keys for the debug tools
+#[derive(Resource, Debug, Clone)]
+pub struct DebugBindings {
+    pub overlay: KeyCode,
+    pub palette: KeyCode,
+    pub seams: KeyCode,
+    pub photo_mode: KeyCode,
+    pub dump: KeyCode,
+    pub chunks: KeyCode,
+}
+
+impl Default for DebugBindings {
+    fn default() -> Self {
+        Self {
+            overlay: KeyCode::F3,
+            palette: KeyCode::F5,
+            seams: KeyCode::F7,
+            photo_mode: KeyCode::F8,
+            dump: KeyCode::F9,
+            chunks: KeyCode::F10,
+        }
+    }
+}
+
 /// Resource to track debug overlay state
 #[derive(Resource, Default)]
 pub struct DebugOverlayState {
@@ -67,31 +114,24 @@ pub struct DebugText;
 pub struct ChunkBoundaryLine;
 
 /// Sets up the debug overlay UI elements (initially hidden)
-fn setup_debug_overlay(mut commands: Commands) {
+fn setup_debug_overlay(mut commands: Commands, theme: Res<UiTheme>) {
     commands.spawn((
-        Node {
+        theme.panel(Node {
             position_type: PositionType::Absolute,
             top: Val::Px(10.0),
             left: Val::Px(10.0),
             width: Val::Px(450.0),
             height: Val::Px(500.0), // Increased height to show more debug info
-            padding: UiRect::all(Val::Px(10.0)),
+            border: UiRect::ZERO,
             display: Display::None, // Initially hidden
-            ..default()
-        },
-        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
-        BorderColor(Color::srgb(0.5, 0.5, 0.5)),
+            ..theme.panel_node()
+        }),
         BorderRadius::all(Val::Px(5.0)),
         DebugOverlay,
     ))
     .with_children(|parent| {
         parent.spawn((
-            Text::new("Debug Information"),
-            TextFont {
-                font_size: 12.0, // Smaller font to fit more text
-                ..default()
-            },
-            TextColor(Color::WHITE),
+            theme.text("Debug Information", theme.small_size), // Smaller font to fit more text
             Node {
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
@@ -107,11 +147,12 @@ fn setup_debug_overlay(mut commands: Commands) {
 fn toggle_debug_overlay(
     mut commands: Commands,
     keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<DebugBindings>,
     mut debug_state: ResMut<DebugOverlayState>,
     mut debug_overlay_query: Query<&mut Node, With<DebugOverlay>>,
     chunk_boundary_query: Query<Entity, With<ChunkBoundaryLine>>,
 ) {
-    if keyboard.just_pressed(KeyCode::F3) {
+    if keyboard.just_pressed(bindings.overlay) {
         debug_state.show_overlay = !debug_state.show_overlay;
 
         // Update UI visibility
@@ -147,6 +188,9 @@ fn update_debug_text(
     game_state: Res<GameState>,
     diagnostics: Res<DiagnosticsStore>,
     time: Res<Time>,
+    pool_metrics: Option<Res<crate::pool::PoolMetrics>>,
+    ui_stats: Option<Res<crate::ui::UiUpdateStats>>,
+    event_log: Option<Res<crate::events::GameEventLog>>,
 ) {
     if let Ok(mut text) = debug_text_query.single_mut() {
         let mut debug_info = String::new();
@@ -236,6 +280,38 @@ fn update_debug_text(
 
         debug_info.push('\n');
 
+        // Pooling metrics
+        if let Some(pool_metrics) = pool_metrics.filter(|metrics| !metrics.pools.is_empty()) {
+            debug_info.push_str("Pools:\n");
+            for (name, stats) in &pool_metrics.pools {
+                debug_info.push_str(&format!(
+                    "  {}: {} new, {} reused ({:.0}%)\n",
+                    name, stats.spawned, stats.reused, stats.reuse_ratio() * 100.0,
+                ));
+            }
+            debug_info.push('\n');
+        }
+
+        // UI rebuild counts, these should stay put while nothing changes
+        if let Some(ui_stats) = ui_stats.filter(|stats| !stats.updates.is_empty()) {
+            debug_info.push_str("UI updates:\n");
+            for (system, count) in &ui_stats.updates {
+                debug_info.push_str(&format!("  {}: {}\n", system, count));
+            }
+            debug_info.push('\n');
+        }
+
+        // Most recent gameplay events, newest last
+        if let Some(event_log) = event_log.filter(|log| log.total_recorded > 0) {
+            debug_info.push_str(&format!("Events ({} total):\n", event_log.total_recorded));
+            let recent: Vec<_> = event_log.entries().rev().take(5).collect();
+            for record in recent.into_iter().rev() {
+                let details: String = record.details.chars().take(48).collect();
+                debug_info.push_str(&format!("  {:.1}s {}: {}\n", record.timestamp, record.kind, details));
+            }
+            debug_info.push('\n');
+        }
+
         // Game state information
         debug_info.push_str(&format!("Game State: {:?}\n", *game_state));
 
@@ -0,0 +1,139 @@
+//! Chunk load state overlay (F10)
+//!
+//! For tuning `ChunkLoader` radii: F10 draws the chunk grid over the world,
+//! outlines every chunk a loader cares about in the color of its load state
+//! and labels it with its coordinates and how many loaders hold it, until it
+//! is pressed again.
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::{
+    components::MainCamera,
+    depth::DepthLayer,
+    world::{
+        chunks::{world_pos_to_chunk_coord, ChunkCoord, ChunkLoadState, ChunkLoader, ChunkRegistry, CHUNK_SIZE},
+        tiles::TILE_SIZE,
+    },
+};
+
+/// World units from the camera to the edge of the drawn area at a scale of 1
+const VIEW_RADIUS: f32 = 1000.0;
+/// Gap between a chunk's grid line and its state outline, in world units
+const STATE_INSET: f32 = 6.0;
+const LABEL_FONT_SIZE: f32 = 12.0;
+const GRID_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.5);
+
+/// Plugin for the chunk load state overlay
+pub struct ChunkOverlayPlugin;
+
+impl Plugin for ChunkOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ChunkOverlay>()
+            .add_systems(Update, (
+                toggle_chunk_overlay,
+                draw_chunk_overlay.run_if(|overlay: Res<ChunkOverlay>| overlay.visible),
+            ).chain());
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ChunkOverlay {
+    pub visible: bool,
+}
+
+/// Coordinates and refcount label over a chunk
+#[derive(Component)]
+struct ChunkOverlayLabel(ChunkCoord);
+
+fn state_color(state: ChunkLoadState) -> Color {
+    match state {
+        ChunkLoadState::Active => Color::srgb(0.2, 1.0, 0.3),
+        ChunkLoadState::Preload => Color::srgb(0.3, 0.6, 1.0),
+        ChunkLoadState::PendingUnload => Color::srgb(1.0, 0.4, 0.2),
+    }
+}
+
+fn toggle_chunk_overlay(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<super::DebugBindings>,
+    mut overlay: ResMut<ChunkOverlay>,
+    labels: Query<Entity, With<ChunkOverlayLabel>>,
+) {
+    if !keyboard.just_pressed(bindings.chunks) {
+        return;
+    }
+
+    overlay.visible = !overlay.visible;
+    if !overlay.visible {
+        for entity in labels.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn draw_chunk_overlay(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    registry: Res<ChunkRegistry>,
+    loaders: Query<(&Transform, &ChunkLoader)>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    mut labels: Query<(Entity, &ChunkOverlayLabel, &mut Text2d, &mut TextColor)>,
+) {
+    let Ok(camera) = camera_query.single() else {
+        // No view to label, as between scenes
+        for (entity, ..) in labels.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    let chunk_size_world = CHUNK_SIZE as f32 * TILE_SIZE;
+    let view_radius = VIEW_RADIUS * camera.scale.x.min(camera.scale.y);
+    let min_chunk = world_pos_to_chunk_coord(camera.translation.truncate() - Vec2::splat(view_radius));
+    let max_chunk = world_pos_to_chunk_coord(camera.translation.truncate() + Vec2::splat(view_radius));
+
+    let loaders: Vec<(ChunkCoord, &ChunkLoader)> = loaders
+        .iter()
+        .map(|(transform, loader)| (world_pos_to_chunk_coord(transform.translation.truncate()), loader))
+        .collect();
+
+    let mut states = HashMap::new();
+    for x in min_chunk.x..=max_chunk.x {
+        for y in min_chunk.y..=max_chunk.y {
+            let coord = ChunkCoord::new(x, y);
+            let center = (coord.as_vec2() + Vec2::splat(0.5)) * chunk_size_world;
+            gizmos.rect_2d(Isometry2d::from_translation(center), Vec2::splat(chunk_size_world), GRID_COLOR);
+
+            if let Some(state) = registry.load_state(coord, &loaders) {
+                let inset = Vec2::splat(chunk_size_world - STATE_INSET * 2.0);
+                gizmos.rect_2d(Isometry2d::from_translation(center), inset, state_color(state));
+                states.insert(coord, (state, center));
+            }
+        }
+    }
+
+    // Update the labels still on screen and drop the rest, then label the new chunks
+    for (entity, label, mut text, mut color) in labels.iter_mut() {
+        match states.remove(&label.0) {
+            Some((state, _)) => {
+                text.0 = label_text(label.0, registry.get_refcount(label.0));
+                color.0 = state_color(state);
+            }
+            None => commands.entity(entity).despawn(),
+        }
+    }
+    for (coord, (state, center)) in states {
+        commands.spawn((
+            Text2d::new(label_text(coord, registry.get_refcount(coord))),
+            TextFont { font_size: LABEL_FONT_SIZE, ..default() },
+            TextColor(state_color(state)),
+            Transform::from_translation(center.extend(DepthLayer::Labels.z())),
+            ChunkOverlayLabel(coord),
+        ));
+    }
+}
+
+fn label_text(coord: ChunkCoord, refcount: usize) -> String {
+    format!("({}, {})\n{} loaders", coord.x, coord.y, refcount)
+}
@@ -0,0 +1,152 @@
+//! Entity dump (F9)
+//!
+//! Writes every reflected component of an entity to the log as RON, so a bug
+//! report about a broken enemy or item can carry its actual state. F9 dumps
+//! whatever is under the cursor; anything else can send `DumpEntity`.
+//! Components whose types were never given to `register_type` can't be
+//! serialized and are listed by name instead.
+use bevy::{
+    prelude::*,
+    reflect::serde::ReflectSerializer,
+    scene::ron::{self, ser::PrettyConfig},
+    window::PrimaryWindow,
+};
+use bevy_rapier2d::prelude::Collider;
+
+use crate::components::MainCamera;
+
+/// How far from the cursor F9 looks for an entity, in world units
+const PICK_RADIUS: f32 = 32.0;
+
+/// Plugin for the entity dump
+pub struct EntityDumpPlugin;
+
+impl Plugin for EntityDumpPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<DumpEntity>()
+            .add_systems(Update, (pick_entity_to_dump, dump_entities).chain());
+    }
+}
+
+/// Log the components of `entity`
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DumpEntity {
+    pub entity: Entity,
+}
+
+/// Every reflected component of `entity` as RON, or `None` if it doesn't exist
+pub fn dump_entity(world: &World, entity: Entity) -> Option<String> {
+    let entity_ref = world.get_entity(entity).ok()?;
+    let registry = world.resource::<AppTypeRegistry>().read();
+
+    let mut dump = format!("{entity}");
+    if let Some(name) = entity_ref.get::<Name>() {
+        dump.push_str(&format!(" \"{name}\""));
+    }
+    dump.push('\n');
+
+    let mut unreflected = Vec::new();
+    for component_id in entity_ref.archetype().components() {
+        let Some(info) = world.components().get_info(component_id) else {
+            continue;
+        };
+        let reflect_component = info
+            .type_id()
+            .and_then(|type_id| registry.get(type_id))
+            .and_then(|registration| registration.data::<ReflectComponent>());
+        let Some(value) = reflect_component.and_then(|reflect_component| reflect_component.reflect(entity_ref)) else {
+            unreflected.push(info.name());
+            continue;
+        };
+
+        let serializer = ReflectSerializer::new(value.as_partial_reflect(), &registry);
+        match ron::ser::to_string_pretty(&serializer, PrettyConfig::default()) {
+            Ok(ron) => dump.push_str(&ron),
+            Err(error) => dump.push_str(&format!("// {}: {}", info.name(), error)),
+        }
+        dump.push('\n');
+    }
+
+    if !unreflected.is_empty() {
+        unreflected.sort_unstable();
+        dump.push_str("// Not registered for reflection:\n");
+        for name in unreflected {
+            dump.push_str(&format!("//   {name}\n"));
+        }
+    }
+
+    Some(dump)
+}
+
+/// Dump the entity nearest the cursor with F9
+fn pick_entity_to_dump(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<super::DebugBindings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    candidates: Query<(Entity, &GlobalTransform), With<Collider>>,
+    mut dump_events: EventWriter<DumpEntity>,
+) {
+    if !keyboard.just_pressed(bindings.dump) {
+        return;
+    }
+
+    let Ok(window) = windows.single() else { return; };
+    let Ok((camera, camera_transform)) = cameras.single() else { return; };
+    let Some(cursor) = window.cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok()) else {
+        return;
+    };
+
+    let nearest = candidates
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation().truncate().distance(cursor)))
+        .filter(|&(_, distance)| distance <= PICK_RADIUS)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    match nearest {
+        Some((entity, _)) => {
+            dump_events.write(DumpEntity { entity });
+        }
+        None => info!("Nothing to dump under the cursor"),
+    }
+}
+
+fn dump_entities(world: &World, mut dump_events: EventReader<DumpEntity>) {
+    for event in dump_events.read() {
+        match dump_entity(world, event.entity) {
+            Some(dump) => info!("Entity dump:\n{dump}"),
+            None => warn!("Can't dump {}, it doesn't exist", event.entity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Reflected {
+        value: u32,
+    }
+
+    #[derive(Component)]
+    struct Unreflected;
+
+    #[test]
+    fn test_dump_lists_reflected_and_unreflected_components() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.resource::<AppTypeRegistry>().write().register::<Reflected>();
+        let entity = world.spawn((Reflected { value: 7 }, Unreflected)).id();
+
+        let dump = dump_entity(&world, entity).unwrap();
+        assert!(dump.contains("value: 7"));
+        assert!(dump.contains("Unreflected"));
+
+        world.despawn(entity);
+        assert!(dump_entity(&world, entity).is_none());
+    }
+}
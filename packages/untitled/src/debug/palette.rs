@@ -0,0 +1,387 @@
+//! Debug spawn palette (F5 by default, see `DebugBindings`)
+//!
+//! Lists enemy archetypes, registered items and simple props. Click an entry,
+//! then left-click in the world to place it at the cursor; right-click clears
+//! the selection. With chunking enabled, placement is limited to loaded chunks.
+//! Placed enemies join the chunk population, so they go to sleep with their
+//! chunk and wake up when it loads again; items and props stay put when their
+//! chunk unloads. Everything placed is cleared on scene change.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    components::{EnemyArchetype, MainCamera, Surface},
+    enemy::{spawn_enemy, ArchetypeConfig, ENEMY_ARCHETYPES},
+    inventory::{factory::ItemFactory, registry::ItemId, spawn_world_item, ItemRegistry},
+    player::key_label,
+    ui::theme::UiTheme,
+    world::{
+        chunks::{world_pos_to_chunk_coord, ChunkRegistry, ChunkingState},
+        scenes::dungeon::population::ChunkPopulant,
+        WorldState,
+    },
+};
+
+/// Plugin for the debug spawn palette
+pub struct DebugPalettePlugin;
+
+impl Plugin for DebugPalettePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DebugPalette>()
+            .add_systems(Update, (
+                toggle_debug_palette,
+                select_palette_entries,
+                update_palette_buttons,
+                place_palette_selection,
+                draw_palette_cursor,
+            ).chain())
+            .add_systems(Update, despawn_debug_spawns.run_if(state_changed::<WorldState>));
+    }
+}
+
+/// Simple static props for building test arenas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugProp {
+    Crate,
+    Pillar,
+}
+
+impl DebugProp {
+    pub const ALL: [DebugProp; 2] = [DebugProp::Crate, DebugProp::Pillar];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DebugProp::Crate => "Crate",
+            DebugProp::Pillar => "Pillar",
+        }
+    }
+
+    fn size(&self) -> Vec2 {
+        match self {
+            DebugProp::Crate => Vec2::new(32.0, 32.0),
+            DebugProp::Pillar => Vec2::new(24.0, 64.0),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            DebugProp::Crate => Color::srgb(0.55, 0.4, 0.2),
+            DebugProp::Pillar => Color::srgb(0.5, 0.5, 0.55),
+        }
+    }
+
+    /// What shots hitting the prop look like
+    fn surface(&self) -> Surface {
+        match self {
+            DebugProp::Crate => Surface::Stone,
+            DebugProp::Pillar => Surface::Metal,
+        }
+    }
+}
+
+/// Something the palette can place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteEntry {
+    Enemy(EnemyArchetype),
+    Item(ItemId),
+    Prop(DebugProp),
+}
+
+impl PaletteEntry {
+    fn label(&self, registry: &ItemRegistry) -> String {
+        match self {
+            PaletteEntry::Enemy(archetype) => format!("{:?}", archetype),
+            PaletteEntry::Item(item_id) => registry.get(*item_id)
+                .map(|def| def.name.clone())
+                .unwrap_or_else(|| format!("Item {}", item_id.0)),
+            PaletteEntry::Prop(prop) => prop.name().to_string(),
+        }
+    }
+
+    /// Rough footprint radius, used for the cursor preview
+    fn preview_radius(&self) -> f32 {
+        match self {
+            PaletteEntry::Enemy(archetype) => ArchetypeConfig::for_archetype(*archetype).radius,
+            PaletteEntry::Item(_) => 8.0,
+            PaletteEntry::Prop(prop) => prop.size().max_element() / 2.0,
+        }
+    }
+}
+
+/// Open state and current selection of the spawn palette
+#[derive(Resource, Default)]
+pub struct DebugPalette {
+    pub open: bool,
+    pub selected: Option<PaletteEntry>,
+}
+
+/// Root node of the palette window
+#[derive(Component)]
+struct DebugPalettePanel;
+
+/// Button selecting a palette entry
+#[derive(Component)]
+struct PaletteButton(PaletteEntry);
+
+/// Marker for everything placed through the palette, cleared on scene change
+#[derive(Component)]
+pub struct DebugSpawned;
+
+/// Toggle the palette window with its debug binding
+fn toggle_debug_palette(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<super::DebugBindings>,
+    mut palette: ResMut<DebugPalette>,
+    registry: Res<ItemRegistry>,
+    theme: Res<UiTheme>,
+    panels: Query<Entity, With<DebugPalettePanel>>,
+) {
+    if !keyboard.just_pressed(bindings.palette) {
+        return;
+    }
+
+    palette.open = !palette.open;
+
+    if !palette.open {
+        palette.selected = None;
+        for entity in panels.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let mut items: Vec<_> = registry.items.keys().copied().collect();
+    items.sort_by_key(|id| id.0);
+
+    let sections: [(&str, Vec<PaletteEntry>); 3] = [
+        ("Enemies", ENEMY_ARCHETYPES.iter().copied().map(PaletteEntry::Enemy).collect()),
+        ("Items", items.into_iter().map(PaletteEntry::Item).collect()),
+        ("Props", DebugProp::ALL.iter().copied().map(PaletteEntry::Prop).collect()),
+    ];
+
+    commands
+        .spawn((
+            theme.panel(Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                width: Val::Px(200.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                row_gap: Val::Px(2.0),
+                ..theme.panel_node()
+            }),
+            Interaction::default(),
+            DebugPalettePanel,
+        ))
+        .with_children(|parent| {
+            let title = format!("Spawn Palette ({})\nLMB place, RMB cancel", key_label(bindings.palette));
+            parent.spawn(theme.text(title, theme.small_size));
+
+            for (title, entries) in sections {
+                parent.spawn((
+                    Text::new(title),
+                    theme.font(theme.small_size),
+                    TextColor(theme.text_heading),
+                    Node {
+                        margin: UiRect::top(Val::Px(6.0)),
+                        ..default()
+                    },
+                ));
+
+                for entry in entries {
+                    parent
+                        .spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                                ..default()
+                            },
+                            BackgroundColor(theme.button_background),
+                            PaletteButton(entry),
+                        ))
+                        .with_child(theme.text(entry.label(&registry), theme.small_size));
+                }
+            }
+        });
+}
+
+/// Select (or deselect) an entry when its button is clicked, right-click clears
+fn select_palette_entries(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut palette: ResMut<DebugPalette>,
+    buttons: Query<(&Interaction, &PaletteButton), Changed<Interaction>>,
+) {
+    if !palette.open {
+        return;
+    }
+
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            palette.selected = if palette.selected == Some(button.0) {
+                None
+            } else {
+                Some(button.0)
+            };
+        }
+    }
+
+    if mouse.just_pressed(MouseButton::Right) {
+        palette.selected = None;
+    }
+}
+
+/// Color buttons by hover and selection state
+fn update_palette_buttons(
+    palette: Res<DebugPalette>,
+    theme: Res<UiTheme>,
+    mut buttons: Query<(&Interaction, &PaletteButton, &mut BackgroundColor)>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        let color = if palette.selected == Some(button.0) {
+            theme.button_selected
+        } else if *interaction != Interaction::None {
+            theme.button_hover
+        } else {
+            theme.button_background
+        };
+
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
+
+/// World position under the cursor, if any
+fn cursor_world_position(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) -> Option<Vec2> {
+    let window = windows.single().ok()?;
+    let (camera, camera_transform) = cameras.single().ok()?;
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world_2d(camera_transform, cursor).ok()
+}
+
+/// Placement is only allowed in loaded chunks while chunking is active
+fn can_place_at(
+    position: Vec2,
+    chunking_state: &State<ChunkingState>,
+    chunk_registry: &ChunkRegistry,
+) -> bool {
+    *chunking_state.get() == ChunkingState::Disabled
+        || chunk_registry.get_refcount(world_pos_to_chunk_coord(position)) > 0
+}
+
+/// Place the selected entry at the cursor on left click
+fn place_palette_selection(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    palette: Res<DebugPalette>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    ui_hover: Query<&Interaction, Or<(With<DebugPalettePanel>, With<PaletteButton>)>>,
+    chunking_state: Res<State<ChunkingState>>,
+    chunk_registry: Res<ChunkRegistry>,
+    registry: Res<ItemRegistry>,
+    mut factory: ResMut<ItemFactory>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !palette.open || !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Some(entry) = palette.selected else {
+        return;
+    };
+
+    // Clicks on the palette window itself are selections, not placements
+    if ui_hover.iter().any(|interaction| *interaction != Interaction::None) {
+        return;
+    }
+
+    let Some(position) = cursor_world_position(&windows, &cameras) else {
+        return;
+    };
+
+    if !can_place_at(position, &chunking_state, &chunk_registry) {
+        warn!("Debug palette: chunk {:?} is not loaded", world_pos_to_chunk_coord(position));
+        return;
+    }
+
+    let entity = match entry {
+        PaletteEntry::Enemy(archetype) => {
+            spawn_enemy(&mut commands, &mut meshes, &mut materials, archetype, position)
+        }
+        PaletteEntry::Item(item_id) => {
+            let Some(definition) = registry.get(item_id) else {
+                warn!("Debug palette: unknown item {:?}", item_id);
+                return;
+            };
+            let Some(item) = factory.create_item(item_id, &registry) else {
+                return;
+            };
+            spawn_world_item(&mut commands, &mut meshes, &mut materials, item, definition, position)
+        }
+        PaletteEntry::Prop(prop) => {
+            let size = prop.size();
+            commands
+                .spawn((
+                    Mesh2d(meshes.add(Rectangle::new(size.x, size.y))),
+                    MeshMaterial2d(materials.add(prop.color())),
+                    Transform::from_translation(position.extend(crate::depth::DepthLayer::Actors.z())),
+                    crate::depth::YSort::with_offset(-size.y / 2.0),
+                    RigidBody::Fixed,
+                    Collider::cuboid(size.x / 2.0, size.y / 2.0),
+                    crate::physics_layers::terrain(),
+                    prop.surface(),
+                ))
+                .id()
+        }
+    };
+
+    commands.entity(entity).insert(DebugSpawned);
+    // Chunk unload puts populants to sleep instead of losing them
+    if matches!(entry, PaletteEntry::Enemy(_)) && *chunking_state.get() == ChunkingState::Enabled {
+        commands.entity(entity).insert(ChunkPopulant);
+    }
+
+    info!("Debug palette: placed {:?} at ({:.1}, {:.1})", entry, position.x, position.y);
+}
+
+/// Preview the selected entry's footprint under the cursor
+fn draw_palette_cursor(
+    mut gizmos: Gizmos,
+    palette: Res<DebugPalette>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    chunking_state: Res<State<ChunkingState>>,
+    chunk_registry: Res<ChunkRegistry>,
+) {
+    let Some(entry) = palette.selected else {
+        return;
+    };
+    let Some(position) = cursor_world_position(&windows, &cameras) else {
+        return;
+    };
+
+    let color = if can_place_at(position, &chunking_state, &chunk_registry) {
+        Color::srgb(0.3, 1.0, 0.4)
+    } else {
+        Color::srgb(1.0, 0.3, 0.3)
+    };
+    gizmos.circle_2d(position, entry.preview_radius(), color);
+}
+
+/// Remove palette spawns when leaving a scene so they don't leak into the next one
+fn despawn_debug_spawns(
+    mut commands: Commands,
+    spawned: Query<Entity, With<DebugSpawned>>,
+) {
+    for entity in spawned.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,161 @@
+//! Photo mode free camera (F8)
+//!
+//! Detaches the camera from the player so it can fly over the map with WASD
+//! and zoom far out with the scroll wheel, for looking over generated
+//! dungeons and taking screenshots. The HUD is hidden while it's on, and P
+//! pauses the simulation. Everything runs on real time so the camera still
+//! moves while paused.
+use bevy::{input::mouse::MouseWheel, prelude::*};
+
+use crate::{
+    components::MainCamera,
+    player::CameraZoom,
+    resources::PhotoModeActive,
+};
+
+/// Camera speed in pixels per second at a zoom of 1
+const FLY_SPEED: f32 = 800.0;
+/// Held to fly this many times faster
+const FLY_BOOST: f32 = 4.0;
+/// Zoom change per scroll step, as a factor
+const ZOOM_STEP: f32 = 1.15;
+const MIN_ZOOM: f32 = 0.25;
+/// Far enough out to see a large dungeon at once
+const MAX_ZOOM: f32 = 40.0;
+
+/// Plugin for the photo mode camera
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PhotoMode>()
+            .add_systems(Update, (
+                toggle_photo_mode,
+                (
+                    toggle_photo_pause,
+                    fly_photo_camera,
+                ).run_if(resource_exists::<PhotoModeActive>),
+            ).chain());
+    }
+}
+
+/// What photo mode changed, so it can all be put back on the way out
+#[derive(Resource, Default)]
+pub struct PhotoMode {
+    /// Camera transform from before photo mode
+    saved_camera: Option<Transform>,
+    /// HUD roots photo mode hid, with the visibility they had
+    hidden_hud: Vec<(Entity, Visibility)>,
+    /// Whether photo mode paused the simulation
+    paused: bool,
+}
+
+/// Toggle photo mode with F8
+fn toggle_photo_mode(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<super::DebugBindings>,
+    active: Option<Res<PhotoModeActive>>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    camera_zoom: Res<CameraZoom>,
+    mut cameras: Query<&mut Transform, With<MainCamera>>,
+    mut hud: Query<(Entity, &mut Visibility), (With<Node>, Without<ChildOf>)>,
+) {
+    if !keyboard.just_pressed(bindings.photo_mode) {
+        return;
+    }
+
+    if active.is_none() {
+        photo_mode.saved_camera = cameras.single().ok().copied();
+        for (entity, mut visibility) in hud.iter_mut() {
+            if *visibility != Visibility::Hidden {
+                photo_mode.hidden_hud.push((entity, *visibility));
+                *visibility = Visibility::Hidden;
+            }
+        }
+        commands.insert_resource(PhotoModeActive);
+        info!("Photo mode on: WASD to fly, Shift to go faster, scroll to zoom, P to pause");
+        return;
+    }
+
+    for (entity, visibility) in photo_mode.hidden_hud.drain(..) {
+        if let Ok((_, mut current)) = hud.get_mut(entity) {
+            *current = visibility;
+        }
+    }
+    if let (Some(saved), Ok(mut transform)) = (photo_mode.saved_camera.take(), cameras.single_mut()) {
+        transform.translation = saved.translation;
+        transform.scale = Vec3::splat(camera_zoom.level);
+    }
+    if std::mem::take(&mut photo_mode.paused) {
+        virtual_time.unpause();
+    }
+    commands.remove_resource::<PhotoModeActive>();
+    info!("Photo mode off");
+}
+
+/// Pause or resume the simulation with P
+fn toggle_photo_pause(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    photo_mode.paused = !photo_mode.paused;
+    if photo_mode.paused {
+        virtual_time.pause();
+    } else {
+        virtual_time.unpause();
+    }
+}
+
+/// Fly the camera with WASD and zoom with the scroll wheel
+fn fly_photo_camera(
+    time: Res<Time<Real>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut scroll_events: EventReader<MouseWheel>,
+    mut cameras: Query<&mut Transform, With<MainCamera>>,
+) {
+    let Ok(mut transform) = cameras.single_mut() else {
+        return;
+    };
+
+    let mut zoom = transform.scale.x;
+    for scroll in scroll_events.read() {
+        if scroll.y > 0.0 {
+            zoom /= ZOOM_STEP;
+        } else if scroll.y < 0.0 {
+            zoom *= ZOOM_STEP;
+        }
+    }
+    zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    transform.scale = Vec3::splat(zoom);
+
+    let direction = fly_direction(&keyboard);
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let boost = if keyboard.pressed(KeyCode::ShiftLeft) { FLY_BOOST } else { 1.0 };
+    // Scaled by zoom so crossing the screen takes as long at any zoom
+    let step = direction * FLY_SPEED * boost * zoom * time.delta_secs();
+    transform.translation += step.extend(0.0);
+}
+
+fn fly_direction(keyboard: &ButtonInput<KeyCode>) -> Vec2 {
+    let keys = [
+        (KeyCode::KeyW, Vec2::Y),
+        (KeyCode::KeyS, Vec2::NEG_Y),
+        (KeyCode::KeyA, Vec2::NEG_X),
+        (KeyCode::KeyD, Vec2::X),
+    ];
+    keys.iter()
+        .filter(|(key, _)| keyboard.pressed(*key))
+        .map(|(_, direction)| *direction)
+        .sum::<Vec2>()
+        .normalize_or_zero()
+}
@@ -0,0 +1,159 @@
+//! Chunk border validation (F7)
+//!
+//! Chunks are generated and persisted one at a time, so a generation bug or a
+//! stale chunk loaded from the database shows up as a seam: a corridor cut off
+//! by a straight wall lying exactly on a chunk border. F7 scans the loaded
+//! dungeon chunks and outlines the tiles on both sides of every seam until it
+//! is pressed again.
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::world::{
+    chunks::{ChunkCoord, CHUNK_SIZE},
+    scenes::dungeon::terrain::{ChunkTiles, TerrainChunks},
+    tiles::TILE_SIZE,
+};
+
+/// Consecutive mismatched tiles along a border before it counts as a seam;
+/// walls crossing a border at an angle only mismatch for a tile or two
+const MIN_SEAM_RUN: usize = 3;
+
+/// Plugin for the chunk seam scan
+pub struct ChunkSeamPlugin;
+
+impl Plugin for ChunkSeamPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ChunkSeamReport>()
+            .add_systems(Update, (scan_chunk_seams, draw_chunk_seams).chain());
+    }
+}
+
+/// Result of the last scan, drawn while visible
+#[derive(Resource, Default)]
+pub struct ChunkSeamReport {
+    /// Global tile coordinates on both sides of every seam
+    pub tiles: Vec<IVec2>,
+    pub visible: bool,
+}
+
+/// Find tiles on seams between loaded chunks, in global tile coordinates
+pub fn find_chunk_seams(chunks: &HashMap<ChunkCoord, &ChunkTiles>) -> Vec<IVec2> {
+    let mut seams = Vec::new();
+    for (&coord, tiles) in chunks {
+        // Each border is checked once, from the chunk to its east or north
+        for axis in [IVec2::X, IVec2::Y] {
+            if let Some(neighbor) = chunks.get(&(coord + axis)) {
+                find_border_seams(coord, tiles, neighbor, axis, &mut seams);
+            }
+        }
+    }
+    seams
+}
+
+/// Walk the border between `tiles` and the neighbor at `coord + axis`
+fn find_border_seams(coord: ChunkCoord, tiles: &ChunkTiles, neighbor: &ChunkTiles, axis: IVec2, seams: &mut Vec<IVec2>) {
+    let last = CHUNK_SIZE as usize - 1;
+    let origin = coord * CHUNK_SIZE as i32;
+
+    let mut run = Vec::new();
+    let mut run_inside = None;
+    for i in 0..=last {
+        let (x, y) = if axis == IVec2::X { (last, i) } else { (i, last) };
+        let inside = tiles[x][y];
+        let outside = if axis == IVec2::X { neighbor[0][i] } else { neighbor[i][0] };
+
+        // A seam keeps floor on the same side the whole way along
+        let mismatch = (inside != outside).then_some(inside);
+        if mismatch != run_inside {
+            flush_seam_run(&mut run, axis, seams);
+            run_inside = mismatch;
+        }
+        if mismatch.is_some() {
+            run.push(origin + IVec2::new(x as i32, y as i32));
+        }
+    }
+    flush_seam_run(&mut run, axis, seams);
+}
+
+fn flush_seam_run(run: &mut Vec<IVec2>, axis: IVec2, seams: &mut Vec<IVec2>) {
+    if run.len() >= MIN_SEAM_RUN {
+        seams.extend(run.iter().flat_map(|tile| [*tile, *tile + axis]));
+    }
+    run.clear();
+}
+
+fn scan_chunk_seams(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<super::DebugBindings>,
+    terrain_chunks: Option<Res<TerrainChunks>>,
+    mut report: ResMut<ChunkSeamReport>,
+) {
+    if !keyboard.just_pressed(bindings.seams) {
+        return;
+    }
+
+    if report.visible {
+        *report = ChunkSeamReport::default();
+        return;
+    }
+
+    let Some(terrain_chunks) = terrain_chunks else {
+        return;
+    };
+    let chunks: HashMap<_, _> = terrain_chunks.iter_loaded().collect();
+    report.tiles = find_chunk_seams(&chunks);
+    report.visible = true;
+
+    if report.tiles.is_empty() {
+        info!("No chunk seams across {} loaded chunks", chunks.len());
+    } else {
+        warn!("Found {} tiles on chunk seams across {} loaded chunks", report.tiles.len(), chunks.len());
+    }
+}
+
+fn draw_chunk_seams(mut gizmos: Gizmos, report: Res<ChunkSeamReport>) {
+    if !report.visible {
+        return;
+    }
+
+    for tile in &report.tiles {
+        // Tiles are centered on their grid position
+        let center = tile.as_vec2() * TILE_SIZE;
+        gizmos.rect_2d(Isometry2d::from_translation(center), Vec2::splat(TILE_SIZE), Color::srgb(1.0, 0.0, 0.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{mapgen, scenes::dungeon::terrain::generate_chunk_tiles, tiles::TileType};
+
+    #[test]
+    fn test_straight_cut_is_a_seam() {
+        let floor = [[TileType::Floor; CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
+        let wall = [[TileType::Wall; CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
+        let chunks = HashMap::from([(IVec2::ZERO, &floor), (IVec2::X, &wall), (IVec2::new(0, 1), &floor)]);
+
+        let seams = find_chunk_seams(&chunks);
+        assert_eq!(seams.len(), CHUNK_SIZE as usize * 2);
+        assert!(seams.contains(&IVec2::new(CHUNK_SIZE as i32, 0)));
+    }
+
+    #[test]
+    fn test_generated_region_has_no_seams() {
+        let macro_map = mapgen::roomy(160, 42);
+        // 10x10 chunks around the spawn room in the middle of the 80x80 macro map
+        let region: HashMap<ChunkCoord, ChunkTiles> = (5..15)
+            .flat_map(|x| (5..15).map(move |y| IVec2::new(x, y)))
+            .map(|coord| (coord, generate_chunk_tiles(coord, &macro_map)))
+            .collect();
+
+        let tiles = region.values().flatten().flatten();
+        assert!(tiles.clone().any(|tile| *tile == TileType::Floor));
+        assert!(tiles.clone().any(|tile| *tile == TileType::Wall));
+
+        let chunks = region.iter().map(|(coord, tiles)| (*coord, tiles)).collect();
+        assert!(find_chunk_seams(&chunks).is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+//! Draw order for the top-down view
+//!
+//! Every 2D entity is spawned at the z of its `DepthLayer`. Within a layer,
+//! entities with `YSort` are drawn by their world y: the one lower on screen is
+//! closer to the camera, so the player walks behind a chest when above it and
+//! in front of it when below. The bias is added to the `GlobalTransform` after
+//! propagation, so gameplay, physics and interpolation only ever see the layer z.
+use bevy::prelude::*;
+
+/// Total z range the y bias may use, centered on the layer z
+const Y_SORT_BAND: f32 = 0.08;
+/// World y mapped to the edges of the band; anything further out is clamped
+/// Roughly the extent of a loaded world, so nearby actors stay apart in depth precision
+const Y_SORT_RANGE: f32 = 2_000.0;
+
+/// Fixed draw depths, back to front
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthLayer {
+    /// Tilemaps and arena floors
+    Terrain,
+    /// Drawn over the floor: arena walls, hazard zones, encounter markings
+    TerrainDetail,
+    /// Player, enemies, props, portals and items, y-sorted among each other
+    Actors,
+    Projectiles,
+    /// World-space labels above everything on the ground
+    Labels,
+    /// Enemy health bars and status icons
+    HealthBars,
+    /// Heal and damage numbers, over the bars they rise from
+    FloatingText,
+    /// Fog of war covers the whole scene
+    Fog,
+}
+
+impl DepthLayer {
+    pub fn z(&self) -> f32 {
+        match self {
+            Self::Terrain => -1.0,
+            Self::TerrainDetail => -0.5,
+            Self::Actors => 0.0,
+            Self::Projectiles => 0.5,
+            Self::Labels => 1.0,
+            Self::HealthBars => 5.0,
+            Self::FloatingText => 6.0,
+            Self::Fog => 10.0,
+        }
+    }
+}
+
+/// Sort this entity against others on its layer by world y
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct YSort {
+    /// Offset from the entity's center to the point it stands on
+    pub offset: f32,
+}
+
+impl YSort {
+    pub fn with_offset(offset: f32) -> Self {
+        Self { offset }
+    }
+}
+
+/// Depth bias for world y; lower on screen gives a larger z
+pub fn y_sort_bias(y: f32) -> f32 {
+    (-y / Y_SORT_RANGE).clamp(-1.0, 1.0) * Y_SORT_BAND / 2.0
+}
+
+/// Plugin that applies y-sorting after transforms propagate
+pub struct DepthPlugin;
+
+impl Plugin for DepthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, apply_y_sort.after(TransformSystem::TransformPropagate));
+    }
+}
+
+fn apply_y_sort(mut query: Query<(&Transform, &YSort, &mut GlobalTransform)>) {
+    for (transform, sort, mut global) in query.iter_mut() {
+        let translation = global.translation();
+        let z = transform.translation.z + y_sort_bias(translation.y + sort.offset);
+        // Static entities keep their sorted transform, don't mark them changed every frame
+        if translation.z == z {
+            continue;
+        }
+
+        let mut affine = global.affine();
+        affine.translation.z = z;
+        *global = GlobalTransform::from(affine);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_lower_entities_draw_in_front() {
+        assert!(y_sort_bias(-10.0) > y_sort_bias(10.0));
+        assert_eq!(y_sort_bias(0.0), 0.0);
+        assert_eq!(y_sort_bias(-1.0e9), Y_SORT_BAND / 2.0);
+        assert!(DepthLayer::Actors.z() - Y_SORT_BAND / 2.0 > DepthLayer::TerrainDetail.z() + Y_SORT_BAND / 2.0);
+    }
+
+    #[test]
+    fn test_y_sort_only_touches_global_transform() {
+        let mut world = World::new();
+        let spawn = |world: &mut World, y: f32| {
+            let transform = Transform::from_xyz(0.0, y, DepthLayer::Actors.z());
+            world.spawn((transform, GlobalTransform::from(transform), YSort::default())).id()
+        };
+        let above = spawn(&mut world, 50.0);
+        let below = spawn(&mut world, -50.0);
+
+        world.run_system_once(apply_y_sort).unwrap();
+
+        let z = |world: &World, entity| world.get::<GlobalTransform>(entity).unwrap().translation().z;
+        assert!(z(&world, below) > z(&world, above));
+        assert_eq!(world.get::<Transform>(below).unwrap().translation.z, DepthLayer::Actors.z());
+    }
+
+    #[test]
+    fn test_actors_one_unit_apart_get_distinct_z() {
+        let mut world = World::new();
+        let spawn = |world: &mut World, y: f32| {
+            let transform = Transform::from_xyz(0.0, y, DepthLayer::Actors.z());
+            world.spawn((transform, GlobalTransform::from(transform), YSort::default())).id()
+        };
+        let pairs = [(0.0, 1.0), (750.0, 751.0), (-1_499.0, -1_500.0)];
+        let entities: Vec<_> = pairs.iter().map(|&(a, b)| (spawn(&mut world, a), spawn(&mut world, b))).collect();
+
+        world.run_system_once(apply_y_sort).unwrap();
+
+        let z = |world: &World, entity| world.get::<GlobalTransform>(entity).unwrap().translation().z;
+        for (a, b) in entities {
+            assert_ne!(z(&world, a), z(&world, b));
+        }
+    }
+}
@@ -5,7 +5,11 @@ use crate::{
     constants::*,
     sounds::*,
     line_of_sight::*,
+    interpolation::InterpolatedTransform,
+    steering::Flocking,
+    physics_layers,
     player::Player,
+    combat::{AttackDefinition, AttackShape},
 };
 
 /// Configuration for enemy archetype properties
@@ -17,9 +21,16 @@ pub struct ArchetypeConfig {
     pub color: Color,
     pub preferred_distance: f32,
     pub fire_rate: f32,
+    /// Ranged attack, telegraphed on the ground before it fires; melee enemies only touch
+    pub attack: Option<AttackDefinition>,
 }
 
 impl ArchetypeConfig {
+    /// Whether the enemy attacks from `distance` away, the range gate its laser and telegraph share
+    pub fn in_attack_range(&self, distance: f32) -> bool {
+        self.attack.and_then(|attack| attack.max_range).is_none_or(|range| distance <= range)
+    }
+
     /// Get configuration for a specific enemy archetype
     pub fn for_archetype(archetype: EnemyArchetype) -> Self {
         match archetype {
@@ -30,6 +41,7 @@ impl ArchetypeConfig {
                 color: Color::srgb(1.0, 0.2, 0.2), // Bright red
                 preferred_distance: 0.0,
                 fire_rate: 1.0,
+                attack: None,
             },
             EnemyArchetype::BigMelee => Self {
                 health: BIG_MELEE_HEALTH,
@@ -38,6 +50,7 @@ impl ArchetypeConfig {
                 color: Color::srgb(0.6, 0.1, 0.1), // Dark red
                 preferred_distance: 0.0,
                 fire_rate: 1.0,
+                attack: None,
             },
             EnemyArchetype::Shotgunner => Self {
                 health: SHOTGUNNER_HEALTH,
@@ -46,6 +59,12 @@ impl ArchetypeConfig {
                 color: Color::srgb(1.0, 0.5, 0.0), // Orange
                 preferred_distance: SHOTGUNNER_RANGE,
                 fire_rate: SHOTGUNNER_FIRE_RATE,
+                // The outer pellets leave at 0.4 radians either side
+                attack: Some(AttackDefinition {
+                    shape: AttackShape::Cone { range: SHOTGUNNER_RANGE * 2.0, half_angle: 0.45 },
+                    windup: SHOTGUNNER_WINDUP,
+                    max_range: None,
+                }),
             },
             EnemyArchetype::Sniper => Self {
                 health: SNIPER_HEALTH,
@@ -54,6 +73,12 @@ impl ArchetypeConfig {
                 color: Color::srgb(0.0, 0.8, 0.2), // Green
                 preferred_distance: SNIPER_RANGE,
                 fire_rate: SNIPER_FIRE_RATE,
+                attack: Some(AttackDefinition {
+                    shape: AttackShape::Line { range: SNIPER_RANGE, width: PROJECTILE_SIZE * 4.0 },
+                    windup: SNIPER_WINDUP,
+                    // Holds fire until the player is in range
+                    max_range: Some(SNIPER_RANGE),
+                }),
             },
             EnemyArchetype::MachineGunner => Self {
                 health: MACHINE_GUNNER_HEALTH,
@@ -62,6 +87,12 @@ impl ArchetypeConfig {
                 color: Color::srgb(0.8, 0.0, 0.8), // Purple
                 preferred_distance: MACHINE_GUNNER_RANGE,
                 fire_rate: MACHINE_GUNNER_FIRE_RATE,
+                // Fires too often to wind up, so its spray stays telegraphed while it can see you
+                attack: Some(AttackDefinition {
+                    shape: AttackShape::Cone { range: MACHINE_GUNNER_RANGE * 1.5, half_angle: 0.1 },
+                    windup: MACHINE_GUNNER_FIRE_RATE,
+                    max_range: None,
+                }),
             },
         }
     }
@@ -70,6 +101,7 @@ impl ArchetypeConfig {
 /// Behavior context for AI decision making
 #[derive(Debug, Clone)]
 pub struct BehaviorContext {
+    pub enemy: Entity,
     pub enemy_pos: Vec2,
     pub distance_to_player: f32,
     pub direction_to_player: Vec2,
@@ -179,9 +211,9 @@ impl EnemyArchetype {
                 // Spawn shotgun spread outside the enemy to prevent immediate collision
                 let spawn_offset = context.direction_to_player * (config.radius + PROJECTILE_SIZE * 2.0 + 5.0);
                 let bullet_spawn_pos = context.enemy_pos + spawn_offset;
-                spawn_shotgun_spread(commands, meshes, materials, bullet_spawn_pos, context.direction_to_player);
+                spawn_shotgun_spread(commands, meshes, materials, context.enemy, bullet_spawn_pos, context.direction_to_player);
                 play_sound(commands, game_sounds.gun_03.clone(), 0.4);
-                ai.timer.reset();
+                ai.fire();
             }
         } else if let Some(last_pos) = context.last_known_player_pos {
             // Move towards last known position but don't shoot
@@ -220,7 +252,7 @@ impl EnemyArchetype {
 
             // Laser sight behavior - only show if we can see the player
             if let Some(laser) = laser_sight {
-                let in_range = context.distance_to_player <= config.preferred_distance;
+                let in_range = config.in_attack_range(context.distance_to_player);
                 let ready_to_shoot = ai.timer.remaining().as_secs_f32() < 1.0; // Show laser 1 second before shooting
 
                 laser.is_active = in_range && ready_to_shoot;
@@ -231,14 +263,14 @@ impl EnemyArchetype {
             }
 
             // Shooting behavior - only shoot if we can see the player
-            if ai.timer.finished() && context.distance_to_player <= config.preferred_distance {
+            if ai.timer.finished() && config.in_attack_range(context.distance_to_player) {
                 let bullet_velocity = context.direction_to_player * SNIPER_BULLET_SPEED;
                 // Spawn bullet outside the enemy to prevent immediate collision
                 let spawn_offset = context.direction_to_player * (config.radius + PROJECTILE_SIZE * 2.0 + 5.0);
                 let bullet_spawn_pos = context.enemy_pos + spawn_offset;
-                spawn_enemy_bullet(commands, meshes, materials, bullet_spawn_pos, bullet_velocity, Color::srgb(0.0, 1.0, 0.5));
+                spawn_enemy_bullet(commands, meshes, materials, bullet_spawn_pos, bullet_velocity, Color::srgb(0.0, 1.0, 0.5), enemy_projectile(context.enemy, ProjectileKind::Bullet));
                 play_sound(commands, game_sounds.gun_02.clone(), 0.6);
-                ai.timer.reset();
+                ai.fire();
             }
         } else {
             // No line of sight - disable laser and search behavior
@@ -296,9 +328,9 @@ impl EnemyArchetype {
                 // Spawn bullet outside the enemy to prevent immediate collision
                 let spawn_offset = jittered_direction * (config.radius + PROJECTILE_SIZE * 2.0 + 5.0);
                 let bullet_spawn_pos = context.enemy_pos + spawn_offset;
-                spawn_enemy_bullet(commands, meshes, materials, bullet_spawn_pos, bullet_velocity, Color::srgb(0.8, 0.2, 0.8));
+                spawn_enemy_bullet(commands, meshes, materials, bullet_spawn_pos, bullet_velocity, Color::srgb(0.8, 0.2, 0.8), enemy_projectile(context.enemy, ProjectileKind::Bullet));
                 play_sound(commands, game_sounds.gun_01.clone(), 0.3);
-                ai.timer.reset();
+                ai.fire();
             }
         } else if let Some(last_pos) = context.last_known_player_pos {
             // Move towards last known position but don't shoot
@@ -311,12 +343,63 @@ impl EnemyArchetype {
     }
 }
 
+/// Every enemy archetype, in spawn palette order
+pub const ENEMY_ARCHETYPES: [EnemyArchetype; 5] = [
+    EnemyArchetype::SmallMelee,
+    EnemyArchetype::BigMelee,
+    EnemyArchetype::Shotgunner,
+    EnemyArchetype::Sniper,
+    EnemyArchetype::MachineGunner,
+];
+
+/// Spawns a fully configured enemy of the given archetype
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    archetype: EnemyArchetype,
+    position: Vec2,
+) -> Entity {
+    let config = ArchetypeConfig::for_archetype(archetype);
+
+    let mut enemy = commands.spawn((
+        Mesh2d(meshes.add(Circle::new(config.radius))),
+        MeshMaterial2d(materials.add(config.color)),
+        Transform::from_translation(position.extend(crate::depth::DepthLayer::Actors.z())),
+        Enemy { archetype },
+        Team::Enemy,
+        crate::combat::CombatState::new(config.health),
+        AIBehavior::new(config.fire_rate),
+        LineOfSight::new(),
+        RigidBody::Dynamic,
+        Collider::ball(config.radius),
+        physics_layers::enemy(),
+        Velocity::zero(),
+        LockedAxes::ROTATION_LOCKED,
+        ActiveEvents::COLLISION_EVENTS,
+        (InterpolatedTransform::default(), crate::depth::YSort::with_offset(-config.radius)),
+    ));
+
+    if matches!(archetype, EnemyArchetype::SmallMelee | EnemyArchetype::BigMelee) {
+        enemy.insert(Flocking::for_radius(config.radius));
+    }
 
+    if archetype == EnemyArchetype::Sniper {
+        enemy.insert(LaserSight {
+            is_active: false,
+            target_pos: position,
+            beam_end: position,
+            beam_hit: None,
+        });
+    }
 
+    enemy.id()
+}
 
 /// AI system that controls enemy behavior based on their archetype
 pub fn enemy_ai(
     mut enemy_query: Query<(
+        Entity,
         &Transform,
         &mut Velocity,
         &Enemy,
@@ -330,12 +413,15 @@ pub fn enemy_ai(
     mut materials: ResMut<Assets<ColorMaterial>>,
     game_sounds: Res<GameSounds>,
     time: Res<Time>,
-    rapier_context: ReadRapierContext,
+    sight_occluders: Res<SightOccluders>,
+    run_modifiers: Option<Res<crate::world::RunModifiers>>,
 ) {
+    let speed_multiplier = run_modifiers.map_or(1.0, |modifiers| modifiers.enemy_speed);
+
     if let Ok(player_transform) = player_query.single() {
         let player_pos = player_transform.translation.truncate();
 
-        for (enemy_transform, mut enemy_velocity, enemy, mut ai_behavior, mut laser_sight, mut los) in enemy_query.iter_mut() {
+        for (entity, enemy_transform, mut enemy_velocity, enemy, mut ai_behavior, mut laser_sight, mut los) in enemy_query.iter_mut() {
             let enemy_pos = enemy_transform.translation.truncate();
             let distance_to_player = enemy_pos.distance(player_pos);
             let direction_to_player = (player_pos - enemy_pos).normalize_or_zero();
@@ -343,14 +429,7 @@ pub fn enemy_ai(
             // Update line of sight check
             los.los_check_timer.tick(time.delta());
             if los.los_check_timer.finished() {
-                let config = ArchetypeConfig::for_archetype(enemy.archetype);
-                let has_los = has_line_of_sight(
-                    enemy_pos,
-                    player_pos,
-                    config.radius,
-                    PLAYER_RADIUS,
-                    &rapier_context,
-                );
+                let has_los = has_line_of_sight(enemy_pos, player_pos, &sight_occluders);
 
                 los.has_los_to_player = has_los;
 
@@ -367,6 +446,7 @@ pub fn enemy_ai(
 
             // Create behavior context
             let context = BehaviorContext {
+                enemy: entity,
                 enemy_pos,
                 distance_to_player,
                 direction_to_player,
@@ -376,6 +456,7 @@ pub fn enemy_ai(
 
             // Update AI timer
             ai_behavior.timer.tick(time.delta());
+            ai_behavior.since_fired += time.delta_secs();
 
             // Execute archetype-specific behavior
             enemy.archetype.execute_behavior(
@@ -388,6 +469,7 @@ pub fn enemy_ai(
                 laser_sight.as_deref_mut(),
                 &game_sounds,
             );
+            enemy_velocity.linvel *= speed_multiplier;
         }
     }
 }
@@ -397,6 +479,7 @@ fn spawn_shotgun_spread(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
+    source: Entity,
     spawn_pos: Vec2,
     base_direction: Vec2,
 ) {
@@ -419,11 +502,21 @@ fn spawn_shotgun_spread(
         let bullet_velocity = direction * SHOTGUN_BULLET_SPEED;
 
         println!("Pellet {}: angle={:.2}, direction={:?}", i, spread_angle, direction);
-        spawn_enemy_bullet(commands, meshes, materials, spawn_pos, bullet_velocity, Color::srgb(1.0, 0.7, 0.0));
+        spawn_enemy_bullet(commands, meshes, materials, spawn_pos, bullet_velocity, Color::srgb(1.0, 0.7, 0.0), enemy_projectile(source, ProjectileKind::Pellet));
+    }
+}
+
+/// Projectile fired by the enemy `source`; `kind` picks its impact effects
+fn enemy_projectile(source: Entity, kind: ProjectileKind) -> Projectile {
+    Projectile {
+        lifetime: Timer::from_seconds(ENEMY_BULLET_LIFETIME, TimerMode::Once),
+        team: Team::Enemy,
+        kind,
+        source,
     }
 }
 
-/// Spawns a generic enemy bullet with specified properties
+/// Spawns an enemy bullet with specified properties
 fn spawn_enemy_bullet(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -431,38 +524,49 @@ fn spawn_enemy_bullet(
     spawn_pos: Vec2,
     velocity: Vec2,
     color: Color,
+    projectile: Projectile,
 ) {
     commands.spawn((
         Mesh2d(meshes.add(Circle::new(PROJECTILE_SIZE * 0.8))), // Slightly smaller than player bullets
         MeshMaterial2d(materials.add(color)),
-        Transform::from_translation(spawn_pos.extend(0.1)),
-        Projectile {
-            lifetime: Timer::from_seconds(ENEMY_BULLET_LIFETIME, TimerMode::Once),
-            team: Team::Enemy,
-        },
+        Transform::from_translation(spawn_pos.extend(crate::depth::DepthLayer::Projectiles.z())),
+        projectile,
         RigidBody::Dynamic,
         Collider::ball(PROJECTILE_SIZE * 0.8),
+        physics_layers::projectile(Team::Enemy),
         Sensor, // Make projectile a sensor so it doesn't physically interact with other projectiles
         Velocity::linear(velocity),
         ActiveEvents::COLLISION_EVENTS,
+        InterpolatedTransform::default(),
     ));
 }
 
-/// System to render laser sights for snipers
+/// System to clip and render laser sights for snipers
+/// The beam stops at the first wall or the player, like the sniper's bullets would, and fades with length
 pub fn laser_sight_system(
     mut gizmos: Gizmos,
-    laser_query: Query<(&Transform, &LaserSight), With<Enemy>>,
+    mut laser_query: Query<(Entity, &Transform, &mut LaserSight), With<Enemy>>,
+    rapier_context: ReadRapierContext,
 ) {
-    for (transform, laser) in laser_query.iter() {
-        if laser.is_active {
-            let start_pos = transform.translation.truncate();
-            let end_pos = laser.target_pos;
-
-            // Draw red laser line
-            gizmos.line_2d(start_pos, end_pos, Color::srgb(1.0, 0.0, 0.0));
-
-            // Draw small targeting dot at the end
-            gizmos.circle_2d(end_pos, 3.0, Color::srgb(1.0, 0.2, 0.0));
+    for (entity, transform, mut laser) in laser_query.iter_mut() {
+        if !laser.is_active {
+            continue;
         }
+
+        let start_pos = transform.translation.truncate();
+        let filter = QueryFilter::default()
+            .groups(physics_layers::projectile(Team::Enemy))
+            .exclude_rigid_body(entity);
+        let hit = first_hit(start_pos, laser.target_pos, SNIPER_RADIUS, filter, &rapier_context);
+        laser.beam_hit = hit.map(|(hit_entity, _)| hit_entity);
+        laser.beam_end = hit.map_or(laser.target_pos, |(_, point)| point);
+
+        // Draw red laser line, fainter toward the far end the longer it is
+        let fade = (start_pos.distance(laser.beam_end) / LASER_FADE_RANGE).min(1.0);
+        let end_alpha = 1.0 - fade * (1.0 - LASER_MIN_ALPHA);
+        gizmos.line_gradient_2d(start_pos, laser.beam_end, Color::srgb(1.0, 0.0, 0.0), Color::srgba(1.0, 0.0, 0.0, end_alpha));
+
+        // Draw small targeting dot where the beam lands
+        gizmos.circle_2d(laser.beam_end, 3.0, Color::srgba(1.0, 0.2, 0.0, end_alpha));
     }
 }
@@ -1,27 +1,135 @@
+//! Gameplay events and the single place they are registered
+//!
+//! Events registered with `add_game_event` are also mirrored into the
+//! `GameEventLog`, so anything that wants to observe gameplay (logging,
+//! telemetry, a replay recorder) reads one stream instead of hooking every
+//! event type separately.
 use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// Number of mirrored events kept before the oldest are dropped
+pub const GAME_EVENT_LOG_CAPACITY: usize = 512;
+
+/// Plugin registering the core gameplay events
+pub struct GameEventsPlugin;
+
+impl Plugin for GameEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_game_event::<ProjectileImpactEvent>()
+            .add_game_event::<DamageEvent>()
+            .add_game_event::<HitFlashEvent>()
+            .add_game_event::<GrenadeExplosionEvent>()
+            .add_game_event::<PortalActivationEvent>();
+    }
+}
+
+/// Registers an event and mirrors it into the `GameEventLog`
+pub trait GameEventAppExt {
+    /// Call once per event type; plugins use this instead of `add_event` for gameplay events
+    fn add_game_event<E: Event + Debug>(&mut self) -> &mut Self;
+}
+
+impl GameEventAppExt for App {
+    fn add_game_event<E: Event + Debug>(&mut self) -> &mut Self {
+        self.add_event::<E>()
+            .init_resource::<GameEventLog>()
+            // Last, so events sent anywhere this frame have been written
+            .add_systems(Last, mirror_game_events::<E>)
+    }
+}
+
+/// A gameplay event as seen by the log
+#[derive(Debug, Clone)]
+pub struct GameEventRecord {
+    /// Seconds since startup when the event was mirrored
+    pub timestamp: f32,
+    /// Short type name of the event, e.g. `DamageEvent`
+    pub kind: &'static str,
+    pub details: String,
+}
+
+/// Ring buffer of recent gameplay events, in the order they were mirrored
+#[derive(Resource)]
+pub struct GameEventLog {
+    entries: VecDeque<GameEventRecord>,
+    capacity: usize,
+    /// Total number of events ever recorded, so readers can tell what they missed
+    pub total_recorded: u64,
+}
+
+impl Default for GameEventLog {
+    fn default() -> Self {
+        Self::with_capacity(GAME_EVENT_LOG_CAPACITY)
+    }
+}
+
+impl GameEventLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            total_recorded: 0,
+        }
+    }
+
+    pub fn push(&mut self, record: GameEventRecord) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(record);
+        self.total_recorded += 1;
+    }
+
+    /// Oldest first
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &GameEventRecord> {
+        self.entries.iter()
+    }
+}
+
+fn mirror_game_events<E: Event + Debug>(
+    mut events: EventReader<E>,
+    time: Res<Time>,
+    mut log: ResMut<GameEventLog>,
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    let kind = std::any::type_name::<E>().rsplit("::").next().unwrap_or_default();
+    for event in events.read() {
+        trace!("{}: {:?}", kind, event);
+        log.push(GameEventRecord {
+            timestamp: time.elapsed_secs(),
+            kind,
+            details: format!("{:?}", event),
+        });
+    }
+}
 
 /// Event fired when a projectile impacts something
-#[derive(Event)]
+#[derive(Event, Debug)]
 pub struct ProjectileImpactEvent {
     pub projectile: Entity,
     pub target: Entity,
 }
 
 /// Event fired when an entity should take damage
-#[derive(Event)]
+#[derive(Event, Debug)]
 pub struct DamageEvent {
     pub target: Entity,
     pub damage: f32,
 }
 
 /// Event fired when an enemy should flash on hit
-#[derive(Event)]
+#[derive(Event, Debug)]
 pub struct HitFlashEvent {
     pub target: Entity,
 }
 
 /// Event fired when a grenade explodes
-#[derive(Event)]
+#[derive(Event, Debug)]
 pub struct GrenadeExplosionEvent {
     pub position: Vec2,
     pub damage: f32,
@@ -30,9 +138,40 @@ pub struct GrenadeExplosionEvent {
 }
 
 /// Event fired when a portal is activated to transition to a scene
-#[derive(Event)]
+#[derive(Event, Debug)]
 pub struct PortalActivationEvent {
     pub portal_id: crate::world::scenes::cathedral::PortalId,
     pub depth: u32,
-    pub modifiers: Vec<String>,
+    pub modifiers: Vec<crate::world::ModifierId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_events_are_mirrored_once() {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(GameEventsPlugin);
+
+        app.world_mut().send_event(HitFlashEvent { target: Entity::PLACEHOLDER });
+        app.update();
+        app.update();
+
+        let log = app.world().resource::<GameEventLog>();
+        assert_eq!(log.total_recorded, 1);
+        assert_eq!(log.entries().next().unwrap().kind, "HitFlashEvent");
+    }
+
+    #[test]
+    fn test_log_drops_oldest_past_capacity() {
+        let mut log = GameEventLog::with_capacity(2);
+        for kind in ["a", "b", "c"] {
+            log.push(GameEventRecord { timestamp: 0.0, kind, details: String::new() });
+        }
+
+        let kinds: Vec<_> = log.entries().map(|record| record.kind).collect();
+        assert_eq!(kinds, ["b", "c"]);
+        assert_eq!(log.total_recorded, 3);
+    }
 }
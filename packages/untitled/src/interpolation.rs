@@ -0,0 +1,147 @@
+//! Fixed-timestep transform interpolation
+//!
+//! Physics and gameplay advance in `FixedUpdate`, which runs slower than the
+//! render rate. Entities with `InterpolatedTransform` keep the transforms of
+//! the last two fixed ticks and are drawn between them, then snapped back to
+//! the authoritative transform before the next tick runs.
+use bevy::prelude::*;
+use bevy_rapier2d::plugin::PhysicsSet;
+
+/// Previous and current fixed-tick transforms of an entity
+///
+/// Only translation and rotation are interpolated. Writing the `Transform`
+/// outside the fixed schedule is treated as a teleport and is not smoothed.
+#[derive(Component, Debug, Clone, Default)]
+pub struct InterpolatedTransform {
+    previous: Transform,
+    current: Transform,
+    /// Transform last written by a tick or the interpolation, `None` until the first tick
+    rendered: Option<Transform>,
+}
+
+impl InterpolatedTransform {
+    /// Blend of the last two ticks, `t` in `[0, 1]`
+    pub fn lerp(&self, t: f32) -> (Vec3, Quat) {
+        (
+            self.previous.translation.lerp(self.current.translation, t),
+            self.previous.rotation.slerp(self.current.rotation, t),
+        )
+    }
+}
+
+/// Plugin that smooths `InterpolatedTransform` entities between fixed ticks
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedFirst, restore_fixed_transforms)
+            .add_systems(
+                FixedPostUpdate,
+                // Rapier steps in the same schedule; record the pose it wrote back
+                record_fixed_transforms.after(PhysicsSet::Writeback),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                interpolate_transforms.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+            );
+    }
+}
+
+fn same_pose(a: &Transform, b: &Transform) -> bool {
+    a.translation == b.translation && a.rotation == b.rotation
+}
+
+/// Put back the last fixed-tick transform so gameplay and physics never see an interpolated one
+fn restore_fixed_transforms(
+    mut query: Query<(&mut Transform, &mut GlobalTransform, &mut InterpolatedTransform)>,
+) {
+    for (mut transform, mut global, mut interpolated) in query.iter_mut() {
+        let untouched = interpolated.rendered.is_some_and(|rendered| same_pose(&rendered, &transform));
+        if !untouched {
+            // New entity or moved outside the fixed schedule: start from where it is now
+            interpolated.previous = *transform;
+            interpolated.current = *transform;
+            interpolated.rendered = Some(*transform);
+            continue;
+        }
+
+        transform.translation = interpolated.current.translation;
+        transform.rotation = interpolated.current.rotation;
+        // Rapier compares GlobalTransform against the body to detect user teleports
+        *global = GlobalTransform::from(*transform);
+    }
+}
+
+/// Shift the result of this tick into `current`
+fn record_fixed_transforms(mut query: Query<(&Transform, &mut InterpolatedTransform)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = interpolated.current;
+        interpolated.current = *transform;
+        // Another tick may run before the next render
+        interpolated.rendered = Some(*transform);
+    }
+}
+
+/// Draw each entity between its last two ticks by how far into the next tick we are
+fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut InterpolatedTransform)>,
+) {
+    let t = fixed_time.overstep_fraction();
+
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        if interpolated.rendered.is_none() {
+            continue;
+        }
+
+        let (translation, rotation) = interpolated.lerp(t);
+        transform.translation = translation;
+        transform.rotation = rotation;
+        interpolated.rendered = Some(*transform);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_lerp_blends_last_two_ticks() {
+        let interpolated = InterpolatedTransform {
+            previous: Transform::from_xyz(0.0, 0.0, 0.0),
+            current: Transform::from_xyz(10.0, -4.0, 0.0),
+            rendered: None,
+        };
+
+        assert_eq!(interpolated.lerp(0.0).0, Vec3::ZERO);
+        assert_eq!(interpolated.lerp(0.5).0, Vec3::new(5.0, -2.0, 0.0));
+        assert_eq!(interpolated.lerp(1.0).0, Vec3::new(10.0, -4.0, 0.0));
+    }
+
+    #[test]
+    fn test_external_move_is_treated_as_teleport() {
+        let mut world = World::new();
+        let entity = world.spawn((
+            Transform::from_xyz(1.0, 1.0, 0.0),
+            GlobalTransform::default(),
+            InterpolatedTransform {
+                previous: Transform::from_xyz(0.0, 0.0, 0.0),
+                current: Transform::from_xyz(2.0, 2.0, 0.0),
+                rendered: Some(Transform::from_xyz(1.0, 1.0, 0.0)),
+            },
+        )).id();
+
+        // Untouched since the last render: restored to the fixed-tick transform
+        world.run_system_once(restore_fixed_transforms).unwrap();
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation, Vec3::new(2.0, 2.0, 0.0));
+
+        // Moved by something else: kept where it was put
+        world.get_mut::<Transform>(entity).unwrap().translation = Vec3::new(50.0, 0.0, 0.0);
+        world.run_system_once(restore_fixed_transforms).unwrap();
+        let interpolated = world.get::<InterpolatedTransform>(entity).unwrap();
+        assert_eq!(interpolated.previous.translation, Vec3::new(50.0, 0.0, 0.0));
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation, Vec3::new(50.0, 0.0, 0.0));
+    }
+}
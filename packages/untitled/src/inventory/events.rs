@@ -1,8 +1,8 @@
 use bevy::prelude::*;
-use crate::inventory::{InstanceId, GridPosition};
+use crate::inventory::{InstanceId, GridPosition, ItemInstance, ItemRotation};
 
 /// Events for inventory interactions
-#[derive(Event)]
+#[derive(Event, Debug)]
 pub enum InventoryEvent {
     /// An item was selected in the inventory
     ItemSelected {
@@ -17,11 +17,19 @@ pub enum InventoryEvent {
         item_id: InstanceId,
         target_position: GridPosition,
     },
-    /// An item was successfully moved
+    /// An item was successfully moved or rotated
     ItemMoved {
         item_id: InstanceId,
         from_position: GridPosition,
         to_position: GridPosition,
+        from_rotation: ItemRotation,
+    },
+    /// A dragged item was merged into another stack
+    ItemStacked {
+        /// The dragged item as it was before the drag
+        item: ItemInstance,
+        into: InstanceId,
+        amount: u32,
     },
     /// An item was used/consumed
     ItemUsed {
@@ -32,10 +40,3 @@ pub enum InventoryEvent {
     /// Inventory panel was closed
     InventoryClosed,
 }
-
-/// Events for UI state changes
-#[derive(Event)]
-pub struct TooltipEvent {
-    pub item_id: Option<InstanceId>,
-    pub show: bool,
-}
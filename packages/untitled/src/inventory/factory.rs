@@ -31,6 +31,13 @@ impl ItemFactory {
         }
     }
 
+    /// Factory whose drops and rolls repeat for the same seed, used by seeded runs
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
     /// Create a new item instance from a definition ID
     pub fn create_item(
         &mut self,
@@ -44,8 +51,10 @@ impl ItemFactory {
 
         let mut instance = ItemInstance::new(instance_id, item_id);
 
-        // Roll all numeric properties
-        for (prop_name, prop_range) in &definition.properties.numeric {
+        // Roll all numeric properties, in name order so seeded factories repeat exactly
+        let mut numeric: Vec<_> = definition.properties.numeric.iter().collect();
+        numeric.sort_by(|a, b| a.0.cmp(b.0));
+        for (prop_name, prop_range) in numeric {
             let rolled_value = prop_range.roll(&mut self.rng);
             instance.properties.insert(prop_name.clone(), rolled_value);
         }
@@ -151,6 +160,16 @@ impl ItemFactory {
         let chosen_def = items_in_category.choose(&mut self.rng)?;
         self.create_item(chosen_def.id, registry)
     }
+
+    /// Generate a random item from one of several categories, picked with the factory's RNG
+    pub fn create_random_from_categories(
+        &mut self,
+        categories: &[&str],
+        registry: &ItemRegistry,
+    ) -> Option<ItemInstance> {
+        let category = categories.choose(&mut self.rng)?;
+        self.create_random_from_category(category, registry)
+    }
 }
 
 /// Errors that can occur during item creation
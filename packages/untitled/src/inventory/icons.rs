@@ -0,0 +1,339 @@
+//! Inventory icons drawn from item definitions
+//!
+//! Items without a hand-drawn icon (an empty `icon_path`) get one generated
+//! from their definition: the category picks the silhouette, the name picks
+//! the palette and each of the item's properties and flags adds a small glyph
+//! in a corner. Generated icons are written to `ICON_CACHE_DIR` and read back
+//! on later runs; the file name includes a fingerprint of everything the icon
+//! is drawn from, so changing a definition draws a new one.
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::registry::{ItemDefinition, ItemId, ItemRegistry};
+
+/// Where generated icons are kept between runs
+const ICON_CACHE_DIR: &str = "icon_cache";
+/// Width and height of an icon, in pixels
+pub const ICON_SIZE: u32 = 32;
+/// Bump to redraw every cached icon after changing how they're drawn
+const ICON_VERSION: u64 = 1;
+/// Width and height of a property glyph, in pixels
+const GLYPH_SIZE: u32 = 9;
+
+/// Icon handles by item, built whenever the registry changes
+#[derive(Resource, Default)]
+pub struct ItemIcons {
+    handles: HashMap<ItemId, Handle<Image>>,
+}
+
+impl ItemIcons {
+    pub fn get(&self, id: ItemId) -> Option<&Handle<Image>> {
+        self.handles.get(&id)
+    }
+}
+
+/// Give every registered item without an icon yet either its own or a generated one
+pub fn build_item_icons(
+    registry: Res<ItemRegistry>,
+    mut icons: ResMut<ItemIcons>,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    for definition in registry.items.values() {
+        if icons.handles.contains_key(&definition.id) {
+            continue;
+        }
+
+        let handle = if definition.icon_path.is_empty() {
+            images.add(to_bevy_image(load_or_generate_icon(definition, Path::new(ICON_CACHE_DIR))))
+        } else {
+            asset_server.load(definition.icon_path.clone())
+        };
+        icons.handles.insert(definition.id, handle);
+    }
+}
+
+/// The cached icon for `definition`, drawing and caching it if there isn't one
+fn load_or_generate_icon(definition: &ItemDefinition, cache_dir: &Path) -> RgbaImage {
+    let path = cache_path(definition, cache_dir);
+    if let Ok(icon) = image::open(&path) {
+        return icon.to_rgba8();
+    }
+
+    let icon = generate_icon(definition);
+    let saved = std::fs::create_dir_all(cache_dir)
+        .map_err(image::ImageError::IoError)
+        .and_then(|_| icon.save(&path));
+    if let Err(e) = saved {
+        // Still usable, it'll just be drawn again next time
+        warn!("Failed to cache icon for {} at {}: {}", definition.name, path.display(), e);
+    }
+    icon
+}
+
+fn cache_path(definition: &ItemDefinition, cache_dir: &Path) -> PathBuf {
+    cache_dir.join(format!("{}_{:016x}.png", definition.id.0, fingerprint(definition)))
+}
+
+/// Hash of everything an icon is drawn from
+fn fingerprint(definition: &ItemDefinition) -> u64 {
+    let mut text = format!(
+        "{}|{}|{}|{}x{}",
+        ICON_VERSION, definition.name, definition.category, definition.size.width, definition.size.height
+    );
+    for affix in affixes(definition) {
+        text.push('|');
+        text.push_str(affix);
+    }
+    fnv1a(text.as_bytes())
+}
+
+/// FNV-1a, which unlike `DefaultHasher` gives the same hash on every build
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Property names and set flags, sorted so the glyphs don't move between runs
+fn affixes(definition: &ItemDefinition) -> Vec<&str> {
+    let properties = &definition.properties;
+    let mut affixes: Vec<&str> = properties.numeric.keys()
+        .chain(properties.flags.iter().filter(|(_, set)| **set).map(|(name, _)| name))
+        .map(String::as_str)
+        .collect();
+    affixes.sort_unstable();
+    affixes
+}
+
+/// Draw the icon for `definition`
+pub fn generate_icon(definition: &ItemDefinition) -> RgbaImage {
+    let hue = (fnv1a(definition.name.as_bytes()) % 360) as f32;
+    let body = hsv(hue, 0.55, 0.8);
+    let light = hsv(hue, 0.35, 0.95);
+    let outline = hsv(hue, 0.6, 0.25);
+
+    let shape = Silhouette::for_category(&definition.category);
+    let inside = |x: i32, y: i32| {
+        (0..ICON_SIZE as i32).contains(&x) && (0..ICON_SIZE as i32).contains(&y) && {
+            // Pixel centers from -1 to 1
+            let to_unit = |v: i32| (v as f32 + 0.5) / ICON_SIZE as f32 * 2.0 - 1.0;
+            shape.contains(to_unit(x), to_unit(y))
+        }
+    };
+
+    let mut icon = RgbaImage::from_pixel(ICON_SIZE, ICON_SIZE, Rgba([0, 0, 0, 0]));
+    for (x, y, pixel) in icon.enumerate_pixels_mut() {
+        let (x, y) = (x as i32, y as i32);
+        if !inside(x, y) {
+            continue;
+        }
+        let edge = !(inside(x - 1, y) && inside(x + 1, y) && inside(x, y - 1) && inside(x, y + 1));
+        *pixel = if edge {
+            outline
+        } else if !inside(x - 2, y - 2) {
+            // Lit from the top left
+            light
+        } else {
+            body
+        };
+    }
+
+    let corners = [
+        (ICON_SIZE - GLYPH_SIZE, 0),
+        (ICON_SIZE - GLYPH_SIZE, ICON_SIZE - GLYPH_SIZE),
+        (0, ICON_SIZE - GLYPH_SIZE),
+        (0, 0),
+    ];
+    for (affix, (left, top)) in affixes(definition).into_iter().zip(corners) {
+        draw_glyph(&mut icon, Glyph::for_affix(affix), left, top);
+    }
+    icon
+}
+
+fn to_bevy_image(icon: RgbaImage) -> Image {
+    Image::new(
+        Extent3d { width: icon.width(), height: icon.height(), depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        icon.into_raw(),
+        TextureFormat::Rgba8UnormSrgb,
+        Default::default(),
+    )
+}
+
+/// Outline of an item, by category
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Silhouette {
+    /// A gun seen from the side
+    Weapon,
+    /// A shield-shaped vest
+    Armor,
+    /// A round flask
+    Consumable,
+    /// A row of cartridges
+    Ammo,
+    /// A gem, for anything else
+    Misc,
+}
+
+impl Silhouette {
+    fn for_category(category: &str) -> Self {
+        match category {
+            "weapon" => Self::Weapon,
+            "armor" => Self::Armor,
+            "consumable" => Self::Consumable,
+            "ammo" => Self::Ammo,
+            _ => Self::Misc,
+        }
+    }
+
+    /// Whether a point, from -1 to 1 with y down, is part of the shape
+    fn contains(self, x: f32, y: f32) -> bool {
+        match self {
+            Self::Weapon => {
+                let barrel = x.abs() < 0.85 && (-0.3..0.05).contains(&y);
+                let grip = (-0.55..-0.25).contains(&x) && (0.05..0.6).contains(&y);
+                barrel || grip
+            }
+            Self::Armor => {
+                let width = if y < 0.2 { 0.7 } else { 0.7 * (1.0 - (y - 0.2) / 0.65) };
+                (-0.75..0.85).contains(&y) && x.abs() < width && !(y < -0.45 && x.abs() < 0.25)
+            }
+            Self::Consumable => {
+                let flask = x * x + (y - 0.25).powi(2) < 0.55 * 0.55;
+                let neck = x.abs() < 0.18 && (-0.75..0.0).contains(&y);
+                flask || neck
+            }
+            Self::Ammo => [-0.45, 0.0, 0.45].iter().any(|cx: &f32| {
+                let dx = x - cx;
+                (dx.abs() < 0.15 && (-0.4..0.7).contains(&y)) || dx * dx + (y + 0.4).powi(2) < 0.15 * 0.15
+            }),
+            Self::Misc => x.abs() + y.abs() < 0.75,
+        }
+    }
+}
+
+/// Small mark for one of an item's properties or flags
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Glyph {
+    kind: GlyphKind,
+    color: Rgba<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GlyphKind {
+    Pip,
+    Cross,
+    Chevron,
+    Bar,
+    Ring,
+}
+
+impl Glyph {
+    fn for_affix(affix: &str) -> Self {
+        let hash = fnv1a(affix.as_bytes());
+        match affix {
+            "damage" => Self { kind: GlyphKind::Pip, color: Rgba([230, 60, 50, 255]) },
+            "fire_rate" => Self { kind: GlyphKind::Chevron, color: Rgba([250, 200, 60, 255]) },
+            "heal_amount" => Self { kind: GlyphKind::Cross, color: Rgba([80, 220, 90, 255]) },
+            "armor" => Self { kind: GlyphKind::Bar, color: Rgba([90, 150, 240, 255]) },
+            "durability" => Self { kind: GlyphKind::Ring, color: Rgba([200, 200, 200, 255]) },
+            // Anything new still gets a mark of its own
+            _ => {
+                let kinds = [GlyphKind::Pip, GlyphKind::Cross, GlyphKind::Chevron, GlyphKind::Bar, GlyphKind::Ring];
+                Self {
+                    kind: kinds[(hash % kinds.len() as u64) as usize],
+                    color: hsv(((hash >> 8) % 360) as f32, 0.7, 0.95),
+                }
+            }
+        }
+    }
+
+    /// Whether a pixel of the glyph, from its center, is colored
+    fn contains(self, dx: i32, dy: i32) -> bool {
+        match self.kind {
+            GlyphKind::Pip => dx * dx + dy * dy <= 6,
+            GlyphKind::Cross => (dx.abs() <= 1 && dy.abs() <= 3) || (dy.abs() <= 1 && dx.abs() <= 3),
+            GlyphKind::Chevron => (dy + dx.abs() - 1).abs() <= 1 && dx.abs() <= 3,
+            GlyphKind::Bar => dy.abs() <= 1 && dx.abs() <= 3,
+            GlyphKind::Ring => (5..=10).contains(&(dx * dx + dy * dy)),
+        }
+    }
+}
+
+/// Draw a glyph on a dark backing so it reads over the item
+fn draw_glyph(icon: &mut RgbaImage, glyph: Glyph, left: u32, top: u32) {
+    let center = (GLYPH_SIZE / 2) as i32;
+    for y in 0..GLYPH_SIZE {
+        for x in 0..GLYPH_SIZE {
+            let color = if glyph.contains(x as i32 - center, y as i32 - center) {
+                glyph.color
+            } else {
+                Rgba([20, 20, 24, 200])
+            };
+            icon.put_pixel(left + x, top + y, color);
+        }
+    }
+}
+
+/// Color from hue in degrees and saturation and value from 0 to 1
+fn hsv(hue: f32, saturation: f32, value: f32) -> Rgba<u8> {
+    let color = Color::hsv(hue, saturation, value).to_srgba();
+    Rgba([
+        (color.red * 255.0).round() as u8,
+        (color.green * 255.0).round() as u8,
+        (color.blue * 255.0).round() as u8,
+        255,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::registry::PropertyRange;
+
+    fn rifle() -> ItemDefinition {
+        ItemDefinition::new(ItemId(2), "Basic Rifle")
+            .with_category("weapon")
+            .with_size(3, 1)
+            .with_property("damage", PropertyRange::Fixed(30.0))
+    }
+
+    #[test]
+    fn test_icons_follow_their_definition() {
+        assert_eq!(generate_icon(&rifle()), generate_icon(&rifle()));
+
+        let renamed = ItemDefinition { name: "Old Rifle".to_string(), ..rifle() };
+        assert_ne!(generate_icon(&renamed), generate_icon(&rifle()));
+        assert_ne!(fingerprint(&renamed), fingerprint(&rifle()));
+
+        let potion = ItemDefinition { category: "consumable".to_string(), ..rifle() };
+        assert_ne!(generate_icon(&potion), generate_icon(&rifle()));
+    }
+
+    #[test]
+    fn test_each_affix_gets_a_corner_glyph() {
+        let plain = ItemDefinition::new(ItemId(7), "Trinket");
+        let icon = generate_icon(&plain);
+        let top_right = *icon.get_pixel(ICON_SIZE - 1, 0);
+        assert_eq!(top_right, Rgba([0, 0, 0, 0]));
+
+        let icon = generate_icon(&plain.with_flag("cursed", true));
+        let top_right = *icon.get_pixel(ICON_SIZE - 1, 0);
+        assert_ne!(top_right, Rgba([0, 0, 0, 0]));
+        assert_eq!(*icon.get_pixel(ICON_SIZE - 1, ICON_SIZE - 1), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_generated_icon_is_cached() {
+        let cache_dir = std::env::temp_dir().join(format!("icon_cache_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        let icon = load_or_generate_icon(&rifle(), &cache_dir);
+        assert!(cache_path(&rifle(), &cache_dir).exists());
+        assert_eq!(load_or_generate_icon(&rifle(), &cache_dir), icon);
+
+        let _ = std::fs::remove_dir_all(&cache_dir);
+    }
+}
@@ -1,16 +1,21 @@
 pub mod components;
 pub mod factory;
+pub mod icons;
 pub mod operations;
 pub mod registry;
 pub mod events;
 pub mod ui;
+pub mod undo;
+pub mod world_item;
 
 // Re-export commonly used types
 pub use components::{Inventory, ItemInstance, InstanceId, GridPosition, ItemRotation};
 pub use registry::{ItemRegistry, ItemDefinition};
 pub use events::*;
+pub use world_item::spawn_world_item;
 
 use bevy::prelude::*;
+use crate::events::GameEventAppExt;
 
 /// Inventory plugin that sets up all inventory systems
 pub struct InventoryPlugin;
@@ -19,12 +24,12 @@ impl Plugin for InventoryPlugin {
     fn build(&self, app: &mut App) {
         app
             // Add events
-            .add_event::<InventoryEvent>()
-            .add_event::<events::TooltipEvent>()
+            .add_game_event::<InventoryEvent>()
             // Add resources
             .init_resource::<ui::InventoryUiState>()
-            .init_resource::<ui::TooltipState>()
             .init_resource::<ui::DragState>()
+            .init_resource::<undo::InventoryUndo>()
+            .init_resource::<icons::ItemIcons>()
             // Add startup systems
             .add_systems(Startup, (
                 registry::setup_item_registry,
@@ -33,14 +38,19 @@ impl Plugin for InventoryPlugin {
             .add_systems(Update, (
                 // Core inventory systems
                 operations::inventory_operations_system,
+                world_item::pickup_world_items,
                 // UI systems
                 ui::toggle_inventory_panel,
                 ui::spawn_inventory_panel,
+                icons::build_item_icons
+                    .run_if(resource_exists_and_changed::<ItemRegistry>)
+                    .before(ui::update_inventory_display),
                 ui::update_inventory_display,
                 ui::handle_cell_clicks,
+                ui::use_consumable_items,
                 ui::handle_drag_and_drop,
-                ui::update_tooltip_state,
-                ui::spawn_tooltips,
+                (undo::record_inventory_changes, undo::undo_inventory_change).chain().after(ui::handle_drag_and_drop),
+                ui::request_item_tooltips.in_set(crate::ui::tooltip::TooltipSystems::Request),
                 // Drag and drop systems
                 ui::update_drag_preview,
                 ui::spawn_drag_preview,
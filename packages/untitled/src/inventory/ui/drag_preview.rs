@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use crate::{
     inventory::{InstanceId, GridPosition, ItemRotation},
     player::Player,
+    ui::theme::UiTheme,
 };
 
 /// Component for drag preview visual elements
@@ -45,6 +46,7 @@ pub fn update_drag_preview(
     cell_query: Query<(&crate::inventory::ui::InventoryCell, &GlobalTransform)>,
     player_query: Query<&crate::inventory::Inventory, With<Player>>,
     item_registry: Res<crate::inventory::ItemRegistry>,
+    theme: Res<UiTheme>,
 ) {
     if !drag_state.is_dragging {
         return;
@@ -83,12 +85,8 @@ pub fn update_drag_preview(
             &item_registry,
         );
 
-        // Update color based on validity (green for valid, red for invalid)
-        bg_color.0 = if is_valid_drop {
-            Color::srgba(0.2, 1.0, 0.2, 0.6) // Green
-        } else {
-            Color::srgba(1.0, 0.2, 0.2, 0.6) // Red
-        };
+        // Update color based on validity
+        bg_color.0 = if is_valid_drop { theme.drop_valid } else { theme.drop_invalid };
     }
 }
 
@@ -146,6 +144,7 @@ pub fn spawn_drag_preview(
     existing_preview: Query<Entity, With<DragPreview>>,
     player_query: Query<&crate::inventory::Inventory, With<Player>>,
     item_registry: Res<crate::inventory::ItemRegistry>,
+    theme: Res<UiTheme>,
 ) {
     if drag_state.is_dragging && drag_state.dragged_item.is_some() && existing_preview.is_empty() {
         let Some(item_id) = drag_state.dragged_item else { return; };
@@ -177,7 +176,7 @@ pub fn spawn_drag_preview(
                 height: Val::Px(preview_size.y),
                 ..default()
             },
-            BackgroundColor(Color::srgba(1.0, 0.0, 0.0, 0.8)), // Bright red for visibility
+            BackgroundColor(theme.drop_invalid),
             DragPreview { item_id },
         ));
     }
@@ -1,9 +1,10 @@
 use bevy::prelude::*;
 use crate::{
     inventory::{
-        Inventory, InstanceId, GridPosition, ItemRotation,
+        Inventory, InstanceId, GridPosition, ItemInstance, ItemRotation, InventoryEvent,
     },
     player::Player,
+    ui::{theme::UiTheme, UiUpdateStats},
 };
 use super::DragState;
 
@@ -39,10 +40,6 @@ pub struct InventoryUiState {
 }
 
 // Placeholder color constants for different cell states
-pub const CELL_EMPTY_COLOR: Color = Color::srgb(0.2, 0.2, 0.3);
-pub const CELL_OCCUPIED_COLOR: Color = Color::srgb(0.3, 0.3, 0.4);
-pub const CELL_HOVER_COLOR: Color = Color::srgb(0.4, 0.4, 0.5);
-pub const CELL_SELECTED_COLOR: Color = Color::srgb(0.5, 0.3, 0.3);
 
 // UI layout constants
 pub const CELL_SIZE: f32 = 40.0;
@@ -52,9 +49,10 @@ pub const PANEL_PADDING: f32 = 10.0;
 /// System to handle opening/closing the inventory panel
 pub fn toggle_inventory_panel(
     keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<crate::player::PlayerInputBindings>,
     mut ui_state: ResMut<InventoryUiState>,
 ) {
-    if keyboard.just_pressed(KeyCode::Tab) {
+    if keyboard.just_pressed(bindings.toggle_inventory) {
         ui_state.is_open = !ui_state.is_open;
         info!("Inventory panel {}", if ui_state.is_open { "opened" } else { "closed" });
     }
@@ -64,11 +62,12 @@ pub fn toggle_inventory_panel(
 pub fn spawn_inventory_panel(
     mut commands: Commands,
     ui_state: Res<InventoryUiState>,
+    theme: Res<UiTheme>,
     existing_panels: Query<Entity, With<InventoryPanel>>,
 ) {
     // If panel should be open but doesn't exist, create it
     if ui_state.is_open && existing_panels.is_empty() {
-        spawn_panel(&mut commands);
+        spawn_panel(&mut commands, &theme);
     }
     // If panel should be closed but exists, remove it
     else if !ui_state.is_open && !existing_panels.is_empty() {
@@ -79,7 +78,7 @@ pub fn spawn_inventory_panel(
 }
 
 /// Helper function to create the inventory panel UI
-fn spawn_panel(commands: &mut Commands) {
+fn spawn_panel(commands: &mut Commands, theme: &UiTheme) {
     // Calculate panel size based on standard inventory dimensions (6x4 for player)
     let panel_width = (CELL_SIZE + CELL_SPACING) * 6.0 + PANEL_PADDING * 2.0;
     let panel_height = (CELL_SIZE + CELL_SPACING) * 4.0 + PANEL_PADDING * 2.0;
@@ -87,7 +86,7 @@ fn spawn_panel(commands: &mut Commands) {
     // Create the main panel container
     commands
         .spawn((
-            Node {
+            theme.panel(Node {
                 position_type: PositionType::Absolute,
                 left: Val::Px(100.0), // Position it away from health bar
                 top: Val::Px(100.0),
@@ -101,9 +100,7 @@ fn spawn_panel(commands: &mut Commands) {
                 row_gap: Val::Px(CELL_SPACING),
                 column_gap: Val::Px(CELL_SPACING),
                 ..default()
-            },
-            BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
-            BorderColor(Color::srgb(0.6, 0.6, 0.6)),
+            }),
             InventoryPanel,
         ))
         .with_children(|parent| {
@@ -118,8 +115,8 @@ fn spawn_panel(commands: &mut Commands) {
                             border: UiRect::all(Val::Px(1.0)),
                             ..default()
                         },
-                        BackgroundColor(CELL_EMPTY_COLOR),
-                        BorderColor(Color::srgb(0.5, 0.5, 0.5)),
+                        BackgroundColor(theme.cell_empty),
+                        BorderColor(theme.button_border),
                         InventoryCell { grid_x: x, grid_y: y },
                     ));
                 }
@@ -128,24 +125,34 @@ fn spawn_panel(commands: &mut Commands) {
 }
 
 /// System to update the visual state of inventory cells based on content
+/// Rebuilds the icons only when the inventory, selection or panel changed
 pub fn update_inventory_display(
     mut commands: Commands,
     mut cell_query: Query<(Entity, &mut BackgroundColor, &InventoryCell), With<InventoryCell>>,
+    new_cells: Query<(), Added<InventoryCell>>,
     item_icon_query: Query<Entity, With<InventoryItemIcon>>,
-    player_query: Query<&Inventory, With<Player>>,
+    player_query: Query<Ref<Inventory>, With<Player>>,
     item_registry: Res<crate::inventory::ItemRegistry>,
+    item_icons: Res<crate::inventory::icons::ItemIcons>,
     ui_state: Res<InventoryUiState>,
+    theme: Res<UiTheme>,
+    mut stats: ResMut<UiUpdateStats>,
 ) {
     if !ui_state.is_open {
         return;
     }
 
-    // Clean up existing item icons
-    for entity in item_icon_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
     if let Ok(inventory) = player_query.single() {
+        if !inventory.is_changed() && !ui_state.is_changed() && new_cells.is_empty() {
+            return;
+        }
+        stats.record("inventory_display");
+
+        // Clean up existing item icons
+        for entity in item_icon_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
         for (cell_entity, mut bg_color, cell) in cell_query.iter_mut() {
             let pos = GridPosition::new(cell.grid_x, cell.grid_y);
 
@@ -153,37 +160,44 @@ pub fn update_inventory_display(
             if let Some(item) = inventory.get_item_at(pos) {
                 // Update background color
                 if ui_state.selected_item == Some(item.id) {
-                    bg_color.0 = CELL_SELECTED_COLOR;
+                    bg_color.0 = theme.cell_selected;
                 } else {
-                    bg_color.0 = CELL_OCCUPIED_COLOR;
+                    bg_color.0 = theme.cell_occupied;
                 }
 
                 // Add item icon and text
                 if let Some(definition) = item_registry.get(item.item_id) {
                     commands.entity(cell_entity).with_children(|parent| {
-                        // Item icon placeholder (using colored rectangle for now)
-                        parent.spawn((
-                            Node {
-                                width: Val::Px(32.0),
-                                height: Val::Px(32.0),
-                                position_type: PositionType::Absolute,
-                                left: Val::Px(4.0),
-                                top: Val::Px(4.0),
-                                ..default()
-                            },
-                            BackgroundColor(get_item_icon_color(&definition.name)),
-                            InventoryItemIcon { instance_id: item.id },
-                        ));
+                        let icon_node = Node {
+                            width: Val::Px(32.0),
+                            height: Val::Px(32.0),
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(4.0),
+                            top: Val::Px(4.0),
+                            ..default()
+                        };
+                        match item_icons.get(item.item_id) {
+                            Some(icon) => {
+                                parent.spawn((
+                                    icon_node,
+                                    ImageNode::new(icon.clone()),
+                                    InventoryItemIcon { instance_id: item.id },
+                                ));
+                            }
+                            // Not built yet, so fall back to a flat color
+                            None => {
+                                parent.spawn((
+                                    icon_node,
+                                    BackgroundColor(get_item_icon_color(&definition.name)),
+                                    InventoryItemIcon { instance_id: item.id },
+                                ));
+                            }
+                        }
 
                         // Stack size indicator (if > 1)
                         if item.stack_size > 1 {
                             parent.spawn((
-                                Text::new(item.stack_size.to_string()),
-                                TextFont {
-                                    font_size: 10.0,
-                                    ..default()
-                                },
-                                TextColor(Color::WHITE),
+                                theme.text(item.stack_size.to_string(), 10.0),
                                 Node {
                                     position_type: PositionType::Absolute,
                                     right: Val::Px(2.0),
@@ -196,13 +210,13 @@ pub fn update_inventory_display(
                     });
                 }
             } else {
-                bg_color.0 = CELL_EMPTY_COLOR;
+                bg_color.0 = theme.cell_empty;
             }
         }
     }
 }
 
-/// Helper function to get item icon color based on item name (placeholder)
+/// Placeholder icon color based on item name, until the item's icon is built
 fn get_item_icon_color(item_name: &str) -> Color {
     match item_name {
         "Health Potion" => Color::srgb(1.0, 0.2, 0.2), // Red
@@ -242,6 +256,55 @@ pub fn handle_cell_clicks(
     }
 }
 
+/// System to consume the hovered item with the use key, e.g. drinking health potions
+/// Right click is taken by rotation and grenades, so this has its own binding
+pub fn use_consumable_items(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<crate::player::PlayerInputBindings>,
+    interaction_query: Query<(&Interaction, &InventoryCell)>,
+    mut player_query: Query<(Entity, &mut Inventory), With<Player>>,
+    item_registry: Res<crate::inventory::ItemRegistry>,
+    mut heal_events: EventWriter<crate::combat::HealEvent>,
+    mut inventory_events: EventWriter<crate::inventory::InventoryEvent>,
+) {
+    if !keyboard_input.just_pressed(bindings.use_item) {
+        return;
+    }
+
+    let Some((_, cell)) = interaction_query.iter().find(|(interaction, _)| **interaction != Interaction::None) else {
+        return;
+    };
+    let Ok((player_entity, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+
+    let pos = GridPosition::new(cell.grid_x, cell.grid_y);
+    let Some(item) = inventory.get_item_at(pos) else {
+        return;
+    };
+
+    let is_consumable = item_registry.get(item.item_id).is_some_and(|def| def.category == "consumable");
+    if !is_consumable {
+        return;
+    }
+
+    let instance_id = item.id;
+    if let Some(heal_amount) = item.get_property("heal_amount") {
+        heal_events.write(crate::combat::HealEvent::instant(player_entity, player_entity, heal_amount));
+    }
+
+    // Use up one from the stack
+    let remaining = inventory.grid.items.get_mut(&instance_id).map(|item| {
+        item.stack_size = item.stack_size.saturating_sub(1);
+        item.stack_size
+    });
+    if remaining == Some(0) {
+        inventory.remove_item(instance_id);
+    }
+
+    inventory_events.write(crate::inventory::InventoryEvent::ItemUsed { item_id: instance_id });
+}
+
 /// System to handle drag and drop interactions
 pub fn handle_drag_and_drop(
     mut drag_state: ResMut<DragState>,
@@ -252,6 +315,7 @@ pub fn handle_drag_and_drop(
     mut player_query: Query<&mut Inventory, With<Player>>,
     item_registry: Res<crate::inventory::ItemRegistry>,
     ui_state: Res<InventoryUiState>,
+    mut inventory_events: EventWriter<InventoryEvent>,
 ) {
     let Ok(window) = windows.single() else { return; };
     let Some(cursor_pos) = window.cursor_position() else { return; };
@@ -319,10 +383,16 @@ pub fn handle_drag_and_drop(
         if should_rotate {
             if let Some(selected_id) = ui_state.selected_item {
                 if let Ok(mut inventory) = player_query.single_mut() {
-                    if inventory.grid.items.contains_key(&selected_id) {
+                    if let Some(item) = inventory.grid.items.get(&selected_id) {
+                        let (position, rotation) = (item.position, item.rotation);
                         match inventory.rotate_item(selected_id, &item_registry) {
                             Ok(()) => {
-                                // Successfully rotated
+                                inventory_events.write(InventoryEvent::ItemMoved {
+                                    item_id: selected_id,
+                                    from_position: position,
+                                    to_position: position,
+                                    from_rotation: rotation,
+                                });
                             }
                             Err(_) => {
                                 // Rotation failed (item can't be rotated or not enough space)
@@ -340,7 +410,7 @@ pub fn handle_drag_and_drop(
 
 
             // Handle drop logic
-            handle_item_drop(&drag_state, &interaction_query, &mut player_query, &item_registry);
+            handle_item_drop(&drag_state, &interaction_query, &mut player_query, &item_registry, &mut inventory_events);
         }
 
         // Reset drag state
@@ -354,6 +424,7 @@ fn handle_item_drop(
     _interaction_query: &Query<(&Interaction, &InventoryCell), Changed<Interaction>>,
     player_query: &mut Query<&mut Inventory, With<Player>>,
     item_registry: &Res<crate::inventory::ItemRegistry>,
+    inventory_events: &mut EventWriter<InventoryEvent>,
 ) {
     let Some(item_id) = drag_state.dragged_item else { return; };
     let Ok(mut inventory) = player_query.single_mut() else { return; };
@@ -408,6 +479,13 @@ fn handle_item_drop(
         }
     }
 
+    // Remember the item and the stacks it could merge into, to announce what the drop did
+    let Some(before) = inventory.grid.items.get(&item_id).cloned() else { return; };
+    let stacks_before: Vec<(InstanceId, u32)> = inventory.grid.items.values()
+        .filter(|other| other.item_id == before.item_id && other.id != item_id)
+        .map(|other| (other.id, other.stack_size))
+        .collect();
+
     // Attempt to move the item to the new position
     // First, remove the item from its current position
     if let Some(mut item) = inventory.remove_item(item_id) {
@@ -473,4 +551,31 @@ fn handle_item_drop(
             }
         }
     }
+
+    if let Some(event) = describe_drop(&inventory, before, &stacks_before) {
+        inventory_events.write(event);
+    }
+}
+
+/// The event for what a drop did to the dragged item, if it changed anything
+fn describe_drop(inventory: &Inventory, before: ItemInstance, stacks_before: &[(InstanceId, u32)]) -> Option<InventoryEvent> {
+    let merged = stacks_before.iter().find_map(|&(id, size)| {
+        let size_now = inventory.grid.items.get(&id)?.stack_size;
+        (size_now > size).then_some((id, size_now - size))
+    });
+    if let Some((into, amount)) = merged {
+        return Some(InventoryEvent::ItemStacked { item: before, into, amount });
+    }
+
+    let after = inventory.grid.items.get(&before.id)?;
+    if after.position == before.position && after.rotation == before.rotation {
+        return None;
+    }
+    Some(InventoryEvent::ItemMoved {
+        item_id: before.id,
+        from_position: before.position,
+        to_position: after.position,
+        from_rotation: before.rotation,
+    })
 }
+
@@ -0,0 +1,210 @@
+//! Single-step undo for inventory changes (Ctrl+Z)
+//!
+//! Moves and stack merges are announced as `InventoryEvent`s; the last one is
+//! kept here as the event's inverse for a few seconds. Ctrl+Z while the
+//! inventory panel is open applies it. Only one step is kept, and anything
+//! else that changes the inventory, like using an item, drops it.
+use bevy::prelude::*;
+
+use crate::inventory::{
+    operations::InventoryError,
+    GridPosition, InstanceId, Inventory, InventoryEvent, ItemInstance, ItemRegistry, ItemRotation,
+};
+use crate::player::Player;
+use super::ui::InventoryUiState;
+
+/// Seconds after a change that it can still be undone
+const UNDO_WINDOW_SECS: f32 = 10.0;
+
+/// How to reverse one inventory change
+#[derive(Debug, Clone)]
+pub enum InventoryUndoStep {
+    /// Put a moved or rotated item back where it was
+    MoveBack { item_id: InstanceId, position: GridPosition, rotation: ItemRotation },
+    /// Take `amount` back off `from` and restore the item that was merged into it
+    Unstack { item: ItemInstance, from: InstanceId, amount: u32 },
+}
+
+impl InventoryUndoStep {
+    /// The inverse of an inventory event, for events that can be undone
+    pub fn inverse_of(event: &InventoryEvent) -> Option<Self> {
+        match event {
+            InventoryEvent::ItemMoved { item_id, from_position, from_rotation, .. } => Some(Self::MoveBack {
+                item_id: *item_id,
+                position: *from_position,
+                rotation: *from_rotation,
+            }),
+            InventoryEvent::ItemStacked { item, into, amount } => Some(Self::Unstack {
+                item: item.clone(),
+                from: *into,
+                amount: *amount,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Apply the step, leaving the inventory as it was if it can't be
+    pub fn apply(&self, inventory: &mut Inventory, registry: &ItemRegistry) -> Result<(), InventoryError> {
+        match self {
+            Self::MoveBack { item_id, position, rotation } => {
+                let mut item = inventory.remove_item(*item_id).ok_or(InventoryError::ItemNotFound)?;
+                let (current_position, current_rotation) = (item.position, item.rotation);
+                item.rotation = *rotation;
+                if let Err(error) = inventory.try_place_item(item.clone(), *position, registry) {
+                    item.rotation = current_rotation;
+                    inventory.try_place_item(item, current_position, registry)?;
+                    return Err(error);
+                }
+                Ok(())
+            }
+            Self::Unstack { item, from, amount } => {
+                let stack = inventory.grid.items.get(from).ok_or(InventoryError::ItemNotFound)?;
+                if stack.stack_size <= *amount {
+                    return Err(InventoryError::NotAllowed);
+                }
+
+                // Whatever didn't fit in the stack was placed on its own and goes back into the restored item
+                let remainder = inventory.remove_item(item.id);
+                if let Err(error) = inventory.try_place_item(item.clone(), item.position, registry) {
+                    if let Some(remainder) = remainder {
+                        let position = remainder.position;
+                        inventory.try_place_item(remainder, position, registry)?;
+                    }
+                    return Err(error);
+                }
+                if let Some(stack) = inventory.grid.items.get_mut(from) {
+                    stack.stack_size -= amount;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The last undoable inventory change
+#[derive(Resource, Debug, Default)]
+pub struct InventoryUndo {
+    /// The step with the real time it was recorded
+    last: Option<(InventoryUndoStep, f32)>,
+}
+
+/// Keep the inverse of the latest change, or forget it when something else changed the inventory
+pub fn record_inventory_changes(
+    time: Res<Time<Real>>,
+    mut inventory_events: EventReader<InventoryEvent>,
+    mut undo: ResMut<InventoryUndo>,
+) {
+    for event in inventory_events.read() {
+        match (InventoryUndoStep::inverse_of(event), event) {
+            (Some(step), _) => undo.last = Some((step, time.elapsed_secs())),
+            (None, InventoryEvent::ItemUsed { .. }) => undo.last = None,
+            (None, _) => {}
+        }
+    }
+}
+
+/// Undo the last change with Ctrl+Z while the inventory panel is open
+pub fn undo_inventory_change(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    ui_state: Res<InventoryUiState>,
+    mut undo: ResMut<InventoryUndo>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    item_registry: Res<ItemRegistry>,
+) {
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ui_state.is_open || !ctrl || !keyboard.just_pressed(KeyCode::KeyZ) {
+        return;
+    }
+
+    let Some((step, recorded_at)) = undo.last.take() else {
+        info!("Nothing to undo");
+        return;
+    };
+    if time.elapsed_secs() - recorded_at > UNDO_WINDOW_SECS {
+        info!("Too long ago to undo");
+        return;
+    }
+    let Ok(mut inventory) = player_query.single_mut() else {
+        return;
+    };
+
+    match step.apply(&mut inventory, &item_registry) {
+        Ok(()) => info!("Undid inventory change"),
+        Err(error) => info!("Can't undo: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::{factory::ItemFactory, registry::{ItemDefinition, ItemId}};
+
+    fn setup() -> (Inventory, ItemRegistry, ItemFactory) {
+        let mut registry = ItemRegistry::new();
+        registry.register(ItemDefinition::new(ItemId(1), "Plank").with_size(2, 1));
+        registry.register(ItemDefinition::new(ItemId(2), "Arrow").with_size(1, 1).with_stack_size(20));
+        (Inventory::new(4, 4), registry, ItemFactory::new())
+    }
+
+    #[test]
+    fn test_undo_move_puts_item_back() {
+        let (mut inventory, registry, mut factory) = setup();
+        let item = factory.create_item(ItemId(1), &registry).unwrap();
+        let item_id = item.id;
+        inventory.try_place_item(item, GridPosition::new(0, 0), &registry).unwrap();
+
+        let mut moved = inventory.remove_item(item_id).unwrap();
+        moved.rotation = ItemRotation::Clockwise90;
+        inventory.try_place_item(moved, GridPosition::new(3, 1), &registry).unwrap();
+
+        let event = InventoryEvent::ItemMoved {
+            item_id,
+            from_position: GridPosition::new(0, 0),
+            to_position: GridPosition::new(3, 1),
+            from_rotation: ItemRotation::None,
+        };
+        InventoryUndoStep::inverse_of(&event).unwrap().apply(&mut inventory, &registry).unwrap();
+
+        let item = inventory.get_item_at(GridPosition::new(1, 0)).unwrap();
+        assert_eq!((item.id, item.rotation), (item_id, ItemRotation::None));
+        assert!(inventory.get_item_at(GridPosition::new(3, 1)).is_none());
+    }
+
+    #[test]
+    fn test_undo_stack_splits_merged_items_back_out() {
+        let (mut inventory, registry, mut factory) = setup();
+        let mut stack = factory.create_item(ItemId(2), &registry).unwrap();
+        stack.stack_size = 5;
+        let stack_id = stack.id;
+        inventory.try_place_item(stack, GridPosition::new(0, 0), &registry).unwrap();
+
+        let mut dragged = factory.create_item(ItemId(2), &registry).unwrap();
+        dragged.stack_size = 3;
+        dragged.position = GridPosition::new(2, 2);
+        let before = dragged.clone();
+        inventory.try_stack_item(dragged, &registry).unwrap();
+        assert_eq!(inventory.total_stack_count(), 8);
+
+        let event = InventoryEvent::ItemStacked { item: before.clone(), into: stack_id, amount: 3 };
+        InventoryUndoStep::inverse_of(&event).unwrap().apply(&mut inventory, &registry).unwrap();
+
+        assert_eq!(inventory.grid.items[&stack_id].stack_size, 5);
+        assert_eq!(inventory.get_item_at(GridPosition::new(2, 2)).unwrap().stack_size, before.stack_size);
+    }
+
+    #[test]
+    fn test_blocked_undo_leaves_inventory_alone() {
+        let (mut inventory, registry, mut factory) = setup();
+        let item = factory.create_item(ItemId(1), &registry).unwrap();
+        let item_id = item.id;
+        inventory.try_place_item(item, GridPosition::new(2, 3), &registry).unwrap();
+        let blocker = factory.create_item(ItemId(1), &registry).unwrap();
+        inventory.try_place_item(blocker, GridPosition::new(0, 0), &registry).unwrap();
+
+        let step = InventoryUndoStep::MoveBack { item_id, position: GridPosition::new(0, 0), rotation: ItemRotation::None };
+        assert!(step.apply(&mut inventory, &registry).is_err());
+        assert_eq!(inventory.get_item_at(GridPosition::new(2, 3)).unwrap().id, item_id);
+        assert_eq!(inventory.item_count(), 2);
+    }
+}
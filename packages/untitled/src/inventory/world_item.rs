@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use crate::{
+    player::Player,
+    world::{Interactable, InteractableHighlight, InteractionEvent},
+};
+use super::{
+    components::ItemInstance,
+    operations::InventoryError,
+    registry::{ItemDefinition, ItemRegistry},
+    ui::item_tooltip,
+    Inventory,
+};
+
+/// Radius of the marker drawn for an item lying in the world
+const WORLD_ITEM_RADIUS: f32 = 8.0;
+
+/// An item lying in the world, picked up into the player's inventory on interact
+#[derive(Component)]
+pub struct WorldItem {
+    pub item: ItemInstance,
+}
+
+/// Spawns an item pickup at a world position
+pub fn spawn_world_item(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    item: ItemInstance,
+    definition: &ItemDefinition,
+    position: Vec2,
+) -> Entity {
+    let tooltip = item_tooltip(&item, definition, None).with_keybind("E", "Pick up");
+
+    commands
+        .spawn((
+            Mesh2d(meshes.add(Rectangle::new(WORLD_ITEM_RADIUS * 2.0, WORLD_ITEM_RADIUS * 2.0))),
+            MeshMaterial2d(materials.add(Color::srgb(0.9, 0.8, 0.3))),
+            Transform::from_translation(position.extend(crate::depth::DepthLayer::Actors.z())),
+            crate::depth::YSort::with_offset(-WORLD_ITEM_RADIUS),
+            tooltip,
+            Interactable::new(
+                format!("item_{}", item.id.0),
+                definition.name.clone(),
+                |_| {},
+            ),
+            InteractableHighlight::default(),
+            WorldItem { item },
+        ))
+        .id()
+}
+
+/// Move interacted world items into the player's inventory
+pub fn pickup_world_items(
+    mut commands: Commands,
+    mut interaction_events: EventReader<InteractionEvent>,
+    mut players: Query<&mut Inventory, With<Player>>,
+    world_items: Query<&WorldItem>,
+    registry: Res<ItemRegistry>,
+) {
+    for event in interaction_events.read() {
+        let Ok(world_item) = world_items.get(event.target_entity) else {
+            continue;
+        };
+        let Ok(mut inventory) = players.get_mut(event.source_entity) else {
+            continue;
+        };
+
+        match inventory.auto_place_item(world_item.item.clone(), &registry) {
+            Ok(()) => {
+                commands.entity(event.target_entity).despawn();
+            }
+            Err(InventoryError::NoSpace) => {
+                info!("No room in inventory for item {:?}", world_item.item.item_id);
+            }
+            Err(e) => {
+                warn!("Failed to pick up item {:?}: {}", world_item.item.item_id, e);
+            }
+        }
+    }
+}
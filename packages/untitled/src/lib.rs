@@ -3,11 +3,18 @@ pub mod world;
 pub mod combat;
 pub mod components;
 pub mod constants;
+pub mod depth;
 pub mod enemy;
 pub mod events;
 pub mod line_of_sight;
+pub mod loading;
 pub mod persistence;
+pub mod physics_layers;
+pub mod interpolation;
 pub mod player;
+pub mod pool;
 pub mod resources;
 pub mod sounds;
+pub mod steering;
+pub mod time_scale;
 pub mod ui;
@@ -2,67 +2,105 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use crate::{
     constants::*,
+    world::WallTile,
 };
 
-/// Fast line of sight check using Rapier's raycasting
-/// Returns true if no walls block the path between start and end
-/// The start_radius and end_radius parameters should be the collision radii of the entities
-pub fn has_line_of_sight(
+/// Outlines of every wall, for sight checks
+///
+/// Rebuilt only when walls spawn, change or despawn, so enemies don't re-read
+/// colliders every check. Each outline keeps its bounds, so a check only looks
+/// at the walls between the two points.
+#[derive(Resource, Default)]
+pub struct SightOccluders {
+    outlines: Vec<(Rect, Vec<Vec2>)>,
+}
+
+impl SightOccluders {
+    /// Outlines with some part inside `area`
+    /// Edges count as inside, since a straight horizontal or vertical check gives an area with no height or width
+    fn within(&self, area: Rect) -> Vec<Vec<Vec2>> {
+        self.outlines
+            .iter()
+            .filter(|(bounds, _)| bounds.min.cmple(area.max).all() && area.min.cmple(bounds.max).all())
+            .map(|(_, outline)| outline.clone())
+            .collect()
+    }
+}
+
+/// Whether an entity at `start` can see `end`
+/// Asks the same visibility polygons as the fog of war and the shadow caster, against the wall outlines
+pub fn has_line_of_sight(start: Vec2, end: Vec2, occluders: &SightOccluders) -> bool {
+    if start.distance(end) > LOS_MAX_RANGE {
+        return false;
+    }
+
+    let nearby = occluders.within(Rect::from_corners(start, end));
+    shadowcast::is_visible(start, end, &nearby)
+}
+
+/// Rebuild the wall outlines when walls spawn, move or despawn
+pub fn refresh_sight_occluders(
+    walls: Query<(&Collider, &Transform), With<WallTile>>,
+    changed: Query<(), (With<WallTile>, Or<(Changed<Collider>, Changed<Transform>)>)>,
+    mut removed: RemovedComponents<WallTile>,
+    mut occluders: ResMut<SightOccluders>,
+) {
+    let removed = removed.read().count() > 0;
+    if changed.is_empty() && !removed {
+        return;
+    }
+
+    occluders.outlines = walls
+        .iter()
+        .flat_map(|(collider, transform)| shadowcast::get_collider_vertices(collider, transform))
+        .map(|outline| {
+            let bounds = outline.iter().fold(Rect::EMPTY, |bounds, &point| bounds.union_point(point));
+            (bounds, outline)
+        })
+        .collect();
+}
+
+/// First solid thing a ray from `start` toward `end` hits, with where it hits
+/// Returns None when the way to `end` is clear; the ray starts outside `start_radius` so it can't hit the caster
+/// Beams use this to stop at walls; `filter` decides what else blocks them
+pub fn first_hit(
     start: Vec2,
     end: Vec2,
     start_radius: f32,
-    end_radius: f32,
+    filter: QueryFilter,
     rapier_context: &ReadRapierContext,
-) -> bool {
+) -> Option<(Entity, Vec2)> {
     let direction = end - start;
     let distance = direction.length();
-
-    if distance > LOS_MAX_RANGE {
-        return false;
-    }
-
-    let ray_dir = direction.normalize();
     let buffer = 2.0;
 
-    // Calculate total offset needed (both entities' radii plus buffers)
-    let total_offset = start_radius + end_radius + (buffer * 2.0);
-
-    if distance <= total_offset {
-        return true; // Too close to raycast meaningfully, assume visible
+    if distance <= start_radius + buffer {
+        return None;
     }
 
-    // Start the ray outside the start entity's collision radius
+    let ray_dir = direction / distance;
     let ray_start = start + ray_dir * (start_radius + buffer);
+    let context = rapier_context.single().ok()?;
+    context
+        .cast_ray(ray_start, ray_dir, distance - (start_radius + buffer), true, filter.exclude_sensors())
+        .map(|(entity, toi)| (entity, ray_start + ray_dir * toi))
+}
 
-    // Calculate remaining distance, stopping before the end entity's collision radius
-    let remaining_distance = distance - (start_radius + buffer) - (end_radius + buffer);
-
-    if remaining_distance <= 0.0 {
-        return true; // Too close to raycast meaningfully
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
 
-    // Create a filter that excludes sensors (projectiles) but includes solid objects (walls)
-    let filter = QueryFilter::default().exclude_sensors();
+    #[test]
+    fn test_walls_block_sight() {
+        let mut world = World::new();
+        world.init_resource::<SightOccluders>();
+        world.spawn((WallTile, Collider::cuboid(16.0, 16.0), Transform::from_xyz(100.0, 0.0, 0.0)));
+        world.run_system_once(refresh_sight_occluders).unwrap();
 
-    // Get the rapier context and perform raycast
-    if let Ok(context) = rapier_context.single() {
-        // Perform the raycast using Rapier's efficient raycasting
-        if let Some((_entity, _toi)) = context.cast_ray(
-            ray_start,
-            ray_dir,
-            remaining_distance,
-            true, // solid objects only
-            filter,
-        ) {
-            // Ray hit something solid before reaching the target
-            false
-        } else {
-            // Clear line of sight
-            true
-        }
-    } else {
-        // Fallback: assume no line of sight if we can't access Rapier context
-        println!("Warning: Unable to access Rapier context for line of sight check.");
-        false
+        let occluders = world.resource::<SightOccluders>();
+        assert!(!has_line_of_sight(Vec2::ZERO, Vec2::new(200.0, 0.0), occluders));
+        assert!(has_line_of_sight(Vec2::ZERO, Vec2::new(200.0, 100.0), occluders));
+        assert!(!has_line_of_sight(Vec2::ZERO, Vec2::new(LOS_MAX_RANGE + 1.0, 500.0), occluders));
     }
-}// The update_line_of_sight system has been moved into the enemy_ai system for better efficiency
+}
@@ -0,0 +1,406 @@
+//! Asset manifest and loading screen
+//!
+//! Textures, sounds and fonts are listed by name in `assets/manifest.ron` and
+//! all loaded before the game leaves `WorldState::Loading`. The loading screen
+//! shows progress and lists any asset that failed, so a missing file surfaces
+//! once at startup instead of as warnings in the middle of a run.
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, LoadContext, LoadState, UntypedAssetId},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::sounds::GameSounds;
+use crate::ui::theme::UiTheme;
+use crate::world::WorldState;
+
+const MANIFEST_PATH: &str = "manifest.ron";
+
+/// Asset names mapped to paths under `assets/`
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct AssetManifest {
+    #[serde(default)]
+    pub textures: BTreeMap<String, String>,
+    #[serde(default)]
+    pub sounds: BTreeMap<String, String>,
+    #[serde(default)]
+    pub fonts: BTreeMap<String, String>,
+}
+
+impl AssetManifest {
+    pub fn parse(text: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::de::from_str(text)
+    }
+
+    pub fn len(&self) -> usize {
+        self.textures.len() + self.sounds.len() + self.fonts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Default)]
+struct AssetManifestLoader;
+
+impl AssetLoader for AssetManifestLoader {
+    type Asset = AssetManifest;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<AssetManifest, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(AssetManifest::parse(std::str::from_utf8(&bytes)?)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Handles for everything in the manifest, looked up by name
+#[derive(Resource, Default)]
+pub struct LoadedAssets {
+    pub textures: HashMap<String, Handle<Image>>,
+    pub sounds: HashMap<String, Handle<AudioSource>>,
+    pub fonts: HashMap<String, Handle<Font>>,
+}
+
+impl LoadedAssets {
+    /// Texture by manifest name; missing entries were already reported on the loading screen
+    pub fn texture(&self, name: &str) -> Handle<Image> {
+        self.textures.get(name).cloned().unwrap_or_default()
+    }
+
+    pub fn sound(&self, name: &str) -> Handle<AudioSource> {
+        self.sounds.get(name).cloned().unwrap_or_default()
+    }
+}
+
+/// Progress through the manifest
+#[derive(Resource, Default, Debug)]
+pub struct LoadingProgress {
+    manifest: Handle<AssetManifest>,
+    /// Every queued asset and its path
+    pending: Vec<(UntypedAssetId, String)>,
+    pub total: usize,
+    pub loaded: usize,
+    /// Path and error of every asset that could not be loaded
+    pub failed: Vec<(String, String)>,
+    /// Manifest read and all its assets queued
+    pub queued: bool,
+}
+
+impl LoadingProgress {
+    pub fn fraction(&self) -> f32 {
+        if !self.queued {
+            return 0.0;
+        }
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.loaded + self.failed.len()) as f32 / self.total as f32
+    }
+
+    /// Every asset finished loading or failed
+    pub fn is_settled(&self) -> bool {
+        self.queued && self.pending.is_empty()
+    }
+
+    fn record(&mut self, path: String, state: Option<LoadState>) -> bool {
+        match state {
+            Some(LoadState::Loaded) => {
+                self.loaded += 1;
+                true
+            }
+            Some(LoadState::Failed(error)) => {
+                error!("Failed to load asset {}: {}", path, error);
+                self.failed.push((path, error.to_string()));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Plugin for the asset manifest, preloading and the loading screen
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<AssetManifest>()
+            .register_asset_loader(AssetManifestLoader)
+            .init_resource::<LoadedAssets>()
+            .init_resource::<LoadingProgress>()
+            .add_systems(OnEnter(WorldState::Loading), (load_manifest, setup_loading_screen))
+            .add_systems(Update, (
+                queue_manifest_assets,
+                track_loading_progress,
+                update_loading_screen,
+                finish_loading,
+            ).chain().run_if(in_state(WorldState::Loading)))
+            .add_systems(OnExit(WorldState::Loading), (apply_loaded_assets, cleanup_loading_screen));
+    }
+}
+
+/// Root of the loading screen
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingBarFill;
+
+#[derive(Component)]
+struct LoadingStatusText;
+
+fn load_manifest(asset_server: Res<AssetServer>, mut progress: ResMut<LoadingProgress>) {
+    progress.manifest = asset_server.load(MANIFEST_PATH);
+}
+
+/// Start loading every asset once the manifest itself is in
+fn queue_manifest_assets(
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<AssetManifest>>,
+    mut progress: ResMut<LoadingProgress>,
+    mut loaded: ResMut<LoadedAssets>,
+) {
+    if progress.queued {
+        return;
+    }
+
+    if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&progress.manifest) {
+        error!("Failed to load asset manifest: {}", error);
+        progress.failed.push((MANIFEST_PATH.to_string(), error.to_string()));
+        progress.queued = true;
+        return;
+    }
+
+    let Some(manifest) = manifests.get(&progress.manifest) else {
+        return;
+    };
+    let manifest = manifest.clone();
+    if manifest.is_empty() {
+        warn!("Asset manifest lists no assets");
+    }
+
+    for (name, path) in &manifest.textures {
+        let handle: Handle<Image> = asset_server.load(path);
+        progress.pending.push((handle.id().untyped(), path.clone()));
+        loaded.textures.insert(name.clone(), handle);
+    }
+    for (name, path) in &manifest.sounds {
+        let handle: Handle<AudioSource> = asset_server.load(path);
+        progress.pending.push((handle.id().untyped(), path.clone()));
+        loaded.sounds.insert(name.clone(), handle);
+    }
+    for (name, path) in &manifest.fonts {
+        let handle: Handle<Font> = asset_server.load(path);
+        progress.pending.push((handle.id().untyped(), path.clone()));
+        loaded.fonts.insert(name.clone(), handle);
+    }
+
+    progress.total = manifest.len();
+    progress.queued = true;
+    info!("Loading {} assets from manifest", progress.total);
+}
+
+fn track_loading_progress(asset_server: Res<AssetServer>, mut progress: ResMut<LoadingProgress>) {
+    let pending = std::mem::take(&mut progress.pending);
+    for (id, path) in pending {
+        let state = asset_server.get_load_state(id);
+        if !progress.record(path.clone(), state) {
+            progress.pending.push((id, path));
+        }
+    }
+}
+
+/// Continue straight away when everything loaded, or on Enter after failures were shown
+fn finish_loading(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    progress: Res<LoadingProgress>,
+    mut next_state: ResMut<NextState<WorldState>>,
+) {
+    if !progress.is_settled() {
+        return;
+    }
+
+    if progress.failed.is_empty() || keyboard.just_pressed(KeyCode::Enter) {
+        info!("Loading finished: {} loaded, {} failed", progress.loaded, progress.failed.len());
+        next_state.set(WorldState::Cathedral);
+    }
+}
+
+/// Hand the loaded handles to the systems that use them
+fn apply_loaded_assets(
+    mut commands: Commands,
+    loaded: Res<LoadedAssets>,
+    mut theme: ResMut<UiTheme>,
+) {
+    commands.insert_resource(GameSounds {
+        gun_01: loaded.sound("gun_01"),
+        gun_02: loaded.sound("gun_02"),
+        gun_03: loaded.sound("gun_03"),
+        explosion_01: loaded.sound("explosion_01"),
+    });
+
+    if let Some(font) = loaded.fonts.get("ui") {
+        theme.font = font.clone();
+    }
+}
+
+fn setup_loading_screen(mut commands: Commands, theme: Res<UiTheme>) {
+    commands.spawn((Camera2d, LoadingScreen));
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.gap * 2.0),
+                ..default()
+            },
+            BackgroundColor(theme.screen_background),
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme.text("Loading", 32.0));
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(400.0),
+                        height: Val::Px(16.0),
+                        border: UiRect::all(Val::Px(theme.border_width)),
+                        ..default()
+                    },
+                    BackgroundColor(theme.bar_background),
+                    BorderColor(theme.panel_border),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(theme.progress_fill),
+                    LoadingBarFill,
+                ));
+
+            parent.spawn((
+                theme.text("", theme.body_size),
+                TextLayout::new_with_justify(JustifyText::Center),
+                LoadingStatusText,
+            ));
+        });
+}
+
+fn update_loading_screen(
+    progress: Res<LoadingProgress>,
+    mut fills: Query<&mut Node, With<LoadingBarFill>>,
+    mut texts: Query<&mut Text, With<LoadingStatusText>>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+
+    if let Ok(mut fill) = fills.single_mut() {
+        fill.width = Val::Percent(progress.fraction() * 100.0);
+    }
+
+    let Ok(mut text) = texts.single_mut() else {
+        return;
+    };
+
+    let mut status = format!("{} / {} assets", progress.loaded + progress.failed.len(), progress.total);
+    if !progress.failed.is_empty() {
+        status.push_str("\n\nMissing or broken assets:");
+        for (path, error) in &progress.failed {
+            status.push_str(&format!("\n{}: {}", path, error));
+        }
+        if progress.is_settled() {
+            status.push_str("\n\nPress Enter to continue without them");
+        }
+    }
+    **text = status;
+}
+
+fn cleanup_loading_screen(mut commands: Commands, screens: Query<Entity, With<LoadingScreen>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_entries_exist() {
+        let assets_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let text = std::fs::read_to_string(assets_dir.join(MANIFEST_PATH)).unwrap();
+        let manifest = AssetManifest::parse(&text).unwrap();
+
+        assert!(manifest.textures.contains_key("tiles"));
+        let paths = manifest.textures.values().chain(manifest.sounds.values()).chain(manifest.fonts.values());
+        for path in paths {
+            assert!(assets_dir.join(path).exists(), "{} is listed in the manifest but missing", path);
+        }
+    }
+
+    #[test]
+    fn test_progress_counts_failures_as_settled() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin {
+            file_path: concat!(env!("CARGO_MANIFEST_DIR"), "/assets").to_string(),
+            ..default()
+        }))
+        .init_asset::<AssetManifest>()
+        .register_asset_loader(AssetManifestLoader)
+        .init_resource::<LoadingProgress>()
+        .add_systems(Update, track_loading_progress);
+
+        let progress = app.world().resource::<LoadingProgress>();
+        assert_eq!(progress.fraction(), 0.0);
+        assert!(!progress.is_settled());
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let found: Handle<AssetManifest> = asset_server.load(MANIFEST_PATH);
+        let missing: Handle<AssetManifest> = asset_server.load("missing.ron");
+        let mut progress = app.world_mut().resource_mut::<LoadingProgress>();
+        progress.pending.push((found.id().untyped(), MANIFEST_PATH.to_string()));
+        progress.pending.push((missing.id().untyped(), "missing.ron".to_string()));
+        progress.total = 2;
+        progress.queued = true;
+
+        // Loading happens on other threads, so give it a few seconds
+        for _ in 0..500 {
+            app.update();
+            let progress = app.world().resource::<LoadingProgress>();
+            assert_eq!(progress.is_settled(), progress.pending.is_empty());
+            if progress.is_settled() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let progress = app.world().resource::<LoadingProgress>();
+        assert!(progress.is_settled());
+        assert_eq!(progress.loaded, 1);
+        assert_eq!(progress.failed.len(), 1);
+        assert_eq!(progress.failed[0].0, "missing.ron");
+        assert_eq!(progress.fraction(), 1.0);
+    }
+}
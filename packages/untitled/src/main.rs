@@ -17,6 +17,13 @@ mod line_of_sight;
 mod inventory;
 mod debug;
 mod persistence;
+mod pool;
+mod interpolation;
+mod time_scale;
+mod depth;
+mod steering;
+mod physics_layers;
+mod loading;
 
 // Import everything we need
 use events::*;
@@ -37,16 +44,21 @@ fn main() {
     let size = 2048;
     let seed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
     let arg = std::env::args().into_iter().nth(1).unwrap_or_default();
+    // optional second arg is a symmetry, e.g. "mirror_x" or "rotate6"
+    let symmetry = std::env::args()
+        .nth(2)
+        .map(|name| crate::world::mapgen::Symmetry::parse(&name).expect("unknown symmetry"))
+        .unwrap_or_default();
     // if first arg is "roomy" then generate a roomy map and save to out/roomy_test.png
     if arg == "roomy" {
-        let map = crate::world::mapgen::roomy::roomy(size, seed);
+        let map = crate::world::mapgen::roomy::roomy_symmetric(size, seed, symmetry);
         crate::world::mapgen::save_png(&map, "out/roomy_test.png");
         let fill = crate::world::mapgen::operators::flood_fill_bool(&map, (map[0].len() / 2, map.len() / 2));
         crate::world::mapgen::save_png(&fill, "out/roomy_test_filled.png");
         return;
     }
     // otherwise generate a freeform map and save to out/freeform_test.png
-    let map = crate::world::mapgen::freeform::freeform(size, seed);
+    let map = crate::world::mapgen::freeform::freeform_symmetric(size, seed, symmetry);
     crate::world::mapgen::save_png(&map, "out/freeform_test.png");
     let fill = crate::world::mapgen::operators::flood_fill_bool(&map, (map[0].len() / 2, map.len() / 2));
     crate::world::mapgen::save_png(&fill, "out/freeform_test_filled.png");
@@ -65,40 +77,56 @@ fn main() {
         }))
 
         // Set fixed timestep to 20 Hz for more consistent behavior updates
-        .insert_resource(Time::<Fixed>::from_hz(20.0))
-
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+        .insert_resource(Time::<Fixed>::from_hz(constants::FIXED_UPDATE_HZ))
+
+        // Physics steps with gameplay on the fixed tick; rendering interpolates between ticks
+        .insert_resource(TimestepMode::Fixed {
+            dt: 1.0 / constants::FIXED_UPDATE_HZ as f32,
+            substeps: constants::PHYSICS_SUBSTEPS,
+        })
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0).in_fixed_schedule())
+        .add_plugins(interpolation::InterpolationPlugin)
+        .add_plugins(time_scale::TimeScalePlugin)
+        .add_plugins(depth::DepthPlugin)
+        .add_plugins(steering::SteeringPlugin)
         .add_plugins(TilemapPlugin)
         .add_plugins(persistence::PersistencePlugin)
+        .add_plugins(loading::LoadingPlugin)
         .add_plugins(PlayerPlugin)
+        .add_plugins(ui::theme::ThemePlugin)
+        .add_plugins(ui::tooltip::TooltipPlugin)
+        .add_plugins(ui::enemy_health_bars::EnemyHealthBarPlugin)
         .add_plugins(InventoryPlugin)
         .add_plugins(WorldPlugin)
         .add_plugins(DebugOverlayPlugin)
         .add_plugins(combat::FowPlugin)
-
-        .add_event::<ProjectileImpactEvent>()
-        .add_event::<DamageEvent>()
-        .add_event::<HitFlashEvent>()
-        .add_event::<GrenadeExplosionEvent>()
-        .add_event::<PortalActivationEvent>()
+        .add_plugins(combat::CombatPlugin)
+        .add_plugins(ui::combat_log::CombatLogPanelPlugin)
+        .add_plugins(ui::heal_numbers::HealNumbersPlugin)
+        .add_plugins(ui::hit_flash::HitFlashPlugin)
+        .add_plugins(ui::ability_bar::AbilityBarPlugin)
+        .add_plugins(ui::run_map::RunMapPlugin)
+        .add_plugins(ui::accessibility::AccessibilityPlugin)
+        .add_plugins(ui::persistence_banner::PersistenceBannerPlugin)
+        .add_plugins(ui::tutorial::TutorialPlugin)
+
+        .add_plugins(GameEventsPlugin)
 
         .insert_resource(GameState::default())
-        .insert_resource(ui::tooltip::TooltipState::default())
+        .init_resource::<GameSounds>()
+        .init_resource::<ui::UiUpdateStats>()
+        .init_resource::<line_of_sight::SightOccluders>()
 
         .add_systems(Startup, (
             disable_gravity,
             setup_health_bar,
-            load_sounds,
         ))
         .add_systems(Update, (
             handle_restart_button,
-
-            // Tooltip systems
-            ui::tooltip::cleanup_orphaned_tooltips,
-            ui::tooltip::handle_tooltip_hover.run_if(resource_equals(GameState::Playing)),
         ))
         .add_systems(FixedUpdate, (
             // Enemy systems
+            line_of_sight::refresh_sight_occluders.before(enemy_ai),
             enemy_ai.run_if(resource_equals(GameState::Playing)),
             laser_sight_system.run_if(resource_equals(GameState::Playing)),
 
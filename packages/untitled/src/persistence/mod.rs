@@ -1,7 +1,15 @@
 //! World persistence using SQLite
 //!
 //! This module handles saving and loading of chunk data (terrain tiles and FOW masks)
-//! to/from a SQLite database for seamless chunk unload/reload cycles.
+//! to/from a SQLite database for seamless chunk unload/reload cycles. The same
+//! database also keeps the arena leaderboard and which tutorial prompts have
+//! been seen.
+//!
+//! Persistence is optional. If the database can't be opened, or writes keep
+//! failing, the `ChunkDatabase` resource is left out, every system carries on
+//! without it (chunks regenerate from the seed when they reload), and
+//! `PersistenceStatus` says why so the UI can warn. Opening is retried a few
+//! times in case the problem was temporary.
 
 use bevy::prelude::*;
 use rusqlite::{Connection, Result as SqlResult};
@@ -11,11 +19,67 @@ use crate::world::chunks::{ChunkCoord, CHUNK_SIZE};
 use crate::world::tiles::TileType;
 use crate::world::MapId;
 
+/// Result of one arena run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaScore {
+    pub score: u32,
+    pub wave: u32,
+    pub kills: u32,
+    pub duration_secs: f32,
+}
+
+/// Result of one daily challenge attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyResult {
+    /// Days since the Unix epoch the challenge belongs to
+    pub day: i64,
+    pub depth_reached: u32,
+    pub died: bool,
+}
+
+/// Where the database lives, relative to the working directory
+const DATABASE_PATH: &str = "chunks.db";
+/// Writes that fail in a row before saving is turned off
+const MAX_FAILED_WRITES: u32 = 3;
+/// Times opening the database is retried in a session
+const MAX_RETRIES: u32 = 3;
+/// Seconds between attempts to reopen the database
+const RETRY_INTERVAL_SECS: f32 = 10.0;
+
 /// Resource wrapping a SQLite connection for chunk persistence
 #[derive(Resource, Clone)]
 pub struct ChunkDatabase {
     /// Thread-safe database connection
     connection: Arc<Mutex<Connection>>,
+    /// Shared by every clone, so a failure seen by one system counts for all
+    write_failures: Arc<Mutex<WriteFailures>>,
+}
+
+#[derive(Debug, Default)]
+struct WriteFailures {
+    in_a_row: u32,
+    last_error: Option<String>,
+}
+
+/// Whether chunks and scores are being saved this session
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PersistenceStatus {
+    /// Why nothing is being saved, or `None` while the database works
+    pub error: Option<String>,
+    /// Attempts left to reopen the database this session
+    pub retries_left: u32,
+}
+
+impl Default for PersistenceStatus {
+    fn default() -> Self {
+        Self { error: None, retries_left: MAX_RETRIES }
+    }
+}
+
+impl PersistenceStatus {
+    pub fn is_available(&self) -> bool {
+        self.error.is_none()
+    }
 }
 
 impl ChunkDatabase {
@@ -47,11 +111,146 @@ impl ChunkDatabase {
             [],
         )?;
 
+        // Create arena leaderboard table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS arena_scores (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                score INTEGER NOT NULL,
+                wave INTEGER NOT NULL,
+                kills INTEGER NOT NULL,
+                duration_secs REAL NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create daily challenge results table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS daily_results (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                day INTEGER NOT NULL,
+                depth_reached INTEGER NOT NULL,
+                died INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create seen tutorial prompts table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS seen_tutorial_prompts (
+                id TEXT PRIMARY KEY
+            )",
+            [],
+        )?;
+
         Ok(Self {
             connection: Arc::new(Mutex::new(conn)),
+            write_failures: Arc::new(Mutex::new(WriteFailures::default())),
         })
     }
 
+    /// The last write error, once writes have failed `MAX_FAILED_WRITES` times in a row
+    pub fn persistent_write_error(&self) -> Option<String> {
+        let failures = self.write_failures.lock().unwrap();
+        if failures.in_a_row >= MAX_FAILED_WRITES {
+            failures.last_error.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Count a write towards `persistent_write_error`
+    fn track_write<T>(&self, result: SqlResult<T>) -> SqlResult<T> {
+        let mut failures = self.write_failures.lock().unwrap();
+        match &result {
+            Ok(_) => *failures = WriteFailures::default(),
+            Err(e) => {
+                failures.in_a_row += 1;
+                failures.last_error = Some(e.to_string());
+            }
+        }
+        result
+    }
+
+    /// Save the result of a daily challenge attempt
+    pub fn record_daily_result(&self, result: &DailyResult) -> SqlResult<()> {
+        let conn = self.connection.lock().unwrap();
+        let result = conn.execute(
+            "INSERT INTO daily_results (day, depth_reached, died) VALUES (?1, ?2, ?3)",
+            rusqlite::params![result.day, result.depth_reached, result.died],
+        );
+        self.track_write(result).map(|_| ())
+    }
+
+    /// Deepest attempt for a day, preferring runs that survived
+    pub fn best_daily_result(&self, day: i64) -> SqlResult<Option<DailyResult>> {
+        let conn = self.connection.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT day, depth_reached, died FROM daily_results WHERE day = ?1
+             ORDER BY depth_reached DESC, died ASC LIMIT 1",
+            rusqlite::params![day],
+            |row| {
+                Ok(DailyResult {
+                    day: row.get(0)?,
+                    depth_reached: row.get(1)?,
+                    died: row.get(2)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(result) => Ok(Some(result)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Save the result of an arena run
+    pub fn record_arena_score(&self, result: &ArenaScore) -> SqlResult<()> {
+        let conn = self.connection.lock().unwrap();
+        let result = conn.execute(
+            "INSERT INTO arena_scores (score, wave, kills, duration_secs) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![result.score, result.wave, result.kills, result.duration_secs],
+        );
+        self.track_write(result).map(|_| ())
+    }
+
+    /// Best arena runs, highest score first
+    pub fn top_arena_scores(&self, limit: u32) -> SqlResult<Vec<ArenaScore>> {
+        let conn = self.connection.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT score, wave, kills, duration_secs FROM arena_scores ORDER BY score DESC, id ASC LIMIT ?1"
+        )?;
+
+        let rows = stmt.query_map(rusqlite::params![limit], |row| {
+            Ok(ArenaScore {
+                score: row.get(0)?,
+                wave: row.get(1)?,
+                kills: row.get(2)?,
+                duration_secs: row.get(3)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// Remember that a tutorial prompt was shown
+    pub fn mark_tutorial_prompt_seen(&self, id: &str) -> SqlResult<()> {
+        let conn = self.connection.lock().unwrap();
+        let result = conn.execute(
+            "INSERT OR IGNORE INTO seen_tutorial_prompts (id) VALUES (?1)",
+            rusqlite::params![id],
+        );
+        self.track_write(result).map(|_| ())
+    }
+
+    /// Ids of every tutorial prompt shown so far
+    pub fn seen_tutorial_prompts(&self) -> SqlResult<Vec<String>> {
+        let conn = self.connection.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id FROM seen_tutorial_prompts")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
     /// Save terrain chunk data to database
     pub fn save_terrain_chunk(
         &self,
@@ -69,12 +268,12 @@ impl ChunkDatabase {
             }
         }
 
-        conn.execute(
+        let result = conn.execute(
             "INSERT OR REPLACE INTO terrain_chunks (map_id, chunk_x, chunk_y, tiles) VALUES (?1, ?2, ?3, ?4)",
             rusqlite::params![map_id.to_db_key(), chunk_coord.x, chunk_coord.y, &bytes],
-        )?;
+        );
 
-        Ok(())
+        self.track_write(result).map(|_| ())
     }
 
     /// Load terrain chunk data from database
@@ -122,18 +321,14 @@ impl ChunkDatabase {
     ) -> SqlResult<()> {
         let conn = self.connection.lock().unwrap();
 
-        // Flatten vision data to bytes
-        let mut bytes = Vec::with_capacity(vision.len() * vision[0].len());
-        for row in vision.iter() {
-            bytes.extend_from_slice(row);
-        }
+        let bytes = encode_fow_blob(vision);
 
-        conn.execute(
+        let result = conn.execute(
             "INSERT OR REPLACE INTO fow_chunks (map_id, chunk_x, chunk_y, vision) VALUES (?1, ?2, ?3, ?4)",
             rusqlite::params![map_id.to_db_key(), chunk_coord.x, chunk_coord.y, &bytes],
-        )?;
+        );
 
-        Ok(())
+        self.track_write(result).map(|_| ())
     }
 
     /// Load FOW chunk vision data from database
@@ -153,14 +348,9 @@ impl ChunkDatabase {
             |row| {
                 let bytes: Vec<u8> = row.get(0)?;
 
-                // Reconstruct 2D vision array
-                let size = (bytes.len() as f32).sqrt() as usize;
-                let mut vision = vec![vec![0u8; size]; size];
-                for (i, byte) in bytes.iter().enumerate() {
-                    let y = i / size;
-                    let x = i % size;
-                    vision[y][x] = *byte;
-                }
+                let vision = decode_fow_blob(&bytes).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Blob, e.into())
+                })?;
 
                 Ok(vision)
             },
@@ -176,21 +366,21 @@ impl ChunkDatabase {
     /// Delete terrain chunk from database (optional cleanup)
     pub fn delete_terrain_chunk(&self, chunk_coord: ChunkCoord) -> SqlResult<()> {
         let conn = self.connection.lock().unwrap();
-        conn.execute(
+        let result = conn.execute(
             "DELETE FROM terrain_chunks WHERE chunk_x = ?1 AND chunk_y = ?2",
             rusqlite::params![chunk_coord.x, chunk_coord.y],
-        )?;
-        Ok(())
+        );
+        self.track_write(result).map(|_| ())
     }
 
     /// Delete FOW chunk from database (optional cleanup)
     pub fn delete_fow_chunk(&self, chunk_coord: ChunkCoord) -> SqlResult<()> {
         let conn = self.connection.lock().unwrap();
-        conn.execute(
+        let result = conn.execute(
             "DELETE FROM fow_chunks WHERE chunk_x = ?1 AND chunk_y = ?2",
             rusqlite::params![chunk_coord.x, chunk_coord.y],
-        )?;
-        Ok(())
+        );
+        self.track_write(result).map(|_| ())
     }
 
     /// Get count of saved terrain chunks (for debugging)
@@ -216,6 +406,81 @@ impl ChunkDatabase {
     }
 }
 
+/// Marks a FOW blob with a header; older blobs are bare square grids of bytes
+const FOW_BLOB_MAGIC: &[u8; 3] = b"FOW";
+/// Current FOW blob layout
+const FOW_BLOB_VERSION: u8 = 1;
+/// Magic, version, width and height (u16, little endian), bits per cell
+const FOW_HEADER_LEN: usize = 9;
+/// Bits per cell written by `encode_fow_blob`; vision levels use the full byte
+const FOW_BITS_PER_CELL: u8 = 8;
+
+/// Serialize a vision grid with its dimensions in a header
+fn encode_fow_blob(vision: &[Vec<u8>]) -> Vec<u8> {
+    let height = vision.len();
+    let width = vision.first().map_or(0, Vec::len);
+
+    let mut bytes = Vec::with_capacity(FOW_HEADER_LEN + width * height);
+    bytes.extend_from_slice(FOW_BLOB_MAGIC);
+    bytes.push(FOW_BLOB_VERSION);
+    bytes.extend_from_slice(&(width as u16).to_le_bytes());
+    bytes.extend_from_slice(&(height as u16).to_le_bytes());
+    bytes.push(FOW_BITS_PER_CELL);
+    for row in vision {
+        bytes.extend_from_slice(row);
+    }
+    bytes
+}
+
+/// Deserialize a vision grid written by `encode_fow_blob`, or a legacy headerless square one
+///
+/// Cells packed below 8 bits are read most significant bits first, each row
+/// starting on a fresh byte.
+fn decode_fow_blob(bytes: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    if !bytes.starts_with(FOW_BLOB_MAGIC) || bytes.len() < FOW_HEADER_LEN {
+        return decode_legacy_fow_blob(bytes);
+    }
+
+    let version = bytes[3];
+    let width = u16::from_le_bytes([bytes[4], bytes[5]]) as usize;
+    let height = u16::from_le_bytes([bytes[6], bytes[7]]) as usize;
+    let bits = bytes[8] as usize;
+    let row_bytes = (width * bits).div_ceil(8);
+    let cells = &bytes[FOW_HEADER_LEN..];
+
+    if version != FOW_BLOB_VERSION || !matches!(bits, 1 | 2 | 4 | 8) || cells.len() != row_bytes * height {
+        // A legacy grid whose first cells happen to spell the magic
+        return decode_legacy_fow_blob(bytes).map_err(|_| {
+            format!("FOW blob version {} with {}x{} cells of {} bits doesn't match its {} bytes",
+                version, width, height, bits, bytes.len())
+        });
+    }
+
+    let mask = ((1u16 << bits) - 1) as u8;
+    Ok(cells
+        .chunks(row_bytes.max(1))
+        .take(height)
+        .map(|row| {
+            (0..width)
+                .map(|x| {
+                    let bit = x * bits;
+                    let shift = 8 - bits - bit % 8;
+                    (row[bit / 8] >> shift) & mask
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Blobs saved before the header: one byte per cell of a square grid
+fn decode_legacy_fow_blob(bytes: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let size = (bytes.len() as f64).sqrt().round() as usize;
+    if size * size != bytes.len() {
+        return Err(format!("Legacy FOW blob of {} bytes isn't a square grid", bytes.len()));
+    }
+    Ok(bytes.chunks(size.max(1)).map(<[u8]>::to_vec).collect())
+}
+
 impl TileType {
     /// Convert u8 back to TileType (must match the as u8 conversion)
     fn from_u8(value: u8) -> Self {
@@ -228,19 +493,65 @@ impl TileType {
 }
 
 /// System to initialize the chunk database
-fn initialize_chunk_database(mut commands: Commands) {
-    // Store database in the current directory as "chunks.db"
-    match ChunkDatabase::new("chunks.db") {
+fn initialize_chunk_database(mut commands: Commands, mut status: ResMut<PersistenceStatus>) {
+    match ChunkDatabase::new(DATABASE_PATH) {
         Ok(db) => {
             info!("Chunk database initialized successfully");
             commands.insert_resource(db);
         }
         Err(e) => {
-            error!("Failed to initialize chunk database: {}", e);
+            error!("Failed to initialize chunk database, nothing will be saved: {}", e);
+            status.error = Some(e.to_string());
+        }
+    }
+}
+
+/// Try to open the database again every `RETRY_INTERVAL_SECS` while saving is off
+fn retry_chunk_database(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut since_attempt: Local<f32>,
+    mut status: ResMut<PersistenceStatus>,
+) {
+    if status.is_available() || status.retries_left == 0 {
+        return;
+    }
+
+    *since_attempt += time.delta_secs();
+    if *since_attempt < RETRY_INTERVAL_SECS {
+        return;
+    }
+    *since_attempt = 0.0;
+    status.retries_left -= 1;
+
+    match ChunkDatabase::new(DATABASE_PATH) {
+        Ok(db) => {
+            info!("Chunk database reopened, saving is back on");
+            commands.insert_resource(db);
+            status.error = None;
+        }
+        Err(e) => {
+            warn!("Retrying chunk database failed ({} retries left): {}", status.retries_left, e);
+            status.error = Some(e.to_string());
         }
     }
 }
 
+/// Drop the database once writes keep failing, so systems stop trying and the UI can warn
+fn watch_database_writes(
+    mut commands: Commands,
+    database: Option<Res<ChunkDatabase>>,
+    mut status: ResMut<PersistenceStatus>,
+) {
+    let Some(error) = database.and_then(|db| db.persistent_write_error()) else {
+        return;
+    };
+
+    error!("Chunk database writes keep failing, saving is off: {}", error);
+    commands.remove_resource::<ChunkDatabase>();
+    status.error = Some(error);
+}
+
 /// Plugin for chunk persistence using SQLite
 ///
 /// This plugin initializes the SQLite database connection on startup
@@ -250,7 +561,109 @@ pub struct PersistencePlugin;
 impl Plugin for PersistencePlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<PersistenceStatus>()
             // Initialize the database on startup
-            .add_systems(Startup, initialize_chunk_database);
+            .add_systems(Startup, initialize_chunk_database)
+            .add_systems(Update, (watch_database_writes, retry_chunk_database).chain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fow_blob_keeps_non_square_dimensions() {
+        let vision = vec![vec![0, 255, 7], vec![1, 2, 3]];
+        let bytes = encode_fow_blob(&vision);
+
+        assert_eq!(bytes.len(), FOW_HEADER_LEN + 6);
+        assert_eq!(decode_fow_blob(&bytes), Ok(vision));
+    }
+
+    #[test]
+    fn test_legacy_fow_blob_still_loads() {
+        assert_eq!(decode_fow_blob(&[1, 2, 3, 4]), Ok(vec![vec![1, 2], vec![3, 4]]));
+        // Starts like a header, but is a plain 3x3 grid
+        assert_eq!(decode_fow_blob(b"FOW\x01abcde").unwrap().len(), 3);
+        assert!(decode_fow_blob(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_packed_fow_cells_are_unpacked() {
+        // 2 bits per cell: 3 cells per row, 2 rows, each row padded to a byte
+        let mut bytes = FOW_BLOB_MAGIC.to_vec();
+        bytes.extend_from_slice(&[FOW_BLOB_VERSION, 3, 0, 2, 0, 2]);
+        bytes.extend_from_slice(&[0b11_01_10_00, 0b00_10_01_00]);
+
+        assert_eq!(decode_fow_blob(&bytes), Ok(vec![vec![3, 1, 2], vec![0, 2, 1]]));
+    }
+
+    /// An in-memory database that refuses writes, like one in a read-only directory
+    fn read_only_database() -> ChunkDatabase {
+        let db = ChunkDatabase::new(":memory:").unwrap();
+        db.connection.lock().unwrap().execute_batch("PRAGMA query_only = ON").unwrap();
+        db
+    }
+
+    fn empty_tiles() -> [[TileType; CHUNK_SIZE as usize]; CHUNK_SIZE as usize] {
+        [[TileType::Floor; CHUNK_SIZE as usize]; CHUNK_SIZE as usize]
+    }
+
+    #[test]
+    fn test_database_in_unwritable_place_fails_to_open() {
+        // A path under a file can't be created even when running as root, unlike a chmod'ed directory
+        let blocker = std::env::temp_dir().join(format!("untitled-persistence-test-{}", std::process::id()));
+        std::fs::write(&blocker, b"").unwrap();
+
+        let result = ChunkDatabase::new(blocker.join("chunks.db").to_str().unwrap());
+        std::fs::remove_file(&blocker).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_repeated_write_failures_are_reported() {
+        let db = read_only_database();
+        let map_id = MapId::new(1);
+
+        for _ in 1..MAX_FAILED_WRITES {
+            assert!(db.save_terrain_chunk(map_id, ChunkCoord::ZERO, &empty_tiles()).is_err());
+        }
+        assert!(db.persistent_write_error().is_none());
+        assert!(db.record_arena_score(&ArenaScore { score: 1, wave: 1, kills: 1, duration_secs: 1.0 }).is_err());
+        assert!(db.persistent_write_error().unwrap().contains("readonly"));
+
+        db.connection.lock().unwrap().execute_batch("PRAGMA query_only = OFF").unwrap();
+        assert!(db.save_terrain_chunk(map_id, ChunkCoord::ZERO, &empty_tiles()).is_ok());
+        assert!(db.persistent_write_error().is_none());
+    }
+
+    #[test]
+    fn test_failing_database_is_dropped() {
+        let db = read_only_database();
+        for _ in 0..MAX_FAILED_WRITES {
+            let _ = db.delete_fow_chunk(ChunkCoord::ZERO);
+        }
+
+        let mut app = App::new();
+        app.insert_resource(db)
+            .init_resource::<PersistenceStatus>()
+            .add_systems(Update, watch_database_writes);
+        app.update();
+
+        assert!(!app.world().contains_resource::<ChunkDatabase>());
+        assert!(!app.world().resource::<PersistenceStatus>().is_available());
+    }
+
+    #[test]
+    fn test_seen_tutorial_prompts_are_kept_once() {
+        let db = ChunkDatabase::new(":memory:").unwrap();
+        db.mark_tutorial_prompt_seen("move").unwrap();
+        db.mark_tutorial_prompt_seen("move").unwrap();
+        db.mark_tutorial_prompt_seen("dash").unwrap();
+
+        let mut seen = db.seen_tutorial_prompts().unwrap();
+        seen.sort();
+        assert_eq!(seen, vec!["dash", "move"]);
     }
 }
@@ -0,0 +1,59 @@
+//! Rapier collision groups
+//!
+//! Every collider belongs to one layer and lists the layers it can touch.
+//! Rapier only generates contacts between two colliders when each one is in
+//! the other's filter, so pairs like a bullet and its own team are discarded
+//! before any gameplay code sees them.
+use bevy_rapier2d::prelude::*;
+
+use crate::components::Team;
+
+pub const PLAYER: Group = Group::GROUP_1;
+pub const ENEMY: Group = Group::GROUP_2;
+pub const PLAYER_PROJECTILE: Group = Group::GROUP_3;
+pub const ENEMY_PROJECTILE: Group = Group::GROUP_4;
+pub const TERRAIN: Group = Group::GROUP_5;
+
+pub fn player() -> CollisionGroups {
+    CollisionGroups::new(PLAYER, ENEMY.union(ENEMY_PROJECTILE).union(TERRAIN))
+}
+
+pub fn enemy() -> CollisionGroups {
+    CollisionGroups::new(ENEMY, PLAYER.union(ENEMY).union(PLAYER_PROJECTILE).union(TERRAIN))
+}
+
+/// Projectiles and grenades: hit the other team and walls, pass through their own side
+pub fn projectile(team: Team) -> CollisionGroups {
+    match team {
+        Team::Player => CollisionGroups::new(PLAYER_PROJECTILE, ENEMY.union(TERRAIN)),
+        Team::Enemy => CollisionGroups::new(ENEMY_PROJECTILE, PLAYER.union(TERRAIN)),
+    }
+}
+
+pub fn terrain() -> CollisionGroups {
+    CollisionGroups::new(TERRAIN, Group::ALL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interacts(a: CollisionGroups, b: CollisionGroups) -> bool {
+        a.memberships.intersects(b.filters) && b.memberships.intersects(a.filters)
+    }
+
+    #[test]
+    fn test_projectiles_skip_their_own_team() {
+        assert!(!interacts(projectile(Team::Player), player()));
+        assert!(!interacts(projectile(Team::Enemy), enemy()));
+        assert!(!interacts(projectile(Team::Player), projectile(Team::Enemy)));
+    }
+
+    #[test]
+    fn test_projectiles_hit_opponents_and_walls() {
+        assert!(interacts(projectile(Team::Player), enemy()));
+        assert!(interacts(projectile(Team::Enemy), player()));
+        assert!(interacts(projectile(Team::Player), terrain()));
+        assert!(interacts(projectile(Team::Enemy), terrain()));
+    }
+}
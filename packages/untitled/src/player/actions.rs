@@ -23,6 +23,7 @@ pub enum PlayerAction {
     Shoot,
     ThrowGrenade,
     Reload,
+    UseAbility(usize), // Ability slot index
 
     // Interaction
     Interact,
@@ -55,9 +56,13 @@ pub struct PlayerInputBindings {
     pub shoot: MouseButton,
     pub throw_grenade: MouseButton,
     pub reload: KeyCode,
+    pub abilities: [KeyCode; crate::constants::ABILITY_SLOT_COUNT],
 
     // Interaction
     pub interact: KeyCode,
+    /// Consume the hovered inventory item
+    pub use_item: KeyCode,
+    pub toggle_inventory: KeyCode,
 }
 
 impl Default for PlayerInputBindings {
@@ -74,13 +79,22 @@ impl Default for PlayerInputBindings {
             shoot: MouseButton::Left,
             throw_grenade: MouseButton::Right,
             reload: KeyCode::KeyR,
+            abilities: [KeyCode::KeyQ, KeyCode::KeyF, KeyCode::KeyC],
 
             // Interaction
             interact: KeyCode::KeyE,
+            use_item: KeyCode::KeyU,
+            toggle_inventory: KeyCode::Tab,
         }
     }
 }
 
+/// Short key label for prompts and hotbars, e.g. `KeyQ` -> `Q`
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key").map(str::to_string).unwrap_or(name)
+}
+
 impl PlayerActionEvent {
     pub fn new(action: PlayerAction, state: ActionState, value: f32) -> Self {
         Self {
@@ -0,0 +1,134 @@
+//! Input buffering
+//!
+//! Gameplay runs on the 20 Hz fixed tick, so a press that lands while an action
+//! is still illegal (dashing again mid-dash, clicking just before the gun is
+//! ready, casting a frame before the cooldown ends) would otherwise be dropped.
+//! Presses are kept in a short queue instead, and the fixed-step systems take
+//! them from it the first tick the action becomes possible.
+use bevy::prelude::*;
+
+use super::actions::{PlayerAction, PlayerActionEvent};
+
+/// How long each action stays buffered, in seconds; zero disables buffering
+#[derive(Resource, Debug, Clone)]
+pub struct InputBufferConfig {
+    pub dash: f32,
+    pub shoot: f32,
+    pub reload: f32,
+    pub ability: f32,
+}
+
+impl Default for InputBufferConfig {
+    fn default() -> Self {
+        Self {
+            dash: 0.15,
+            shoot: 0.1,
+            reload: 0.25,
+            ability: 0.2,
+        }
+    }
+}
+
+impl InputBufferConfig {
+    pub fn window(&self, action: &PlayerAction) -> f32 {
+        match action {
+            PlayerAction::Dash => self.dash,
+            PlayerAction::Shoot => self.shoot,
+            PlayerAction::Reload => self.reload,
+            PlayerAction::UseAbility(_) => self.ability,
+            _ => 0.0,
+        }
+    }
+}
+
+/// A press waiting for its action to become legal
+#[derive(Debug, Clone)]
+pub struct BufferedAction {
+    pub action: PlayerAction,
+    pub world_position: Option<Vec2>,
+    /// Seconds of game time left before the press is dropped
+    pub remaining: f32,
+}
+
+/// Queue of recent presses, oldest first
+#[derive(Resource, Debug, Default)]
+pub struct InputBuffer {
+    pub actions: Vec<BufferedAction>,
+}
+
+impl InputBuffer {
+    /// Buffer a press, replacing any older press of the same action
+    pub fn push(&mut self, action: PlayerAction, world_position: Option<Vec2>, window: f32) {
+        if window <= 0.0 {
+            return;
+        }
+        self.actions.retain(|buffered| buffered.action != action);
+        self.actions.push(BufferedAction { action, world_position, remaining: window });
+    }
+
+    /// Remove and return the buffered press of `action`, if it hasn't expired
+    pub fn take(&mut self, action: &PlayerAction) -> Option<BufferedAction> {
+        let index = self.actions.iter().position(|buffered| buffered.action == *action)?;
+        Some(self.actions.remove(index))
+    }
+
+    /// Age every press by `delta` seconds and drop the expired ones
+    pub fn tick(&mut self, delta: f32) {
+        for buffered in &mut self.actions {
+            buffered.remaining -= delta;
+        }
+        self.actions.retain(|buffered| buffered.remaining > 0.0);
+    }
+}
+
+/// Queue the presses written by `player_input_system` this frame
+pub fn buffer_player_actions(
+    mut action_events: EventReader<PlayerActionEvent>,
+    config: Res<InputBufferConfig>,
+    mut buffer: ResMut<InputBuffer>,
+) {
+    for event in action_events.read() {
+        if event.just_started() {
+            buffer.push(event.action.clone(), event.world_position, config.window(&event.action));
+        }
+    }
+}
+
+/// Expire buffered presses after this tick's consumers had their chance
+pub fn expire_buffered_actions(time: Res<Time>, mut buffer: ResMut<InputBuffer>) {
+    buffer.tick(time.delta_secs());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_press_survives_for_its_window() {
+        let mut buffer = InputBuffer::default();
+        buffer.push(PlayerAction::Dash, None, 0.15);
+
+        // One 20 Hz tick of dash recovery, still buffered
+        buffer.tick(0.05);
+        assert_eq!(buffer.actions.len(), 1);
+
+        buffer.tick(0.05);
+        buffer.tick(0.05);
+        buffer.tick(0.05);
+        assert!(buffer.take(&PlayerAction::Dash).is_none());
+    }
+
+    #[test]
+    fn test_take_consumes_latest_press() {
+        let config = InputBufferConfig::default();
+        let mut buffer = InputBuffer::default();
+        buffer.push(PlayerAction::UseAbility(1), Some(Vec2::X), config.window(&PlayerAction::UseAbility(1)));
+        buffer.push(PlayerAction::UseAbility(1), Some(Vec2::Y), config.window(&PlayerAction::UseAbility(1)));
+        buffer.push(PlayerAction::Interact, None, config.window(&PlayerAction::Interact));
+
+        assert!(buffer.take(&PlayerAction::Interact).is_none());
+        assert!(buffer.take(&PlayerAction::UseAbility(0)).is_none());
+        assert_eq!(buffer.take(&PlayerAction::UseAbility(1)).unwrap().world_position, Some(Vec2::Y));
+        assert!(buffer.take(&PlayerAction::UseAbility(1)).is_none());
+    }
+}
@@ -52,6 +52,10 @@ impl Dash {
 #[reflect(Component)]
 pub struct GrenadeThrower {
     pub cooldown_timer: Timer,
+    /// Whether the throw button is held down (aiming, grenade leaves on release)
+    pub is_aiming: bool,
+    /// Last world position aimed at while holding the throw button
+    pub aim_target: Option<Vec2>,
 }
 
 impl GrenadeThrower {
@@ -59,6 +63,8 @@ impl GrenadeThrower {
         use crate::constants::*;
         Self {
             cooldown_timer: Timer::from_seconds(GRENADE_THROW_COOLDOWN, TimerMode::Once),
+            is_aiming: false,
+            aim_target: None,
         }
     }
 
@@ -79,6 +85,9 @@ pub struct PlayerBundle {
     pub health: crate::components::Health,
     pub dash: Dash,
     pub grenade_thrower: GrenadeThrower,
+    pub energy: crate::combat::Energy,
+    pub crits: crate::combat::CriticalHits,
+    pub abilities: crate::combat::AbilitySlots,
     pub inventory: crate::inventory::Inventory,
     pub chunk_loader: crate::world::chunks::ChunkLoader,
     pub fow_revealer: crate::combat::FowRevealer,
@@ -92,9 +101,12 @@ pub struct PlayerBundle {
     // Physics components
     pub rigid_body: bevy_rapier2d::prelude::RigidBody,
     pub collider: bevy_rapier2d::prelude::Collider,
+    pub collision_groups: bevy_rapier2d::prelude::CollisionGroups,
     pub velocity: bevy_rapier2d::prelude::Velocity,
     pub locked_axes: bevy_rapier2d::prelude::LockedAxes,
     pub active_events: bevy_rapier2d::prelude::ActiveEvents,
+    pub interpolation: crate::interpolation::InterpolatedTransform,
+    pub y_sort: crate::depth::YSort,
 }
 
 impl PlayerBundle {
@@ -113,6 +125,13 @@ impl PlayerBundle {
             health: crate::components::Health::new(PLAYER_MAX_HEALTH),
             dash: Dash::new(),
             grenade_thrower: GrenadeThrower::new(),
+            energy: crate::combat::Energy::new(PLAYER_MAX_ENERGY, PLAYER_ENERGY_REGEN),
+            crits: crate::combat::CriticalHits { chance: PLAYER_CRIT_CHANCE, multiplier: PLAYER_CRIT_MULTIPLIER },
+            abilities: crate::combat::AbilitySlots::new([
+                crate::combat::AbilityId(1),
+                crate::combat::AbilityId(2),
+                crate::combat::AbilityId(3),
+            ]),
             inventory: crate::inventory::Inventory::player_inventory(),
             chunk_loader: crate::world::chunks::ChunkLoader::new(16),
             fow_revealer: crate::combat::FowRevealer::new(12, 32),
@@ -126,9 +145,12 @@ impl PlayerBundle {
             // Physics components
             rigid_body: bevy_rapier2d::prelude::RigidBody::Dynamic,
             collider: bevy_rapier2d::prelude::Collider::ball(PLAYER_RADIUS),
+            collision_groups: crate::physics_layers::player(),
             velocity: bevy_rapier2d::prelude::Velocity::zero(),
             locked_axes: bevy_rapier2d::prelude::LockedAxes::ROTATION_LOCKED,
             active_events: bevy_rapier2d::prelude::ActiveEvents::COLLISION_EVENTS,
+            interpolation: crate::interpolation::InterpolatedTransform::default(),
+            y_sort: crate::depth::YSort::with_offset(-PLAYER_RADIUS),
         }
     }
 }
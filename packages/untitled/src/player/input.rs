@@ -93,6 +93,17 @@ fn handle_combat_input(
         action_events.write(PlayerActionEvent::new(PlayerAction::Reload, ActionState::Started, 1.0));
     }
 
+    // Ability slots, aimed at the cursor
+    for (slot, key) in bindings.abilities.iter().enumerate() {
+        if keyboard.just_pressed(*key) {
+            let mut event = PlayerActionEvent::new(PlayerAction::UseAbility(slot), ActionState::Started, 1.0);
+            if let Some(world_pos) = get_mouse_world_position(windows, cameras) {
+                event = event.with_world_position(world_pos);
+            }
+            action_events.write(event);
+        }
+    }
+
     // Interaction actions
     if keyboard.just_pressed(bindings.interact) {
         action_events.write(PlayerActionEvent::new(PlayerAction::Interact, ActionState::Started, 1.0));
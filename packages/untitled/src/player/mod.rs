@@ -5,12 +5,14 @@ pub mod systems;
 pub mod resources;
 pub mod actions;
 pub mod input;
+pub mod buffer;
 
 pub use components::*;
 pub use systems::*;
 pub use resources::*;
 pub use actions::*;
 pub use input::*;
+pub use buffer::*;
 
 /// Plugin that handles all player-related functionality
 pub struct PlayerPlugin;
@@ -28,22 +30,34 @@ impl Plugin for PlayerPlugin {
             .insert_resource(PlayerConfig::default())
             .insert_resource(PlayerInputBindings::default())
             .insert_resource(CameraZoom::default())
+            .init_resource::<InputBufferConfig>()
+            .init_resource::<InputBuffer>()
 
             // Add player action events
             .add_event::<PlayerActionEvent>()
 
             // Add input processing system first
-            .add_systems(PreUpdate, player_input_system)
-
-            // Add player systems
+            .add_systems(PreUpdate, (
+                player_input_system
+                    .run_if(not(resource_exists::<crate::resources::PhotoModeActive>))
+                    .run_if(in_state(crate::world::SceneLoadState::Ready)),
+                buffer_player_actions,
+            ).chain())
+
+            // Add player systems, expiring buffered presses once they had a chance to fire
             .add_systems(FixedUpdate, (
-                player_movement,
-                shoot_projectiles,
-                throw_grenades,
-            ))
+                (
+                    player_movement,
+                    shoot_projectiles,
+                    throw_grenades,
+                    cast_abilities,
+                ),
+                expire_buffered_actions,
+            ).chain())
             .add_systems(Update, (
-                camera_follow,
+                camera_follow.run_if(not(resource_exists::<crate::resources::PhotoModeActive>)),
                 handle_camera_zoom,
+                draw_grenade_preview,
             ));
     }
 }
@@ -6,12 +6,15 @@ use crate::{
     constants::*,
     sounds::*,
     player::resources::*,
+    interpolation::InterpolatedTransform,
+    physics_layers,
 };
 
 // Add missing constant that was used in player shooting
 const PROJECTILE_MOMENTUM_TRANSFER: f32 = 0.5;
 use super::{Player, Dash, GrenadeThrower, FireTimer, PlayerConfig};
 use super::actions::{PlayerActionEvent, PlayerAction};
+use super::buffer::InputBuffer;
 
 /// Handles player movement based on player action events
 pub fn player_movement(
@@ -19,6 +22,7 @@ pub fn player_movement(
     mut query: Query<(&mut Velocity, &mut Dash), With<Player>>,
     time: Res<Time>,
     config: Res<PlayerConfig>,
+    mut buffer: ResMut<InputBuffer>,
 ) {
     for (mut velocity, mut dash) in query.iter_mut() {
         // Update dash timers
@@ -38,7 +42,6 @@ pub fn player_movement(
 
         // Process action events
         let mut movement = Vec2::ZERO;
-        let mut dash_requested = false;
         let mut dash_direction = Vec2::ZERO;
 
         for action_event in action_events.read() {
@@ -59,15 +62,12 @@ pub fn player_movement(
                     movement.x += action_event.value;
                     dash_direction.x += action_event.value;
                 }
-                PlayerAction::Dash if action_event.just_started() => {
-                    dash_requested = true;
-                }
                 _ => {}
             }
         }
 
-        // Handle dash
-        if dash_requested && dash_direction != Vec2::ZERO {
+        // Handle dash, pressed now or buffered during the previous dash or cooldown
+        if dash.can_dash() && dash_direction != Vec2::ZERO && buffer.take(&PlayerAction::Dash).is_some() {
             dash.start_dash(dash_direction.normalize());
         }
 
@@ -93,63 +93,75 @@ pub fn shoot_projectiles(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut action_events: EventReader<PlayerActionEvent>,
-    player_query: Query<(&Transform, &Velocity), (With<Player>, Without<Camera>)>,
+    player_query: Query<(Entity, &Transform, &Velocity), (With<Player>, Without<Camera>)>,
     mut fire_timer: ResMut<FireTimer>,
     game_sounds: Res<GameSounds>,
     time: Res<Time>,
+    mut buffer: ResMut<InputBuffer>,
 ) {
     // Update fire timer
     fire_timer.timer.tick(time.delta());
 
-    // Process shoot action events
-    for action_event in action_events.read() {
-        if matches!(action_event.action, PlayerAction::Shoot) &&
-           (action_event.just_started() || action_event.is_active()) &&
-           fire_timer.timer.finished() {
-
-            if let Ok((player_transform, player_velocity)) = player_query.single() {
-                let player_pos = player_transform.translation.truncate();
-
-                // Use world position from action event if available, otherwise default upward
-                let shoot_direction = if let Some(target_pos) = action_event.world_position {
-                    (target_pos - player_pos).normalize()
-                } else {
-                    Vec2::Y
-                };
-
-                // Calculate spawn position on the edge of the player closest to the target
-                let spawn_offset = shoot_direction * (PLAYER_RADIUS + PROJECTILE_SIZE * 2.0 + 5.0);
-                let spawn_pos = player_pos + spawn_offset;
-
-                // Calculate projectile velocity: base velocity + player momentum
-                let projectile_velocity = (shoot_direction * PROJECTILE_SPEED) +
-                    (player_velocity.linvel * PROJECTILE_MOMENTUM_TRANSFER);
-
-                // Spawn projectile
-                commands.spawn((
-                    Mesh2d(meshes.add(Circle::new(PROJECTILE_SIZE))),
-                    MeshMaterial2d(materials.add(Color::WHITE)),
-                    Transform::from_translation(spawn_pos.extend(0.1)),
-                    Projectile {
-                        lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
-                        team: Team::Player,
-                    },
-                    RigidBody::Dynamic,
-                    Collider::ball(PROJECTILE_SIZE),
-                    Velocity::linear(projectile_velocity),
-                    ActiveEvents::COLLISION_EVENTS,
-                ));
-
-                // Play shooting sound
-                commands.spawn((
-                    AudioPlayer(game_sounds.gun_01.clone()),
-                    PlaybackSettings::DESPAWN,
-                ));
-
-                // Reset fire timer
-                fire_timer.timer.reset();
-            }
-        }
+    // Aim from the latest held shoot event, or from a click buffered during cooldown
+    let held_aim = action_events.read()
+        .filter(|action_event| matches!(action_event.action, PlayerAction::Shoot) && action_event.is_active())
+        .map(|action_event| action_event.world_position)
+        .last();
+
+    if !fire_timer.timer.finished() {
+        return;
+    }
+
+    let buffered_aim = buffer.take(&PlayerAction::Shoot).map(|buffered| buffered.world_position);
+    let Some(target) = held_aim.or(buffered_aim) else {
+        return;
+    };
+
+    if let Ok((player, player_transform, player_velocity)) = player_query.single() {
+        let player_pos = player_transform.translation.truncate();
+
+        // Use world position from action event if available, otherwise default upward
+        let shoot_direction = if let Some(target_pos) = target {
+            (target_pos - player_pos).normalize()
+        } else {
+            Vec2::Y
+        };
+
+        // Calculate spawn position on the edge of the player closest to the target
+        let spawn_offset = shoot_direction * (PLAYER_RADIUS + PROJECTILE_SIZE * 2.0 + 5.0);
+        let spawn_pos = player_pos + spawn_offset;
+
+        // Calculate projectile velocity: base velocity + player momentum
+        let projectile_velocity = (shoot_direction * PROJECTILE_SPEED) +
+            (player_velocity.linvel * PROJECTILE_MOMENTUM_TRANSFER);
+
+        // Spawn projectile
+        commands.spawn((
+            Mesh2d(meshes.add(Circle::new(PROJECTILE_SIZE))),
+            MeshMaterial2d(materials.add(Color::WHITE)),
+            Transform::from_translation(spawn_pos.extend(crate::depth::DepthLayer::Projectiles.z())),
+            Projectile {
+                lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+                team: Team::Player,
+                kind: ProjectileKind::Bullet,
+                source: player,
+            },
+            RigidBody::Dynamic,
+            Collider::ball(PROJECTILE_SIZE),
+            physics_layers::projectile(Team::Player),
+            Velocity::linear(projectile_velocity),
+            ActiveEvents::COLLISION_EVENTS,
+            InterpolatedTransform::default(),
+        ));
+
+        // Play shooting sound
+        commands.spawn((
+            AudioPlayer(game_sounds.gun_01.clone()),
+            PlaybackSettings::DESPAWN,
+        ));
+
+        // Reset fire timer
+        fire_timer.timer.reset();
     }
 }
 
@@ -167,53 +179,196 @@ pub fn throw_grenades(
         grenade_thrower.cooldown_timer.tick(time.delta());
     }
 
-    // Process grenade throw action events
+    // Process grenade throw action events: hold to aim, release to throw
     for action_event in action_events.read() {
-        if matches!(action_event.action, PlayerAction::ThrowGrenade) &&
-           (action_event.just_started() || action_event.is_active()) {
-            if let Ok((player_transform, player_velocity, mut grenade_thrower)) = player_query.single_mut() {
-                if grenade_thrower.can_throw() {
-                    let player_pos = player_transform.translation.truncate();
-
-                    // Use world position from action event if available, otherwise default upward
-                    let throw_direction = if let Some(target_pos) = action_event.world_position {
-                        (target_pos - player_pos).normalize()
-                    } else {
-                        Vec2::Y
-                    };
-
-                    // Calculate spawn position on the edge of the player
-                    let spawn_offset = throw_direction * (PLAYER_RADIUS + GRENADE_SIZE * 2.0 + 5.0);
-                    let spawn_pos = player_pos + spawn_offset;
-
-                    // Calculate grenade velocity: base velocity + player momentum (reduced)
-                    let grenade_velocity = (throw_direction * GRENADE_SPEED) + (player_velocity.linvel * 0.3);
-
-                    // Spawn grenade
-                    commands.spawn((
-                        Mesh2d(meshes.add(Circle::new(GRENADE_SIZE))),
-                        MeshMaterial2d(materials.add(Color::srgb(0.2, 0.8, 0.2))), // Green grenade
-                        Transform::from_translation(spawn_pos.extend(0.1)),
-                        Grenade {
-                            fuse_timer: Timer::from_seconds(GRENADE_FUSE_TIME, TimerMode::Once),
-                            team: Team::Player,
-                        },
-                        RigidBody::Dynamic,
-                        Collider::ball(GRENADE_SIZE),
-                        Restitution::coefficient(GRENADE_BOUNCE), // Make it bouncy
-                        Velocity::linear(grenade_velocity),
-                        ActiveEvents::COLLISION_EVENTS,
-                        Damping {
-                            linear_damping: GRENADE_DAMPING,
-                            angular_damping: 0.0,
-                        },
-                    ));
-
-                    // Reset grenade cooldown
-                    grenade_thrower.throw_grenade();
-                }
+        if !matches!(action_event.action, PlayerAction::ThrowGrenade) {
+            continue;
+        }
+
+        let Ok((player_transform, player_velocity, mut grenade_thrower)) = player_query.single_mut() else {
+            continue;
+        };
+
+        if action_event.just_started() || action_event.is_active() {
+            grenade_thrower.is_aiming = true;
+            if action_event.world_position.is_some() {
+                grenade_thrower.aim_target = action_event.world_position;
+            }
+            continue;
+        }
+
+        // Button released
+        let was_aiming = std::mem::take(&mut grenade_thrower.is_aiming);
+        if !was_aiming || !grenade_thrower.can_throw() {
+            continue;
+        }
+
+        let player_pos = player_transform.translation.truncate();
+        let (spawn_pos, grenade_velocity) = grenade_launch(player_pos, player_velocity.linvel, grenade_thrower.aim_target);
+
+        // Spawn grenade
+        commands.spawn((
+            Mesh2d(meshes.add(Circle::new(GRENADE_SIZE))),
+            MeshMaterial2d(materials.add(Color::srgb(0.2, 0.8, 0.2))), // Green grenade
+            Transform::from_translation(spawn_pos.extend(crate::depth::DepthLayer::Projectiles.z())),
+            Grenade {
+                fuse_timer: Timer::from_seconds(GRENADE_FUSE_TIME, TimerMode::Once),
+                team: Team::Player,
+            },
+            RigidBody::Dynamic,
+            Collider::ball(GRENADE_SIZE),
+            physics_layers::projectile(Team::Player),
+            Restitution::coefficient(GRENADE_BOUNCE), // Make it bouncy
+            Velocity::linear(grenade_velocity),
+            ActiveEvents::COLLISION_EVENTS,
+            Damping {
+                linear_damping: GRENADE_DAMPING,
+                angular_damping: 0.0,
+            },
+            InterpolatedTransform::default(),
+        ));
+
+        // Reset grenade cooldown
+        grenade_thrower.throw_grenade();
+    }
+}
+
+/// Turns buffered ability presses into cast requests once the slot is off cooldown and affordable
+pub fn cast_abilities(
+    mut cast_events: EventWriter<crate::combat::AbilityCastEvent>,
+    mut buffer: ResMut<InputBuffer>,
+    registry: Res<crate::combat::AbilityRegistry>,
+    player_query: Query<(Entity, &crate::combat::AbilitySlots, &crate::combat::Energy), With<Player>>,
+) {
+    let Ok((player_entity, slots, energy)) = player_query.single() else {
+        return;
+    };
+
+    for (index, slot) in slots.slots.iter().enumerate() {
+        let Some(slot) = slot else {
+            continue;
+        };
+        let affordable = registry.get(slot.ability)
+            .is_some_and(|definition| energy.current >= definition.energy_cost);
+        if slot.cooldown_remaining > 0.0 || !affordable {
+            continue;
+        }
+
+        if let Some(buffered) = buffer.take(&PlayerAction::UseAbility(index)) {
+            cast_events.write(crate::combat::AbilityCastEvent {
+                caster: player_entity,
+                slot: index,
+                target_position: buffered.world_position,
+            });
+        }
+    }
+}
+
+/// Spawn position and initial velocity for a grenade thrown from `player_pos` towards `target`
+pub fn grenade_launch(player_pos: Vec2, player_velocity: Vec2, target: Option<Vec2>) -> (Vec2, Vec2) {
+    // Default upward if there's no aim target
+    let throw_direction = target
+        .map(|target_pos| (target_pos - player_pos).normalize_or(Vec2::Y))
+        .unwrap_or(Vec2::Y);
+
+    // Calculate spawn position on the edge of the player
+    let spawn_offset = throw_direction * (PLAYER_RADIUS + GRENADE_SIZE * 2.0 + 5.0);
+
+    // Calculate grenade velocity: base velocity + player momentum (reduced)
+    let velocity = (throw_direction * GRENADE_SPEED) + (player_velocity * 0.3);
+
+    (player_pos + spawn_offset, velocity)
+}
+
+/// Simulation step used for trajectory prediction
+const GRENADE_PREVIEW_STEP: f32 = 1.0 / 60.0;
+
+/// Predict where a grenade travels before its fuse runs out
+/// Integrates Rapier's linear damping and reflects off fixed colliders using the grenade's restitution
+pub fn predict_grenade_path(start: Vec2, velocity: Vec2, context: Option<&RapierContext>) -> Vec<Vec2> {
+    let steps = (GRENADE_FUSE_TIME / GRENADE_PREVIEW_STEP).ceil() as usize;
+    let filter = QueryFilter::only_fixed().exclude_sensors();
+
+    let mut path = Vec::with_capacity(steps + 1);
+    let mut position = start;
+    let mut velocity = velocity;
+    path.push(position);
+
+    for _ in 0..steps {
+        // Rapier applies damping as v *= 1 / (1 + dt * damping)
+        velocity *= 1.0 / (1.0 + GRENADE_PREVIEW_STEP * GRENADE_DAMPING);
+        let step = velocity * GRENADE_PREVIEW_STEP;
+        let distance = step.length();
+
+        if distance <= f32::EPSILON {
+            path.push(position);
+            continue;
+        }
+
+        let direction = step / distance;
+        let hit = context.and_then(|context| {
+            context.cast_ray_and_get_normal(position, direction, distance + GRENADE_SIZE, true, filter)
+        });
+
+        match hit {
+            Some((_, intersection)) => {
+                // Stop at the wall surface and bounce
+                let normal = intersection.normal;
+                position = intersection.point + normal * GRENADE_SIZE;
+                velocity = (velocity - 2.0 * velocity.dot(normal) * normal) * GRENADE_BOUNCE;
             }
+            None => position += step,
         }
+
+        path.push(position);
+    }
+
+    path
+}
+
+/// Draw a dotted aim arc and the blast radius while the throw button is held
+pub fn draw_grenade_preview(
+    mut gizmos: Gizmos,
+    player_query: Query<(&Transform, &Velocity, &GrenadeThrower), With<Player>>,
+    windows: Query<&Window>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    rapier_context: ReadRapierContext,
+) {
+    let Ok((player_transform, player_velocity, grenade_thrower)) = player_query.single() else {
+        return;
+    };
+
+    if !grenade_thrower.is_aiming {
+        return;
+    }
+
+    // Follow the live cursor so the arc doesn't lag behind the fixed tick
+    let cursor_target = windows.single().ok()
+        .and_then(|window| window.cursor_position())
+        .zip(cameras.single().ok())
+        .and_then(|(cursor, (camera, camera_transform))| camera.viewport_to_world_2d(camera_transform, cursor).ok());
+    let target = cursor_target.or(grenade_thrower.aim_target);
+
+    let player_pos = player_transform.translation.truncate();
+    let (start, velocity) = grenade_launch(player_pos, player_velocity.linvel, target);
+    let context = rapier_context.single().ok();
+    let path = predict_grenade_path(start, velocity, context.as_ref());
+
+    let color = if grenade_thrower.can_throw() {
+        Color::srgba(0.4, 1.0, 0.4, 0.8)
+    } else {
+        Color::srgba(0.6, 0.6, 0.6, 0.5) // Still on cooldown
+    };
+
+    // Dotted arc
+    for point in path.iter().step_by(4) {
+        gizmos.circle_2d(*point, 1.5, color);
+    }
+
+    // Landing point and blast radius
+    if let Some(landing) = path.last() {
+        gizmos.circle_2d(*landing, GRENADE_SIZE * 2.0, color);
+        gizmos.circle_2d(*landing, GRENADE_EXPLOSION_RADIUS, color.with_alpha(0.35));
     }
 }
 
@@ -0,0 +1,160 @@
+//! Entity pooling for short-lived effects
+//!
+//! Effects such as floating numbers are spawned and despawned many times per
+//! second during heavy combat. An `EntityPool<M>` hides released entities
+//! instead of despawning them and hands them back out on the next acquire,
+//! so the entity and its asset handles are reused.
+use bevy::prelude::*;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+/// Allocation counters for one pool
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PoolStats {
+    /// Fresh entities or assets created
+    pub spawned: u64,
+    /// Acquires served from the free list
+    pub reused: u64,
+    /// Entities returned to the pool
+    pub released: u64,
+}
+
+impl PoolStats {
+    /// Fraction of acquires that avoided a fresh allocation
+    pub fn reuse_ratio(&self) -> f32 {
+        let total = self.spawned + self.reused;
+        if total == 0 { 0.0 } else { self.reused as f32 / total as f32 }
+    }
+}
+
+/// Stats of every pool, keyed by pool name, for the debug overlay
+#[derive(Resource, Debug, Default)]
+pub struct PoolMetrics {
+    pub pools: BTreeMap<&'static str, PoolStats>,
+}
+
+impl PoolMetrics {
+    pub fn stats_mut(&mut self, name: &'static str) -> &mut PoolStats {
+        self.pools.entry(name).or_default()
+    }
+}
+
+/// Free list of released entities marked with `M`
+///
+/// Released entities lose `M` and are hidden, so systems querying `M` skip them.
+#[derive(Resource)]
+pub struct EntityPool<M: Component> {
+    pub name: &'static str,
+    free: Vec<Entity>,
+    pub stats: PoolStats,
+    _marker: PhantomData<M>,
+}
+
+impl<M: Component> EntityPool<M> {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            free: Vec::new(),
+            stats: PoolStats::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reuse a released entity or spawn a new one, then insert `bundle`
+    /// The bundle should contain `M` so the entity is picked up again
+    pub fn acquire(&mut self, commands: &mut Commands, bundle: impl Bundle) -> Entity {
+        while let Some(entity) = self.free.pop() {
+            // Pooled entities may have been despawned with their scene
+            let Ok(mut entity_commands) = commands.get_entity(entity) else {
+                continue;
+            };
+            entity_commands.insert((bundle, Visibility::Inherited));
+            self.stats.reused += 1;
+            return entity;
+        }
+
+        self.stats.spawned += 1;
+        commands.spawn(bundle).id()
+    }
+
+    /// Hide an entity and keep it for the next acquire
+    pub fn release(&mut self, commands: &mut Commands, entity: Entity) {
+        commands.entity(entity).remove::<M>().insert(Visibility::Hidden);
+        self.free.push(entity);
+        self.stats.released += 1;
+    }
+}
+
+/// Registers an `EntityPool<M>` and reports its stats to `PoolMetrics`
+pub struct PoolPlugin<M: Component> {
+    name: &'static str,
+    _marker: PhantomData<M>,
+}
+
+impl<M: Component> PoolPlugin<M> {
+    pub fn new(name: &'static str) -> Self {
+        Self { name, _marker: PhantomData }
+    }
+}
+
+impl<M: Component> Plugin for PoolPlugin<M> {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PoolMetrics>()
+            .insert_resource(EntityPool::<M>::new(self.name))
+            .add_systems(Last, publish_pool_metrics::<M>);
+    }
+}
+
+fn publish_pool_metrics<M: Component>(pool: Res<EntityPool<M>>, mut metrics: ResMut<PoolMetrics>) {
+    if pool.is_changed() {
+        *metrics.stats_mut(pool.name) = pool.stats;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component)]
+    struct Effect;
+
+    #[test]
+    fn test_released_entities_are_reused() {
+        let mut world = World::new();
+        let mut pool = EntityPool::<Effect>::new("effects");
+
+        let first = {
+            let mut commands = world.commands();
+            let entity = pool.acquire(&mut commands, Effect);
+            pool.release(&mut commands, entity);
+            entity
+        };
+        world.flush();
+        assert!(!world.entity(first).contains::<Effect>());
+
+        let second = pool.acquire(&mut world.commands(), Effect);
+        world.flush();
+
+        assert_eq!(first, second);
+        assert!(world.entity(second).contains::<Effect>());
+        assert_eq!(pool.stats, PoolStats { spawned: 1, reused: 1, released: 1 });
+    }
+
+    #[test]
+    fn test_despawned_entities_are_skipped() {
+        let mut world = World::new();
+        let mut pool = EntityPool::<Effect>::new("effects");
+
+        let entity = pool.acquire(&mut world.commands(), Effect);
+        pool.release(&mut world.commands(), entity);
+        world.flush();
+        world.despawn(entity);
+
+        let fresh = pool.acquire(&mut world.commands(), Effect);
+        world.flush();
+        assert_ne!(fresh, entity);
+        assert_eq!(pool.stats.spawned, 2);
+        assert!(pool.free.is_empty());
+    }
+}
@@ -7,3 +7,8 @@ pub enum GameState {
     Playing,
     GameOver,
 }
+
+/// Marker resource present while the photo mode camera is detached from the player
+/// Player input and the follow camera stand down while it exists
+#[derive(Resource, Debug)]
+pub struct PhotoModeActive;
@@ -0,0 +1,185 @@
+//! Group steering for enemies
+//!
+//! `enemy_ai` picks where each enemy wants to go. Enemies with `Flocking` then
+//! get a boids-style pass on top: separation pushes them away from close
+//! neighbours and alignment nudges them towards their group's heading, so a
+//! pack spreads out around the player instead of collapsing into one blob.
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::collections::HashMap;
+
+use crate::components::Enemy;
+use crate::enemy::enemy_ai;
+
+/// Side length of a spatial hash cell in world units
+const SPATIAL_HASH_CELL_SIZE: f32 = 64.0;
+
+/// Uniform grid of entity positions for neighbour queries, rebuilt every fixed tick
+#[derive(Resource, Debug)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new(SPATIAL_HASH_CELL_SIZE)
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self { cell_size, cells: HashMap::new() }
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((entity, position));
+    }
+
+    /// Entities within `radius` of `position`
+    pub fn query_radius(&self, position: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = self.cell(position - Vec2::splat(radius));
+        let max = self.cell(position + Vec2::splat(radius));
+        let radius_squared = radius * radius;
+
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, other)| other.distance_squared(position) <= radius_squared)
+    }
+}
+
+/// Boids-style steering weights for an enemy
+#[derive(Component, Debug, Clone)]
+pub struct Flocking {
+    /// Neighbours closer than this push the enemy away
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    /// How strongly the enemy matches its neighbours' heading
+    pub alignment_weight: f32,
+}
+
+impl Flocking {
+    /// Steering tuned for an enemy of the given collider radius
+    pub fn for_radius(radius: f32) -> Self {
+        Self {
+            separation_radius: radius * 4.0,
+            separation_weight: 1.5,
+            alignment_weight: 0.3,
+        }
+    }
+}
+
+/// Plugin for enemy group steering
+pub struct SteeringPlugin;
+
+impl Plugin for SteeringPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SpatialHash>()
+            .add_systems(FixedUpdate, (
+                rebuild_spatial_hash,
+                apply_flocking,
+            ).chain().after(enemy_ai));
+    }
+}
+
+fn rebuild_spatial_hash(
+    mut spatial_hash: ResMut<SpatialHash>,
+    enemies: Query<(Entity, &Transform), With<Enemy>>,
+) {
+    spatial_hash.clear();
+    for (entity, transform) in enemies.iter() {
+        spatial_hash.insert(entity, transform.translation.truncate());
+    }
+}
+
+/// Push away from neighbours inside `radius`, stronger the closer they are
+pub fn separation(entity: Entity, position: Vec2, radius: f32, neighbours: impl Iterator<Item = (Entity, Vec2)>) -> Vec2 {
+    neighbours
+        .filter(|(other, _)| *other != entity)
+        .map(|(other, other_position)| {
+            let offset = position - other_position;
+            let distance = offset.length();
+            // Perfectly stacked enemies split in a direction picked from their ids
+            let away = if distance > f32::EPSILON {
+                offset / distance
+            } else {
+                Vec2::from_angle(entity.index() as f32 - other.index() as f32)
+            };
+            away * (1.0 - distance / radius).max(0.0)
+        })
+        .sum()
+}
+
+/// Blend separation and alignment into the velocity chosen by `enemy_ai`, keeping its speed
+fn apply_flocking(
+    spatial_hash: Res<SpatialHash>,
+    mut enemies: Query<(Entity, &Transform, &Flocking, &mut Velocity)>,
+) {
+    // Headings from this tick's AI, read before any of them are steered
+    let headings: HashMap<Entity, Vec2> = enemies.iter()
+        .map(|(entity, _, _, velocity)| (entity, velocity.linvel.normalize_or_zero()))
+        .collect();
+
+    for (entity, transform, flocking, mut velocity) in enemies.iter_mut() {
+        let speed = velocity.linvel.length();
+        if speed <= f32::EPSILON {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let radius = flocking.separation_radius;
+        let push = separation(entity, position, radius, spatial_hash.query_radius(position, radius));
+
+        let alignment: Vec2 = spatial_hash.query_radius(position, radius * 2.0)
+            .filter(|(other, _)| *other != entity)
+            .filter_map(|(other, _)| headings.get(&other))
+            .sum();
+
+        let direction = velocity.linvel / speed
+            + push * flocking.separation_weight
+            + alignment.normalize_or_zero() * flocking.alignment_weight;
+        velocity.linvel = direction.normalize_or_zero() * speed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_radius_crosses_cells() {
+        let mut hash = SpatialHash::new(10.0);
+        let near = Entity::from_raw(1);
+        let far = Entity::from_raw(2);
+        hash.insert(near, Vec2::new(11.0, 0.0));
+        hash.insert(far, Vec2::new(40.0, 0.0));
+
+        let found: Vec<Entity> = hash.query_radius(Vec2::new(8.0, 0.0), 5.0).map(|(entity, _)| entity).collect();
+        assert_eq!(found, vec![near]);
+    }
+
+    #[test]
+    fn test_separation_pushes_away_from_close_neighbours() {
+        let me = Entity::from_raw(1);
+        let neighbours = [(me, Vec2::ZERO), (Entity::from_raw(2), Vec2::new(5.0, 0.0)), (Entity::from_raw(3), Vec2::new(50.0, 0.0))];
+
+        let push = separation(me, Vec2::ZERO, 10.0, neighbours.into_iter());
+        assert_eq!(push, Vec2::new(-0.5, 0.0));
+
+        let stacked = separation(me, Vec2::ZERO, 10.0, [(Entity::from_raw(2), Vec2::ZERO)].into_iter());
+        assert!(stacked.length() > 0.99);
+    }
+}
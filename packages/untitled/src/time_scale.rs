@@ -0,0 +1,119 @@
+//! Game speed and slow motion
+//!
+//! Gameplay runs on virtual time, so scaling it slows everything together:
+//! the fixed tick runs less often, Rapier steps with it since it runs in the
+//! fixed schedule, and timers ticked from `Res<Time>` fall behind at the same
+//! rate. UI that should keep its pace reads `Time<Real>` instead, and sounds
+//! keep playing at their normal pitch.
+use bevy::prelude::*;
+use crate::events::GameEventAppExt;
+
+/// Plugin scaling virtual time by the `GameSpeed` resource
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<GameSpeed>()
+            .add_game_event::<SlowMotionEvent>()
+            .add_systems(PreUpdate, (
+                start_slow_motion,
+                apply_game_speed,
+            ).chain());
+    }
+}
+
+/// How fast gameplay runs compared to real time
+#[derive(Resource, Debug, Clone)]
+pub struct GameSpeed {
+    /// Speed outside slow motion; 1 is normal
+    pub base: f32,
+    slow_motion: Option<SlowMotion>,
+}
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        Self { base: 1.0, slow_motion: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SlowMotion {
+    scale: f32,
+    /// Real seconds left
+    remaining: f32,
+}
+
+impl GameSpeed {
+    /// Speed right now, the slower of the base and any slow motion
+    pub fn current(&self) -> f32 {
+        self.slow_motion.map_or(self.base, |slow_motion| slow_motion.scale.min(self.base))
+    }
+
+    /// Slow down to `scale` for `seconds` of real time
+    /// Overlapping requests keep the slowest scale and the longest time left
+    pub fn slow_motion(&mut self, scale: f32, seconds: f32) {
+        self.slow_motion = Some(match self.slow_motion {
+            Some(running) => SlowMotion {
+                scale: running.scale.min(scale),
+                remaining: running.remaining.max(seconds),
+            },
+            None => SlowMotion { scale, remaining: seconds },
+        });
+    }
+
+    /// Count down slow motion by `real_seconds`, ending it when time runs out
+    pub fn tick(&mut self, real_seconds: f32) {
+        if let Some(slow_motion) = &mut self.slow_motion {
+            slow_motion.remaining -= real_seconds;
+            if slow_motion.remaining <= 0.0 {
+                self.slow_motion = None;
+            }
+        }
+    }
+}
+
+/// Request a stretch of slow motion, e.g. after a big kill or from an ability
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SlowMotionEvent {
+    pub scale: f32,
+    /// Real seconds it lasts
+    pub duration: f32,
+}
+
+fn start_slow_motion(mut events: EventReader<SlowMotionEvent>, mut speed: ResMut<GameSpeed>) {
+    for event in events.read() {
+        speed.slow_motion(event.scale, event.duration);
+    }
+}
+
+fn apply_game_speed(
+    real_time: Res<Time<Real>>,
+    mut speed: ResMut<GameSpeed>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    speed.tick(real_time.delta_secs());
+
+    let current = speed.current();
+    if virtual_time.relative_speed() != current {
+        virtual_time.set_relative_speed(current);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_slow_motion_keeps_slowest_and_longest() {
+        let mut speed = GameSpeed::default();
+        speed.slow_motion(0.5, 1.0);
+        speed.slow_motion(0.25, 0.5);
+        assert_eq!(speed.current(), 0.25);
+
+        speed.tick(0.75);
+        assert_eq!(speed.current(), 0.25);
+        speed.tick(0.5);
+        assert_eq!(speed.current(), 1.0);
+    }
+}
@@ -1,16 +1,55 @@
 use bevy::prelude::*;
+use std::collections::BTreeMap;
 use crate::{
+    combat::Overheal,
     components::*,
     resources::*,
     player::{Player, FireTimer},
 };
+use theme::UiTheme;
 
 // Tooltip system module
 pub mod tooltip;
+// Inline color/icon markup for UI text
+pub mod rich_text;
+// Floating health bars over damaged enemies
+pub mod enemy_health_bars;
+// Toggleable combat log panel
+pub mod combat_log;
+// Floating numbers for healing feedback
+pub mod heal_numbers;
+// Brief tint on entities that get hit
+pub mod hit_flash;
+// Energy bar and ability hotbar
+pub mod ability_bar;
+// World map of the current run
+pub mod run_map;
+// Named colors shared by UI builders
+pub mod theme;
+// Colorblind palettes, flash reduction and UI scale
+pub mod accessibility;
+// Warning shown while chunks and scores aren't being saved
+pub mod persistence_banner;
+// One-time hints for moving, dashing, the inventory and portals
+pub mod tutorial;
+
+/// How often each change-driven UI system actually rebuilt its display
+/// Shown in the debug overlay; a count that keeps climbing while nothing happens is a bug
+#[derive(Resource, Debug, Default)]
+pub struct UiUpdateStats {
+    pub updates: BTreeMap<&'static str, u64>,
+}
+
+impl UiUpdateStats {
+    pub fn record(&mut self, system: &'static str) {
+        *self.updates.entry(system).or_default() += 1;
+    }
+}
 
 /// Sets up the health bar UI elements
 pub fn setup_health_bar(
     mut commands: Commands,
+    theme: Res<UiTheme>,
 ) {
     // Create the health bar container
     commands
@@ -31,7 +70,7 @@ pub fn setup_health_bar(
                     height: Val::Percent(100.0),
                     ..default()
                 },
-                BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                BackgroundColor(theme.bar_background),
             ));
 
             // Health bar fill
@@ -41,22 +80,47 @@ pub fn setup_health_bar(
                     height: Val::Percent(100.0),
                     ..default()
                 },
-                BackgroundColor(Color::srgb(0.8, 0.2, 0.2)),
+                BackgroundColor(theme.health_low),
                 HealthBar,
             ));
+
+            // Overheal overlay, grows from the left over the fill
+            parent.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(40.0),
+                    ..default()
+                },
+                BackgroundColor(theme.overheal_fill),
+                OverhealBar,
+            ));
         })
         .insert((
-            BorderColor(Color::srgb(0.6, 0.6, 0.6)),
+            BorderColor(theme.panel_border),
             BackgroundColor(Color::NONE),
         ));
 }
 
-/// Updates the health bar based on player health
+/// Updates the health bar when player health or overheal changes
 pub fn update_health_bar(
-    player_query: Query<&Health, With<Player>>,
-    mut health_bar_query: Query<&mut Node, With<HealthBar>>,
+    player_query: Query<(&Health, Option<&Overheal>), With<Player>>,
+    changed_players: Query<(), (With<Player>, Or<(Changed<Health>, Changed<Overheal>)>)>,
+    mut removed_overheal: RemovedComponents<Overheal>,
+    mut health_bar_query: Query<&mut Node, (With<HealthBar>, Without<OverhealBar>)>,
+    mut overheal_bar_query: Query<&mut Node, (With<OverhealBar>, Without<HealthBar>)>,
+    mut stats: ResMut<UiUpdateStats>,
 ) {
-    if let Ok(player_health) = player_query.single() {
+    let overheal_removed = removed_overheal.read().count() > 0;
+
+    if let Ok((player_health, overheal)) = player_query.single() {
+        if changed_players.is_empty() && !overheal_removed {
+            return;
+        }
+        stats.record("health_bar");
+
         if let Ok(mut health_bar_node) = health_bar_query.single_mut() {
             let health_percentage = (player_health.current / player_health.max) * 100.0;
             health_bar_node.width = Val::Percent(health_percentage);
@@ -64,30 +128,36 @@ pub fn update_health_bar(
             // Change color based on health level
             // This will be handled by a separate system for the BackgroundColor component
         }
+
+        if let Ok(mut overheal_bar_node) = overheal_bar_query.single_mut() {
+            let overheal = overheal.map_or(0.0, |o| o.amount);
+            let overheal_percentage = (overheal / player_health.max * 100.0).min(100.0);
+            overheal_bar_node.width = Val::Percent(overheal_percentage);
+        }
     }
 }
 
 /// Updates the health bar color based on health percentage
 pub fn update_health_bar_color(
-    player_query: Query<&Health, With<Player>>,
+    player_query: Query<&Health, (With<Player>, Changed<Health>)>,
     mut health_bar_query: Query<&mut BackgroundColor, With<HealthBar>>,
+    theme: Res<UiTheme>,
+    mut stats: ResMut<UiUpdateStats>,
 ) {
     if let Ok(player_health) = player_query.single() {
+        stats.record("health_bar_color");
+
         if let Ok(mut health_bar_color) = health_bar_query.single_mut() {
             let health_percentage = player_health.current / player_health.max;
 
-            // Color transitions: Red -> Yellow -> Green
+            // Color transitions: low -> half -> full
+            let mix = |from: Color, to: Color, t: f32| Color::from(from.to_srgba().mix(&to.to_srgba(), t));
             let color = if health_percentage > 0.6 {
-                // Green to yellow transition
-                let t = (health_percentage - 0.6) / 0.4;
-                Color::srgb(1.0 - t * 0.2, 0.8, 0.2)
+                mix(theme.health_half, theme.health_full, (health_percentage - 0.6) / 0.4)
             } else if health_percentage > 0.3 {
-                // Yellow to red transition
-                let t = (health_percentage - 0.3) / 0.3;
-                Color::srgb(1.0, 0.8 * t + 0.2, 0.2)
+                mix(theme.health_low, theme.health_half, (health_percentage - 0.3) / 0.3)
             } else {
-                // Red
-                Color::srgb(0.8, 0.2, 0.2)
+                theme.health_low
             };
 
             health_bar_color.0 = color;
@@ -98,6 +168,7 @@ pub fn update_health_bar_color(
 /// Sets up the game over overlay
 pub fn setup_game_over_overlay(
     mut commands: Commands,
+    theme: &UiTheme,
 ) {
     // Semi-transparent dark overlay
     commands.spawn((
@@ -113,18 +184,15 @@ pub fn setup_game_over_overlay(
             align_items: AlignItems::Center,
             ..default()
         },
-        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        BackgroundColor(theme.overlay_background),
         GameOverOverlay,
     ))
     .with_children(|parent| {
         // Game Over title
         parent.spawn((
             Text::new("GAME OVER"),
-            TextFont {
-                font_size: 48.0,
-                ..default()
-            },
-            TextColor(Color::srgb(1.0, 0.2, 0.2)),
+            theme.font(48.0),
+            TextColor(theme.text_danger),
             Node {
                 margin: UiRect::bottom(Val::Px(20.0)),
                 ..default()
@@ -141,19 +209,10 @@ pub fn setup_game_over_overlay(
                 align_items: AlignItems::Center,
                 ..default()
             },
-            BackgroundColor(Color::srgb(0.2, 0.6, 0.2)),
+            BackgroundColor(theme.button_confirm),
             RestartButton,
         ))
-        .with_children(|button| {
-            button.spawn((
-                Text::new("RESTART"),
-                TextFont {
-                    font_size: 24.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-            ));
-        });
+        .with_child(theme.text("RESTART", 24.0));
     });
 }
 
@@ -164,8 +223,6 @@ pub fn handle_restart_button(
     mut game_state: ResMut<GameState>,
     overlay_query: Query<Entity, With<GameOverOverlay>>,
     entities_query: Query<Entity, (Or<(With<Enemy>, With<Projectile>)>, Without<Player>, Without<MainCamera>)>,
-    dungeon_query: Query<Entity, With<DungeonWall>>,
-    floor_query: Query<Entity, (With<Mesh2d>, Without<Player>, Without<MainCamera>, Without<DungeonWall>, Without<Enemy>)>,
     mut fire_timer: ResMut<FireTimer>,
     mut next_state: ResMut<NextState<crate::world::states::WorldState>>,
 ) {
@@ -187,12 +244,10 @@ pub fn handle_restart_button(
             commands.entity(entity).despawn();
         }
 
-        // Clean up all game entities (enemies, projectiles)
+        // Clean up all game entities (enemies, projectiles); the rest of the
+        // scene is state scoped and goes when the state changes below
         crate::world::cleanup_game_entities(&mut commands, &entities_query);
 
-        // Clean up the entire dungeon (walls, floors) for complete regeneration
-        crate::world::cleanup_dungeon_entities(&mut commands, &dungeon_query, &floor_query);
-
         // Reset game state
         *game_state = GameState::Playing;
         fire_timer.timer.reset();
@@ -206,9 +261,57 @@ pub fn handle_restart_button(
 pub fn show_game_over_overlay(
     commands: Commands,
     game_state: Res<GameState>,
+    theme: Res<UiTheme>,
     overlay_query: Query<Entity, With<GameOverOverlay>>,
 ) {
     if *game_state == GameState::GameOver && overlay_query.is_empty() {
-        setup_game_over_overlay(commands);
+        setup_game_over_overlay(commands, &theme);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health_bar_app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.init_resource::<UiUpdateStats>()
+            .init_resource::<UiTheme>()
+            .add_systems(Update, (update_health_bar, update_health_bar_color));
+
+        let player = app.world_mut().spawn((Player, Health::new(100.0))).id();
+        let bar = app.world_mut().spawn((Node::default(), BackgroundColor::default(), HealthBar)).id();
+        (app, player, bar)
+    }
+
+    #[test]
+    fn test_health_bar_idles_until_health_changes() {
+        let (mut app, player, _) = health_bar_app();
+        app.update();
+        app.update();
+        app.update();
+
+        let stats = app.world().resource::<UiUpdateStats>();
+        assert_eq!(stats.updates["health_bar"], 1);
+        assert_eq!(stats.updates["health_bar_color"], 1);
+
+        app.world_mut().get_mut::<Health>(player).unwrap().take_damage(50.0);
+        app.update();
+        app.update();
+        assert_eq!(app.world().resource::<UiUpdateStats>().updates["health_bar"], 2);
+    }
+
+    #[test]
+    fn test_overheal_removal_shrinks_overlay() {
+        let (mut app, player, _) = health_bar_app();
+        let overlay = app.world_mut().spawn((Node::default(), OverhealBar)).id();
+        app.world_mut().entity_mut(player).insert(Overheal { amount: 50.0, decay_per_second: 1.0 });
+        app.update();
+        assert_eq!(app.world().get::<Node>(overlay).unwrap().width, Val::Percent(50.0));
+
+        app.world_mut().entity_mut(player).remove::<Overheal>();
+        app.update();
+        assert_eq!(app.world().get::<Node>(overlay).unwrap().width, Val::Percent(0.0));
+        assert_eq!(app.world().resource::<UiUpdateStats>().updates["health_bar"], 2);
     }
 }
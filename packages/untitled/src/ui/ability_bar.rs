@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use crate::{
+    combat::{AbilityRegistry, AbilitySlots, Energy},
+    constants::ABILITY_SLOT_COUNT,
+    player::{key_label, Player, PlayerInputBindings},
+};
+use super::theme::UiTheme;
+
+/// Plugin for the energy bar and ability hotbar
+pub struct AbilityBarPlugin;
+
+impl Plugin for AbilityBarPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, setup_ability_bar)
+            .add_systems(Update, (
+                update_energy_bar,
+                update_ability_slots,
+            ));
+    }
+}
+
+/// Fill of the energy bar
+#[derive(Component)]
+struct EnergyBarFill;
+
+/// One hotbar slot
+#[derive(Component)]
+struct AbilitySlotUi {
+    slot: usize,
+}
+
+/// Text inside a hotbar slot
+#[derive(Component)]
+struct AbilitySlotText;
+
+fn setup_ability_bar(mut commands: Commands, theme: Res<UiTheme>) {
+    // Energy bar sits just above the health bar
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                bottom: Val::Px(46.0),
+                width: Val::Px(200.0),
+                height: Val::Px(10.0),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(theme.panel_border),
+            BackgroundColor(theme.bar_background),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(theme.energy_fill),
+                EnergyBarFill,
+            ));
+        });
+
+    // Hotbar to the right of the health bar
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(240.0),
+            bottom: Val::Px(20.0),
+            column_gap: Val::Px(theme.gap),
+            ..default()
+        })
+        .with_children(|parent| {
+            for slot in 0..ABILITY_SLOT_COUNT {
+                parent
+                    .spawn((
+                        Node {
+                            width: Val::Px(72.0),
+                            height: Val::Px(36.0),
+                            border: UiRect::all(Val::Px(theme.border_width)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BorderColor(theme.panel_border),
+                        BackgroundColor(theme.slot_ready),
+                        AbilitySlotUi { slot },
+                    ))
+                    .with_child((
+                        theme.text("", 11.0),
+                        TextLayout::new_with_justify(JustifyText::Center),
+                        AbilitySlotText,
+                    ));
+            }
+        });
+}
+
+fn update_energy_bar(
+    player_query: Query<&Energy, With<Player>>,
+    mut fill_query: Query<&mut Node, With<EnergyBarFill>>,
+) {
+    let (Ok(energy), Ok(mut fill)) = (player_query.single(), fill_query.single_mut()) else {
+        return;
+    };
+
+    let percentage = if energy.max > 0.0 { energy.current / energy.max * 100.0 } else { 0.0 };
+    fill.width = Val::Percent(percentage);
+}
+
+fn update_ability_slots(
+    player_query: Query<(&AbilitySlots, Option<&Energy>), With<Player>>,
+    registry: Res<AbilityRegistry>,
+    bindings: Res<PlayerInputBindings>,
+    mut slots_ui: Query<(&AbilitySlotUi, &mut BackgroundColor, &Children)>,
+    mut texts: Query<&mut Text, With<AbilitySlotText>>,
+    theme: Res<UiTheme>,
+) {
+    let Ok((abilities, energy)) = player_query.single() else {
+        return;
+    };
+
+    for (slot_ui, mut background, children) in slots_ui.iter_mut() {
+        let key = bindings.abilities.get(slot_ui.slot).map(|key| key_label(*key)).unwrap_or_default();
+        let equipped = abilities.slots.get(slot_ui.slot).and_then(|slot| slot.as_ref());
+        let definition = equipped.and_then(|slot| registry.get(slot.ability));
+
+        let (label, color) = match (equipped, definition) {
+            (Some(slot), Some(definition)) if slot.cooldown_remaining > 0.0 => (
+                format!("[{}] {}\n{:.1}s", key, definition.name, slot.cooldown_remaining),
+                theme.slot_cooldown,
+            ),
+            (Some(_), Some(definition)) => {
+                let affordable = energy.is_none_or(|energy| energy.current >= definition.energy_cost);
+                (
+                    format!("[{}] {}\n{:.0} energy", key, definition.name, definition.energy_cost),
+                    if affordable { theme.slot_ready } else { theme.slot_no_energy },
+                )
+            }
+            _ => (format!("[{}]", key), theme.slot_cooldown),
+        };
+
+        if background.0 != color {
+            background.0 = color;
+        }
+
+        for child in children.iter() {
+            let Ok(mut text) = texts.get_mut(child) else {
+                continue;
+            };
+            if text.0 != label {
+                text.0 = label.clone();
+            }
+        }
+    }
+}
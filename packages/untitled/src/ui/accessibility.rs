@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use super::theme::{ColorblindMode, UiTheme};
+
+/// UI scale steps offered by the options panel
+const UI_SCALE_STEPS: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+/// Brightness multiplier for flashes when flash reduction is on
+const REDUCED_FLASH_INTENSITY: f32 = 0.25;
+
+/// Plugin for the accessibility options panel (F6) and applying the settings
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AccessibilitySettings>()
+            .init_resource::<AccessibilityPanelState>()
+            .add_systems(Update, (
+                toggle_accessibility_panel,
+                handle_accessibility_buttons,
+                apply_accessibility_settings,
+                refresh_accessibility_panel,
+            ).chain());
+    }
+}
+
+/// Player-facing accessibility settings
+#[derive(Resource, Debug, Clone)]
+pub struct AccessibilitySettings {
+    pub colorblind_mode: ColorblindMode,
+    /// Dim explosions, hit flashes and other bright flashes
+    pub reduce_flashing: bool,
+    /// Multiplier for UI layout and floating text
+    pub ui_scale: f32,
+    /// Show one-time hints like how to move and dash
+    pub tutorial_prompts: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            colorblind_mode: ColorblindMode::Off,
+            reduce_flashing: false,
+            ui_scale: 1.0,
+            tutorial_prompts: true,
+        }
+    }
+}
+
+impl AccessibilitySettings {
+    /// Multiplier flash effects should apply to their brightness
+    pub fn flash_intensity(&self) -> f32 {
+        if self.reduce_flashing {
+            REDUCED_FLASH_INTENSITY
+        } else {
+            1.0
+        }
+    }
+
+    /// Step to the next UI scale, wrapping back to the smallest
+    pub fn cycle_ui_scale(&mut self) {
+        self.ui_scale = UI_SCALE_STEPS
+            .iter()
+            .copied()
+            .find(|step| *step > self.ui_scale + f32::EPSILON)
+            .unwrap_or(UI_SCALE_STEPS[0]);
+    }
+}
+
+/// Whether the options panel is shown
+#[derive(Resource, Default)]
+pub struct AccessibilityPanelState {
+    pub open: bool,
+}
+
+/// Root node of the options panel
+#[derive(Component)]
+pub struct AccessibilityPanel;
+
+/// Option a panel button changes
+#[derive(Component, Debug, Clone, Copy)]
+enum AccessibilityOption {
+    ColorblindMode,
+    ReduceFlashing,
+    UiScale,
+    TutorialPrompts,
+}
+
+fn toggle_accessibility_panel(keyboard: Res<ButtonInput<KeyCode>>, mut state: ResMut<AccessibilityPanelState>) {
+    if keyboard.just_pressed(KeyCode::F6) {
+        state.open = !state.open;
+    }
+}
+
+fn handle_accessibility_buttons(
+    buttons: Query<(&Interaction, &AccessibilityOption), Changed<Interaction>>,
+    mut settings: ResMut<AccessibilitySettings>,
+) {
+    for (interaction, option) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match option {
+            AccessibilityOption::ColorblindMode => settings.colorblind_mode = settings.colorblind_mode.next(),
+            AccessibilityOption::ReduceFlashing => settings.reduce_flashing = !settings.reduce_flashing,
+            AccessibilityOption::UiScale => settings.cycle_ui_scale(),
+            AccessibilityOption::TutorialPrompts => settings.tutorial_prompts = !settings.tutorial_prompts,
+        }
+    }
+}
+
+/// Rebuild the color theme and UI scale when settings change
+fn apply_accessibility_settings(
+    settings: Res<AccessibilitySettings>,
+    mut theme: ResMut<UiTheme>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if theme.colorblind_mode != settings.colorblind_mode {
+        theme.set_colorblind_mode(settings.colorblind_mode);
+    }
+    ui_scale.0 = settings.ui_scale;
+}
+
+fn refresh_accessibility_panel(
+    mut commands: Commands,
+    state: Res<AccessibilityPanelState>,
+    settings: Res<AccessibilitySettings>,
+    theme: Res<UiTheme>,
+    panels: Query<Entity, With<AccessibilityPanel>>,
+) {
+    if !state.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    for entity in panels.iter() {
+        commands.entity(entity).despawn();
+    }
+    if !state.open {
+        return;
+    }
+
+    let options = [
+        (AccessibilityOption::ColorblindMode, format!("Colorblind mode: {}", settings.colorblind_mode.label())),
+        (AccessibilityOption::ReduceFlashing, format!("Reduce flashing: {}", if settings.reduce_flashing { "On" } else { "Off" })),
+        (AccessibilityOption::UiScale, format!("UI scale: {:.0}%", settings.ui_scale * 100.0)),
+        (AccessibilityOption::TutorialPrompts, format!("Tutorial prompts: {}", if settings.tutorial_prompts { "On" } else { "Off" })),
+    ];
+
+    commands
+        .spawn((
+            theme.panel(Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(40.0),
+                top: Val::Px(40.0),
+                width: Val::Px(280.0),
+                ..theme.panel_node()
+            }),
+            GlobalZIndex(90),
+            AccessibilityPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme.title("Accessibility (F6 to close)"));
+
+            for (option, label) in options {
+                parent
+                    .spawn((
+                        theme.button(Node {
+                            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                            border: UiRect::all(Val::Px(theme.border_width)),
+                            ..default()
+                        }),
+                        option,
+                    ))
+                    .with_child(theme.text(label, theme.body_size));
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ui_scale_cycles_and_wraps() {
+        let mut settings = AccessibilitySettings::default();
+        settings.cycle_ui_scale();
+        assert_eq!(settings.ui_scale, 1.25);
+
+        settings.ui_scale = 2.0;
+        settings.cycle_ui_scale();
+        assert_eq!(settings.ui_scale, 0.75);
+    }
+
+    #[test]
+    fn test_flash_reduction_dims_flashes() {
+        let mut settings = AccessibilitySettings::default();
+        assert_eq!(settings.flash_intensity(), 1.0);
+
+        settings.reduce_flashing = true;
+        assert!(settings.flash_intensity() < 1.0);
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::{input::mouse::{MouseScrollUnit, MouseWheel}, prelude::*};
+use crate::combat::{CombatLog, EffectRegistry};
+use super::rich_text::spawn_rich_text;
+use super::theme::UiTheme;
+
+/// Number of log lines visible at once
+const VISIBLE_LINES: usize = 12;
+
+/// Plugin for the toggleable combat log panel (F4)
+pub struct CombatLogPanelPlugin;
+
+impl Plugin for CombatLogPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<CombatLogPanelState>()
+            .add_systems(Update, (
+                toggle_combat_log_panel,
+                scroll_combat_log_panel,
+                refresh_combat_log_panel,
+            ).chain());
+    }
+}
+
+/// Open/scroll state of the combat log panel
+#[derive(Resource, Default)]
+pub struct CombatLogPanelState {
+    pub open: bool,
+    /// Lines scrolled up from the newest entry
+    pub scroll: usize,
+    /// `CombatLog::total_recorded` when the panel was last drawn
+    last_drawn: Option<(u64, usize)>,
+}
+
+/// Root node of the combat log panel
+#[derive(Component)]
+pub struct CombatLogPanel;
+
+/// Container holding the log lines
+#[derive(Component)]
+struct CombatLogLines;
+
+/// Toggle the panel with F4
+fn toggle_combat_log_panel(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<CombatLogPanelState>,
+    theme: Res<UiTheme>,
+    panels: Query<Entity, With<CombatLogPanel>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F4) {
+        return;
+    }
+
+    state.open = !state.open;
+    state.scroll = 0;
+    state.last_drawn = None;
+
+    if state.open {
+        commands
+            .spawn((
+                theme.panel(Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(20.0),
+                    bottom: Val::Px(20.0),
+                    width: Val::Px(420.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    row_gap: Val::Px(0.0),
+                    ..theme.panel_node()
+                }),
+                CombatLogPanel,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    theme.text("Combat Log (F4 to close, scroll for history)", theme.title_size - 2.0),
+                    Node {
+                        margin: UiRect::bottom(Val::Px(6.0)),
+                        ..default()
+                    },
+                ));
+                parent.spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    CombatLogLines,
+                ));
+            });
+    } else {
+        for entity in panels.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Scroll through history with the mouse wheel while the panel is open
+fn scroll_combat_log_panel(
+    mut wheel_events: EventReader<MouseWheel>,
+    mut state: ResMut<CombatLogPanelState>,
+    log: Res<CombatLog>,
+) {
+    if !state.open {
+        wheel_events.clear();
+        return;
+    }
+
+    let max_scroll = log.len().saturating_sub(VISIBLE_LINES);
+    for event in wheel_events.read() {
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y.round() as i32,
+            MouseScrollUnit::Pixel => (event.y / 16.0).round() as i32,
+        };
+        state.scroll = (state.scroll as i32 + lines).clamp(0, max_scroll as i32) as usize;
+    }
+}
+
+/// Redraw the visible lines when the log or scroll position changed
+fn refresh_combat_log_panel(
+    mut commands: Commands,
+    mut state: ResMut<CombatLogPanelState>,
+    log: Res<CombatLog>,
+    registry: Res<EffectRegistry>,
+    theme: Res<UiTheme>,
+    lines: Query<Entity, With<CombatLogLines>>,
+) {
+    if !state.open {
+        return;
+    }
+
+    let Ok(container) = lines.single() else {
+        return;
+    };
+
+    let drawn = (log.total_recorded, state.scroll);
+    if state.last_drawn == Some(drawn) && !theme.is_changed() {
+        return;
+    }
+    state.last_drawn = Some(drawn);
+
+    let end = log.len().saturating_sub(state.scroll);
+    let start = end.saturating_sub(VISIBLE_LINES);
+
+    commands.entity(container).despawn_related::<Children>();
+    commands.entity(container).with_children(|parent| {
+        if log.is_empty() {
+            spawn_rich_text(parent, &theme, "{grey}No combat yet{/}", theme.small_size, theme.text, Node::default());
+        }
+
+        for entry in log.entries().skip(start).take(end - start) {
+            let line = format!("{{grey}}[{:>6.1}]{{/}} {}", entry.timestamp, entry.describe(&registry));
+            spawn_rich_text(parent, &theme, &line, theme.small_size, theme.text_muted, Node::default());
+        }
+    });
+}
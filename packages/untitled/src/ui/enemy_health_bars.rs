@@ -0,0 +1,265 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::{
+    combat::{CombatState, StatusEffect, StatusId},
+    components::{Enemy, Health},
+};
+use super::rich_text::markup_icon;
+
+const BAR_WIDTH: f32 = 40.0;
+const BAR_HEIGHT: f32 = 5.0;
+const BAR_OFFSET: Vec2 = Vec2::new(0.0, 32.0);
+
+/// Plugin for floating health bars above damaged enemies
+pub struct EnemyHealthBarPlugin;
+
+impl Plugin for EnemyHealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<EnemyHealthBarSettings>()
+            .init_resource::<EnemyHealthBarPool>()
+            .add_systems(Startup, setup_health_bar_assets)
+            .add_systems(Update, (
+                show_health_bars_on_damage,
+                update_enemy_health_bars,
+            ).chain());
+    }
+}
+
+/// Player-facing settings for enemy health bars
+#[derive(Resource)]
+pub struct EnemyHealthBarSettings {
+    /// Whether floating enemy health bars are shown at all
+    pub enabled: bool,
+    /// Seconds a bar stays fully visible after the last damage
+    pub visible_duration: f32,
+    /// Seconds the bar takes to fade out afterwards
+    pub fade_duration: f32,
+}
+
+impl Default for EnemyHealthBarSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            visible_duration: 3.0,
+            fade_duration: 0.5,
+        }
+    }
+}
+
+/// Pool of health bar entities so heavy combat doesn't spawn and despawn bars constantly
+#[derive(Resource, Default)]
+pub struct EnemyHealthBarPool {
+    /// Hidden bars ready for reuse
+    free: Vec<Entity>,
+    /// Enemy entity -> bar entity currently following it
+    active: HashMap<Entity, Entity>,
+}
+
+/// Shared meshes for every health bar
+#[derive(Resource)]
+struct HealthBarAssets {
+    background: Handle<Mesh>,
+    fill: Handle<Mesh>,
+}
+
+/// Root of a pooled health bar; fades out once `remaining` runs out
+#[derive(Component)]
+pub struct EnemyHealthBar {
+    pub owner: Entity,
+    remaining: f32,
+}
+
+/// Fill part of an enemy health bar
+#[derive(Component)]
+struct EnemyHealthBarFill;
+
+/// Status icon row of an enemy health bar
+#[derive(Component)]
+struct EnemyStatusIcons;
+
+fn setup_health_bar_assets(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(HealthBarAssets {
+        background: meshes.add(Rectangle::new(BAR_WIDTH + 2.0, BAR_HEIGHT + 2.0)),
+        fill: meshes.add(Rectangle::new(BAR_WIDTH, BAR_HEIGHT)),
+    });
+}
+
+/// Health fraction from whichever health model the enemy uses
+fn health_fraction(health: Option<&Health>, combat: Option<&CombatState>) -> Option<f32> {
+    match (health, combat) {
+        (Some(health), _) if health.max > 0.0 => Some(health.current / health.max),
+        (_, Some(combat)) if combat.max_health > 0.0 => Some(combat.health / combat.max_health),
+        _ => None,
+    }
+}
+
+/// Attach a bar to enemies whose health dropped this frame
+fn show_health_bars_on_damage(
+    mut commands: Commands,
+    mut pool: ResMut<EnemyHealthBarPool>,
+    settings: Res<EnemyHealthBarSettings>,
+    assets: Option<Res<HealthBarAssets>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    damaged: Query<(Entity, Option<&Health>, Option<&CombatState>), (With<Enemy>, Or<(Changed<Health>, Changed<CombatState>)>)>,
+    mut bars: Query<(&mut EnemyHealthBar, &mut Visibility)>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+
+    if !settings.enabled {
+        return;
+    }
+
+    for (enemy, health, combat) in damaged.iter() {
+        // Freshly spawned enemies also count as changed, only react to actual damage
+        let Some(fraction) = health_fraction(health, combat) else { continue };
+        if fraction >= 1.0 {
+            continue;
+        }
+
+        let bar_entity = match pool.active.get(&enemy) {
+            Some(&bar) => bar,
+            None => {
+                let bar = pool.free.pop()
+                    .unwrap_or_else(|| spawn_health_bar(&mut commands, &assets, &mut materials));
+                pool.active.insert(enemy, bar);
+                bar
+            }
+        };
+
+        match bars.get_mut(bar_entity) {
+            Ok((mut bar, mut visibility)) => {
+                bar.owner = enemy;
+                bar.remaining = settings.visible_duration + settings.fade_duration;
+                *visibility = Visibility::Visible;
+            }
+            // Newly spawned this frame, finish setting it up
+            Err(_) => {
+                commands.entity(bar_entity).insert((
+                    EnemyHealthBar {
+                        owner: enemy,
+                        remaining: settings.visible_duration + settings.fade_duration,
+                    },
+                    Visibility::Visible,
+                ));
+            }
+        }
+    }
+}
+
+fn spawn_health_bar(
+    commands: &mut Commands,
+    assets: &HealthBarAssets,
+    materials: &mut Assets<ColorMaterial>,
+) -> Entity {
+    commands
+        .spawn((
+            Transform::default(),
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Mesh2d(assets.background.clone()),
+                MeshMaterial2d(materials.add(Color::srgba(0.0, 0.0, 0.0, 0.8))),
+                Transform::default(),
+            ));
+            parent.spawn((
+                Mesh2d(assets.fill.clone()),
+                MeshMaterial2d(materials.add(Color::srgb(0.85, 0.15, 0.15))),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+                EnemyHealthBarFill,
+            ));
+            parent.spawn((
+                Text2d::new(""),
+                TextFont {
+                    font_size: 10.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Transform::from_xyz(0.0, BAR_HEIGHT + 6.0, 0.1),
+                EnemyStatusIcons,
+            ));
+        })
+        .id()
+}
+
+/// Follow owners, update fill and status icons, fade out and return bars to the pool
+fn update_enemy_health_bars(
+    time: Res<Time<Real>>,
+    mut pool: ResMut<EnemyHealthBarPool>,
+    settings: Res<EnemyHealthBarSettings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    owners: Query<(&Transform, Option<&Health>, Option<&CombatState>, Option<&StatusEffect>), With<Enemy>>,
+    mut bars: Query<(&mut EnemyHealthBar, &mut Transform, &mut Visibility, &Children), Without<Enemy>>,
+    mut fills: Query<&mut Transform, (With<EnemyHealthBarFill>, Without<EnemyHealthBar>, Without<Enemy>)>,
+    mut icons: Query<&mut Text2d, With<EnemyStatusIcons>>,
+    child_materials: Query<&MeshMaterial2d<ColorMaterial>>,
+) {
+    let mut released = Vec::new();
+
+    for (&enemy, &bar_entity) in pool.active.iter() {
+        let Ok((mut bar, mut bar_transform, mut visibility, children)) = bars.get_mut(bar_entity) else {
+            continue;
+        };
+
+        bar.remaining -= time.delta_secs();
+        let owner = owners.get(enemy).ok();
+        let fraction = owner.and_then(|(_, health, combat, _)| health_fraction(health, combat));
+
+        // Owner despawned, died, timed out, or bars were disabled
+        if !settings.enabled || bar.remaining <= 0.0 || fraction.is_none_or(|f| f <= 0.0) {
+            *visibility = Visibility::Hidden;
+            released.push(enemy);
+            continue;
+        }
+
+        let (owner_transform, _, _, status) = owner.unwrap();
+        let fraction = fraction.unwrap().clamp(0.0, 1.0);
+        bar_transform.translation = (owner_transform.translation.truncate() + BAR_OFFSET).extend(crate::depth::DepthLayer::HealthBars.z());
+
+        let alpha = if settings.fade_duration > 0.0 {
+            (bar.remaining / settings.fade_duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        for child in children.iter() {
+            if let Ok(mut fill_transform) = fills.get_mut(child) {
+                // Shrink towards the left edge
+                fill_transform.scale.x = fraction;
+                fill_transform.translation.x = -(1.0 - fraction) * BAR_WIDTH / 2.0;
+            }
+
+            if let Ok(mut text) = icons.get_mut(child) {
+                let glyph = status.and_then(|status| status_icon(status.status_id)).unwrap_or("");
+                if text.0 != glyph {
+                    text.0 = glyph.to_string();
+                }
+            }
+
+            if let Some(material) = child_materials.get(child).ok().and_then(|m| materials.get_mut(&m.0)) {
+                let base_alpha = if fills.contains(child) { 1.0 } else { 0.8 };
+                material.color.set_alpha(alpha * base_alpha);
+            }
+        }
+    }
+
+    for enemy in released {
+        if let Some(bar) = pool.active.remove(&enemy) {
+            pool.free.push(bar);
+        }
+    }
+}
+
+/// Icon glyph for a status effect, if it has one
+fn status_icon(status_id: StatusId) -> Option<&'static str> {
+    let name = match status_id {
+        StatusId::SLOW => "slow",
+        StatusId::STUN => "stun",
+        StatusId::DAMAGE_OVER_TIME => "fire",
+        _ => return None,
+    };
+    markup_icon(name).map(|(glyph, _)| glyph)
+}
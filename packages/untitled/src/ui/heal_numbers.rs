@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::combat::HealedEvent;
+use crate::pool::{EntityPool, PoolPlugin};
+use super::accessibility::AccessibilitySettings;
+use super::theme::UiTheme;
+
+/// Seconds small heals are gathered before showing one number
+const ACCUMULATE_INTERVAL: f32 = 0.5;
+/// Seconds a number floats before disappearing
+const NUMBER_LIFETIME: f32 = 1.0;
+const RISE_SPEED: f32 = 30.0;
+
+/// Plugin for floating "+N" numbers over healed entities
+pub struct HealNumbersPlugin;
+
+impl Plugin for HealNumbersPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(PoolPlugin::<HealNumber>::new("heal_numbers"))
+            .init_resource::<PendingHeals>()
+            .add_systems(Update, (
+                collect_heals,
+                spawn_heal_numbers,
+                animate_heal_numbers,
+            ).chain());
+    }
+}
+
+/// Heals per target not shown yet, so regeneration ticks become one readable number
+#[derive(Resource, Default)]
+struct PendingHeals {
+    totals: HashMap<Entity, (f32, f32)>,
+    timer: f32,
+}
+
+/// A floating heal number
+#[derive(Component)]
+struct HealNumber {
+    remaining: f32,
+}
+
+fn collect_heals(
+    mut healed_events: EventReader<HealedEvent>,
+    mut pending: ResMut<PendingHeals>,
+) {
+    for event in healed_events.read() {
+        let total = pending.totals.entry(event.target).or_default();
+        total.0 += event.healed;
+        total.1 += event.overhealed;
+    }
+}
+
+fn spawn_heal_numbers(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut pending: ResMut<PendingHeals>,
+    mut pool: ResMut<EntityPool<HealNumber>>,
+    theme: Res<UiTheme>,
+    settings: Res<AccessibilitySettings>,
+    transforms: Query<&Transform>,
+) {
+    pending.timer += time.delta_secs();
+    if pending.timer < ACCUMULATE_INTERVAL {
+        return;
+    }
+    pending.timer = 0.0;
+
+    for (target, (healed, overhealed)) in pending.totals.drain() {
+        let Ok(transform) = transforms.get(target) else {
+            continue;
+        };
+
+        // Sub-point totals aren't worth showing
+        if healed + overhealed < 1.0 {
+            continue;
+        }
+
+        // Overheal is shown in blue, in brackets when it comes with regular healing
+        let (text, color) = match (healed >= 1.0, overhealed >= 1.0) {
+            (true, true) => (format!("+{:.0} (+{:.0})", healed, overhealed), theme.get("heal")),
+            (true, false) => (format!("+{:.0}", healed), theme.get("heal")),
+            _ => (format!("+{:.0}", overhealed), theme.get("overheal")),
+        };
+
+        pool.acquire(&mut commands, (
+            Text2d::new(text),
+            TextFont {
+                font_size: 14.0 * settings.ui_scale,
+                ..default()
+            },
+            TextColor(color),
+            Transform::from_translation((transform.translation.truncate() + Vec2::new(0.0, 20.0)).extend(crate::depth::DepthLayer::FloatingText.z())),
+            HealNumber { remaining: NUMBER_LIFETIME },
+        ));
+    }
+}
+
+fn animate_heal_numbers(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut pool: ResMut<EntityPool<HealNumber>>,
+    mut numbers: Query<(Entity, &mut HealNumber, &mut Transform, &mut TextColor)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut number, mut transform, mut color) in numbers.iter_mut() {
+        number.remaining -= dt;
+        if number.remaining <= 0.0 {
+            pool.release(&mut commands, entity);
+            continue;
+        }
+
+        transform.translation.y += RISE_SPEED * dt;
+        color.0.set_alpha(number.remaining / NUMBER_LIFETIME);
+    }
+}
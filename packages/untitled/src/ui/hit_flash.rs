@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use crate::combat::DamageEvent;
+use crate::components::HitFlash;
+use crate::events::HitFlashEvent;
+use super::accessibility::AccessibilitySettings;
+use super::theme::UiTheme;
+
+/// Plugin tinting entities briefly when they are hit
+pub struct HitFlashPlugin;
+
+impl Plugin for HitFlashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            start_hit_flashes,
+            apply_hit_flashes,
+        ).chain());
+    }
+}
+
+/// Start or restart the flash of everything damaged or sent a `HitFlashEvent`
+fn start_hit_flashes(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut flash_events: EventReader<HitFlashEvent>,
+    materials: Res<Assets<ColorMaterial>>,
+    mut targets: Query<(&MeshMaterial2d<ColorMaterial>, Option<&mut HitFlash>)>,
+) {
+    let damaged = damage_events.read().filter(|event| event.damage > 0.0).map(|event| event.target);
+    let flashed = flash_events.read().map(|event| event.target);
+
+    for target in damaged.chain(flashed) {
+        let Ok((material, flash)) = targets.get_mut(target) else {
+            continue;
+        };
+
+        // Overlapping hits keep the color from before the first one, not the tinted one
+        if let Some(mut flash) = flash {
+            flash.refresh();
+        } else if let Some(material) = materials.get(&material.0) {
+            commands.entity(target).insert(HitFlash::new(material.color));
+        }
+    }
+}
+
+/// Fade each flashing entity's material from the flash color back to its own
+///
+/// The material is changed in place, so it should belong to the entity alone;
+/// enemies and the player each get their own when spawned. White reads by
+/// brightness rather than hue, so the standard flash needs no colorblind
+/// override, but palettes can still replace `hit`.
+fn apply_hit_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    theme: Res<UiTheme>,
+    settings: Res<AccessibilitySettings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut flashes: Query<(Entity, &mut HitFlash, &MeshMaterial2d<ColorMaterial>)>,
+) {
+    let flash_color = theme.get("hit");
+
+    for (entity, mut flash, material) in flashes.iter_mut() {
+        flash.remaining -= time.delta_secs();
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+
+        if flash.remaining <= 0.0 {
+            material.color = flash.base_color;
+            commands.entity(entity).remove::<HitFlash>();
+            continue;
+        }
+
+        let strength = flash.strength() * settings.flash_intensity();
+        material.color = flash.base_color.mix(&flash_color, strength);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_hits_restart_the_fade_and_keep_the_base_color() {
+        let base = Color::srgb(1.0, 0.2, 0.2);
+        let mut flash = HitFlash::new(base);
+        assert_eq!(flash.strength(), 1.0);
+
+        flash.remaining /= 2.0;
+        assert!(flash.strength() < 1.0);
+
+        flash.refresh();
+        assert_eq!(flash.strength(), 1.0);
+        assert_eq!(flash.base_color, base);
+    }
+}
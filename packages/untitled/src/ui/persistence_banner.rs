@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use crate::persistence::PersistenceStatus;
+use super::theme::UiTheme;
+
+/// Plugin for the banner warning that nothing is being saved
+pub struct PersistenceBannerPlugin;
+
+impl Plugin for PersistenceBannerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, refresh_persistence_banner.run_if(resource_changed::<PersistenceStatus>));
+    }
+}
+
+/// Root node of the banner
+#[derive(Component)]
+struct PersistenceBanner;
+
+/// Show the banner while saving is off and remove it once the database is back
+fn refresh_persistence_banner(
+    mut commands: Commands,
+    status: Res<PersistenceStatus>,
+    theme: Res<UiTheme>,
+    banners: Query<Entity, With<PersistenceBanner>>,
+) {
+    for entity in banners.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let Some(error) = &status.error else {
+        return;
+    };
+
+    let mut message = format!("Saving is off: {}\nExplored areas and scores won't be kept this session", error);
+    if status.retries_left > 0 {
+        message.push_str(", retrying");
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            GlobalZIndex(210), // Above the loading screen too
+            PersistenceBanner,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(theme.panel(Node {
+                    padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                    ..theme.panel_node()
+                }))
+                .with_child((
+                    Text::new(message),
+                    theme.font(theme.body_size),
+                    TextColor(theme.text_warning),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                ));
+        });
+}
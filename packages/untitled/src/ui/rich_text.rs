@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+use super::theme::UiTheme;
+
+/// A run of text sharing one color, produced by `parse_markup`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichSpan {
+    pub text: String,
+    /// Color override; `None` uses the caller's default text color
+    pub color: Option<Color>,
+}
+
+/// Look up a named markup color, e.g. `{red}` or `{magical}`
+pub fn markup_color(name: &str) -> Option<Color> {
+    let color = match name {
+        "red" => Color::srgb(0.9, 0.3, 0.3),
+        "green" => Color::srgb(0.4, 0.9, 0.4),
+        "blue" => Color::srgb(0.4, 0.6, 1.0),
+        "yellow" => Color::srgb(1.0, 0.9, 0.3),
+        "orange" => Color::srgb(1.0, 0.6, 0.2),
+        "purple" => Color::srgb(0.7, 0.4, 1.0),
+        "grey" | "gray" => Color::srgb(0.6, 0.6, 0.6),
+        "white" => Color::WHITE,
+        // Damage type colors
+        "physical" => Color::srgb(0.85, 0.85, 0.85),
+        "magical" => Color::srgb(0.6, 0.5, 1.0),
+        "true" => Color::srgb(1.0, 1.0, 0.8),
+        // Healing feedback
+        "heal" => Color::srgb(0.4, 0.95, 0.4),
+        "overheal" => Color::srgb(0.5, 0.75, 1.0),
+        // Tint of entities that were just hit
+        "hit" => Color::WHITE,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// Look up an inline icon, e.g. `{icon:fire}`
+/// The default font has no pictographs, so icons are tinted glyphs until we have an icon atlas
+pub fn markup_icon(name: &str) -> Option<(&'static str, Color)> {
+    let icon = match name {
+        "fire" => ("*", Color::srgb(1.0, 0.5, 0.1)),
+        "frost" => ("*", Color::srgb(0.6, 0.85, 1.0)),
+        "poison" => ("%", Color::srgb(0.5, 0.9, 0.3)),
+        "physical" => ("#", Color::srgb(0.85, 0.85, 0.85)),
+        "magical" => ("~", Color::srgb(0.6, 0.5, 1.0)),
+        "true" => ("!", Color::srgb(1.0, 1.0, 0.8)),
+        "slow" => (">", Color::srgb(0.4, 0.6, 1.0)),
+        "stun" => ("@", Color::srgb(1.0, 0.9, 0.3)),
+        "health" => ("+", Color::srgb(0.9, 0.2, 0.2)),
+        "key" => ("k", Color::srgb(1.0, 0.8, 0.3)),
+        _ => return None,
+    };
+    Some(icon)
+}
+
+/// Parse `{color}...{/}` spans and `{icon:name}` glyphs into colored runs, resolving names through `theme`
+/// Color tags nest; unknown tags are kept as literal text
+pub fn parse_markup_with(input: &str, theme: &UiTheme) -> Vec<RichSpan> {
+    let mut spans = Vec::new();
+    let mut color_stack: Vec<Color> = Vec::new();
+    let mut current = String::new();
+    let mut rest = input;
+
+    fn flush(spans: &mut Vec<RichSpan>, current: &mut String, color: Option<Color>) {
+        if !current.is_empty() {
+            spans.push(RichSpan { text: std::mem::take(current), color });
+        }
+    }
+
+    while let Some(open) = rest.find('{') {
+        current.push_str(&rest[..open]);
+        let after_open = &rest[open + 1..];
+
+        let Some(close) = after_open.find('}') else {
+            // Unterminated tag, treat the remainder as text
+            current.push_str(&rest[open..]);
+            rest = "";
+            break;
+        };
+
+        let tag = &after_open[..close];
+        let literal = &rest[open..open + close + 2];
+        rest = &after_open[close + 1..];
+
+        if tag == "/" {
+            flush(&mut spans, &mut current, color_stack.last().copied());
+            color_stack.pop();
+        } else if let Some((glyph, color)) = tag.strip_prefix("icon:").and_then(|name| theme.icon(name)) {
+            flush(&mut spans, &mut current, color_stack.last().copied());
+            spans.push(RichSpan { text: glyph.to_string(), color: Some(color) });
+        } else if let Some(color) = theme.color(tag) {
+            flush(&mut spans, &mut current, color_stack.last().copied());
+            color_stack.push(color);
+        } else {
+            current.push_str(literal);
+        }
+    }
+
+    current.push_str(rest);
+    flush(&mut spans, &mut current, color_stack.last().copied());
+    spans
+}
+
+/// Spawn a UI text entity rendering markup as colored text spans
+pub fn spawn_rich_text(
+    parent: &mut ChildSpawnerCommands,
+    theme: &UiTheme,
+    markup: &str,
+    font_size: f32,
+    default_color: Color,
+    node: Node,
+) -> Entity {
+    parent
+        .spawn((
+            Text::default(),
+            theme.font(font_size),
+            TextColor(default_color),
+            node,
+        ))
+        .with_children(|text| {
+            for span in parse_markup_with(markup, theme) {
+                text.spawn((
+                    TextSpan::new(span.text),
+                    theme.font(font_size),
+                    TextColor(span.color.unwrap_or(default_color)),
+                ));
+            }
+        })
+        .id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_markup(input: &str) -> Vec<RichSpan> {
+        parse_markup_with(input, &UiTheme::default())
+    }
+
+    /// The visible text, without markup
+    fn plain_text(input: &str) -> String {
+        parse_markup(input).into_iter().map(|span| span.text).collect()
+    }
+
+    #[test]
+    fn test_plain_text_passthrough() {
+        let spans = parse_markup("Just text");
+        assert_eq!(spans, vec![RichSpan { text: "Just text".to_string(), color: None }]);
+    }
+
+    #[test]
+    fn test_color_spans_nest_and_close() {
+        let spans = parse_markup("a {red}b {blue}c{/} d{/} e");
+        let texts: Vec<_> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["a ", "b ", "c", " d", " e"]);
+        assert_eq!(spans[1].color, markup_color("red"));
+        assert_eq!(spans[2].color, markup_color("blue"));
+        assert_eq!(spans[3].color, markup_color("red"));
+        assert_eq!(spans[4].color, None);
+    }
+
+    #[test]
+    fn test_icons_and_unknown_tags() {
+        assert_eq!(plain_text("{icon:fire} Burn"), "* Burn");
+        assert_eq!(plain_text("{nope} and {icon:nope}"), "{nope} and {icon:nope}");
+        assert_eq!(plain_text("open {brace"), "open {brace");
+    }
+}
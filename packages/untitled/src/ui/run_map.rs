@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use crate::world::{ModifierRegistry, RunStructure, WorldState};
+use crate::world::run_structure::{FloorFeature, FloorNode};
+use super::rich_text::spawn_rich_text;
+use super::theme::UiTheme;
+
+/// Plugin for the world map screen (M), opened automatically in the Sanctuary
+pub struct RunMapPlugin;
+
+impl Plugin for RunMapPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<RunMapState>()
+            .add_systems(OnEnter(WorldState::Sanctuary), open_run_map)
+            .add_systems(OnExit(WorldState::Sanctuary), close_run_map)
+            .add_systems(OnExit(WorldState::Cathedral), close_run_map)
+            .add_systems(Update, (
+                toggle_run_map.run_if(in_state(WorldState::Cathedral).or(in_state(WorldState::Sanctuary))),
+                select_run_branch,
+                refresh_run_map,
+            ).chain());
+    }
+}
+
+/// Whether the world map is shown
+#[derive(Resource, Default)]
+pub struct RunMapState {
+    pub open: bool,
+}
+
+/// Root node of the world map
+#[derive(Component)]
+pub struct RunMapPanel;
+
+/// Button choosing a branch for the next depth
+#[derive(Component)]
+struct RunMapBranchButton(usize);
+
+fn open_run_map(mut state: ResMut<RunMapState>) {
+    state.open = true;
+}
+
+fn close_run_map(mut state: ResMut<RunMapState>) {
+    state.open = false;
+}
+
+fn toggle_run_map(keyboard: Res<ButtonInput<KeyCode>>, mut state: ResMut<RunMapState>) {
+    if keyboard.just_pressed(KeyCode::KeyM) {
+        state.open = !state.open;
+    }
+}
+
+fn select_run_branch(
+    buttons: Query<(&Interaction, &RunMapBranchButton), Changed<Interaction>>,
+    run: Option<ResMut<RunStructure>>,
+) {
+    let Some(mut run) = run else {
+        return;
+    };
+
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            run.select_branch(button.0);
+        }
+    }
+}
+
+/// Markup for a branch: features, then modifiers for branches the player can see
+fn branch_markup(node: &FloorNode, registry: &ModifierRegistry, revealed: bool) -> String {
+    let mut markup = String::new();
+    if node.has_feature(FloorFeature::Boss) {
+        markup.push_str("{red}Boss{/} ");
+    }
+    if revealed && node.has_feature(FloorFeature::Shrine) {
+        markup.push_str("{purple}Shrine{/} ");
+    }
+
+    if revealed {
+        let names: Vec<&str> = node.modifiers.iter()
+            .filter_map(|id| registry.get(*id))
+            .map(|definition| definition.name.as_str())
+            .collect();
+        if names.is_empty() {
+            markup.push_str("{grey}No modifiers{/}");
+        } else {
+            markup.push_str(&names.join(", "));
+        }
+    } else if markup.is_empty() {
+        markup.push_str("{grey}?{/}");
+    }
+
+    markup
+}
+
+/// Rebuild the panel whenever it is toggled or the run changes
+fn refresh_run_map(
+    mut commands: Commands,
+    state: Res<RunMapState>,
+    run: Option<Res<RunStructure>>,
+    registry: Res<ModifierRegistry>,
+    theme: Res<UiTheme>,
+    panels: Query<Entity, With<RunMapPanel>>,
+) {
+    let run_changed = run.as_ref().is_some_and(|run| run.is_changed());
+    if !state.is_changed() && !run_changed && !theme.is_changed() {
+        return;
+    }
+
+    for entity in panels.iter() {
+        commands.entity(entity).despawn();
+    }
+    if !state.open {
+        return;
+    }
+
+    commands
+        .spawn((
+            theme.panel(Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(40.0),
+                top: Val::Px(40.0),
+                width: Val::Px(560.0),
+                row_gap: Val::Px(4.0),
+                ..theme.panel_node()
+            }),
+            RunMapPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme.title("World Map (M to close)"));
+
+            let Some(run) = run else {
+                spawn_rich_text(parent, &theme, "{grey}No run in progress{/}", theme.body_size, theme.text, Node::default());
+                return;
+            };
+
+            let current_depth = run.current_depth();
+            for (index, branches) in run.floors.iter().enumerate() {
+                let depth = run.start_depth + index as u32;
+                let is_next = depth == current_depth + 1;
+
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(6.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        let label_color = if depth <= current_depth { theme.text } else { theme.get("grey") };
+                        row.spawn((
+                            Text::new(format!("Depth {}", depth)),
+                            theme.font(theme.body_size),
+                            TextColor(label_color),
+                            Node {
+                                width: Val::Px(70.0),
+                                ..default()
+                            },
+                        ));
+
+                        for (branch, node) in branches.iter().enumerate() {
+                            let taken = run.path.get(index) == Some(&branch);
+                            let selected = is_next && run.selected_branch == branch;
+                            let background = if taken {
+                                theme.button_selected
+                            } else if selected {
+                                theme.button_pending
+                            } else {
+                                theme.button_background
+                            };
+
+                            let mut cell = row.spawn((
+                                Node {
+                                    padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                    border: UiRect::all(Val::Px(theme.border_width)),
+                                    ..default()
+                                },
+                                BackgroundColor(background),
+                                BorderColor(if selected { theme.get("yellow") } else { theme.button_border }),
+                            ));
+                            if is_next {
+                                cell.insert((Button, RunMapBranchButton(branch)));
+                            }
+
+                            let markup = branch_markup(node, &registry, node.visited || is_next);
+                            cell.with_children(|cell| {
+                                spawn_rich_text(cell, &theme, &markup, theme.small_size, theme.text, Node::default());
+                            });
+                        }
+                    });
+            }
+
+            if !run.next_choices().is_empty() {
+                spawn_rich_text(
+                    parent,
+                    &theme,
+                    "{yellow}Click a branch to choose the next depth{/}",
+                    theme.small_size,
+                    theme.text,
+                    Node {
+                        margin: UiRect::top(Val::Px(6.0)),
+                        ..default()
+                    },
+                );
+            }
+        });
+}
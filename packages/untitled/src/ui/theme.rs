@@ -0,0 +1,309 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use super::rich_text::{markup_color, markup_icon};
+
+/// Plugin providing the shared UI theme and color palette
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<UiTheme>()
+            .add_systems(PostUpdate, apply_panel_images);
+    }
+}
+
+/// Image drawn behind panels, stretched with fixed-size corners
+#[derive(Debug, Clone)]
+pub struct NineSlice {
+    pub image: Handle<Image>,
+    /// Width in pixels of the unstretched border
+    pub border: f32,
+}
+
+/// Panel colors, named colors, text sizes and spacing read by every UI builder
+///
+/// Named colors come from the `rich_text` palette; colorblind modes override
+/// part of it.
+#[derive(Resource, Debug, Clone)]
+pub struct UiTheme {
+    pub panel_background: Color,
+    pub panel_border: Color,
+    pub button_background: Color,
+    pub button_hover: Color,
+    pub button_selected: Color,
+    pub button_border: Color,
+    /// Empty part of health and energy bars
+    pub bar_background: Color,
+    /// Player health bar from full through half to nearly empty
+    pub health_full: Color,
+    pub health_half: Color,
+    pub health_low: Color,
+    /// Shield over the health bar
+    pub overheal_fill: Color,
+    pub energy_fill: Color,
+    /// Loading screen bars
+    pub progress_fill: Color,
+    /// Full-screen backdrops like loading screens
+    pub screen_background: Color,
+    /// Dimming behind modal overlays like game over
+    pub overlay_background: Color,
+    pub button_confirm: Color,
+    /// Highlighted choice that isn't selected yet, like the next branch on the run map
+    pub button_pending: Color,
+    /// Hotbar slots by whether the ability can be cast
+    pub slot_ready: Color,
+    pub slot_cooldown: Color,
+    pub slot_no_energy: Color,
+    /// Inventory grid cells
+    pub cell_empty: Color,
+    pub cell_occupied: Color,
+    pub cell_selected: Color,
+    /// Dragged item over a cell it can and can't drop into
+    pub drop_valid: Color,
+    pub drop_invalid: Color,
+    pub text: Color,
+    pub text_muted: Color,
+    /// Warnings that don't stop play, like saving being off
+    pub text_warning: Color,
+    pub text_danger: Color,
+    /// Section headings inside panels
+    pub text_heading: Color,
+    /// Stat lines and keybind hints in tooltips
+    pub text_stat: Color,
+    pub text_keybind: Color,
+    pub font: Handle<Font>,
+    pub title_size: f32,
+    pub body_size: f32,
+    pub small_size: f32,
+    pub padding: f32,
+    pub gap: f32,
+    pub border_width: f32,
+    /// Replaces the flat panel background when set
+    pub panel_image: Option<NineSlice>,
+    pub colorblind_mode: ColorblindMode,
+    color_overrides: HashMap<&'static str, Color>,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self {
+            panel_background: Color::srgba(0.0, 0.0, 0.0, 0.85),
+            panel_border: Color::srgb(0.5, 0.5, 0.5),
+            button_background: Color::srgba(0.15, 0.15, 0.2, 0.9),
+            button_hover: Color::srgba(0.3, 0.3, 0.3, 0.9),
+            button_selected: Color::srgba(0.2, 0.45, 0.25, 0.9),
+            button_border: Color::srgb(0.4, 0.4, 0.4),
+            bar_background: Color::srgb(0.2, 0.2, 0.2),
+            health_full: Color::srgb(0.8, 0.8, 0.2),
+            health_half: Color::srgb(1.0, 0.8, 0.2),
+            health_low: Color::srgb(0.8, 0.2, 0.2),
+            overheal_fill: Color::srgba(0.5, 0.75, 1.0, 0.9),
+            energy_fill: Color::srgb(0.3, 0.5, 1.0),
+            progress_fill: Color::srgb(0.3, 0.5, 1.0),
+            screen_background: Color::BLACK,
+            overlay_background: Color::srgba(0.0, 0.0, 0.0, 0.8),
+            button_confirm: Color::srgb(0.2, 0.6, 0.2),
+            button_pending: Color::srgb(0.35, 0.3, 0.15),
+            slot_ready: Color::srgba(0.15, 0.15, 0.25, 0.9),
+            slot_cooldown: Color::srgba(0.1, 0.1, 0.1, 0.9),
+            slot_no_energy: Color::srgba(0.25, 0.1, 0.1, 0.9),
+            cell_empty: Color::srgb(0.2, 0.2, 0.3),
+            cell_occupied: Color::srgb(0.3, 0.3, 0.4),
+            cell_selected: Color::srgb(0.5, 0.3, 0.3),
+            drop_valid: Color::srgba(0.2, 1.0, 0.2, 0.6),
+            drop_invalid: Color::srgba(1.0, 0.2, 0.2, 0.6),
+            text: Color::WHITE,
+            text_muted: Color::srgb(0.8, 0.8, 0.8),
+            text_warning: Color::srgb(1.0, 0.75, 0.3),
+            text_danger: Color::srgb(1.0, 0.2, 0.2),
+            text_heading: Color::srgb(1.0, 0.9, 0.3),
+            text_stat: Color::srgb(0.7, 0.9, 0.7),
+            text_keybind: Color::srgb(0.9, 0.8, 0.5),
+            font: Handle::default(),
+            title_size: 16.0,
+            body_size: 13.0,
+            small_size: 12.0,
+            padding: 10.0,
+            gap: 6.0,
+            border_width: 1.0,
+            panel_image: None,
+            colorblind_mode: ColorblindMode::Off,
+            color_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl UiTheme {
+    /// Column layout with the theme's padding, border and gap; position it with struct update syntax
+    pub fn panel_node(&self) -> Node {
+        Node {
+            padding: UiRect::all(Val::Px(self.padding)),
+            border: UiRect::all(Val::Px(self.border_width)),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(self.gap),
+            ..default()
+        }
+    }
+
+    /// Background, border and marker for a panel laid out by `node`
+    pub fn panel(&self, node: Node) -> (Node, BackgroundColor, BorderColor, ThemedPanel) {
+        (node, BackgroundColor(self.panel_background), BorderColor(self.panel_border), ThemedPanel)
+    }
+
+    /// A clickable button with the theme's colors
+    pub fn button(&self, node: Node) -> (Button, Node, BackgroundColor, BorderColor) {
+        (Button, node, BackgroundColor(self.button_background), BorderColor(self.button_border))
+    }
+
+    pub fn font(&self, font_size: f32) -> TextFont {
+        TextFont {
+            font: self.font.clone(),
+            font_size,
+            ..default()
+        }
+    }
+
+    pub fn text(&self, text: impl Into<String>, font_size: f32) -> (Text, TextFont, TextColor) {
+        (Text::new(text), self.font(font_size), TextColor(self.text))
+    }
+
+    pub fn title(&self, text: impl Into<String>) -> (Text, TextFont, TextColor) {
+        self.text(text, self.title_size)
+    }
+
+    /// Switch the named colors to the palette for `mode`
+    pub fn set_colorblind_mode(&mut self, mode: ColorblindMode) {
+        let overrides = match mode {
+            ColorblindMode::Off => &[][..],
+            ColorblindMode::Deuteranopia | ColorblindMode::Protanopia => RED_GREEN_OVERRIDES,
+            ColorblindMode::Tritanopia => BLUE_YELLOW_OVERRIDES,
+        };
+        self.colorblind_mode = mode;
+        self.color_overrides = overrides.iter().copied().collect();
+    }
+
+    /// Look up a named color, e.g. `red`, `magical` or `heal`
+    pub fn color(&self, name: &str) -> Option<Color> {
+        self.color_overrides.get(name).copied().or_else(|| markup_color(name))
+    }
+
+    /// Named color, falling back to white for unknown names
+    pub fn get(&self, name: &str) -> Color {
+        self.color(name).unwrap_or(Color::WHITE)
+    }
+
+    /// Glyph and tint of an inline icon
+    pub fn icon(&self, name: &str) -> Option<(&'static str, Color)> {
+        markup_icon(name).map(|(glyph, color)| (glyph, self.color_overrides.get(name).copied().unwrap_or(color)))
+    }
+}
+
+/// Panel built from `UiTheme::panel`, gets the nine-slice image when the theme has one
+#[derive(Component, Default)]
+pub struct ThemedPanel;
+
+fn apply_panel_images(
+    mut commands: Commands,
+    theme: Res<UiTheme>,
+    panels: Query<Entity, Added<ThemedPanel>>,
+) {
+    let Some(slice) = &theme.panel_image else {
+        return;
+    };
+
+    for entity in panels.iter() {
+        commands.entity(entity).insert((
+            BackgroundColor(Color::NONE),
+            ImageNode::new(slice.image.clone()).with_mode(NodeImageMode::Sliced(TextureSlicer {
+                border: BorderRect::all(slice.border),
+                ..default()
+            })),
+        ));
+    }
+}
+
+/// Color vision deficiency the palette is adjusted for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorblindMode {
+    #[default]
+    Off,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl ColorblindMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Deuteranopia => "Deuteranopia",
+            Self::Protanopia => "Protanopia",
+            Self::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// Next mode when cycling through the options
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Off => Self::Deuteranopia,
+            Self::Deuteranopia => Self::Protanopia,
+            Self::Protanopia => Self::Tritanopia,
+            Self::Tritanopia => Self::Off,
+        }
+    }
+}
+
+/// Red and green pulled apart into orange and sky blue (Okabe-Ito hues)
+const RED_GREEN_OVERRIDES: &[(&str, Color)] = &[
+    ("red", Color::srgb(0.84, 0.37, 0.0)),
+    ("green", Color::srgb(0.35, 0.7, 0.9)),
+    ("blue", Color::srgb(0.2, 0.45, 0.95)),
+    ("orange", Color::srgb(0.9, 0.6, 0.0)),
+    ("heal", Color::srgb(0.35, 0.7, 0.9)),
+    ("overheal", Color::srgb(0.85, 0.8, 1.0)),
+    ("fire", Color::srgb(0.9, 0.6, 0.0)),
+    ("poison", Color::srgb(0.8, 0.47, 0.65)),
+    ("health", Color::srgb(0.84, 0.37, 0.0)),
+];
+
+/// Blue and yellow swapped for cyan and pink, which tritanopes can tell apart
+const BLUE_YELLOW_OVERRIDES: &[(&str, Color)] = &[
+    ("blue", Color::srgb(0.3, 0.85, 0.9)),
+    ("yellow", Color::srgb(1.0, 0.55, 0.6)),
+    ("purple", Color::srgb(0.85, 0.3, 0.6)),
+    ("magical", Color::srgb(0.85, 0.3, 0.6)),
+    ("overheal", Color::srgb(0.3, 0.85, 0.9)),
+    ("frost", Color::srgb(0.3, 0.85, 0.9)),
+    ("slow", Color::srgb(0.3, 0.85, 0.9)),
+    ("stun", Color::srgb(1.0, 0.55, 0.6)),
+    ("key", Color::srgb(1.0, 0.55, 0.6)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_theme_matches_markup() {
+        let theme = UiTheme::default();
+        assert_eq!(theme.color("red"), markup_color("red"));
+        assert_eq!(theme.icon("fire"), markup_icon("fire"));
+        assert_eq!(theme.color("nope"), None);
+        assert_eq!(theme.get("nope"), Color::WHITE);
+    }
+
+    #[test]
+    fn test_colorblind_modes_override_confusable_colors() {
+        let mut red_green = UiTheme::default();
+        red_green.set_colorblind_mode(ColorblindMode::Deuteranopia);
+        assert_ne!(red_green.color("green"), markup_color("green"));
+        assert_ne!(red_green.get("red"), red_green.get("green"));
+        assert_eq!(red_green.color("grey"), markup_color("grey"));
+
+        let mut blue_yellow = UiTheme::default();
+        blue_yellow.set_colorblind_mode(ColorblindMode::Tritanopia);
+        assert_ne!(blue_yellow.icon("frost"), markup_icon("frost"));
+        assert_eq!(blue_yellow.icon("frost").unwrap().0, "*");
+    }
+}
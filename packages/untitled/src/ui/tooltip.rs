@@ -1,18 +1,63 @@
 use bevy::prelude::*;
+use crate::inventory::InstanceId;
+use super::rich_text::spawn_rich_text;
+use super::theme::{ThemedPanel, UiTheme};
+
+/// Plugin providing the shared tooltip service used by world entities and inventory items
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TooltipState>()
+            .configure_sets(Update, TooltipSystems::Request.before(TooltipSystems::Display))
+            .add_systems(Update, (
+                handle_tooltip_hover
+                    .run_if(resource_equals(crate::resources::GameState::Playing))
+                    .in_set(TooltipSystems::Request),
+                (cleanup_orphaned_tooltips, display_tooltips)
+                    .chain()
+                    .in_set(TooltipSystems::Display),
+            ));
+    }
+}
+
+/// System sets for the tooltip service; providers run in `Request`, rendering in `Display`
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TooltipSystems {
+    Request,
+    Display,
+}
+
+/// A single block of rich tooltip content
+/// Text fields accept `{color}...{/}` and `{icon:name}` markup (see `ui::rich_text`)
+#[derive(Clone, Debug, PartialEq)]
+pub enum TooltipBlock {
+    /// Large heading line
+    Title(String),
+    /// Plain descriptive text
+    Text(String),
+    /// Labelled stat line, e.g. "Damage: 12.0"
+    Stat { label: String, value: String },
+    /// Stat compared against another value, showing the difference
+    Comparison { label: String, current: f32, other: f32 },
+    /// Key binding hint, e.g. "[E] Enter"
+    Keybind { key: String, action: String },
+}
 
 /// Tooltip component that holds content to display
 #[derive(Component, Clone)]
 pub struct Tooltip {
-    /// Text content to display in the tooltip
-    pub content: String,
+    /// Content blocks rendered top to bottom
+    pub blocks: Vec<TooltipBlock>,
     /// Offset from the entity position where tooltip should appear
     pub offset: Vec3,
-    /// Font size for the tooltip text
+    /// Base font size for the tooltip text
     pub font_size: f32,
-    /// Background color of the tooltip
-    pub background_color: Color,
-    /// Text color of the tooltip
-    pub text_color: Color,
+    /// Background color of the tooltip; the theme's panel background when not set
+    pub background_color: Option<Color>,
+    /// Title color of the tooltip; the theme's text color when not set
+    pub text_color: Option<Color>,
     /// Maximum width before text wrapping
     pub max_width: f32,
     /// Padding around the text
@@ -22,11 +67,11 @@ pub struct Tooltip {
 impl Default for Tooltip {
     fn default() -> Self {
         Self {
-            content: String::new(),
+            blocks: Vec::new(),
             offset: Vec3::new(0.0, 50.0, 10.0), // Default above the entity
             font_size: 14.0,
-            background_color: Color::srgba(0.0, 0.0, 0.0, 0.8), // Semi-transparent black
-            text_color: Color::WHITE,
+            background_color: None,
+            text_color: None,
             max_width: 200.0,
             padding: 10.0,
         }
@@ -34,20 +79,40 @@ impl Default for Tooltip {
 }
 
 impl Tooltip {
-    /// Create a new tooltip with content
-    pub fn new(content: impl Into<String>) -> Self {
+    /// Create a new tooltip with a title
+    pub fn new(title: impl Into<String>) -> Self {
         Self {
-            content: content.into(),
+            blocks: vec![TooltipBlock::Title(title.into())],
             ..default()
         }
     }
 
-    /// Set the tooltip content
-    pub fn with_content(mut self, content: impl Into<String>) -> Self {
-        self.content = content.into();
+    /// Append plain text content
+    pub fn with_content(self, content: impl Into<String>) -> Self {
+        self.with_block(TooltipBlock::Text(content.into()))
+    }
+
+    /// Append an arbitrary content block
+    pub fn with_block(mut self, block: TooltipBlock) -> Self {
+        self.blocks.push(block);
         self
     }
 
+    /// Append a labelled stat line
+    pub fn with_stat(self, label: impl Into<String>, value: impl ToString) -> Self {
+        self.with_block(TooltipBlock::Stat { label: label.into(), value: value.to_string() })
+    }
+
+    /// Append a stat comparison line
+    pub fn with_comparison(self, label: impl Into<String>, current: f32, other: f32) -> Self {
+        self.with_block(TooltipBlock::Comparison { label: label.into(), current, other })
+    }
+
+    /// Append a key binding hint
+    pub fn with_keybind(self, key: impl Into<String>, action: impl Into<String>) -> Self {
+        self.with_block(TooltipBlock::Keybind { key: key.into(), action: action.into() })
+    }
+
     /// Set the tooltip offset from the entity
     pub fn with_offset(mut self, offset: Vec3) -> Self {
         self.offset = offset;
@@ -62,13 +127,13 @@ impl Tooltip {
 
     /// Set the background color
     pub fn with_background_color(mut self, color: Color) -> Self {
-        self.background_color = color;
+        self.background_color = Some(color);
         self
     }
 
     /// Set the text color
     pub fn with_text_color(mut self, color: Color) -> Self {
-        self.text_color = color;
+        self.text_color = Some(color);
         self
     }
 
@@ -85,31 +150,70 @@ impl Tooltip {
     }
 }
 
+/// What a displayed tooltip is describing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TooltipSource {
+    /// A world entity carrying a `Tooltip` component (interactables, portals)
+    Entity(Entity),
+    /// An inventory item instance
+    Item(InstanceId),
+}
+
+/// A tooltip some provider wants shown this frame
+#[derive(Clone)]
+pub struct TooltipRequest {
+    pub source: TooltipSource,
+    pub tooltip: Tooltip,
+    /// Top-left corner of the tooltip in screen space
+    pub screen_position: Vec2,
+    /// Higher priority requests win when several providers ask in the same frame
+    pub priority: u8,
+}
+
 /// Component to mark tooltip UI entities for cleanup
 #[derive(Component)]
-pub struct TooltipUI {
-    /// The entity that owns this tooltip
-    pub owner: Entity,
-}
+pub struct TooltipUI;
 
 /// Resource to track the currently displayed tooltip
 #[derive(Resource, Default)]
 pub struct TooltipState {
     /// Currently displayed tooltip entity (if any)
     pub current_tooltip: Option<Entity>,
-    /// The owner of the current tooltip
-    pub current_owner: Option<Entity>,
+    /// The source of the current tooltip
+    pub current_source: Option<TooltipSource>,
+    /// Best request submitted this frame, consumed by `display_tooltips`
+    pending: Option<TooltipRequest>,
+}
+
+impl TooltipState {
+    /// Ask for a tooltip to be shown this frame; keeps only the highest priority request
+    pub fn request(&mut self, request: TooltipRequest) {
+        match &self.pending {
+            Some(existing) if existing.priority >= request.priority => {}
+            _ => self.pending = Some(request),
+        }
+    }
+
+    fn hide(&mut self, commands: &mut Commands) {
+        if let Some(mut entity) = self.current_tooltip.take().and_then(|e| commands.get_entity(e).ok()) {
+            entity.despawn();
+        }
+        self.current_source = None;
+    }
 }
 
-/// System to handle showing tooltips when hovering over entities with tooltip components
+/// Hover radius for world tooltips, matching the interaction system
+const HOVER_RADIUS: f32 = 60.0;
+
+/// Player distance at which proximity tooltips appear
+const PROXIMITY_RANGE: f32 = 100.0;
+
+/// System requesting tooltips for world entities under the cursor
 pub fn handle_tooltip_hover(
-    mut commands: Commands,
     mut tooltip_state: ResMut<TooltipState>,
-    tooltip_query: Query<(Entity, &Tooltip, &Transform), With<Tooltip>>,
+    tooltip_query: Query<(Entity, &Tooltip, &Transform)>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera>>,
     windows: Query<&Window>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
     let Ok(window) = windows.single() else {
         return;
@@ -120,162 +224,155 @@ pub fn handle_tooltip_hover(
     };
 
     // Get cursor position and convert to world coordinates (matching interaction system)
-    let world_cursor = window.cursor_position()
-        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok());
-
-    const HOVER_RADIUS: f32 = 60.0; // Match interaction system hover radius
+    let Some(cursor_world) = window.cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok()) else {
+        return;
+    };
 
     // Find the closest tooltip entity under the cursor (matching interaction system logic)
-    let mut closest_hovered: Option<(Entity, &Tooltip, &Transform, f32)> = None;
-
-    if let Some(cursor_world) = world_cursor {
-        for (entity, tooltip, transform) in tooltip_query.iter() {
-            let hover_distance = cursor_world.distance(transform.translation.truncate());
-            if hover_distance <= HOVER_RADIUS {
-                if let Some((_, _, _, closest_distance)) = closest_hovered {
-                    if hover_distance < closest_distance {
-                        closest_hovered = Some((entity, tooltip, transform, hover_distance));
-                    }
-                } else {
-                    closest_hovered = Some((entity, tooltip, transform, hover_distance));
-                }
-            }
-        }
-    }
-
-    // Handle tooltip display based on closest hovered entity (matching interaction system logic)
-    match closest_hovered {
-        Some((entity, tooltip, transform, _)) => {
-            // Show tooltip if not already showing for this entity
-            if tooltip_state.current_owner != Some(entity) {
-                // Clean up existing tooltip
-                if let Some(current_tooltip) = tooltip_state.current_tooltip {
-                    commands.entity(current_tooltip).despawn();
-                }
-
-                // Create new tooltip
-                let tooltip_entity = spawn_tooltip_ui(&mut commands, tooltip, transform, &mut meshes, &mut materials);
-                tooltip_state.current_tooltip = Some(tooltip_entity);
-                tooltip_state.current_owner = Some(entity);
-            }
-        }
-        None => {
-            // Hide tooltip if cursor is not over any tooltip entity
-            if let Some(current_tooltip) = tooltip_state.current_tooltip.take() {
-                commands.entity(current_tooltip).despawn();
-                tooltip_state.current_owner = None;
-            }
+    let closest_hovered = tooltip_query.iter()
+        .map(|(entity, tooltip, transform)| {
+            (entity, tooltip, transform, cursor_world.distance(transform.translation.truncate()))
+        })
+        .filter(|(_, _, _, distance)| *distance <= HOVER_RADIUS)
+        .min_by(|a, b| a.3.total_cmp(&b.3));
+
+    if let Some((entity, tooltip, transform, _)) = closest_hovered {
+        let anchor = transform.translation + tooltip.offset;
+        if let Ok(screen_position) = camera.world_to_viewport(camera_transform, anchor) {
+            tooltip_state.request(TooltipRequest {
+                source: TooltipSource::Entity(entity),
+                tooltip: tooltip.clone(),
+                screen_position,
+                priority: 0,
+            });
         }
     }
 }
 
-/// System to handle showing tooltips when close to interactable entities
+/// System requesting tooltips when the player is close to entities with tooltips
 pub fn handle_tooltip_proximity(
-    mut commands: Commands,
     mut tooltip_state: ResMut<TooltipState>,
-    tooltip_query: Query<(Entity, &Tooltip, &Transform), With<Tooltip>>,
+    tooltip_query: Query<(Entity, &Tooltip, &Transform)>,
     player_query: Query<&Transform, (With<crate::player::Player>, Without<Tooltip>)>,
-    ui_tooltip_query: Query<Entity, With<TooltipUI>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera>>,
 ) {
     let Ok(player_transform) = player_query.single() else {
         return;
     };
 
-    let mut closest_tooltip: Option<(Entity, &Tooltip, &Transform, f32)> = None;
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        return;
+    };
 
     // Find the closest tooltip entity within range
-    for (entity, tooltip, transform) in tooltip_query.iter() {
-        let distance = player_transform.translation.distance(transform.translation);
-
-        // Check if within interaction range (you can adjust this threshold)
-        if distance < 100.0 {
-            match closest_tooltip {
-                Some((_, _, _, closest_distance)) if distance < closest_distance => {
-                    closest_tooltip = Some((entity, tooltip, transform, distance));
-                }
-                None => {
-                    closest_tooltip = Some((entity, tooltip, transform, distance));
-                }
-                _ => {}
-            }
+    let closest_tooltip = tooltip_query.iter()
+        .map(|(entity, tooltip, transform)| {
+            (entity, tooltip, transform, player_transform.translation.distance(transform.translation))
+        })
+        .filter(|(_, _, _, distance)| *distance < PROXIMITY_RANGE)
+        .min_by(|a, b| a.3.total_cmp(&b.3));
+
+    if let Some((entity, tooltip, transform, _)) = closest_tooltip {
+        let anchor = transform.translation + tooltip.offset;
+        if let Ok(screen_position) = camera.world_to_viewport(camera_transform, anchor) {
+            tooltip_state.request(TooltipRequest {
+                source: TooltipSource::Entity(entity),
+                tooltip: tooltip.clone(),
+                screen_position,
+                priority: 0,
+            });
         }
     }
+}
 
-    match closest_tooltip {
-        Some((entity, tooltip, transform, _)) => {
-            // Show tooltip if not already showing for this entity
-            if tooltip_state.current_owner != Some(entity) {
-                // Clean up existing tooltip
-                if let Some(current_tooltip) = tooltip_state.current_tooltip {
-                    if let Ok(ui_entity) = ui_tooltip_query.get(current_tooltip) {
-                        commands.entity(ui_entity).despawn();
-                    }
-                }
-
-                // Create new tooltip
-                let tooltip_entity = spawn_tooltip_ui(&mut commands, tooltip, transform, &mut meshes, &mut materials);
-                tooltip_state.current_tooltip = Some(tooltip_entity);
-                tooltip_state.current_owner = Some(entity);
-            }
-        }
-        None => {
-            // Hide tooltip if player is not close to any tooltip entity
-            if let Some(current_tooltip) = tooltip_state.current_tooltip.take() {
-                if let Ok(ui_entity) = ui_tooltip_query.get(current_tooltip) {
-                    commands.entity(ui_entity).despawn();
-                }
-                tooltip_state.current_owner = None;
-            }
+/// System that shows the winning tooltip request, reusing the panel while the source is unchanged
+pub fn display_tooltips(
+    mut commands: Commands,
+    mut tooltip_state: ResMut<TooltipState>,
+    theme: Res<UiTheme>,
+    mut panel_query: Query<&mut Node, With<TooltipUI>>,
+) {
+    let Some(request) = tooltip_state.pending.take() else {
+        tooltip_state.hide(&mut commands);
+        return;
+    };
+
+    if tooltip_state.current_source == Some(request.source) && !theme.is_changed() {
+        // Same source, just follow the anchor
+        if let Some(mut node) = tooltip_state.current_tooltip.and_then(|e| panel_query.get_mut(e).ok()) {
+            node.left = Val::Px(request.screen_position.x);
+            node.top = Val::Px(request.screen_position.y);
         }
+        return;
     }
+
+    tooltip_state.hide(&mut commands);
+    let tooltip_entity = spawn_tooltip_panel(&mut commands, &theme, &request.tooltip, request.screen_position);
+    tooltip_state.current_tooltip = Some(tooltip_entity);
+    tooltip_state.current_source = Some(request.source);
 }
 
-/// Helper function to spawn tooltip UI using 2D text rendering
-fn spawn_tooltip_ui(
+/// Spawn a tooltip panel rendering each content block as its own line
+pub fn spawn_tooltip_panel(
     commands: &mut Commands,
+    theme: &UiTheme,
     tooltip: &Tooltip,
-    entity_transform: &Transform,
-    meshes: &mut Assets<Mesh>,
-    materials: &mut Assets<ColorMaterial>,
+    screen_position: Vec2,
 ) -> Entity {
-    let tooltip_position = entity_transform.translation + tooltip.offset;
-
-    // Calculate background size based on text content
-    let text_width = tooltip.content.len() as f32 * tooltip.font_size * 0.6; // Rough estimate
-    let text_height = tooltip.content.lines().count() as f32 * tooltip.font_size * 1.2;
-    let bg_width = (text_width + tooltip.padding * 2.0).min(tooltip.max_width + tooltip.padding * 2.0);
-    let bg_height = text_height + tooltip.padding * 2.0;
-
-    // Create background mesh and material
-    let background_mesh = meshes.add(Rectangle::new(bg_width, bg_height));
-    let background_material = materials.add(tooltip.background_color);
-
-    // Create a parent entity that contains both background and text
-    commands.spawn((
-        TooltipUI { owner: Entity::PLACEHOLDER },
-        Transform::from_translation(tooltip_position),
-        Visibility::Inherited,
-    )).with_children(|parent| {
-        // Background rectangle
-        parent.spawn((
-            Mesh2d(background_mesh),
-            MeshMaterial2d(background_material),
-            Transform::from_translation(Vec3::ZERO),
-        ));
-
-        // Text
-        parent.spawn((
-            Text2d::new(&tooltip.content),
-            TextFont {
-                font_size: tooltip.font_size,
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(screen_position.x),
+                top: Val::Px(screen_position.y),
+                max_width: Val::Px(tooltip.max_width),
+                padding: UiRect::all(Val::Px(tooltip.padding)),
+                border: UiRect::all(Val::Px(theme.border_width)),
+                flex_direction: FlexDirection::Column,
                 ..default()
             },
-            TextColor(tooltip.text_color),
-            Transform::from_translation(Vec3::new(0.0, 0.0, 0.1)), // Slightly above background
-        ));
-    }).id()
+            BackgroundColor(tooltip.background_color.unwrap_or(theme.panel_background)),
+            BorderColor(theme.panel_border),
+            ThemedPanel,
+            GlobalZIndex(100), // Above inventory and HUD
+            TooltipUI,
+        ))
+        .with_children(|parent| {
+            for block in &tooltip.blocks {
+                let (text, font_size, color, margin) = block_style(block, tooltip, theme);
+                spawn_rich_text(parent, theme, &text, font_size, color, Node {
+                    margin: UiRect::bottom(Val::Px(margin)),
+                    ..default()
+                });
+            }
+        })
+        .id()
+}
+
+/// Text, font size, color and bottom margin for a content block
+fn block_style(block: &TooltipBlock, tooltip: &Tooltip, theme: &UiTheme) -> (String, f32, Color, f32) {
+    match block {
+        TooltipBlock::Title(title) => (title.clone(), tooltip.font_size + 2.0, tooltip.text_color.unwrap_or(theme.text), 4.0),
+        TooltipBlock::Text(text) => (text.clone(), tooltip.font_size - 2.0, theme.text_muted, 4.0),
+        TooltipBlock::Stat { label, value } => {
+            (format!("{}: {}", label, value), tooltip.font_size - 3.0, theme.text_stat, 1.0)
+        }
+        TooltipBlock::Comparison { label, current, other } => {
+            let delta = current - other;
+            let color = if delta > 0.0 {
+                theme.get("green")
+            } else if delta < 0.0 {
+                theme.get("red")
+            } else {
+                theme.get("grey")
+            };
+            (format!("{}: {:.1} ({:+.1})", label, current, delta), tooltip.font_size - 3.0, color, 1.0)
+        }
+        TooltipBlock::Keybind { key, action } => {
+            (format!("[{}] {}", key, action), tooltip.font_size - 3.0, theme.text_keybind, 0.0)
+        }
+    }
 }
 
 /// Cleanup system to remove tooltips when their owner entities are despawned
@@ -284,14 +381,10 @@ pub fn cleanup_orphaned_tooltips(
     mut tooltip_state: ResMut<TooltipState>,
     tooltip_query: Query<Entity, With<Tooltip>>,
 ) {
-    if let Some(current_owner) = tooltip_state.current_owner {
-        // Check if the owner entity still exists
-        if tooltip_query.get(current_owner).is_err() {
-            // Owner is gone, clean up the tooltip
-            if let Some(current_tooltip) = tooltip_state.current_tooltip.take() {
-                commands.entity(current_tooltip).despawn();
-                tooltip_state.current_owner = None;
-            }
+    if let Some(TooltipSource::Entity(owner)) = tooltip_state.current_source {
+        // Owner is gone, clean up the tooltip
+        if tooltip_query.get(owner).is_err() {
+            tooltip_state.hide(&mut commands);
         }
     }
 }
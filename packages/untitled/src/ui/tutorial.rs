@@ -0,0 +1,357 @@
+//! One-time tutorial prompts
+//!
+//! Each prompt is queued the first time its trigger happens (the player
+//! spawning, taking a hit, picking something up, walking up to a portal) and
+//! shown until the player does what it asks or it times out. Either way it
+//! counts as seen and is kept in the chunk database, so it never shows again.
+//! Doing the action before the prompt comes up counts too. Prompts can be
+//! turned off in the options panel.
+use bevy::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+use crate::events::{DamageEvent, PortalActivationEvent};
+use crate::inventory::{ui::InventoryUiState, Inventory};
+use crate::persistence::ChunkDatabase;
+use crate::player::{
+    actions::{key_label, PlayerAction, PlayerActionEvent, PlayerInputBindings},
+    Player,
+};
+use crate::world::{scenes::cathedral::Portal, Interactable, SceneLoadState};
+use super::accessibility::AccessibilitySettings;
+use super::theme::UiTheme;
+
+/// Seconds a prompt stays up if the player doesn't do what it asks
+const PROMPT_TIMEOUT_SECS: f32 = 12.0;
+
+/// A hint shown once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TutorialPrompt {
+    Move,
+    Dash,
+    OpenInventory,
+    UsePortal,
+}
+
+impl TutorialPrompt {
+    pub const ALL: [TutorialPrompt; 4] = [Self::Move, Self::Dash, Self::OpenInventory, Self::UsePortal];
+
+    /// Name the prompt is saved under; don't change these or seen prompts show again
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Move => "move",
+            Self::Dash => "dash",
+            Self::OpenInventory => "open_inventory",
+            Self::UsePortal => "use_portal",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|prompt| prompt.id() == id)
+    }
+
+    /// What the prompt says, with the keys as currently bound
+    fn text(self, bindings: &PlayerInputBindings) -> String {
+        match self {
+            Self::Move => format!(
+                "Move with {} {} {} {}",
+                key_label(bindings.move_up),
+                key_label(bindings.move_left),
+                key_label(bindings.move_down),
+                key_label(bindings.move_right),
+            ),
+            Self::Dash => format!("Press {} to dash out of danger", key_label(bindings.dash)),
+            Self::OpenInventory => format!(
+                "Picked something up! Press {} to open your inventory",
+                key_label(bindings.toggle_inventory),
+            ),
+            Self::UsePortal => format!("Press {} to step through the portal", key_label(bindings.interact)),
+        }
+    }
+}
+
+/// Which prompts have been seen and which are waiting to show
+#[derive(Resource, Debug, Default)]
+pub struct TutorialPrompts {
+    seen: HashSet<TutorialPrompt>,
+    queued: VecDeque<TutorialPrompt>,
+    showing: Option<TutorialPrompt>,
+    /// Seconds the prompt on screen has been up
+    shown_for: f32,
+    /// Seen but not yet written to the database
+    unsaved: Vec<TutorialPrompt>,
+}
+
+impl TutorialPrompts {
+    /// Queue a prompt unless it's been seen or is already waiting
+    pub fn trigger(&mut self, prompt: TutorialPrompt) {
+        if !self.seen.contains(&prompt) && self.showing != Some(prompt) && !self.queued.contains(&prompt) {
+            self.queued.push_back(prompt);
+        }
+    }
+
+    /// The player did what a prompt asks, so it needn't show (again)
+    pub fn complete(&mut self, prompt: TutorialPrompt) {
+        if self.seen.insert(prompt) {
+            self.unsaved.push(prompt);
+        }
+        self.queued.retain(|queued| *queued != prompt);
+        if self.showing == Some(prompt) {
+            self.showing = None;
+        }
+    }
+
+    /// Time out the prompt on screen and bring up the next one
+    pub fn tick(&mut self, delta_secs: f32) {
+        if let Some(prompt) = self.showing {
+            self.shown_for += delta_secs;
+            if self.shown_for >= PROMPT_TIMEOUT_SECS {
+                self.complete(prompt);
+            }
+        }
+        if self.showing.is_none() {
+            self.showing = self.queued.pop_front();
+            self.shown_for = 0.0;
+        }
+    }
+
+    /// Drop everything waiting, for when prompts are turned off
+    pub fn clear(&mut self) {
+        self.queued.clear();
+        self.showing = None;
+    }
+
+    pub fn showing(&self) -> Option<TutorialPrompt> {
+        self.showing
+    }
+}
+
+/// Plugin for tutorial prompts
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TutorialPrompts>()
+            .add_systems(Update, (
+                // Also when the database comes back after failing to open
+                load_seen_tutorial_prompts.run_if(resource_added::<ChunkDatabase>),
+                trigger_tutorial_prompts,
+                complete_tutorial_prompts,
+                advance_tutorial_prompts.run_if(in_state(SceneLoadState::Ready)),
+                save_seen_tutorial_prompts,
+                refresh_tutorial_prompt,
+            ).chain());
+    }
+}
+
+/// Root node of the prompt on screen
+#[derive(Component)]
+struct TutorialPromptBox;
+
+fn load_seen_tutorial_prompts(database: Res<ChunkDatabase>, mut prompts: ResMut<TutorialPrompts>) {
+    match database.seen_tutorial_prompts() {
+        Ok(ids) => {
+            for prompt in ids.iter().filter_map(|id| TutorialPrompt::from_id(id)) {
+                prompts.complete(prompt);
+            }
+            // Already in the database
+            prompts.unsaved.retain(|prompt| !ids.iter().any(|id| id == prompt.id()));
+        }
+        Err(e) => warn!("Failed to load seen tutorial prompts: {}", e),
+    }
+}
+
+fn trigger_tutorial_prompts(
+    settings: Res<AccessibilitySettings>,
+    mut prompts: ResMut<TutorialPrompts>,
+    new_players: Query<(), Added<Player>>,
+    players: Query<(Entity, &GlobalTransform, &Inventory), With<Player>>,
+    portals: Query<(&GlobalTransform, &Interactable), With<Portal>>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut last_item_count: Local<Option<usize>>,
+) {
+    let Ok((player, transform, inventory)) = players.single() else {
+        damage_events.clear();
+        return;
+    };
+
+    // Count items even while prompts are off, so turning them on doesn't count the whole inventory as new
+    let item_count = inventory.item_count();
+    let picked_up = last_item_count.is_some_and(|last| item_count > last);
+    *last_item_count = Some(item_count);
+
+    let hit = damage_events.read().any(|event| event.target == player);
+    if !settings.tutorial_prompts {
+        return;
+    }
+
+    if !new_players.is_empty() {
+        prompts.trigger(TutorialPrompt::Move);
+    }
+    if hit {
+        prompts.trigger(TutorialPrompt::Dash);
+    }
+    if picked_up {
+        prompts.trigger(TutorialPrompt::OpenInventory);
+    }
+
+    let position = transform.translation().truncate();
+    let near_portal = portals.iter().any(|(portal, interactable)| {
+        portal.translation().truncate().distance(position) <= interactable.interaction_range
+    });
+    if near_portal {
+        prompts.trigger(TutorialPrompt::UsePortal);
+    }
+}
+
+fn complete_tutorial_prompts(
+    mut prompts: ResMut<TutorialPrompts>,
+    mut action_events: EventReader<PlayerActionEvent>,
+    mut portal_events: EventReader<PortalActivationEvent>,
+    inventory_ui: Res<InventoryUiState>,
+) {
+    for event in action_events.read() {
+        match event.action {
+            PlayerAction::MoveUp | PlayerAction::MoveDown | PlayerAction::MoveLeft | PlayerAction::MoveRight => {
+                prompts.complete(TutorialPrompt::Move);
+            }
+            PlayerAction::Dash => prompts.complete(TutorialPrompt::Dash),
+            _ => {}
+        }
+    }
+    if inventory_ui.is_open {
+        prompts.complete(TutorialPrompt::OpenInventory);
+    }
+    if portal_events.read().count() > 0 {
+        prompts.complete(TutorialPrompt::UsePortal);
+    }
+}
+
+fn advance_tutorial_prompts(
+    time: Res<Time<Real>>,
+    settings: Res<AccessibilitySettings>,
+    mut prompts: ResMut<TutorialPrompts>,
+) {
+    if settings.tutorial_prompts {
+        prompts.tick(time.delta_secs());
+    } else if prompts.showing().is_some() || !prompts.queued.is_empty() {
+        prompts.clear();
+    }
+}
+
+/// Write newly seen prompts, holding on to them while the database is unavailable
+fn save_seen_tutorial_prompts(database: Option<Res<ChunkDatabase>>, mut prompts: ResMut<TutorialPrompts>) {
+    let Some(database) = database else {
+        return;
+    };
+    if prompts.unsaved.is_empty() {
+        return;
+    }
+
+    let unsaved = std::mem::take(&mut prompts.unsaved);
+    for prompt in unsaved {
+        if let Err(e) = database.mark_tutorial_prompt_seen(prompt.id()) {
+            warn!("Failed to save seen tutorial prompt {}: {}", prompt.id(), e);
+            prompts.unsaved.push(prompt);
+        }
+    }
+}
+
+fn refresh_tutorial_prompt(
+    mut commands: Commands,
+    prompts: Res<TutorialPrompts>,
+    theme: Res<UiTheme>,
+    bindings: Res<PlayerInputBindings>,
+    boxes: Query<Entity, With<TutorialPromptBox>>,
+    mut shown: Local<Option<TutorialPrompt>>,
+) {
+    // The prompts resource changes every frame while one is up, so compare what's shown instead
+    if prompts.showing() == *shown && !bindings.is_changed() {
+        return;
+    }
+    *shown = prompts.showing();
+
+    for entity in boxes.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(prompt) = prompts.showing() else {
+        return;
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(80.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            TutorialPromptBox,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(theme.panel(Node {
+                    padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                    ..theme.panel_node()
+                }))
+                .with_child(theme.text(prompt.text(&bindings), theme.body_size));
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_shows_once_until_completed() {
+        let mut prompts = TutorialPrompts::default();
+        prompts.trigger(TutorialPrompt::Move);
+        prompts.trigger(TutorialPrompt::Dash);
+        prompts.trigger(TutorialPrompt::Move);
+        prompts.tick(0.1);
+        assert_eq!(prompts.showing(), Some(TutorialPrompt::Move));
+
+        prompts.complete(TutorialPrompt::Move);
+        prompts.tick(0.1);
+        assert_eq!(prompts.showing(), Some(TutorialPrompt::Dash));
+
+        prompts.trigger(TutorialPrompt::Move);
+        prompts.complete(TutorialPrompt::Dash);
+        prompts.tick(0.1);
+        assert_eq!(prompts.showing(), None);
+        assert_eq!(prompts.unsaved, vec![TutorialPrompt::Move, TutorialPrompt::Dash]);
+    }
+
+    #[test]
+    fn test_prompt_times_out_as_seen() {
+        let mut prompts = TutorialPrompts::default();
+        prompts.trigger(TutorialPrompt::UsePortal);
+        prompts.tick(0.0);
+        prompts.tick(PROMPT_TIMEOUT_SECS);
+
+        assert_eq!(prompts.showing(), None);
+        prompts.trigger(TutorialPrompt::UsePortal);
+        prompts.tick(0.0);
+        assert_eq!(prompts.showing(), None);
+    }
+
+    #[test]
+    fn test_prompt_text_follows_bindings() {
+        let mut bindings = PlayerInputBindings::default();
+        assert_eq!(TutorialPrompt::Move.text(&bindings), "Move with W A S D");
+        assert_eq!(TutorialPrompt::Dash.text(&bindings), "Press Space to dash out of danger");
+
+        bindings.dash = KeyCode::ShiftLeft;
+        bindings.interact = KeyCode::KeyF;
+        assert_eq!(TutorialPrompt::Dash.text(&bindings), "Press ShiftLeft to dash out of danger");
+        assert_eq!(TutorialPrompt::UsePortal.text(&bindings), "Press F to step through the portal");
+    }
+
+    #[test]
+    fn test_prompt_ids_round_trip() {
+        for prompt in TutorialPrompt::ALL {
+            assert_eq!(TutorialPrompt::from_id(prompt.id()), Some(prompt));
+        }
+    }
+}
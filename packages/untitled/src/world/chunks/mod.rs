@@ -142,6 +142,36 @@ impl ChunkRegistry {
     pub fn get_loaders_for_chunk(&self, chunk_coord: ChunkCoord) -> Vec<Entity> {
         self.active_chunks.get(&chunk_coord).map_or(Vec::new(), |set| set.iter().copied().collect())
     }
+
+    /// Where a chunk is in its load cycle for the given loaders and the chunks they stand in,
+    /// or `None` if no loader wants it
+    pub fn load_state(&self, chunk_coord: ChunkCoord, loaders: &[(ChunkCoord, &ChunkLoader)]) -> Option<ChunkLoadState> {
+        // Same Manhattan distance the loaders use
+        let within = |center: ChunkCoord, radius: i32| (chunk_coord - center).abs().element_sum() <= radius;
+        let in_load_radius = loaders.iter().any(|(center, loader)| within(*center, loader.radius));
+        let in_preload_radius = loaders.iter().any(|(center, loader)| {
+            loader.preload_radius.is_some_and(|radius| within(*center, radius))
+        });
+
+        match (self.active_chunks.contains_key(&chunk_coord), in_load_radius, in_preload_radius) {
+            (true, true, _) => Some(ChunkLoadState::Active),
+            (true, false, _) => Some(ChunkLoadState::PendingUnload),
+            (false, false, true) => Some(ChunkLoadState::Preload),
+            // Chunks just come into the load radius are picked up next frame
+            (false, ..) => None,
+        }
+    }
+}
+
+/// Where a chunk is in its load cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkLoadState {
+    /// Loaded and inside a loader's load radius
+    Active,
+    /// Being loaded in the background ahead of a loader
+    Preload,
+    /// Still loaded, but only held by the unload radius; it goes once every loader is further away
+    PendingUnload,
 }
 
 /// Plugin for chunk management systems
@@ -169,3 +199,26 @@ impl Plugin for ChunkPlugin {
             );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_state_follows_loader_radii() {
+        let loader = ChunkLoader::with_preload(2, 4, 3);
+        let loaders = [(ChunkCoord::ZERO, &loader)];
+        let holder = Entity::from_raw(1);
+        let registry = ChunkRegistry {
+            active_chunks: HashMap::from([
+                (ChunkCoord::new(1, 1), HashSet::from([holder])),
+                (ChunkCoord::new(4, 0), HashSet::from([holder])),
+            ]),
+        };
+
+        assert_eq!(registry.load_state(ChunkCoord::new(1, 1), &loaders), Some(ChunkLoadState::Active));
+        assert_eq!(registry.load_state(ChunkCoord::new(4, 0), &loaders), Some(ChunkLoadState::PendingUnload));
+        assert_eq!(registry.load_state(ChunkCoord::new(0, 3), &loaders), Some(ChunkLoadState::Preload));
+        assert_eq!(registry.load_state(ChunkCoord::new(5, 5), &loaders), None);
+    }
+}
@@ -107,7 +107,7 @@ pub fn track_chunk_loaders(
 }
 
 /// Helper function to calculate chunks within a given Manhattan distance radius
-fn calculate_chunks_in_radius(center: ChunkCoord, radius: i32) -> Vec<ChunkCoord> {
+pub fn calculate_chunks_in_radius(center: ChunkCoord, radius: i32) -> Vec<ChunkCoord> {
     let mut chunks = Vec::new();
     for x in (center.x - radius)..=(center.x + radius) {
         let remaining_distance = radius - (x - center.x).abs();
@@ -0,0 +1,238 @@
+//! Daily seeded challenge
+//!
+//! Everyone playing on the same (UTC) day gets the same run: the dungeon seed,
+//! modifiers, branch layout and item drops are all derived from the date. A
+//! portal in the Cathedral starts the challenge and the result of each attempt
+//! is recorded in the local database.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::inventory::factory::ItemFactory;
+use crate::persistence::{ChunkDatabase, DailyResult};
+use crate::resources::GameState;
+use super::modifiers::{ModifierId, ModifierRegistry};
+use super::run_structure::RunStructure;
+use super::scenes::dungeon::resources::DungeonState;
+use super::states::{DungeonConfig, WorldState};
+use super::MapId;
+
+/// Modifiers rolled for every daily challenge
+const DAILY_MODIFIER_COUNT: usize = 3;
+
+/// Challenge for one calendar day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyChallenge {
+    /// Days since the Unix epoch
+    pub day: i64,
+    pub seed: u64,
+    pub modifiers: Vec<ModifierId>,
+}
+
+impl DailyChallenge {
+    pub fn for_day(day: i64, registry: &ModifierRegistry) -> Self {
+        let seed = day_seed(day);
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self {
+            day,
+            seed,
+            modifiers: registry.roll(DAILY_MODIFIER_COUNT, &mut rng),
+        }
+    }
+
+    /// Date as YYYY-MM-DD
+    pub fn date_label(&self) -> String {
+        let (year, month, day) = civil_from_days(self.day);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Current UTC day as days since the Unix epoch
+pub fn today() -> i64 {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    (secs / 86_400) as i64
+}
+
+/// Spread consecutive day numbers into unrelated seeds (splitmix64)
+fn day_seed(day: i64) -> u64 {
+    let mut z = (day as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Gregorian (year, month, day) for days since the Unix epoch
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month as u32, day as u32)
+}
+
+/// Present while a daily challenge attempt is in progress
+#[derive(Resource, Debug)]
+pub struct DailyRun {
+    pub day: i64,
+    pub died: bool,
+}
+
+/// Portal in the Cathedral that starts today's challenge
+#[derive(Component, Debug)]
+pub struct DailyPortal;
+
+/// Plugin for the daily challenge portal and result tracking
+pub struct DailyChallengePlugin;
+
+impl Plugin for DailyChallengePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(WorldState::Cathedral), (record_daily_result, spawn_daily_portal).chain())
+            .add_systems(FixedUpdate, (
+                start_daily_challenge.run_if(in_state(WorldState::Cathedral)),
+                mark_daily_death.run_if(resource_exists::<DailyRun>),
+            ));
+    }
+}
+
+fn spawn_daily_portal(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    registry: Res<ModifierRegistry>,
+    database: Option<Res<ChunkDatabase>>,
+) {
+    let challenge = DailyChallenge::for_day(today(), &registry);
+
+    let mut tooltip = crate::ui::tooltip::Tooltip::new(format!("Daily Challenge {}", challenge.date_label()))
+        .with_content("Same seed, modifiers and drops for everyone today");
+    for id in &challenge.modifiers {
+        tooltip = tooltip.with_content(registry.describe(*id));
+    }
+    let best = database.and_then(|db| db.best_daily_result(challenge.day).ok().flatten());
+    tooltip = match best {
+        Some(best) => tooltip.with_stat(
+            "Best today",
+            format!("depth {}{}", best.depth_reached, if best.died { " (died)" } else { "" }),
+        ),
+        None => tooltip.with_stat("Best today", "not attempted"),
+    };
+    tooltip = tooltip.with_keybind("E", "Start");
+
+    let center_x = (crate::world::tiles::TILEMAP_WIDTH as f32 * crate::world::tiles::TILE_SIZE) / 2.0;
+    let center_y = (crate::world::tiles::TILEMAP_HEIGHT as f32 * crate::world::tiles::TILE_SIZE) / 2.0;
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(80.0, 120.0))),
+        MeshMaterial2d(materials.add(Color::srgb(0.9, 0.75, 0.2))),
+        Transform::from_translation(Vec3::new(center_x, center_y + 250.0, crate::depth::DepthLayer::Actors.z())),
+        crate::depth::YSort::with_offset(-60.0),
+        DailyPortal,
+        StateScoped(WorldState::Cathedral),
+        tooltip,
+        crate::world::Interactable::new(
+            "daily_portal".to_string(),
+            "Daily Challenge".to_string(),
+            |_| {
+                info!("Daily challenge portal activated");
+            }
+        ),
+        crate::world::InteractableHighlight::default(),
+    ));
+}
+
+/// Seed the dungeon, run layout and item drops from today's challenge and descend
+fn start_daily_challenge(
+    mut commands: Commands,
+    mut interaction_events: EventReader<crate::world::InteractionEvent>,
+    portals: Query<(), With<DailyPortal>>,
+    registry: Res<ModifierRegistry>,
+    mut dungeon_state: ResMut<DungeonState>,
+    mut factory: ResMut<ItemFactory>,
+    mut next_state: ResMut<NextState<WorldState>>,
+) {
+    for event in interaction_events.read() {
+        if !portals.contains(event.target_entity) {
+            continue;
+        }
+
+        let challenge = DailyChallenge::for_day(today(), &registry);
+        info!("Starting daily challenge {} (seed {})", challenge.date_label(), challenge.seed);
+
+        *dungeon_state = DungeonState::new_for_depth(1);
+        dungeon_state.seed = challenge.seed;
+        dungeon_state.map_id = MapId::new(challenge.seed);
+        *factory = ItemFactory::with_seed(challenge.seed);
+
+        commands.insert_resource(DungeonConfig {
+            depth: 1,
+            modifiers: challenge.modifiers.clone(),
+            ..default()
+        });
+        commands.insert_resource(RunStructure::generate(1, challenge.modifiers, &registry, challenge.seed));
+        commands.insert_resource(DailyRun { day: challenge.day, died: false });
+
+        next_state.set(WorldState::Dungeon);
+        return;
+    }
+}
+
+fn mark_daily_death(game_state: Res<GameState>, mut run: ResMut<DailyRun>) {
+    if *game_state == GameState::GameOver && !run.died {
+        run.died = true;
+    }
+}
+
+/// Record a finished attempt when the player is back in the Cathedral
+fn record_daily_result(
+    mut commands: Commands,
+    run: Option<Res<DailyRun>>,
+    structure: Option<Res<RunStructure>>,
+    database: Option<Res<ChunkDatabase>>,
+    mut factory: ResMut<ItemFactory>,
+) {
+    let Some(run) = run else {
+        return;
+    };
+
+    let result = DailyResult {
+        day: run.day,
+        depth_reached: structure.map_or(1, |structure| structure.current_depth()),
+        died: run.died,
+    };
+    if let Some(database) = database {
+        match database.record_daily_result(&result) {
+            Ok(()) => info!("Daily challenge result recorded: {:?}", result),
+            Err(e) => error!("Failed to record daily result: {}", e),
+        }
+    }
+
+    // Back to unseeded drops for normal runs
+    *factory = ItemFactory::new();
+    commands.remove_resource::<DailyRun>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_742), (2026, 10, 16));
+    }
+
+    #[test]
+    fn test_challenge_depends_only_on_day() {
+        let registry = ModifierRegistry::with_basic_modifiers();
+        assert_eq!(DailyChallenge::for_day(20_742, &registry), DailyChallenge::for_day(20_742, &registry));
+        assert_ne!(DailyChallenge::for_day(20_742, &registry).seed, DailyChallenge::for_day(20_743, &registry).seed);
+        assert_eq!(DailyChallenge::for_day(20_742, &registry).date_label(), "2026-10-16");
+    }
+}
@@ -28,6 +28,13 @@ pub struct InteractionType {
     pub on_interact: InteractionCallback,
 }
 
+/// Callbacks can't be printed, so events show which interaction fired by id
+impl std::fmt::Debug for InteractionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InteractionType").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
 /// Component for objects that can be interacted with by the player
 #[derive(Component)]
 pub struct Interactable {
@@ -113,16 +120,14 @@ pub struct InteractableHighlight {
     pub is_hovered: bool,
     /// Whether the player is within interaction range
     pub in_range: bool,
-    /// Entity of the halo effect (if spawned)
-    pub halo_entity: Option<Entity>,
-    /// Halo color when player is in range
+    /// Outline color when player is in range
     pub in_range_color: Color,
-    /// Halo color when player is out of range
+    /// Outline color when player is out of range
     pub out_of_range_color: Color,
-    /// Halo radius multiplier (relative to object size)
-    pub halo_radius: f32,
-    /// Halo opacity when fully visible
-    pub halo_opacity: f32,
+    /// Outline width in world units
+    pub outline_width: f32,
+    /// Material to put back when the outline comes off
+    pub base_material: Option<Handle<ColorMaterial>>,
 }
 
 impl Default for InteractableHighlight {
@@ -130,35 +135,26 @@ impl Default for InteractableHighlight {
         Self {
             is_hovered: false,
             in_range: false,
-            halo_entity: None,
-            in_range_color: Color::srgba(0.2, 1.0, 0.3, 0.6), // Green glow for in-range
-            out_of_range_color: Color::srgba(1.0, 0.8, 0.2, 0.4), // Yellow/orange glow for out-of-range
-            halo_radius: 1.3,
-            halo_opacity: 0.6,
+            in_range_color: Color::srgba(0.2, 1.0, 0.3, 0.9), // Green glow for in-range
+            out_of_range_color: Color::srgba(1.0, 0.8, 0.2, 0.7), // Yellow/orange glow for out-of-range
+            outline_width: 6.0,
+            base_material: None,
         }
     }
 }
 
 impl InteractableHighlight {
-    /// Create a new highlight component with custom halo settings
-    pub fn new(in_range_color: Color, out_of_range_color: Color, halo_radius: f32) -> Self {
+    /// Create a new highlight component with custom outline settings
+    pub fn new(in_range_color: Color, out_of_range_color: Color, outline_width: f32) -> Self {
         Self {
             in_range_color,
             out_of_range_color,
-            halo_radius,
+            outline_width,
             ..Default::default()
         }
     }
 
-    /// Create a new highlight with default colors but custom radius
-    pub fn with_radius(halo_radius: f32) -> Self {
-        Self {
-            halo_radius,
-            ..Default::default()
-        }
-    }
-
-    /// Get the current halo color based on range state
+    /// Get the current outline color based on range state
     pub fn current_color(&self) -> Color {
         if self.in_range {
             self.in_range_color
@@ -173,7 +169,7 @@ impl InteractableHighlight {
 pub struct HoveredInteractable;
 
 /// Event fired when an interactable is activated
-#[derive(Event)]
+#[derive(Event, Debug)]
 pub struct InteractionEvent {
     /// Entity that was interacted with
     pub target_entity: Entity,
@@ -183,6 +179,19 @@ pub struct InteractionEvent {
     pub interaction_type: InteractionType,
 }
 
+/// Give interactables a default tooltip (name plus interact hint) unless they provide their own
+pub fn attach_interactable_tooltips(
+    mut commands: Commands,
+    interactables: Query<(Entity, &Interactable), (Added<Interactable>, Without<crate::ui::tooltip::Tooltip>)>,
+) {
+    for (entity, interactable) in interactables.iter() {
+        commands.entity(entity).insert(
+            crate::ui::tooltip::Tooltip::new(&interactable.display_name)
+                .with_keybind("E", "Interact"),
+        );
+    }
+}
+
 /// System to update cooldown timers on all interactables
 pub fn update_interactable_cooldowns(
     time: Res<Time>,
@@ -377,70 +386,3 @@ pub fn update_interactable_highlights(
         }
     }
 }
-
-/// Component to mark an entity as a halo effect
-#[derive(Component)]
-pub struct HaloEffect {
-    /// The interactable entity this halo belongs to
-    pub parent_entity: Entity,
-}
-
-/// Consolidated system to manage all halo effects for interactables
-/// Handles spawning, positioning, color updates, and cleanup in one system
-pub fn manage_halo_effects(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut interactables: Query<(Entity, &mut InteractableHighlight, &Transform), With<Interactable>>,
-    mut halos: Query<(&mut Transform, &MeshMaterial2d<ColorMaterial>), (With<HaloEffect>, Without<Interactable>)>,
-) {
-    for (entity, mut highlight, transform) in interactables.iter_mut() {
-        // Handle halo spawning
-        if highlight.is_hovered && highlight.halo_entity.is_none() {
-            let current_color = highlight.current_color();
-            let halo_entity = commands.spawn((
-                Mesh2d(meshes.add(Circle::new(80.0 * highlight.halo_radius))),
-                MeshMaterial2d(materials.add(ColorMaterial::from(current_color))),
-                Transform::from_translation(transform.translation + Vec3::new(0.0, 0.0, -0.1)),
-                HaloEffect { parent_entity: entity },
-            )).id();
-            highlight.halo_entity = Some(halo_entity);
-        }
-        // Handle halo cleanup
-        else if !highlight.is_hovered && highlight.halo_entity.is_some() {
-            if let Some(halo_entity) = highlight.halo_entity.take() {
-                commands.entity(halo_entity).despawn();
-            }
-        }
-        // Handle halo updates (position and color) for existing halos
-        else if let Some(halo_entity) = highlight.halo_entity {
-            if let Ok((mut halo_transform, material_handle)) = halos.get_mut(halo_entity) {
-                // Update position
-                halo_transform.translation = transform.translation + Vec3::new(0.0, 0.0, -0.1);
-
-                // Update color if highlight state changed
-                if let Some(material) = materials.get_mut(&material_handle.0) {
-                    let target_color = highlight.current_color();
-                    if material.color != target_color {
-                        material.color = target_color;
-                    }
-                }
-            }
-        }
-    }
-}
-
-/// System to clean up orphaned halo effects when their parent interactables are despawned
-pub fn cleanup_orphaned_halos(
-    mut commands: Commands,
-    halos: Query<(Entity, &HaloEffect)>,
-    interactables: Query<Entity, With<Interactable>>,
-) {
-    for (halo_entity, halo_effect) in halos.iter() {
-        // Check if the parent entity still exists and has an Interactable component
-        if !interactables.contains(halo_effect.parent_entity) {
-            // Parent interactable no longer exists, despawn the halo
-            commands.entity(halo_entity).despawn();
-        }
-    }
-}
@@ -0,0 +1,121 @@
+use bevy::color::Color;
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::components::EnemyArchetype;
+
+/// Noise frequency in chunks, lower values give larger biome regions
+const BIOME_NOISE_SCALE: f64 = 0.04;
+/// How far each dungeon level shifts the noise towards deeper biomes
+const BIOME_DEPTH_SHIFT: f64 = 0.04;
+
+/// Region type layered on top of a generated map
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Biome {
+    #[default]
+    Crypt,
+    Catacombs,
+    FungalCaverns,
+    Abyss,
+}
+
+/// Everything a biome changes about the chunks it covers
+#[derive(Clone, Debug)]
+pub struct BiomeDefinition {
+    pub name: &'static str,
+    /// Atlas indices used for this biome's tiles
+    pub floor_texture_index: u32,
+    pub wall_texture_index: u32,
+    /// Color multiplied onto every tile
+    pub tint: Color,
+    /// Background color while the player is inside the biome
+    pub ambience: Color,
+    /// Relative chance of hazards being placed here (0 = never)
+    pub hazard_frequency: f32,
+    /// Enemy spawn weights, multiplied with run modifiers
+    pub spawn_weights: [(EnemyArchetype, f32); 5],
+}
+
+impl Biome {
+    /// Biomes ordered from shallowest to deepest
+    pub const ALL: [Biome; 4] = [Biome::Crypt, Biome::Catacombs, Biome::FungalCaverns, Biome::Abyss];
+
+    pub fn definition(&self) -> BiomeDefinition {
+        use EnemyArchetype::*;
+
+        match self {
+            Biome::Crypt => BiomeDefinition {
+                name: "Crypt",
+                floor_texture_index: 0,
+                wall_texture_index: 1,
+                tint: Color::WHITE,
+                ambience: Color::srgb(0.08, 0.08, 0.1),
+                hazard_frequency: 0.5,
+                spawn_weights: [(SmallMelee, 2.0), (BigMelee, 1.0), (Shotgunner, 1.0), (Sniper, 0.5), (MachineGunner, 0.5)],
+            },
+            Biome::Catacombs => BiomeDefinition {
+                name: "Catacombs",
+                floor_texture_index: 0,
+                wall_texture_index: 1,
+                tint: Color::srgb(0.9, 0.85, 0.7),
+                ambience: Color::srgb(0.1, 0.08, 0.05),
+                hazard_frequency: 1.0,
+                spawn_weights: [(SmallMelee, 1.0), (BigMelee, 1.5), (Shotgunner, 1.5), (Sniper, 0.5), (MachineGunner, 1.0)],
+            },
+            Biome::FungalCaverns => BiomeDefinition {
+                name: "Fungal Caverns",
+                floor_texture_index: 0,
+                wall_texture_index: 1,
+                tint: Color::srgb(0.7, 0.95, 0.75),
+                ambience: Color::srgb(0.04, 0.1, 0.06),
+                hazard_frequency: 2.0,
+                spawn_weights: [(SmallMelee, 2.0), (BigMelee, 0.5), (Shotgunner, 1.0), (Sniper, 1.0), (MachineGunner, 0.5)],
+            },
+            Biome::Abyss => BiomeDefinition {
+                name: "Abyss",
+                floor_texture_index: 0,
+                wall_texture_index: 1,
+                tint: Color::srgb(0.75, 0.65, 0.95),
+                ambience: Color::srgb(0.05, 0.02, 0.08),
+                hazard_frequency: 1.5,
+                spawn_weights: [(SmallMelee, 0.5), (BigMelee, 1.5), (Shotgunner, 1.0), (Sniper, 1.5), (MachineGunner, 1.5)],
+            },
+        }
+    }
+}
+
+/// Biome for a chunk, from seeded noise shifted by depth
+///
+/// Deterministic for a given seed, so chunks loaded from the database get the
+/// same biome they were generated with.
+pub fn assign_biome(chunk_x: i32, chunk_y: i32, depth: u32, seed: u64) -> Biome {
+    let noise = OpenSimplex::new(seed as u32);
+    let value = noise.get([chunk_x as f64 * BIOME_NOISE_SCALE, chunk_y as f64 * BIOME_NOISE_SCALE]) * 0.5 + 0.5;
+    let shifted = (value + depth.saturating_sub(1) as f64 * BIOME_DEPTH_SHIFT).clamp(0.0, 0.999);
+
+    Biome::ALL[(shifted * Biome::ALL.len() as f64) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assignment_is_deterministic() {
+        for (x, y) in [(0, 0), (12, -4), (100, 37)] {
+            assert_eq!(assign_biome(x, y, 3, 42), assign_biome(x, y, 3, 42));
+        }
+    }
+
+    #[test]
+    fn test_depth_shifts_towards_deeper_biomes() {
+        let average = |depth| {
+            let total: usize = (0..40).flat_map(|x| (0..40).map(move |y| (x, y)))
+                .map(|(x, y)| assign_biome(x, y, depth, 7) as usize)
+                .sum();
+            total as f32 / 1600.0
+        };
+
+        assert!(average(20) > average(1));
+        assert_eq!(assign_biome(5, 5, 100, 7), Biome::Abyss);
+    }
+}
@@ -8,6 +8,11 @@ use super::*;
 /// The map will consist of a large central room with random paths leading
 /// outwards radially with some noise added to make it less uniform.
 pub fn freeform(size: usize, seed: u64) -> Vec<Vec<bool>> {
+    freeform_symmetric(size, seed, Symmetry::None)
+}
+
+/// `freeform` for arena maps, repeated by `symmetry` so every side gets the same layout
+pub fn freeform_symmetric(size: usize, seed: u64, symmetry: Symmetry) -> Vec<Vec<bool>> {
     let mut rng = StdRng::seed_from_u64(seed);
     let width = 3 * size / 4;
     let height = width;
@@ -56,7 +61,9 @@ pub fn freeform(size: usize, seed: u64) -> Vec<Vec<bool>> {
     simplex::generate_simplex_noise(&mut map, &mut rng, 0.045, 0.8);
     simplex::generate_simplex_noise(&mut map, &mut rng, 0.1, 0.7);
 
-    // Smoothing
+    apply_symmetry(&mut map, symmetry);
+
+    // Smoothing, which also evens out the seams between mirrored parts
     ca::cellular_automata(&mut map, 5, |_, _| { });
 
     save_png(&map, "out/macro_map.png");
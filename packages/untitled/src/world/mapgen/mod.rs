@@ -1,11 +1,16 @@
+pub mod biome;
 pub mod ca;
 pub mod freeform;
 pub mod operators;
 pub mod roomy;
+pub mod set_pieces;
 pub mod simplex;
 
+pub use biome::*;
 pub use ca::*;
 pub use freeform::*;
 pub use operators::*;
 pub use roomy::*;
+pub use set_pieces::*;
 pub use simplex::*;
+pub use generation::post::symmetry::{apply_symmetry, Symmetry};
@@ -22,6 +22,19 @@ const MAX_LOOPS: i32 = 8;
 /// The map will consist of a central spawn room and many rooms of
 /// various sizes connected by wide corridors.
 pub fn roomy(size: usize, seed: u64) -> Vec<Vec<bool>> {
+    roomy_symmetric(size, seed, Symmetry::None)
+}
+
+/// `roomy` for arena maps, repeated by `symmetry` so every side gets the same layout
+pub fn roomy_symmetric(size: usize, seed: u64, symmetry: Symmetry) -> Vec<Vec<bool>> {
+    roomy_scaled(size, seed, symmetry, 1.0)
+}
+
+/// `roomy_symmetric` with every room but the spawn room scaled by `room_scale`
+///
+/// The same seed gives the same layout at any scale, only the rooms grow or shrink.
+pub fn roomy_scaled(size: usize, seed: u64, symmetry: Symmetry, room_scale: f32) -> Vec<Vec<bool>> {
+    let room_size = |size: usize| ((size as f32 * room_scale).round() as usize).max(2);
     let mut rng = StdRng::seed_from_u64(seed);
     let width = size / 2;
     let height = width;
@@ -40,7 +53,7 @@ pub fn roomy(size: usize, seed: u64) -> Vec<Vec<bool>> {
         .min(ROOM_COUNT_MAX);
     let room_positions = random(&map, room_count as usize);
     for room_pos in room_positions.iter() {
-        let room_size = rng.random_range(MIN_ROOM_SIZE..MAX_ROOM_SIZE);
+        let room_size = room_size(rng.random_range(MIN_ROOM_SIZE..MAX_ROOM_SIZE));
         square_fill(&mut map, room_size / 2, *room_pos);
     }
 
@@ -190,7 +203,7 @@ pub fn roomy(size: usize, seed: u64) -> Vec<Vec<bool>> {
 
     // Create rooms in outer region
     for &room_pos in &outer_rooms {
-        let room_size = rng.random_range(MIN_ROOM_SIZE..MAX_ROOM_SIZE);
+        let room_size = room_size(rng.random_range(MIN_ROOM_SIZE..MAX_ROOM_SIZE));
         square_fill(&mut map, room_size / 2, room_pos);
     }
 
@@ -234,7 +247,9 @@ pub fn roomy(size: usize, seed: u64) -> Vec<Vec<bool>> {
     simplex::generate_simplex_noise(&mut map, &mut rng, 0.045, 0.8);
     simplex::generate_simplex_noise(&mut map, &mut rng, 0.1, 0.7);
 
-    // Smoothing
+    apply_symmetry(&mut map, symmetry);
+
+    // Smoothing, which also evens out the seams between mirrored parts
     ca::cellular_automata(&mut map, 5, |_, _| { });
 
     return map;
@@ -0,0 +1,180 @@
+use rand::Rng;
+
+/// Set pieces are kept at least this many macro cells from the map center (spawn room)
+const MIN_DISTANCE_FROM_SPAWN: usize = 40;
+/// Candidate positions tried per set piece before giving up
+const PLACEMENT_ATTEMPTS: usize = 200;
+
+/// Hand-authored room stamped into a generated map
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SetPieceKind {
+    TreasureVault,
+    TrapGauntlet,
+    Arena,
+}
+
+/// A set piece stamped into the macro map
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SetPiecePlacement {
+    pub kind: SetPieceKind,
+    /// Top-left macro cell of the prefab
+    pub origin: (usize, usize),
+    /// Prefab size in macro cells
+    pub size: (usize, usize),
+}
+
+impl SetPieceKind {
+    /// Prefab layout: `.` floor, `#` wall
+    ///
+    /// Walls are interior features only and never split the room, so a prefab
+    /// stamped over connected floor stays connected.
+    pub fn layout(&self) -> &'static [&'static str] {
+        match self {
+            SetPieceKind::TreasureVault => &[
+                "...........",
+                "...........",
+                "..##...##..",
+                "..#.....#..",
+                "...........",
+                "..#.....#..",
+                "..##...##..",
+                "...........",
+                "...........",
+            ],
+            SetPieceKind::TrapGauntlet => &[
+                ".........................",
+                "..#...#...#...#...#...#..",
+                "..#...#...#...#...#...#..",
+                ".........................",
+                "....#...#...#...#...#....",
+                "....#...#...#...#...#....",
+                ".........................",
+            ],
+            SetPieceKind::Arena => &[
+                "...............",
+                "...............",
+                "...##.....##...",
+                "...##.....##...",
+                "...............",
+                "...............",
+                "...............",
+                "...............",
+                "...............",
+                "...............",
+                "...............",
+                "...##.....##...",
+                "...##.....##...",
+                "...............",
+                "...............",
+            ],
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        let layout = self.layout();
+        (layout[0].len(), layout.len())
+    }
+}
+
+/// Set pieces for a depth: guaranteed minimums plus a random extra
+pub fn set_pieces_for_depth(depth: u32, rng: &mut impl Rng) -> Vec<SetPieceKind> {
+    let mut kinds = vec![SetPieceKind::TreasureVault];
+    if depth >= 2 {
+        kinds.push(SetPieceKind::Arena);
+    }
+    if depth >= 3 {
+        kinds.push(SetPieceKind::TrapGauntlet);
+    }
+
+    let extras = [SetPieceKind::TreasureVault, SetPieceKind::Arena, SetPieceKind::TrapGauntlet];
+    let extra_count = rng.random_range(0..=(depth as usize / 3).min(2));
+    for _ in 0..extra_count {
+        kinds.push(extras[rng.random_range(0..extras.len())]);
+    }
+
+    kinds
+}
+
+/// Stamp set pieces over existing floor, away from spawn and from each other
+///
+/// Pieces that can't find room are skipped, so the result may be shorter than `kinds`.
+pub fn place_set_pieces(map: &mut [Vec<bool>], kinds: &[SetPieceKind], rng: &mut impl Rng) -> Vec<SetPiecePlacement> {
+    let height = map.len();
+    let width = map[0].len();
+    let (cx, cy) = (width / 2, height / 2);
+    let mut placements: Vec<SetPiecePlacement> = Vec::new();
+
+    for &kind in kinds {
+        let (w, h) = kind.size();
+        if w >= width || h >= height {
+            continue;
+        }
+
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let x = rng.random_range(0..width - w);
+            let y = rng.random_range(0..height - h);
+            let center = (x + w / 2, y + h / 2);
+
+            // Must sit on existing floor so it is reachable
+            if !map[center.1][center.0] {
+                continue;
+            }
+            if center.0.abs_diff(cx).max(center.1.abs_diff(cy)) < MIN_DISTANCE_FROM_SPAWN {
+                continue;
+            }
+
+            let overlaps = placements.iter().any(|other| {
+                x < other.origin.0 + other.size.0 + 1
+                    && other.origin.0 < x + w + 1
+                    && y < other.origin.1 + other.size.1 + 1
+                    && other.origin.1 < y + h + 1
+            });
+            if overlaps {
+                continue;
+            }
+
+            for (row, line) in kind.layout().iter().enumerate() {
+                for (col, cell) in line.chars().enumerate() {
+                    map[y + row][x + col] = cell == '.';
+                }
+            }
+
+            placements.push(SetPiecePlacement { kind, origin: (x, y), size: (w, h) });
+            break;
+        }
+    }
+
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_minimum_set_pieces_per_depth() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(set_pieces_for_depth(1, &mut rng).contains(&SetPieceKind::TreasureVault));
+
+        let deep = set_pieces_for_depth(5, &mut rng);
+        for kind in [SetPieceKind::TreasureVault, SetPieceKind::Arena, SetPieceKind::TrapGauntlet] {
+            assert!(deep.contains(&kind));
+        }
+    }
+
+    #[test]
+    fn test_set_pieces_stamped_over_floor() {
+        let mut map = vec![vec![true; 200]; 200];
+        let mut rng = StdRng::seed_from_u64(3);
+        let kinds = [SetPieceKind::Arena, SetPieceKind::TrapGauntlet];
+        let placements = place_set_pieces(&mut map, &kinds, &mut rng);
+
+        assert_eq!(placements.len(), 2);
+        for placement in &placements {
+            let (x, y) = placement.origin;
+            let layout = placement.kind.layout();
+            assert_eq!(map[y + 2][x + 3], layout[2].as_bytes()[3] == b'.');
+        }
+    }
+}
@@ -3,6 +3,7 @@
 //! This module is responsible for world initialization and state-based scene management.
 
 pub mod constants;
+pub mod daily;
 pub mod scenes;
 pub mod interaction;
 pub mod states;
@@ -10,6 +11,10 @@ pub mod tiles;
 pub mod chunks;
 pub mod mapgen;
 pub mod map_id;
+pub mod modifiers;
+pub mod outline;
+pub mod run_structure;
+pub mod scene_loading;
 
 pub use constants::*;
 pub use interaction::{
@@ -18,6 +23,9 @@ pub use interaction::{
 pub use states::WorldState;
 pub use tiles::{WallTile};
 pub use map_id::MapId;
+pub use modifiers::{ModifierId, ModifierRegistry, RunModifiers};
+pub use run_structure::RunStructure;
+pub use scene_loading::{SceneLoadProgress, SceneLoadState};
 
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
@@ -26,6 +34,7 @@ use crate::{
     components::*,
     resources::*,
     player::Player,
+    events::GameEventAppExt,
 };
 
 /// Disables gravity for the 2D physics world
@@ -45,40 +54,26 @@ pub fn cleanup_game_entities(
     }
 }
 
-/// Cleans up all dungeon entities (walls, floors) for complete regeneration
-pub fn cleanup_dungeon_entities(
-    commands: &mut Commands,
-    dungeon_query: &Query<Entity, With<DungeonWall>>,
-    floor_query: &Query<Entity, (With<Mesh2d>, Without<Player>, Without<MainCamera>, Without<DungeonWall>, Without<Enemy>)>,
-) {
-    // Remove all dungeon walls
-    for entity in dungeon_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Remove all floor tiles (this is a bit of a hack - we're removing all mesh entities that aren't player/camera/walls/enemies)
-    // In a more complex game, we'd want to tag floor tiles explicitly
-    for entity in floor_query.iter() {
-        commands.entity(entity).despawn();
-    }
-}
-
 /// Plugin that organizes all world-related systems using state-based scene management
 pub struct WorldPlugin;
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         app
-            // Initialize world state
+            // Initialize world state; scene entities are tagged `StateScoped` and despawned when their scene exits
             .init_state::<WorldState>()
+            .enable_state_scoped_entities::<WorldState>()
+            .insert_resource(ModifierRegistry::with_basic_modifiers())
 
             // Events
-            .add_event::<InteractionEvent>()
+            .add_game_event::<InteractionEvent>()
+            .add_plugins(bevy::sprite::Material2dPlugin::<outline::OutlineMaterial>::default())
 
             // Tile and chunk plugins
             .add_plugins((
                 tiles::TilePlugin,
                 chunks::ChunkPlugin,
+                scene_loading::SceneLoadingPlugin,
             ))
 
             // Add scene plugins (each handles their own OnEnter/OnExit transitions)
@@ -86,8 +81,12 @@ impl Plugin for WorldPlugin {
                 scenes::cathedral::CathedralPlugin,
                 scenes::sanctuary::SanctuaryPlugin,
                 scenes::dungeon::DungeonPlugin,
+                scenes::arena::ArenaPlugin,
+                daily::DailyChallengePlugin,
             ))
 
+            .add_systems(Update, interaction::attach_interactable_tooltips)
+
             // Global interaction systems (run regardless of scene)
             .add_systems(FixedUpdate, (
                 // First update cooldowns and highlights (based on player/cursor position)
@@ -97,9 +96,8 @@ impl Plugin for WorldPlugin {
                 interaction::update_hovered_interactable,
                 // Then handle interactions (depends on hovered state)
                 interaction::handle_basic_interactions.run_if(resource_equals(GameState::Playing)),
-                // Finally manage visual effects
-                interaction::manage_halo_effects,
-                interaction::cleanup_orphaned_halos,
+                // Finally outline whatever is hovered
+                outline::update_interaction_outlines,
             ).chain());
     }
 }
@@ -0,0 +1,269 @@
+//! Run modifiers
+//!
+//! Modifiers are data: each one is a typed `RunModifier` registered under a
+//! `ModifierId`. Portals roll modifiers from the registry, and the dungeon folds
+//! the chosen ones into `RunModifiers` when it is set up.
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::mem::discriminant;
+
+use crate::components::EnemyArchetype;
+
+/// Unique identifier for modifier definitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ModifierId(pub u32);
+
+/// A single typed change to a dungeon run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RunModifier {
+    /// Scales how many enemies spawn
+    EnemyDensity { multiplier: f32 },
+    /// Scales enemy health and movement speed
+    EnemyStats { health: f32, speed: f32 },
+    /// Scales how often an archetype is picked from the spawn table
+    SpawnWeight { archetype: EnemyArchetype, multiplier: f32 },
+    /// Scales how much loot drops
+    LootQuantity { multiplier: f32 },
+    /// Scatters damaging pools across the level
+    Hazard { count: u32, radius: f32, damage_per_second: f32 },
+    /// Scales the rooms the dungeon is generated with
+    RoomSize { multiplier: f32 },
+}
+
+/// Format a multiplier as a signed percentage, e.g. 1.5 -> "+50%"
+fn percent(multiplier: f32) -> String {
+    format!("{:+.0}%", (multiplier - 1.0) * 100.0)
+}
+
+impl RunModifier {
+    /// Player-facing description generated from the modifier's parameters
+    pub fn description(&self) -> String {
+        match self {
+            RunModifier::EnemyDensity { multiplier } => format!("{} enemies", percent(*multiplier)),
+            RunModifier::EnemyStats { health, speed } => {
+                let mut parts = Vec::new();
+                if *health != 1.0 {
+                    parts.push(format!("{} enemy health", percent(*health)));
+                }
+                if *speed != 1.0 {
+                    parts.push(format!("{} enemy speed", percent(*speed)));
+                }
+                parts.join(", ")
+            }
+            RunModifier::SpawnWeight { archetype, multiplier } => {
+                format!("{} {:?} spawns", percent(*multiplier), archetype)
+            }
+            RunModifier::LootQuantity { multiplier } => format!("{} loot", percent(*multiplier)),
+            RunModifier::Hazard { count, damage_per_second, .. } => {
+                format!("{} hazard pools ({:.0} damage/s)", count, damage_per_second)
+            }
+            RunModifier::RoomSize { multiplier } => format!("{} room size", percent(*multiplier)),
+        }
+    }
+}
+
+/// Named, registered modifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifierDefinition {
+    pub name: String,
+    pub modifier: RunModifier,
+}
+
+/// Registry of all modifier definitions
+#[derive(Resource, Default)]
+pub struct ModifierRegistry {
+    pub modifiers: HashMap<ModifierId, ModifierDefinition>,
+}
+
+impl ModifierRegistry {
+    /// Starter set of modifiers
+    pub fn with_basic_modifiers() -> Self {
+        let mut registry = Self::default();
+
+        let definitions = [
+            ("Sparse Enemies", RunModifier::EnemyDensity { multiplier: 0.6 }),
+            ("Swarming Enemies", RunModifier::EnemyDensity { multiplier: 1.5 }),
+            ("Sluggish Enemies", RunModifier::EnemyStats { health: 1.0, speed: 0.75 }),
+            ("Fast Enemies", RunModifier::EnemyStats { health: 1.0, speed: 1.3 }),
+            ("Hardened Enemies", RunModifier::EnemyStats { health: 1.5, speed: 0.9 }),
+            ("Sniper Nest", RunModifier::SpawnWeight { archetype: EnemyArchetype::Sniper, multiplier: 3.0 }),
+            ("Brute Pack", RunModifier::SpawnWeight { archetype: EnemyArchetype::BigMelee, multiplier: 3.0 }),
+            ("Scarce Loot", RunModifier::LootQuantity { multiplier: 0.6 }),
+            ("Rich Loot", RunModifier::LootQuantity { multiplier: 1.5 }),
+            ("Toxic Pools", RunModifier::Hazard { count: 12, radius: 48.0, damage_per_second: 8.0 }),
+            ("Cramped Rooms", RunModifier::RoomSize { multiplier: 0.7 }),
+            ("Vast Rooms", RunModifier::RoomSize { multiplier: 1.4 }),
+        ];
+
+        for (i, (name, modifier)) in definitions.into_iter().enumerate() {
+            registry.register(ModifierId(i as u32 + 1), ModifierDefinition {
+                name: name.to_string(),
+                modifier,
+            });
+        }
+
+        registry
+    }
+
+    pub fn register(&mut self, id: ModifierId, definition: ModifierDefinition) {
+        self.modifiers.insert(id, definition);
+    }
+
+    pub fn get(&self, id: ModifierId) -> Option<&ModifierDefinition> {
+        self.modifiers.get(&id)
+    }
+
+    /// "Name: description" line for UI, or the raw id if it isn't registered
+    pub fn describe(&self, id: ModifierId) -> String {
+        match self.get(id) {
+            Some(definition) => format!("{}: {}", definition.name, definition.modifier.description()),
+            None => format!("Unknown modifier {}", id.0),
+        }
+    }
+
+    /// Pick up to `count` modifiers, never two of the same kind
+    pub fn roll(&self, count: usize, rng: &mut impl Rng) -> Vec<ModifierId> {
+        let mut candidates: Vec<ModifierId> = self.modifiers.keys().copied().collect();
+        candidates.sort();
+
+        let mut picked: Vec<ModifierId> = Vec::new();
+        while picked.len() < count && !candidates.is_empty() {
+            let id = candidates.swap_remove(rng.random_range(0..candidates.len()));
+            let kind = discriminant(&self.modifiers[&id].modifier);
+            candidates.retain(|other| discriminant(&self.modifiers[other].modifier) != kind);
+            picked.push(id);
+        }
+
+        picked
+    }
+}
+
+/// Hazard placement derived from a `RunModifier::Hazard`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HazardSpec {
+    pub count: u32,
+    pub radius: f32,
+    pub damage_per_second: f32,
+}
+
+/// Combined effect of the modifiers on the current run
+/// Inserted when entering the dungeon and read by spawning, loot and hazard code
+#[derive(Resource, Debug, Clone)]
+pub struct RunModifiers {
+    pub enemy_density: f32,
+    pub enemy_health: f32,
+    pub enemy_speed: f32,
+    pub spawn_weights: HashMap<EnemyArchetype, f32>,
+    pub loot_quantity: f32,
+    pub hazards: Vec<HazardSpec>,
+    /// Scale of the generated rooms
+    pub room_size: f32,
+}
+
+impl Default for RunModifiers {
+    fn default() -> Self {
+        Self {
+            enemy_density: 1.0,
+            enemy_health: 1.0,
+            enemy_speed: 1.0,
+            spawn_weights: HashMap::new(),
+            loot_quantity: 1.0,
+            hazards: Vec::new(),
+            room_size: 1.0,
+        }
+    }
+}
+
+impl RunModifiers {
+    /// Fold the given modifiers together; multipliers of the same kind stack multiplicatively
+    pub fn from_modifiers(registry: &ModifierRegistry, ids: &[ModifierId]) -> Self {
+        let mut result = Self::default();
+
+        for id in ids {
+            let Some(definition) = registry.get(*id) else {
+                warn!("Modifier {:?} is not registered", id);
+                continue;
+            };
+
+            match &definition.modifier {
+                RunModifier::EnemyDensity { multiplier } => result.enemy_density *= multiplier,
+                RunModifier::EnemyStats { health, speed } => {
+                    result.enemy_health *= health;
+                    result.enemy_speed *= speed;
+                }
+                RunModifier::SpawnWeight { archetype, multiplier } => {
+                    *result.spawn_weights.entry(*archetype).or_insert(1.0) *= multiplier;
+                }
+                RunModifier::LootQuantity { multiplier } => result.loot_quantity *= multiplier,
+                RunModifier::Hazard { count, radius, damage_per_second } => {
+                    result.hazards.push(HazardSpec {
+                        count: *count,
+                        radius: *radius,
+                        damage_per_second: *damage_per_second,
+                    });
+                }
+                RunModifier::RoomSize { multiplier } => result.room_size *= multiplier,
+            }
+        }
+
+        result
+    }
+
+    /// Enemy spawn table: base weights (e.g. from the biome) scaled by the modifiers
+    pub fn spawn_table(&self, base: &[(EnemyArchetype, f32)]) -> Vec<(EnemyArchetype, f32)> {
+        base.iter()
+            .map(|(archetype, weight)| (*archetype, weight * self.spawn_weights.get(archetype).copied().unwrap_or(1.0)))
+            .collect()
+    }
+
+    /// Number of enemies to spawn given an unmodified count
+    pub fn enemy_count(&self, base: u32) -> u32 {
+        (base as f32 * self.enemy_density).round() as u32
+    }
+
+    /// Number of loot drops given an unmodified count
+    pub fn loot_count(&self, base: u32) -> u32 {
+        (base as f32 * self.loot_quantity).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descriptions_come_from_parameters() {
+        assert_eq!(RunModifier::EnemyDensity { multiplier: 1.5 }.description(), "+50% enemies");
+        assert_eq!(
+            RunModifier::EnemyStats { health: 1.25, speed: 0.75 }.description(),
+            "+25% enemy health, -25% enemy speed",
+        );
+        assert_eq!(RunModifier::RoomSize { multiplier: 0.7 }.description(), "-30% room size");
+    }
+
+    #[test]
+    fn test_modifiers_fold_and_roll_distinct_kinds() {
+        let registry = ModifierRegistry::with_basic_modifiers();
+        let modifiers = RunModifiers::from_modifiers(&registry, &[ModifierId(2), ModifierId(9), ModifierId(6)]);
+        assert_eq!(modifiers.enemy_count(10), 15);
+        assert_eq!(modifiers.loot_count(10), 15);
+        assert!(modifiers.spawn_table(&[(EnemyArchetype::Sniper, 0.5)]).contains(&(EnemyArchetype::Sniper, 1.5)));
+
+        let rolled = registry.roll(4, &mut rand::rng());
+        assert_eq!(rolled.len(), 4);
+        for (i, a) in rolled.iter().enumerate() {
+            for b in &rolled[i + 1..] {
+                assert_ne!(discriminant(&registry.modifiers[a].modifier), discriminant(&registry.modifiers[b].modifier));
+            }
+        }
+    }
+
+    #[test]
+    fn test_modifier_round_trips_through_serde() {
+        let modifier = RunModifier::Hazard { count: 3, radius: 20.0, damage_per_second: 5.0 };
+        let json = serde_json::to_string(&modifier).unwrap();
+        assert_eq!(serde_json::from_str::<RunModifier>(&json).unwrap(), modifier);
+    }
+}
@@ -0,0 +1,128 @@
+//! Outlines on hovered interactables
+//!
+//! While an interactable is hovered its `ColorMaterial` is swapped for an
+//! `OutlineMaterial` of the same color, which draws a glowing rim just inside
+//! the mesh's edge, and swapped back when the hover ends. Nothing is spawned
+//! alongside the interactable, so there is nothing to clean up when it goes.
+use bevy::prelude::*;
+use bevy::render::mesh::MeshAabb;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{AlphaMode2d, Material2d};
+
+use super::interaction::{Interactable, InteractableHighlight};
+
+const OUTLINE_SHADER_PATH: &str = "shaders/interaction_outline.wgsl";
+
+/// Mesh color with a rim drawn inside its edge
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct OutlineMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+    #[uniform(0)]
+    pub outline_color: LinearRgba,
+    /// Size of the mesh in world units, so the rim is as wide on every mesh
+    #[uniform(0)]
+    pub size: Vec2,
+    /// Width of the rim in world units
+    #[uniform(0)]
+    pub width: f32,
+}
+
+impl Material2d for OutlineMaterial {
+    fn fragment_shader() -> ShaderRef {
+        OUTLINE_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+/// Swap hovered interactables onto an outline material and back again
+pub fn update_interaction_outlines(
+    mut commands: Commands,
+    meshes: Res<Assets<Mesh>>,
+    color_materials: Res<Assets<ColorMaterial>>,
+    mut outline_materials: ResMut<Assets<OutlineMaterial>>,
+    mut interactables: Query<(
+        Entity,
+        &mut InteractableHighlight,
+        &Mesh2d,
+        Option<&MeshMaterial2d<ColorMaterial>>,
+        Option<&MeshMaterial2d<OutlineMaterial>>,
+    ), With<Interactable>>,
+) {
+    for (entity, mut highlight, mesh, color_material, outline_material) in interactables.iter_mut() {
+        match (highlight.is_hovered, color_material, outline_material) {
+            // Hover started
+            (true, Some(color_material), None) => {
+                let color = color_materials.get(&color_material.0).map_or(Color::WHITE, |material| material.color);
+                let size = meshes
+                    .get(&mesh.0)
+                    .and_then(Mesh::compute_aabb)
+                    .map_or(Vec2::ONE, |aabb| aabb.half_extents.truncate() * 2.0);
+                let outline = outline_materials.add(OutlineMaterial {
+                    color: color.into(),
+                    outline_color: highlight.current_color().into(),
+                    size,
+                    width: highlight.outline_width,
+                });
+                highlight.base_material = Some(color_material.0.clone());
+                commands
+                    .entity(entity)
+                    .remove::<MeshMaterial2d<ColorMaterial>>()
+                    .insert(MeshMaterial2d(outline));
+            }
+            // Still hovered; follow the player moving in and out of range
+            (true, _, Some(outline_material)) => {
+                if let Some(material) = outline_materials.get_mut(&outline_material.0) {
+                    let target = highlight.current_color().into();
+                    if material.outline_color != target {
+                        material.outline_color = target;
+                    }
+                }
+            }
+            // Hover ended
+            (false, _, Some(_)) => {
+                let mut entity_commands = commands.entity(entity);
+                entity_commands.remove::<MeshMaterial2d<OutlineMaterial>>();
+                if let Some(base_material) = highlight.base_material.take() {
+                    entity_commands.insert(MeshMaterial2d(base_material));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_outline_comes_off_with_the_hover() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<ColorMaterial>>();
+        world.init_resource::<Assets<OutlineMaterial>>();
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(Rectangle::new(60.0, 80.0));
+        let base = world.resource_mut::<Assets<ColorMaterial>>().add(Color::srgb(0.4, 0.4, 0.8));
+        let portal = world.spawn((
+            Mesh2d(mesh),
+            MeshMaterial2d(base.clone()),
+            Interactable::new("portal", "Portal", |_| {}),
+            InteractableHighlight { is_hovered: true, ..default() },
+        )).id();
+
+        world.run_system_once(update_interaction_outlines).unwrap();
+        let outline = world.get::<MeshMaterial2d<OutlineMaterial>>(portal).unwrap().0.clone();
+        assert!(world.get::<MeshMaterial2d<ColorMaterial>>(portal).is_none());
+        assert_eq!(world.resource::<Assets<OutlineMaterial>>().get(&outline).unwrap().size, Vec2::new(60.0, 80.0));
+
+        world.get_mut::<InteractableHighlight>(portal).unwrap().is_hovered = false;
+        world.run_system_once(update_interaction_outlines).unwrap();
+        assert!(world.get::<MeshMaterial2d<OutlineMaterial>>(portal).is_none());
+        assert_eq!(world.get::<MeshMaterial2d<ColorMaterial>>(portal).unwrap().0, base);
+    }
+}
@@ -0,0 +1,165 @@
+//! Run structure
+//!
+//! A run is laid out up front when the player steps through a Cathedral
+//! portal: every depth ahead gets a few branches, each with its own modifiers
+//! and features. Between floors the player picks which branch to take next,
+//! and the world map screen draws the whole structure.
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::modifiers::{ModifierId, ModifierRegistry};
+
+/// Number of depths generated ahead of the starting depth
+pub const RUN_LENGTH: u32 = 10;
+/// Every this many depths is a boss floor
+const BOSS_INTERVAL: u32 = 5;
+/// Chance that a branch holds a shrine
+const SHRINE_CHANCE: f64 = 0.3;
+
+/// Notable content on a floor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloorFeature {
+    Shrine,
+    Boss,
+}
+
+/// One branch the player can take at a depth
+#[derive(Debug, Clone)]
+pub struct FloorNode {
+    pub depth: u32,
+    pub modifiers: Vec<ModifierId>,
+    pub features: Vec<FloorFeature>,
+    pub visited: bool,
+}
+
+impl FloorNode {
+    pub fn has_feature(&self, feature: FloorFeature) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+/// Floors of the current run, generated at run start
+#[derive(Resource, Debug, Clone)]
+pub struct RunStructure {
+    pub start_depth: u32,
+    /// Branches per depth, starting at `start_depth`
+    pub floors: Vec<Vec<FloorNode>>,
+    /// Branch taken at each visited depth
+    pub path: Vec<usize>,
+    /// Branch picked for the next depth
+    pub selected_branch: usize,
+}
+
+impl RunStructure {
+    /// Lay out a run starting at `start_depth`; the first floor uses the portal's modifiers
+    pub fn generate(start_depth: u32, first_modifiers: Vec<ModifierId>, registry: &ModifierRegistry, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut floors = vec![vec![FloorNode {
+            depth: start_depth,
+            modifiers: first_modifiers,
+            features: Vec::new(),
+            visited: true,
+        }]];
+
+        for depth in start_depth + 1..start_depth + RUN_LENGTH {
+            let branch_count = rng.random_range(2..=3);
+            let branches = (0..branch_count)
+                .map(|_| {
+                    let mut features = Vec::new();
+                    if depth % BOSS_INTERVAL == 0 {
+                        features.push(FloorFeature::Boss);
+                    }
+                    if rng.random_bool(SHRINE_CHANCE) {
+                        features.push(FloorFeature::Shrine);
+                    }
+
+                    let modifier_count = rng.random_range(1..=3);
+                    FloorNode {
+                        depth,
+                        modifiers: registry.roll(modifier_count, &mut rng),
+                        features,
+                        visited: false,
+                    }
+                })
+                .collect();
+            floors.push(branches);
+        }
+
+        Self {
+            start_depth,
+            floors,
+            path: vec![0],
+            selected_branch: 0,
+        }
+    }
+
+    /// Deepest depth reached so far
+    pub fn current_depth(&self) -> u32 {
+        self.start_depth + self.path.len() as u32 - 1
+    }
+
+    /// Branches at a depth, empty outside the run
+    pub fn branches(&self, depth: u32) -> &[FloorNode] {
+        depth.checked_sub(self.start_depth)
+            .and_then(|index| self.floors.get(index as usize))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Branches the player can pick from for the next floor
+    pub fn next_choices(&self) -> &[FloorNode] {
+        self.branches(self.current_depth() + 1)
+    }
+
+    pub fn select_branch(&mut self, branch: usize) {
+        if branch < self.next_choices().len() {
+            self.selected_branch = branch;
+        }
+    }
+
+    /// Go down into the selected branch and return it, or `None` past the end of the run
+    pub fn descend(&mut self) -> Option<&FloorNode> {
+        let index = self.path.len();
+        let branch = self.selected_branch;
+        let node = self.floors.get_mut(index)?.get_mut(branch)?;
+        node.visited = true;
+
+        self.path.push(branch);
+        self.selected_branch = 0;
+        Some(&self.floors[index][branch])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_is_seeded() {
+        let registry = ModifierRegistry::with_basic_modifiers();
+        let a = RunStructure::generate(1, vec![], &registry, 9);
+        let b = RunStructure::generate(1, vec![], &registry, 9);
+
+        assert_eq!(a.floors.len(), RUN_LENGTH as usize);
+        for (x, y) in a.floors.iter().flatten().zip(b.floors.iter().flatten()) {
+            assert_eq!(x.modifiers, y.modifiers);
+            assert_eq!(x.features, y.features);
+        }
+        assert!(a.branches(5).iter().all(|node| node.has_feature(FloorFeature::Boss)));
+    }
+
+    #[test]
+    fn test_descend_follows_selected_branch() {
+        let registry = ModifierRegistry::with_basic_modifiers();
+        let mut run = RunStructure::generate(3, vec![ModifierId(1)], &registry, 4);
+        assert_eq!(run.current_depth(), 3);
+
+        run.select_branch(1);
+        let expected = run.next_choices()[1].modifiers.clone();
+        let node = run.descend().unwrap();
+        assert_eq!(node.depth, 4);
+        assert_eq!(node.modifiers, expected);
+        assert_eq!(run.path, vec![0, 1]);
+        assert!(run.branches(4)[1].visited && !run.branches(4)[0].visited);
+    }
+}
@@ -0,0 +1,130 @@
+//! Loading screen for scene transitions
+//!
+//! A scene that takes more than a frame to build sets `SceneLoadState::Loading`
+//! when it's entered and spreads the work over the following frames, reporting
+//! through `SceneLoadProgress`. A loading screen covers the half-built scene
+//! and player input waits until the scene sets `SceneLoadState::Ready`.
+use bevy::prelude::*;
+
+use crate::ui::theme::UiTheme;
+use crate::world::WorldState;
+
+/// Whether the current scene is still being built
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum SceneLoadState {
+    #[default]
+    Ready,
+    Loading,
+}
+
+/// What the loading scene is doing, shown on the loading screen
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SceneLoadProgress {
+    pub stage: &'static str,
+    /// From 0 to 1
+    pub fraction: f32,
+}
+
+impl SceneLoadProgress {
+    pub fn set(&mut self, stage: &'static str, fraction: f32) {
+        self.stage = stage;
+        self.fraction = fraction.clamp(0.0, 1.0);
+    }
+}
+
+/// Plugin for the scene loading state and its loading screen
+pub struct SceneLoadingPlugin;
+
+impl Plugin for SceneLoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_state::<SceneLoadState>()
+            .init_resource::<SceneLoadProgress>()
+            .add_systems(OnEnter(SceneLoadState::Loading), setup_scene_loading_screen)
+            .add_systems(Update, update_scene_loading_screen.run_if(in_state(SceneLoadState::Loading)))
+            .add_systems(OnExit(SceneLoadState::Loading), cleanup_scene_loading_screen)
+            // Leaving a scene before it finished loading mustn't leave the next one behind a loading screen
+            .add_systems(OnExit(WorldState::Dungeon), abandon_scene_loading)
+            .add_systems(OnExit(WorldState::Sanctuary), abandon_scene_loading);
+    }
+}
+
+#[derive(Component)]
+struct SceneLoadingScreen;
+
+#[derive(Component)]
+struct SceneLoadingBarFill;
+
+#[derive(Component)]
+struct SceneLoadingStageText;
+
+fn abandon_scene_loading(mut commands: Commands, state: Res<State<SceneLoadState>>) {
+    if *state.get() == SceneLoadState::Loading {
+        commands.set_state(SceneLoadState::Ready);
+    }
+}
+
+fn setup_scene_loading_screen(mut commands: Commands, theme: Res<UiTheme>, progress: Res<SceneLoadProgress>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.gap * 2.0),
+                ..default()
+            },
+            BackgroundColor(theme.screen_background),
+            GlobalZIndex(200), // Above everything, the scene behind is half built
+            SceneLoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(400.0),
+                        height: Val::Px(16.0),
+                        border: UiRect::all(Val::Px(theme.border_width)),
+                        ..default()
+                    },
+                    BackgroundColor(theme.bar_background),
+                    BorderColor(theme.panel_border),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(progress.fraction * 100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(theme.progress_fill),
+                    SceneLoadingBarFill,
+                ));
+
+            parent.spawn((theme.text(progress.stage, theme.body_size), SceneLoadingStageText));
+        });
+}
+
+fn update_scene_loading_screen(
+    progress: Res<SceneLoadProgress>,
+    mut fills: Query<&mut Node, With<SceneLoadingBarFill>>,
+    mut texts: Query<&mut Text, With<SceneLoadingStageText>>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+
+    if let Ok(mut fill) = fills.single_mut() {
+        fill.width = Val::Percent(progress.fraction * 100.0);
+    }
+    if let Ok(mut text) = texts.single_mut() {
+        **text = progress.stage.to_string();
+    }
+}
+
+fn cleanup_scene_loading_screen(mut commands: Commands, screens: Query<Entity, With<SceneLoadingScreen>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+/// Enemy spawned by an arena wave
+#[derive(Component, Debug)]
+pub struct ArenaEnemy;
+
+/// Portal in the Cathedral that starts an arena run
+#[derive(Component, Debug)]
+pub struct ArenaPortal;
+
+/// Portal in the arena leading back to the Cathedral
+#[derive(Component, Debug)]
+pub struct ArenaExitPortal;
+
+/// Wave, timer and score readout
+#[derive(Component, Debug)]
+pub struct ArenaHud;
@@ -0,0 +1,29 @@
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+use bevy::prelude::*;
+use crate::resources::GameState;
+use crate::world::states::WorldState;
+
+/// Plugin for the endless arena mode, entered from a Cathedral portal
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // The portal is added after the Cathedral builds its scene
+            .add_systems(OnEnter(WorldState::Cathedral), systems::spawn_arena_portal)
+            .add_systems(FixedUpdate, systems::handle_arena_portal_interactions.run_if(in_state(WorldState::Cathedral)))
+
+            .add_systems(OnEnter(WorldState::Arena), systems::setup_arena_scene)
+            .add_systems(OnExit(WorldState::Arena), systems::teardown_arena_scene)
+            .add_systems(FixedUpdate, (
+                systems::tally_arena_kills,
+                systems::advance_arena_waves.run_if(resource_equals(GameState::Playing)),
+                systems::handle_arena_exit_interactions,
+                systems::record_arena_death,
+            ).chain().run_if(in_state(WorldState::Arena)))
+            .add_systems(Update, systems::update_arena_hud.run_if(resource_exists::<resources::ArenaState>));
+    }
+}
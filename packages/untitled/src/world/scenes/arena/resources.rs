@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::components::EnemyArchetype;
+use crate::persistence::ArenaScore;
+use crate::world::RunModifiers;
+
+/// Side length of the square arena in world units
+pub const ARENA_SIZE: f32 = 1600.0;
+/// Seconds before the next wave arrives whether or not the current one is dead
+pub const WAVE_INTERVAL: f32 = 20.0;
+/// Points per second left on the timer when a wave is cleared early
+const EARLY_CLEAR_BONUS: f32 = 5.0;
+
+/// Resource tracking the current arena run
+#[derive(Resource, Debug, Default)]
+pub struct ArenaState {
+    /// Current wave, 0 before the first one spawns
+    pub wave: u32,
+    /// Seconds until the next wave
+    pub wave_timer: f32,
+    /// Seconds survived
+    pub elapsed: f32,
+    pub score: u32,
+    pub kills: u32,
+    /// Best score on the leaderboard when the run started
+    pub best_score: u32,
+    /// Living wave enemies, so a wave counts as cleared once they're all gone
+    pub alive: HashMap<Entity, EnemyArchetype>,
+    /// Whether this run is on the leaderboard already, so dying then leaving records it once
+    pub recorded: bool,
+}
+
+impl ArenaState {
+    pub fn with_best_score(best_score: u32) -> Self {
+        Self {
+            wave_timer: 3.0, // Short breather before the first wave
+            best_score,
+            ..default()
+        }
+    }
+
+    /// Next wave is due when the timer runs out or every enemy is dead
+    pub fn wave_due(&self) -> bool {
+        self.wave_timer <= 0.0 || (self.wave > 0 && self.alive.is_empty())
+    }
+
+    /// Advance to the next wave, awarding the early clear bonus
+    pub fn start_next_wave(&mut self) -> u32 {
+        if self.wave > 0 && self.wave_timer > 0.0 {
+            self.score += (self.wave_timer * EARLY_CLEAR_BONUS).round() as u32;
+        }
+        self.wave += 1;
+        self.wave_timer = WAVE_INTERVAL;
+        self.wave
+    }
+
+    /// The run as it goes on the leaderboard
+    pub fn result(&self) -> ArenaScore {
+        ArenaScore {
+            score: self.score,
+            wave: self.wave,
+            kills: self.kills,
+            duration_secs: self.elapsed,
+        }
+    }
+
+    pub fn record_kill(&mut self, archetype: EnemyArchetype) {
+        self.kills += 1;
+        self.score += archetype.score_value();
+    }
+
+    /// Number of enemies in a wave
+    pub fn wave_enemy_count(wave: u32) -> u32 {
+        3 + wave * 2
+    }
+
+    /// Difficulty for a wave, applied through the same modifiers as dungeon runs
+    pub fn wave_modifiers(wave: u32) -> RunModifiers {
+        let steps = wave.saturating_sub(1) as f32;
+        RunModifiers {
+            enemy_health: 1.0 + steps * 0.15,
+            enemy_speed: (1.0 + steps * 0.03).min(1.5),
+            ..default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waves_escalate() {
+        assert!(ArenaState::wave_enemy_count(5) > ArenaState::wave_enemy_count(1));
+        assert_eq!(ArenaState::wave_modifiers(1).enemy_health, 1.0);
+        assert!(ArenaState::wave_modifiers(8).enemy_health > ArenaState::wave_modifiers(4).enemy_health);
+        assert_eq!(ArenaState::wave_modifiers(100).enemy_speed, 1.5);
+    }
+
+    #[test]
+    fn test_early_clear_bonus() {
+        let mut arena = ArenaState::with_best_score(0);
+        assert!(!arena.wave_due());
+
+        arena.wave_timer = 0.0;
+        assert_eq!(arena.start_next_wave(), 1);
+        assert_eq!(arena.score, 0);
+
+        arena.record_kill(EnemyArchetype::Sniper);
+        arena.wave_timer = 10.0;
+        assert!(arena.wave_due());
+        arena.start_next_wave();
+        assert_eq!(arena.score, 75 + 50);
+        assert_eq!(arena.kills, 1);
+    }
+}
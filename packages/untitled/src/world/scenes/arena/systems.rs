@@ -0,0 +1,357 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::{distr::{weighted::WeightedIndex, Distribution}, Rng};
+
+use crate::combat::EnemyKilledEvent;
+use crate::enemy::spawn_enemy;
+use crate::persistence::ChunkDatabase;
+use crate::player::Player;
+use crate::resources::GameState;
+use crate::ui::theme::UiTheme;
+use crate::world::{chunks::ChunkingState, mapgen::Biome, states::WorldState, RunModifiers};
+
+use super::components::{ArenaEnemy, ArenaExitPortal, ArenaHud, ArenaPortal};
+use super::resources::{ArenaState, ARENA_SIZE};
+
+/// Thickness of the arena's outer walls
+const WALL_THICKNESS: f32 = 32.0;
+/// Enemies never spawn closer than this to the player
+const SPAWN_CLEARANCE: f32 = 350.0;
+
+/// Spawn a wall block with collider and visual
+fn spawn_wall(commands: &mut Commands, meshes: &mut Assets<Mesh>, materials: &mut Assets<ColorMaterial>, center: Vec2, size: Vec2) {
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(size.x, size.y))),
+        MeshMaterial2d(materials.add(Color::srgb(0.3, 0.28, 0.32))),
+        Transform::from_translation(center.extend(crate::depth::DepthLayer::TerrainDetail.z())),
+        RigidBody::Fixed,
+        Collider::cuboid(size.x / 2.0, size.y / 2.0),
+        crate::physics_layers::terrain(),
+        crate::world::tiles::WallTile,
+        StateScoped(WorldState::Arena),
+    ));
+}
+
+/// Set up the arena scene when entering
+pub fn setup_arena_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    database: Option<Res<ChunkDatabase>>,
+    camera_zoom: Res<crate::player::resources::CameraZoom>,
+    theme: Res<UiTheme>,
+) {
+    info!("Setting up Arena scene");
+
+    // Fixed map, no chunk streaming
+    commands.set_state(ChunkingState::Disabled);
+
+    commands.spawn((
+        Camera2d,
+        Transform {
+            scale: Vec3::splat(camera_zoom.level),
+            ..default()
+        },
+        crate::components::MainCamera,
+        StateScoped(WorldState::Arena),
+    ));
+
+    // Floor
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(ARENA_SIZE, ARENA_SIZE))),
+        MeshMaterial2d(materials.add(Color::srgb(0.14, 0.12, 0.12))),
+        Transform::from_translation(Vec3::new(0.0, 0.0, crate::depth::DepthLayer::Terrain.z())),
+        StateScoped(WorldState::Arena),
+    ));
+
+    // Outer walls
+    let half = ARENA_SIZE / 2.0 + WALL_THICKNESS / 2.0;
+    let span = ARENA_SIZE + WALL_THICKNESS * 2.0;
+    for (center, size) in [
+        (Vec2::new(0.0, half), Vec2::new(span, WALL_THICKNESS)),
+        (Vec2::new(0.0, -half), Vec2::new(span, WALL_THICKNESS)),
+        (Vec2::new(half, 0.0), Vec2::new(WALL_THICKNESS, span)),
+        (Vec2::new(-half, 0.0), Vec2::new(WALL_THICKNESS, span)),
+    ] {
+        spawn_wall(&mut commands, &mut meshes, &mut materials, center, size);
+    }
+
+    // Cover pillars
+    for x in [-1.0, 1.0] {
+        for y in [-1.0, 1.0] {
+            spawn_wall(&mut commands, &mut meshes, &mut materials, Vec2::new(x, y) * ARENA_SIZE * 0.25, Vec2::splat(96.0));
+        }
+    }
+
+    // Exit portal back to the Cathedral
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(60.0, 80.0))),
+        MeshMaterial2d(materials.add(Color::srgb(0.4, 0.4, 0.8))),
+        Transform::from_translation(Vec3::new(0.0, -ARENA_SIZE / 2.0 + 80.0, crate::depth::DepthLayer::Actors.z())),
+        crate::depth::YSort::with_offset(-40.0),
+        ArenaExitPortal,
+        StateScoped(WorldState::Arena),
+        crate::world::Interactable::new(
+            "arena_exit_portal".to_string(),
+            "Leave Arena".to_string(),
+            |_| {
+                info!("Arena exit portal activated");
+            }
+        ),
+        crate::world::InteractableHighlight::default(),
+    ));
+
+    let player_entity = commands.spawn(
+        crate::player::components::PlayerBundle::new(&mut meshes, &mut materials, Vec3::new(0.0, 0.0, crate::depth::DepthLayer::Actors.z())),
+    ).id();
+    commands.entity(player_entity).insert(StateScoped(WorldState::Arena));
+
+    commands.spawn((
+        theme.text("", 18.0),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Percent(40.0),
+            ..default()
+        },
+        ArenaHud,
+        StateScoped(WorldState::Arena),
+    ));
+
+    let best_score = database
+        .and_then(|db| db.top_arena_scores(1).ok())
+        .and_then(|scores| scores.first().map(|s| s.score))
+        .unwrap_or(0);
+
+    commands.insert_resource(ArenaState::with_best_score(best_score));
+    commands.insert_resource(RunModifiers::default());
+
+    info!("Arena scene setup complete");
+}
+
+/// Put the run on the leaderboard, once per run
+fn record_arena_run(arena: &mut ArenaState, database: &ChunkDatabase) {
+    if arena.recorded || arena.wave == 0 {
+        return;
+    }
+    arena.recorded = true;
+
+    let result = arena.result();
+    match database.record_arena_score(&result) {
+        Ok(()) => info!("Arena run recorded: {:?}", result),
+        Err(e) => error!("Failed to record arena score: {}", e),
+    }
+}
+
+/// Record the run as soon as the player dies, whether or not they leave the arena afterwards
+pub fn record_arena_death(
+    game_state: Res<GameState>,
+    mut arena: ResMut<ArenaState>,
+    database: Option<Res<ChunkDatabase>>,
+) {
+    if *game_state != GameState::GameOver {
+        return;
+    }
+    if let Some(database) = database {
+        record_arena_run(&mut arena, &database);
+    }
+}
+
+/// Record the run and clean up when leaving the arena
+pub fn teardown_arena_scene(
+    mut commands: Commands,
+    arena: Option<ResMut<ArenaState>>,
+    database: Option<Res<ChunkDatabase>>,
+) {
+    info!("Tearing down Arena scene");
+
+    if let (Some(mut arena), Some(database)) = (arena, database) {
+        record_arena_run(&mut arena, &database);
+    }
+
+    commands.remove_resource::<ArenaState>();
+    commands.remove_resource::<RunModifiers>();
+}
+
+/// Count down to the next wave and spawn it
+pub fn advance_arena_waves(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+    mut arena: ResMut<ArenaState>,
+    mut modifiers: ResMut<RunModifiers>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let dt = time.delta_secs();
+    arena.elapsed += dt;
+    arena.wave_timer -= dt;
+
+    if !arena.wave_due() {
+        return;
+    }
+
+    let wave = arena.start_next_wave();
+    *modifiers = ArenaState::wave_modifiers(wave);
+
+    let spawn_table = modifiers.spawn_table(&Biome::Crypt.definition().spawn_weights);
+    let Ok(distribution) = WeightedIndex::new(spawn_table.iter().map(|(_, weight)| *weight)) else {
+        return;
+    };
+
+    let player_pos = player_query.single().map_or(Vec2::ZERO, |t| t.translation.truncate());
+    let mut rng = rand::rng();
+    let extent = ARENA_SIZE / 2.0 - 64.0;
+
+    for _ in 0..ArenaState::wave_enemy_count(wave) {
+        let mut position = Vec2::new(rng.random_range(-extent..extent), rng.random_range(-extent..extent));
+        if position.distance(player_pos) < SPAWN_CLEARANCE {
+            // Push spawns out of the player's face towards the far side
+            position = -player_pos.normalize_or(Vec2::X) * extent * 0.8;
+        }
+
+        let archetype = spawn_table[distribution.sample(&mut rng)].0;
+        let enemy = spawn_enemy(&mut commands, &mut meshes, &mut materials, archetype, position);
+        commands.entity(enemy).insert((ArenaEnemy, StateScoped(WorldState::Arena)));
+        arena.alive.insert(enemy, archetype);
+    }
+
+    info!("Arena wave {} spawned", wave);
+}
+
+/// Score enemies from the current run as they die
+///
+/// Only deaths score; wave enemies despawned any other way just stop counting
+/// towards the wave.
+pub fn tally_arena_kills(
+    mut arena: ResMut<ArenaState>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    mut removed: RemovedComponents<ArenaEnemy>,
+) {
+    for event in killed_events.read() {
+        if arena.alive.remove(&event.entity).is_some() {
+            arena.record_kill(event.archetype);
+        }
+    }
+    for entity in removed.read() {
+        arena.alive.remove(&entity);
+    }
+}
+
+pub fn update_arena_hud(
+    arena: Res<ArenaState>,
+    mut hud: Query<&mut Text, With<ArenaHud>>,
+) {
+    let Ok(mut text) = hud.single_mut() else {
+        return;
+    };
+
+    **text = format!(
+        "Wave {}  |  Next wave in {:.0}s  |  Score {}  |  Best {}",
+        arena.wave,
+        arena.wave_timer.max(0.0),
+        arena.score,
+        arena.best_score.max(arena.score),
+    );
+}
+
+/// Spawn the arena portal in the Cathedral, showing the leaderboard in its tooltip
+pub fn spawn_arena_portal(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    database: Option<Res<ChunkDatabase>>,
+) {
+    let center_x = (crate::world::tiles::TILEMAP_WIDTH as f32 * crate::world::tiles::TILE_SIZE) / 2.0;
+    let center_y = (crate::world::tiles::TILEMAP_HEIGHT as f32 * crate::world::tiles::TILE_SIZE) / 2.0;
+
+    let mut tooltip = crate::ui::tooltip::Tooltip::new("Arena")
+        .with_content("Endless waves, escalating every round");
+    let scores = database.and_then(|db| db.top_arena_scores(5).ok()).unwrap_or_default();
+    for (rank, score) in scores.iter().enumerate() {
+        tooltip = tooltip.with_stat(
+            format!("#{}", rank + 1),
+            format!("{} (wave {}, {} kills)", score.score, score.wave, score.kills),
+        );
+    }
+    tooltip = tooltip.with_keybind("E", "Enter");
+
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(80.0, 120.0))),
+        MeshMaterial2d(materials.add(Color::srgb(0.8, 0.3, 0.2))),
+        Transform::from_translation(Vec3::new(center_x, center_y - 250.0, crate::depth::DepthLayer::Actors.z())),
+        crate::depth::YSort::with_offset(-60.0),
+        ArenaPortal,
+        StateScoped(WorldState::Cathedral),
+        tooltip,
+        crate::world::Interactable::new(
+            "arena_portal".to_string(),
+            "Arena Portal".to_string(),
+            |_| {
+                info!("Arena portal activated");
+            }
+        ),
+        crate::world::InteractableHighlight::default(),
+    ));
+}
+
+/// Enter the arena from the Cathedral portal
+pub fn handle_arena_portal_interactions(
+    mut interaction_events: EventReader<crate::world::InteractionEvent>,
+    portals: Query<(), With<ArenaPortal>>,
+    mut next_state: ResMut<NextState<WorldState>>,
+) {
+    for event in interaction_events.read() {
+        if portals.contains(event.target_entity) {
+            info!("Cathedral: Arena portal activated - transitioning");
+            next_state.set(WorldState::Arena);
+            return;
+        }
+    }
+}
+
+/// Leave the arena through its exit portal
+pub fn handle_arena_exit_interactions(
+    mut interaction_events: EventReader<crate::world::InteractionEvent>,
+    portals: Query<(), With<ArenaExitPortal>>,
+    mut next_state: ResMut<NextState<WorldState>>,
+) {
+    for event in interaction_events.read() {
+        if portals.contains(event.target_entity) {
+            info!("Arena: exit portal activated - returning to Cathedral");
+            next_state.set(WorldState::Cathedral);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::EnemyArchetype;
+
+    #[test]
+    fn test_only_deaths_score() {
+        let mut app = App::new();
+        app.add_event::<EnemyKilledEvent>()
+            .insert_resource(ArenaState::with_best_score(0))
+            .add_systems(Update, tally_arena_kills);
+
+        let killed = app.world_mut().spawn(ArenaEnemy).id();
+        let cleared = app.world_mut().spawn(ArenaEnemy).id();
+        let mut arena = app.world_mut().resource_mut::<ArenaState>();
+        arena.alive.insert(killed, EnemyArchetype::Sniper);
+        arena.alive.insert(cleared, EnemyArchetype::BigMelee);
+
+        app.world_mut().send_event(EnemyKilledEvent { entity: killed, archetype: EnemyArchetype::Sniper });
+        app.world_mut().despawn(killed);
+        // Despawned by the scene ending, not by dying
+        app.world_mut().despawn(cleared);
+        app.update();
+
+        let arena = app.world().resource::<ArenaState>();
+        assert_eq!(arena.kills, 1);
+        assert_eq!(arena.score, EnemyArchetype::Sniper.score_value());
+        assert!(arena.alive.is_empty());
+    }
+}
@@ -15,7 +15,7 @@ impl Plugin for CathedralPlugin {
             .init_resource::<crate::world::states::CathedralConfig>()
 
             // Scene lifecycle systems
-            .add_systems(OnEnter(WorldState::Cathedral), setup_cathedral_scene)
+            .add_systems(OnEnter(WorldState::Cathedral), (setup_cathedral_scene, initialize_portals).chain())
             .add_systems(OnExit(WorldState::Cathedral), teardown_cathedral_scene)
             .add_systems(
                 FixedUpdate,
@@ -23,6 +23,7 @@ impl Plugin for CathedralPlugin {
                     handle_portal_interaction_events.run_if(in_state(WorldState::Cathedral)),
                     handle_portal_activation.run_if(in_state(WorldState::Cathedral)),
                     update_portal_displays.run_if(in_state(WorldState::Cathedral)),
+                    update_portal_tooltips.run_if(in_state(WorldState::Cathedral)),
                 )
             );
     }
@@ -2,7 +2,8 @@ use bevy::prelude::*;
 use rand::Rng;
 use std::collections::HashMap;
 
-use super::components::{PortalId, ModifierId};
+use crate::world::{ModifierId, ModifierRegistry};
+use super::components::PortalId;
 
 /// Central resource for managing Cathedral state
 #[derive(Resource, Default, Clone)]
@@ -12,27 +13,11 @@ pub struct CathedralState {
 }
 
 /// Resource for managing the modifier system and portal configurations
-#[derive(Resource, Clone)]
+#[derive(Resource, Default, Clone)]
 pub struct ModifierSystem {
     /// Stable modifier combinations for each portal at each depth
     /// Key: (depth, portal_id) -> Vec<ModifierId>
     pub portal_configurations: HashMap<(u32, PortalId), Vec<ModifierId>>,
-    /// Available modifier types for random generation
-    pub available_modifier_types: Vec<String>,
-}
-
-impl Default for ModifierSystem {
-    fn default() -> Self {
-        Self {
-            portal_configurations: HashMap::new(),
-            available_modifier_types: vec![
-                "enemy_density".to_string(),
-                "enemy_speed".to_string(),
-                "room_size".to_string(),
-                "loot_quantity".to_string(),
-            ],
-        }
-    }
 }
 
 impl ModifierSystem {
@@ -42,32 +27,13 @@ impl ModifierSystem {
     }
 
     /// Generate stable modifier combinations for a specific depth level
-    pub fn generate_portal_modifiers(&mut self, depth: u32, rng: &mut impl Rng) {
+    pub fn generate_portal_modifiers(&mut self, depth: u32, registry: &ModifierRegistry, rng: &mut impl Rng) {
         for portal_id in [PortalId::DungeonLeft, PortalId::DungeonCenter, PortalId::DungeonRight] {
             // Only generate if we don't already have modifiers for this portal/depth combo
             if !self.portal_configurations.contains_key(&(depth, portal_id)) {
-                let mut modifiers = Vec::new();
-
                 // Generate 2-3 modifiers per portal for variety
                 let modifier_count = rng.random_range(2..=3);
-                let mut used_types = Vec::new();
-
-                for _ in 0..modifier_count {
-                    // Pick a random modifier type we haven't used yet
-                    let available_types: Vec<_> = self.available_modifier_types
-                        .iter()
-                        .filter(|t| !used_types.contains(*t))
-                        .collect();
-
-                    if !available_types.is_empty() {
-                        let index = rng.random_range(0..available_types.len());
-                        let modifier_type = available_types[index];
-                        if let Some(modifier) = ModifierId::random_of_type(modifier_type, rng) {
-                            modifiers.push(modifier);
-                            used_types.push(modifier_type.to_string());
-                        }
-                    }
-                }
+                let modifiers = registry.roll(modifier_count, rng);
 
                 self.portal_configurations.insert((depth, portal_id), modifiers);
             }
@@ -83,12 +49,12 @@ impl ModifierSystem {
     }
 
     /// Reroll modifiers for all portals at a given depth (costs currency in later phases)
-    pub fn reroll_depth_modifiers(&mut self, depth: u32, rng: &mut impl Rng) {
+    pub fn reroll_depth_modifiers(&mut self, depth: u32, registry: &ModifierRegistry, rng: &mut impl Rng) {
         // Remove existing configurations for this depth
         self.portal_configurations.retain(|(d, _), _| *d != depth);
 
         // Generate new ones
-        self.generate_portal_modifiers(depth, rng);
+        self.generate_portal_modifiers(depth, registry, rng);
     }
 }
 
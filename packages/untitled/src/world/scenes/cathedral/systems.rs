@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use bevy_ecs_tilemap::prelude::*;
 use bevy_rapier2d::prelude::*;
-use crate::world::{chunks::ChunkingState, InteractionEvent};
+use crate::ui::UiUpdateStats;
+use crate::world::{chunks::ChunkingState, InteractionEvent, ModifierRegistry, WorldState};
 
 use super::{
     components::*,
@@ -16,7 +17,7 @@ pub fn setup_cathedral_scene(
     mut modifier_system: ResMut<ModifierSystem>,
     mut cathedral_state: ResMut<CathedralState>,
     progression_state: Res<ProgressionState>,
-    asset_server: Res<AssetServer>,
+    loaded_assets: Res<crate::loading::LoadedAssets>,
     camera_zoom: Res<crate::player::resources::CameraZoom>,
 ) {
      // Disable chunking in cathedral, fixed map
@@ -35,7 +36,7 @@ pub fn setup_cathedral_scene(
             ..default()
         },
         crate::components::MainCamera,
-        Cathedral, // Tag for cleanup
+        StateScoped(WorldState::Cathedral),
     ));
 
     // Spawn player in the center of the tilemap area (now in positive quadrant)
@@ -45,15 +46,15 @@ pub fn setup_cathedral_scene(
         crate::player::components::PlayerBundle::new(
             &mut meshes,
             &mut materials,
-            Vec3::new(tilemap_center_x, tilemap_center_y, 1.0) // Center of tilemap, Z=1 to render above tilemap at Z=-1
+            Vec3::new(tilemap_center_x, tilemap_center_y, crate::depth::DepthLayer::Actors.z()) // Center of tilemap
         ),
     ).id();
 
-    // Add cathedral tag for cleanup
-    commands.entity(player_entity).insert(Cathedral);
+    // Scope the player to the cathedral so it's despawned on exit
+    commands.entity(player_entity).insert(StateScoped(WorldState::Cathedral));
 
     // Create tilemap directly in cathedral scene
-    let texture_handle: Handle<Image> = asset_server.load("sprites/tiles.png");
+    let texture_handle = loaded_assets.texture("tiles");
 
     let map_size = TilemapSize {
         x: crate::world::tiles::TILEMAP_WIDTH as u32,
@@ -108,10 +109,11 @@ pub fn setup_cathedral_scene(
                 crate::world::tiles::TILE_SIZE / 2.0,
                 crate::world::tiles::TILE_SIZE / 2.0
             ),
+            crate::physics_layers::terrain(),
             RigidBody::Fixed,
-            Transform::from_xyz(world_x, world_y, -1.0),
+            Transform::from_xyz(world_x, world_y, crate::depth::DepthLayer::Terrain.z()),
             Visibility::Hidden, // Invisible collider - visual handled by tile
-            Cathedral, // Tag for cleanup
+            StateScoped(WorldState::Cathedral),
         ));
     }
 
@@ -134,11 +136,11 @@ pub fn setup_cathedral_scene(
         storage: tile_storage,
         texture: TilemapTexture::Single(texture_handle),
         tile_size,
-        transform: Transform::from_xyz(0.0, 0.0, -1.0), // No scaling needed
+        transform: Transform::from_xyz(0.0, 0.0, crate::depth::DepthLayer::Terrain.z()), // No scaling needed
         ..Default::default()
     })
     .insert(crate::world::tiles::GameTilemap)
-    .insert(Cathedral); // Tag for cleanup
+    .insert(StateScoped(WorldState::Cathedral));
 
     setup_cathedral_entities(
         &mut commands,
@@ -150,17 +152,10 @@ pub fn setup_cathedral_scene(
 }
 
 /// System to cleanup the cathedral scene when exiting WorldState::Cathedral
-pub fn teardown_cathedral_scene(
-    mut commands: Commands,
-    mut cathedral_state: ResMut<CathedralState>,
-    cathedral_entities: Query<Entity, With<Cathedral>>,
-) {
+/// Entities spawned for the scene are `StateScoped` and despawn on their own
+pub fn teardown_cathedral_scene(mut cathedral_state: ResMut<CathedralState>) {
     // Mark Cathedral as inactive
     cathedral_state.is_active = false;
-
-    for entity in cathedral_entities.iter() {
-        commands.entity(entity).despawn();
-    }
 }
 
 // Helper function to set up cathedral entities
@@ -179,7 +174,8 @@ fn setup_cathedral_entities(
     let tilemap_height = crate::world::tiles::TILEMAP_HEIGHT as f32 * crate::world::tiles::TILE_SIZE;
     commands.spawn((
         Cathedral,
-        Transform::from_translation(Vec3::new(tilemap_width / 2.0, tilemap_height / 2.0, -1.0)),
+        StateScoped(WorldState::Cathedral),
+        Transform::from_translation(Vec3::new(tilemap_width / 2.0, tilemap_height / 2.0, crate::depth::DepthLayer::Terrain.z())),
         Visibility::Visible,
     ));
 
@@ -187,9 +183,9 @@ fn setup_cathedral_entities(
     let box_center_x = (crate::world::tiles::TILEMAP_WIDTH as f32 * crate::world::tiles::TILE_SIZE) / 2.0;
     let box_center_y = (crate::world::tiles::TILEMAP_HEIGHT as f32 * crate::world::tiles::TILE_SIZE) / 2.0;
     let portal_positions = [
-        Vec3::new(box_center_x - 200.0, box_center_y, 0.0), // Left portal
-        Vec3::new(box_center_x, box_center_y, 0.0),         // Center portal
-        Vec3::new(box_center_x + 200.0, box_center_y, 0.0), // Right portal
+        Vec3::new(box_center_x - 200.0, box_center_y, crate::depth::DepthLayer::Actors.z()), // Left portal
+        Vec3::new(box_center_x, box_center_y, crate::depth::DepthLayer::Actors.z()),         // Center portal
+        Vec3::new(box_center_x + 200.0, box_center_y, crate::depth::DepthLayer::Actors.z()), // Right portal
     ];
 
     let portal_ids = [
@@ -224,19 +220,21 @@ fn setup_cathedral_entities(
             Mesh2d(meshes.add(Rectangle::new(width, height))),
             MeshMaterial2d(materials.add(portal_colors[i])),
             Transform::from_translation(position),
-            Cathedral,
+            crate::depth::YSort::with_offset(-height / 2.0),
+            StateScoped(WorldState::Cathedral),
             Portal {
                 id: portal_id,
                 portal_type,
                 depth: 1,
                 modifiers: Vec::new(),
             },
+            crate::ui::tooltip::Tooltip::new(format!("{:?} Portal", portal_id)),
             Interactable::new(
                 format!("portal_{:?}", portal_id),
                 format!("{:?} Portal", portal_id),
                 |context| { },
             ),
-            InteractableHighlight::with_radius(1.4),
+            InteractableHighlight::default(),
         ));
     }
 
@@ -252,14 +250,15 @@ fn setup_cathedral_entities(
             ..default()
         },
         TextColor(Color::WHITE),
-        Transform::from_translation(Vec3::new(title_x, 50.0, 1.0)), // Above portals, centered on tilemap
-        Cathedral,
+        Transform::from_translation(Vec3::new(title_x, 50.0, crate::depth::DepthLayer::Labels.z())), // Above portals, centered on tilemap
+        StateScoped(WorldState::Cathedral),
     ));
 }
 
 /// Initialize portal configurations with modifiers
 pub fn initialize_portals(
     mut modifier_system: ResMut<ModifierSystem>,
+    registry: Res<ModifierRegistry>,
     mut portal_query: Query<&mut Portal>,
     progression_state: Res<ProgressionState>,
 ) {
@@ -270,7 +269,7 @@ pub fn initialize_portals(
     let current_depth = available_depths.first().copied().unwrap_or(1);
 
     // Generate modifiers for the current depth
-    modifier_system.generate_portal_modifiers(current_depth, &mut rng);
+    modifier_system.generate_portal_modifiers(current_depth, &registry, &mut rng);
 
     // Update portal components with their modifiers
     for mut portal in portal_query.iter_mut() {
@@ -279,43 +278,59 @@ pub fn initialize_portals(
     }
 }
 
-/// Update portal display texts with current modifier information
+/// Rebuild portal tooltips whenever a portal's depth or modifiers change
+pub fn update_portal_tooltips(
+    mut portal_query: Query<(&Portal, &mut crate::ui::tooltip::Tooltip), Changed<Portal>>,
+    registry: Res<ModifierRegistry>,
+) {
+    for (portal, mut tooltip) in portal_query.iter_mut() {
+        let mut updated = crate::ui::tooltip::Tooltip::new(format!("{:?} Portal", portal.id))
+            .with_stat("Depth", portal.depth);
+
+        if portal.modifiers.is_empty() {
+            updated = updated.with_content("No modifiers");
+        }
+        for modifier in &portal.modifiers {
+            updated = updated.with_content(registry.describe(*modifier));
+        }
+
+        *tooltip = updated.with_keybind("E", "Enter");
+    }
+}
+
+/// Update portal display texts when a portal's depth or modifiers change
 pub fn update_portal_displays(
     portal_query: Query<&Portal>,
-    mut display_query: Query<&mut Text2d, With<PortalDisplay>>,
-    display_component_query: Query<&PortalDisplay>,
+    changed_portals: Query<(), Changed<Portal>>,
+    new_displays: Query<(), Added<PortalDisplay>>,
+    mut display_query: Query<(&PortalDisplay, &mut Text2d)>,
+    registry: Res<ModifierRegistry>,
+    mut stats: ResMut<UiUpdateStats>,
 ) {
-    for mut text2d in display_query.iter_mut() {
-        // Find the corresponding PortalDisplay component
-        if let Some(display_component) = display_component_query.iter()
-            .find(|_d| {
-                // This is a bit hacky - we need to match the display component to the text
-                // In a more robust system, we'd store entity IDs or use a better linking method
-                true // For now, we'll update all displays
-            }) {
-
-            // Find the portal with the matching ID
-            if let Some(portal) = portal_query.iter()
-                .find(|p| p.id == display_component.portal_id) {
-
-                let mut display_text = format!("Depth {}\n", portal.depth);
-
-                if portal.modifiers.is_empty() {
-                    display_text.push_str("No Modifiers");
-                } else {
-                    for modifier in &portal.modifiers {
-                        display_text.push_str(&format!("• {}\n", modifier.display_name()));
-                    }
-                }
+    if changed_portals.is_empty() && new_displays.is_empty() && !registry.is_changed() {
+        return;
+    }
+    stats.record("portal_displays");
+
+    for (display, mut text2d) in display_query.iter_mut() {
+        let Some(portal) = portal_query.iter().find(|p| p.id == display.portal_id) else {
+            continue;
+        };
 
-                **text2d = display_text;
+        let mut display_text = format!("Depth {}\n", portal.depth);
+
+        if portal.modifiers.is_empty() {
+            display_text.push_str("No Modifiers");
+        } else {
+            for modifier in &portal.modifiers {
+                display_text.push_str(&format!("• {}\n", registry.describe(*modifier)));
             }
         }
+
+        **text2d = display_text;
     }
 }
 
-
-
 /// Handle portal interactions through the event system
 pub fn handle_portal_interaction_events(
     mut interaction_events: EventReader<InteractionEvent>,
@@ -329,7 +344,7 @@ pub fn handle_portal_interaction_events(
             portal_activation_events.write(crate::events::PortalActivationEvent {
                 portal_id: portal.id,
                 depth: portal.depth,
-                modifiers: portal.modifiers.iter().map(|m| m.display_name()).collect(),
+                modifiers: portal.modifiers.clone(),
             });
 
             return;
@@ -343,8 +358,11 @@ pub fn handle_portal_activation(
     current_state: Res<State<crate::world::states::WorldState>>,
     mut next_state: ResMut<NextState<crate::world::states::WorldState>>,
     portals: Query<&super::components::Portal>,
+    registry: Res<crate::world::ModifierRegistry>,
+    mut dungeon_state: ResMut<crate::world::scenes::dungeon::resources::DungeonState>,
+    mut commands: Commands,
 ) {
-    use crate::world::states::WorldState;
+    use crate::world::states::DungeonConfig;
 
     for event in events.read() {
         if matches!(current_state.get(), WorldState::Cathedral) {
@@ -352,6 +370,25 @@ pub fn handle_portal_activation(
             if let Some(portal) = portals.iter().find(|p| p.id == event.portal_id) {
                 match portal.portal_type {
                     super::components::PortalType::Dungeon => {
+                        let portal_id = match portal.id {
+                            PortalId::DungeonLeft => crate::world::states::PortalId::Left,
+                            PortalId::DungeonCenter => crate::world::states::PortalId::Center,
+                            PortalId::DungeonRight => crate::world::states::PortalId::Right,
+                        };
+                        commands.insert_resource(DungeonConfig {
+                            depth: event.depth,
+                            modifiers: event.modifiers.clone(),
+                            portal_id,
+                        });
+
+                        // A new run starts here: lay out the depths ahead of it
+                        *dungeon_state = crate::world::scenes::dungeon::resources::DungeonState::new_for_depth(event.depth);
+                        commands.insert_resource(crate::world::RunStructure::generate(
+                            event.depth,
+                            event.modifiers.clone(),
+                            &registry,
+                            dungeon_state.seed,
+                        ));
                         next_state.set(WorldState::Dungeon);
                     },
                 }
@@ -1,9 +1,27 @@
 use bevy::prelude::*;
 
-/// Dungeon marker component for entities that belong to the dungeon scene
-#[derive(Component, Debug)]
-pub struct Dungeon;
+use crate::combat::HAZARD_PULSE_DAMAGE;
 
 /// Portal to sanctuary from dungeon
 #[derive(Component, Debug)]
 pub struct DungeonExitPortal;
+
+/// Environmental hazard that damages the player while they stand in it
+///
+/// Damage goes through the effects pipeline as `EffectDefId::HAZARD_PULSE`,
+/// pulsed often enough to deal the damage per second it was made with on average.
+#[derive(Component, Debug)]
+pub struct HazardZone {
+    pub radius: f32,
+    pub pulse: Timer,
+}
+
+impl HazardZone {
+    pub fn new(radius: f32, damage_per_second: f32) -> Self {
+        let interval = HAZARD_PULSE_DAMAGE / damage_per_second.max(f32::EPSILON);
+        Self {
+            radius,
+            pulse: Timer::from_seconds(interval, TimerMode::Repeating),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Scripted encounters for set-piece rooms
+//!
+//! Each set piece stamped into the macro map gets an `Encounter` covering its
+//! bounds. Entering the room starts the encounter's waves; clearing the last
+//! wave spawns a reward chest.
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::components::{Enemy, EnemyArchetype};
+use crate::enemy::spawn_enemy;
+use crate::inventory::{factory::ItemFactory, spawn_world_item, ItemRegistry};
+use crate::player::Player;
+use crate::world::mapgen::{SetPieceKind, SetPiecePlacement};
+use crate::world::{Interactable, InteractableHighlight, InteractionEvent, RunModifiers, WorldState};
+use super::components::HazardZone;
+use super::population::EnemyBudget;
+use super::resources::DungeonState;
+use super::systems::macro_cell_center;
+
+/// Seconds after a wave spawns before it can count as cleared
+const WAVE_GRACE_PERIOD: f32 = 1.5;
+/// Item categories a reward chest draws from
+const REWARD_CATEGORIES: [&str; 3] = ["consumable", "weapon", "armor"];
+
+/// Plugin for set-piece encounters
+pub struct EncounterPlugin;
+
+impl Plugin for EncounterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, (
+            trigger_encounters,
+            advance_encounters,
+            open_reward_chests,
+        ).chain().run_if(in_state(WorldState::Dungeon)));
+    }
+}
+
+/// One group of enemies spawned together
+#[derive(Debug, Clone)]
+pub struct EncounterWave {
+    pub enemies: Vec<(EnemyArchetype, u32)>,
+}
+
+/// Script for a set piece: waves to fight and the reward for clearing them
+#[derive(Debug, Clone)]
+pub struct EncounterDefinition {
+    pub waves: Vec<EncounterWave>,
+    /// Items in the reward chest, before loot modifiers
+    pub reward_items: u32,
+    /// Hazard pools scattered through the room when it is built
+    pub hazards: u32,
+}
+
+impl EncounterDefinition {
+    /// Encounter script for a set piece, scaled by depth
+    pub fn for_set_piece(kind: SetPieceKind, depth: u32) -> Self {
+        use EnemyArchetype::*;
+        let extra = depth / 3;
+
+        match kind {
+            SetPieceKind::TreasureVault => Self {
+                waves: vec![EncounterWave { enemies: vec![(BigMelee, 1 + extra), (Shotgunner, 1)] }],
+                reward_items: 4,
+                hazards: 0,
+            },
+            SetPieceKind::TrapGauntlet => Self {
+                waves: vec![EncounterWave { enemies: vec![(Sniper, 2 + extra)] }],
+                reward_items: 2,
+                hazards: 6,
+            },
+            SetPieceKind::Arena => Self {
+                waves: vec![
+                    EncounterWave { enemies: vec![(SmallMelee, 4 + extra)] },
+                    EncounterWave { enemies: vec![(SmallMelee, 2 + extra), (Shotgunner, 2)] },
+                    EncounterWave { enemies: vec![(BigMelee, 1 + extra), (MachineGunner, 1), (Sniper, 1)] },
+                ],
+                reward_items: 3,
+                hazards: 0,
+            },
+        }
+    }
+}
+
+/// Progress of an encounter
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncounterState {
+    /// Waiting for the player to enter
+    Dormant,
+    /// `next_wave` spawns once the previous wave is dead and `grace` has run out
+    Active { next_wave: usize, grace: f32 },
+    /// All waves beaten and the reward handed out
+    Cleared,
+}
+
+/// A scripted encounter bound to a set-piece room
+#[derive(Component, Debug)]
+pub struct Encounter {
+    pub kind: SetPieceKind,
+    /// Seed for everything random in this room, from the dungeon seed and the room's index
+    pub seed: u64,
+    pub definition: EncounterDefinition,
+    pub bounds: Rect,
+    pub state: EncounterState,
+}
+
+/// Enemy spawned by an encounter wave
+#[derive(Component, Debug)]
+pub struct EncounterEnemy {
+    pub encounter: Entity,
+}
+
+/// Chest spawned when an encounter is cleared
+#[derive(Component, Debug)]
+pub struct RewardChest {
+    pub items: u32,
+}
+
+/// World-space bounds of a set piece
+fn placement_bounds(placement: &SetPiecePlacement) -> Rect {
+    let min = macro_cell_center(placement.origin.0, placement.origin.1);
+    let max = macro_cell_center(placement.origin.0 + placement.size.0 - 1, placement.origin.1 + placement.size.1 - 1);
+    Rect::from_corners(min, max)
+}
+
+/// Seed for one set-piece room, so the same dungeon seed plays out the same encounters
+fn encounter_seed(dungeon_seed: u64, room: usize) -> u64 {
+    dungeon_seed ^ (room as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Spawn an `Encounter` (and any built-in hazards) for every set piece in the level
+pub fn spawn_encounters(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    dungeon_state: Res<DungeonState>,
+) {
+    for (room, placement) in dungeon_state.set_pieces.iter().enumerate() {
+        let definition = EncounterDefinition::for_set_piece(placement.kind, dungeon_state.depth);
+        let bounds = placement_bounds(placement);
+        let seed = encounter_seed(dungeon_state.seed, room);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        for _ in 0..definition.hazards {
+            let position = Vec2::new(
+                rng.random_range(bounds.min.x..bounds.max.x),
+                rng.random_range(bounds.min.y..bounds.max.y),
+            );
+            commands.spawn((
+                Mesh2d(meshes.add(Circle::new(32.0))),
+                MeshMaterial2d(materials.add(Color::srgba(0.9, 0.4, 0.1, 0.35))),
+                Transform::from_translation(position.extend(crate::depth::DepthLayer::TerrainDetail.z())),
+                HazardZone::new(32.0, 12.0),
+                StateScoped(WorldState::Dungeon),
+            ));
+        }
+
+        commands.spawn((
+            Encounter {
+                kind: placement.kind,
+                seed,
+                definition,
+                bounds,
+                state: EncounterState::Dormant,
+            },
+            StateScoped(WorldState::Dungeon),
+        ));
+    }
+
+    info!("Spawned {} set-piece encounters", dungeon_state.set_pieces.len());
+}
+
+/// Start dormant encounters when the player walks into their room
+pub fn trigger_encounters(
+    mut encounters: Query<&mut Encounter>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for mut encounter in encounters.iter_mut() {
+        if encounter.state == EncounterState::Dormant && encounter.bounds.contains(player_pos) {
+            info!("Encounter {:?} started", encounter.kind);
+            encounter.state = EncounterState::Active { next_wave: 0, grace: 0.0 };
+        }
+    }
+}
+
+/// Spawn waves as the previous one dies, then drop the reward chest
+///
+/// Waves share the `EnemyBudget` with ambient enemies: a wave is cut down to
+/// the room left in the budget and waits while there is none.
+pub fn advance_encounters(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+    modifiers: Option<Res<RunModifiers>>,
+    budget: Res<EnemyBudget>,
+    mut encounters: Query<(Entity, &mut Encounter)>,
+    enemies: Query<&EncounterEnemy>,
+    alive: Query<(), With<Enemy>>,
+) {
+    let dt = time.delta_secs();
+    let mut room = budget.max_alive.saturating_sub(alive.iter().count());
+
+    for (entity, mut encounter) in encounters.iter_mut() {
+        let EncounterState::Active { next_wave, grace } = encounter.state else {
+            continue;
+        };
+
+        if grace > 0.0 {
+            encounter.state = EncounterState::Active { next_wave, grace: grace - dt };
+            continue;
+        }
+
+        if enemies.iter().any(|enemy| enemy.encounter == entity) {
+            continue;
+        }
+
+        let Some(wave) = encounter.definition.waves.get(next_wave) else {
+            let items = modifiers.as_ref()
+                .map_or(encounter.definition.reward_items, |m| m.loot_count(encounter.definition.reward_items));
+            spawn_reward_chest(&mut commands, &mut meshes, &mut materials, encounter.bounds.center(), items);
+            info!("Encounter {:?} cleared", encounter.kind);
+            encounter.state = EncounterState::Cleared;
+            continue;
+        };
+
+        if room == 0 {
+            continue;
+        }
+
+        let archetypes: Vec<EnemyArchetype> = wave.enemies
+            .iter()
+            .flat_map(|&(archetype, count)| {
+                let count = modifiers.as_ref().map_or(count, |m| m.enemy_count(count).max(1));
+                std::iter::repeat_n(archetype, count as usize)
+            })
+            .take(room)
+            .collect();
+        room -= archetypes.len();
+
+        // Each wave rolls from its own stream, so waves don't depend on how the hazards were placed
+        let mut rng = StdRng::seed_from_u64(encounter.seed.wrapping_add(next_wave as u64 + 1));
+        let bounds = encounter.bounds;
+        for archetype in archetypes {
+            let position = Vec2::new(
+                rng.random_range(bounds.min.x..bounds.max.x),
+                rng.random_range(bounds.min.y..bounds.max.y),
+            );
+            let enemy = spawn_enemy(&mut commands, &mut meshes, &mut materials, archetype, position);
+            commands.entity(enemy).insert((EncounterEnemy { encounter: entity }, StateScoped(WorldState::Dungeon)));
+        }
+
+        encounter.state = EncounterState::Active { next_wave: next_wave + 1, grace: WAVE_GRACE_PERIOD };
+    }
+}
+
+fn spawn_reward_chest(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    position: Vec2,
+    items: u32,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(36.0, 24.0))),
+        MeshMaterial2d(materials.add(Color::srgb(0.8, 0.6, 0.2))),
+        Transform::from_translation(position.extend(crate::depth::DepthLayer::Actors.z())),
+        crate::depth::YSort::with_offset(-12.0),
+        crate::ui::tooltip::Tooltip::new("Reward Chest")
+            .with_stat("Items", items)
+            .with_keybind("E", "Open"),
+        Interactable::new(
+            "reward_chest".to_string(),
+            "Reward Chest".to_string(),
+            |_| {},
+        ),
+        InteractableHighlight::default(),
+        RewardChest { items },
+        StateScoped(WorldState::Dungeon),
+    ));
+}
+
+/// Spill a chest's items around it when opened
+pub fn open_reward_chests(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut interaction_events: EventReader<InteractionEvent>,
+    chests: Query<(&RewardChest, &Transform)>,
+    mut factory: ResMut<ItemFactory>,
+    registry: Res<ItemRegistry>,
+) {
+    for event in interaction_events.read() {
+        let Ok((chest, transform)) = chests.get(event.target_entity) else {
+            continue;
+        };
+
+        let center = transform.translation.truncate();
+        for i in 0..chest.items {
+            let Some(item) = factory.create_random_from_categories(&REWARD_CATEGORIES, &registry) else {
+                continue;
+            };
+            let Some(definition) = registry.get(item.item_id) else {
+                continue;
+            };
+
+            let angle = i as f32 / chest.items.max(1) as f32 * std::f32::consts::TAU;
+            let position = center + Vec2::from_angle(angle) * 40.0;
+            let entity = spawn_world_item(&mut commands, &mut meshes, &mut materials, item, definition, position);
+            commands.entity(entity).insert(StateScoped(WorldState::Dungeon));
+        }
+
+        commands.entity(event.target_entity).despawn();
+    }
+}
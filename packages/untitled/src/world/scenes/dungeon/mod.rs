@@ -2,11 +2,14 @@ pub mod resources;
 pub mod components;
 
 mod systems;
+pub mod encounters;
 pub mod terrain;
+pub mod population;
 
 use bevy::prelude::*;
 
 use crate::world::states::WorldState;
+use crate::world::SceneLoadState;
 
 /// Plugin for the dungeon scene
 pub struct DungeonPlugin;
@@ -17,13 +20,27 @@ impl Plugin for DungeonPlugin {
             .init_resource::<resources::DungeonState>()
 
             // Add systems for dungeon state transitions
-            .add_systems(OnEnter(WorldState::Dungeon), systems::setup_dungeon_scene)
+            .add_systems(OnEnter(WorldState::Dungeon), (
+                systems::apply_run_modifiers,
+                systems::setup_dungeon_scene,
+            ).chain())
+            // The map and terrain load over several frames behind the loading screen
+            .add_systems(Update, (
+                systems::finish_dungeon_generation,
+                systems::wait_for_initial_chunks,
+            ).chain().run_if(in_state(WorldState::Dungeon).and(in_state(SceneLoadState::Loading))))
+            .add_systems(OnEnter(SceneLoadState::Ready), (
+                systems::spawn_modifier_hazards,
+                encounters::spawn_encounters,
+            ).chain().run_if(in_state(WorldState::Dungeon)))
             .add_systems(OnExit(WorldState::Dungeon), systems::teardown_dungeon_scene)
-            .add_plugins(terrain::TerrainChunkPlugin)
+            .add_plugins((terrain::TerrainChunkPlugin, encounters::EncounterPlugin, population::PopulationPlugin))
 
             // Add systems that run while in dungeon
             .add_systems(FixedUpdate, (
                 systems::handle_dungeon_portal_interactions,
-            ).run_if(in_state(WorldState::Dungeon)));
+                systems::apply_hazard_damage,
+            ).run_if(in_state(WorldState::Dungeon)))
+            .add_systems(Update, systems::apply_enemy_stat_modifiers.run_if(resource_exists::<crate::world::RunModifiers>));
     }
 }
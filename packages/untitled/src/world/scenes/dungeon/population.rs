@@ -0,0 +1,284 @@
+//! Ambient enemies living in terrain chunks
+//!
+//! Each chunk rolls its enemies once, from the dungeon seed, the first time it
+//! loads. Enemies only exist as entities while their chunk is loaded: when a
+//! chunk unloads, the enemies standing in it are despawned and kept as dormant
+//! records (archetype, position, remaining health) until it loads again. A
+//! global `EnemyBudget` caps how many enemies are alive at once, so a wide
+//! loading radius can't flood FixedUpdate with AI; chunks nearest the player
+//! are populated first and the rest wait for room.
+use bevy::prelude::*;
+use rand::{distr::{weighted::WeightedIndex, Distribution}, rngs::StdRng, seq::IndexedRandom, Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+use crate::combat::CombatState;
+use crate::components::{Enemy, EnemyArchetype};
+use crate::enemy::{spawn_enemy, ArchetypeConfig};
+use crate::player::Player;
+use crate::world::chunks::{world_pos_to_chunk_coord, ChunkCoord, UnloadChunk, CHUNK_SIZE};
+use crate::world::chunks::systems::track_chunk_loaders;
+use crate::world::mapgen::Biome;
+use crate::world::tiles::{TileType, TILE_SIZE};
+use crate::world::{RunModifiers, WorldState, DUNGEON_SIZE_PX};
+use super::resources::DungeonState;
+use super::terrain::{poll_terrain_loading_tasks, ChunkTiles, TerrainChunks};
+
+/// Chance a chunk holds a group of enemies
+const CHUNK_SPAWN_CHANCE: f64 = 0.2;
+/// Enemies in a group, before the density modifier
+const GROUP_SIZE_MIN: u32 = 1;
+const GROUP_SIZE_MAX: u32 = 3;
+/// Chunks with less open floor than this stay empty
+const MIN_FLOOR_TILES: usize = 24;
+/// No ambient enemies this close to where the player enters the dungeon
+const SPAWN_CLEARANCE: f32 = 800.0;
+
+/// Plugin for per-chunk enemy populations and the alive-enemy budget
+pub struct PopulationPlugin;
+
+impl Plugin for PopulationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<EnemyBudget>()
+            .init_resource::<ChunkPopulation>()
+            .add_systems(FixedUpdate, materialize_chunk_populations
+                .after(poll_terrain_loading_tasks)
+                .run_if(in_state(WorldState::Dungeon)))
+            .add_systems(Update, persist_unloaded_populations
+                .after(track_chunk_loaders)
+                .run_if(in_state(WorldState::Dungeon)))
+            .add_systems(OnExit(WorldState::Dungeon), reset_chunk_population);
+    }
+}
+
+/// Maximum number of enemies alive at once, including encounter waves
+#[derive(Resource, Debug, Clone)]
+pub struct EnemyBudget {
+    pub max_alive: usize,
+}
+
+impl Default for EnemyBudget {
+    fn default() -> Self {
+        Self { max_alive: 48 }
+    }
+}
+
+/// An enemy waiting for its chunk to load, or for room in the budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct DormantEnemy {
+    pub archetype: EnemyArchetype,
+    pub position: Vec2,
+    /// Fraction of max health left, so run modifiers apply again on respawn
+    pub health_fraction: f32,
+}
+
+/// Ambient enemy spawned from a chunk's population
+#[derive(Component, Debug)]
+pub struct ChunkPopulant;
+
+/// Dormant enemies per chunk and which chunks already rolled theirs
+#[derive(Resource, Debug, Default)]
+pub struct ChunkPopulation {
+    pub dormant: HashMap<ChunkCoord, Vec<DormantEnemy>>,
+    generated: HashSet<ChunkCoord>,
+}
+
+impl ChunkPopulation {
+    /// Take up to `room` dormant enemies, visiting chunks in `order`
+    pub fn take_within_budget(&mut self, order: &[ChunkCoord], mut room: usize) -> Vec<DormantEnemy> {
+        let mut taken = Vec::new();
+        for coord in order {
+            if room == 0 {
+                break;
+            }
+            let Some(dormant) = self.dormant.get_mut(coord) else {
+                continue;
+            };
+
+            let count = room.min(dormant.len());
+            taken.extend(dormant.drain(..count));
+            room -= count;
+            if dormant.is_empty() {
+                self.dormant.remove(coord);
+            }
+        }
+        taken
+    }
+}
+
+/// Seed for one chunk's rolls, stable for a given dungeon seed
+fn chunk_seed(seed: u64, coord: ChunkCoord) -> u64 {
+    seed ^ (coord.x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (coord.y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+}
+
+/// Roll the enemies a freshly generated chunk starts with
+pub fn generate_chunk_spawns(
+    coord: ChunkCoord,
+    tiles: &ChunkTiles,
+    biome: Biome,
+    seed: u64,
+    modifiers: Option<&RunModifiers>,
+) -> Vec<DormantEnemy> {
+    let mut rng = StdRng::seed_from_u64(chunk_seed(seed, coord));
+    if !rng.random_bool(CHUNK_SPAWN_CHANCE) {
+        return Vec::new();
+    }
+
+    let floors: Vec<(usize, usize)> = (0..CHUNK_SIZE as usize)
+        .flat_map(|x| (0..CHUNK_SIZE as usize).map(move |y| (x, y)))
+        .filter(|&(x, y)| tiles[x][y] == TileType::Floor)
+        .collect();
+    if floors.len() < MIN_FLOOR_TILES {
+        return Vec::new();
+    }
+
+    let base_weights = biome.definition().spawn_weights;
+    let spawn_table = modifiers.map_or(base_weights.to_vec(), |m| m.spawn_table(&base_weights));
+    let Ok(distribution) = WeightedIndex::new(spawn_table.iter().map(|(_, weight)| *weight)) else {
+        return Vec::new();
+    };
+
+    let group_size = rng.random_range(GROUP_SIZE_MIN..=GROUP_SIZE_MAX);
+    let group_size = modifiers.map_or(group_size, |m| m.enemy_count(group_size));
+    let origin = coord.as_vec2() * CHUNK_SIZE as f32 * TILE_SIZE;
+
+    (0..group_size)
+        .filter_map(|_| {
+            let &(x, y) = floors.choose(&mut rng)?;
+            Some(DormantEnemy {
+                archetype: spawn_table[distribution.sample(&mut rng)].0,
+                position: origin + Vec2::new(x as f32, y as f32) * TILE_SIZE,
+                health_fraction: 1.0,
+            })
+        })
+        .collect()
+}
+
+/// Roll populations for new chunks and spawn dormant enemies while the budget allows
+pub fn materialize_chunk_populations(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut population: ResMut<ChunkPopulation>,
+    terrain_chunks: Res<TerrainChunks>,
+    dungeon_state: Res<DungeonState>,
+    budget: Res<EnemyBudget>,
+    modifiers: Option<Res<RunModifiers>>,
+    enemies: Query<(), With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let entrance = Vec2::splat((DUNGEON_SIZE_PX / 2) as f32);
+    for (coord, tiles) in terrain_chunks.iter_loaded() {
+        if !population.generated.insert(coord) {
+            continue;
+        }
+
+        let Some(biome) = terrain_chunks.biome_at(coord) else {
+            continue;
+        };
+        let spawns: Vec<_> = generate_chunk_spawns(coord, tiles, biome, dungeon_state.seed, modifiers.as_deref())
+            .into_iter()
+            .filter(|enemy| enemy.position.distance(entrance) > SPAWN_CLEARANCE)
+            .collect();
+        if !spawns.is_empty() {
+            population.dormant.insert(coord, spawns);
+        }
+    }
+
+    let room = budget.max_alive.saturating_sub(enemies.iter().count());
+    if room == 0 || population.dormant.is_empty() {
+        return;
+    }
+
+    // Fill the budget from the chunks closest to the player
+    let player_pos = player_query.single().map_or(entrance, |t| t.translation.truncate());
+    let mut order: Vec<ChunkCoord> = population.dormant.keys()
+        .copied()
+        .filter(|coord| terrain_chunks.biome_at(*coord).is_some())
+        .collect();
+    order.sort_by_key(|coord| coord.distance_squared(world_pos_to_chunk_coord(player_pos)));
+
+    for dormant in population.take_within_budget(&order, room) {
+        let max_health = ArchetypeConfig::for_archetype(dormant.archetype).health;
+        let mut combat = CombatState::new(max_health);
+        combat.health = max_health * dormant.health_fraction;
+
+        let enemy = spawn_enemy(&mut commands, &mut meshes, &mut materials, dormant.archetype, dormant.position);
+        commands.entity(enemy).insert((combat, ChunkPopulant, StateScoped(WorldState::Dungeon)));
+    }
+}
+
+/// Put ambient enemies in unloading chunks back to sleep
+pub fn persist_unloaded_populations(
+    mut commands: Commands,
+    mut unload_events: EventReader<UnloadChunk>,
+    mut population: ResMut<ChunkPopulation>,
+    populants: Query<(Entity, &Transform, &Enemy, &CombatState), With<ChunkPopulant>>,
+) {
+    let unloaded: HashSet<ChunkCoord> = unload_events.read().map(|event| event.pos).collect();
+    if unloaded.is_empty() {
+        return;
+    }
+
+    for (entity, transform, enemy, combat) in populants.iter() {
+        // Enemies wander, so they sleep in the chunk they ended up in
+        let position = transform.translation.truncate();
+        let coord = world_pos_to_chunk_coord(position);
+        if !unloaded.contains(&coord) {
+            continue;
+        }
+
+        population.dormant.entry(coord).or_default().push(DormantEnemy {
+            archetype: enemy.archetype,
+            position,
+            health_fraction: (combat.health / combat.max_health).clamp(0.0, 1.0),
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Forget the level's population when leaving the dungeon
+pub fn reset_chunk_population(mut population: ResMut<ChunkPopulation>) {
+    *population = ChunkPopulation::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawns_are_seeded_and_on_floor() {
+        let mut tiles = [[TileType::Wall; CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
+        for column in tiles.iter_mut().skip(4).take(8) {
+            column.fill(TileType::Floor);
+        }
+
+        // Roll a few chunks until one holds enemies
+        let coord = (0..100)
+            .map(|x| IVec2::new(x, 3))
+            .find(|coord| !generate_chunk_spawns(*coord, &tiles, Biome::Crypt, 7, None).is_empty())
+            .unwrap();
+        let spawns = generate_chunk_spawns(coord, &tiles, Biome::Crypt, 7, None);
+        assert_eq!(spawns, generate_chunk_spawns(coord, &tiles, Biome::Crypt, 7, None));
+
+        let origin = coord.as_vec2() * CHUNK_SIZE as f32 * TILE_SIZE;
+        for enemy in &spawns {
+            let tile = ((enemy.position - origin) / TILE_SIZE).round().as_uvec2();
+            assert_eq!(tiles[tile.x as usize][tile.y as usize], TileType::Floor);
+        }
+    }
+
+    #[test]
+    fn test_budget_takes_nearest_chunks_first() {
+        let dormant = |x| DormantEnemy { archetype: EnemyArchetype::SmallMelee, position: Vec2::new(x, 0.0), health_fraction: 0.5 };
+        let mut population = ChunkPopulation::default();
+        population.dormant.insert(IVec2::ZERO, vec![dormant(0.0), dormant(1.0)]);
+        population.dormant.insert(IVec2::X, vec![dormant(2.0), dormant(3.0)]);
+
+        let taken = population.take_within_budget(&[IVec2::X, IVec2::ZERO], 3);
+        assert_eq!(taken.len(), 3);
+        assert_eq!(taken[0].position.x, 2.0);
+        assert_eq!(population.dormant[&IVec2::ZERO].len(), 1);
+        assert!(!population.dormant.contains_key(&IVec2::X));
+    }
+}
@@ -2,6 +2,7 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use bevy::prelude::*;
 use crate::world::MapId;
+use crate::world::mapgen::SetPiecePlacement;
 
 /// Resource tracking the current dungeon state and configuration
 #[derive(Resource, Debug, Clone)]
@@ -32,6 +33,9 @@ pub struct DungeonState {
     pub map_id: MapId,
 
     pub macro_map: Vec<Vec<bool>>,
+
+    /// Set-piece rooms stamped into the macro map
+    pub set_pieces: Vec<SetPiecePlacement>,
 }
 
 impl Default for DungeonState {
@@ -48,6 +52,7 @@ impl Default for DungeonState {
             seed,
             map_id: MapId::new(seed),
             macro_map: vec![],
+            set_pieces: vec![],
         }
     }
 }
@@ -65,6 +70,7 @@ impl DungeonState {
             seed,
             map_id: MapId::new(seed),
             macro_map: vec![],
+            set_pieces: vec![],
         }
     }
 
@@ -1,32 +1,59 @@
 use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use rand::{distr::{weighted::WeightedIndex, Distribution}, rngs::StdRng, SeedableRng};
 
+use crate::combat::{CombatState, EffectDefId, EffectEvent};
+use crate::components::Enemy;
+use crate::player::Player;
 use crate::world;
+use crate::world::{ModifierRegistry, RunModifiers};
+use crate::world::states::DungeonConfig;
 use crate::world::mapgen;
 use crate::world::chunks;
+use crate::world::{SceneLoadProgress, SceneLoadState};
 use super::components;
 use super::resources;
+use super::terrain::TerrainChunks;
+
+/// Chunks around the spawn, by Manhattan distance, that load behind the loading screen
+const INITIAL_CHUNK_RADIUS: i32 = 3;
+
+/// Map generation running off the main thread while the loading screen is up
+#[derive(Resource)]
+pub struct DungeonGenerationTask(Task<(Vec<Vec<bool>>, Vec<mapgen::SetPiecePlacement>)>);
 
 /// Set up the dungeon scene when entering
+/// The map is generated on a background thread; the rest of the scene follows in `finish_dungeon_generation`
 pub fn setup_dungeon_scene(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut dungeon_state: ResMut<resources::DungeonState>,
+    dungeon_state: Res<resources::DungeonState>,
+    mut progress: ResMut<SceneLoadProgress>,
     camera_zoom: Res<crate::player::resources::CameraZoom>,
+    modifiers: Res<RunModifiers>,
 ) {
     info!("Setting up Dungeon scene (depth {})", dungeon_state.depth);
 
-    // Create level based on dungeon state
-    // Note: each macro cell is 0.5 chunks (16x16 meters)
-    dungeon_state.macro_map = mapgen::roomy(
-        world::DUNGEON_SIZE_M / world::METERS_PER_CHUNK * world::MACRO_PX_PER_CHUNK,
-        dungeon_state.seed,
-    );
+    commands.set_state(SceneLoadState::Loading);
+    progress.set("Generating dungeon", 0.0);
 
-    info!("Dungeon macro map generated with dimensions: {}x{}", dungeon_state.macro_map.len(), dungeon_state.macro_map[0].len());
+    let (seed, depth, room_size) = (dungeon_state.seed, dungeon_state.depth, modifiers.room_size);
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        // Create level based on dungeon state
+        // Note: each macro cell is 0.5 chunks (16x16 meters)
+        let mut macro_map = mapgen::roomy_scaled(
+            world::DUNGEON_SIZE_M / world::METERS_PER_CHUNK * world::MACRO_PX_PER_CHUNK,
+            seed,
+            mapgen::Symmetry::None,
+            room_size,
+        );
 
-    // Enable chunking in dungeon, procedural map
-    commands.set_state(chunks::ChunkingState::Enabled);
+        // Stamp set-piece rooms over the generated layout
+        let mut rng = StdRng::seed_from_u64(seed);
+        let kinds = mapgen::set_pieces_for_depth(depth, &mut rng);
+        let set_pieces = mapgen::place_set_pieces(&mut macro_map, &kinds, &mut rng);
+        (macro_map, set_pieces)
+    });
+    commands.insert_resource(DungeonGenerationTask(task));
 
     // Spawn camera
     let zoom_level = camera_zoom.level;
@@ -37,8 +64,32 @@ pub fn setup_dungeon_scene(
             ..default()
         },
         crate::components::MainCamera,
-        components::Dungeon, // Tag for cleanup
+        StateScoped(world::WorldState::Dungeon),
     ));
+}
+
+/// Spawn the player and portal once the map is generated, which starts the terrain around them loading
+pub fn finish_dungeon_generation(
+    mut commands: Commands,
+    task: Option<ResMut<DungeonGenerationTask>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut dungeon_state: ResMut<resources::DungeonState>,
+    mut progress: ResMut<SceneLoadProgress>,
+) {
+    let Some(mut task) = task.filter(|task| task.0.is_finished()) else {
+        return;
+    };
+    let (macro_map, set_pieces) = bevy::tasks::block_on(&mut task.0);
+    commands.remove_resource::<DungeonGenerationTask>();
+
+    info!("Dungeon macro map generated with dimensions: {}x{}", macro_map.len(), macro_map[0].len());
+    dungeon_state.macro_map = macro_map;
+    dungeon_state.set_pieces = set_pieces;
+
+    // Enable chunking in dungeon, procedural map
+    commands.set_state(chunks::ChunkingState::Enabled);
+    progress.set("Loading terrain", 0.2);
 
     let spawn_pos = (world::DUNGEON_SIZE_PX / 2, world::DUNGEON_SIZE_PX / 2);
 
@@ -46,9 +97,10 @@ pub fn setup_dungeon_scene(
     commands.spawn((
         Mesh2d(meshes.add(Rectangle::new(60.0, 80.0))),
         MeshMaterial2d(materials.add(Color::srgb(0.3, 0.8, 0.3))), // Green portal
-        Transform::from_translation(Vec3::new(spawn_pos.0 as f32, spawn_pos.1 as f32 - 150.0, 0.0)),
+        Transform::from_translation(Vec3::new(spawn_pos.0 as f32, spawn_pos.1 as f32 - 150.0, crate::depth::DepthLayer::Actors.z())),
+        crate::depth::YSort::with_offset(-40.0),
         components::DungeonExitPortal,
-        components::Dungeon,
+        StateScoped(world::WorldState::Dungeon),
         crate::world::Interactable::new(
             "dungeon_exit_portal".to_string(),
             "Portal to Sanctuary".to_string(),
@@ -56,7 +108,7 @@ pub fn setup_dungeon_scene(
                 info!("Dungeon exit portal activated");
             }
         ),
-        crate::world::InteractableHighlight::with_radius(1.4),
+        crate::world::InteractableHighlight::default(),
     ));
 
     // Spawn player at dungeon entrance (at spawn room center)
@@ -64,26 +116,157 @@ pub fn setup_dungeon_scene(
         crate::player::components::PlayerBundle::new(
             &mut meshes,
             &mut materials,
-            Vec3::new(spawn_pos.0 as f32, spawn_pos.1 as f32, 0.0),
+            Vec3::new(spawn_pos.0 as f32, spawn_pos.1 as f32, crate::depth::DepthLayer::Actors.z()),
         ),
-        components::Dungeon,
+        StateScoped(world::WorldState::Dungeon),
     ));
+}
+
+/// Finish loading once the chunks around the player are spawned
+pub fn wait_for_initial_chunks(
+    mut commands: Commands,
+    terrain_chunks: Option<Res<TerrainChunks>>,
+    players: Query<&Transform, With<Player>>,
+    mut progress: ResMut<SceneLoadProgress>,
+) {
+    let (Some(terrain_chunks), Ok(player_transform)) = (terrain_chunks, players.single()) else {
+        return;
+    };
 
-    info!("Dungeon scene setup complete");
+    let center = chunks::world_pos_to_chunk_coord(player_transform.translation.truncate());
+    let ring = chunks::systems::calculate_chunks_in_radius(center, INITIAL_CHUNK_RADIUS);
+    let loaded = ring.iter().filter(|&&chunk_coord| terrain_chunks.is_loaded(chunk_coord)).count();
+    progress.set("Loading terrain", 0.2 + 0.8 * loaded as f32 / ring.len() as f32);
+
+    if loaded == ring.len() {
+        info!("Dungeon scene setup complete");
+        commands.set_state(SceneLoadState::Ready);
+    }
 }
 
-/// Clean up dungeon scene when exiting
-pub fn teardown_dungeon_scene(
+/// Hazards are kept at least this far from the player spawn
+const HAZARD_SPAWN_CLEARANCE: f32 = 400.0;
+
+/// Fold the chosen portal modifiers into the run-wide `RunModifiers` resource
+pub fn apply_run_modifiers(
     mut commands: Commands,
-    dungeon_entities: Query<Entity, With<components::Dungeon>>,
+    config: Option<Res<DungeonConfig>>,
+    registry: Res<ModifierRegistry>,
 ) {
-    info!("Tearing down Dungeon scene");
+    let modifiers = config
+        .map(|config| RunModifiers::from_modifiers(&registry, &config.modifiers))
+        .unwrap_or_default();
+
+    info!("Dungeon run modifiers: {:?}", modifiers);
+    commands.insert_resource(modifiers);
+}
+
+/// World position of the center of a macro map cell
+pub(super) fn macro_cell_center(x: usize, y: usize) -> Vec2 {
+    let cell_size = chunks::CHUNK_SIZE as f32 / world::MACRO_PX_PER_CHUNK as f32 * world::tiles::TILE_SIZE;
+    Vec2::new((x as f32 + 0.5) * cell_size, (y as f32 + 0.5) * cell_size)
+}
 
-    // Despawn all dungeon entities
-    for entity in dungeon_entities.iter() {
-        commands.entity(entity).despawn();
+/// Scatter hazard zones over open floor, away from the player spawn
+pub fn spawn_modifier_hazards(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    modifiers: Res<RunModifiers>,
+    dungeon_state: Res<resources::DungeonState>,
+) {
+    if modifiers.hazards.is_empty() {
+        return;
     }
 
+    let spawn_pos = Vec2::splat((world::DUNGEON_SIZE_PX / 2) as f32);
+    let floor_cells: Vec<Vec2> = dungeon_state.macro_map.iter().enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, floor)| **floor).map(move |(x, _)| macro_cell_center(x, y)))
+        .filter(|pos| pos.distance(spawn_pos) > HAZARD_SPAWN_CLEARANCE)
+        .collect();
+
+    // Biomes decide how likely each cell is to hold a hazard
+    let weights = floor_cells.iter().map(|pos| {
+        let chunk = chunks::world_pos_to_chunk_coord(*pos);
+        mapgen::assign_biome(chunk.x, chunk.y, dungeon_state.depth, dungeon_state.seed)
+            .definition()
+            .hazard_frequency
+    });
+    let Ok(distribution) = WeightedIndex::new(weights) else {
+        return;
+    };
+
+    let mut rng = StdRng::seed_from_u64(dungeon_state.seed);
+    for hazard in &modifiers.hazards {
+        let mesh = meshes.add(Circle::new(hazard.radius));
+        let material = materials.add(Color::srgba(0.3, 0.8, 0.2, 0.35));
+
+        for _ in 0..hazard.count {
+            let position = floor_cells[distribution.sample(&mut rng)];
+            commands.spawn((
+                Mesh2d(mesh.clone()),
+                MeshMaterial2d(material.clone()),
+                Transform::from_translation(position.extend(crate::depth::DepthLayer::TerrainDetail.z())),
+                components::HazardZone::new(hazard.radius, hazard.damage_per_second),
+                StateScoped(world::WorldState::Dungeon),
+            ));
+        }
+    }
+}
+
+/// Scale the stats of newly spawned enemies by the run modifiers
+pub fn apply_enemy_stat_modifiers(
+    modifiers: Res<RunModifiers>,
+    mut enemies: Query<&mut CombatState, Added<Enemy>>,
+) {
+    for mut combat in enemies.iter_mut() {
+        combat.max_health *= modifiers.enemy_health;
+        combat.health *= modifiers.enemy_health;
+    }
+}
+
+/// Pulse hazard zones, damaging the players standing in them through the effects pipeline
+pub fn apply_hazard_damage(
+    time: Res<Time>,
+    mut hazards: Query<(Entity, &Transform, &mut components::HazardZone)>,
+    players: Query<(Entity, &Transform), With<Player>>,
+    mut effect_events: EventWriter<EffectEvent>,
+) {
+    for (hazard_entity, hazard_transform, mut hazard) in hazards.iter_mut() {
+        let pulses = hazard.pulse.tick(time.delta()).times_finished_this_tick();
+        if pulses == 0 {
+            continue;
+        }
+
+        let center = hazard_transform.translation.truncate();
+        let (targets, hit_positions): (Vec<Entity>, Vec<Vec2>) = players
+            .iter()
+            .map(|(player, transform)| (player, transform.translation.truncate()))
+            .filter(|(_, position)| position.distance(center) <= hazard.radius)
+            .unzip();
+        if targets.is_empty() {
+            continue;
+        }
+
+        for _ in 0..pulses {
+            effect_events.write(EffectEvent {
+                source: hazard_entity,
+                targets: targets.clone(),
+                effect_id: EffectDefId::HAZARD_PULSE,
+                hit_positions: hit_positions.clone(),
+            });
+        }
+    }
+}
+
+/// Clean up dungeon scene when exiting
+/// Entities spawned for the scene are `StateScoped` and despawn on their own
+pub fn teardown_dungeon_scene(mut commands: Commands) {
+    info!("Tearing down Dungeon scene");
+
+    commands.remove_resource::<RunModifiers>();
+    commands.remove_resource::<DungeonGenerationTask>();
+
     info!("Dungeon scene teardown complete");
 }
 
@@ -104,3 +287,33 @@ pub fn handle_dungeon_portal_interactions(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_hazards_pulse_effects_at_players_inside() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_event::<EffectEvent>()
+            .add_systems(Update, apply_hazard_damage);
+
+        let hazard = app.world_mut().spawn((Transform::default(), components::HazardZone::new(32.0, 8.0))).id();
+        let inside = app.world_mut().spawn((Transform::from_xyz(10.0, 0.0, 0.0), Player)).id();
+        app.world_mut().spawn((Transform::from_xyz(100.0, 0.0, 0.0), Player));
+
+        // 8 damage per second pulses twice a second
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_secs(1));
+        app.update();
+
+        let events: Vec<EffectEvent> = app.world_mut().resource_mut::<Events<EffectEvent>>().drain().collect();
+        assert_eq!(events.len(), 2);
+        for event in events {
+            assert_eq!(event.source, hazard);
+            assert_eq!(event.targets, [inside]);
+            assert_eq!(event.effect_id, EffectDefId::HAZARD_PULSE);
+        }
+    }
+}
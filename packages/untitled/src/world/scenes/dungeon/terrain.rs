@@ -9,6 +9,7 @@ use bevy_ecs_tilemap::prelude::*;
 use std::collections::HashMap;
 
 use crate::world::chunks::*;
+use crate::world::mapgen::{assign_biome, Biome};
 use crate::world::tiles::{TileType, TILE_SIZE};
 use crate::world::scenes::dungeon::resources::DungeonState;
 use crate::world::constants::MACRO_PX_PER_CHUNK;
@@ -34,8 +35,6 @@ const NOISE_AMPLITUDE: f32 = 0.02;
 
 /// Size of each tile texture in pixels (must match sprite sheet)
 const TEXTURE_TILE_SIZE: f32 = PX_PER_TILE as f32;
-/// Z-depth for tilemap rendering (negative to render behind entities)
-const TILEMAP_Z_DEPTH: f32 = -1.0;
 
 // === Macro Map Sampling Constants ===
 
@@ -51,13 +50,6 @@ const NOISE_HASH_Y: f32 = 78.233;
 /// Hash function multiplier for pseudo-random noise generation
 const NOISE_HASH_MULTIPLIER: f32 = 43758.5453;
 
-// === Texture Mapping Constants ===
-
-/// Texture atlas index for floor tiles
-const FLOOR_TEXTURE_INDEX: u32 = 0;
-/// Texture atlas index for wall tiles
-const WALL_TEXTURE_INDEX: u32 = 1;
-
 // === Macro Map Value Mappings ===
 
 /// Density value for floor areas (white pixels in macro map)
@@ -80,6 +72,9 @@ const NOISE_OFFSET: f32 = 1.0;
 /// Budget (in seconds) for chunk loading per frame to avoid frame drops
 const CHUNK_LOADING_BUDGET: f32 = 0.004;
 
+/// Tiles of one chunk, indexed `[x][y]`
+pub type ChunkTiles = [[TileType; CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
+
 /// Terrain-specific chunk state
 #[derive(Debug)]
 pub enum TerrainChunkState {
@@ -89,6 +84,7 @@ pub enum TerrainChunkState {
     Loaded {
         entity: Entity,
         tiles: [[TileType; CHUNK_SIZE as usize]; CHUNK_SIZE as usize],
+        biome: Biome,
     },
 }
 
@@ -113,8 +109,21 @@ impl TerrainChunks {
         self.chunks.len()
     }
 
+    /// Biome of a loaded chunk
+    pub fn biome_at(&self, chunk_coord: ChunkCoord) -> Option<Biome> {
+        match self.chunks.get(&chunk_coord) {
+            Some(TerrainChunkState::Loaded { biome, .. }) => Some(*biome),
+            _ => None,
+        }
+    }
+
+    /// Whether a chunk has finished loading and its tiles are spawned
+    pub fn is_loaded(&self, chunk_coord: ChunkCoord) -> bool {
+        matches!(self.chunks.get(&chunk_coord), Some(TerrainChunkState::Loaded { .. }))
+    }
+
     /// Iterate over all loaded chunks and their tile data
-    pub fn iter_loaded(&self) -> impl Iterator<Item = (ChunkCoord, &[[TileType; CHUNK_SIZE as usize]; CHUNK_SIZE as usize])> {
+    pub fn iter_loaded(&self) -> impl Iterator<Item = (ChunkCoord, &ChunkTiles)> {
         self.chunks.iter().filter_map(|(coord, state)| {
             if let TerrainChunkState::Loaded { tiles, .. } = state {
                 Some((*coord, tiles))
@@ -156,6 +165,9 @@ fn handle_single_load_event(
         return; // Already being handled
     }
 
+    // Biomes are derived from the seed, so they don't need to be persisted
+    let biome = assign_biome(chunk_coord.x, chunk_coord.y, dungeon_state.depth, dungeon_state.seed);
+
     // Try to load from database first
     if let Some(database) = db {
         if let Ok(Some(tiles)) = database.load_terrain_chunk(dungeon_state.map_id, chunk_coord) {
@@ -168,6 +180,7 @@ fn handle_single_load_event(
                 ChunkData {
                     position: chunk_coord,
                     tiles,
+                    biome,
                 }
             });
             terrain_chunks.chunks.insert(chunk_coord, TerrainChunkState::Loading { task });
@@ -186,6 +199,7 @@ fn handle_single_load_event(
         ChunkData {
             position: chunk_coord,
             tiles,
+            biome,
         }
     });
 
@@ -240,6 +254,7 @@ pub fn poll_terrain_loading_tasks(
                     terrain_chunks.chunks.insert(chunk_coord, TerrainChunkState::Loaded {
                         entity: parent_entity,
                         tiles: chunk_data.tiles,
+                        biome: chunk_data.biome,
                     });
 
                     // Count wall tiles for debugging
@@ -272,7 +287,7 @@ pub fn handle_chunk_unload_events(
                 TerrainChunkState::Loading { task: _ } => {
                     // Task will be dropped automatically, canceling the async work
                 }
-                TerrainChunkState::Loaded { entity, tiles } => {
+                TerrainChunkState::Loaded { entity, tiles, .. } => {
                     // Save terrain data to database before unloading
                     if let Some(database) = db.as_deref() {
                         if let Err(e) = database.save_terrain_chunk(dungeon_state.map_id, chunk_coord, &tiles) {
@@ -326,12 +341,13 @@ fn spawn_chunk_tilemap(
     commands.entity(parent_entity).add_child(tilemap_entity);
 
     let mut tile_storage = TileStorage::empty(map_size);
+    let biome = chunk_data.biome.definition();
 
     // Calculate transform for the tilemap (relative to parent)
     let tilemap_transform = Transform::from_translation(Vec3::new(
         -half_chunk_size,
         -half_chunk_size,
-        TILEMAP_Z_DEPTH,
+        crate::depth::DepthLayer::Terrain.z(),
     ));
 
     // Create tiles for the chunk
@@ -341,14 +357,15 @@ fn spawn_chunk_tilemap(
             let tile_type = chunk_data.tiles[x as usize][y as usize];
 
             let texture_index = match tile_type {
-                TileType::Floor => FLOOR_TEXTURE_INDEX,
-                TileType::Wall => WALL_TEXTURE_INDEX,
+                TileType::Floor => biome.floor_texture_index,
+                TileType::Wall => biome.wall_texture_index,
             };
 
             let mut tile_cmd = commands.spawn(TileBundle {
                 position: tile_pos,
                 tilemap_id: TilemapId(tilemap_entity),
                 texture_index: TileTextureIndex(texture_index),
+                color: TileColor(biome.tint),
                 ..Default::default()
             });
 
@@ -371,6 +388,7 @@ fn spawn_chunk_tilemap(
                     let wall_collider = commands.spawn((
                         crate::world::tiles::WallTile,
                         collider,
+                        crate::physics_layers::terrain(),
                         RigidBody::Fixed,
                         Transform::default(),
                         GlobalTransform::default(),
@@ -603,10 +621,10 @@ fn mark_rectangle_processed(
 }
 
 /// Generate chunk tiles (copied from ChunkManager for async use)
-fn generate_chunk_tiles(
+pub fn generate_chunk_tiles(
     position: ChunkCoord,
     macro_map: &Vec<Vec<bool>>,
-) -> [[TileType; CHUNK_SIZE as usize]; CHUNK_SIZE as usize] {
+) -> ChunkTiles {
     let mut tiles = [[TileType::Floor; CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
 
     for x in 0..CHUNK_SIZE as usize {
@@ -693,6 +711,40 @@ fn noise_2d(x: f32, y: f32, frequency: f32) -> f32 {
     n * NOISE_SCALE_FACTOR - NOISE_OFFSET // Scale to [-1, 1]
 }
 
+/// Biome the player is currently standing in
+#[derive(Resource, Default, Debug)]
+pub struct CurrentBiome(pub Option<Biome>);
+
+/// Track the player's biome and apply its ambience when it changes
+pub fn update_current_biome(
+    mut commands: Commands,
+    mut current: ResMut<CurrentBiome>,
+    terrain_chunks: Res<TerrainChunks>,
+    player_query: Query<&Transform, With<crate::player::Player>>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+
+    let chunk_coord = world_pos_to_chunk_coord(player_transform.translation.truncate());
+    let Some(biome) = terrain_chunks.biome_at(chunk_coord) else {
+        return;
+    };
+
+    if current.0 != Some(biome) {
+        let definition = biome.definition();
+        info!("Entered biome {}", definition.name);
+        commands.insert_resource(ClearColor(definition.ambience));
+        current.0 = Some(biome);
+    }
+}
+
+/// Drop biome ambience when chunking is turned off (leaving the dungeon)
+pub fn reset_current_biome(mut commands: Commands, mut current: ResMut<CurrentBiome>) {
+    current.0 = None;
+    commands.insert_resource(ClearColor::default());
+}
+
 /// System to initialize the TerrainChunks resource
 pub fn initialize_terrain_chunks(
     mut commands: Commands,
@@ -708,8 +760,10 @@ pub struct TerrainChunkPlugin;
 impl Plugin for TerrainChunkPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<CurrentBiome>()
+            .add_systems(OnExit(ChunkingState::Enabled), reset_current_biome)
             // Initialize terrain chunks resource
-            .add_systems(Startup,
+            .add_systems(OnExit(crate::world::WorldState::Loading),
                 initialize_terrain_chunks
                     .after(crate::world::tiles::systems::load_tilemap_texture)
             )
@@ -722,6 +776,8 @@ impl Plugin for TerrainChunkPlugin {
                     .after(handle_chunk_load_events),
                 handle_chunk_unload_events
                     .after(poll_terrain_loading_tasks),
+                update_current_biome
+                    .after(poll_terrain_loading_tasks),
             ).run_if(in_state(ChunkingState::Enabled)));
     }
 }
@@ -736,6 +792,8 @@ struct ChunkData {
     position: ChunkCoord,
     /// 64x64 tile data for this chunk
     tiles: [[TileType; CHUNK_SIZE as usize]; CHUNK_SIZE as usize],
+    /// Biome layered over the tiles
+    biome: Biome,
 }
 
 /// Loading state for chunk management
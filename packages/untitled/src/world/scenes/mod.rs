@@ -1,8 +1,10 @@
 pub mod cathedral;
 pub mod sanctuary;
 pub mod dungeon;
+pub mod arena;
 
 // Re-export commonly used types
 pub use cathedral::CathedralPlugin;
 pub use sanctuary::SanctuaryPlugin;
 pub use dungeon::DungeonPlugin;
+pub use arena::ArenaPlugin;
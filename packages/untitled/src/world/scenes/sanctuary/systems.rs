@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::world::{states::WorldState, chunks::ChunkingState};
 
-use super::components::{SanctuaryEntity, SanctuaryExitPortal, SanctuaryDungeonPortal};
+use super::components::{SanctuaryExitPortal, SanctuaryDungeonPortal};
 
 /// Set up the sanctuary scene when entering
 pub fn setup_sanctuary_scene(
@@ -20,15 +20,15 @@ pub fn setup_sanctuary_scene(
     commands.spawn((
         Camera2d,
         crate::components::MainCamera,
-        SanctuaryEntity,
+        StateScoped(WorldState::Sanctuary),
     ));
 
     // Spawn simple floor
     commands.spawn((
         Mesh2d(meshes.add(Rectangle::new(400.0, 400.0))),
         MeshMaterial2d(materials.add(Color::srgb(0.8, 0.9, 0.8))), // Light green
-        Transform::from_translation(Vec3::new(0.0, 0.0, -1.0)),
-        SanctuaryEntity,
+        Transform::from_translation(Vec3::new(0.0, 0.0, crate::depth::DepthLayer::Terrain.z())),
+        StateScoped(WorldState::Sanctuary),
     ));
 
     // Add sanctuary title text with depth
@@ -39,15 +39,15 @@ pub fn setup_sanctuary_scene(
             ..default()
         },
         TextColor(Color::srgb(0.2, 0.6, 0.2)),
-        Transform::from_translation(Vec3::new(0.0, 150.0, 1.0)),
-        SanctuaryEntity,
+        Transform::from_translation(Vec3::new(0.0, 150.0, crate::depth::DepthLayer::Labels.z())),
+        StateScoped(WorldState::Sanctuary),
     ));
 
     // Create three dungeon portals
     let portal_positions = [
-        Vec3::new(-200.0, 50.0, 0.0),  // Left dungeon portal
-        Vec3::new(0.0, 50.0, 0.0),     // Center dungeon portal
-        Vec3::new(200.0, 50.0, 0.0),   // Right dungeon portal
+        Vec2::new(-200.0, 50.0),  // Left dungeon portal
+        Vec2::new(0.0, 50.0),     // Center dungeon portal
+        Vec2::new(200.0, 50.0),   // Right dungeon portal
     ];
 
     let portal_colors = [
@@ -67,9 +67,10 @@ pub fn setup_sanctuary_scene(
         commands.spawn((
             Mesh2d(meshes.add(Rectangle::new(60.0, 80.0))),
             MeshMaterial2d(materials.add(color)),
-            Transform::from_translation(position),
+            Transform::from_translation(position.extend(crate::depth::DepthLayer::Actors.z())),
+            crate::depth::YSort::with_offset(-40.0),
             SanctuaryDungeonPortal,
-            SanctuaryEntity,
+            StateScoped(WorldState::Sanctuary),
             crate::world::Interactable::new(
                 format!("sanctuary_dungeon_portal_{}", i),
                 format!("Portal to {}", portal_labels[i]),
@@ -77,7 +78,7 @@ pub fn setup_sanctuary_scene(
                     info!("Sanctuary dungeon portal activated");
                 }
             ),
-            crate::world::InteractableHighlight::with_radius(1.4),
+            crate::world::InteractableHighlight::default(),
         ));
 
         // Add portal label
@@ -88,8 +89,8 @@ pub fn setup_sanctuary_scene(
                 ..default()
             },
             TextColor(Color::WHITE),
-            Transform::from_translation(position + Vec3::new(0.0, -50.0, 1.0)),
-            SanctuaryEntity,
+            Transform::from_translation((position + Vec2::new(0.0, -50.0)).extend(crate::depth::DepthLayer::Labels.z())),
+            StateScoped(WorldState::Sanctuary),
         ));
     }
 
@@ -97,9 +98,10 @@ pub fn setup_sanctuary_scene(
     commands.spawn((
         Mesh2d(meshes.add(Rectangle::new(60.0, 80.0))),
         MeshMaterial2d(materials.add(Color::srgb(0.4, 0.4, 0.8))), // Blue portal
-        Transform::from_translation(Vec3::new(0.0, -150.0, 0.0)),
+        Transform::from_translation(Vec3::new(0.0, -150.0, crate::depth::DepthLayer::Actors.z())),
+        crate::depth::YSort::with_offset(-40.0),
         SanctuaryExitPortal,
-        SanctuaryEntity,
+        StateScoped(WorldState::Sanctuary),
         crate::world::Interactable::new(
             "sanctuary_exit_portal".to_string(),
             "Portal to Cathedral".to_string(),
@@ -107,7 +109,7 @@ pub fn setup_sanctuary_scene(
                 info!("Sanctuary exit portal activated");
             }
         ),
-        crate::world::InteractableHighlight::with_radius(1.4),
+        crate::world::InteractableHighlight::default(),
     ));
 
     // Add cathedral portal label
@@ -118,8 +120,8 @@ pub fn setup_sanctuary_scene(
             ..default()
         },
         TextColor(Color::WHITE),
-        Transform::from_translation(Vec3::new(0.0, -200.0, 1.0)),
-        SanctuaryEntity,
+        Transform::from_translation(Vec3::new(0.0, -200.0, crate::depth::DepthLayer::Labels.z())),
+        StateScoped(WorldState::Sanctuary),
     ));
 
     // Spawn player at sanctuary entrance
@@ -127,29 +129,20 @@ pub fn setup_sanctuary_scene(
         crate::player::components::PlayerBundle::new(
             &mut meshes,
             &mut materials,
-            Vec3::new(0.0, 100.0, 0.0), // Position player above center
+            Vec3::new(0.0, 100.0, crate::depth::DepthLayer::Actors.z()), // Position player above center
         ),
     ).id();
 
-    // Mark player as a sanctuary entity for cleanup
-    commands.entity(player_entity).insert(SanctuaryEntity);
+    // Scope the player to the sanctuary so it's despawned on exit
+    commands.entity(player_entity).insert(StateScoped(WorldState::Sanctuary));
 
     info!("Sanctuary scene setup complete");
 }
 
 /// Clean up sanctuary scene when exiting
-pub fn teardown_sanctuary_scene(
-    mut commands: Commands,
-    sanctuary_entities: Query<Entity, With<SanctuaryEntity>>,
-) {
+/// Entities spawned for the scene are `StateScoped` and despawn on their own
+pub fn teardown_sanctuary_scene() {
     info!("Tearing down Sanctuary scene");
-
-    // Despawn all sanctuary entities
-    for entity in sanctuary_entities.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    info!("Sanctuary scene teardown complete");
 }
 
 /// Handle sanctuary portal interactions (both cathedral and dungeon portals)
@@ -160,6 +153,8 @@ pub fn handle_sanctuary_portal_interactions(
     mut next_state: ResMut<NextState<WorldState>>,
     sanctuary_state: Res<super::resources::SanctuaryState>,
     mut dungeon_state: ResMut<crate::world::scenes::dungeon::resources::DungeonState>,
+    run: Option<ResMut<crate::world::RunStructure>>,
+    mut commands: Commands,
 ) {
     for event in interaction_events.read() {
         // Check if the interacted entity is a sanctuary exit portal (to cathedral)
@@ -182,6 +177,15 @@ pub fn handle_sanctuary_portal_interactions(
                 dungeon_state.cleared_rooms = 0; // Reset progress for new depth
                 dungeon_state.is_completed = false;
 
+                // Take the branch chosen on the world map
+                if let Some(node) = run.and_then(|mut run| run.descend().cloned()) {
+                    commands.insert_resource(crate::world::states::DungeonConfig {
+                        depth: node.depth,
+                        modifiers: node.modifiers,
+                        ..default()
+                    });
+                }
+
                 next_state.set(WorldState::Dungeon);
                 return;
             }
@@ -1,20 +1,24 @@
 use bevy::prelude::*;
 
+use super::modifiers::ModifierId;
+
 /// Main world state enum for game scenes
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum WorldState {
     // System states
+    Loading,
     MainMenu,
 
     // Game world states
     Cathedral,
     Sanctuary,
     Dungeon,
+    Arena,
 }
 
 impl Default for WorldState {
     fn default() -> Self {
-        WorldState::Cathedral
+        WorldState::Loading
     }
 }
 
@@ -71,7 +75,7 @@ impl PlayerProgress {
 #[derive(Resource, Debug, Clone)]
 pub struct DungeonConfig {
     pub depth: u32,
-    pub modifiers: Vec<String>, // Using String for now, can refactor to ModifierId later
+    pub modifiers: Vec<ModifierId>,
     pub portal_id: PortalId,
 }
 
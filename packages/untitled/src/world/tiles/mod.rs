@@ -19,8 +19,8 @@ pub struct TilePlugin;
 impl Plugin for TilePlugin {
     fn build(&self, app: &mut App) {
         app
-            // Add startup systems for loading assets
-            .add_systems(Startup, systems::load_tilemap_texture);
+            // The tile atlas is preloaded by the loading screen
+            .add_systems(OnExit(crate::world::WorldState::Loading), systems::load_tilemap_texture);
             // Note: chunk management is now handled by ChunkPlugin
     }
 }
@@ -3,13 +3,12 @@ use bevy::prelude::*;
 use super::tiles::*;
 use super::resources::*;
 
-/// System to load tilemap texture atlas on startup
+/// System to set up the tilemap from the preloaded texture atlas
 pub fn load_tilemap_texture(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    loaded_assets: Res<crate::loading::LoadedAssets>,
 ) {
-    // Load the tile texture atlas
-    let texture_handle: Handle<Image> = asset_server.load("sprites/tiles.png");
+    let texture_handle = loaded_assets.texture("tiles");
 
     let tilemap_data = TilemapData {
         tiles: generate_test_tilemap(),